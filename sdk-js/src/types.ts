@@ -61,6 +61,8 @@ export interface BasePluginPayload {
   historyId?: string | null;
   timeRange?: string | null;
   downloadKind: string;
+  relativePath?: string | null;
+  extraFiles?: string[];
   workflowRunId?: string | null;
   workflowStepIndex?: number | null;
   workflowStepPluginId?: string | null;
@@ -5,7 +5,8 @@ use tokio::process::Command;
 
 use crate::database;
 use crate::services::{
-    build_cookie_args, build_site_header_args, get_deno_path, get_ytdlp_path, run_ytdlp_with_stderr,
+    build_cookie_args, build_site_header_args, get_deno_path, get_ytdlp_path,
+    run_ytdlp_with_stderr, validate_download_profile_name,
 };
 use crate::types::{ChannelInfo, ChannelVideo, FollowedChannel, PlaylistVideoEntry};
 use crate::utils::CommandExt;
@@ -707,7 +708,12 @@ pub async fn update_channel_settings(
     filter_max_videos: Option<i64>,
     download_threads: Option<i64>,
     youtube_content_type: Option<String>,
+    download_profile: Option<String>,
 ) -> Result<(), String> {
+    let download_profile = match download_profile.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => Some(validate_download_profile_name(name)?),
+        _ => None,
+    };
     database::update_channel_settings_db(
         id,
         check_interval,
@@ -723,6 +729,7 @@ pub async fn update_channel_settings(
         filter_max_videos,
         download_threads.unwrap_or(1),
         sanitize_youtube_content_type(youtube_content_type.as_deref()),
+        download_profile,
     )
 }
 
@@ -1,5 +1,11 @@
 use std::sync::Mutex;
-use tauri::Url;
+use tauri::{AppHandle, Url};
+
+use crate::services::{
+    download_profile_exists, enqueue_download_item, validate_download_profile_name,
+};
+use crate::types::{DownloadOptions, GalleryDlOptions, NewDownloadQueueItem};
+use crate::utils::validate_gallerydl_options;
 
 static PENDING_CLI_DOWNLOAD_REQUESTS: Mutex<Vec<CliDownloadRequest>> = Mutex::new(Vec::new());
 const MAX_PENDING_CLI_DOWNLOAD_REQUESTS: usize = 100;
@@ -10,6 +16,7 @@ const ALLOWED_VIDEO_QUALITIES: [&str; 8] = ["best", "8k", "4k", "2k", "1080", "7
 const ALLOWED_AUDIO_QUALITIES: [&str; 2] = ["128", "auto"];
 const ALLOWED_SUBTITLE_MODES: [&str; 3] = ["off", "auto", "manual"];
 const ALLOWED_SUBTITLE_FORMATS: [&str; 3] = ["srt", "vtt", "ass"];
+const MAX_CLI_DOWNLOAD_SECTIONS: usize = 20;
 
 #[derive(Clone, serde::Serialize)]
 pub struct CliDownloadRequest {
@@ -26,7 +33,11 @@ pub struct CliDownloadRequest {
     pub subtitle_embed: bool,
     pub subtitle_format: Option<String>,
     pub download_sections: Option<String>,
+    pub join_sections: bool,
     pub live_from_start: bool,
+    pub profile: Option<String>,
+    /// gallery-dl options of a `gallery` target request
+    pub gallery_options: Option<GalleryDlOptions>,
     pub trusted_local: bool,
 }
 
@@ -49,8 +60,19 @@ pub struct CliDownloadArgs {
     pub subtitle_langs: Option<String>,
     pub subtitle_embed: bool,
     pub subtitle_format: Option<String>,
-    pub download_sections: Option<String>,
+    /// Values of every `--download-sections`; the flag can be repeated
+    pub download_sections: Vec<String>,
+    pub join_sections: bool,
     pub live_from_start: bool,
+    pub profile: Option<String>,
+    pub gallery_range: Option<String>,
+    pub gallery_filter: Option<String>,
+    pub gallery_filename: Option<String>,
+    /// Values of every `--gallery-directory`, one folder each
+    pub gallery_directory: Vec<String>,
+    pub write_metadata: bool,
+    pub zip: bool,
+    pub ugoira_conv: bool,
 }
 
 pub fn print_cli_usage_and_should_exit(argv: &[String]) -> bool {
@@ -143,7 +165,7 @@ Options:
   -o, --output <DIR>    Absolute output folder for this queued download
   -a, --audio           Download audio only
       --queue-only      Only add the URL to the queue without starting the download
-  -t, --target <VALUE>  Routing target: auto, youtube, or universal
+  -t, --target <VALUE>  Routing target: auto, youtube, universal, or gallery
       --skip-live       Skip live, scheduled, or was-live videos before downloading
       --playlist        Allow yt-dlp to download playlist URLs
       --no-playlist     Force single-video download for playlist URLs
@@ -151,8 +173,17 @@ Options:
       --subtitle-langs <VALUE> Comma-separated subtitle languages, e.g. en,vi,ja
       --subtitle-format <VALUE> Subtitle format: srt, vtt, or ass
       --embed-subs      Embed subtitles into the output file
-      --download-sections <VALUE> Time range as START-END, e.g. 00:30-02:10
+      --download-sections <VALUE> Time range as START-END, e.g. 00:30-02:10. Repeat for several clips
+      --join-sections   Join the clips of several time ranges into one file
       --live-from-start Download livestreams from the beginning
+      --profile <NAME>  Apply a saved download profile
+      --gallery-range <VALUE> Gallery files to download, e.g. 1-20 or 5,10:40:2
+      --gallery-filter <EXPR> Gallery file filter, e.g. \"width >= 1000 and extension != 'gif'\"
+      --gallery-filename <FORMAT> Gallery file name format, e.g. {{id}}_{{num}}.{{extension}}
+      --gallery-directory <FORMAT> Gallery folder format, e.g. {{category}}. Repeat for sub-folders
+      --write-metadata  Save the metadata of every gallery file as JSON
+      --zip             Store gallery files in a zip archive
+      --ugoira-conv     Convert Pixiv ugoira animations to WebM
   -h, --help            Print help
   -V, --version         Print version",
         version = env!("CARGO_PKG_VERSION")
@@ -230,7 +261,7 @@ fn is_private_or_local_host(hostname: &str) -> bool {
 }
 
 /// Build a structured CLI download request from parsed CLI arguments.
-/// Returns `None` when no usable URL is present.
+/// Returns `None` when no usable URL is present or an option is invalid.
 pub fn build_cli_download_request(args: &CliDownloadArgs) -> Option<CliDownloadRequest> {
     let url = normalize_cli_url_arg(args.url.as_ref()?);
     if !is_accepted_cli_url(&url) {
@@ -265,7 +296,29 @@ pub fn build_cli_download_request(args: &CliDownloadArgs) -> Option<CliDownloadR
                 None
             }
         });
-    let download_sections = normalize_cli_download_sections(args.download_sections.as_deref());
+    let download_sections = normalize_cli_download_sections(&args.download_sections);
+    let profile = match args.profile.as_deref().map(validate_download_profile_name) {
+        Some(Err(e)) => {
+            log::error!("Rejected CLI download {}: {}", url, e);
+            return None;
+        }
+        Some(Ok(name)) => Some(name),
+        None => None,
+    };
+    let gallery_options = match normalize_cli_gallery_options(args) {
+        Ok(options) => options,
+        Err(e) => {
+            log::error!("Rejected CLI download {}: {}", url, e);
+            return None;
+        }
+    };
+    // Gallery options only apply to gallery-dl, so they pick the gallery target
+    let target = if gallery_options.is_some() && target == "auto" {
+        "gallery".to_string()
+    } else {
+        target
+    };
+    let gallery_options = gallery_options.filter(|_| target == "gallery");
 
     Some(CliDownloadRequest {
         url,
@@ -280,8 +333,11 @@ pub fn build_cli_download_request(args: &CliDownloadArgs) -> Option<CliDownloadR
         subtitle_langs,
         subtitle_embed: args.subtitle_embed,
         subtitle_format,
+        join_sections: args.join_sections && download_sections.is_some(),
         download_sections,
         live_from_start: args.live_from_start,
+        profile,
+        gallery_options,
         trusted_local: true,
     })
 }
@@ -384,7 +440,7 @@ fn normalize_cli_target(value: Option<&str>) -> String {
     let Some(target) = value.map(|t| t.trim().to_ascii_lowercase()) else {
         return "auto".to_string();
     };
-    if target == "youtube" || target == "universal" {
+    if target == "youtube" || target == "universal" || target == "gallery" {
         target
     } else {
         "auto".to_string()
@@ -434,8 +490,20 @@ fn normalize_cli_subtitle_langs(value: Option<&str>) -> Vec<String> {
         .collect()
 }
 
-fn normalize_cli_download_sections(value: Option<&str>) -> Option<String> {
-    let section = value?.trim().trim_start_matches('*');
+/// Valid ranges of every `--download-sections` value (each may also list ranges separated
+/// by commas), joined into one `*start-end,*start-end` value.
+fn normalize_cli_download_sections(values: &[String]) -> Option<String> {
+    let ranges: Vec<String> = values
+        .iter()
+        .flat_map(|value| value.split(','))
+        .filter_map(normalize_cli_download_section)
+        .take(MAX_CLI_DOWNLOAD_SECTIONS)
+        .collect();
+    (!ranges.is_empty()).then(|| ranges.join(","))
+}
+
+fn normalize_cli_download_section(value: &str) -> Option<String> {
+    let section = value.trim().trim_start_matches('*');
     if section.is_empty() || section.len() > 64 || section.contains(char::is_whitespace) {
         return None;
     }
@@ -457,6 +525,37 @@ fn is_valid_time_marker(value: &str) -> bool {
         .all(|part| !part.is_empty() && part.len() <= 2 && part.chars().all(|c| c.is_ascii_digit()))
 }
 
+/// gallery-dl options of the CLI arguments. `None` when none were given, an error when
+/// any of them does not pass validation.
+fn normalize_cli_gallery_options(
+    args: &CliDownloadArgs,
+) -> Result<Option<GalleryDlOptions>, String> {
+    let text = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(String::from)
+    };
+
+    let options = GalleryDlOptions {
+        range: text(&args.gallery_range),
+        filter: text(&args.gallery_filter),
+        filename_format: text(&args.gallery_filename),
+        directory_format: args
+            .gallery_directory
+            .iter()
+            .map(|folder| folder.trim().to_string())
+            .filter(|folder| !folder.is_empty())
+            .collect(),
+        write_metadata: args.write_metadata,
+        zip: args.zip,
+        ugoira_conv: args.ugoira_conv,
+    };
+    validate_gallerydl_options(&options)?;
+    Ok((!options.is_empty()).then_some(options))
+}
+
 /// Best-effort parser for raw argv (used by the single-instance callback where
 /// only the raw process arguments are available). Supports the same flags as
 /// the declared CLI schema. Unknown flags are ignored.
@@ -493,6 +592,10 @@ pub fn parse_cli_args_from_argv(argv: &[String]) -> CliDownloadArgs {
             "--no-playlist" => args.download_playlist = Some(false),
             "--embed-subs" => args.subtitle_embed = true,
             "--live-from-start" => args.live_from_start = true,
+            "--join-sections" => args.join_sections = true,
+            "--write-metadata" => args.write_metadata = true,
+            "--zip" => args.zip = true,
+            "--ugoira-conv" => args.ugoira_conv = true,
             "--subtitle-mode" => {
                 if let Some(value) = iter.next() {
                     args.subtitle_mode = Some(value.clone());
@@ -510,7 +613,32 @@ pub fn parse_cli_args_from_argv(argv: &[String]) -> CliDownloadArgs {
             }
             "--download-sections" => {
                 if let Some(value) = iter.next() {
-                    args.download_sections = Some(value.clone());
+                    args.download_sections.push(value.clone());
+                }
+            }
+            "--profile" => {
+                if let Some(value) = iter.next() {
+                    args.profile = Some(value.clone());
+                }
+            }
+            "--gallery-range" => {
+                if let Some(value) = iter.next() {
+                    args.gallery_range = Some(value.clone());
+                }
+            }
+            "--gallery-filter" => {
+                if let Some(value) = iter.next() {
+                    args.gallery_filter = Some(value.clone());
+                }
+            }
+            "--gallery-filename" => {
+                if let Some(value) = iter.next() {
+                    args.gallery_filename = Some(value.clone());
+                }
+            }
+            "--gallery-directory" => {
+                if let Some(value) = iter.next() {
+                    args.gallery_directory.push(value.clone());
                 }
             }
             other => {
@@ -532,7 +660,17 @@ pub fn parse_cli_args_from_argv(argv: &[String]) -> CliDownloadArgs {
                 } else if let Some(rest) = other.strip_prefix("--subtitle-format=") {
                     args.subtitle_format = Some(rest.to_string());
                 } else if let Some(rest) = other.strip_prefix("--download-sections=") {
-                    args.download_sections = Some(rest.to_string());
+                    args.download_sections.push(rest.to_string());
+                } else if let Some(rest) = other.strip_prefix("--profile=") {
+                    args.profile = Some(rest.to_string());
+                } else if let Some(rest) = other.strip_prefix("--gallery-range=") {
+                    args.gallery_range = Some(rest.to_string());
+                } else if let Some(rest) = other.strip_prefix("--gallery-filter=") {
+                    args.gallery_filter = Some(rest.to_string());
+                } else if let Some(rest) = other.strip_prefix("--gallery-filename=") {
+                    args.gallery_filename = Some(rest.to_string());
+                } else if let Some(rest) = other.strip_prefix("--gallery-directory=") {
+                    args.gallery_directory.push(rest.to_string());
                 } else if !other.starts_with('-') && args.url.is_none() {
                     // First positional argument is treated as the URL.
                     args.url = Some(other.to_string());
@@ -573,7 +711,10 @@ pub fn enqueue_cli_download_requests(requests: Vec<CliDownloadRequest>) {
                     && existing.subtitle_embed == request.subtitle_embed
                     && existing.subtitle_format == request.subtitle_format
                     && existing.download_sections == request.download_sections
+                    && existing.join_sections == request.join_sections
                     && existing.live_from_start == request.live_from_start
+                    && existing.profile == request.profile
+                    && existing.gallery_options == request.gallery_options
             }) {
                 existing.trusted_local = existing.trusted_local || request.trusted_local;
             } else {
@@ -594,6 +735,82 @@ pub fn take_pending_cli_download_requests() -> Vec<CliDownloadRequest> {
     Vec::new()
 }
 
+/// Hand "download now" CLI requests to the backend download queue.
+/// Returns the requests the frontend should still handle (queue-only ones and failures).
+/// Requests naming a profile that is not saved are dropped.
+pub fn dispatch_cli_download_requests(
+    app: &AppHandle,
+    requests: Vec<CliDownloadRequest>,
+) -> Vec<CliDownloadRequest> {
+    let mut remaining = Vec::new();
+    for request in requests {
+        if let Some(profile) = request.profile.as_deref() {
+            if !download_profile_exists(profile) {
+                log::error!(
+                    "Rejected CLI download {}: unknown download profile {}",
+                    request.url,
+                    profile
+                );
+                continue;
+            }
+        }
+        // Gallery downloads run from the Gallery page
+        if request.action != "download_now" || request.target == "gallery" {
+            remaining.push(request);
+            continue;
+        }
+        if let Err(e) = enqueue_download_item(app, cli_request_to_queue_item(&request)) {
+            log::error!("Failed to queue CLI download {}: {}", request.url, e);
+            remaining.push(request);
+        }
+    }
+    remaining
+}
+
+fn cli_request_to_queue_item(request: &CliDownloadRequest) -> NewDownloadQueueItem {
+    let mut options = DownloadOptions {
+        output_path: request.output_path.clone(),
+        skip_live: Some(request.skip_live),
+        live_from_start: Some(request.live_from_start),
+        download_playlist: request.download_playlist.unwrap_or(false),
+        subtitle_embed: request.subtitle_embed,
+        subtitle_langs: request.subtitle_langs.join(","),
+        download_sections: request.download_sections.clone(),
+        join_sections: Some(request.join_sections),
+        source: Some("cli".to_string()),
+        download_kind: Some("cli".to_string()),
+        profile: request.profile.clone(),
+        ..Default::default()
+    };
+    if let Some(mode) = &request.subtitle_mode {
+        options.subtitle_mode = mode.clone();
+    }
+    if let Some(format) = &request.subtitle_format {
+        options.subtitle_format = format.clone();
+    }
+    if request.media == "audio" {
+        options.quality = "audio".to_string();
+        options.format = "mp3".to_string();
+        options.audio_bitrate = request.quality.clone();
+    } else {
+        options.quality = request.quality.clone();
+    }
+
+    NewDownloadQueueItem {
+        id: None,
+        url: request.url.clone(),
+        queue_kind: match request.target.as_str() {
+            "youtube" | "universal" => Some(request.target.clone()),
+            _ => None,
+        },
+        title: None,
+        origin: Some("cli".to_string()),
+        origin_ref: None,
+        priority: None,
+        options,
+    }
+}
+
 #[tauri::command]
 pub fn consume_pending_cli_download_requests() -> Vec<CliDownloadRequest> {
     take_pending_cli_download_requests()
@@ -761,6 +978,7 @@ mod tests {
             "--embed-subs".to_string(),
             "--download-sections=00:30-02:10".to_string(),
             "--live-from-start".to_string(),
+            "--profile=podcast-320".to_string(),
         ];
 
         let request = build_cli_download_request_from_argv(&argv).expect("expected CLI request");
@@ -773,6 +991,7 @@ mod tests {
         assert_eq!(request.subtitle_format.as_deref(), Some("vtt"));
         assert_eq!(request.download_sections.as_deref(), Some("*00:30-02:10"));
         assert!(request.live_from_start);
+        assert_eq!(request.profile.as_deref(), Some("podcast-320"));
     }
 
     #[test]
@@ -782,7 +1001,7 @@ mod tests {
             subtitle_mode: Some("invalid".to_string()),
             subtitle_langs: Some("en,../../secret,vi".to_string()),
             subtitle_format: Some("txt".to_string()),
-            download_sections: Some("not a range".to_string()),
+            download_sections: vec!["not a range".to_string()],
             output_path: Some("relative/videos".to_string()),
             ..Default::default()
         };
@@ -794,6 +1013,41 @@ mod tests {
         assert_eq!(request.subtitle_format.as_deref(), Some("srt"));
         assert_eq!(request.download_sections, None);
         assert_eq!(request.output_path, None);
+        assert_eq!(request.profile, None);
+    }
+
+    #[test]
+    fn cli_request_rejects_invalid_profile_names() {
+        let args = CliDownloadArgs {
+            url: Some("https://example.com/video".to_string()),
+            profile: Some("my profile".to_string()),
+            ..Default::default()
+        };
+
+        assert!(build_cli_download_request(&args).is_none());
+    }
+
+    #[test]
+    fn raw_argv_collects_repeated_download_sections() {
+        let argv = vec![
+            "youwee".to_string(),
+            "https://www.youtube.com/watch?v=abc123".to_string(),
+            "--download-sections".to_string(),
+            "00:30-02:10".to_string(),
+            "--download-sections=*05:00-06:00,bad".to_string(),
+            "--join-sections".to_string(),
+        ];
+
+        let request = build_cli_download_request_from_argv(&argv).expect("expected CLI request");
+        assert_eq!(
+            request.download_sections.as_deref(),
+            Some("*00:30-02:10,*05:00-06:00")
+        );
+        assert!(request.join_sections);
+        assert_eq!(
+            cli_request_to_queue_item(&request).options.join_sections,
+            Some(true)
+        );
     }
 
     #[test]
@@ -815,6 +1069,72 @@ mod tests {
         assert_eq!(request.subtitle_mode.as_deref(), Some("auto"));
     }
 
+    #[test]
+    fn cli_request_maps_to_queue_item() {
+        let args = CliDownloadArgs {
+            url: Some("https://www.youtube.com/watch?v=abc123".to_string()),
+            audio: true,
+            quality: Some("128".to_string()),
+            subtitle_langs: Some("en,vi".to_string()),
+            target: Some("youtube".to_string()),
+            ..Default::default()
+        };
+        let request = build_cli_download_request(&args).expect("expected CLI request");
+        let item = cli_request_to_queue_item(&request);
+
+        assert_eq!(item.origin.as_deref(), Some("cli"));
+        assert_eq!(item.queue_kind.as_deref(), Some("youtube"));
+        assert_eq!(item.options.quality, "audio");
+        assert_eq!(item.options.format, "mp3");
+        assert_eq!(item.options.audio_bitrate, "128");
+        assert_eq!(item.options.subtitle_mode, "manual");
+        assert_eq!(item.options.subtitle_langs, "en,vi");
+    }
+
+    #[test]
+    fn raw_argv_supports_gallery_options() {
+        let argv = vec![
+            "youwee".to_string(),
+            "https://www.pixiv.net/en/users/123".to_string(),
+            "--gallery-range=1-20".to_string(),
+            "--gallery-directory".to_string(),
+            "{category}".to_string(),
+            "--gallery-directory={user[name]}".to_string(),
+            "--ugoira-conv".to_string(),
+        ];
+
+        let request = build_cli_download_request_from_argv(&argv).expect("expected CLI request");
+
+        assert_eq!(request.target, "gallery");
+        assert_eq!(
+            request.gallery_options,
+            Some(GalleryDlOptions {
+                range: Some("1-20".to_string()),
+                directory_format: vec!["{category}".to_string(), "{user[name]}".to_string()],
+                ugoira_conv: true,
+                ..Default::default()
+            })
+        );
+
+        let mut video = argv[..2].to_vec();
+        video.extend([
+            "--target".to_string(),
+            "youtube".to_string(),
+            "--zip".to_string(),
+        ]);
+        let request = build_cli_download_request_from_argv(&video).expect("expected CLI request");
+        assert_eq!(request.target, "youtube");
+        assert_eq!(request.gallery_options, None);
+
+        let mut unsafe_filter = argv.clone();
+        unsafe_filter.extend(["--gallery-filter".to_string(), "open('x')".to_string()]);
+        assert!(build_cli_download_request_from_argv(&unsafe_filter).is_none());
+
+        let mut bad_range = argv[..2].to_vec();
+        bad_range.push("--gallery-range=1-20; rm".to_string());
+        assert!(build_cli_download_request_from_argv(&bad_range).is_none());
+    }
+
     #[test]
     fn cli_help_request_exits_before_app_start() {
         let argv = vec!["youwee".to_string(), "--help".to_string()];
@@ -1,15 +1,20 @@
 use crate::services::{
-    DenoUpdateInfo, FfmpegUpdateInfo, check_deno_internal, check_deno_update_internal,
-    check_ffmpeg_internal, check_ffmpeg_update_internal, check_gallerydl_internal,
-    get_all_ytdlp_versions, get_channel_api_url, get_deno_download_url, get_ffmpeg_download_info,
-    get_ffmpeg_path, get_ffmpeg_source, get_latest_ffmpeg_release_info, get_ytdlp_channel,
+    DenoUpdateInfo, FfmpegUpdateInfo, app_gallerydl_binary_name, check_deno_internal,
+    check_deno_update_internal, check_ffmpeg_internal, check_ffmpeg_update_internal,
+    check_gallerydl_internal, fetch_gallerydl_release, gallerydl_asset_name,
+    gallerydl_binary_version, gallerydl_installed_release_path, get_all_ytdlp_versions,
+    get_app_gallerydl_channel_path, get_channel_api_url, get_deno_download_url,
+    get_ffmpeg_download_info, get_ffmpeg_path, get_ffmpeg_source, get_gallerydl_channel,
+    get_gallerydl_source, get_latest_ffmpeg_release_info, get_ytdlp_channel,
     get_ytdlp_channel_download_url, get_ytdlp_download_info, get_ytdlp_source,
-    get_ytdlp_version_internal, parse_ffmpeg_version, set_ffmpeg_source, set_ytdlp_channel,
-    set_ytdlp_source, system_ffmpeg_upgrade_message, system_ytdlp_upgrade_message, verify_sha256,
+    get_ytdlp_version_internal, parse_ffmpeg_version, set_ffmpeg_source, set_gallerydl_channel,
+    set_gallerydl_source, set_ytdlp_channel, set_ytdlp_source, system_ffmpeg_upgrade_message,
+    system_gallerydl_upgrade_message, system_ytdlp_upgrade_message, verify_sha256,
     write_app_ffmpeg_release_version,
 };
 use crate::types::{
-    BackendError, DenoStatus, DependencySource, FfmpegStatus, GalleryDlStatus, YtdlpAllVersions,
+    BackendError, DenoStatus, DependencySource, FfmpegStatus, GalleryDlChannel, GalleryDlStatus,
+    GalleryDlUpdateInfo, YtdlpAllVersions,
     YtdlpChannel, YtdlpChannelUpdateInfo, YtdlpVersionInfo,
 };
 use crate::utils::{CommandExt, extract_deno_zip, extract_tar_gz, extract_tar_xz, extract_zip};
@@ -797,6 +802,174 @@ pub async fn check_gallerydl(app: AppHandle) -> Result<GalleryDlStatus, String>
     check_gallerydl_internal(&app).await
 }
 
+// ============ gallery-dl Commands ============
+
+#[tauri::command]
+pub async fn get_gallerydl_source_cmd(app: AppHandle) -> Result<String, String> {
+    let source = get_gallerydl_source(&app).await;
+    Ok(source.as_str().to_string())
+}
+
+#[tauri::command]
+pub async fn set_gallerydl_source_cmd(app: AppHandle, source: String) -> Result<(), String> {
+    let source_enum = DependencySource::from_str(&source);
+    set_gallerydl_source(&app, &source_enum).await
+}
+
+#[tauri::command]
+pub async fn get_gallerydl_channel_cmd(app: AppHandle) -> Result<String, String> {
+    let channel = get_gallerydl_channel(&app).await;
+    Ok(channel.as_str().to_string())
+}
+
+#[tauri::command]
+pub async fn set_gallerydl_channel_cmd(app: AppHandle, channel: String) -> Result<(), String> {
+    set_gallerydl_channel(&app, GalleryDlChannel::from_str(&channel)).await
+}
+
+/// Release tag the app-managed binary of `channel` was installed from, or its
+/// `--version` when it was installed by hand.
+async fn get_installed_gallerydl_version(
+    app: &AppHandle,
+    channel: GalleryDlChannel,
+) -> Option<String> {
+    let binary_path = get_app_gallerydl_channel_path(app, channel)?;
+    let release_path = gallerydl_installed_release_path(&binary_path);
+
+    if let Ok(tag) = tokio::fs::read_to_string(&release_path).await {
+        let tag = tag.trim();
+        if !tag.is_empty() {
+            return Some(tag.to_string());
+        }
+    }
+
+    gallerydl_binary_version(&binary_path)
+        .await
+        .ok()
+        .filter(|version| !version.is_empty())
+}
+
+#[tauri::command]
+pub async fn check_gallerydl_update(
+    app: AppHandle,
+    channel: String,
+) -> Result<GalleryDlUpdateInfo, String> {
+    let channel_enum = GalleryDlChannel::from_str(&channel);
+    let current_version = get_installed_gallerydl_version(&app, channel_enum).await;
+    let release = fetch_gallerydl_release(channel_enum).await?;
+
+    let latest_version = release.tag_name;
+    let normalize_version = |v: &str| v.trim().trim_start_matches('v').to_string();
+    let update_available = current_version
+        .as_ref()
+        .map(|cv| normalize_version(cv) != normalize_version(&latest_version))
+        .unwrap_or(true); // If not installed, update is available
+
+    Ok(GalleryDlUpdateInfo {
+        channel: channel_enum.as_str().to_string(),
+        current_version,
+        latest_version,
+        update_available,
+    })
+}
+
+#[tauri::command]
+pub async fn download_gallerydl(app: AppHandle, channel: String) -> Result<String, String> {
+    if get_gallerydl_source(&app).await == DependencySource::System {
+        return Err(BackendError::new(
+            crate::types::code::GALLERYDL_SYSTEM_MANAGED,
+            system_gallerydl_upgrade_message(),
+        )
+        .with_retryable(false)
+        .to_wire_string());
+    }
+
+    let channel_enum = GalleryDlChannel::from_str(&channel);
+    let asset_name = gallerydl_asset_name(channel_enum).ok_or_else(|| {
+        format!("No {} gallery-dl build for this platform", channel_enum.as_str())
+    })?;
+
+    let release = fetch_gallerydl_release(channel_enum).await?;
+    let asset = release
+        .asset(asset_name)
+        .ok_or_else(|| format!("{} not found in gallery-dl {}", asset_name, release.tag_name))?;
+    let expected_hash = asset
+        .sha256()
+        .ok_or_else(|| format!("Checksum not found for {}", asset_name))?;
+
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    let bin_dir = app_data_dir.join("bin");
+
+    tokio::fs::create_dir_all(&bin_dir)
+        .await
+        .map_err(|e| format!("Failed to create bin directory: {}", e))?;
+
+    let binary_path = bin_dir.join(app_gallerydl_binary_name(channel_enum));
+
+    let client = reqwest::Client::builder()
+        .user_agent("Youwee/0.6.0")
+        .timeout(std::time::Duration::from_secs(300))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    // Download binary
+    let response = client
+        .get(&asset.browser_download_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download gallery-dl: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Download failed with status: {}",
+            response.status()
+        ));
+    }
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+
+    // Verify checksum
+    if !verify_sha256(&bytes, expected_hash) {
+        return Err("Security error: SHA256 checksum verification failed.".to_string());
+    }
+
+    // Write binary
+    let temp_path = binary_path.with_extension("tmp");
+    tokio::fs::write(&temp_path, &bytes)
+        .await
+        .map_err(|e| format!("Failed to write binary: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = tokio::fs::metadata(&temp_path)
+            .await
+            .map_err(|e| format!("Failed to get file metadata: {}", e))?
+            .permissions();
+        perms.set_mode(0o755);
+        tokio::fs::set_permissions(&temp_path, perms)
+            .await
+            .map_err(|e| format!("Failed to set permissions: {}", e))?;
+    }
+
+    tokio::fs::rename(&temp_path, &binary_path)
+        .await
+        .map_err(|e| format!("Failed to rename binary: {}", e))?;
+
+    tokio::fs::write(gallerydl_installed_release_path(&binary_path), &release.tag_name)
+        .await
+        .map_err(|e| format!("Failed to save gallery-dl version: {}", e))?;
+
+    gallerydl_binary_version(&binary_path).await
+}
+
 #[tauri::command]
 pub async fn check_deno_update(app: AppHandle) -> Result<DenoUpdateInfo, String> {
     check_deno_update_internal(&app).await
@@ -6,10 +6,10 @@
 //! - Progress tracking
 //! - Subtitle handling
 
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::process::Stdio;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
 
 use crate::utils::{normalize_url, validate_url};
 use tauri::{AppHandle, Emitter};
@@ -18,35 +18,303 @@ use tauri_plugin_shell::ShellExt;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 
+use crate::database::add_download_queue_item_partial_path_db;
 use crate::database::add_history_internal;
 use crate::database::add_log_internal;
 use crate::database::update_history_download;
+use crate::database::{
+    archive_key_from_url, bypasses_download_archive, get_download_archive_lines_db,
+    is_download_archived_db, record_download_archive_lines_db,
+};
+use crate::database::{
+    delete_history_parts, link_history_part, link_history_playlist_item, set_history_content_hash,
+};
 use crate::services::{
-    build_cookie_args, build_proxy_args, build_site_header_args, enqueue_post_download_workflow,
-    get_deno_path, get_ffmpeg_path, get_ytdlp_path, get_ytdlp_source, is_upcoming_live_error,
-    resolve_download_workflow_snapshot, run_ytdlp_with_stderr, system_ytdlp_not_found_message,
+    apply_download_profile, available_space, bandwidth_balancer_woken, build_cookie_args,
+    build_proxy_args, build_site_header_args, check_free_space, check_quota, clip_output_template,
+    enqueue_post_download_workflow, finish_clips, get_deno_path, get_ffmpeg_path,
+    get_scheduler_config, get_ytdlp_path, get_ytdlp_source, is_retryable_error,
+    is_upcoming_live_error, journal_download_finished, journal_download_progress,
+    journal_download_started, library_content_hash, measure_folder_usage, note_bandwidth_progress,
+    note_rate_limit, parse_rate, parse_ytdlp_error, plan_bandwidth_rebalance, quota_for_folder,
+    required_space, resolve_download_workflow_snapshot, run_ytdlp_with_stderr, should_probe_size,
+    split_download_sections, split_into_segments, system_ytdlp_not_found_message,
+    take_bandwidth_share, time_range_label, verify_media_file, with_retry, BandwidthGuard,
+    ClipPlan, ExpectedMedia,
 };
 use crate::types::{
-    BackendError, DependencySource, DownloadProgress, PluginWorkflowStepSnapshot,
-    PostDownloadPluginPayload,
+    BackendError, DependencySource, DownloadAttempt, DownloadOptions, DownloadProgress,
+    DownloadRequest, FormatOption, PluginWorkflowStepSnapshot, PostDownloadPluginPayload,
 };
 use crate::utils::{
-    build_format_string, format_size, parse_progress, sanitize_output_path, CommandExt,
+    build_explicit_format_string, build_format_string, chapter_output_template,
+    compile_format_preferences, downloaded_media_print_args, format_size, is_progress_line,
+    kill_process_tree, parse_chapter_part, parse_downloaded_media, parse_error_line,
+    parse_format_options, parse_playlist_item, parse_playlist_item_start, parse_progress,
+    playlist_item_print_args, progress_template_args, sanitize_output_path, selected_formats_size,
+    validate_format_selection, validate_output_template, ChapterPart, CommandExt, DownloadedMedia,
+    PlaylistItemRecord, ProgressUpdate,
 };
 
-pub static CANCEL_FLAG: AtomicBool = AtomicBool::new(false);
+/// Running downloads keyed by download id, so each job can be stopped on its own.
+static ACTIVE_DOWNLOADS: LazyLock<Mutex<HashMap<String, ActiveDownload>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Downloads stopped to restart with a new bandwidth share rather than to cancel them.
+static RESTART_REQUESTED: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+fn is_restart_requested(id: &str) -> bool {
+    RESTART_REQUESTED
+        .lock()
+        .map(|ids| ids.contains(id))
+        .unwrap_or(false)
+}
+
+/// Log why a running download stopped and build its error: a bandwidth restart or a stop
+/// by the user.
+fn stopped_download_error(id: &str, url: &str) -> String {
+    if is_restart_requested(id) {
+        add_log_internal(
+            "info",
+            "Download stopped to restart with a new bandwidth share",
+            None,
+            Some(url),
+        )
+        .ok();
+        return BackendError::from_message("Download restarting").to_wire_string();
+    }
+    add_log_internal("info", "Download cancelled by user", None, Some(url)).ok();
+    BackendError::from_message("Download cancelled").to_wire_string()
+}
+
+static BANDWIDTH_BALANCER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// How often bandwidth shares are looked at without a download starting or finishing,
+/// so time-of-day limits take over on time
+const BANDWIDTH_BALANCE_INTERVAL_SECS: u64 = 15;
+
+struct ActiveDownload {
+    cancelled: Arc<AtomicBool>,
+    pid: Option<u32>,
+}
+
+/// Registration of a download in `ACTIVE_DOWNLOADS`, removed again on drop.
+struct ActiveDownloadGuard {
+    id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ActiveDownloadGuard {
+    fn register(id: &str) -> Self {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        if let Ok(mut jobs) = ACTIVE_DOWNLOADS.lock() {
+            jobs.insert(
+                id.to_string(),
+                ActiveDownload {
+                    cancelled: cancelled.clone(),
+                    pid: None,
+                },
+            );
+        }
+        Self {
+            id: id.to_string(),
+            cancelled,
+        }
+    }
+
+    fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Record the spawned process. A stop request that raced the spawn kills it right away.
+    fn set_pid(&self, pid: Option<u32>) {
+        if let Ok(mut jobs) = ACTIVE_DOWNLOADS.lock() {
+            if let Some(job) = jobs.get_mut(&self.id) {
+                if Arc::ptr_eq(&job.cancelled, &self.cancelled) {
+                    job.pid = pid;
+                }
+            }
+        }
+        if self.is_cancelled() {
+            if let Some(pid) = pid {
+                kill_process_tree(pid);
+            }
+        }
+    }
+}
+
+impl Drop for ActiveDownloadGuard {
+    fn drop(&mut self) {
+        if let Ok(mut jobs) = ACTIVE_DOWNLOADS.lock() {
+            let is_current = jobs
+                .get(&self.id)
+                .is_some_and(|job| Arc::ptr_eq(&job.cancelled, &self.cancelled));
+            if is_current {
+                jobs.remove(&self.id);
+            }
+        }
+    }
+}
+
+/// Per-run copy of the download archive handed to yt-dlp via `--download-archive`.
+/// yt-dlp skips the videos listed in it and appends the ones it finishes,
+/// which are copied back into the `download_archive` table on drop.
+struct DownloadArchiveRun {
+    path: std::path::PathBuf,
+    url: String,
+}
+
+impl DownloadArchiveRun {
+    /// A forced re-download starts from an empty file: nothing is skipped,
+    /// but the videos it finishes are still recorded.
+    fn create(id: &str, url: &str, force: bool) -> Result<Self, String> {
+        let lines = if force {
+            Vec::new()
+        } else {
+            get_download_archive_lines_db()?
+        };
+        let mut contents = lines.join("\n");
+        if !contents.is_empty() {
+            contents.push('\n');
+        }
+
+        let path = std::env::temp_dir().join(format!("youwee-archive-{}.txt", id));
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write download archive: {}", e))?;
+        Ok(Self {
+            path,
+            url: url.to_string(),
+        })
+    }
+}
+
+impl Drop for DownloadArchiveRun {
+    fn drop(&mut self) {
+        if let Ok(contents) = std::fs::read_to_string(&self.path) {
+            if let Err(e) = record_download_archive_lines_db(&contents, &self.url) {
+                log::warn!("Failed to record download archive: {}", e);
+            }
+        }
+        std::fs::remove_file(&self.path).ok();
+    }
+}
+
+/// yt-dlp output for a video it skipped because of `--download-archive`.
+fn is_archive_skip_line(line: &str) -> bool {
+    line.contains("has already been recorded in the archive")
+}
+
+fn already_archived_error(key: Option<(String, String)>) -> BackendError {
+    let error = BackendError::new(
+        crate::types::code::DOWNLOAD_ALREADY_ARCHIVED,
+        "Already downloaded (found in download archive)",
+    )
+    .with_retryable(false);
+    match key {
+        Some((extractor, video_id)) => error
+            .with_param("extractor", extractor)
+            .with_param("videoId", video_id),
+        None => error,
+    }
+}
+
+/// Take a video out of a per-run archive file before it is copied to the database.
+fn forget_archive_line(archive_path: &std::path::Path, line: &str) {
+    let Ok(contents) = std::fs::read_to_string(archive_path) else {
+        return;
+    };
+    let kept: String = contents
+        .lines()
+        .filter(|archived| archived.trim() != line)
+        .map(|archived| format!("{}\n", archived))
+        .collect();
+    std::fs::write(archive_path, kept).ok();
+}
+
+/// How finished files are checked with ffprobe when `verify_output` is on.
+#[derive(Clone)]
+struct OutputVerification {
+    audio_only: bool,
+    /// Off for files cut on purpose (time ranges, removed SponsorBlock segments)
+    check_duration: bool,
+    archive_path: Option<std::path::PathBuf>,
+}
+
+impl OutputVerification {
+    /// Check a finished file against what yt-dlp downloaded. A file that fails is
+    /// deleted and taken out of the archive, so a retry downloads it again.
+    async fn check(
+        &self,
+        app: &AppHandle,
+        filepath: &str,
+        media: &DownloadedMedia,
+    ) -> Result<(), BackendError> {
+        let expected = ExpectedMedia::from_download(media, self.audio_only, self.check_duration);
+        let result = verify_media_file(app, filepath, &expected).await;
+        if result.is_err() {
+            std::fs::remove_file(filepath).ok();
+            if let (Some(archive_path), Some(line)) = (&self.archive_path, media.archive_line()) {
+                forget_archive_line(archive_path, &line);
+            }
+        }
+        result
+    }
+}
+
+/// Verification of a download. yt-dlp prints what it downloaded to `media_path`
+/// for a single video; playlist entries carry it in their own record.
+struct OutputCheck {
+    verification: OutputVerification,
+    media_path: Option<std::path::PathBuf>,
+}
+
+impl OutputCheck {
+    async fn run(&self, app: &AppHandle, filepath: &str) -> Result<(), BackendError> {
+        let media = self
+            .media_path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| parse_downloaded_media(&contents))
+            .unwrap_or_default();
+        self.verification.check(app, filepath, &media).await
+    }
+}
+
+impl Drop for OutputCheck {
+    fn drop(&mut self) {
+        if let Some(ref media_path) = self.media_path {
+            std::fs::remove_file(media_path).ok();
+        }
+    }
+}
 
 const RECENT_OUTPUT_LIMIT: usize = 30;
 
 fn extract_time_range(download_sections: &Option<String>) -> Option<String> {
-    download_sections.as_ref().and_then(|s| {
-        let stripped = s.strip_prefix('*').unwrap_or(s);
-        if stripped.is_empty() {
-            None
-        } else {
-            Some(stripped.to_string())
+    download_sections.as_deref().and_then(time_range_label)
+}
+
+/// Paths yt-dlp printed to the `--print-to-file` file: the downloaded file, or for a
+/// download of time ranges (`clip_plan` set) the first clip or joined file and the
+/// other clips as parts.
+async fn read_output_files(
+    app: &AppHandle,
+    id: &str,
+    printed: &str,
+    clip_plan: Option<&ClipPlan>,
+) -> (Option<String>, Vec<ChapterPart>) {
+    match clip_plan {
+        Some(plan) => finish_clips(app, id, printed, plan).await,
+        None => {
+            let path = printed.trim();
+            ((!path.is_empty()).then(|| path.to_string()), Vec::new())
         }
-    })
+    }
 }
 
 async fn skipped_live_status(
@@ -122,6 +390,133 @@ async fn skipped_live_status(
     }
 }
 
+/// Ask yt-dlp how large the formats it would pick are, without downloading them.
+/// `None` when the site doesn't say or the probe fails; the download goes ahead anyway.
+#[allow(clippy::too_many_arguments)]
+async fn probe_download_size(
+    app: &AppHandle,
+    url: &str,
+    format_string: &str,
+    format_sort: Option<&str>,
+    cookie_mode: Option<&str>,
+    cookie_browser: Option<&str>,
+    cookie_browser_profile: Option<&str>,
+    cookie_file_path: Option<&str>,
+    proxy_url: Option<&str>,
+) -> Option<u64> {
+    let mut args = vec![
+        "--simulate".to_string(),
+        "--print".to_string(),
+        "%(filesize,filesize_approx)s".to_string(),
+        "-f".to_string(),
+        format_string.to_string(),
+        "--no-warnings".to_string(),
+        "--no-playlist".to_string(),
+        "--socket-timeout".to_string(),
+        "15".to_string(),
+    ];
+    if let Some(sort) = format_sort {
+        args.push("-S".to_string());
+        args.push(sort.to_string());
+    }
+
+    if url.contains("youtube.com") || url.contains("youtu.be") {
+        if let Some(deno_path) = get_deno_path(app).await {
+            args.push("--js-runtimes".to_string());
+            args.push(format!("deno:{}", deno_path.to_string_lossy()));
+        }
+    }
+
+    args.extend(build_site_header_args(url));
+    args.extend(build_cookie_args(
+        cookie_mode,
+        cookie_browser,
+        cookie_browser_profile,
+        cookie_file_path,
+    ));
+    args.extend(build_proxy_args(proxy_url));
+    args.push("--".to_string());
+    args.push(url.to_string());
+
+    let command_str = format!("yt-dlp {}", args.join(" "));
+    add_log_internal("command", &command_str, None, Some(url)).ok();
+
+    let args_ref: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    let output = run_ytdlp_with_stderr(app, &args_ref).await.ok()?;
+    if !output.success {
+        log::warn!("Size probe failed: {}", output.stderr.trim());
+        return None;
+    }
+    let size: f64 = output.stdout.lines().last()?.trim().parse().ok()?;
+    (size > 0.0).then_some(size as u64)
+}
+
+/// Fetch the formats the extractor offers for a single video,
+/// to check explicitly picked format ids before downloading.
+async fn fetch_format_options(
+    app: &AppHandle,
+    url: &str,
+    cookie_mode: Option<&str>,
+    cookie_browser: Option<&str>,
+    cookie_browser_profile: Option<&str>,
+    cookie_file_path: Option<&str>,
+    proxy_url: Option<&str>,
+) -> Result<Vec<FormatOption>, String> {
+    let mut args = vec![
+        "--dump-single-json".to_string(),
+        "--no-warnings".to_string(),
+        "--no-playlist".to_string(),
+        "--socket-timeout".to_string(),
+        "15".to_string(),
+    ];
+
+    if url.contains("youtube.com") || url.contains("youtu.be") {
+        if let Some(deno_path) = get_deno_path(app).await {
+            args.push("--js-runtimes".to_string());
+            args.push(format!("deno:{}", deno_path.to_string_lossy()));
+        }
+    }
+
+    args.extend(build_site_header_args(url));
+    args.extend(build_cookie_args(
+        cookie_mode,
+        cookie_browser,
+        cookie_browser_profile,
+        cookie_file_path,
+    ));
+    args.extend(build_proxy_args(proxy_url));
+    args.push("--".to_string());
+    args.push(url.to_string());
+
+    let command_str = format!("yt-dlp {}", args.join(" "));
+    add_log_internal("command", &command_str, None, Some(url)).ok();
+
+    let args_ref: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    let args_ref = &args_ref;
+    let stdout = with_retry("Fetching formats", url, || async move {
+        let output = run_ytdlp_with_stderr(app, args_ref).await?;
+        if output.success {
+            return Ok(output.stdout);
+        }
+        let error = parse_ytdlp_error(&output.stderr).unwrap_or_else(|| {
+            let message = output.stderr.trim();
+            BackendError::from_message(if message.is_empty() {
+                "Failed to fetch formats before download.".to_string()
+            } else {
+                format!("Failed to fetch formats before download: {}", message)
+            })
+        });
+        add_log_internal("error", error.message(), None, Some(url)).ok();
+        Err(error.to_wire_string())
+    })
+    .await?;
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).map_err(|e| {
+        BackendError::from_message(format!("Failed to parse format list: {}", e)).to_wire_string()
+    })?;
+    Ok(parse_format_options(&json))
+}
+
 fn workflow_steps_for_trigger(
     app: &AppHandle,
     trigger: &str,
@@ -170,6 +565,8 @@ fn build_trigger_payload(
         history_id,
         time_range,
         download_kind: download_kind.to_string(),
+        relative_path: None,
+        extra_files: Vec::new(),
         workflow_run_id: None,
         workflow_step_index: None,
         workflow_step_plugin_id: None,
@@ -268,6 +665,8 @@ async fn run_completed_plugins(
     history_id: Option<String>,
     time_range: Option<String>,
     download_kind: &str,
+    output_root: &str,
+    extra_files: Vec<String>,
 ) {
     if workflow_steps.is_empty() {
         return;
@@ -283,6 +682,11 @@ async fn run_completed_plugins(
         .parent()
         .map(|parent| parent.to_string_lossy().to_string())
         .unwrap_or_default();
+    // Path below the output folder, so plugins can see folders created by the template.
+    let relative_path = path
+        .strip_prefix(output_root)
+        .ok()
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"));
 
     let payload = PostDownloadPluginPayload {
         job_id: job_id.to_string(),
@@ -300,6 +704,8 @@ async fn run_completed_plugins(
         history_id,
         time_range,
         download_kind: download_kind.to_string(),
+        relative_path,
+        extra_files,
         workflow_run_id: None,
         workflow_step_index: None,
         workflow_step_plugin_id: None,
@@ -309,6 +715,389 @@ async fn run_completed_plugins(
     let _ = enqueue_post_download_workflow(app, workflow_steps.to_vec(), payload);
 }
 
+/// Cut a finished recording into parts of `segment_secs`. The parts replace the full file:
+/// the download's file becomes the first part and the others are recorded as its parts.
+async fn replace_with_segments(
+    app: &AppHandle,
+    final_filepath: &mut Option<String>,
+    chapter_parts: &mut Vec<ChapterPart>,
+    segment_secs: u32,
+) {
+    let Some(filepath) = final_filepath.as_deref() else {
+        return;
+    };
+    let mut segments = split_into_segments(app, filepath, segment_secs)
+        .await
+        .into_iter();
+    if let Some(first) = segments.next() {
+        *final_filepath = Some(first.filepath);
+        chapter_parts.extend(segments);
+    }
+}
+
+/// Store the sampled content hash of a downloaded file, so a library relink can find it
+/// after it is moved or renamed outside the app.
+fn record_history_content_hash(history_id: &str, filepath: &str) {
+    if let Ok(hash) = library_content_hash(std::path::Path::new(filepath)) {
+        set_history_content_hash(history_id, &hash).ok();
+    }
+}
+
+/// Add a history entry for each chapter part written by `--split-chapters` (or later part
+/// cut by `segment_minutes`), linked to the entry of the download, and return the
+/// filepaths of the parts.
+#[allow(clippy::too_many_arguments)]
+fn record_chapter_parts(
+    parts: &[ChapterPart],
+    parent_history_id: Option<&str>,
+    url: &str,
+    thumbnail: Option<String>,
+    quality: Option<String>,
+    format: &str,
+    source: Option<String>,
+) -> Vec<String> {
+    if parts.is_empty() {
+        return Vec::new();
+    }
+    if let Some(parent_id) = parent_history_id {
+        // A re-download splits the video again, so the parts of the last run are replaced.
+        delete_history_parts(parent_id).ok();
+    }
+
+    let mut filepaths = Vec::new();
+    for part in parts {
+        let path = std::path::Path::new(&part.filepath);
+        let Ok(metadata) = std::fs::metadata(path) else {
+            continue;
+        };
+        let title = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(&part.filepath)
+            .to_string();
+        let history_id = add_history_internal(
+            url.to_string(),
+            title,
+            thumbnail.clone(),
+            part.filepath.clone(),
+            Some(metadata.len()),
+            None,
+            quality.clone(),
+            Some(format.to_string()),
+            source.clone(),
+            None,
+        );
+        if let Ok(history_id) = history_id {
+            record_history_content_hash(&history_id, &part.filepath);
+            if let Some(parent_id) = parent_history_id {
+                link_history_part(&history_id, parent_id, part.index).ok();
+            }
+        }
+        filepaths.push(part.filepath.clone());
+    }
+    filepaths
+}
+
+/// Progress identity of one entry of a playlist download.
+fn playlist_item_id(job_id: &str, playlist_index: u32) -> String {
+    format!("{}:{}", job_id, playlist_index)
+}
+
+/// What every entry of a playlist download is recorded with.
+struct PlaylistJob {
+    app: AppHandle,
+    id: String,
+    url: String,
+    quality: String,
+    quality_display: Option<String>,
+    format: String,
+    source: Option<String>,
+    thumbnail: Option<String>,
+    time_range: Option<String>,
+    completed_workflow_steps: Vec<PluginWorkflowStepSnapshot>,
+    download_kind: String,
+    output_root: String,
+    attempt: Option<DownloadAttempt>,
+    verification: Option<OutputVerification>,
+}
+
+/// Entry number yt-dlp is working on and the `ERROR:` lines of entries it gave up on.
+/// Cloned into the stderr reader, which sees the errors while stdout sees the entries start.
+#[derive(Clone, Default)]
+struct PlaylistItemTracker {
+    current_index: Arc<AtomicU32>,
+    failures: Arc<Mutex<Vec<(u32, String)>>>,
+}
+
+impl PlaylistItemTracker {
+    /// Follow a line of yt-dlp output; returns `true` when it starts the next entry.
+    fn observe(&self, line: &str) -> bool {
+        if let Some(index) = parse_playlist_item_start(line) {
+            self.current_index.store(index, Ordering::SeqCst);
+            return true;
+        }
+        if let Some(message) = parse_error_line(line) {
+            // Errors before the first entry belong to the playlist itself.
+            let index = self.current_index.load(Ordering::SeqCst);
+            if index > 0 {
+                if let Ok(mut failures) = self.failures.lock() {
+                    failures.push((index, message.to_string()));
+                }
+            }
+        }
+        false
+    }
+
+    fn take_failures(&self) -> Vec<(u32, String)> {
+        self.failures
+            .lock()
+            .map(|mut failures| std::mem::take(&mut *failures))
+            .unwrap_or_default()
+    }
+}
+
+/// Entries of a playlist download. yt-dlp appends a line to `record_path` for each
+/// entry it finishes (see `playlist_item_print_args`); every entry gets its own
+/// history entry, progress events and `download.completed` run, and an entry that
+/// fails is reported on its own instead of failing the whole playlist.
+struct PlaylistItems {
+    job: PlaylistJob,
+    record_path: std::path::PathBuf,
+    records_read: usize,
+    tracker: PlaylistItemTracker,
+    playlist_id: Option<String>,
+    /// Filepath and size of each finished entry
+    finished: Vec<(String, Option<u64>)>,
+    /// Entry numbers already reported as finished or failed
+    reported: Vec<u32>,
+}
+
+impl PlaylistItems {
+    fn new(job: PlaylistJob, record_path: std::path::PathBuf) -> Self {
+        Self {
+            job,
+            record_path,
+            records_read: 0,
+            tracker: PlaylistItemTracker::default(),
+            playlist_id: None,
+            finished: Vec::new(),
+            reported: Vec::new(),
+        }
+    }
+
+    /// Record the entries finished or failed since the last call.
+    async fn flush(&mut self) {
+        let contents = std::fs::read_to_string(&self.record_path).unwrap_or_default();
+        // A line without its newline is still being written.
+        let lines: Vec<&str> = contents
+            .split_inclusive('\n')
+            .filter(|line| line.ends_with('\n'))
+            .collect();
+        let start = self.records_read;
+        self.records_read = lines.len().max(start);
+        for line in lines.into_iter().skip(start) {
+            if let Some(record) = parse_playlist_item(line) {
+                self.record_finished(record).await;
+            }
+        }
+
+        for (index, message) in self.tracker.take_failures() {
+            if !self.reported.contains(&index) {
+                self.reported.push(index);
+                let error = parse_ytdlp_error(&message).unwrap_or_else(|| {
+                    BackendError::from_message(format!("Download failed: {}", message))
+                });
+                self.report_failed(index, &error);
+            }
+        }
+    }
+
+    async fn record_finished(&mut self, record: PlaylistItemRecord) {
+        if let Some(ref verification) = self.job.verification {
+            if let Err(error) = verification
+                .check(&self.job.app, &record.filepath, &record.media)
+                .await
+            {
+                match record.playlist_index {
+                    Some(index) => {
+                        self.reported.push(index);
+                        self.report_failed(index, &error);
+                    }
+                    None => log::warn!("{}", error.message()),
+                }
+                return;
+            }
+        }
+
+        let job = &self.job;
+        let filepath = record.filepath;
+        let filesize = std::fs::metadata(&filepath).ok().map(|m| m.len());
+        let url = record.webpage_url.unwrap_or_else(|| job.url.clone());
+        let title = record.title.or_else(|| {
+            std::path::Path::new(&filepath)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(|s| s.to_string())
+        });
+        let thumbnail = record
+            .thumbnail
+            .or_else(|| job.thumbnail.clone())
+            .or_else(|| generate_thumbnail_url(&url));
+        let source = job.source.clone().or_else(|| detect_source(&url));
+        let playlist_id = record.playlist_id;
+
+        let details = format!(
+            "Size: {} · Quality: {} · Format: {}",
+            filesize
+                .map(format_size)
+                .unwrap_or_else(|| "Unknown".to_string()),
+            job.quality_display
+                .clone()
+                .unwrap_or_else(|| job.quality.clone()),
+            job.format
+        );
+        let display_title = title.clone().unwrap_or_else(|| "Unknown".to_string());
+        add_log_internal(
+            "success",
+            &format!("Downloaded: {}", display_title),
+            Some(&details),
+            Some(&url),
+        )
+        .ok();
+
+        let history_id = add_history_internal(
+            url.clone(),
+            display_title,
+            thumbnail.clone(),
+            filepath.clone(),
+            filesize,
+            None,
+            job.quality_display.clone(),
+            Some(job.format.clone()),
+            source.clone(),
+            job.time_range.clone(),
+        )
+        .ok();
+        if let Some(history_id) = &history_id {
+            record_history_content_hash(history_id, &filepath);
+        }
+        if let (Some(history_id), Some(index)) = (&history_id, record.playlist_index) {
+            link_history_playlist_item(history_id, playlist_id.as_deref(), index).ok();
+        }
+
+        let item_id = record
+            .playlist_index
+            .map(|index| playlist_item_id(&job.id, index));
+        let progress = DownloadProgress {
+            id: job.id.clone(),
+            percent: 100.0,
+            status: "item_finished".to_string(),
+            title: title.clone(),
+            playlist_index: record.playlist_index,
+            playlist_count: record.n_entries,
+            filesize,
+            resolution: job.quality_display.clone(),
+            format_ext: Some(job.format.clone()),
+            history_id: history_id.clone(),
+            filepath: Some(filepath.clone()),
+            attempt: job.attempt.map(|a| a.attempt),
+            max_attempts: job.attempt.map(|a| a.max_attempts),
+            item_id: item_id.clone(),
+            playlist_id: playlist_id.clone(),
+            ..Default::default()
+        };
+        job.app.emit("download-progress", progress).ok();
+
+        run_completed_plugins(
+            &job.app,
+            &job.completed_workflow_steps,
+            item_id.as_deref().unwrap_or(&job.id),
+            source,
+            &filepath,
+            filesize,
+            Some(job.format.clone()),
+            job.quality_display
+                .clone()
+                .or_else(|| Some(job.quality.clone())),
+            &url,
+            title,
+            thumbnail,
+            history_id,
+            job.time_range.clone(),
+            &job.download_kind,
+            &job.output_root,
+            Vec::new(),
+        )
+        .await;
+
+        if let Some(index) = record.playlist_index {
+            self.reported.push(index);
+        }
+        if playlist_id.is_some() {
+            self.playlist_id = playlist_id;
+        }
+        self.finished.push((filepath, filesize));
+    }
+
+    fn report_failed(&self, index: u32, error: &BackendError) {
+        let job = &self.job;
+        add_log_internal(
+            "error",
+            &format!("Playlist entry {} failed: {}", index, error.message()),
+            None,
+            Some(&job.url),
+        )
+        .ok();
+
+        let progress = DownloadProgress {
+            id: job.id.clone(),
+            status: "item_error".to_string(),
+            playlist_index: Some(index),
+            error_message: Some(error.message().to_string()),
+            error_code: Some(error.code().to_string()),
+            error_params: error.params().cloned(),
+            attempt: job.attempt.map(|a| a.attempt),
+            max_attempts: job.attempt.map(|a| a.max_attempts),
+            item_id: Some(playlist_item_id(&job.id, index)),
+            playlist_id: self.playlist_id.clone(),
+            ..Default::default()
+        };
+        job.app.emit("download-progress", progress).ok();
+    }
+
+    /// Finish the job once yt-dlp exits. Returns `false` when no entry was downloaded,
+    /// leaving the outcome to the usual single-download handling.
+    fn finish(&self, playlist_count: Option<u32>) -> bool {
+        let Some((last_filepath, _)) = self.finished.last() else {
+            return false;
+        };
+        let job = &self.job;
+        let total_size: u64 = self.finished.iter().filter_map(|(_, size)| *size).sum();
+        let progress = DownloadProgress {
+            id: job.id.clone(),
+            percent: 100.0,
+            status: "finished".to_string(),
+            playlist_count,
+            filesize: (total_size > 0).then_some(total_size),
+            resolution: job.quality_display.clone(),
+            format_ext: Some(job.format.clone()),
+            filepath: Some(last_filepath.clone()),
+            attempt: job.attempt.map(|a| a.attempt),
+            max_attempts: job.attempt.map(|a| a.max_attempts),
+            playlist_id: self.playlist_id.clone(),
+            ..Default::default()
+        };
+        job.app.emit("download-progress", progress).ok();
+        true
+    }
+}
+
+impl Drop for PlaylistItems {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.record_path).ok();
+    }
+}
+
 /// Decode raw bytes from a child process into a Rust String.
 ///
 /// On Windows with a non-UTF-8 locale (e.g. Chinese → GBK), yt-dlp outputs
@@ -370,39 +1159,67 @@ fn decode_process_output(bytes: &[u8]) -> String {
     String::from_utf8_lossy(bytes).into_owned()
 }
 
-/// Kill all yt-dlp and ffmpeg processes
-fn kill_all_download_processes() {
-    #[cfg(unix)]
-    {
-        use std::process::Command as StdCommand;
-        StdCommand::new("pkill")
-            .args(["-9", "-f", "yt-dlp"])
-            .spawn()
-            .ok();
-        StdCommand::new("pkill")
-            .args(["-9", "-f", "ffmpeg"])
-            .spawn()
-            .ok();
+/// Build the progress event for a parsed yt-dlp progress line.
+fn progress_event(
+    id: &str,
+    update: ProgressUpdate,
+    title: Option<String>,
+    playlist_index: Option<u32>,
+    playlist_count: Option<u32>,
+    attempt: Option<DownloadAttempt>,
+) -> DownloadProgress {
+    DownloadProgress {
+        id: id.to_string(),
+        percent: update.percent,
+        speed: update.speed,
+        eta: update.eta,
+        status: "downloading".to_string(),
+        title,
+        playlist_index,
+        playlist_count,
+        filesize: None,
+        resolution: None,
+        format_ext: None,
+        error_message: None,
+        error_code: None,
+        error_params: None,
+        history_id: None,
+        filepath: None,
+        downloaded_size: update.downloaded_size,
+        elapsed_time: update.elapsed_time,
+        phase: Some(update.phase),
+        downloaded_bytes: update.downloaded_bytes,
+        total_bytes: update.total_bytes.or(update.total_bytes_estimate),
+        fragment_index: update.fragment_index,
+        fragment_count: update.fragment_count,
+        attempt: attempt.map(|a| a.attempt),
+        max_attempts: attempt.map(|a| a.max_attempts),
+        item_id: playlist_index.map(|index| playlist_item_id(id, index)),
+        playlist_id: None,
     }
-    #[cfg(windows)]
-    {
-        use crate::utils::CommandExt as _;
-        use std::process::Command as StdCommand;
-        let mut cmd1 = StdCommand::new("taskkill");
-        cmd1.args(["/F", "/IM", "yt-dlp.exe"]);
-        cmd1.hide_window();
-        cmd1.spawn().ok();
-
-        let mut cmd2 = StdCommand::new("taskkill");
-        cmd2.args(["/F", "/IM", "ffmpeg.exe"]);
-        cmd2.hide_window();
-        cmd2.spawn().ok();
+}
+
+/// Sum the exact sizes of the streams of a download (e.g. video + audio before merging).
+fn track_stream_size(
+    update: &ProgressUpdate,
+    current_stream_size: &mut Option<u64>,
+    total_filesize: &mut u64,
+) {
+    let Some(size_bytes) = update.total_bytes else {
+        return;
+    };
+    if *current_stream_size != Some(size_bytes) {
+        if let Some(prev_size) = *current_stream_size {
+            *total_filesize += prev_size;
+        }
+        *current_stream_size = Some(size_bytes);
     }
 }
 
 fn push_recent_output(buffer: &mut VecDeque<String>, line: &str) {
     let trimmed = line.trim();
-    if trimmed.is_empty() {
+    // Progress lines would push the actual error output out of the buffer.
+    if trimmed.is_empty() || is_progress_line(trimmed) {
         return;
     }
     if buffer.len() >= RECENT_OUTPUT_LIMIT {
@@ -417,6 +1234,16 @@ fn push_recent_output_shared(buffer: &Arc<Mutex<VecDeque<String>>>, line: &str)
     }
 }
 
+/// Remember the file a queued job is writing so its `.part` data can be resumed or cleaned up.
+fn record_partial_destination(id: &str, line: &str) {
+    if let Some(path) = line.trim().strip_prefix("[download] Destination:") {
+        let path = path.trim();
+        if !path.is_empty() {
+            add_download_queue_item_partial_path_db(id, path).ok();
+        }
+    }
+}
+
 fn recent_output_snapshot(buffer: &Arc<Mutex<VecDeque<String>>>) -> Vec<String> {
     buffer
         .lock()
@@ -491,71 +1318,135 @@ fn build_download_error_message(exit_code: Option<i32>, recent_lines: &[String])
             BackendError::from_message(format!("Download failed (exit code {}): {}", code, reason))
                 .with_param("exitCode", code)
         }
-        None => BackendError::from_message(format!("Download failed: {}", reason)),
+        None => BackendError::from_message(format!("Download failed: {}", reason)),
+    }
+}
+
+/// Whether the caller runs a failed download again, so the failure is not final yet.
+fn will_retry(attempt: Option<DownloadAttempt>, error: &BackendError) -> bool {
+    attempt.is_some_and(|a| a.attempt < a.max_attempts) && is_retryable_error(&error.to_wire())
+}
+
+/// Start the site's rate-limit cooldown for a rate-limited download and tell the caller
+/// how long it lasts, so its own retries wait for it too.
+fn with_rate_limit_cooldown(url: &str, error: BackendError) -> BackendError {
+    match note_rate_limit(url, error.code()) {
+        Some(secs) => error.with_param("cooldownSeconds", secs),
+        None => error,
+    }
+}
+
+/// Download a video or playlist. A download stopped to apply a new bandwidth share runs
+/// again straight away and resumes from its partial file.
+#[tauri::command]
+pub async fn download_video(app: AppHandle, request: DownloadRequest) -> Result<(), String> {
+    let _bandwidth = BandwidthGuard::new(&request.id);
+    journal_download_started(&request.id, &request.url, request.attempt);
+    let mut restarted = false;
+    loop {
+        let result = run_download_video(app.clone(), request.clone(), restarted).await;
+        let restart_requested = RESTART_REQUESTED
+            .lock()
+            .map(|mut ids| ids.remove(&request.id))
+            .unwrap_or(false);
+        if result.is_ok() || !restart_requested {
+            journal_download_finished(&request.id, &request.url, request.attempt, &result);
+            return result;
+        }
+        log::info!(
+            "Restarting download {} with a new bandwidth share",
+            request.id
+        );
+        restarted = true;
     }
 }
 
-#[tauri::command]
-pub async fn download_video(
+/// One yt-dlp run of a download. A `restarted` run continues a run stopped for a new
+/// bandwidth share, so the checks the first run made before starting are not repeated.
+async fn run_download_video(
     app: AppHandle,
-    id: String,
-    url: String,
-    output_path: String,
-    quality: String,
-    format: String,
-    download_playlist: bool,
-    video_codec: String,
-    audio_bitrate: String,
-    playlist_limit: Option<u32>,
-    subtitle_mode: String,
-    subtitle_langs: String,
-    subtitle_embed: bool,
-    subtitle_format: String,
-    log_stderr: Option<bool>,
-    _use_bun_runtime: Option<bool>, // Deprecated - now auto uses deno
-    use_actual_player_js: Option<bool>,
-    history_id: Option<String>,
-    // Cookie settings
-    cookie_mode: Option<String>,
-    cookie_browser: Option<String>,
-    cookie_browser_profile: Option<String>,
-    cookie_file_path: Option<String>,
-    // Embed settings
-    embed_metadata: Option<bool>,
-    embed_thumbnail: Option<bool>,
-    // Proxy settings
-    proxy_url: Option<String>,
-    // Live stream settings
-    live_from_start: Option<bool>,
-    skip_live: Option<bool>,
-    // Speed limit settings
-    speed_limit: Option<String>,
-    // External downloader settings
-    use_aria2: Option<bool>,
-    aria2_args: Option<String>,
-    // SponsorBlock settings
-    sponsorblock_remove: Option<String>, // comma-separated categories to remove
-    sponsorblock_mark: Option<String>,   // comma-separated categories to mark as chapters
-    // Download sections (time range)
-    download_sections: Option<String>, // e.g. "*10:30-14:30" for partial download
-    // Title (optional, passed from frontend for display purposes)
-    title: Option<String>,
-    // Thumbnail URL (optional, passed from frontend for non-YouTube sites)
-    thumbnail: Option<String>,
-    // Source/extractor name (optional, from yt-dlp extractor e.g. "BiliBili", "TikTok")
-    source: Option<String>,
-    // Legacy snapshot of plugin ids enabled when the job was queued
-    post_download_plugins: Option<Vec<String>>,
-    // Snapshot of workflow steps by trigger at queue time
-    plugin_workflow_snapshots: Option<BTreeMap<String, Vec<PluginWorkflowStepSnapshot>>>,
-    // Full workflow step snapshot used for post-processing
-    post_download_workflow_steps: Option<Vec<PluginWorkflowStepSnapshot>>,
-    // When false, caller is responsible for firing the final download.failed workflow.
-    emit_failed_workflow: Option<bool>,
-    // Caller context used in plugin payload
-    download_kind: Option<String>,
+    request: DownloadRequest,
+    restarted: bool,
 ) -> Result<(), String> {
-    CANCEL_FLAG.store(false, Ordering::SeqCst);
+    let DownloadRequest {
+        id,
+        url,
+        title,
+        post_download_plugins,
+        emit_failed_workflow,
+        attempt,
+        resumable,
+        mut options,
+    } = request;
+    let job = ActiveDownloadGuard::register(&id);
+    apply_download_profile(&mut options)?;
+    if let Some(attempt) = attempt.filter(|a| a.attempt > 1 && !restarted) {
+        add_log_internal(
+            "info",
+            &format!(
+                "Retrying download (attempt {}/{})",
+                attempt.attempt, attempt.max_attempts
+            ),
+            None,
+            Some(&url),
+        )
+        .ok();
+    }
+    let DownloadOptions {
+        output_path,
+        quality,
+        format,
+        download_playlist,
+        video_codec,
+        audio_bitrate,
+        playlist_limit,
+        subtitle_mode,
+        subtitle_langs,
+        subtitle_embed,
+        subtitle_format,
+        log_stderr,
+        use_actual_player_js,
+        history_id,
+        cookie_mode,
+        cookie_browser,
+        cookie_browser_profile,
+        cookie_file_path,
+        embed_metadata,
+        embed_thumbnail,
+        proxy_url,
+        live_from_start,
+        skip_live,
+        speed_limit,
+        use_aria2,
+        aria2_args,
+        sponsorblock_remove,
+        sponsorblock_mark,
+        download_sections,
+        join_sections,
+        precise_sections,
+        thumbnail,
+        source,
+        plugin_workflow_snapshots,
+        post_download_workflow_steps,
+        download_kind,
+        force_redownload,
+        output_template,
+        profile: _,
+        video_format_id,
+        audio_format_ids,
+        format_preferences,
+        split_chapters,
+        verify_output,
+        segment_minutes,
+    } = options;
+    // CLI and Telegram requests may leave the folder to the scheduler's default.
+    let output_path = output_path
+        .filter(|path| !path.trim().is_empty())
+        .or_else(|| get_scheduler_config().default_output_path)
+        .ok_or_else(|| {
+            "No output folder configured for queued downloads. Please select an output folder."
+                .to_string()
+        })?;
     validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
     let url = normalize_url(&url);
     let post_download_plugins = post_download_plugins.unwrap_or_default();
@@ -571,8 +1462,31 @@ pub async fn download_video(
     }
     let emit_failed_workflow = emit_failed_workflow.unwrap_or(true);
     let download_kind = download_kind.unwrap_or_else(|| "download".to_string());
+    let force_redownload = bypasses_download_archive(force_redownload, history_id.as_deref());
+
+    // A clip is not a download of the whole video, so time-range jobs bypass the archive.
+    let use_archive = !download_sections
+        .as_deref()
+        .is_some_and(|sections| !sections.trim().is_empty());
+    if use_archive && !force_redownload && !download_playlist && !restarted {
+        if let Some((extractor, video_id)) = archive_key_from_url(&url) {
+            if is_download_archived_db(&extractor, &video_id).unwrap_or(false) {
+                add_log_internal(
+                    "info",
+                    &format!(
+                        "Skipped already downloaded video ({} {})",
+                        extractor, video_id
+                    ),
+                    None,
+                    Some(&url),
+                )
+                .ok();
+                return Err(already_archived_error(Some((extractor, video_id))).to_wire_string());
+            }
+        }
+    }
 
-    if skip_live.unwrap_or(false) {
+    if skip_live.unwrap_or(false) && !restarted {
         if let Some(live_status) = skipped_live_status(
             &app,
             &url,
@@ -607,8 +1521,140 @@ pub async fn download_video(
     let should_log_stderr = log_stderr.unwrap_or(true);
     let sanitized_path = sanitize_output_path(&output_path)
         .map_err(|e| BackendError::from_message(e).to_wire_string())?;
-    let format_string = build_format_string(&quality, &format, &video_codec);
-    let output_template = format!("{}/%(title)s.%(ext)s", sanitized_path);
+    let video_format_id = video_format_id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty());
+    let audio_format_ids: Vec<String> = audio_format_ids
+        .unwrap_or_default()
+        .iter()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect();
+    let explicit_format =
+        build_explicit_format_string(video_format_id.as_deref(), &audio_format_ids);
+    let explicit_size = if explicit_format.is_some() && !restarted {
+        // Format ids belong to one video, so they cannot describe a whole playlist.
+        if download_playlist {
+            return Err(BackendError::new(
+                crate::types::code::VALIDATION_INVALID_INPUT,
+                "Explicit format ids cannot be used for playlist downloads",
+            )
+            .with_retryable(false)
+            .to_wire_string());
+        }
+        let formats = fetch_format_options(
+            &app,
+            &url,
+            cookie_mode.as_deref(),
+            cookie_browser.as_deref(),
+            cookie_browser_profile.as_deref(),
+            cookie_file_path.as_deref(),
+            proxy_url.as_deref(),
+        )
+        .await?;
+        if let Err(error) =
+            validate_format_selection(&formats, video_format_id.as_deref(), &audio_format_ids)
+        {
+            add_log_internal("error", error.message(), None, Some(&url)).ok();
+            return Err(error.to_wire_string());
+        }
+        selected_formats_size(&formats, video_format_id.as_deref(), &audio_format_ids)
+    } else {
+        None
+    };
+    // Explicit ids win over preference rules, which win over the quality/codec preset.
+    let format_plan = match (&explicit_format, &format_preferences) {
+        (None, Some(preferences)) => Some(
+            compile_format_preferences(preferences, &quality, &format)
+                .map_err(|e| e.to_wire_string())?,
+        ),
+        _ => None,
+    };
+    let format_sort = format_plan.as_ref().and_then(|plan| plan.sort.clone());
+    let format_string = explicit_format
+        .or_else(|| format_plan.map(|plan| plan.format))
+        .unwrap_or_else(|| build_format_string(&quality, &format, &video_codec));
+    let output_template = validate_output_template(output_template.as_deref().unwrap_or_default())
+        .map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    // Chapter parts are linked to the history entry of one video, so playlists are not split.
+    let chapter_template = (split_chapters.unwrap_or(false) && !download_playlist).then(|| {
+        format!(
+            "chapter:{}/{}",
+            sanitized_path,
+            chapter_output_template(&output_template)
+        )
+    });
+    // Several ranges of one video are downloaded as clips named after where they start.
+    // Precise cuts download the whole video and cut the clips from it afterwards.
+    let section_ranges = download_sections
+        .as_deref()
+        .map(split_download_sections)
+        .unwrap_or_default();
+    let clip_plan = if download_playlist {
+        None
+    } else {
+        ClipPlan::for_sections(
+            &section_ranges,
+            join_sections.unwrap_or(false),
+            precise_sections.unwrap_or(false),
+        )
+    };
+    let cuts_locally = clip_plan.as_ref().is_some_and(ClipPlan::cuts_locally);
+    let output_template = match &clip_plan {
+        Some(plan) if !plan.cuts_locally() => clip_output_template(&output_template),
+        _ => output_template,
+    };
+    let output_template = format!("{}/{}", sanitized_path, output_template);
+    let is_audio_format =
+        format == "mp3" || format == "m4a" || format == "opus" || quality == "audio";
+
+    // Refuse what cannot fit now rather than failing late with partial files. A restarted
+    // run already passed this check and has part of the file on disk.
+    let folder_quota =
+        quota_for_folder(&get_scheduler_config().folder_quotas, &sanitized_path).cloned();
+    let available = available_space(std::path::Path::new(&sanitized_path));
+    let estimated_size = match explicit_size {
+        Some(size) => Some(size),
+        None if restarted => None,
+        None if !download_playlist && should_probe_size(available, folder_quota.is_some()) => {
+            probe_download_size(
+                &app,
+                &url,
+                &format_string,
+                format_sort.as_deref(),
+                cookie_mode.as_deref(),
+                cookie_browser.as_deref(),
+                cookie_browser_profile.as_deref(),
+                cookie_file_path.as_deref(),
+                proxy_url.as_deref(),
+            )
+            .await
+        }
+        None => None,
+    };
+    let keeps_intermediate = is_audio_format || format_string.contains('+');
+    let storage_check = if restarted {
+        Ok(())
+    } else {
+        // Walked off the async runtime; a fresh size also updates the scheduler's copy
+        let quota_usage = match folder_quota {
+            Some(ref quota) => measure_folder_usage(std::path::Path::new(&quota.path)).await,
+            None => 0,
+        };
+        check_free_space(
+            &sanitized_path,
+            available,
+            required_space(estimated_size, keeps_intermediate),
+        )
+        .and_then(|()| match folder_quota {
+            Some(ref quota) => check_quota(quota, quota_usage, estimated_size),
+            None => Ok(()),
+        })
+    };
+    if let Err(error) = storage_check {
+        add_log_internal("error", error.message(), None, Some(&url)).ok();
+        return Err(error.to_wire_string());
+    }
 
     // Use a temp file to capture the final filepath from yt-dlp.
     // On Windows with non-UTF-8 locales (e.g. Chinese/GBK), stdout is encoded
@@ -616,6 +1662,12 @@ pub async fn download_video(
     // (such as ⧸ U+29F8 used by yt-dlp to replace / in filenames).
     // --print-to-file always writes UTF-8, so we get the exact filepath.
     let filepath_tmp = std::env::temp_dir().join(format!("youwee-fp-{}.txt", id));
+    // Playlist entries finished by this run; a retry reuses the id, so start from scratch.
+    let playlist_record =
+        download_playlist.then(|| std::env::temp_dir().join(format!("youwee-items-{}.txt", id)));
+    if let Some(ref record_path) = playlist_record {
+        std::fs::remove_file(record_path).ok();
+    }
 
     let mut args = vec![
         "--newline".to_string(),
@@ -639,6 +1691,37 @@ pub async fn download_video(
         "--file-access-retries".to_string(),
         "2".to_string(),
     ];
+    args.extend(progress_template_args());
+    if let Some(sort) = format_sort {
+        args.push("-S".to_string());
+        args.push(sort);
+    }
+    if audio_format_ids.len() > 1 {
+        args.push("--audio-multistreams".to_string());
+    }
+    if let Some(chapter_template) = chapter_template {
+        args.push("--split-chapters".to_string());
+        args.push("-o".to_string());
+        args.push(chapter_template);
+    }
+
+    // Let yt-dlp consult the archive per video (covers playlist entries and sites
+    // whose ids can't be read from the URL), and record what this run downloads.
+    let archive_run = if use_archive {
+        match DownloadArchiveRun::create(&id, &url, force_redownload) {
+            Ok(run) => {
+                args.push("--download-archive".to_string());
+                args.push(run.path.to_string_lossy().to_string());
+                Some(run)
+            }
+            Err(e) => {
+                log::warn!("Download archive unavailable: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
 
     // Auto use Deno runtime for YouTube (required for JS extractor)
     // Use --js-runtimes instead of --extractor-args (handles spaces in path correctly)
@@ -727,12 +1810,18 @@ pub async fn download_video(
         args.push("--no-part".to_string());
     }
 
-    // Speed limit settings
-    if let Some(limit) = speed_limit.as_ref() {
-        if !limit.is_empty() {
-            args.push("--limit-rate".to_string());
-            args.push(limit.clone());
-        }
+    // Speed limit: the download's own limit, capped by its share of the global budget.
+    // Only single videos are restarted when the share moves; the rest keep their first share.
+    let restartable = !download_playlist
+        && !live_from_start.unwrap_or(false)
+        && download_kind != "live"
+        && download_sections
+            .as_deref()
+            .map_or(true, |sections| sections.trim().is_empty());
+    let own_speed_limit = speed_limit.as_deref().and_then(parse_rate);
+    if let Some(limit) = take_bandwidth_share(&id, own_speed_limit, restartable) {
+        args.push("--limit-rate".to_string());
+        args.push(limit.to_string());
     }
 
     // External downloader settings (aria2c)
@@ -747,8 +1836,14 @@ pub async fn download_video(
         }
     }
 
-    // Force overwrite to avoid HTTP 416 errors from stale .part files
-    args.push("--force-overwrites".to_string());
+    if resumable.unwrap_or(false) || restarted {
+        // Keep and resume .part files so a paused, interrupted or restarted job picks up where
+        // it stopped. yt-dlp restarts the file itself when the server rejects the resume range.
+        args.push("--continue".to_string());
+    } else {
+        // Force overwrite to avoid HTTP 416 errors from stale .part files
+        args.push("--force-overwrites".to_string());
+    }
 
     // Playlist handling
     if !download_playlist {
@@ -759,11 +1854,42 @@ pub async fn download_video(
             args.push(limit.to_string());
         }
     }
+    if let Some(ref record_path) = playlist_record {
+        // Keep going past a failed entry; each entry is reported on its own.
+        args.push("--no-abort-on-error".to_string());
+        args.extend(playlist_item_print_args(&record_path.to_string_lossy()));
+    }
 
-    // Audio formats
-    let is_audio_format =
-        format == "mp3" || format == "m4a" || format == "opus" || quality == "audio";
+    // Cut long recordings into parts once finished, replacing the full file; a playlist is
+    // left whole.
+    let segment_secs = segment_minutes
+        .filter(|minutes| *minutes > 0 && !download_playlist)
+        .map(|minutes| minutes * 60);
+
+    // Check the finished file with ffprobe; a cut file is not compared by duration.
+    let output_check = verify_output.unwrap_or(false).then(|| {
+        let media_path = (!download_playlist)
+            .then(|| std::env::temp_dir().join(format!("youwee-media-{}.txt", id)));
+        if let Some(ref media_path) = media_path {
+            std::fs::remove_file(media_path).ok();
+            args.extend(downloaded_media_print_args(&media_path.to_string_lossy()));
+        }
+        OutputCheck {
+            verification: OutputVerification {
+                audio_only: is_audio_format,
+                check_duration: !download_sections
+                    .as_deref()
+                    .is_some_and(|sections| !sections.trim().is_empty())
+                    && !sponsorblock_remove
+                        .as_deref()
+                        .is_some_and(|categories| !categories.is_empty()),
+                archive_path: archive_run.as_ref().map(|run| run.path.clone()),
+            },
+            media_path,
+        }
+    });
 
+    // Audio formats
     if is_audio_format {
         args.push("-x".to_string());
         args.push("--audio-format".to_string());
@@ -808,11 +1934,11 @@ pub async fn download_video(
         }
     }
 
-    // Download sections (time range)
-    if let Some(ref sections) = download_sections {
-        if !sections.is_empty() {
+    // Download sections (time ranges), one clip per range
+    if !cuts_locally {
+        for range in section_ranges {
             args.push("--download-sections".to_string());
-            args.push(sections.clone());
+            args.push(range);
         }
     }
 
@@ -891,6 +2017,8 @@ pub async fn download_video(
             }
         };
 
+        job.set_pid(process.id());
+
         enqueue_before_start_workflow(
             &app,
             &before_start_steps,
@@ -919,13 +2047,19 @@ pub async fn download_video(
             thumbnail,
             source,
             download_sections,
+            clip_plan,
             history_id.clone(),
             filepath_tmp.clone(),
+            playlist_record,
+            output_check,
+            segment_secs,
             sanitized_path.clone(),
             completed_workflow_steps.clone(),
             failed_workflow_steps.clone(),
             emit_failed_workflow,
             download_kind.clone(),
+            job.cancel_flag(),
+            attempt,
         )
         .await;
     }
@@ -989,6 +2123,8 @@ pub async fn download_video(
                 }
             };
 
+            job.set_pid(Some(child.pid()));
+
             enqueue_before_start_workflow(
                 &app,
                 &before_start_steps,
@@ -1013,6 +2149,8 @@ pub async fn download_video(
             let mut total_filesize: u64 = 0;
             let mut current_stream_size: Option<u64> = None;
             let mut final_filepath: Option<String> = None;
+            let mut chapter_parts: Vec<ChapterPart> = Vec::new();
+            let mut archive_skipped = false;
             let mut recent_output: VecDeque<String> = VecDeque::new();
 
             let quality_display = match quality.as_str() {
@@ -1027,29 +2165,46 @@ pub async fn download_video(
                 "best" => Some("Best".to_string()),
                 _ => None,
             };
+            let mut playlist_items = playlist_record.map(|record_path| {
+                PlaylistItems::new(
+                    PlaylistJob {
+                        app: app.clone(),
+                        id: id.clone(),
+                        url: url.clone(),
+                        quality: quality.clone(),
+                        quality_display: quality_display.clone(),
+                        format: format.clone(),
+                        source: source.clone(),
+                        thumbnail: thumbnail.clone(),
+                        time_range: extract_time_range(&download_sections),
+                        completed_workflow_steps: completed_workflow_steps.clone(),
+                        download_kind: download_kind.clone(),
+                        output_root: sanitized_path.clone(),
+                        attempt,
+                        verification: output_check
+                            .as_ref()
+                            .map(|check| check.verification.clone()),
+                    },
+                    record_path,
+                )
+            });
 
             while let Some(event) = rx.recv().await {
-                if CANCEL_FLAG.load(Ordering::SeqCst) {
+                if job.is_cancelled() {
                     child.kill().ok();
-                    kill_all_download_processes();
-                    return Err(BackendError::from_message("Download cancelled").to_wire_string());
+                    return Err(stopped_download_error(&id, &url));
                 }
 
                 match event {
                     CommandEvent::Stdout(line_bytes) => {
                         let line = decode_process_output(&line_bytes);
                         push_recent_output(&mut recent_output, &line);
-
-                        // Parse playlist item info
-                        if line.contains("Downloading item") {
-                            if let Some(re) =
-                                regex::Regex::new(r"Downloading item (\d+) of (\d+)").ok()
-                            {
-                                if let Some(caps) = re.captures(&line) {
-                                    current_index =
-                                        caps.get(1).and_then(|m| m.as_str().parse().ok());
-                                    total_count = caps.get(2).and_then(|m| m.as_str().parse().ok());
-                                }
+                        record_partial_destination(&id, &line);
+                        archive_skipped |= is_archive_skip_line(&line);
+                        chapter_parts.extend(parse_chapter_part(&line));
+                        if let Some(items) = playlist_items.as_mut() {
+                            if items.tracker.observe(&line) {
+                                items.flush().await;
                             }
                         }
 
@@ -1091,67 +2246,30 @@ pub async fn download_video(
                             final_filepath = Some(trimmed.to_string());
                         }
 
-                        // Parse filesize
-                        if line.contains(" of ")
-                            && (line.contains("MiB")
-                                || line.contains("GiB")
-                                || line.contains("KiB"))
-                        {
-                            if let Some(re) =
-                                regex::Regex::new(r"of\s+(\d+(?:\.\d+)?)\s*(GiB|MiB|KiB)").ok()
-                            {
-                                if let Some(caps) = re.captures(&line) {
-                                    if let (Some(num), Some(unit)) = (caps.get(1), caps.get(2)) {
-                                        if let Ok(size) = num.as_str().parse::<f64>() {
-                                            let size_bytes = match unit.as_str() {
-                                                "GiB" => (size * 1024.0 * 1024.0 * 1024.0) as u64,
-                                                "MiB" => (size * 1024.0 * 1024.0) as u64,
-                                                "KiB" => (size * 1024.0) as u64,
-                                                _ => size as u64,
-                                            };
-                                            if current_stream_size != Some(size_bytes) {
-                                                if let Some(prev_size) = current_stream_size {
-                                                    total_filesize += prev_size;
-                                                }
-                                                current_stream_size = Some(size_bytes);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
                         // Parse progress
-                        if let Some((percent, speed, eta, pi, pc, downloaded_size, elapsed_time)) =
-                            parse_progress(&line)
-                        {
-                            if pi.is_some() {
-                                current_index = pi;
+                        if let Some(update) = parse_progress(&line) {
+                            if update.playlist_index.is_some() {
+                                current_index = update.playlist_index;
                             }
-                            if pc.is_some() {
-                                total_count = pc;
+                            if update.playlist_count.is_some() {
+                                total_count = update.playlist_count;
                             }
+                            track_stream_size(
+                                &update,
+                                &mut current_stream_size,
+                                &mut total_filesize,
+                            );
 
-                            let progress = DownloadProgress {
-                                id: id.clone(),
-                                percent,
-                                speed,
-                                eta,
-                                status: "downloading".to_string(),
-                                title: current_title.clone(),
-                                playlist_index: current_index,
-                                playlist_count: total_count,
-                                filesize: None,
-                                resolution: None,
-                                format_ext: None,
-                                error_message: None,
-                                error_code: None,
-                                error_params: None,
-                                history_id: None,
-                                filepath: None,
-                                downloaded_size,
-                                elapsed_time,
-                            };
+                            note_bandwidth_progress(&id, &update);
+                            journal_download_progress(&id, &update);
+                            let progress = progress_event(
+                                &id,
+                                update,
+                                current_title.clone(),
+                                current_index,
+                                total_count,
+                                attempt,
+                            );
                             app.emit("download-progress", progress).ok();
                         }
                     }
@@ -1159,37 +2277,28 @@ pub async fn download_video(
                         let stderr_line = decode_process_output(&bytes);
                         let stderr_line = stderr_line.trim().to_string();
                         push_recent_output(&mut recent_output, &stderr_line);
+                        if let Some(items) = playlist_items.as_ref() {
+                            items.tracker.observe(&stderr_line);
+                        }
 
-                        if let Some((percent, speed, eta, pi, pc, downloaded_size, elapsed_time)) =
-                            parse_progress(&stderr_line)
-                        {
-                            if pi.is_some() {
-                                current_index = pi;
+                        if let Some(update) = parse_progress(&stderr_line) {
+                            if update.playlist_index.is_some() {
+                                current_index = update.playlist_index;
                             }
-                            if pc.is_some() {
-                                total_count = pc;
+                            if update.playlist_count.is_some() {
+                                total_count = update.playlist_count;
                             }
 
-                            let progress = DownloadProgress {
-                                id: id.clone(),
-                                percent,
-                                speed,
-                                eta,
-                                status: "downloading".to_string(),
-                                title: current_title.clone(),
-                                playlist_index: current_index,
-                                playlist_count: total_count,
-                                filesize: None,
-                                resolution: None,
-                                format_ext: None,
-                                error_message: None,
-                                error_code: None,
-                                error_params: None,
-                                history_id: None,
-                                filepath: None,
-                                downloaded_size,
-                                elapsed_time,
-                            };
+                            note_bandwidth_progress(&id, &update);
+                            journal_download_progress(&id, &update);
+                            let progress = progress_event(
+                                &id,
+                                update,
+                                current_title.clone(),
+                                current_index,
+                                total_count,
+                                attempt,
+                            );
                             app.emit("download-progress", progress).ok();
                         }
 
@@ -1220,29 +2329,56 @@ pub async fn download_video(
                         return Err(error.to_wire_string());
                     }
                     CommandEvent::Terminated(status) => {
-                        if CANCEL_FLAG.load(Ordering::SeqCst) {
+                        if job.is_cancelled() {
+                            return Err(stopped_download_error(&id, &url));
+                        }
+
+                        // Primary filepath source: read from --print-to-file temp file (UTF-8)
+                        if let Ok(contents) = std::fs::read_to_string(&filepath_tmp) {
+                            let (path, clips) =
+                                read_output_files(&app, &id, &contents, clip_plan.as_ref()).await;
+                            if path.is_some() {
+                                final_filepath = path;
+                            }
+                            chapter_parts.extend(clips);
+                        }
+                        std::fs::remove_file(&filepath_tmp).ok();
+
+                        if let Some(items) = playlist_items.as_mut() {
+                            items.flush().await;
+                            if items.finish(total_count) {
+                                return Ok(());
+                            }
+                        }
+
+                        if status.code == Some(0) && final_filepath.is_none() && archive_skipped {
                             add_log_internal(
                                 "info",
-                                "Download cancelled by user",
+                                "Skipped already downloaded video",
                                 None,
                                 Some(&url),
                             )
                             .ok();
-                            return Err(
-                                BackendError::from_message("Download cancelled").to_wire_string()
-                            );
+                            return Err(already_archived_error(None).to_wire_string());
                         }
 
-                        // Primary filepath source: read from --print-to-file temp file (UTF-8)
-                        if let Ok(contents) = std::fs::read_to_string(&filepath_tmp) {
-                            let path = contents.trim().to_string();
-                            if !path.is_empty() {
-                                final_filepath = Some(path);
+                        let verify_error = match (&output_check, &final_filepath) {
+                            (Some(check), Some(filepath)) if status.code == Some(0) => {
+                                check.run(&app, filepath).await.err()
                             }
-                        }
-                        std::fs::remove_file(&filepath_tmp).ok();
+                            _ => None,
+                        };
 
-                        if status.code == Some(0) {
+                        if status.code == Some(0) && verify_error.is_none() {
+                            if let Some(secs) = segment_secs {
+                                replace_with_segments(
+                                    &app,
+                                    &mut final_filepath,
+                                    &mut chapter_parts,
+                                    secs,
+                                )
+                                .await;
+                            }
                             let actual_filesize = final_filepath
                                 .as_ref()
                                 .and_then(|fp| std::fs::metadata(fp).ok())
@@ -1327,6 +2463,21 @@ pub async fn download_video(
                             } else {
                                 None
                             };
+                            if let (Some(history_id), Some(filepath)) =
+                                (&progress_history_id, &final_filepath)
+                            {
+                                record_history_content_hash(history_id, filepath);
+                            }
+
+                            let chapter_files = record_chapter_parts(
+                                &chapter_parts,
+                                progress_history_id.as_deref(),
+                                &url,
+                                thumbnail.clone().or_else(|| generate_thumbnail_url(&url)),
+                                quality_display.clone(),
+                                &format,
+                                source.clone().or_else(|| detect_source(&url)),
+                            );
 
                             let progress = DownloadProgress {
                                 id: id.clone(),
@@ -1347,6 +2498,15 @@ pub async fn download_video(
                                 filepath: final_filepath.clone(),
                                 downloaded_size: None,
                                 elapsed_time: None,
+                                phase: None,
+                                downloaded_bytes: None,
+                                total_bytes: None,
+                                fragment_index: None,
+                                fragment_count: None,
+                                attempt: attempt.map(|a| a.attempt),
+                                max_attempts: attempt.map(|a| a.max_attempts),
+                                item_id: None,
+                                playlist_id: None,
                             };
                             app.emit("download-progress", progress).ok();
                             if let Some(ref filepath) = final_filepath {
@@ -1365,13 +2525,20 @@ pub async fn download_video(
                                     progress_history_id.clone(),
                                     extract_time_range(&download_sections),
                                     &download_kind,
+                                    &sanitized_path,
+                                    chapter_files,
                                 )
                                 .await;
                             }
                             return Ok(());
                         } else {
                             let recent_lines: Vec<String> = recent_output.iter().cloned().collect();
-                            let error = build_download_error_message(status.code, &recent_lines);
+                            let error = verify_error.unwrap_or_else(|| {
+                                with_rate_limit_cooldown(
+                                    &url,
+                                    build_download_error_message(status.code, &recent_lines),
+                                )
+                            });
                             add_log_internal("error", error.message(), None, Some(&url)).ok();
 
                             // Emit error progress so frontend can display error message
@@ -1394,10 +2561,22 @@ pub async fn download_video(
                                 filepath: None,
                                 downloaded_size: None,
                                 elapsed_time: None,
+                                phase: None,
+                                downloaded_bytes: None,
+                                total_bytes: None,
+                                fragment_index: None,
+                                fragment_count: None,
+                                attempt: attempt.map(|a| a.attempt),
+                                max_attempts: attempt.map(|a| a.max_attempts),
+                                item_id: None,
+                                playlist_id: None,
                             };
                             app.emit("download-progress", progress).ok();
 
-                            if emit_failed_workflow && !failed_workflow_steps.is_empty() {
+                            if emit_failed_workflow
+                                && !failed_workflow_steps.is_empty()
+                                && !will_retry(attempt, &error)
+                            {
                                 let payload = build_trigger_payload(
                                     &id,
                                     source.clone().or_else(|| detect_source(&url)),
@@ -1490,6 +2669,8 @@ pub async fn download_video(
                 }
             };
 
+            job.set_pid(process.id());
+
             enqueue_before_start_workflow(
                 &app,
                 &before_start_steps,
@@ -1518,13 +2699,19 @@ pub async fn download_video(
                 thumbnail,
                 source,
                 download_sections,
+                clip_plan,
                 history_id.clone(),
                 filepath_tmp,
+                playlist_record,
+                output_check,
+                segment_secs,
                 sanitized_path,
                 completed_workflow_steps,
                 failed_workflow_steps,
                 emit_failed_workflow,
                 download_kind,
+                job.cancel_flag(),
+                attempt,
             )
             .await
         }
@@ -1543,13 +2730,19 @@ async fn handle_tokio_download(
     thumbnail: Option<String>,
     source: Option<String>,
     download_sections: Option<String>,
+    clip_plan: Option<ClipPlan>,
     history_id: Option<String>,
     filepath_tmp: std::path::PathBuf,
+    playlist_record: Option<std::path::PathBuf>,
+    output_check: Option<OutputCheck>,
+    segment_secs: Option<u32>,
     output_directory: String,
     completed_workflow_steps: Vec<PluginWorkflowStepSnapshot>,
     failed_workflow_steps: Vec<PluginWorkflowStepSnapshot>,
     emit_failed_workflow: bool,
     download_kind: String,
+    cancelled: Arc<AtomicBool>,
+    attempt: Option<DownloadAttempt>,
 ) -> Result<(), String> {
     let stdout = process
         .stdout
@@ -1565,6 +2758,8 @@ async fn handle_tokio_download(
     let mut total_filesize: u64 = 0;
     let mut current_stream_size: Option<u64> = None;
     let mut final_filepath: Option<String> = None;
+    let mut chapter_parts: Vec<ChapterPart> = Vec::new();
+    let mut archive_skipped = false;
     let recent_output = Arc::new(Mutex::new(VecDeque::new()));
     let stderr_filepath: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
 
@@ -1580,6 +2775,29 @@ async fn handle_tokio_download(
         "best" => Some("Best".to_string()),
         _ => None,
     };
+    let mut playlist_items = playlist_record.map(|record_path| {
+        PlaylistItems::new(
+            PlaylistJob {
+                app: app.clone(),
+                id: id.clone(),
+                url: url.clone(),
+                quality: quality.clone(),
+                quality_display: quality_display.clone(),
+                format: format.clone(),
+                source: source.clone(),
+                thumbnail: thumbnail.clone(),
+                time_range: extract_time_range(&download_sections),
+                completed_workflow_steps: completed_workflow_steps.clone(),
+                download_kind: download_kind.clone(),
+                output_root: output_directory.clone(),
+                attempt,
+                verification: output_check
+                    .as_ref()
+                    .map(|check| check.verification.clone()),
+            },
+            record_path,
+        )
+    });
 
     // Spawn task to read stderr in parallel (for live stream progress)
     let stderr_app = app.clone();
@@ -1587,6 +2805,8 @@ async fn handle_tokio_download(
     let stderr_url = url.clone();
     let stderr_recent_output = recent_output.clone();
     let stderr_fp_clone = stderr_filepath.clone();
+    let stderr_cancelled = cancelled.clone();
+    let stderr_playlist = playlist_items.as_ref().map(|items| items.tracker.clone());
     let stderr_task = if let Some(stderr_handle) = stderr {
         Some(tokio::spawn(async move {
             let mut stderr_reader = BufReader::new(stderr_handle);
@@ -1603,10 +2823,13 @@ async fn handle_tokio_download(
                 }
                 let line = decode_process_output(&line_buf);
 
-                if CANCEL_FLAG.load(Ordering::SeqCst) {
+                if stderr_cancelled.load(Ordering::SeqCst) {
                     break;
                 }
                 push_recent_output_shared(&stderr_recent_output, &line);
+                if let Some(ref tracker) = stderr_playlist {
+                    tracker.observe(&line);
+                }
 
                 // On Windows, yt-dlp may print --print after_move:filepath to stderr.
                 // Capture it here as a fallback in case stdout doesn't contain the path.
@@ -1641,29 +2864,19 @@ async fn handle_tokio_download(
                 }
 
                 // Parse progress from stderr (live streams output here)
-                if let Some((percent, speed, eta, pi, pc, downloaded_size, elapsed_time)) =
-                    parse_progress(&line)
-                {
-                    let progress = DownloadProgress {
-                        id: stderr_id.clone(),
-                        percent,
-                        speed,
-                        eta,
-                        status: "downloading".to_string(),
-                        title: None,
-                        playlist_index: pi,
-                        playlist_count: pc,
-                        filesize: None,
-                        resolution: None,
-                        format_ext: None,
-                        error_message: None,
-                        error_code: None,
-                        error_params: None,
-                        history_id: None,
-                        filepath: None,
-                        downloaded_size,
-                        elapsed_time,
-                    };
+                if let Some(update) = parse_progress(&line) {
+                    let (playlist_index, playlist_count) =
+                        (update.playlist_index, update.playlist_count);
+                    note_bandwidth_progress(&stderr_id, &update);
+                    journal_download_progress(&stderr_id, &update);
+                    let progress = progress_event(
+                        &stderr_id,
+                        update,
+                        None,
+                        playlist_index,
+                        playlist_count,
+                        attempt,
+                    );
                     stderr_app.emit("download-progress", progress).ok();
                 }
 
@@ -1695,44 +2908,40 @@ async fn handle_tokio_download(
         }
         let line = decode_process_output(&stdout_line_buf);
 
-        if CANCEL_FLAG.load(Ordering::SeqCst) {
+        if cancelled.load(Ordering::SeqCst) {
             process.kill().await.ok();
-            kill_all_download_processes();
-            return Err(BackendError::from_message("Download cancelled").to_wire_string());
+            return Err(stopped_download_error(&id, &url));
         }
         push_recent_output_shared(&recent_output, &line);
+        record_partial_destination(&id, &line);
+        archive_skipped |= is_archive_skip_line(&line);
+        chapter_parts.extend(parse_chapter_part(&line));
+        if let Some(items) = playlist_items.as_mut() {
+            if items.tracker.observe(&line) {
+                items.flush().await;
+            }
+        }
 
         // Parse progress and emit events
-        if let Some((percent, speed, eta, pi, pc, downloaded_size, elapsed_time)) =
-            parse_progress(&line)
-        {
-            if pi.is_some() {
-                current_index = pi;
+        if let Some(update) = parse_progress(&line) {
+            if update.playlist_index.is_some() {
+                current_index = update.playlist_index;
             }
-            if pc.is_some() {
-                total_count = pc;
+            if update.playlist_count.is_some() {
+                total_count = update.playlist_count;
             }
+            track_stream_size(&update, &mut current_stream_size, &mut total_filesize);
 
-            let progress = DownloadProgress {
-                id: id.clone(),
-                percent,
-                speed,
-                eta,
-                status: "downloading".to_string(),
-                title: current_title.clone(),
-                playlist_index: current_index,
-                playlist_count: total_count,
-                filesize: None,
-                resolution: None,
-                format_ext: None,
-                error_message: None,
-                error_code: None,
-                error_params: None,
-                history_id: None,
-                filepath: None,
-                downloaded_size,
-                elapsed_time,
-            };
+            note_bandwidth_progress(&id, &update);
+            journal_download_progress(&id, &update);
+            let progress = progress_event(
+                &id,
+                update,
+                current_title.clone(),
+                current_index,
+                total_count,
+                attempt,
+            );
             app.emit("download-progress", progress).ok();
         }
 
@@ -1778,30 +2987,6 @@ async fn handle_tokio_download(
                 }
             }
         }
-
-        // Parse filesize
-        if line.contains(" of ") && (line.contains("MiB") || line.contains("GiB")) {
-            if let Some(re) = regex::Regex::new(r"of\s+(\d+(?:\.\d+)?)\s*(GiB|MiB|KiB)").ok() {
-                if let Some(caps) = re.captures(&line) {
-                    if let (Some(num), Some(unit)) = (caps.get(1), caps.get(2)) {
-                        if let Ok(size) = num.as_str().parse::<f64>() {
-                            let size_bytes = match unit.as_str() {
-                                "GiB" => (size * 1024.0 * 1024.0 * 1024.0) as u64,
-                                "MiB" => (size * 1024.0 * 1024.0) as u64,
-                                "KiB" => (size * 1024.0) as u64,
-                                _ => size as u64,
-                            };
-                            if current_stream_size != Some(size_bytes) {
-                                if let Some(prev_size) = current_stream_size {
-                                    total_filesize += prev_size;
-                                }
-                                current_stream_size = Some(size_bytes);
-                            }
-                        }
-                    }
-                }
-            }
-        }
     }
 
     // Wait for stderr task to finish reading all lines.
@@ -1838,14 +3023,20 @@ async fn handle_tokio_download(
         }
     };
 
+    if cancelled.load(Ordering::SeqCst) {
+        std::fs::remove_file(&filepath_tmp).ok();
+        return Err(stopped_download_error(&id, &url));
+    }
+
     // Primary filepath source: read from the --print-to-file temp file (UTF-8).
     // This is reliable on all platforms, especially Windows with non-UTF-8 locales
     // where stdout encoding (GBK) corrupts Unicode characters in file paths.
     if let Ok(contents) = std::fs::read_to_string(&filepath_tmp) {
-        let path = contents.trim().to_string();
-        if !path.is_empty() {
-            final_filepath = Some(path);
+        let (path, clips) = read_output_files(&app, &id, &contents, clip_plan.as_ref()).await;
+        if path.is_some() {
+            final_filepath = path;
         }
+        chapter_parts.extend(clips);
     }
     // Clean up the temp file
     std::fs::remove_file(&filepath_tmp).ok();
@@ -1859,7 +3050,27 @@ async fn handle_tokio_download(
         }
     }
 
-    if status.success() {
+    if let Some(items) = playlist_items.as_mut() {
+        items.flush().await;
+        if items.finish(total_count) {
+            return Ok(());
+        }
+    }
+
+    if status.success() && final_filepath.is_none() && archive_skipped {
+        add_log_internal("info", "Skipped already downloaded video", None, Some(&url)).ok();
+        return Err(already_archived_error(None).to_wire_string());
+    }
+
+    let verify_error = match (&output_check, &final_filepath) {
+        (Some(check), Some(filepath)) if status.success() => check.run(&app, filepath).await.err(),
+        _ => None,
+    };
+
+    if status.success() && verify_error.is_none() {
+        if let Some(secs) = segment_secs {
+            replace_with_segments(&app, &mut final_filepath, &mut chapter_parts, secs).await;
+        }
         let actual_filesize = final_filepath
             .as_ref()
             .and_then(|fp| std::fs::metadata(fp).ok())
@@ -1940,6 +3151,19 @@ async fn handle_tokio_download(
         } else {
             None
         };
+        if let (Some(history_id), Some(filepath)) = (&progress_history_id, &final_filepath) {
+            record_history_content_hash(history_id, filepath);
+        }
+
+        let chapter_files = record_chapter_parts(
+            &chapter_parts,
+            progress_history_id.as_deref(),
+            &url,
+            thumbnail.clone().or_else(|| generate_thumbnail_url(&url)),
+            quality_display.clone(),
+            &format,
+            source.clone().or_else(|| detect_source(&url)),
+        );
 
         let progress = DownloadProgress {
             id: id.clone(),
@@ -1960,6 +3184,15 @@ async fn handle_tokio_download(
             filepath: final_filepath.clone(),
             downloaded_size: None,
             elapsed_time: None,
+            phase: None,
+            downloaded_bytes: None,
+            total_bytes: None,
+            fragment_index: None,
+            fragment_count: None,
+            attempt: attempt.map(|a| a.attempt),
+            max_attempts: attempt.map(|a| a.max_attempts),
+            item_id: None,
+            playlist_id: None,
         };
         app.emit("download-progress", progress).ok();
         if let Some(ref filepath) = final_filepath {
@@ -1978,13 +3211,20 @@ async fn handle_tokio_download(
                 progress_history_id.clone(),
                 extract_time_range(&download_sections),
                 &download_kind,
+                &output_directory,
+                chapter_files,
             )
             .await;
         }
         Ok(())
     } else {
         let recent_lines = recent_output_snapshot(&recent_output);
-        let error = build_download_error_message(status.code(), &recent_lines);
+        let error = verify_error.unwrap_or_else(|| {
+            with_rate_limit_cooldown(
+                &url,
+                build_download_error_message(status.code(), &recent_lines),
+            )
+        });
         add_log_internal("error", error.message(), None, Some(&url)).ok();
 
         // Emit error progress so frontend can display error message
@@ -2007,10 +3247,20 @@ async fn handle_tokio_download(
             filepath: None,
             downloaded_size: None,
             elapsed_time: None,
+            phase: None,
+            downloaded_bytes: None,
+            total_bytes: None,
+            fragment_index: None,
+            fragment_count: None,
+            attempt: attempt.map(|a| a.attempt),
+            max_attempts: attempt.map(|a| a.max_attempts),
+            item_id: None,
+            playlist_id: None,
         };
         app.emit("download-progress", progress).ok();
 
-        if emit_failed_workflow && !failed_workflow_steps.is_empty() {
+        if emit_failed_workflow && !failed_workflow_steps.is_empty() && !will_retry(attempt, &error)
+        {
             let payload = build_trigger_payload(
                 &id,
                 source.clone().or_else(|| detect_source(&url)),
@@ -2033,16 +3283,83 @@ async fn handle_tokio_download(
     }
 }
 
+/// Stop a single download and its child processes (ffmpeg merges, aria2c, ...).
+/// Other downloads and processing jobs keep running.
 #[tauri::command]
-pub async fn stop_download() -> Result<(), String> {
-    CANCEL_FLAG.store(true, Ordering::SeqCst);
-    kill_all_download_processes();
-    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-    kill_all_download_processes();
+pub async fn stop_download(id: String) -> Result<(), String> {
+    // A stop wins over a pending bandwidth restart
+    if let Ok(mut ids) = RESTART_REQUESTED.lock() {
+        ids.remove(&id);
+    }
+    kill_download(&id).await.map(|_| ())
+}
+
+/// Stop a download so `download_video` runs it again with its new bandwidth share.
+async fn restart_download(id: &str) -> Result<(), String> {
+    if let Ok(mut ids) = RESTART_REQUESTED.lock() {
+        ids.insert(id.to_string());
+    }
+    let running = kill_download(id).await?;
+    if !running {
+        if let Ok(mut ids) = RESTART_REQUESTED.lock() {
+            ids.remove(id);
+        }
+    }
     Ok(())
 }
 
-fn detect_source(url: &str) -> Option<String> {
+/// Start the loop that restarts running downloads whose share of the bandwidth budget
+/// moved: when downloads start or finish, the settings change or a time-of-day limit begins.
+pub fn start_bandwidth_balancer() {
+    if BANDWIDTH_BALANCER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = bandwidth_balancer_woken() => {}
+                _ = tokio::time::sleep(tokio::time::Duration::from_secs(
+                    BANDWIDTH_BALANCE_INTERVAL_SECS,
+                )) => {}
+            }
+            // Let downloads starting or finishing together settle first
+            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+            for id in plan_bandwidth_rebalance() {
+                if let Err(e) = restart_download(&id).await {
+                    log::warn!("Failed to restart download {}: {}", id, e);
+                }
+            }
+        }
+    });
+}
+
+/// Stop a running download. Returns whether it was running.
+async fn kill_download(id: &str) -> Result<bool, String> {
+    let pid = {
+        let jobs = ACTIVE_DOWNLOADS
+            .lock()
+            .map_err(|e| format!("Failed to acquire download registry lock: {}", e))?;
+        match jobs.get(id) {
+            Some(job) => {
+                job.cancelled.store(true, Ordering::SeqCst);
+                job.pid
+            }
+            // Already finished or never started: nothing to stop.
+            None => return Ok(false),
+        }
+    };
+
+    if let Some(pid) = pid {
+        tokio::task::spawn_blocking(move || kill_process_tree(pid))
+            .await
+            .ok();
+    }
+    Ok(true)
+}
+
+/// History source of a URL: the site it is from, or `other`
+pub(crate) fn detect_source(url: &str) -> Option<String> {
     if url.contains("youtube.com") || url.contains("youtu.be") {
         Some("youtube".to_string())
     } else if url.contains("tiktok.com") {
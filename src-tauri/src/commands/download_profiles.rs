@@ -0,0 +1,31 @@
+use crate::database::{
+    delete_download_profile_db, get_download_profiles_db, save_download_profile_db,
+};
+use crate::services::validate_download_profile_name;
+use crate::types::{BackendError, DownloadProfile, DownloadProfileSettings};
+use crate::utils::validate_output_template;
+
+#[tauri::command]
+pub fn list_download_profiles() -> Result<Vec<DownloadProfile>, String> {
+    get_download_profiles_db()
+}
+
+/// Create or replace a named download profile.
+#[tauri::command]
+pub fn save_download_profile(
+    name: String,
+    mut settings: DownloadProfileSettings,
+) -> Result<DownloadProfile, String> {
+    let name = validate_download_profile_name(&name)?;
+    if let Some(template) = settings.output_template.as_deref() {
+        let template = validate_output_template(template)
+            .map_err(|e| BackendError::from_message(e).to_wire_string())?;
+        settings.output_template = Some(template);
+    }
+    save_download_profile_db(&name, &settings)
+}
+
+#[tauri::command]
+pub fn delete_download_profile(name: String) -> Result<(), String> {
+    delete_download_profile_db(&name)
+}
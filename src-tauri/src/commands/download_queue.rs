@@ -1,7 +1,43 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::{download_video, stop_download, wake_download_schedules, wake_live_recorder};
 use crate::database::{
-    clear_download_queue_from_db, load_download_queue_from_db, save_download_queue_to_db,
+    add_log_internal, clear_download_queue_from_db, clear_finished_download_queue_items_db,
+    delete_download_queue_item_db, finish_download_queue_item_db, get_download_queue_item_db,
+    get_download_queue_items_db, load_download_queue_from_db, load_download_scheduler_config_db,
+    mark_download_queue_item_started_db, pause_download_queue_item_db,
+    requeue_download_queue_item_db, reset_interrupted_download_queue_items_db,
+    resume_download_queue_item_db, save_download_queue_to_db, save_download_scheduler_config_db,
+    schedule_download_queue_retry_db, update_channel_video_status_db,
+};
+use crate::services::{
+    self, backend_error_wire, emit_download_queue_updated, enqueue_download_item,
+    get_scheduler_config, is_due_for_dispatch, is_folder_full, is_retryable_error,
+    is_scheduler_configured, quota_folder_usage, rate_limit_cooldown_remaining, retry_delay,
+    scheduler_woken, select_runnable, set_scheduler_config, wake_scheduler,
+};
+use crate::types::{
+    code, BackendErrorWire, DownloadAttempt, DownloadProgress, DownloadQueueItem,
+    DownloadQueueStatus, DownloadRequest, DownloadSchedulerConfig, NewDownloadQueueItem,
 };
 
+/// Queue items currently downloading, keyed by item id with their site key.
+static RUNNING_QUEUE_ITEMS: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Running items being stopped by `pause_download`, so their result is recorded as paused.
+static PAUSE_REQUESTED: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+static SCHEDULER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// How often the scheduler re-checks the queue even without a wake-up.
+const SCHEDULER_IDLE_MS: u64 = 30_000;
+
 #[tauri::command]
 pub fn load_download_queue(queue_kind: String) -> Result<Option<String>, String> {
     load_download_queue_from_db(queue_kind)
@@ -16,3 +52,471 @@ pub fn save_download_queue(queue_kind: String, items_json: String) -> Result<(),
 pub fn clear_download_queue(queue_kind: String) -> Result<(), String> {
     clear_download_queue_from_db(queue_kind)
 }
+
+/// Start the backend download scheduler.
+/// Items left downloading by a previous run go back to pending first, and the settings
+/// saved by the last run apply until the frontend syncs its own.
+pub fn start_download_scheduler(app: AppHandle) {
+    if SCHEDULER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    match load_download_scheduler_config_db() {
+        Ok(Some(config)) if !is_scheduler_configured() => set_scheduler_config(config),
+        Ok(_) => {}
+        Err(e) => log::warn!("Failed to load download scheduler settings: {}", e),
+    }
+
+    match reset_interrupted_download_queue_items_db() {
+        Ok(0) => {}
+        Ok(count) => log::info!("Resuming {} interrupted download(s)", count),
+        Err(e) => log::error!("Failed to requeue interrupted downloads: {}", e),
+    }
+
+    tauri::async_runtime::spawn(async move {
+        log::info!("Download scheduler started");
+        loop {
+            // Wake up early when a retry or a rate-limit cooldown is due.
+            let idle_ms = dispatch_pending_downloads(&app)
+                .await
+                .map_or(SCHEDULER_IDLE_MS, |due_in| due_in.min(SCHEDULER_IDLE_MS));
+            tokio::select! {
+                _ = scheduler_woken() => {}
+                _ = tokio::time::sleep(tokio::time::Duration::from_millis(idle_ms)) => {}
+            }
+        }
+    });
+}
+
+fn running_by_site() -> HashMap<String, u32> {
+    let mut counts = HashMap::new();
+    if let Ok(running) = RUNNING_QUEUE_ITEMS.lock() {
+        for site in running.values() {
+            *counts.entry(site.clone()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Start the pending items that may run now.
+/// Returns the milliseconds until the next waiting item becomes due, if any.
+async fn dispatch_pending_downloads(app: &AppHandle) -> Option<u64> {
+    let pending = match get_download_queue_items_db(None, Some(DownloadQueueStatus::Pending)) {
+        Ok(items) => items,
+        Err(e) => {
+            log::error!("Failed to read download queue: {}", e);
+            return None;
+        }
+    };
+    if pending.is_empty() || !is_scheduler_configured() {
+        return None;
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let (due, waiting): (Vec<_>, Vec<_>) = pending
+        .into_iter()
+        .partition(|item| is_due_for_dispatch(item, now));
+    let next_due_in = waiting
+        .iter()
+        .map(|item| {
+            let retry_in = item
+                .next_attempt_at
+                .map_or(0, |at| (at - now).max(0) as u64);
+            let cooldown_in =
+                rate_limit_cooldown_remaining(&item.site).map_or(0, |left| left.as_millis() as u64);
+            retry_in.max(cooldown_in)
+        })
+        .min();
+
+    let config = get_scheduler_config();
+    let folder_usage = quota_folder_usage(&config).await;
+    let due: Vec<DownloadQueueItem> = due
+        .into_iter()
+        .filter(|item| !is_folder_full(item, &config, &folder_usage))
+        .collect();
+    for item in select_runnable(&due, &running_by_site(), &config) {
+        match mark_download_queue_item_started_db(&item.id) {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => {
+                log::error!("Failed to start queued download {}: {}", item.id, e);
+                continue;
+            }
+        }
+
+        if let Ok(mut running) = RUNNING_QUEUE_ITEMS.lock() {
+            running.insert(item.id.clone(), item.site.clone());
+        }
+        emit_download_queue_updated(app, &item.id);
+
+        let app = app.clone();
+        let item = item.clone();
+        tauri::async_runtime::spawn(async move {
+            run_queue_item(app, item).await;
+        });
+    }
+    next_due_in
+}
+
+async fn run_queue_item(app: AppHandle, item: DownloadQueueItem) {
+    let result = run_download(&app, &item).await;
+
+    let pause_requested = PAUSE_REQUESTED
+        .lock()
+        .map(|mut paused| paused.remove(&item.id))
+        .unwrap_or(false);
+    if pause_requested && result.is_err() {
+        // Keep the .part files and the row as-is so `resume_download` continues from here.
+        pause_download_queue_item_db(&item.id).ok();
+        finish_running_item(&app, &item.id);
+        return;
+    }
+
+    let (status, error_code, error_message) = match &result {
+        Ok(()) => (DownloadQueueStatus::Completed, None, None),
+        Err(raw) => {
+            let wire = backend_error_wire(raw);
+            if schedule_retry(&app, &item, &wire) {
+                finish_running_item(&app, &item.id);
+                return;
+            }
+            // An archived video counts as done; the code records why nothing was downloaded.
+            let status = match wire.code.as_str() {
+                code::DOWNLOAD_CANCELLED => DownloadQueueStatus::Cancelled,
+                code::DOWNLOAD_ALREADY_ARCHIVED => DownloadQueueStatus::Completed,
+                _ => DownloadQueueStatus::Failed,
+            };
+            (status, Some(wire.code), Some(wire.message))
+        }
+    };
+
+    // A removed item has no row left to update.
+    if matches!(get_download_queue_item_db(&item.id), Ok(Some(_))) {
+        if let Err(e) = finish_download_queue_item_db(
+            &item.id,
+            status,
+            error_code.as_deref(),
+            error_message.as_deref(),
+        ) {
+            log::error!("Failed to record queued download result: {}", e);
+        }
+    }
+
+    notify_origin(&app, &item, status, error_message.as_deref());
+    finish_running_item(&app, &item.id);
+}
+
+/// Queue a failed item again after a backoff delay if its error is retryable
+/// and it has attempts left. Returns whether a retry was scheduled.
+fn schedule_retry(app: &AppHandle, item: &DownloadQueueItem, error: &BackendErrorWire) -> bool {
+    let policy = get_scheduler_config().retry;
+    // `attempts` was counted when this run started
+    let failed_attempts = item.attempts.max(1) as u32;
+    if failed_attempts >= policy.max_attempts || !is_retryable_error(error) {
+        return false;
+    }
+
+    let delay = retry_delay(&policy, failed_attempts)
+        .max(rate_limit_cooldown_remaining(&item.site).unwrap_or_default());
+    let next_attempt_at = chrono::Utc::now().timestamp_millis() + delay.as_millis() as i64;
+    match schedule_download_queue_retry_db(&item.id, &error.code, &error.message, next_attempt_at) {
+        Ok(true) => {}
+        Ok(false) => return false,
+        Err(e) => {
+            log::error!("Failed to schedule download retry: {}", e);
+            return false;
+        }
+    }
+
+    add_log_internal(
+        "info",
+        &format!(
+            "Queued download failed (attempt {}/{}), retrying in {}s: {}",
+            failed_attempts,
+            policy.max_attempts,
+            delay.as_secs(),
+            error.message
+        ),
+        None,
+        Some(&item.url),
+    )
+    .ok();
+
+    let _ = app.emit(
+        "download-progress",
+        DownloadProgress {
+            id: item.id.clone(),
+            percent: 0.0,
+            speed: String::new(),
+            eta: String::new(),
+            status: "retrying".to_string(),
+            title: item.title.clone(),
+            playlist_index: None,
+            playlist_count: None,
+            filesize: None,
+            resolution: None,
+            format_ext: None,
+            error_message: Some(error.message.clone()),
+            error_code: Some(error.code.clone()),
+            error_params: error.params.clone(),
+            history_id: None,
+            filepath: None,
+            downloaded_size: None,
+            elapsed_time: None,
+            phase: None,
+            downloaded_bytes: None,
+            total_bytes: None,
+            fragment_index: None,
+            fragment_count: None,
+            attempt: Some(failed_attempts),
+            max_attempts: Some(policy.max_attempts),
+            item_id: None,
+            playlist_id: None,
+        },
+    );
+    true
+}
+
+fn finish_running_item(app: &AppHandle, id: &str) {
+    if let Ok(mut running) = RUNNING_QUEUE_ITEMS.lock() {
+        running.remove(id);
+    }
+    emit_download_queue_updated(app, id);
+    wake_scheduler();
+}
+
+/// Delete the `.part`, `.ytdl` and fragment files yt-dlp left for an unfinished job.
+fn remove_partial_files(partial_paths: &[String]) {
+    for partial in partial_paths {
+        std::fs::remove_file(format!("{}.part", partial)).ok();
+        std::fs::remove_file(format!("{}.ytdl", partial)).ok();
+
+        let path = Path::new(partial);
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            continue;
+        };
+        let fragment_prefix = format!("{}.part-Frag", name.to_string_lossy());
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                if entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(&fragment_prefix)
+                {
+                    std::fs::remove_file(entry.path()).ok();
+                }
+            }
+        }
+    }
+}
+
+/// Report a finished item back to where it came from.
+fn notify_origin(
+    app: &AppHandle,
+    item: &DownloadQueueItem,
+    status: DownloadQueueStatus,
+    error_message: Option<&str>,
+) {
+    match item.origin.as_str() {
+        "channel" => {
+            // Failed or cancelled videos go back to "new" so they can be picked up again.
+            if let Some(video_row_id) = item.origin_ref.clone() {
+                let video_status = if status == DownloadQueueStatus::Completed {
+                    "downloaded"
+                } else {
+                    "new"
+                };
+                update_channel_video_status_db(video_row_id, video_status.to_string()).ok();
+                crate::rebuild_tray_menu(app);
+            }
+        }
+        "telegram" => {
+            let Some(chat_id) = item.origin_ref.clone() else {
+                return;
+            };
+            let name = item.title.clone().unwrap_or_else(|| item.url.clone());
+            let text = match status {
+                // Completed with a message means the archive skipped it
+                DownloadQueueStatus::Completed if error_message.is_some() => {
+                    format!("Already downloaded: {}", name)
+                }
+                DownloadQueueStatus::Completed => format!("Downloaded: {}", name),
+                DownloadQueueStatus::Cancelled => format!("Cancelled: {}", name),
+                _ => format!(
+                    "Download failed: {}\n{}",
+                    name,
+                    error_message.unwrap_or("Unknown error")
+                ),
+            };
+            tauri::async_runtime::spawn(async move {
+                services::telegram::send_reply(chat_id, text).await.ok();
+            });
+        }
+        // Lets a window schedule see its download is done without waiting for the window to close
+        "schedule" => wake_download_schedules(),
+        // The recorder checks whether the stream is still live and records it again
+        "live" => wake_live_recorder(),
+        _ => {}
+    }
+}
+
+async fn run_download(app: &AppHandle, item: &DownloadQueueItem) -> Result<(), String> {
+    let mut options = item.options.clone();
+    // CLI and Telegram items carry no network settings; use the ones synced for polling.
+    if item.origin != "app" && options.cookie_mode.is_none() && options.proxy_url.is_none() {
+        let network = services::polling::get_network_config();
+        options.cookie_mode = network.cookie_mode;
+        options.cookie_browser = network.cookie_browser;
+        options.cookie_browser_profile = network.cookie_browser_profile;
+        options.cookie_file_path = network.cookie_file_path;
+        options.proxy_url = network.proxy_url;
+    }
+
+    add_log_internal(
+        "info",
+        &format!("Starting queued download ({})", item.origin),
+        None,
+        Some(&item.url),
+    )
+    .ok();
+
+    download_video(
+        app.clone(),
+        DownloadRequest {
+            id: item.id.clone(),
+            url: item.url.clone(),
+            title: item.title.clone(),
+            post_download_plugins: None,
+            emit_failed_workflow: None,
+            attempt: Some(DownloadAttempt {
+                attempt: item.attempts.max(1) as u32,
+                max_attempts: get_scheduler_config().retry.max_attempts,
+            }),
+            resumable: Some(true),
+            options,
+        },
+    )
+    .await
+}
+
+#[tauri::command]
+pub fn enqueue_download(
+    app: AppHandle,
+    item: NewDownloadQueueItem,
+) -> Result<DownloadQueueItem, String> {
+    enqueue_download_item(&app, item)
+}
+
+#[tauri::command]
+pub fn get_download_queue_items(
+    queue_kind: Option<String>,
+    status: Option<DownloadQueueStatus>,
+) -> Result<Vec<DownloadQueueItem>, String> {
+    get_download_queue_items_db(queue_kind, status)
+}
+
+/// Remove an item from the queue, stopping it first if it is downloading.
+#[tauri::command]
+pub async fn remove_download_queue_item(app: AppHandle, id: String) -> Result<(), String> {
+    let Some(item) = get_download_queue_item_db(&id)? else {
+        return Ok(());
+    };
+    delete_download_queue_item_db(&id)?;
+    match item.status {
+        DownloadQueueStatus::Downloading => stop_download(id.clone()).await?,
+        DownloadQueueStatus::Pending | DownloadQueueStatus::Paused => {
+            notify_origin(&app, &item, DownloadQueueStatus::Cancelled, None)
+        }
+        _ => {}
+    }
+    remove_partial_files(&item.partial_paths);
+    emit_download_queue_updated(&app, &id);
+    wake_scheduler();
+    Ok(())
+}
+
+/// Stop a downloading item and mark it cancelled. Pending and paused items are cancelled in place.
+#[tauri::command]
+pub async fn cancel_download_queue_item(app: AppHandle, id: String) -> Result<(), String> {
+    let Some(item) = get_download_queue_item_db(&id)? else {
+        return Ok(());
+    };
+    match item.status {
+        DownloadQueueStatus::Downloading => stop_download(id).await,
+        DownloadQueueStatus::Pending | DownloadQueueStatus::Paused => {
+            finish_download_queue_item_db(&id, DownloadQueueStatus::Cancelled, None, None)?;
+            notify_origin(&app, &item, DownloadQueueStatus::Cancelled, None);
+            emit_download_queue_updated(&app, &id);
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Put a failed or cancelled item back into the queue.
+#[tauri::command]
+pub fn retry_download_queue_item(app: AppHandle, id: String) -> Result<bool, String> {
+    let requeued = requeue_download_queue_item_db(&id)?;
+    if requeued {
+        emit_download_queue_updated(&app, &id);
+        wake_scheduler();
+    }
+    Ok(requeued)
+}
+
+#[tauri::command]
+pub fn clear_finished_download_queue_items(queue_kind: Option<String>) -> Result<usize, String> {
+    for item in get_download_queue_items_db(queue_kind.clone(), None)? {
+        if item.status.is_finished() {
+            remove_partial_files(&item.partial_paths);
+        }
+    }
+    clear_finished_download_queue_items_db(queue_kind)
+}
+
+/// Pause a queued download. A running job is stopped but keeps its `.part` files;
+/// downloads started outside the queue are simply stopped.
+#[tauri::command]
+pub async fn pause_download(app: AppHandle, id: String) -> Result<(), String> {
+    let Some(item) = get_download_queue_item_db(&id)? else {
+        return stop_download(id).await;
+    };
+    match item.status {
+        DownloadQueueStatus::Pending => {
+            pause_download_queue_item_db(&id)?;
+            emit_download_queue_updated(&app, &id);
+            Ok(())
+        }
+        DownloadQueueStatus::Downloading => {
+            if let Ok(mut paused) = PAUSE_REQUESTED.lock() {
+                paused.insert(id.clone());
+            }
+            stop_download(id).await
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Put a paused download back into the queue; it continues from its `.part` files.
+#[tauri::command]
+pub fn resume_download(app: AppHandle, id: String) -> Result<(), String> {
+    if !resume_download_queue_item_db(&id)? {
+        return Err("Download is not paused".to_string());
+    }
+    emit_download_queue_updated(&app, &id);
+    wake_scheduler();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_download_scheduler_config(config: DownloadSchedulerConfig) {
+    set_scheduler_config(config);
+    if let Err(e) = save_download_scheduler_config_db(&get_scheduler_config()) {
+        log::warn!("Failed to save download scheduler settings: {}", e);
+    }
+}
+
+#[tauri::command]
+pub fn get_download_scheduler_config() -> DownloadSchedulerConfig {
+    get_scheduler_config()
+}
@@ -0,0 +1,212 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Notify;
+
+use crate::commands::{pause_download, resume_download};
+use crate::database::{
+    add_log_internal, delete_download_schedule_db, get_download_queue_item_db,
+    get_download_schedule_db, get_download_schedules_db, insert_download_schedule_db,
+    record_download_schedule_run_db, set_download_schedule_enabled_db,
+};
+use crate::services::{
+    enqueue_download_item, first_run_at, plan_schedule_run, validate_schedule_rule, ScheduleAction,
+};
+use crate::types::{
+    BackendError, DownloadQueueStatus, DownloadSchedule, NewDownloadQueueItem, NewDownloadSchedule,
+};
+use crate::utils::{normalize_url, validate_url};
+
+/// Event emitted whenever a schedule is added, changed, removed or runs
+pub const DOWNLOAD_SCHEDULES_UPDATED_EVENT: &str = "download-schedules-updated";
+
+static SCHEDULE_TIMER_STARTED: AtomicBool = AtomicBool::new(false);
+
+static SCHEDULE_TIMER_WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// How often the timer re-checks schedules even when none is due,
+/// so a changed clock or a machine waking from sleep is noticed.
+const SCHEDULE_TIMER_IDLE_MS: u64 = 60_000;
+
+/// Ask the schedule timer to re-evaluate schedules (new schedule, finished download).
+pub fn wake_download_schedules() {
+    SCHEDULE_TIMER_WAKE.notify_one();
+}
+
+/// Start the timer that queues scheduled downloads. Schedules missed while
+/// the app was closed run on the first pass.
+pub fn start_download_schedules(app: AppHandle) {
+    if SCHEDULE_TIMER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        log::info!("Download schedule timer started");
+        loop {
+            let idle_ms = run_due_schedules(&app)
+                .await
+                .map_or(SCHEDULE_TIMER_IDLE_MS, |due_in| {
+                    due_in.min(SCHEDULE_TIMER_IDLE_MS)
+                });
+            tokio::select! {
+                _ = SCHEDULE_TIMER_WAKE.notified() => {}
+                _ = tokio::time::sleep(tokio::time::Duration::from_millis(idle_ms)) => {}
+            }
+        }
+    });
+}
+
+/// Run every enabled schedule that is due.
+/// Returns the milliseconds until the next one is due, if any.
+async fn run_due_schedules(app: &AppHandle) -> Option<u64> {
+    let schedules = match get_download_schedules_db() {
+        Ok(schedules) => schedules,
+        Err(e) => {
+            log::error!("Failed to read download schedules: {}", e);
+            return None;
+        }
+    };
+
+    let now = chrono::Local::now();
+    let mut ran_any = false;
+    for schedule in &schedules {
+        let due = schedule.enabled
+            && schedule
+                .next_run_at
+                .is_some_and(|at| at <= now.timestamp_millis());
+        if due {
+            run_schedule(app, schedule, &now).await;
+            ran_any = true;
+        }
+    }
+
+    let schedules = if ran_any {
+        let _ = app.emit(DOWNLOAD_SCHEDULES_UPDATED_EVENT, ());
+        get_download_schedules_db().unwrap_or_default()
+    } else {
+        schedules
+    };
+    let waiting: Vec<i64> = schedules
+        .iter()
+        .filter(|schedule| schedule.enabled)
+        .filter_map(|schedule| schedule.next_run_at)
+        .collect();
+    let next_run_at = waiting.iter().min().copied();
+    crate::update_tray_download_schedules(app, waiting.len(), next_run_at);
+
+    let now = chrono::Utc::now().timestamp_millis();
+    next_run_at.map(|at| (at - now).max(0) as u64)
+}
+
+async fn run_schedule(
+    app: &AppHandle,
+    schedule: &DownloadSchedule,
+    now: &chrono::DateTime<chrono::Local>,
+) {
+    // A queued item that was removed since counts as cancelled.
+    let last_item = schedule.last_item_id.as_deref().map(|id| {
+        get_download_queue_item_db(id)
+            .ok()
+            .flatten()
+            .map_or(DownloadQueueStatus::Cancelled, |item| item.status)
+    });
+    let (action, next_run_at) = plan_schedule_run(&schedule.rule, last_item, now);
+
+    let mut queued_item_id = None;
+    let result = match (action, schedule.last_item_id.clone()) {
+        (ScheduleAction::Enqueue, _) => enqueue_download_item(
+            app,
+            NewDownloadQueueItem {
+                id: None,
+                url: schedule.url.clone(),
+                queue_kind: schedule.queue_kind.clone(),
+                title: schedule.title.clone(),
+                origin: Some("schedule".to_string()),
+                origin_ref: Some(schedule.id.clone()),
+                priority: None,
+                options: schedule.options.clone(),
+            },
+        )
+        .map(|item| queued_item_id = Some(item.id)),
+        (ScheduleAction::Pause, Some(item_id)) => pause_download(app.clone(), item_id).await,
+        (ScheduleAction::Resume, Some(item_id)) => resume_download(app.clone(), item_id),
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        log::warn!("Scheduled download {} failed to run: {}", schedule.id, e);
+        add_log_internal(
+            "error",
+            &format!("Scheduled download failed to start: {}", e),
+            None,
+            Some(&schedule.url),
+        )
+        .ok();
+    }
+
+    if let Err(e) =
+        record_download_schedule_run_db(&schedule.id, next_run_at, queued_item_id.as_deref())
+    {
+        log::error!("Failed to record scheduled download run: {}", e);
+    }
+}
+
+fn schedules_changed(app: &AppHandle) {
+    let _ = app.emit(DOWNLOAD_SCHEDULES_UPDATED_EVENT, ());
+    wake_download_schedules();
+}
+
+#[tauri::command]
+pub fn list_download_schedules() -> Result<Vec<DownloadSchedule>, String> {
+    get_download_schedules_db()
+}
+
+/// Save a download to be queued by the backend at the times its rule gives.
+#[tauri::command]
+pub fn add_download_schedule(
+    app: AppHandle,
+    schedule: NewDownloadSchedule,
+) -> Result<DownloadSchedule, String> {
+    validate_url(&schedule.url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    validate_schedule_rule(&schedule.rule)?;
+
+    let title = schedule
+        .title
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty());
+    let saved = insert_download_schedule_db(
+        &normalize_url(&schedule.url),
+        title.as_deref(),
+        schedule.queue_kind.as_deref(),
+        &schedule.options,
+        &schedule.rule,
+        first_run_at(&schedule.rule, &chrono::Local::now()),
+    )?;
+
+    schedules_changed(&app);
+    Ok(saved)
+}
+
+/// Turn a schedule off, or back on. A schedule turned back on starts over,
+/// so a one-off download runs again.
+#[tauri::command]
+pub fn set_download_schedule_enabled(
+    app: AppHandle,
+    id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let Some(schedule) = get_download_schedule_db(&id)? else {
+        return Err(format!("Download schedule not found: {}", id));
+    };
+    let next_run_at = first_run_at(&schedule.rule, &chrono::Local::now());
+    set_download_schedule_enabled_db(&id, enabled, next_run_at)?;
+    schedules_changed(&app);
+    Ok(())
+}
+
+/// Delete a schedule. Downloads it already queued keep running.
+#[tauri::command]
+pub fn remove_download_schedule(app: AppHandle, id: String) -> Result<(), String> {
+    delete_download_schedule_db(&id)?;
+    schedules_changed(&app);
+    Ok(())
+}
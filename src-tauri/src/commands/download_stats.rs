@@ -0,0 +1,24 @@
+use crate::database::{clear_download_events_db, get_download_events_db, get_download_stats_db};
+use crate::types::{DownloadEvent, DownloadStats};
+
+/// Statistics of the downloads journaled between `since` and `until` (ms, either open),
+/// listing at most `top_failures` error codes.
+#[tauri::command]
+pub fn get_download_stats(
+    since: Option<i64>,
+    until: Option<i64>,
+    top_failures: Option<u32>,
+) -> Result<DownloadStats, String> {
+    get_download_stats_db(since, until, top_failures)
+}
+
+/// Everything journaled for one download, oldest first
+#[tauri::command]
+pub fn get_download_events(download_id: String) -> Result<Vec<DownloadEvent>, String> {
+    get_download_events_db(&download_id)
+}
+
+#[tauri::command]
+pub fn clear_download_events() -> Result<(), String> {
+    clear_download_events_db()
+}
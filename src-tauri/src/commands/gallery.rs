@@ -2,22 +2,53 @@ use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::process::Stdio;
 
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 
 use crate::database::add_history_internal;
-use crate::database::add_log_internal;
-use crate::services::{get_gallerydl_path, system_gallerydl_not_found_message};
-use crate::types::BackendError;
-use crate::utils::{normalize_url, sanitize_output_path, validate_url, CommandExt};
+use crate::database::{add_log_internal, link_history_part};
+use crate::services::{
+    enqueue_post_download_workflow, gallery_album_dir, gallerydl_not_found_message,
+    gallerydl_option_args, get_gallerydl_path, get_gallerydl_source, parse_gallery_output_line,
+    resolve_download_workflow_snapshot, GalleryOutputLine,
+};
+use crate::types::{BackendError, GalleryDlOptions, PostDownloadPluginPayload};
+use crate::utils::{
+    normalize_url, sanitize_output_path, validate_gallerydl_options, validate_url, CommandExt,
+};
 
 const RECENT_OUTPUT_LIMIT: usize = 30;
 
+/// Files of one gallery that get their own history entry; larger galleries only keep
+/// the album entry, so they do not push everything else out of history.
+const GALLERY_HISTORY_FILE_LIMIT: usize = 100;
+
 #[derive(serde::Serialize)]
 pub struct GalleryDownloadResult {
+    /// Folder holding the saved files
     pub filepath: String,
+    /// History entry of the whole album
     pub history_id: Option<String>,
+    pub files: Vec<String>,
+    pub skipped: u32,
+}
+
+/// Emitted as `gallery-progress` for every file gallery-dl saves or skips
+#[derive(Clone, serde::Serialize)]
+pub struct GalleryProgress {
+    pub id: String,
+    pub downloaded: u32,
+    pub skipped: u32,
+    /// File just saved or skipped
+    pub filepath: String,
+}
+
+/// Files gallery-dl reported on stdout
+#[derive(Default)]
+struct GalleryFiles {
+    saved: Vec<String>,
+    skipped: u32,
 }
 
 fn push_recent_output(buffer: &mut VecDeque<String>, line: &str) {
@@ -108,14 +139,22 @@ pub async fn download_gallery(
     cookie_file_path: Option<String>,
     proxy_url: Option<String>,
     source: Option<String>,
+    id: Option<String>,
+    gallery_options: Option<GalleryDlOptions>,
 ) -> Result<GalleryDownloadResult, String> {
     validate_url(&url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
     let url = normalize_url(&url);
+    let gallery_options = gallery_options.unwrap_or_default();
+    validate_gallerydl_options(&gallery_options).map_err(|e| {
+        BackendError::new(crate::types::code::VALIDATION_INVALID_INPUT, e)
+            .with_retryable(false)
+            .to_wire_string()
+    })?;
 
-    let Some(binary_path) = get_gallerydl_path(&app) else {
+    let Some(binary_path) = get_gallerydl_path(&app).await else {
         return Err(BackendError::new(
             crate::types::code::GALLERYDL_NOT_FOUND,
-            system_gallerydl_not_found_message(),
+            gallerydl_not_found_message(&get_gallerydl_source(&app).await),
         )
         .with_retryable(false)
         .to_wire_string());
@@ -151,6 +190,7 @@ pub async fn download_gallery(
         }
     }
 
+    args.extend(gallerydl_option_args(&gallery_options));
     args.push(url.clone());
 
     let command_str = format!("[{}] gallery-dl {}", binary_path.display(), args.join(" "));
@@ -169,15 +209,36 @@ pub async fn download_gallery(
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
 
+    let progress_app = app.clone();
+    let progress_id = id.clone().unwrap_or_else(|| url.clone());
     let stdout_task = tokio::spawn(async move {
         let mut recent = VecDeque::with_capacity(RECENT_OUTPUT_LIMIT);
+        let mut files = GalleryFiles::default();
         if let Some(stdout) = stdout {
             let mut reader = BufReader::new(stdout).lines();
             while let Ok(Some(line)) = reader.next_line().await {
                 push_recent_output(&mut recent, &line);
+                let filepath = match parse_gallery_output_line(&line) {
+                    Some(GalleryOutputLine::Saved(path)) => {
+                        files.saved.push(path.clone());
+                        path
+                    }
+                    Some(GalleryOutputLine::Skipped(path)) => {
+                        files.skipped += 1;
+                        path
+                    }
+                    None => continue,
+                };
+                let progress = GalleryProgress {
+                    id: progress_id.clone(),
+                    downloaded: files.saved.len() as u32,
+                    skipped: files.skipped,
+                    filepath,
+                };
+                progress_app.emit("gallery-progress", progress).ok();
             }
         }
-        recent
+        (recent, files)
     });
 
     let url_for_stderr = url.clone();
@@ -200,7 +261,8 @@ pub async fn download_gallery(
         BackendError::from_message(format!("gallery-dl process error: {}", e)).to_wire_string()
     })?;
 
-    let mut recent_lines: Vec<String> = stdout_task.await.unwrap_or_default().into_iter().collect();
+    let (stdout_lines, files) = stdout_task.await.unwrap_or_default();
+    let mut recent_lines: Vec<String> = stdout_lines.into_iter().collect();
     recent_lines.extend(stderr_task.await.unwrap_or_default().into_iter());
 
     if !status.success() {
@@ -228,25 +290,159 @@ pub async fn download_gallery(
         .to_wire_string());
     }
 
+    let album_dir = gallery_album_dir(&files.saved, &sanitized_path);
     let title = source.clone().unwrap_or_else(|| url.clone());
+    let source = source.or(Some("gallery-dl".to_string()));
     let history_id = add_history_internal(
         url.clone(),
-        title,
+        title.clone(),
         None,
-        sanitized_path.clone(),
+        album_dir.clone(),
         None,
         None,
         None,
         Some("gallery".to_string()),
-        source.or(Some("gallery-dl".to_string())),
+        source.clone(),
+        None,
+    )
+    .ok();
+    if files.saved.len() <= GALLERY_HISTORY_FILE_LIMIT {
+        record_gallery_files(&files.saved, history_id.as_deref(), &url, source.clone());
+    }
+
+    add_log_internal(
+        "success",
+        &format!(
+            "Gallery download completed: {} files saved, {} skipped",
+            files.saved.len(),
+            files.skipped
+        ),
         None,
+        Some(&url),
     )
     .ok();
 
-    add_log_internal("success", "Gallery download completed", None, Some(&url)).ok();
+    if let Some((first, rest)) = files.saved.split_first() {
+        run_gallery_completed_plugins(
+            &app,
+            id.as_deref().unwrap_or(&url),
+            source,
+            first,
+            rest.to_vec(),
+            &url,
+            title,
+            history_id.clone(),
+            &sanitized_path,
+        );
+    }
 
     Ok(GalleryDownloadResult {
-        filepath: sanitized_path,
+        filepath: album_dir,
         history_id,
+        files: files.saved,
+        skipped: files.skipped,
     })
 }
+
+/// Add a history entry for each saved file, linked to the album entry like chapter parts
+fn record_gallery_files(
+    files: &[String],
+    album_history_id: Option<&str>,
+    url: &str,
+    source: Option<String>,
+) {
+    for (index, filepath) in files.iter().enumerate() {
+        let path = std::path::Path::new(filepath);
+        let Ok(metadata) = std::fs::metadata(path) else {
+            continue;
+        };
+        let title = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(filepath)
+            .to_string();
+        let format = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+        let history_id = add_history_internal(
+            url.to_string(),
+            title,
+            None,
+            filepath.clone(),
+            Some(metadata.len()),
+            None,
+            None,
+            format,
+            source.clone(),
+            None,
+        );
+        if let (Ok(history_id), Some(album_id)) = (history_id, album_history_id) {
+            link_history_part(&history_id, album_id, index as u32 + 1).ok();
+        }
+    }
+}
+
+/// Fire the `download.completed` workflow once for the gallery: the first file is the
+/// payload's file, the others are in `extraFiles`.
+#[allow(clippy::too_many_arguments)]
+fn run_gallery_completed_plugins(
+    app: &AppHandle,
+    job_id: &str,
+    source: Option<String>,
+    filepath: &str,
+    extra_files: Vec<String>,
+    url: &str,
+    title: String,
+    history_id: Option<String>,
+    output_root: &str,
+) {
+    let workflow_steps = resolve_download_workflow_snapshot(app, "download.completed", None, &[]);
+    if workflow_steps.is_empty() {
+        return;
+    }
+
+    let path = std::path::Path::new(filepath);
+    let filename = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(filepath)
+        .to_string();
+    let directory = path
+        .parent()
+        .map(|parent| parent.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let relative_path = path
+        .strip_prefix(output_root)
+        .ok()
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"));
+
+    let payload = PostDownloadPluginPayload {
+        job_id: job_id.to_string(),
+        source,
+        trigger: "download.completed".to_string(),
+        filepath: filepath.to_string(),
+        filename,
+        directory,
+        filesize: std::fs::metadata(path).ok().map(|metadata| metadata.len()),
+        format: path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase()),
+        quality: None,
+        url: url.to_string(),
+        title: Some(title),
+        thumbnail: None,
+        history_id,
+        time_range: None,
+        download_kind: "gallery".to_string(),
+        relative_path,
+        extra_files,
+        workflow_run_id: None,
+        workflow_step_index: None,
+        workflow_step_plugin_id: None,
+        chain_state: None,
+    };
+
+    let _ = enqueue_post_download_workflow(app, workflow_steps, payload);
+}
@@ -9,6 +9,7 @@ use crate::database::{
     rename_collection_in_db, update_history_filepath_and_title,
     update_history_filepath_and_title_by_id, update_history_summary,
 };
+use crate::services::library_content_hash;
 use crate::types::{
     HistoryAdvancedFilters, HistoryCollection, HistoryEntry, HistorySort, HistoryTag,
 };
@@ -216,10 +217,22 @@ pub fn rename_downloaded_file(
         .ok_or_else(|| "New file path contains invalid UTF-8".to_string())?
         .to_string();
 
+    // Entries recorded before content hashes existed get one, so a later move is found too
+    let content_hash = library_content_hash(&new_path).ok();
     let update_result = if let Some(id) = history_id {
-        update_history_filepath_and_title_by_id(id, new_filepath.clone(), new_title.clone())
+        update_history_filepath_and_title_by_id(
+            id,
+            new_filepath.clone(),
+            new_title.clone(),
+            content_hash,
+        )
     } else {
-        update_history_filepath_and_title(filepath.clone(), new_filepath.clone(), new_title.clone())
+        update_history_filepath_and_title(
+            filepath.clone(),
+            new_filepath.clone(),
+            new_title.clone(),
+            content_hash,
+        )
     };
 
     if let Err(e) = update_result {
@@ -245,7 +258,8 @@ pub fn sync_history_renamed_entry(
         return Err("File name cannot be empty".to_string());
     }
 
-    update_history_filepath_and_title_by_id(id, filepath, trimmed_title.to_string())
+    let content_hash = library_content_hash(Path::new(&filepath)).ok();
+    update_history_filepath_and_title_by_id(id, filepath, trimmed_title.to_string(), content_hash)
 }
 
 #[tauri::command]
@@ -415,6 +429,9 @@ mod tests {
             [],
         )
         .expect("create history table");
+        // Other test modules may have created the table without the newer columns
+        conn.execute("ALTER TABLE history ADD COLUMN content_hash TEXT", [])
+            .ok();
         conn.execute("DELETE FROM history", [])
             .expect("clear history table");
     }
@@ -443,12 +460,12 @@ mod tests {
         )
         .expect("rename should succeed");
 
-        let (db_title, db_filepath): (String, String) = {
+        let (db_title, db_filepath, db_hash): (String, String, Option<String>) = {
             let conn = get_db().expect("get db");
             conn.query_row(
-                "SELECT title, filepath FROM history WHERE id = ?1",
+                "SELECT title, filepath, content_hash FROM history WHERE id = ?1",
                 params![history_id],
-                |row| Ok((row.get(0)?, row.get(1)?)),
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
             )
             .expect("query updated history row")
         };
@@ -456,6 +473,11 @@ mod tests {
         assert_eq!(db_title, "renamed");
         assert_eq!(db_filepath, result.new_filepath);
         assert!(Path::new(&result.new_filepath).exists());
+        assert_eq!(
+            db_hash,
+            library_content_hash(Path::new(&result.new_filepath)).ok()
+        );
+        assert!(db_hash.is_some());
 
         let _ = fs::remove_file(&result.new_filepath);
         let _ = fs::remove_dir_all(
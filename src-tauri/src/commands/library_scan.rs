@@ -0,0 +1,391 @@
+//! Library scan commands - import media files that are already on disk into history, and
+//! find the files of history entries that were moved or renamed
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use tauri::{AppHandle, Emitter};
+
+use super::download::detect_source;
+use crate::database::{
+    add_log_internal, get_history_file_records, history_has_filepath, import_history_entry,
+    relink_history_file, set_history_content_hash,
+};
+use crate::services::{
+    build_imported_entry, collect_library_files, collect_library_media_files, find_moved_file,
+    info_json_sidecar, library_content_hash, parse_info_json, probe_library_file, LibraryFile,
+    LibraryFileIndex, RelinkMatch,
+};
+use crate::types::{
+    code, BackendError, LibraryRelinkProgress, LibraryScanProgress, MissingHistoryFile,
+};
+
+/// Stops the running library scan or relink; only one of them runs at a time
+pub static LIBRARY_SCAN_CANCEL_FLAG: AtomicBool = AtomicBool::new(false);
+static LIBRARY_SCAN_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Shortest time between two progress events; skipped files go by quickly
+const LIBRARY_SCAN_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+fn is_library_scan_cancelled() -> bool {
+    LIBRARY_SCAN_CANCEL_FLAG.load(Ordering::SeqCst)
+}
+
+fn start_library_job() -> Result<(), String> {
+    if LIBRARY_SCAN_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(BackendError::new(
+            code::VALIDATION_INVALID_INPUT,
+            "A library scan is already running",
+        )
+        .with_retryable(false)
+        .to_wire_string());
+    }
+    LIBRARY_SCAN_CANCEL_FLAG.store(false, Ordering::SeqCst);
+    Ok(())
+}
+
+fn emit_library_scan_progress(app: &AppHandle, progress: &LibraryScanProgress) {
+    app.emit("library-scan-progress", progress).ok();
+}
+
+/// Read everything known about one media file and record it in history.
+/// Returns false when the file is already in history.
+async fn import_library_file(app: &AppHandle, path: &Path) -> Result<bool, String> {
+    let filepath = path.to_string_lossy().to_string();
+    if history_has_filepath(&filepath)? {
+        return Ok(false);
+    }
+
+    let metadata =
+        std::fs::metadata(path).map_err(|e| format!("Failed to read {}: {}", filepath, e))?;
+    if metadata.len() == 0 {
+        return Err(format!("{} is empty", filepath));
+    }
+    let modified_at = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|since| since.as_secs() as i64)
+        .unwrap_or_else(|| chrono::Utc::now().timestamp());
+
+    let sidecar = info_json_sidecar(path)
+        .and_then(|sidecar| std::fs::read_to_string(sidecar).ok())
+        .and_then(|json| parse_info_json(&json))
+        .unwrap_or_default();
+    let info = match probe_library_file(app, path).await {
+        Some(embedded) => embedded.or(sidecar),
+        None => sidecar,
+    };
+
+    let mut entry = build_imported_entry(path, &info, Some(metadata.len()), modified_at);
+    entry.source = detect_source(&entry.url);
+    entry.content_hash = library_content_hash(path).ok();
+    import_history_entry(&entry)?;
+    Ok(true)
+}
+
+async fn run_library_scan(app: &AppHandle, root: PathBuf) -> LibraryScanProgress {
+    let mut progress = LibraryScanProgress {
+        folder: root.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    emit_library_scan_progress(app, &progress);
+
+    let walk_root = root.clone();
+    let files = tokio::task::spawn_blocking(move || {
+        collect_library_media_files(&walk_root, is_library_scan_cancelled)
+    })
+    .await
+    .unwrap_or_default();
+    progress.total = files.len() as u64;
+
+    let mut last_emit = Instant::now();
+    for path in files {
+        if is_library_scan_cancelled() {
+            progress.cancelled = true;
+            break;
+        }
+
+        progress.current = Some(path.to_string_lossy().to_string());
+        match import_library_file(app, &path).await {
+            Ok(true) => progress.imported += 1,
+            Ok(false) => progress.skipped += 1,
+            Err(e) => {
+                log::warn!("Library scan failed to import {}: {}", path.display(), e);
+                progress.failed += 1;
+            }
+        }
+        progress.processed += 1;
+
+        if last_emit.elapsed() >= LIBRARY_SCAN_PROGRESS_INTERVAL {
+            emit_library_scan_progress(app, &progress);
+            last_emit = Instant::now();
+        }
+    }
+
+    progress.cancelled |= is_library_scan_cancelled();
+    progress.current = None;
+    progress.finished = true;
+    emit_library_scan_progress(app, &progress);
+    progress
+}
+
+/// Import the media files under `folder` into history, with what their `.info.json`
+/// sidecars, embedded tags and ffprobe tell about them. Files already in history are
+/// skipped, so a folder can be scanned again to pick up new files. Progress is emitted
+/// as `library-scan-progress`.
+#[tauri::command]
+pub async fn scan_library_folder(
+    app: AppHandle,
+    folder: String,
+) -> Result<LibraryScanProgress, String> {
+    let root = PathBuf::from(folder.trim());
+    if !root.is_absolute() || !root.is_dir() {
+        return Err(BackendError::new(
+            code::VALIDATION_INVALID_INPUT,
+            "Library folder must be an existing absolute folder",
+        )
+        .with_retryable(false)
+        .to_wire_string());
+    }
+    start_library_job()?;
+
+    let progress = run_library_scan(&app, root).await;
+    LIBRARY_SCAN_RUNNING.store(false, Ordering::SeqCst);
+
+    add_log_internal(
+        "info",
+        &format!(
+            "Library scan of {} {}: {} imported, {} already in history, {} failed",
+            progress.folder,
+            if progress.cancelled {
+                "stopped"
+            } else {
+                "finished"
+            },
+            progress.imported,
+            progress.skipped,
+            progress.failed
+        ),
+        None,
+        None,
+    )
+    .ok();
+    Ok(progress)
+}
+
+#[tauri::command]
+pub fn cancel_library_scan() {
+    LIBRARY_SCAN_CANCEL_FLAG.store(true, Ordering::SeqCst);
+}
+
+fn emit_library_relink_progress(app: &AppHandle, progress: &LibraryRelinkProgress) {
+    app.emit("library-relink-progress", progress).ok();
+}
+
+/// The files under `roots` that have the name or size of a missing file, and aren't the
+/// file of another history entry
+fn index_library_files(
+    roots: &[PathBuf],
+    names: &HashSet<String>,
+    sizes: &HashSet<u64>,
+    known: &HashSet<String>,
+) -> LibraryFileIndex {
+    let mut files = Vec::new();
+    for root in roots {
+        for path in collect_library_files(root, is_library_scan_cancelled) {
+            let filepath = path.to_string_lossy().to_string();
+            if known.contains(&filepath) {
+                continue;
+            }
+            let Ok(metadata) = std::fs::metadata(&path) else {
+                continue;
+            };
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if names.contains(&name) || sizes.contains(&metadata.len()) {
+                files.push(LibraryFile {
+                    path,
+                    size: metadata.len(),
+                });
+            }
+        }
+    }
+    LibraryFileIndex::new(files)
+}
+
+fn run_library_relink(
+    app: &AppHandle,
+    roots: Vec<PathBuf>,
+) -> Result<LibraryRelinkProgress, String> {
+    let mut progress = LibraryRelinkProgress::default();
+    emit_library_relink_progress(app, &progress);
+
+    // Files that are still in place get their content hash on the way, so they can be
+    // found by content if they are moved later
+    let records = get_history_file_records()?;
+    let known: HashSet<String> = records
+        .iter()
+        .map(|record| record.filepath.clone())
+        .collect();
+    let mut missing = Vec::new();
+    for record in records {
+        if is_library_scan_cancelled() {
+            break;
+        }
+        let path = Path::new(&record.filepath);
+        if path.is_file() {
+            if record.content_hash.is_none() {
+                if let Ok(hash) = library_content_hash(path) {
+                    set_history_content_hash(&record.id, &hash).ok();
+                }
+            }
+        } else if !path.exists() {
+            missing.push(record);
+        }
+    }
+    progress.total = missing.len() as u64;
+
+    let names: HashSet<String> = missing
+        .iter()
+        .filter_map(|record| Path::new(&record.filepath).file_name())
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .collect();
+    let sizes: HashSet<u64> = missing
+        .iter()
+        .filter_map(|record| record.filesize)
+        .collect();
+    let mut index = if missing.is_empty() {
+        LibraryFileIndex::default()
+    } else {
+        index_library_files(&roots, &names, &sizes, &known)
+    };
+
+    let mut hashes: HashMap<PathBuf, Option<String>> = HashMap::new();
+    let mut hash_of = |path: &Path| {
+        hashes
+            .entry(path.to_path_buf())
+            .or_insert_with(|| library_content_hash(path).ok())
+            .clone()
+    };
+    let mut last_emit = Instant::now();
+    for record in missing {
+        if is_library_scan_cancelled() {
+            break;
+        }
+
+        progress.current = Some(record.filepath.clone());
+        match find_moved_file(&index, &record, &mut hash_of) {
+            RelinkMatch::Confident(found) => {
+                let hash = hash_of(Path::new(&found.filepath));
+                match relink_history_file(
+                    &record.id,
+                    &found.filepath,
+                    found.filesize,
+                    hash.as_deref(),
+                ) {
+                    Ok(()) => {
+                        log::info!("Relinked {} to {}", record.filepath, found.filepath);
+                        index.claim(Path::new(&found.filepath));
+                        progress.relinked += 1;
+                    }
+                    Err(e) => log::warn!("Failed to relink {}: {}", record.filepath, e),
+                }
+            }
+            RelinkMatch::Ambiguous(candidates) => progress.ambiguous.push(MissingHistoryFile {
+                history_id: record.id,
+                title: record.title,
+                filepath: record.filepath,
+                candidates,
+            }),
+            RelinkMatch::NotFound => progress.not_found += 1,
+        }
+        progress.processed += 1;
+
+        if last_emit.elapsed() >= LIBRARY_SCAN_PROGRESS_INTERVAL {
+            emit_library_relink_progress(app, &progress);
+            last_emit = Instant::now();
+        }
+    }
+
+    progress.cancelled = is_library_scan_cancelled();
+    progress.current = None;
+    progress.finished = true;
+    emit_library_relink_progress(app, &progress);
+    Ok(progress)
+}
+
+/// Look for the files of history entries that are missing under the library folders
+/// `roots`. Entries whose file is found by content hash, or by name and size when no hash
+/// was recorded, are relinked; the others are returned with the files that may be theirs.
+/// Progress is emitted as `library-relink-progress`.
+#[tauri::command]
+pub async fn relink_library_files(
+    app: AppHandle,
+    roots: Vec<String>,
+) -> Result<LibraryRelinkProgress, String> {
+    let roots: Vec<PathBuf> = roots
+        .iter()
+        .map(|root| PathBuf::from(root.trim()))
+        .filter(|root| root.is_absolute() && root.is_dir())
+        .collect();
+    if roots.is_empty() {
+        return Err(BackendError::new(
+            code::VALIDATION_INVALID_INPUT,
+            "Add at least one existing library folder to search",
+        )
+        .with_retryable(false)
+        .to_wire_string());
+    }
+    start_library_job()?;
+
+    let job_app = app.clone();
+    let result = tokio::task::spawn_blocking(move || run_library_relink(&job_app, roots))
+        .await
+        .map_err(|e| format!("Library relink failed: {}", e))
+        .and_then(|result| result);
+    LIBRARY_SCAN_RUNNING.store(false, Ordering::SeqCst);
+
+    let progress = result?;
+    add_log_internal(
+        "info",
+        &format!(
+            "Library relink {}: {} relinked, {} with several candidates, {} not found",
+            if progress.cancelled {
+                "stopped"
+            } else {
+                "finished"
+            },
+            progress.relinked,
+            progress.ambiguous.len(),
+            progress.not_found
+        ),
+        None,
+        None,
+    )
+    .ok();
+    Ok(progress)
+}
+
+/// Point a history entry at the file the user picked for its missing file
+#[tauri::command]
+pub fn relink_history_entry(id: String, filepath: String) -> Result<(), String> {
+    let path = PathBuf::from(filepath.trim());
+    if !path.is_absolute() || !path.is_file() {
+        return Err(BackendError::new(
+            code::VALIDATION_INVALID_INPUT,
+            "The new file must be an existing absolute file path",
+        )
+        .with_retryable(false)
+        .to_wire_string());
+    }
+    let filesize = std::fs::metadata(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .len();
+    let hash = library_content_hash(&path).ok();
+    relink_history_file(&id, &path.to_string_lossy(), filesize, hash.as_deref())
+}
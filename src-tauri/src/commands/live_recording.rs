@@ -0,0 +1,333 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Notify;
+
+use crate::commands::cancel_download_queue_item;
+use crate::database::{
+    add_log_internal, cancel_live_recording_db, delete_live_recording_db,
+    get_download_queue_item_db, get_live_recording_db, get_live_recordings_db,
+    insert_live_recording_db, save_live_recording_progress_db,
+};
+use crate::services::{
+    self, enqueue_download_item, plan_live_check, probe_live_status, session_output_template,
+    LiveStatus,
+};
+use crate::types::{
+    BackendError, DownloadOptions, DownloadQueueStatus, LiveRecording, LiveRecordingStatus,
+    NewDownloadQueueItem, NewLiveRecording,
+};
+use crate::utils::{normalize_url, validate_url, DEFAULT_OUTPUT_TEMPLATE};
+
+/// Event emitted whenever a live recording is added, changes state or is removed
+pub const LIVE_RECORDINGS_UPDATED_EVENT: &str = "live-recordings-updated";
+
+static LIVE_RECORDER_STARTED: AtomicBool = AtomicBool::new(false);
+
+static LIVE_RECORDER_WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// How often the recorder looks at recordings when no poll is due
+const LIVE_RECORDER_IDLE_MS: u64 = 60_000;
+
+/// A stream that ended a session is checked again this soon, in case it only dropped
+const SESSION_RECHECK_MS: i64 = 30_000;
+
+/// Failed polls or sessions in a row before a recording gives up
+const MAX_LIVE_FAILURES: u32 = 5;
+
+/// Ask the recorder to look at recordings again (new recording, finished session).
+pub fn wake_live_recorder() {
+    LIVE_RECORDER_WAKE.notify_one();
+}
+
+/// Start the loop that polls upcoming streams and records them once live.
+/// Recordings persist, so streams waited for or recorded before a restart are
+/// picked up again: a session interrupted by the restart resumes with the queue.
+pub fn start_live_recorder(app: AppHandle) {
+    if LIVE_RECORDER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        log::info!("Live recorder started");
+        loop {
+            let idle_ms = run_live_recorder(&app)
+                .await
+                .map_or(LIVE_RECORDER_IDLE_MS, |due_in| {
+                    due_in.min(LIVE_RECORDER_IDLE_MS)
+                });
+            tokio::select! {
+                _ = LIVE_RECORDER_WAKE.notified() => {}
+                _ = tokio::time::sleep(tokio::time::Duration::from_millis(idle_ms)) => {}
+            }
+        }
+    });
+}
+
+/// Follow up on finished sessions and poll the streams that are due.
+/// Returns the milliseconds until the next poll, if any.
+async fn run_live_recorder(app: &AppHandle) -> Option<u64> {
+    let recordings = match get_live_recordings_db() {
+        Ok(recordings) => recordings,
+        Err(e) => {
+            log::error!("Failed to read live recordings: {}", e);
+            return None;
+        }
+    };
+
+    let mut changed = false;
+    for recording in recordings {
+        let now = chrono::Utc::now().timestamp_millis();
+        let next = match recording.status {
+            LiveRecordingStatus::Recording => follow_session(recording),
+            LiveRecordingStatus::Waiting
+                if recording.next_check_at.map_or(true, |at| at <= now) =>
+            {
+                Some(poll_stream(app, recording).await)
+            }
+            _ => None,
+        };
+        if let Some(next) = next {
+            match save_live_recording_progress_db(&next) {
+                Ok(saved) => changed |= saved,
+                Err(e) => log::error!("Failed to save live recording: {}", e),
+            }
+        }
+    }
+    if changed {
+        let _ = app.emit(LIVE_RECORDINGS_UPDATED_EVENT, ());
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    get_live_recordings_db()
+        .unwrap_or_default()
+        .iter()
+        .filter(|recording| recording.status == LiveRecordingStatus::Waiting)
+        .filter_map(|recording| recording.next_check_at)
+        .min()
+        .map(|at| (at - now).max(0) as u64)
+}
+
+/// State of a recording after its session's queue item has moved on,
+/// or `None` while the session is still queued or running.
+fn follow_session(mut recording: LiveRecording) -> Option<LiveRecording> {
+    // A queue item that was removed counts as cancelled.
+    let item = recording
+        .current_item_id
+        .as_deref()
+        .and_then(|id| get_download_queue_item_db(id).ok().flatten());
+    let status = item
+        .as_ref()
+        .map_or(DownloadQueueStatus::Cancelled, |item| item.status);
+    if !status.is_finished() {
+        return None;
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    recording.current_item_id = None;
+    recording.checks = 0;
+    match status {
+        DownloadQueueStatus::Completed => {
+            recording.status = LiveRecordingStatus::Waiting;
+            recording.failures = 0;
+            recording.error_message = None;
+            recording.next_check_at = Some(now + SESSION_RECHECK_MS);
+        }
+        DownloadQueueStatus::Cancelled => {
+            recording.status = LiveRecordingStatus::Cancelled;
+            recording.next_check_at = None;
+        }
+        _ => {
+            let error = item
+                .and_then(|item| item.error_message)
+                .unwrap_or_else(|| "Recording failed".to_string());
+            fail_attempt(&mut recording, error, now + SESSION_RECHECK_MS);
+        }
+    }
+    Some(recording)
+}
+
+/// Count a failed poll or session; the recording gives up after too many in a row.
+fn fail_attempt(recording: &mut LiveRecording, error: String, retry_at: i64) {
+    recording.failures += 1;
+    recording.error_message = Some(error);
+    if recording.failures >= MAX_LIVE_FAILURES {
+        recording.status = LiveRecordingStatus::Failed;
+        recording.next_check_at = None;
+    } else {
+        recording.status = LiveRecordingStatus::Waiting;
+        recording.next_check_at = Some(retry_at);
+    }
+}
+
+/// Check whether a waiting stream is live and start recording it if so.
+async fn poll_stream(app: &AppHandle, mut recording: LiveRecording) -> LiveRecording {
+    let now = chrono::Utc::now().timestamp_millis();
+    let probe = match probe_live_status(app, &recording.url, &network_options(&recording)).await {
+        Ok(probe) => probe,
+        Err(e) => {
+            log::warn!("Failed to check live stream {}: {}", recording.url, e);
+            let (retry_at, checks) = plan_live_check(recording.checks, recording.release_at, now);
+            recording.checks = checks;
+            fail_attempt(&mut recording, e, retry_at);
+            return recording;
+        }
+    };
+    if recording.title.is_none() {
+        recording.title = probe.title.clone();
+    }
+    if probe.release_at.is_some() {
+        recording.release_at = probe.release_at;
+    }
+
+    // The last session finished cleanly unless an error is still recorded.
+    let recorded = recording.sessions > 0 && recording.error_message.is_none();
+    match probe.status {
+        LiveStatus::Live => start_session(app, &mut recording, true),
+        // Nothing recorded yet, so waiting doesn't count against it
+        LiveStatus::Upcoming | LiveStatus::PostLive if !recorded => {
+            if probe.status == LiveStatus::Upcoming {
+                recording.failures = 0;
+            }
+            let (next_check_at, checks) =
+                plan_live_check(recording.checks, recording.release_at, now);
+            recording.checks = checks;
+            recording.next_check_at = Some(next_check_at);
+        }
+        // Over: done if a session recorded it, otherwise download what the site kept
+        _ if recorded => {
+            recording.status = LiveRecordingStatus::Completed;
+            recording.next_check_at = None;
+        }
+        _ => start_session(app, &mut recording, false),
+    }
+    recording
+}
+
+/// Queue a download of the stream. `live` is false when it ended before it could be recorded.
+fn start_session(app: &AppHandle, recording: &mut LiveRecording, live: bool) {
+    let session = recording.sessions + 1;
+    let mut options = recording.options.clone();
+    // Only the first session records from the start; later ones continue where it dropped.
+    options.live_from_start = Some(live && session == 1 && options.live_from_start == Some(true));
+    if session > 1 {
+        let template = options
+            .output_template
+            .as_deref()
+            .filter(|template| !template.trim().is_empty())
+            .unwrap_or(DEFAULT_OUTPUT_TEMPLATE);
+        options.output_template = Some(session_output_template(template, session));
+    }
+    if options.download_kind.is_none() {
+        options.download_kind = Some("live".to_string());
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let queued = enqueue_download_item(
+        app,
+        NewDownloadQueueItem {
+            id: None,
+            url: recording.url.clone(),
+            queue_kind: None,
+            title: recording.title.clone(),
+            origin: Some("live".to_string()),
+            origin_ref: Some(recording.id.clone()),
+            priority: None,
+            options,
+        },
+    );
+    match queued {
+        Ok(item) => {
+            add_log_internal(
+                "info",
+                &format!("Live stream recording started (session {})", session),
+                None,
+                Some(&recording.url),
+            )
+            .ok();
+            recording.status = LiveRecordingStatus::Recording;
+            recording.sessions = session;
+            recording.current_item_id = Some(item.id);
+            recording.next_check_at = None;
+        }
+        Err(e) => {
+            log::warn!("Failed to queue live recording {}: {}", recording.id, e);
+            fail_attempt(recording, e, now + SESSION_RECHECK_MS);
+        }
+    }
+}
+
+/// Recording options with the network settings synced for polling, like queued downloads.
+fn network_options(recording: &LiveRecording) -> DownloadOptions {
+    let mut options = recording.options.clone();
+    if options.cookie_mode.is_none() && options.proxy_url.is_none() {
+        let network = services::polling::get_network_config();
+        options.cookie_mode = network.cookie_mode;
+        options.cookie_browser = network.cookie_browser;
+        options.cookie_browser_profile = network.cookie_browser_profile;
+        options.cookie_file_path = network.cookie_file_path;
+        options.proxy_url = network.proxy_url;
+    }
+    options
+}
+
+fn recordings_changed(app: &AppHandle) {
+    let _ = app.emit(LIVE_RECORDINGS_UPDATED_EVENT, ());
+    wake_live_recorder();
+}
+
+#[tauri::command]
+pub fn list_live_recordings() -> Result<Vec<LiveRecording>, String> {
+    get_live_recordings_db()
+}
+
+/// Watch an upcoming or running stream and record it once it is live.
+#[tauri::command]
+pub fn add_live_recording(
+    app: AppHandle,
+    recording: NewLiveRecording,
+) -> Result<LiveRecording, String> {
+    validate_url(&recording.url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
+
+    let title = recording
+        .title
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty());
+    let saved = insert_live_recording_db(
+        &normalize_url(&recording.url),
+        title.as_deref(),
+        &recording.options,
+        chrono::Utc::now().timestamp_millis(),
+    )?;
+
+    recordings_changed(&app);
+    Ok(saved)
+}
+
+/// Stop waiting for a stream, or stop recording it. Files of finished sessions are kept.
+#[tauri::command]
+pub async fn cancel_live_recording(app: AppHandle, id: String) -> Result<(), String> {
+    let Some(recording) = get_live_recording_db(&id)? else {
+        return Err(format!("Live recording not found: {}", id));
+    };
+    cancel_live_recording_db(&id)?;
+    if let Some(item_id) = recording.current_item_id {
+        cancel_download_queue_item(app.clone(), item_id).await?;
+    }
+    recordings_changed(&app);
+    Ok(())
+}
+
+/// Delete a recording, cancelling it first if it is still active.
+#[tauri::command]
+pub async fn remove_live_recording(app: AppHandle, id: String) -> Result<(), String> {
+    let Some(recording) = get_live_recording_db(&id)? else {
+        return Ok(());
+    };
+    delete_live_recording_db(&id)?;
+    if let Some(item_id) = recording.current_item_id {
+        cancel_download_queue_item(app.clone(), item_id).await?;
+    }
+    recordings_changed(&app);
+    Ok(())
+}
@@ -5,10 +5,15 @@ mod cli;
 mod cli_shortcut;
 mod dependencies;
 mod download;
+mod download_profiles;
 mod download_queue;
+mod download_schedule;
+mod download_stats;
 mod external;
 mod gallery;
 mod history;
+mod library_scan;
+mod live_recording;
 mod logs;
 mod metadata;
 mod plugin;
@@ -25,10 +30,15 @@ pub use cli::*;
 pub use cli_shortcut::*;
 pub use dependencies::*;
 pub use download::*;
+pub use download_profiles::*;
 pub use download_queue::*;
+pub use download_schedule::*;
+pub use download_stats::*;
 pub use external::*;
 pub use gallery::*;
 pub use history::*;
+pub use library_scan::*;
+pub use live_recording::*;
 pub use logs::*;
 pub use metadata::*;
 pub use plugin::*;
@@ -10,10 +10,11 @@ use tokio::process::Command;
 use tokio::sync::Mutex;
 
 use crate::database::get_db;
-use crate::services::{generate_raw, get_ffmpeg_path, AIConfig};
+use crate::services::{
+    generate_raw, get_ffmpeg_path, get_ffprobe_path, precise_cut, AIConfig, PreciseCutRange,
+};
 use crate::utils::{
-    args_to_display_command, find_system_binary, parse_ffmpeg_command_args,
-    unix_system_binary_dirs, validate_ffmpeg_args, CommandExt,
+    args_to_display_command, parse_ffmpeg_command_args, validate_ffmpeg_args, CommandExt,
 };
 
 #[path = "processing/attachments.rs"]
@@ -58,6 +59,9 @@ pub struct FFmpegCommandResult {
     pub estimated_time_seconds: f64,
     pub output_path: String,
     pub warnings: Vec<String>,
+    /// Set for a precise cut, which runs with `execute_precise_cut` instead of `command_args`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precise_cut: Option<PreciseCutRange>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -277,6 +281,7 @@ fn try_build_subtitle_command(
         estimated_time_seconds: (metadata.duration / 2.0).max(5.0),
         output_path,
         warnings,
+        precise_cut: None,
     }))
 }
 
@@ -461,6 +466,7 @@ async fn try_build_merge_command(
         estimated_time_seconds: (total_duration / 3.0).max(8.0),
         output_path,
         warnings,
+        precise_cut: None,
     }))
 }
 
@@ -783,6 +789,7 @@ For valid video requests:
                     .collect()
             })
             .unwrap_or_default(),
+        precise_cut: None,
     })
 }
 
@@ -802,8 +809,29 @@ pub async fn generate_quick_action_command(
         .map(|s| s.to_string_lossy().to_string())
         .unwrap_or("output".to_string());
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let mut precise_range = None;
 
     let (command_args, output_path, explanation) = match task_type.as_str() {
+        "cut" if options.get("precise").and_then(|p| p.as_bool()) == Some(true) => {
+            let start = timeline_start.ok_or("No start time selected")?;
+            let end = timeline_end.ok_or("No end time selected")?;
+            if end <= start {
+                return Err("The end time must be after the start time".to_string());
+            }
+            let output = output_base_dir.join(format!("{}_cut_{}.mp4", input_stem, timestamp));
+            precise_range = Some(PreciseCutRange { start, end });
+
+            (
+                Vec::new(),
+                output.to_string_lossy().to_string(),
+                format!(
+                    "Cut video precisely from {} to {} (duration: {}), re-encoding only around the cut points",
+                    format_time(start),
+                    format_time(end),
+                    format_time(end - start)
+                ),
+            )
+        }
         "cut" => {
             let start = timeline_start.ok_or("No start time selected")?;
             let end = timeline_end.ok_or("No end time selected")?;
@@ -1154,7 +1182,16 @@ pub async fn generate_quick_action_command(
         _ => return Err(format!("Unknown task type: {}", task_type)),
     };
 
-    let command = args_to_display_command(&command_args);
+    let command = match precise_range {
+        Some(range) => format!(
+            "precise cut {} -> {}: {} -> {}",
+            format_time(range.start),
+            format_time(range.end),
+            input_path,
+            output_path
+        ),
+        None => args_to_display_command(&command_args),
+    };
     let estimated_time = metadata.duration / 10.0;
 
     Ok(FFmpegCommandResult {
@@ -1165,43 +1202,10 @@ pub async fn generate_quick_action_command(
         estimated_time_seconds: estimated_time,
         output_path,
         warnings: vec![],
+        precise_cut: precise_range,
     })
 }
 
-async fn get_ffprobe_path(app: &AppHandle) -> Option<std::path::PathBuf> {
-    if let Ok(app_data_dir) = app.path().app_data_dir() {
-        let bin_dir = app_data_dir.join("bin");
-        #[cfg(windows)]
-        let ffprobe_path = bin_dir.join("ffprobe.exe");
-        #[cfg(not(windows))]
-        let ffprobe_path = bin_dir.join("ffprobe");
-
-        if ffprobe_path.exists() {
-            return Some(ffprobe_path);
-        }
-    }
-
-    #[cfg(windows)]
-    let binary_name = "ffprobe.exe";
-    #[cfg(not(windows))]
-    let binary_name = "ffprobe";
-
-    if let Some(path) = find_system_binary(binary_name, &unix_system_binary_dirs()) {
-        return Some(path);
-    }
-
-    if let Some(ffmpeg_path) = get_ffmpeg_path(app).await {
-        if let Some(parent) = ffmpeg_path.parent() {
-            let ffprobe_path = parent.join(binary_name);
-            if ffprobe_path.exists() {
-                return Some(ffprobe_path);
-            }
-        }
-    }
-
-    None
-}
-
 async fn load_ai_config(app: &AppHandle) -> Result<AIConfig, String> {
     let app_data_dir = app
         .path()
@@ -207,6 +207,61 @@ pub async fn execute_ffmpeg_command(
     }
 }
 
+/// Cut `start..end` of the input to the frame, re-encoding only around the cut points.
+/// Reports progress and is cancelled like `execute_ffmpeg_command`.
+#[tauri::command]
+pub async fn execute_precise_cut(
+    app: AppHandle,
+    job_id: String,
+    input_path: String,
+    output_path: String,
+    start: f64,
+    end: f64,
+) -> Result<(), String> {
+    println!(
+        "[FFMPEG] Precise cut {}: {} [{}-{}] -> {}",
+        job_id, input_path, start, end, output_path
+    );
+
+    let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    {
+        let mut jobs = ACTIVE_JOBS.lock().await;
+        jobs.insert(job_id.clone(), cancel_tx);
+    }
+
+    let emit_progress = |percent: f64| {
+        let _ = app.emit(
+            "processing-progress",
+            ProcessingProgress {
+                job_id: job_id.clone(),
+                percent: percent * 100.0,
+                frame: 0,
+                total_frames: 0,
+                fps: 0.0,
+                speed: if percent >= 1.0 { "done" } else { "" }.to_string(),
+                time: String::new(),
+                size: String::new(),
+            },
+        );
+    };
+    let range = PreciseCutRange { start, end };
+
+    let result = tokio::select! {
+        result = precise_cut(&app, &input_path, range, &output_path, emit_progress) => result,
+        _ = cancel_rx => Err("Processing cancelled".to_string()),
+    };
+
+    {
+        let mut jobs = ACTIVE_JOBS.lock().await;
+        jobs.remove(&job_id);
+    }
+    if let Err(e) = &result {
+        println!("[FFMPEG] Precise cut failed: {}", e);
+        tokio::fs::remove_file(&output_path).await.ok();
+    }
+    result
+}
+
 #[tauri::command]
 pub async fn cancel_ffmpeg(job_id: String) -> Result<(), String> {
     let mut jobs = ACTIVE_JOBS.lock().await;
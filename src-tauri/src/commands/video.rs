@@ -2,11 +2,16 @@ use crate::database::add_log_internal;
 use crate::services::{
     build_cookie_args, build_proxy_args, build_site_header_args, get_deno_path, parse_ytdlp_error,
     run_ytdlp_json_with_cookies, run_ytdlp_with_stderr, run_ytdlp_with_stderr_and_cookies,
+    with_interactive_retry,
 };
 use crate::types::{
-    BackendError, FormatOption, PlaylistVideoEntry, SubtitleInfo, VideoInfo, VideoInfoResponse,
+    BackendError, FormatOption, FormatPlan, FormatPreferences, OutputTemplatePreview,
+    PlaylistVideoEntry, SubtitleInfo, VideoInfo, VideoInfoResponse,
+};
+use crate::utils::{
+    compile_format_preferences, explain_format_choice, normalize_url, parse_format_options,
+    render_output_template, validate_output_template, validate_url,
 };
-use crate::utils::{normalize_url, validate_url};
 use std::time::Duration;
 use tauri::AppHandle;
 use tokio::time::timeout;
@@ -956,40 +961,45 @@ pub async fn get_video_info(
     add_log_internal("command", &command_str, None, Some(&url)).ok();
 
     let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-    let output = match timeout(
-        Duration::from_secs(45),
-        run_ytdlp_with_stderr(&app, &args_ref),
-    )
-    .await
-    {
-        Ok(result) => result?,
-        Err(_) => {
-            let error = BackendError::from_message(
-                "Timed out fetching video info. Please try again or check your cookie/proxy settings.",
-            );
-            add_log_internal("error", error.message(), None, Some(&url)).ok();
-            return Err(error.to_wire_string());
-        }
-    };
+    let (app_ref, args_ref, url_ref) = (&app, &args_ref, &url);
+    // Timeouts and network errors are retried briefly; a rate limit fails right away
+    let json_output = with_interactive_retry("Fetching video info", &url, || async move {
+        let output = match timeout(
+            Duration::from_secs(45),
+            run_ytdlp_with_stderr(app_ref, args_ref),
+        )
+        .await
+        {
+            Ok(result) => result?,
+            Err(_) => {
+                let error = BackendError::from_message(
+                    "Timed out fetching video info. Please try again or check your cookie/proxy settings.",
+                );
+                add_log_internal("error", error.message(), None, Some(url_ref)).ok();
+                return Err(error.to_wire_string());
+            }
+        };
 
-    if !output.stderr.trim().is_empty() {
-        add_log_internal("stderr", output.stderr.trim(), None, Some(&url)).ok();
-    }
+        if !output.stderr.trim().is_empty() {
+            add_log_internal("stderr", output.stderr.trim(), None, Some(url_ref)).ok();
+        }
 
-    if !output.success {
-        let parsed_error = parse_ytdlp_error(&output.stderr).unwrap_or_else(|| {
-            let stderr = output.stderr.trim();
-            if stderr.is_empty() {
-                BackendError::from_message("Failed to fetch video info.")
-            } else {
-                BackendError::from_message(format!("Failed to fetch video info: {}", stderr))
-            }
-        });
-        add_log_internal("error", parsed_error.message(), None, Some(&url)).ok();
-        return Err(parsed_error.to_wire_string());
-    }
+        if !output.success {
+            let parsed_error = parse_ytdlp_error(&output.stderr).unwrap_or_else(|| {
+                let stderr = output.stderr.trim();
+                if stderr.is_empty() {
+                    BackendError::from_message("Failed to fetch video info.")
+                } else {
+                    BackendError::from_message(format!("Failed to fetch video info: {}", stderr))
+                }
+            });
+            add_log_internal("error", parsed_error.message(), None, Some(url_ref)).ok();
+            return Err(parsed_error.to_wire_string());
+        }
 
-    let json_output = output.stdout;
+        Ok(output.stdout)
+    })
+    .await?;
     let json: serde_json::Value = serde_json::from_str(&json_output).map_err(|e| {
         let message = format!("Failed to parse video info JSON: {}", e);
         add_log_internal("error", &message, None, Some(&url)).ok();
@@ -1060,45 +1070,7 @@ pub async fn get_video_info(
             .map(|s| s.to_string()),
     };
 
-    let formats = if let Some(formats_arr) = json.get("formats").and_then(|v| v.as_array()) {
-        formats_arr
-            .iter()
-            .filter_map(|f| {
-                let format_id = f.get("format_id").and_then(|v| v.as_str())?;
-                let ext = f.get("ext").and_then(|v| v.as_str()).unwrap_or("unknown");
-
-                Some(FormatOption {
-                    format_id: format_id.to_string(),
-                    ext: ext.to_string(),
-                    resolution: f
-                        .get("resolution")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string()),
-                    width: f.get("width").and_then(|v| v.as_u64()).map(|v| v as u32),
-                    height: f.get("height").and_then(|v| v.as_u64()).map(|v| v as u32),
-                    vcodec: f
-                        .get("vcodec")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string()),
-                    acodec: f
-                        .get("acodec")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string()),
-                    filesize: f.get("filesize").and_then(|v| v.as_u64()),
-                    filesize_approx: f.get("filesize_approx").and_then(|v| v.as_u64()),
-                    tbr: f.get("tbr").and_then(|v| v.as_f64()),
-                    format_note: f
-                        .get("format_note")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string()),
-                    fps: f.get("fps").and_then(|v| v.as_f64()),
-                    quality: f.get("quality").and_then(|v| v.as_f64()),
-                })
-            })
-            .collect()
-    } else {
-        Vec::new()
-    };
+    let formats = parse_format_options(&json);
 
     add_log_internal(
         "info",
@@ -1408,6 +1380,51 @@ pub async fn get_available_subtitles(
     Ok(subtitles)
 }
 
+/// Preview where a download would be saved with the given output template.
+#[tauri::command]
+pub fn preview_output_template(
+    template: String,
+    info: VideoInfo,
+    ext: Option<String>,
+    output_path: Option<String>,
+) -> Result<OutputTemplatePreview, String> {
+    let template = validate_output_template(&template)
+        .map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    let ext = ext
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "mp4".to_string());
+    let relative_path = render_output_template(&template, &info, &ext)
+        .map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    let filepath = output_path
+        .map(|path| path.trim().trim_end_matches(['/', '\\']).to_string())
+        .filter(|path| !path.is_empty())
+        .map(|path| format!("{}/{}", path, relative_path));
+
+    Ok(OutputTemplatePreview {
+        template,
+        relative_path,
+        filepath,
+    })
+}
+
+/// Compile format preferences for a quality and container, and explain which of the
+/// given formats (from `get_video_info`) they would pick.
+#[tauri::command]
+pub fn explain_format_preferences(
+    preferences: FormatPreferences,
+    quality: String,
+    format: String,
+    formats: Option<Vec<FormatOption>>,
+) -> Result<FormatPlan, String> {
+    let mut plan = compile_format_preferences(&preferences, &quality, &format)
+        .map_err(|e| e.to_wire_string())?;
+    if let Some(formats) = formats.filter(|formats| !formats.is_empty()) {
+        plan.choice = explain_format_choice(&preferences, &quality, &format, &formats)
+            .map_err(|e| e.to_wire_string())?;
+    }
+    Ok(plan)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,7 +61,8 @@ pub fn get_followed_channels_db() -> Result<Vec<FollowedChannel>, String> {
                     check_interval, auto_download, download_quality, download_format, created_at,
                     filter_min_duration, filter_max_duration, filter_include_keywords,
                     filter_exclude_keywords, filter_max_videos, download_threads,
-                    download_video_codec, download_audio_bitrate, youtube_content_type
+                    download_video_codec, download_audio_bitrate, youtube_content_type,
+                    download_profile
              FROM followed_channels ORDER BY created_at DESC",
         )
         .map_err(|e| format!("Failed to prepare query: {}", e))?;
@@ -90,6 +91,7 @@ pub fn get_followed_channels_db() -> Result<Vec<FollowedChannel>, String> {
                 download_video_codec: row.get(18)?,
                 download_audio_bitrate: row.get(19)?,
                 youtube_content_type: row.get(20)?,
+                download_profile: row.get(21)?,
             })
         })
         .map_err(|e| format!("Query failed: {}", e))?
@@ -107,7 +109,8 @@ pub fn get_followed_channel_db(id: String) -> Result<FollowedChannel, String> {
                 check_interval, auto_download, download_quality, download_format, created_at,
                 filter_min_duration, filter_max_duration, filter_include_keywords,
                 filter_exclude_keywords, filter_max_videos, download_threads,
-                download_video_codec, download_audio_bitrate, youtube_content_type
+                download_video_codec, download_audio_bitrate, youtube_content_type,
+                download_profile
          FROM followed_channels WHERE id = ?1",
         params![id],
         |row| {
@@ -133,6 +136,7 @@ pub fn get_followed_channel_db(id: String) -> Result<FollowedChannel, String> {
                 download_video_codec: row.get(18)?,
                 download_audio_bitrate: row.get(19)?,
                 youtube_content_type: row.get(20)?,
+                download_profile: row.get(21)?,
             })
         },
     )
@@ -155,6 +159,7 @@ pub fn update_channel_settings_db(
     filter_max_videos: Option<i64>,
     download_threads: i64,
     youtube_content_type: String,
+    download_profile: Option<String>,
 ) -> Result<(), String> {
     let conn = get_db()?;
     conn.execute(
@@ -163,8 +168,8 @@ pub fn update_channel_settings_db(
             download_format = ?4, download_video_codec = ?5, download_audio_bitrate = ?6,
             filter_min_duration = ?7, filter_max_duration = ?8,
             filter_include_keywords = ?9, filter_exclude_keywords = ?10, filter_max_videos = ?11,
-            download_threads = ?12, youtube_content_type = ?13
-         WHERE id = ?14",
+            download_threads = ?12, youtube_content_type = ?13, download_profile = ?14
+         WHERE id = ?15",
         params![
             check_interval,
             auto_download as i64,
@@ -179,6 +184,7 @@ pub fn update_channel_settings_db(
             filter_max_videos,
             download_threads,
             youtube_content_type,
+            download_profile,
             id,
         ],
     )
@@ -514,7 +520,8 @@ mod tests {
                 download_threads INTEGER NOT NULL DEFAULT 1,
                 download_video_codec TEXT NOT NULL DEFAULT 'h264',
                 download_audio_bitrate TEXT NOT NULL DEFAULT '192',
-                youtube_content_type TEXT NOT NULL DEFAULT 'videos'
+                youtube_content_type TEXT NOT NULL DEFAULT 'videos',
+                download_profile TEXT
             );
             CREATE TABLE IF NOT EXISTS channel_videos (
                 id TEXT PRIMARY KEY,
@@ -234,6 +234,36 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
     conn.execute("ALTER TABLE history ADD COLUMN time_range TEXT", [])
         .ok(); // Ignore error if column already exists
 
+    // Migration: Link chapter parts to the history entry of the full download
+    conn.execute("ALTER TABLE history ADD COLUMN parent_id TEXT", [])
+        .ok(); // Ignore error if column already exists
+    conn.execute("ALTER TABLE history ADD COLUMN chapter_index INTEGER", [])
+        .ok(); // Ignore error if column already exists
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_history_parent ON history(parent_id)",
+        [],
+    )
+    .ok();
+
+    // Migration: Remember which playlist (and position) a downloaded entry came from
+    conn.execute("ALTER TABLE history ADD COLUMN playlist_id TEXT", [])
+        .ok(); // Ignore error if column already exists
+    conn.execute("ALTER TABLE history ADD COLUMN playlist_index INTEGER", [])
+        .ok(); // Ignore error if column already exists
+
+    // Migration: Entries imported from a library scan, kept out of the history size limit
+    conn.execute("ALTER TABLE history ADD COLUMN imported_at INTEGER", [])
+        .ok(); // Ignore error if column already exists
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_history_filepath ON history(filepath)",
+        [],
+    )
+    .ok();
+
+    // Migration: Sampled content hash, used to find files that were moved or renamed
+    conn.execute("ALTER TABLE history ADD COLUMN content_hash TEXT", [])
+        .ok(); // Ignore error if column already exists
+
     conn.execute(
         "CREATE TABLE IF NOT EXISTS tags (
             id TEXT PRIMARY KEY,
@@ -396,7 +426,8 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
             download_threads INTEGER NOT NULL DEFAULT 1,
             download_video_codec TEXT NOT NULL DEFAULT 'h264',
             download_audio_bitrate TEXT NOT NULL DEFAULT '192',
-            youtube_content_type TEXT NOT NULL DEFAULT 'videos'
+            youtube_content_type TEXT NOT NULL DEFAULT 'videos',
+            download_profile TEXT
         )",
         [],
     )
@@ -451,6 +482,152 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
     )
     .map_err(|e| format!("Failed to create download_queues table: {}", e))?;
 
+    // Create backend-scheduled download queue items table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS download_queue_items (
+            id TEXT PRIMARY KEY,
+            queue_kind TEXT NOT NULL,
+            url TEXT NOT NULL,
+            title TEXT,
+            site TEXT NOT NULL,
+            origin TEXT NOT NULL DEFAULT 'app',
+            origin_ref TEXT,
+            status TEXT NOT NULL DEFAULT 'pending',
+            priority INTEGER NOT NULL DEFAULT 0,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            options_json TEXT NOT NULL,
+            partial_paths TEXT,
+            error_code TEXT,
+            error_message TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            started_at INTEGER,
+            finished_at INTEGER,
+            next_attempt_at INTEGER
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create download_queue_items table: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_download_queue_items_status ON download_queue_items(status, priority DESC, created_at ASC)",
+        [],
+    )
+    .ok();
+
+    // Create download archive table (videos already downloaded, keyed like yt-dlp's archive)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS download_archive (
+            extractor TEXT NOT NULL,
+            video_id TEXT NOT NULL,
+            url TEXT,
+            origin TEXT NOT NULL,
+            archived_at INTEGER NOT NULL,
+            PRIMARY KEY (extractor, video_id)
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create download_archive table: {}", e))?;
+
+    // Create download profiles table (named download presets)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS download_profiles (
+            name TEXT PRIMARY KEY COLLATE NOCASE,
+            settings_json TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create download_profiles table: {}", e))?;
+
+    // Download scheduler settings, so queued downloads can start before the frontend syncs
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS download_scheduler_config (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            config_json TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create download_scheduler_config table: {}", e))?;
+
+    // Create download schedules table (downloads the backend queues at set times)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS download_schedules (
+            id TEXT PRIMARY KEY,
+            url TEXT NOT NULL,
+            title TEXT,
+            queue_kind TEXT,
+            options_json TEXT NOT NULL,
+            rule_json TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            next_run_at INTEGER,
+            last_run_at INTEGER,
+            last_item_id TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create download_schedules table: {}", e))?;
+
+    // Create live recordings table (streams recorded when they go live)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS live_recordings (
+            id TEXT PRIMARY KEY,
+            url TEXT NOT NULL,
+            title TEXT,
+            options_json TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'waiting',
+            checks INTEGER NOT NULL DEFAULT 0,
+            sessions INTEGER NOT NULL DEFAULT 0,
+            failures INTEGER NOT NULL DEFAULT 0,
+            release_at INTEGER,
+            next_check_at INTEGER,
+            current_item_id TEXT,
+            error_message TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create live_recordings table: {}", e))?;
+
+    // Download journal: lifecycle events of every download, for statistics
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS download_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            download_id TEXT NOT NULL,
+            event TEXT NOT NULL,
+            url TEXT,
+            site TEXT,
+            attempt INTEGER,
+            percent REAL,
+            bytes INTEGER,
+            speed_bps INTEGER,
+            duration_ms INTEGER,
+            error_code TEXT,
+            error_message TEXT,
+            created_at INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create download_events table: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_download_events_created ON download_events(created_at)",
+        [],
+    )
+    .ok();
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_download_events_download
+         ON download_events(download_id, created_at)",
+        [],
+    )
+    .ok();
+
     // Migration: Add download_threads column if it doesn't exist
     conn.execute(
         "ALTER TABLE followed_channels ADD COLUMN download_threads INTEGER NOT NULL DEFAULT 1",
@@ -479,6 +656,13 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
     )
     .ok();
 
+    // Migration: Add download profile column if it doesn't exist
+    conn.execute(
+        "ALTER TABLE followed_channels ADD COLUMN download_profile TEXT",
+        [],
+    )
+    .ok();
+
     DB_CONNECTION
         .set(Mutex::new(conn))
         .map_err(|_| "Database already initialized".to_string())?;
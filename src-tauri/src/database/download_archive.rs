@@ -0,0 +1,320 @@
+use super::get_db;
+use chrono::Utc;
+use rusqlite::params;
+
+/// Whether a download goes ahead even when the archive has the video: asked for
+/// explicitly, or a re-download of a history entry whose file the user wants back.
+pub fn bypasses_download_archive(force_redownload: Option<bool>, history_id: Option<&str>) -> bool {
+    force_redownload.unwrap_or(false) || history_id.is_some_and(|id| !id.trim().is_empty())
+}
+
+/// Derive the yt-dlp archive key (`extractor`, `video id`) from a URL without
+/// running yt-dlp. Only covers sites whose ids can be read straight off the URL;
+/// everything else is recorded from yt-dlp's own archive output after a run.
+pub fn archive_key_from_url(url: &str) -> Option<(String, String)> {
+    let parsed = reqwest::Url::parse(url.trim()).ok()?;
+    let host = parsed.host_str()?.to_ascii_lowercase();
+    let host = host
+        .strip_prefix("www.")
+        .or_else(|| host.strip_prefix("m."))
+        .unwrap_or(&host)
+        .to_string();
+    let segments: Vec<&str> = parsed
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+
+    match host.as_str() {
+        "youtube.com" | "music.youtube.com" => {
+            let id = match segments.as_slice() {
+                ["watch"] => parsed
+                    .query_pairs()
+                    .find(|(name, _)| name == "v")
+                    .map(|(_, value)| value.into_owned())?,
+                ["shorts" | "live" | "embed", id, ..] => id.to_string(),
+                _ => return None,
+            };
+            is_youtube_video_id(&id).then(|| ("youtube".to_string(), id))
+        }
+        "youtu.be" => {
+            let id = segments.first()?;
+            is_youtube_video_id(id).then(|| ("youtube".to_string(), id.to_string()))
+        }
+        "vimeo.com" => match segments.as_slice() {
+            [id] if id.chars().all(|c| c.is_ascii_digit()) => {
+                Some(("vimeo".to_string(), id.to_string()))
+            }
+            _ => None,
+        },
+        "tiktok.com" => match segments.as_slice() {
+            [user, "video", id]
+                if user.starts_with('@') && id.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                Some(("tiktok".to_string(), id.to_string()))
+            }
+            _ => None,
+        },
+        "dailymotion.com" => match segments.as_slice() {
+            ["video", id] => Some(("dailymotion".to_string(), id.to_string())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_youtube_video_id(id: &str) -> bool {
+    id.len() == 11
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Split a yt-dlp `--download-archive` line (`<extractor> <id>`) into its parts.
+pub fn parse_archive_line(line: &str) -> Option<(String, String)> {
+    let (extractor, video_id) = line.trim().split_once(' ')?;
+    let video_id = video_id.trim();
+    if extractor.is_empty() || video_id.is_empty() {
+        return None;
+    }
+    Some((extractor.to_ascii_lowercase(), video_id.to_string()))
+}
+
+/// Check whether a video has already been downloaded.
+pub fn is_download_archived_db(extractor: &str, video_id: &str) -> Result<bool, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM download_archive WHERE extractor = ?1 AND video_id = ?2)",
+        params![extractor.to_ascii_lowercase(), video_id],
+        |row| row.get::<_, bool>(0),
+    )
+    .map_err(|e| format!("Failed to check download archive: {}", e))
+}
+
+/// All archive entries in yt-dlp's `--download-archive` file format.
+pub fn get_download_archive_lines_db() -> Result<Vec<String>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare("SELECT extractor, video_id FROM download_archive")
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let lines = stmt
+        .query_map([], |row| {
+            Ok(format!(
+                "{} {}",
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?
+            ))
+        })
+        .map_err(|e| format!("Failed to query download archive: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(lines)
+}
+
+/// Record the entries of a yt-dlp archive file written during a download.
+/// Existing entries are kept; returns how many new videos were archived.
+pub fn record_download_archive_lines_db(contents: &str, url: &str) -> Result<usize, String> {
+    let mut conn = get_db()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let now = Utc::now().timestamp();
+    let mut added = 0;
+    for (extractor, video_id) in contents.lines().filter_map(parse_archive_line) {
+        added += tx
+            .execute(
+                "INSERT OR IGNORE INTO download_archive (extractor, video_id, url, origin, archived_at)
+                 VALUES (?1, ?2, ?3, 'download', ?4)",
+                params![extractor, video_id, url, now],
+            )
+            .map_err(|e| format!("Failed to update download archive: {}", e))?;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    Ok(added)
+}
+
+/// Archive every full download in history whose video id can be read from its URL.
+/// Clip downloads (with a time range) are left out so the full video can still be fetched.
+pub fn seed_download_archive_from_history_db() -> Result<usize, String> {
+    let mut conn = get_db()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    let rows: Vec<(String, i64)> = {
+        let mut stmt = tx
+            .prepare(
+                "SELECT url, downloaded_at FROM history
+                 WHERE time_range IS NULL OR time_range = ''",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format!("Failed to query history: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+        rows
+    };
+
+    let mut added = 0;
+    for (url, downloaded_at) in rows {
+        let Some((extractor, video_id)) = archive_key_from_url(&url) else {
+            continue;
+        };
+        added += tx
+            .execute(
+                "INSERT OR IGNORE INTO download_archive (extractor, video_id, url, origin, archived_at)
+                 VALUES (?1, ?2, ?3, 'history', ?4)",
+                params![extractor, video_id, url, downloaded_at],
+            )
+            .map_err(|e| format!("Failed to seed download archive: {}", e))?;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    fn ensure_test_archive_tables() {
+        if DB_CONNECTION.get().is_none() {
+            let conn = rusqlite::Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+
+        let conn = get_db().expect("get db");
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS history (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL,
+                title TEXT NOT NULL,
+                thumbnail TEXT,
+                filepath TEXT NOT NULL,
+                filesize INTEGER,
+                duration INTEGER,
+                quality TEXT,
+                format TEXT,
+                source TEXT,
+                downloaded_at INTEGER NOT NULL,
+                summary TEXT,
+                time_range TEXT
+            );
+            CREATE TABLE IF NOT EXISTS download_archive (
+                extractor TEXT NOT NULL,
+                video_id TEXT NOT NULL,
+                url TEXT,
+                origin TEXT NOT NULL,
+                archived_at INTEGER NOT NULL,
+                PRIMARY KEY (extractor, video_id)
+            );
+            DELETE FROM download_archive;
+            DELETE FROM history;",
+        )
+        .expect("create archive tables");
+    }
+
+    fn insert_history(id: &str, url: &str, time_range: Option<&str>) {
+        let conn = get_db().expect("get db");
+        conn.execute(
+            "INSERT INTO history (id, url, title, filepath, downloaded_at, time_range)
+             VALUES (?1, ?2, 'Title', '/tmp/file.mp4', 100, ?3)",
+            params![id, url, time_range],
+        )
+        .expect("insert history");
+    }
+
+    #[test]
+    fn archive_key_from_url_reads_known_sites() {
+        let yt = Some(("youtube".to_string(), "dQw4w9WgXcQ".to_string()));
+        assert_eq!(
+            archive_key_from_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=10"),
+            yt
+        );
+        assert_eq!(archive_key_from_url("https://youtu.be/dQw4w9WgXcQ"), yt);
+        assert_eq!(
+            archive_key_from_url("https://youtube.com/shorts/dQw4w9WgXcQ"),
+            yt
+        );
+        assert_eq!(
+            archive_key_from_url("https://vimeo.com/123456"),
+            Some(("vimeo".to_string(), "123456".to_string()))
+        );
+        assert_eq!(
+            archive_key_from_url("https://www.tiktok.com/@user/video/7312345678901234567"),
+            Some(("tiktok".to_string(), "7312345678901234567".to_string()))
+        );
+        assert_eq!(
+            archive_key_from_url("https://www.youtube.com/playlist?list=PL123"),
+            None
+        );
+        assert_eq!(
+            archive_key_from_url("https://example.com/watch?v=abc"),
+            None
+        );
+    }
+
+    #[test]
+    fn history_redownloads_bypass_the_archive() {
+        assert!(!bypasses_download_archive(None, None));
+        assert!(!bypasses_download_archive(Some(false), Some(" ")));
+        assert!(bypasses_download_archive(Some(true), None));
+        assert!(bypasses_download_archive(None, Some("history-entry")));
+    }
+
+    #[test]
+    fn parse_archive_line_splits_extractor_and_id() {
+        assert_eq!(
+            parse_archive_line("youtube dQw4w9WgXcQ\n"),
+            Some(("youtube".to_string(), "dQw4w9WgXcQ".to_string()))
+        );
+        assert_eq!(
+            parse_archive_line("BiliBili BV1xx411c7mD_p2"),
+            Some(("bilibili".to_string(), "BV1xx411c7mD_p2".to_string()))
+        );
+        assert_eq!(parse_archive_line("garbage"), None);
+        assert_eq!(parse_archive_line(""), None);
+    }
+
+    #[test]
+    fn seed_skips_clips_and_unknown_urls() {
+        let _guard = db_test_guard();
+        ensure_test_archive_tables();
+
+        insert_history("a", "https://youtu.be/dQw4w9WgXcQ", None);
+        insert_history(
+            "b",
+            "https://www.youtube.com/watch?v=aaaaaaaaaaa",
+            Some("0:10-0:20"),
+        );
+        insert_history("c", "https://example.com/video/1", None);
+
+        assert_eq!(seed_download_archive_from_history_db().expect("seed"), 1);
+        assert!(is_download_archived_db("youtube", "dQw4w9WgXcQ").expect("check"));
+        assert!(!is_download_archived_db("youtube", "aaaaaaaaaaa").expect("check"));
+
+        // Seeding again is a no-op.
+        assert_eq!(seed_download_archive_from_history_db().expect("reseed"), 0);
+    }
+
+    #[test]
+    fn recorded_lines_round_trip_as_archive_file() {
+        let _guard = db_test_guard();
+        ensure_test_archive_tables();
+
+        let added = record_download_archive_lines_db(
+            "youtube dQw4w9WgXcQ\nvimeo 123456\nyoutube dQw4w9WgXcQ\n",
+            "https://www.youtube.com/playlist?list=PL123",
+        )
+        .expect("record lines");
+        assert_eq!(added, 2);
+        assert!(is_download_archived_db("YouTube", "dQw4w9WgXcQ").expect("check"));
+
+        let mut lines = get_download_archive_lines_db().expect("lines");
+        lines.sort();
+        assert_eq!(lines, vec!["vimeo 123456", "youtube dQw4w9WgXcQ"]);
+    }
+}
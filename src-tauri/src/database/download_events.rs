@@ -0,0 +1,436 @@
+use super::get_db;
+use crate::types::{
+    DailyDownloadStats, DownloadEvent, DownloadEventKind, DownloadStats, FailureCodeStats,
+    SiteDownloadStats,
+};
+use rusqlite::{params, Connection};
+
+/// Progress samples are only useful while a download is fresh
+const PROGRESS_EVENT_RETENTION_MS: i64 = 30 * 24 * 60 * 60 * 1000;
+const DOWNLOAD_EVENT_RETENTION_MS: i64 = 365 * 24 * 60 * 60 * 1000;
+
+/// Error codes listed by `get_download_stats_db` when no limit is given
+const DEFAULT_TOP_FAILURES: u32 = 10;
+
+const DOWNLOAD_EVENT_COLUMNS: &str =
+    "id, download_id, event, url, site, attempt, percent, bytes, speed_bps, duration_ms,
+     error_code, error_message, created_at";
+
+fn row_to_download_event(row: &rusqlite::Row) -> rusqlite::Result<DownloadEvent> {
+    let kind: String = row.get(2)?;
+    Ok(DownloadEvent {
+        id: row.get(0)?,
+        download_id: row.get(1)?,
+        kind: DownloadEventKind::parse(&kind).unwrap_or(DownloadEventKind::Progress),
+        url: row.get(3)?,
+        site: row.get(4)?,
+        attempt: row.get(5)?,
+        percent: row.get(6)?,
+        bytes: row
+            .get::<_, Option<i64>>(7)?
+            .map(|bytes| bytes.max(0) as u64),
+        speed_bps: row
+            .get::<_, Option<i64>>(8)?
+            .map(|speed| speed.max(0) as u64),
+        duration_ms: row.get(9)?,
+        error_code: row.get(10)?,
+        error_message: row.get(11)?,
+        created_at: row.get(12)?,
+    })
+}
+
+/// Add an entry to the download journal. The event's `id` is ignored.
+pub fn insert_download_event_db(event: &DownloadEvent) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT INTO download_events
+            (download_id, event, url, site, attempt, percent, bytes, speed_bps, duration_ms,
+             error_code, error_message, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            event.download_id,
+            event.kind.as_str(),
+            event.url,
+            event.site,
+            event.attempt,
+            event.percent,
+            event.bytes.map(|bytes| bytes as i64),
+            event.speed_bps.map(|speed| speed as i64),
+            event.duration_ms,
+            event.error_code,
+            event.error_message,
+            event.created_at
+        ],
+    )
+    .map_err(|e| format!("Failed to record download event: {}", e))?;
+    Ok(())
+}
+
+/// Journal of one download, oldest first
+pub fn get_download_events_db(download_id: &str) -> Result<Vec<DownloadEvent>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {DOWNLOAD_EVENT_COLUMNS} FROM download_events
+             WHERE download_id = ?1 ORDER BY created_at ASC, id ASC"
+        ))
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let events = stmt
+        .query_map(params![download_id], row_to_download_event)
+        .map_err(|e| format!("Failed to query download events: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(events)
+}
+
+/// Drop progress samples older than a month and all events older than a year, as of `now` (ms)
+pub fn prune_download_events_db(now: i64) -> Result<usize, String> {
+    let conn = get_db()?;
+    conn.execute(
+        "DELETE FROM download_events
+         WHERE (event = 'progress' AND created_at < ?1) OR created_at < ?2",
+        params![
+            now - PROGRESS_EVENT_RETENTION_MS,
+            now - DOWNLOAD_EVENT_RETENTION_MS
+        ],
+    )
+    .map_err(|e| format!("Failed to prune download events: {}", e))
+}
+
+/// Clear the whole journal
+pub fn clear_download_events_db() -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute("DELETE FROM download_events", [])
+        .map_err(|e| format!("Failed to clear download events: {}", e))?;
+    Ok(())
+}
+
+fn success_rate(completed: u64, failed: u64) -> Option<f64> {
+    let finished = completed + failed;
+    (finished > 0).then(|| completed as f64 / finished as f64)
+}
+
+fn average_speed(bytes: i64, duration_ms: i64) -> Option<f64> {
+    (bytes > 0 && duration_ms > 0).then(|| bytes as f64 / (duration_ms as f64 / 1000.0))
+}
+
+/// Aggregates of the journal for events at `since..until` (ms, either end open)
+pub fn get_download_stats_db(
+    since: Option<i64>,
+    until: Option<i64>,
+    top_failures: Option<u32>,
+) -> Result<DownloadStats, String> {
+    let conn = get_db()?;
+    let from = since.unwrap_or(0);
+    let to = until.unwrap_or(i64::MAX);
+
+    // Speeds only count completed attempts that reported both their size and duration.
+    let (started, completed, failed, cancelled, retried, total_bytes, speed_bytes, speed_ms) = conn
+        .query_row(
+            "SELECT
+                COALESCE(SUM(event = 'started'), 0),
+                COALESCE(SUM(event = 'completed'), 0),
+                COALESCE(SUM(event = 'failed'), 0),
+                COALESCE(SUM(event = 'cancelled'), 0),
+                COALESCE(SUM(event = 'retried'), 0),
+                COALESCE(SUM(CASE WHEN event = 'completed' THEN bytes END), 0),
+                COALESCE(SUM(CASE WHEN event = 'completed' AND duration_ms > 0 THEN bytes END), 0),
+                COALESCE(SUM(CASE WHEN event = 'completed' AND bytes > 0 THEN duration_ms END), 0)
+             FROM download_events
+             WHERE created_at >= ?1 AND created_at < ?2",
+            params![from, to],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)? as u64,
+                    row.get::<_, i64>(1)? as u64,
+                    row.get::<_, i64>(2)? as u64,
+                    row.get::<_, i64>(3)? as u64,
+                    row.get::<_, i64>(4)? as u64,
+                    row.get::<_, i64>(5)?,
+                    row.get::<_, i64>(6)?,
+                    row.get::<_, i64>(7)?,
+                ))
+            },
+        )
+        .map_err(|e| format!("Failed to count download events: {}", e))?;
+
+    Ok(DownloadStats {
+        since,
+        until,
+        started,
+        completed,
+        failed,
+        cancelled,
+        retried,
+        success_rate: success_rate(completed, failed),
+        total_bytes: total_bytes.max(0) as u64,
+        average_speed_bps: average_speed(speed_bytes, speed_ms),
+        daily: daily_stats(&conn, from, to)?,
+        sites: site_stats(&conn, from, to)?,
+        top_failures: failure_stats(
+            &conn,
+            from,
+            to,
+            top_failures.unwrap_or(DEFAULT_TOP_FAILURES),
+        )?,
+    })
+}
+
+fn daily_stats(conn: &Connection, from: i64, to: i64) -> Result<Vec<DailyDownloadStats>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT date(created_at / 1000, 'unixepoch', 'localtime') AS day,
+                COALESCE(SUM(CASE WHEN event = 'completed' THEN bytes END), 0),
+                SUM(event = 'completed'),
+                SUM(event = 'failed')
+             FROM download_events
+             WHERE event IN ('completed', 'failed') AND created_at >= ?1 AND created_at < ?2
+             GROUP BY day
+             ORDER BY day ASC",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let days = stmt
+        .query_map(params![from, to], |row| {
+            Ok(DailyDownloadStats {
+                day: row.get(0)?,
+                bytes: row.get::<_, i64>(1)?.max(0) as u64,
+                completed: row.get::<_, i64>(2)? as u64,
+                failed: row.get::<_, i64>(3)? as u64,
+            })
+        })
+        .map_err(|e| format!("Failed to query daily download stats: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(days)
+}
+
+fn site_stats(conn: &Connection, from: i64, to: i64) -> Result<Vec<SiteDownloadStats>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(site, 'unknown') AS site_key,
+                SUM(event = 'completed'),
+                SUM(event = 'failed'),
+                COALESCE(SUM(CASE WHEN event = 'completed' THEN bytes END), 0),
+                COALESCE(SUM(CASE WHEN event = 'completed' AND duration_ms > 0 THEN bytes END), 0),
+                COALESCE(SUM(CASE WHEN event = 'completed' AND bytes > 0 THEN duration_ms END), 0)
+             FROM download_events
+             WHERE event IN ('completed', 'failed') AND created_at >= ?1 AND created_at < ?2
+             GROUP BY site_key
+             ORDER BY COUNT(*) DESC, site_key ASC",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let sites = stmt
+        .query_map(params![from, to], |row| {
+            let completed = row.get::<_, i64>(1)? as u64;
+            let failed = row.get::<_, i64>(2)? as u64;
+            Ok(SiteDownloadStats {
+                site: row.get(0)?,
+                completed,
+                failed,
+                success_rate: success_rate(completed, failed),
+                bytes: row.get::<_, i64>(3)?.max(0) as u64,
+                average_speed_bps: average_speed(row.get(4)?, row.get(5)?),
+            })
+        })
+        .map_err(|e| format!("Failed to query site download stats: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(sites)
+}
+
+/// Error codes that ended attempts, final or retried, most frequent first
+fn failure_stats(
+    conn: &Connection,
+    from: i64,
+    to: i64,
+    limit: u32,
+) -> Result<Vec<FailureCodeStats>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(error_code, 'BACKEND_UNKNOWN') AS code, COUNT(*), MAX(created_at)
+             FROM download_events
+             WHERE event IN ('failed', 'retried') AND created_at >= ?1 AND created_at < ?2
+             GROUP BY code
+             ORDER BY COUNT(*) DESC, MAX(created_at) DESC
+             LIMIT ?3",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let codes: Vec<(String, u64, i64)> = stmt
+        .query_map(params![from, to, limit], |row| {
+            Ok((row.get(0)?, row.get::<_, i64>(1)? as u64, row.get(2)?))
+        })
+        .map_err(|e| format!("Failed to query download failures: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut sites_stmt = conn
+        .prepare(
+            "SELECT COALESCE(site, 'unknown') AS site_key FROM download_events
+             WHERE event IN ('failed', 'retried') AND created_at >= ?1 AND created_at < ?2
+                AND COALESCE(error_code, 'BACKEND_UNKNOWN') = ?3
+             GROUP BY site_key
+             ORDER BY COUNT(*) DESC, site_key ASC",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let mut message_stmt = conn
+        .prepare(
+            "SELECT error_message FROM download_events
+             WHERE event IN ('failed', 'retried') AND created_at >= ?1 AND created_at < ?2
+                AND COALESCE(error_code, 'BACKEND_UNKNOWN') = ?3
+             ORDER BY created_at DESC, id DESC
+             LIMIT 1",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let mut failures = Vec::with_capacity(codes.len());
+    for (error_code, count, last_seen_at) in codes {
+        let sites = sites_stmt
+            .query_map(params![from, to, error_code], |row| row.get(0))
+            .map_err(|e| format!("Failed to query failure sites: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+        let last_message = message_stmt
+            .query_row(params![from, to, error_code], |row| row.get(0))
+            .ok()
+            .flatten();
+        failures.push(FailureCodeStats {
+            error_code,
+            count,
+            sites,
+            last_message,
+            last_seen_at,
+        });
+    }
+    Ok(failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    fn ensure_test_download_events_table() {
+        if DB_CONNECTION.get().is_none() {
+            let conn = rusqlite::Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+
+        let conn = get_db().expect("get db");
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS download_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                download_id TEXT NOT NULL,
+                event TEXT NOT NULL,
+                url TEXT,
+                site TEXT,
+                attempt INTEGER,
+                percent REAL,
+                bytes INTEGER,
+                speed_bps INTEGER,
+                duration_ms INTEGER,
+                error_code TEXT,
+                error_message TEXT,
+                created_at INTEGER NOT NULL
+            );
+            DELETE FROM download_events;",
+        )
+        .expect("create download_events table");
+    }
+
+    fn event(id: &str, kind: DownloadEventKind, site: &str, created_at: i64) -> DownloadEvent {
+        DownloadEvent {
+            id: 0,
+            download_id: id.to_string(),
+            kind,
+            url: None,
+            site: Some(site.to_string()),
+            attempt: None,
+            percent: None,
+            bytes: None,
+            speed_bps: None,
+            duration_ms: None,
+            error_code: None,
+            error_message: None,
+            created_at,
+        }
+    }
+
+    #[test]
+    fn stats_aggregate_outcomes_by_site_and_error() {
+        let _guard = db_test_guard();
+        ensure_test_download_events_table();
+
+        let day = 24 * 60 * 60 * 1000;
+        let events = [
+            event("a", DownloadEventKind::Started, "youtube.com", day),
+            DownloadEvent {
+                bytes: Some(4_000_000),
+                duration_ms: Some(2_000),
+                ..event(
+                    "a",
+                    DownloadEventKind::Completed,
+                    "youtube.com",
+                    day + 2_000,
+                )
+            },
+            event("b", DownloadEventKind::Started, "youtube.com", day),
+            DownloadEvent {
+                error_code: Some("YT_RATE_LIMITED".to_string()),
+                error_message: Some("HTTP Error 429".to_string()),
+                ..event("b", DownloadEventKind::Retried, "youtube.com", day + 1_000)
+            },
+            DownloadEvent {
+                error_code: Some("YT_RATE_LIMITED".to_string()),
+                error_message: Some("Too many requests".to_string()),
+                ..event("b", DownloadEventKind::Failed, "youtube.com", day + 5_000)
+            },
+            event("c", DownloadEventKind::Started, "vimeo.com", 3 * day),
+            DownloadEvent {
+                bytes: Some(1_000_000),
+                duration_ms: Some(1_000),
+                ..event("c", DownloadEventKind::Completed, "vimeo.com", 3 * day)
+            },
+        ];
+        for event in &events {
+            insert_download_event_db(event).expect("insert event");
+        }
+
+        let stats = get_download_stats_db(None, None, None).expect("stats");
+        assert_eq!((stats.started, stats.completed, stats.failed), (3, 2, 1));
+        assert_eq!(stats.retried, 1);
+        assert_eq!(stats.total_bytes, 5_000_000);
+        assert_eq!(stats.success_rate, Some(2.0 / 3.0));
+        assert_eq!(stats.average_speed_bps, Some(5_000_000.0 / 3.0));
+        assert_eq!(stats.daily.len(), 2);
+
+        let youtube = &stats.sites[0];
+        assert_eq!(youtube.site, "youtube.com");
+        assert_eq!((youtube.completed, youtube.failed), (1, 1));
+        assert_eq!(youtube.success_rate, Some(0.5));
+        assert_eq!(youtube.average_speed_bps, Some(2_000_000.0));
+
+        assert_eq!(stats.top_failures.len(), 1);
+        let rate_limited = &stats.top_failures[0];
+        assert_eq!(rate_limited.error_code, "YT_RATE_LIMITED");
+        assert_eq!(rate_limited.count, 2);
+        assert_eq!(rate_limited.sites, vec!["youtube.com"]);
+        assert_eq!(
+            rate_limited.last_message.as_deref(),
+            Some("Too many requests")
+        );
+
+        // Only the last download falls in the period
+        let recent = get_download_stats_db(Some(2 * day), None, None).expect("recent stats");
+        assert_eq!((recent.completed, recent.failed), (1, 0));
+        assert!(recent.top_failures.is_empty());
+
+        assert_eq!(get_download_events_db("b").expect("journal").len(), 3);
+        assert_eq!(
+            prune_download_events_db(3 * day + DOWNLOAD_EVENT_RETENTION_MS).expect("prune"),
+            5
+        );
+        clear_download_events_db().expect("clear");
+        assert!(get_download_events_db("c").expect("journal").is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+use super::get_db;
+use crate::types::{DownloadProfile, DownloadProfileSettings};
+use chrono::Utc;
+use rusqlite::params;
+
+fn row_to_download_profile(row: &rusqlite::Row) -> rusqlite::Result<DownloadProfile> {
+    let settings_json: String = row.get(1)?;
+    Ok(DownloadProfile {
+        name: row.get(0)?,
+        settings: serde_json::from_str(&settings_json).unwrap_or_default(),
+        created_at: row.get(2)?,
+        updated_at: row.get(3)?,
+    })
+}
+
+/// All saved download profiles, sorted by name.
+pub fn get_download_profiles_db() -> Result<Vec<DownloadProfile>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT name, settings_json, created_at, updated_at FROM download_profiles
+             ORDER BY name COLLATE NOCASE",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let profiles = stmt
+        .query_map([], row_to_download_profile)
+        .map_err(|e| format!("Failed to query download profiles: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(profiles)
+}
+
+/// Look up a profile by name (case-insensitive).
+pub fn get_download_profile_db(name: &str) -> Result<Option<DownloadProfile>, String> {
+    let conn = get_db()?;
+    let result = conn.query_row(
+        "SELECT name, settings_json, created_at, updated_at FROM download_profiles
+         WHERE name = ?1 COLLATE NOCASE",
+        params![name.trim()],
+        row_to_download_profile,
+    );
+
+    match result {
+        Ok(profile) => Ok(Some(profile)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Failed to load download profile: {}", e)),
+    }
+}
+
+/// Create a profile, or replace the settings of the profile with the same name.
+pub fn save_download_profile_db(
+    name: &str,
+    settings: &DownloadProfileSettings,
+) -> Result<DownloadProfile, String> {
+    let settings_json = serde_json::to_string(settings)
+        .map_err(|e| format!("Failed to serialize download profile: {}", e))?;
+    let now = Utc::now().timestamp();
+
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT INTO download_profiles (name, settings_json, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?3)
+         ON CONFLICT(name) DO UPDATE SET
+            settings_json = excluded.settings_json,
+            updated_at = excluded.updated_at",
+        params![name, settings_json, now],
+    )
+    .map_err(|e| format!("Failed to save download profile: {}", e))?;
+    drop(conn);
+
+    get_download_profile_db(name)?
+        .ok_or_else(|| format!("Failed to load download profile: {}", name))
+}
+
+pub fn delete_download_profile_db(name: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "DELETE FROM download_profiles WHERE name = ?1 COLLATE NOCASE",
+        params![name.trim()],
+    )
+    .map_err(|e| format!("Failed to delete download profile: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    fn ensure_test_profile_table() {
+        if DB_CONNECTION.get().is_none() {
+            let conn = rusqlite::Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+
+        let conn = get_db().expect("get db");
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS download_profiles (
+                name TEXT PRIMARY KEY COLLATE NOCASE,
+                settings_json TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            DELETE FROM download_profiles;",
+        )
+        .expect("create download_profiles table");
+    }
+
+    #[test]
+    fn saving_same_name_replaces_settings() {
+        let _guard = db_test_guard();
+        ensure_test_profile_table();
+
+        let audio = DownloadProfileSettings {
+            quality: Some("audio".to_string()),
+            audio_bitrate: Some("320".to_string()),
+            ..Default::default()
+        };
+        save_download_profile_db("podcast", &audio).expect("save profile");
+        save_download_profile_db(
+            "archive",
+            &DownloadProfileSettings {
+                format: Some("mkv".to_string()),
+                ..Default::default()
+            },
+        )
+        .expect("save profile");
+
+        let updated = DownloadProfileSettings {
+            audio_bitrate: Some("128".to_string()),
+            ..audio
+        };
+        let saved = save_download_profile_db("Podcast", &updated).expect("update profile");
+        assert_eq!(saved.name, "podcast");
+        assert_eq!(saved.settings.audio_bitrate.as_deref(), Some("128"));
+
+        let names: Vec<String> = get_download_profiles_db()
+            .expect("list profiles")
+            .into_iter()
+            .map(|profile| profile.name)
+            .collect();
+        assert_eq!(names, vec!["archive", "podcast"]);
+    }
+
+    #[test]
+    fn lookup_and_delete_ignore_case() {
+        let _guard = db_test_guard();
+        ensure_test_profile_table();
+
+        save_download_profile_db("Music", &DownloadProfileSettings::default())
+            .expect("save profile");
+        assert!(get_download_profile_db(" music ")
+            .expect("lookup")
+            .is_some());
+
+        delete_download_profile_db("MUSIC").expect("delete profile");
+        assert!(get_download_profile_db("Music").expect("lookup").is_none());
+    }
+}
@@ -1,4 +1,7 @@
 use super::get_db;
+use crate::types::{
+    DownloadOptions, DownloadQueueItem, DownloadQueueStatus, DownloadSchedulerConfig,
+};
 use chrono::Utc;
 use rusqlite::params;
 
@@ -58,3 +61,584 @@ pub fn clear_download_queue_from_db(queue_kind: String) -> Result<(), String> {
 
     Ok(())
 }
+
+const DOWNLOAD_QUEUE_ITEM_COLUMNS: &str =
+    "id, queue_kind, url, title, site, origin, origin_ref, status, priority, attempts,
+     options_json, error_code, error_message, created_at, updated_at, started_at, finished_at,
+     partial_paths, next_attempt_at";
+
+fn row_to_download_queue_item(row: &rusqlite::Row) -> rusqlite::Result<DownloadQueueItem> {
+    let options_json: String = row.get(10)?;
+    Ok(DownloadQueueItem {
+        id: row.get(0)?,
+        queue_kind: row.get(1)?,
+        url: row.get(2)?,
+        title: row.get(3)?,
+        site: row.get(4)?,
+        origin: row.get(5)?,
+        origin_ref: row.get(6)?,
+        status: DownloadQueueStatus::parse(&row.get::<_, String>(7)?),
+        priority: row.get(8)?,
+        attempts: row.get(9)?,
+        options: serde_json::from_str(&options_json).unwrap_or_default(),
+        error_code: row.get(11)?,
+        error_message: row.get(12)?,
+        created_at: row.get(13)?,
+        updated_at: row.get(14)?,
+        started_at: row.get(15)?,
+        finished_at: row.get(16)?,
+        partial_paths: row
+            .get::<_, Option<String>>(17)?
+            .map(|paths| paths.lines().map(ToString::to_string).collect())
+            .unwrap_or_default(),
+        next_attempt_at: row.get(18)?,
+    })
+}
+
+/// Insert a new pending item into the backend download queue
+#[allow(clippy::too_many_arguments)]
+pub fn insert_download_queue_item_db(
+    id: Option<&str>,
+    queue_kind: &str,
+    url: &str,
+    title: Option<&str>,
+    site: &str,
+    origin: &str,
+    origin_ref: Option<&str>,
+    priority: i64,
+    options: &DownloadOptions,
+) -> Result<DownloadQueueItem, String> {
+    validate_queue_kind(queue_kind)?;
+
+    let options_json = serde_json::to_string(options)
+        .map_err(|e| format!("Failed to serialize download options: {}", e))?;
+    let id = id
+        .map(ToString::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let now = Utc::now().timestamp_millis();
+
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT INTO download_queue_items
+            (id, queue_kind, url, title, site, origin, origin_ref, status, priority, attempts,
+             options_json, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'pending', ?8, 0, ?9, ?10, ?10)",
+        params![
+            id,
+            queue_kind,
+            url,
+            title,
+            site,
+            origin,
+            origin_ref,
+            priority,
+            options_json,
+            now
+        ],
+    )
+    .map_err(|e| format!("Failed to enqueue download: {}", e))?;
+
+    conn.query_row(
+        &format!("SELECT {DOWNLOAD_QUEUE_ITEM_COLUMNS} FROM download_queue_items WHERE id = ?1"),
+        params![id],
+        row_to_download_queue_item,
+    )
+    .map_err(|e| format!("Failed to load queued download: {}", e))
+}
+
+/// Get queue items, optionally filtered by queue kind and status.
+/// Items are ordered the way the scheduler picks them: priority first, then FIFO.
+pub fn get_download_queue_items_db(
+    queue_kind: Option<String>,
+    status: Option<DownloadQueueStatus>,
+) -> Result<Vec<DownloadQueueItem>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {DOWNLOAD_QUEUE_ITEM_COLUMNS} FROM download_queue_items
+             WHERE (?1 IS NULL OR queue_kind = ?1) AND (?2 IS NULL OR status = ?2)
+             ORDER BY priority DESC, created_at ASC"
+        ))
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let items = stmt
+        .query_map(
+            params![queue_kind, status.map(|s| s.as_str())],
+            row_to_download_queue_item,
+        )
+        .map_err(|e| format!("Failed to query download queue: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read download queue: {}", e))?;
+
+    Ok(items)
+}
+
+/// Get a single queue item by id
+pub fn get_download_queue_item_db(id: &str) -> Result<Option<DownloadQueueItem>, String> {
+    let conn = get_db()?;
+    let result = conn.query_row(
+        &format!("SELECT {DOWNLOAD_QUEUE_ITEM_COLUMNS} FROM download_queue_items WHERE id = ?1"),
+        params![id],
+        row_to_download_queue_item,
+    );
+
+    match result {
+        Ok(item) => Ok(Some(item)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Failed to get queued download: {}", e)),
+    }
+}
+
+/// Claim a pending item for download. Returns false if it was no longer pending.
+pub fn mark_download_queue_item_started_db(id: &str) -> Result<bool, String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp_millis();
+    let rows = conn
+        .execute(
+            "UPDATE download_queue_items
+             SET status = 'downloading', attempts = attempts + 1, started_at = ?2,
+                 updated_at = ?2, finished_at = NULL, error_code = NULL, error_message = NULL,
+                 next_attempt_at = NULL
+             WHERE id = ?1 AND status = 'pending'",
+            params![id, now],
+        )
+        .map_err(|e| format!("Failed to start queued download: {}", e))?;
+    Ok(rows > 0)
+}
+
+/// Record the final state of a queue item
+pub fn finish_download_queue_item_db(
+    id: &str,
+    status: DownloadQueueStatus,
+    error_code: Option<&str>,
+    error_message: Option<&str>,
+) -> Result<(), String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp_millis();
+    conn.execute(
+        "UPDATE download_queue_items
+         SET status = ?2, error_code = ?3, error_message = ?4, updated_at = ?5, finished_at = ?5,
+             partial_paths = CASE WHEN ?2 = 'completed' THEN NULL ELSE partial_paths END
+         WHERE id = ?1",
+        params![id, status.as_str(), error_code, error_message, now],
+    )
+    .map_err(|e| format!("Failed to update queued download: {}", e))?;
+    Ok(())
+}
+
+/// Put a failed running item back into the queue to be retried at `next_attempt_at` (ms).
+/// The error stays on the row so the reason for the retry is visible.
+pub fn schedule_download_queue_retry_db(
+    id: &str,
+    error_code: &str,
+    error_message: &str,
+    next_attempt_at: i64,
+) -> Result<bool, String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp_millis();
+    let rows = conn
+        .execute(
+            "UPDATE download_queue_items
+             SET status = 'pending', error_code = ?2, error_message = ?3, next_attempt_at = ?4,
+                 updated_at = ?5
+             WHERE id = ?1 AND status = 'downloading'",
+            params![id, error_code, error_message, next_attempt_at, now],
+        )
+        .map_err(|e| format!("Failed to schedule download retry: {}", e))?;
+    Ok(rows > 0)
+}
+
+/// Remember a file yt-dlp started writing for a queued job.
+/// Ids that are not queue items (downloads started by the UI) are ignored.
+pub fn add_download_queue_item_partial_path_db(id: &str, path: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE download_queue_items
+         SET partial_paths = CASE
+                WHEN partial_paths IS NULL OR partial_paths = '' THEN ?2
+                WHEN instr(char(10) || partial_paths || char(10), char(10) || ?2 || char(10)) > 0
+                    THEN partial_paths
+                ELSE partial_paths || char(10) || ?2
+             END
+         WHERE id = ?1",
+        params![id, path],
+    )
+    .map_err(|e| format!("Failed to record partial download path: {}", e))?;
+    Ok(())
+}
+
+/// Pause a pending or downloading item. Returns false if it was in another state.
+pub fn pause_download_queue_item_db(id: &str) -> Result<bool, String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp_millis();
+    let rows = conn
+        .execute(
+            "UPDATE download_queue_items SET status = 'paused', updated_at = ?2
+             WHERE id = ?1 AND status IN ('pending', 'downloading')",
+            params![id, now],
+        )
+        .map_err(|e| format!("Failed to pause download: {}", e))?;
+    Ok(rows > 0)
+}
+
+/// Return a paused item to the queue. Returns false if it was not paused.
+pub fn resume_download_queue_item_db(id: &str) -> Result<bool, String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp_millis();
+    let rows = conn
+        .execute(
+            "UPDATE download_queue_items SET status = 'pending', updated_at = ?2
+             WHERE id = ?1 AND status = 'paused'",
+            params![id, now],
+        )
+        .map_err(|e| format!("Failed to resume download: {}", e))?;
+    Ok(rows > 0)
+}
+
+/// Put a finished item back into the pending state with a fresh retry budget
+pub fn requeue_download_queue_item_db(id: &str) -> Result<bool, String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp_millis();
+    let rows = conn
+        .execute(
+            "UPDATE download_queue_items
+             SET status = 'pending', error_code = NULL, error_message = NULL, attempts = 0,
+                 next_attempt_at = NULL, updated_at = ?2, started_at = NULL, finished_at = NULL
+             WHERE id = ?1 AND status != 'downloading'",
+            params![id, now],
+        )
+        .map_err(|e| format!("Failed to requeue download: {}", e))?;
+    Ok(rows > 0)
+}
+
+/// Return items left in the downloading state by a previous run to the pending state
+pub fn reset_interrupted_download_queue_items_db() -> Result<usize, String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp_millis();
+    conn.execute(
+        "UPDATE download_queue_items SET status = 'pending', updated_at = ?1
+         WHERE status = 'downloading'",
+        params![now],
+    )
+    .map_err(|e| format!("Failed to reset interrupted downloads: {}", e))
+}
+
+/// Delete a queue item
+pub fn delete_download_queue_item_db(id: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "DELETE FROM download_queue_items WHERE id = ?1",
+        params![id],
+    )
+    .map_err(|e| format!("Failed to delete queued download: {}", e))?;
+    Ok(())
+}
+
+/// Delete completed, failed and cancelled items, optionally for one queue kind
+pub fn clear_finished_download_queue_items_db(queue_kind: Option<String>) -> Result<usize, String> {
+    let conn = get_db()?;
+    conn.execute(
+        "DELETE FROM download_queue_items
+         WHERE status IN ('completed', 'failed', 'cancelled') AND (?1 IS NULL OR queue_kind = ?1)",
+        params![queue_kind],
+    )
+    .map_err(|e| format!("Failed to clear finished downloads: {}", e))
+}
+
+/// The scheduler config last synced from the frontend, if any
+pub fn load_download_scheduler_config_db() -> Result<Option<DownloadSchedulerConfig>, String> {
+    let conn = get_db()?;
+    let result = conn.query_row(
+        "SELECT config_json FROM download_scheduler_config WHERE id = 1",
+        [],
+        |row| row.get::<_, String>(0),
+    );
+
+    match result {
+        Ok(config_json) => serde_json::from_str(&config_json)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse download scheduler config: {}", e)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Failed to load download scheduler config: {}", e)),
+    }
+}
+
+pub fn save_download_scheduler_config_db(config: &DownloadSchedulerConfig) -> Result<(), String> {
+    let config_json = serde_json::to_string(config)
+        .map_err(|e| format!("Failed to serialize download scheduler config: {}", e))?;
+
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT INTO download_scheduler_config (id, config_json, updated_at)
+         VALUES (1, ?1, ?2)
+         ON CONFLICT(id) DO UPDATE SET
+            config_json = excluded.config_json,
+            updated_at = excluded.updated_at",
+        params![config_json, Utc::now().timestamp()],
+    )
+    .map_err(|e| format!("Failed to save download scheduler config: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    fn ensure_test_queue_table() {
+        if DB_CONNECTION.get().is_none() {
+            let conn = rusqlite::Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+
+        let conn = get_db().expect("get db");
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS download_queue_items (
+                id TEXT PRIMARY KEY,
+                queue_kind TEXT NOT NULL,
+                url TEXT NOT NULL,
+                title TEXT,
+                site TEXT NOT NULL,
+                origin TEXT NOT NULL DEFAULT 'app',
+                origin_ref TEXT,
+                status TEXT NOT NULL DEFAULT 'pending',
+                priority INTEGER NOT NULL DEFAULT 0,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                options_json TEXT NOT NULL,
+                partial_paths TEXT,
+                error_code TEXT,
+                error_message TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                started_at INTEGER,
+                finished_at INTEGER,
+                next_attempt_at INTEGER
+            );
+            DELETE FROM download_queue_items;",
+        )
+        .expect("create download queue items table");
+    }
+
+    fn enqueue(url: &str, priority: i64) -> DownloadQueueItem {
+        insert_download_queue_item_db(
+            None,
+            "universal",
+            url,
+            None,
+            "example.com",
+            "app",
+            None,
+            priority,
+            &DownloadOptions::default(),
+        )
+        .expect("enqueue item")
+    }
+
+    #[test]
+    fn queue_items_are_ordered_by_priority_then_insertion() {
+        let _guard = db_test_guard();
+        ensure_test_queue_table();
+
+        let low = enqueue("https://example.com/low", 0);
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let high = enqueue("https://example.com/high", 5);
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let low_later = enqueue("https://example.com/low-later", 0);
+
+        let ids: Vec<String> =
+            get_download_queue_items_db(None, Some(DownloadQueueStatus::Pending))
+                .expect("list items")
+                .into_iter()
+                .map(|item| item.id)
+                .collect();
+
+        assert_eq!(ids, vec![high.id, low.id, low_later.id]);
+    }
+
+    #[test]
+    fn queue_items_keep_the_id_they_were_given() {
+        let _guard = db_test_guard();
+        ensure_test_queue_table();
+
+        let item = insert_download_queue_item_db(
+            Some("page-item-1"),
+            "youtube",
+            "https://youtube.com/watch?v=abc",
+            Some("Video"),
+            "youtube.com",
+            "app",
+            None,
+            0,
+            &DownloadOptions::default(),
+        )
+        .expect("enqueue item");
+        assert_eq!(item.id, "page-item-1");
+        assert!(get_download_queue_item_db("page-item-1")
+            .expect("get item")
+            .is_some());
+    }
+
+    #[test]
+    fn claiming_an_item_is_exclusive_and_counts_attempts() {
+        let _guard = db_test_guard();
+        ensure_test_queue_table();
+
+        let item = enqueue("https://example.com/video", 0);
+        assert!(mark_download_queue_item_started_db(&item.id).expect("claim"));
+        assert!(!mark_download_queue_item_started_db(&item.id).expect("claim again"));
+
+        let stored = get_download_queue_item_db(&item.id)
+            .expect("get item")
+            .expect("item exists");
+        assert_eq!(stored.status, DownloadQueueStatus::Downloading);
+        assert_eq!(stored.attempts, 1);
+        assert!(stored.started_at.is_some());
+    }
+
+    #[test]
+    fn failed_items_wait_for_their_retry() {
+        let _guard = db_test_guard();
+        ensure_test_queue_table();
+
+        let item = enqueue("https://example.com/flaky", 0);
+        assert!(mark_download_queue_item_started_db(&item.id).expect("claim"));
+        assert!(
+            schedule_download_queue_retry_db(&item.id, "NETWORK_TIMEOUT", "Timed out", 42)
+                .expect("schedule retry")
+        );
+
+        let waiting = get_download_queue_item_db(&item.id)
+            .expect("get item")
+            .expect("item exists");
+        assert_eq!(waiting.status, DownloadQueueStatus::Pending);
+        assert_eq!(waiting.next_attempt_at, Some(42));
+        assert_eq!(waiting.error_code.as_deref(), Some("NETWORK_TIMEOUT"));
+        // A pending item is not running, so it cannot be scheduled again
+        assert!(
+            !schedule_download_queue_retry_db(&item.id, "NETWORK_TIMEOUT", "x", 43)
+                .expect("schedule pending")
+        );
+
+        assert!(mark_download_queue_item_started_db(&item.id).expect("claim retry"));
+        let retrying = get_download_queue_item_db(&item.id)
+            .expect("get item")
+            .expect("item exists");
+        assert_eq!(retrying.attempts, 2);
+        assert_eq!(retrying.next_attempt_at, None);
+        assert_eq!(retrying.error_code, None);
+    }
+
+    #[test]
+    fn interrupted_items_return_to_pending() {
+        let _guard = db_test_guard();
+        ensure_test_queue_table();
+
+        let item = enqueue("https://example.com/video", 0);
+        mark_download_queue_item_started_db(&item.id).expect("claim");
+
+        assert_eq!(
+            reset_interrupted_download_queue_items_db().expect("reset"),
+            1
+        );
+        let stored = get_download_queue_item_db(&item.id)
+            .expect("get item")
+            .expect("item exists");
+        assert_eq!(stored.status, DownloadQueueStatus::Pending);
+        assert_eq!(stored.attempts, 1);
+    }
+
+    #[test]
+    fn paused_items_keep_partial_paths_until_completed() {
+        let _guard = db_test_guard();
+        ensure_test_queue_table();
+
+        let item = enqueue("https://example.com/video", 0);
+        mark_download_queue_item_started_db(&item.id).expect("claim");
+        add_download_queue_item_partial_path_db(&item.id, "/tmp/video.f137.mp4").expect("path");
+        add_download_queue_item_partial_path_db(&item.id, "/tmp/video.f140.m4a").expect("path");
+        add_download_queue_item_partial_path_db(&item.id, "/tmp/video.f137.mp4").expect("path");
+
+        assert!(pause_download_queue_item_db(&item.id).expect("pause"));
+        assert_eq!(
+            reset_interrupted_download_queue_items_db().expect("reset"),
+            0
+        );
+        let paused = get_download_queue_item_db(&item.id)
+            .expect("get item")
+            .expect("item exists");
+        assert_eq!(paused.status, DownloadQueueStatus::Paused);
+        assert_eq!(
+            paused.partial_paths,
+            vec!["/tmp/video.f137.mp4", "/tmp/video.f140.m4a"]
+        );
+
+        assert!(resume_download_queue_item_db(&item.id).expect("resume"));
+        assert!(!resume_download_queue_item_db(&item.id).expect("resume again"));
+        mark_download_queue_item_started_db(&item.id).expect("claim again");
+        finish_download_queue_item_db(&item.id, DownloadQueueStatus::Completed, None, None)
+            .expect("finish");
+
+        let done = get_download_queue_item_db(&item.id)
+            .expect("get item")
+            .expect("item exists");
+        assert_eq!(done.attempts, 2);
+        assert!(done.partial_paths.is_empty());
+    }
+
+    #[test]
+    fn clearing_finished_items_keeps_pending_ones() {
+        let _guard = db_test_guard();
+        ensure_test_queue_table();
+
+        let done = enqueue("https://example.com/done", 0);
+        let pending = enqueue("https://example.com/pending", 0);
+        finish_download_queue_item_db(&done.id, DownloadQueueStatus::Completed, None, None)
+            .expect("finish item");
+
+        assert_eq!(
+            clear_finished_download_queue_items_db(None).expect("clear"),
+            1
+        );
+        let remaining: Vec<String> = get_download_queue_items_db(None, None)
+            .expect("list items")
+            .into_iter()
+            .map(|item| item.id)
+            .collect();
+        assert_eq!(remaining, vec![pending.id]);
+    }
+
+    #[test]
+    fn scheduler_config_round_trips() {
+        let _guard = db_test_guard();
+        ensure_test_queue_table();
+        get_db()
+            .expect("get db")
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS download_scheduler_config (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    config_json TEXT NOT NULL,
+                    updated_at INTEGER NOT NULL
+                );
+                DELETE FROM download_scheduler_config;",
+            )
+            .expect("create scheduler config table");
+
+        assert_eq!(load_download_scheduler_config_db().expect("load"), None);
+
+        let mut config = DownloadSchedulerConfig {
+            max_concurrent: 4,
+            default_output_path: Some("/downloads".to_string()),
+            ..Default::default()
+        };
+        save_download_scheduler_config_db(&config).expect("save");
+        config.max_per_site = 1;
+        save_download_scheduler_config_db(&config).expect("save again");
+
+        assert_eq!(
+            load_download_scheduler_config_db().expect("load"),
+            Some(config)
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use super::get_db;
+use crate::types::{DownloadOptions, DownloadSchedule, ScheduleRule};
+use chrono::Utc;
+use rusqlite::params;
+
+const DOWNLOAD_SCHEDULE_COLUMNS: &str =
+    "id, url, title, queue_kind, options_json, rule_json, enabled, next_run_at, last_run_at,
+     last_item_id, created_at, updated_at";
+
+fn row_to_download_schedule(row: &rusqlite::Row) -> rusqlite::Result<DownloadSchedule> {
+    let options_json: String = row.get(4)?;
+    let rule_json: String = row.get(5)?;
+    let rule = serde_json::from_str(&rule_json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(DownloadSchedule {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        title: row.get(2)?,
+        queue_kind: row.get(3)?,
+        options: serde_json::from_str(&options_json).unwrap_or_default(),
+        rule,
+        enabled: row.get(6)?,
+        next_run_at: row.get(7)?,
+        last_run_at: row.get(8)?,
+        last_item_id: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+    })
+}
+
+/// Save a new schedule that first runs at `next_run_at` (ms)
+pub fn insert_download_schedule_db(
+    url: &str,
+    title: Option<&str>,
+    queue_kind: Option<&str>,
+    options: &DownloadOptions,
+    rule: &ScheduleRule,
+    next_run_at: Option<i64>,
+) -> Result<DownloadSchedule, String> {
+    let options_json = serde_json::to_string(options)
+        .map_err(|e| format!("Failed to serialize download options: {}", e))?;
+    let rule_json = serde_json::to_string(rule)
+        .map_err(|e| format!("Failed to serialize schedule rule: {}", e))?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().timestamp_millis();
+
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT INTO download_schedules
+            (id, url, title, queue_kind, options_json, rule_json, enabled, next_run_at,
+             created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1, ?7, ?8, ?8)",
+        params![
+            id,
+            url,
+            title,
+            queue_kind,
+            options_json,
+            rule_json,
+            next_run_at,
+            now
+        ],
+    )
+    .map_err(|e| format!("Failed to save download schedule: {}", e))?;
+
+    conn.query_row(
+        &format!("SELECT {DOWNLOAD_SCHEDULE_COLUMNS} FROM download_schedules WHERE id = ?1"),
+        params![id],
+        row_to_download_schedule,
+    )
+    .map_err(|e| format!("Failed to load download schedule: {}", e))
+}
+
+/// All schedules, oldest first. Rows with a rule this version cannot read are skipped.
+pub fn get_download_schedules_db() -> Result<Vec<DownloadSchedule>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {DOWNLOAD_SCHEDULE_COLUMNS} FROM download_schedules ORDER BY created_at ASC"
+        ))
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let schedules = stmt
+        .query_map([], row_to_download_schedule)
+        .map_err(|e| format!("Failed to query download schedules: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(schedules)
+}
+
+/// Get a single schedule by id
+pub fn get_download_schedule_db(id: &str) -> Result<Option<DownloadSchedule>, String> {
+    let conn = get_db()?;
+    let result = conn.query_row(
+        &format!("SELECT {DOWNLOAD_SCHEDULE_COLUMNS} FROM download_schedules WHERE id = ?1"),
+        params![id],
+        row_to_download_schedule,
+    );
+
+    match result {
+        Ok(schedule) => Ok(Some(schedule)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Failed to get download schedule: {}", e)),
+    }
+}
+
+/// Record what a schedule did when it ran and when it needs looking at next.
+/// `queued_item_id` is set when the run added a download to the queue.
+pub fn record_download_schedule_run_db(
+    id: &str,
+    next_run_at: Option<i64>,
+    queued_item_id: Option<&str>,
+) -> Result<(), String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp_millis();
+    conn.execute(
+        "UPDATE download_schedules
+         SET next_run_at = ?2, updated_at = ?3,
+             last_run_at = CASE WHEN ?4 IS NULL THEN last_run_at ELSE ?3 END,
+             last_item_id = COALESCE(?4, last_item_id)
+         WHERE id = ?1",
+        params![id, next_run_at, now, queued_item_id],
+    )
+    .map_err(|e| format!("Failed to update download schedule: {}", e))?;
+    Ok(())
+}
+
+/// Turn a schedule on or off. Turning it on starts it over from `next_run_at`.
+pub fn set_download_schedule_enabled_db(
+    id: &str,
+    enabled: bool,
+    next_run_at: Option<i64>,
+) -> Result<bool, String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp_millis();
+    let rows = if enabled {
+        conn.execute(
+            "UPDATE download_schedules
+             SET enabled = 1, next_run_at = ?2, last_item_id = NULL, updated_at = ?3
+             WHERE id = ?1",
+            params![id, next_run_at, now],
+        )
+    } else {
+        conn.execute(
+            "UPDATE download_schedules SET enabled = 0, updated_at = ?2 WHERE id = ?1",
+            params![id, now],
+        )
+    }
+    .map_err(|e| format!("Failed to update download schedule: {}", e))?;
+    Ok(rows > 0)
+}
+
+/// Delete a schedule. Downloads it already queued stay in the queue.
+pub fn delete_download_schedule_db(id: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute("DELETE FROM download_schedules WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to delete download schedule: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    fn ensure_test_schedule_table() {
+        if DB_CONNECTION.get().is_none() {
+            let conn = rusqlite::Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+
+        let conn = get_db().expect("get db");
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS download_schedules (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL,
+                title TEXT,
+                queue_kind TEXT,
+                options_json TEXT NOT NULL,
+                rule_json TEXT NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                next_run_at INTEGER,
+                last_run_at INTEGER,
+                last_item_id TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            DELETE FROM download_schedules;",
+        )
+        .expect("create download_schedules table");
+    }
+
+    #[test]
+    fn runs_are_recorded_and_enabling_starts_over() {
+        let _guard = db_test_guard();
+        ensure_test_schedule_table();
+
+        let rule = ScheduleRule::Weekly {
+            weekdays: vec![0],
+            minute: 180,
+        };
+        let options = DownloadOptions {
+            download_playlist: true,
+            ..Default::default()
+        };
+        let schedule = insert_download_schedule_db(
+            "https://www.youtube.com/playlist?list=PL123",
+            None,
+            None,
+            &options,
+            &rule,
+            Some(1_000),
+        )
+        .expect("insert schedule");
+        assert!(schedule.enabled);
+        assert_eq!(schedule.rule, rule);
+        assert!(schedule.options.download_playlist);
+
+        record_download_schedule_run_db(&schedule.id, Some(2_000), Some("item-1"))
+            .expect("record run");
+        record_download_schedule_run_db(&schedule.id, Some(3_000), None).expect("record check");
+        let ran = get_download_schedule_db(&schedule.id)
+            .expect("load schedule")
+            .expect("schedule exists");
+        assert_eq!(ran.next_run_at, Some(3_000));
+        assert_eq!(ran.last_item_id.as_deref(), Some("item-1"));
+        assert!(ran.last_run_at.is_some());
+
+        set_download_schedule_enabled_db(&schedule.id, false, None).expect("disable");
+        set_download_schedule_enabled_db(&schedule.id, true, Some(4_000)).expect("enable");
+        let restarted = get_download_schedule_db(&schedule.id)
+            .expect("load schedule")
+            .expect("schedule exists");
+        assert!(restarted.enabled);
+        assert_eq!(restarted.next_run_at, Some(4_000));
+        assert!(restarted.last_item_id.is_none());
+
+        delete_download_schedule_db(&schedule.id).expect("delete");
+        assert!(get_download_schedules_db().expect("list").is_empty());
+    }
+}
@@ -2,8 +2,9 @@ use std::collections::HashMap;
 
 use super::get_db;
 use crate::types::{
-    HistoryAdvancedFilters, HistoryCollection, HistoryEntry, HistoryFilterMatchMode,
-    HistoryMediaType, HistorySearchScope, HistorySort, HistoryTag,
+    HistoryAdvancedFilters, HistoryCollection, HistoryEntry, HistoryFileRecord,
+    HistoryFilterMatchMode, HistoryMediaType, HistorySearchScope, HistorySort, HistoryTag,
+    ImportedHistoryEntry,
 };
 use chrono::Utc;
 use rusqlite::{params, params_from_iter, types::Value, Connection};
@@ -31,6 +32,10 @@ fn parse_history_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
         file_exists,
         summary: row.get(11)?,
         time_range: row.get(12)?,
+        parent_id: row.get(13)?,
+        chapter_index: row.get(14)?,
+        playlist_id: row.get(15)?,
+        playlist_index: row.get(16)?,
         tags: Vec::new(),
         collections: Vec::new(),
     })
@@ -490,7 +495,7 @@ pub fn add_history_internal(
     .map_err(|e| format!("Failed to add history: {}", e))?;
 
     conn.execute(
-        "DELETE FROM history WHERE id NOT IN (SELECT id FROM history ORDER BY downloaded_at DESC LIMIT ?1)",
+        "DELETE FROM history WHERE imported_at IS NULL AND id NOT IN (SELECT id FROM history WHERE imported_at IS NULL ORDER BY downloaded_at DESC LIMIT ?1)",
         params![max_entries],
     )
     .ok();
@@ -508,6 +513,150 @@ pub fn add_history_internal(
     Ok(id)
 }
 
+/// Whether a history entry already points at `filepath`
+pub fn history_has_filepath(filepath: &str) -> Result<bool, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM history WHERE filepath = ?1)",
+        params![filepath],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Failed to look up history filepath: {}", e))
+}
+
+/// Add the history entry of a file found by a library scan. Imported entries are dated with
+/// the file's modification time and are not removed by the history size limit.
+pub fn import_history_entry(entry: &ImportedHistoryEntry) -> Result<String, String> {
+    let conn = get_db()?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().timestamp();
+
+    conn.execute(
+        "INSERT INTO history (id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, imported_at, content_hash)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![
+            id,
+            entry.url,
+            entry.title,
+            entry.thumbnail,
+            entry.filepath,
+            entry.filesize,
+            entry.duration,
+            entry.quality,
+            entry.format,
+            entry.source,
+            entry.downloaded_at,
+            now,
+            entry.content_hash
+        ],
+    )
+    .map_err(|e| format!("Failed to import history entry: {}", e))?;
+
+    Ok(id)
+}
+
+/// The history entries that point at a file. Gallery entries point at a folder and are left out.
+pub fn get_history_file_records() -> Result<Vec<HistoryFileRecord>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, title, filepath, filesize, content_hash FROM history
+             WHERE filepath != '' AND COALESCE(format, '') != 'gallery'",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let records = stmt
+        .query_map([], |row| {
+            Ok(HistoryFileRecord {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                filepath: row.get(2)?,
+                filesize: row.get(3)?,
+                content_hash: row.get(4)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query history files: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read history files: {}", e))?;
+    Ok(records)
+}
+
+pub fn set_history_content_hash(id: &str, content_hash: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE history SET content_hash = ?1 WHERE id = ?2",
+        params![content_hash, id],
+    )
+    .map_err(|e| format!("Failed to update content hash: {}", e))?;
+    Ok(())
+}
+
+/// Point a history entry at the file its missing file was moved or renamed to
+pub fn relink_history_file(
+    id: &str,
+    filepath: &str,
+    filesize: u64,
+    content_hash: Option<&str>,
+) -> Result<(), String> {
+    let conn = get_db()?;
+    let rows = conn
+        .execute(
+            "UPDATE history SET filepath = ?1, filesize = ?2, content_hash = COALESCE(?3, content_hash) WHERE id = ?4",
+            params![filepath, filesize, content_hash, id],
+        )
+        .map_err(|e| format!("Failed to relink history file: {}", e))?;
+    if rows == 0 {
+        return Err("History entry not found".to_string());
+    }
+    Ok(())
+}
+
+/// Mark a history entry as one chapter part of the download recorded in `parent_id`
+pub fn link_history_part(id: &str, parent_id: &str, chapter_index: u32) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE history SET parent_id = ?1, chapter_index = ?2 WHERE id = ?3",
+        params![parent_id, chapter_index, id],
+    )
+    .map_err(|e| format!("Failed to link history part: {}", e))?;
+    Ok(())
+}
+
+/// Record the playlist a history entry was downloaded from and its position in it
+pub fn link_history_playlist_item(
+    id: &str,
+    playlist_id: Option<&str>,
+    playlist_index: u32,
+) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE history SET playlist_id = ?1, playlist_index = ?2 WHERE id = ?3",
+        params![playlist_id, playlist_index, id],
+    )
+    .map_err(|e| format!("Failed to link history playlist item: {}", e))?;
+    Ok(())
+}
+
+/// Remove the chapter parts recorded for a download, e.g. before it is downloaded again
+pub fn delete_history_parts(parent_id: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "DELETE FROM history_tags WHERE history_id IN (SELECT id FROM history WHERE parent_id = ?1)",
+        params![parent_id],
+    )
+    .map_err(|e| format!("Failed to delete history tags: {}", e))?;
+    conn.execute(
+        "DELETE FROM history_collections WHERE history_id IN (SELECT id FROM history WHERE parent_id = ?1)",
+        params![parent_id],
+    )
+    .map_err(|e| format!("Failed to delete history collections: {}", e))?;
+    conn.execute(
+        "DELETE FROM history WHERE parent_id = ?1",
+        params![parent_id],
+    )
+    .map_err(|e| format!("Failed to delete history parts: {}", e))?;
+    Ok(())
+}
+
 pub fn update_history_summary(id: String, summary: String) -> Result<(), String> {
     let conn = get_db()?;
     conn.execute(
@@ -529,23 +678,26 @@ pub fn update_history_download(
     let conn = get_db()?;
     let now = Utc::now().timestamp();
     conn.execute(
-        "UPDATE history SET filepath = ?1, filesize = ?2, quality = ?3, format = ?4, downloaded_at = ?5, time_range = ?6 WHERE id = ?7",
+        "UPDATE history SET filepath = ?1, filesize = ?2, quality = ?3, format = ?4, downloaded_at = ?5, time_range = ?6, content_hash = NULL WHERE id = ?7",
         params![filepath, filesize, quality, format, now, time_range, id],
     )
     .map_err(|e| format!("Failed to update history: {}", e))?;
     Ok(())
 }
 
+/// Point the history entry of a renamed file at its new path. A `content_hash` replaces the
+/// recorded one; `None` keeps it.
 pub fn update_history_filepath_and_title(
     old_filepath: String,
     new_filepath: String,
     new_title: String,
+    content_hash: Option<String>,
 ) -> Result<(), String> {
     let conn = get_db()?;
     let rows = conn
         .execute(
-            "UPDATE history SET filepath = ?1, title = ?2 WHERE filepath = ?3",
-            params![new_filepath, new_title, old_filepath],
+            "UPDATE history SET filepath = ?1, title = ?2, content_hash = COALESCE(?3, content_hash) WHERE filepath = ?4",
+            params![new_filepath, new_title, content_hash, old_filepath],
         )
         .map_err(|e| format!("Failed to update history filepath/title: {}", e))?;
     if rows == 0 {
@@ -558,12 +710,13 @@ pub fn update_history_filepath_and_title_by_id(
     id: String,
     new_filepath: String,
     new_title: String,
+    content_hash: Option<String>,
 ) -> Result<(), String> {
     let conn = get_db()?;
     let rows = conn
         .execute(
-            "UPDATE history SET filepath = ?1, title = ?2 WHERE id = ?3",
-            params![new_filepath, new_title, id],
+            "UPDATE history SET filepath = ?1, title = ?2, content_hash = COALESCE(?3, content_hash) WHERE id = ?4",
+            params![new_filepath, new_title, content_hash, id],
         )
         .map_err(|e| format!("Failed to update history filepath/title by id: {}", e))?;
     if rows == 0 {
@@ -618,14 +771,14 @@ pub fn get_history_from_db(
 
     let mut query = if fts_query.is_some() {
         String::from(
-            "SELECT h.id, h.url, h.title, h.thumbnail, h.filepath, h.filesize, h.duration, h.quality, h.format, h.source, h.downloaded_at, h.summary, h.time_range
+            "SELECT h.id, h.url, h.title, h.thumbnail, h.filepath, h.filesize, h.duration, h.quality, h.format, h.source, h.downloaded_at, h.summary, h.time_range, h.parent_id, h.chapter_index, h.playlist_id, h.playlist_index
              FROM history h
              JOIN history_search_fts ON history_search_fts.rowid = h.rowid
              WHERE history_search_fts MATCH ?",
         )
     } else {
         String::from(
-            "SELECT h.id, h.url, h.title, h.thumbnail, h.filepath, h.filesize, h.duration, h.quality, h.format, h.source, h.downloaded_at, h.summary, h.time_range
+            "SELECT h.id, h.url, h.title, h.thumbnail, h.filepath, h.filesize, h.duration, h.quality, h.format, h.source, h.downloaded_at, h.summary, h.time_range, h.parent_id, h.chapter_index, h.playlist_id, h.playlist_index
              FROM history h WHERE 1=1",
         )
     };
@@ -682,7 +835,7 @@ pub fn get_history_entries_by_ids_from_db(ids: Vec<String>) -> Result<Vec<Histor
     let conn = get_db()?;
     let placeholders = vec!["?"; ids.len()].join(", ");
     let query = format!(
-        "SELECT id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, summary, time_range
+        "SELECT id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, summary, time_range, parent_id, chapter_index, playlist_id, playlist_index
          FROM history
          WHERE id IN ({})",
         placeholders
@@ -1049,7 +1202,13 @@ mod tests {
                 source TEXT,
                 downloaded_at INTEGER NOT NULL,
                 summary TEXT,
-                time_range TEXT
+                time_range TEXT,
+                parent_id TEXT,
+                chapter_index INTEGER,
+                playlist_id TEXT,
+                playlist_index INTEGER,
+                imported_at INTEGER,
+                content_hash TEXT
             );
             CREATE TABLE IF NOT EXISTS tags (
                 id TEXT PRIMARY KEY,
@@ -1096,6 +1255,19 @@ mod tests {
             END;",
         )
         .expect("create tables");
+        // Other test modules may have created an older history table first
+        conn.execute("ALTER TABLE history ADD COLUMN parent_id TEXT", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN chapter_index INTEGER", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN playlist_id TEXT", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN playlist_index INTEGER", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN imported_at INTEGER", [])
+            .ok();
+        conn.execute("ALTER TABLE history ADD COLUMN content_hash TEXT", [])
+            .ok();
         conn.execute("DELETE FROM history_search_fts", [])
             .expect("clear history search");
         conn.execute("DELETE FROM history_tags", [])
@@ -1205,6 +1377,116 @@ mod tests {
         assert_eq!(favorites.item_count, Some(1));
     }
 
+    #[test]
+    fn imported_entries_keep_file_dates_and_are_found_by_path() {
+        let _guard = db_test_guard();
+        ensure_test_history_tables();
+        let filepath = make_temp_file("imported.mp4");
+        let filepath = filepath.to_string_lossy().to_string();
+        assert!(!history_has_filepath(&filepath).expect("look up before import"));
+
+        let id = import_history_entry(&ImportedHistoryEntry {
+            title: "Imported".to_string(),
+            filepath: filepath.clone(),
+            source: Some("other".to_string()),
+            downloaded_at: 1_600_000_000,
+            ..Default::default()
+        })
+        .expect("import entry");
+
+        assert!(history_has_filepath(&filepath).expect("look up after import"));
+        let entries = get_history_entries_by_ids_from_db(vec![id]).expect("get imported entry");
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].file_exists);
+        assert!(entries[0].downloaded_at.starts_with("2020-09-13"));
+    }
+
+    #[test]
+    fn relinked_entries_point_at_the_new_file() {
+        let _guard = db_test_guard();
+        ensure_test_history_tables();
+        let moved = make_temp_file("moved.mp4");
+        let moved = moved.to_string_lossy().to_string();
+        let id = import_history_entry(&ImportedHistoryEntry {
+            title: "Moved".to_string(),
+            filepath: "/gone/moved.mp4".to_string(),
+            filesize: Some(5),
+            downloaded_at: 1_600_000_000,
+            content_hash: Some("abc".to_string()),
+            ..Default::default()
+        })
+        .expect("import entry");
+
+        let record = get_history_file_records()
+            .expect("get history files")
+            .into_iter()
+            .find(|record| record.id == id)
+            .expect("record of the imported entry");
+        assert_eq!(record.content_hash.as_deref(), Some("abc"));
+
+        relink_history_file(&id, &moved, 5, None).expect("relink entry");
+        let entries = get_history_entries_by_ids_from_db(vec![id.clone()]).expect("get entry");
+        assert_eq!(entries[0].filepath, moved);
+        assert!(entries[0].file_exists);
+        let record = get_history_file_records()
+            .expect("get history files")
+            .into_iter()
+            .find(|record| record.id == id)
+            .expect("record of the relinked entry");
+        assert_eq!(record.content_hash.as_deref(), Some("abc"));
+        assert!(relink_history_file("missing-id", &moved, 5, None).is_err());
+    }
+
+    #[test]
+    fn chapter_parts_link_to_parent_and_are_replaced_on_redownload() {
+        let _guard = db_test_guard();
+        ensure_test_history_tables();
+        let parent_id = uuid::Uuid::new_v4().to_string();
+        let part_ids = [
+            uuid::Uuid::new_v4().to_string(),
+            uuid::Uuid::new_v4().to_string(),
+        ];
+        insert_history_row(&parent_id, "/tmp/lecture.mp4");
+        for (index, part_id) in part_ids.iter().enumerate() {
+            insert_history_row(part_id, &format!("/tmp/lecture/{:03}.mp4", index + 1));
+            link_history_part(part_id, &parent_id, index as u32 + 1).expect("link part");
+        }
+
+        let entries =
+            get_history_entries_by_ids_from_db(part_ids.to_vec()).expect("get history parts");
+        let second = entries
+            .iter()
+            .find(|entry| entry.id == part_ids[1])
+            .expect("second part");
+        assert_eq!(second.parent_id.as_deref(), Some(parent_id.as_str()));
+        assert_eq!(second.chapter_index, Some(2));
+
+        delete_history_parts(&parent_id).expect("delete parts");
+        let remaining = get_history_entries_by_ids_from_db(vec![
+            parent_id.clone(),
+            part_ids[0].clone(),
+            part_ids[1].clone(),
+        ])
+        .expect("get remaining history");
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, parent_id);
+        assert_eq!(remaining[0].parent_id, None);
+    }
+
+    #[test]
+    fn playlist_items_keep_their_playlist_and_position() {
+        let _guard = db_test_guard();
+        ensure_test_history_tables();
+        let item_id = uuid::Uuid::new_v4().to_string();
+        insert_history_row(&item_id, "/tmp/playlist/02 - Second.mp4");
+        link_history_playlist_item(&item_id, Some("PL123"), 2).expect("link playlist item");
+
+        let entries = get_history_entries_by_ids_from_db(vec![item_id]).expect("get history");
+        assert_eq!(entries[0].playlist_id.as_deref(), Some("PL123"));
+        assert_eq!(entries[0].playlist_index, Some(2));
+        assert_eq!(entries[0].parent_id, None);
+    }
+
     #[test]
     fn history_fts_search_can_scope_to_ai_summary() {
         let _guard = db_test_guard();
@@ -0,0 +1,222 @@
+use super::get_db;
+use crate::types::{DownloadOptions, LiveRecording, LiveRecordingStatus};
+use chrono::Utc;
+use rusqlite::params;
+
+const LIVE_RECORDING_COLUMNS: &str =
+    "id, url, title, options_json, status, checks, sessions, failures, release_at,
+     next_check_at, current_item_id, error_message, created_at, updated_at";
+
+fn row_to_live_recording(row: &rusqlite::Row) -> rusqlite::Result<LiveRecording> {
+    let options_json: String = row.get(3)?;
+    Ok(LiveRecording {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        title: row.get(2)?,
+        options: serde_json::from_str(&options_json).unwrap_or_default(),
+        status: LiveRecordingStatus::parse(&row.get::<_, String>(4)?),
+        checks: row.get(5)?,
+        sessions: row.get(6)?,
+        failures: row.get(7)?,
+        release_at: row.get(8)?,
+        next_check_at: row.get(9)?,
+        current_item_id: row.get(10)?,
+        error_message: row.get(11)?,
+        created_at: row.get(12)?,
+        updated_at: row.get(13)?,
+    })
+}
+
+/// Save a new recording, waiting for its first check at `next_check_at` (ms)
+pub fn insert_live_recording_db(
+    url: &str,
+    title: Option<&str>,
+    options: &DownloadOptions,
+    next_check_at: i64,
+) -> Result<LiveRecording, String> {
+    let options_json = serde_json::to_string(options)
+        .map_err(|e| format!("Failed to serialize download options: {}", e))?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().timestamp_millis();
+
+    let conn = get_db()?;
+    conn.execute(
+        "INSERT INTO live_recordings
+            (id, url, title, options_json, status, next_check_at, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, 'waiting', ?5, ?6, ?6)",
+        params![id, url, title, options_json, next_check_at, now],
+    )
+    .map_err(|e| format!("Failed to save live recording: {}", e))?;
+
+    conn.query_row(
+        &format!("SELECT {LIVE_RECORDING_COLUMNS} FROM live_recordings WHERE id = ?1"),
+        params![id],
+        row_to_live_recording,
+    )
+    .map_err(|e| format!("Failed to load live recording: {}", e))
+}
+
+/// All recordings, newest first
+pub fn get_live_recordings_db() -> Result<Vec<LiveRecording>, String> {
+    let conn = get_db()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {LIVE_RECORDING_COLUMNS} FROM live_recordings ORDER BY created_at DESC"
+        ))
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+    let recordings = stmt
+        .query_map([], row_to_live_recording)
+        .map_err(|e| format!("Failed to query live recordings: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(recordings)
+}
+
+/// Get a single recording by id
+pub fn get_live_recording_db(id: &str) -> Result<Option<LiveRecording>, String> {
+    let conn = get_db()?;
+    let result = conn.query_row(
+        &format!("SELECT {LIVE_RECORDING_COLUMNS} FROM live_recordings WHERE id = ?1"),
+        params![id],
+        row_to_live_recording,
+    );
+
+    match result {
+        Ok(recording) => Ok(Some(recording)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Failed to get live recording: {}", e)),
+    }
+}
+
+/// Store the recorder's progress on a recording. A recording cancelled meanwhile is
+/// left alone, so a poll finishing late can't bring it back.
+/// Returns whether the row was updated.
+pub fn save_live_recording_progress_db(recording: &LiveRecording) -> Result<bool, String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp_millis();
+    let rows = conn
+        .execute(
+            "UPDATE live_recordings
+             SET title = ?2, status = ?3, checks = ?4, sessions = ?5, failures = ?6,
+                 release_at = ?7, next_check_at = ?8, current_item_id = ?9,
+                 error_message = ?10, updated_at = ?11
+             WHERE id = ?1 AND status IN ('waiting', 'recording')",
+            params![
+                recording.id,
+                recording.title,
+                recording.status.as_str(),
+                recording.checks,
+                recording.sessions,
+                recording.failures,
+                recording.release_at,
+                recording.next_check_at,
+                recording.current_item_id,
+                recording.error_message,
+                now
+            ],
+        )
+        .map_err(|e| format!("Failed to update live recording: {}", e))?;
+    Ok(rows > 0)
+}
+
+/// Stop waiting for or recording a stream
+pub fn cancel_live_recording_db(id: &str) -> Result<bool, String> {
+    let conn = get_db()?;
+    let now = Utc::now().timestamp_millis();
+    let rows = conn
+        .execute(
+            "UPDATE live_recordings
+             SET status = 'cancelled', next_check_at = NULL, updated_at = ?2
+             WHERE id = ?1 AND status IN ('waiting', 'recording')",
+            params![id, now],
+        )
+        .map_err(|e| format!("Failed to cancel live recording: {}", e))?;
+    Ok(rows > 0)
+}
+
+/// Delete a recording. Files it already recorded stay in the history.
+pub fn delete_live_recording_db(id: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute("DELETE FROM live_recordings WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to delete live recording: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{db_test_guard, DB_CONNECTION};
+    use std::sync::Mutex;
+
+    fn ensure_test_live_recording_table() {
+        if DB_CONNECTION.get().is_none() {
+            let conn = rusqlite::Connection::open_in_memory().expect("open in-memory db");
+            let _ = DB_CONNECTION.set(Mutex::new(conn));
+        }
+
+        let conn = get_db().expect("get db");
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS live_recordings (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL,
+                title TEXT,
+                options_json TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'waiting',
+                checks INTEGER NOT NULL DEFAULT 0,
+                sessions INTEGER NOT NULL DEFAULT 0,
+                failures INTEGER NOT NULL DEFAULT 0,
+                release_at INTEGER,
+                next_check_at INTEGER,
+                current_item_id TEXT,
+                error_message TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            DELETE FROM live_recordings;",
+        )
+        .expect("create live_recordings table");
+    }
+
+    #[test]
+    fn cancelled_recordings_keep_their_state() {
+        let _guard = db_test_guard();
+        ensure_test_live_recording_table();
+
+        let options = DownloadOptions {
+            live_from_start: Some(true),
+            segment_minutes: Some(60),
+            ..Default::default()
+        };
+        let mut recording = insert_live_recording_db(
+            "https://www.youtube.com/watch?v=abc123def45",
+            None,
+            &options,
+            1_000,
+        )
+        .expect("insert recording");
+        assert_eq!(recording.status, LiveRecordingStatus::Waiting);
+        assert_eq!(recording.options.segment_minutes, Some(60));
+
+        recording.status = LiveRecordingStatus::Recording;
+        recording.sessions = 1;
+        recording.current_item_id = Some("item-1".to_string());
+        assert!(save_live_recording_progress_db(&recording).expect("save progress"));
+        let saved = get_live_recording_db(&recording.id)
+            .expect("load recording")
+            .expect("recording exists");
+        assert_eq!(saved.status, LiveRecordingStatus::Recording);
+        assert_eq!(saved.current_item_id.as_deref(), Some("item-1"));
+
+        assert!(cancel_live_recording_db(&recording.id).expect("cancel"));
+        recording.status = LiveRecordingStatus::Waiting;
+        assert!(!save_live_recording_progress_db(&recording).expect("late save"));
+        let cancelled = get_live_recording_db(&recording.id)
+            .expect("load recording")
+            .expect("recording exists");
+        assert_eq!(cancelled.status, LiveRecordingStatus::Cancelled);
+        assert!(cancelled.next_check_at.is_none());
+
+        delete_live_recording_db(&recording.id).expect("delete");
+        assert!(get_live_recordings_db().expect("list").is_empty());
+    }
+}
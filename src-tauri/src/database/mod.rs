@@ -1,11 +1,21 @@
 mod channels;
 mod connection;
+mod download_archive;
+mod download_events;
+mod download_profiles;
 mod download_queue;
+mod download_schedules;
 mod history;
+mod live_recordings;
 mod logs;
 
 pub use channels::*;
 pub use connection::*;
+pub use download_archive::*;
+pub use download_events::*;
+pub use download_profiles::*;
 pub use download_queue::*;
+pub use download_schedules::*;
 pub use history::*;
+pub use live_recordings::*;
 pub use logs::*;
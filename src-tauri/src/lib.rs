@@ -32,6 +32,9 @@ static TRAY_LANG: Mutex<String> = Mutex::new(String::new());
 /// Schedule status text for tray menu (empty = no schedule active)
 static TRAY_SCHEDULE_STATUS: Mutex<String> = Mutex::new(String::new());
 
+/// Backend download schedules shown in the tray menu: how many are waiting and the next run (ms)
+static TRAY_DOWNLOAD_SCHEDULES: Mutex<(usize, Option<i64>)> = Mutex::new((0, None));
+
 /// Download queue summary shown in the tray menu.
 static TRAY_DOWNLOAD_STATUS: Mutex<TrayDownloadStatus> = Mutex::new(TrayDownloadStatus {
     pending: 0,
@@ -111,6 +114,24 @@ fn update_tray_schedule(app: tauri::AppHandle, status: String) {
     rebuild_tray_menu(&app);
 }
 
+/// Update the backend download schedules shown in the tray menu
+pub fn update_tray_download_schedules(
+    app_handle: &tauri::AppHandle,
+    waiting: usize,
+    next_run_at: Option<i64>,
+) {
+    let mut should_rebuild = false;
+    if let Ok(mut stored) = TRAY_DOWNLOAD_SCHEDULES.lock() {
+        if *stored != (waiting, next_run_at) {
+            *stored = (waiting, next_run_at);
+            should_rebuild = true;
+        }
+    }
+    if should_rebuild {
+        rebuild_tray_menu(app_handle);
+    }
+}
+
 /// Tauri command: update the download queue summary shown in the tray menu
 #[tauri::command]
 fn update_tray_download_status(app: tauri::AppHandle, status: TrayDownloadStatus) {
@@ -143,12 +164,14 @@ pub fn run() {
             // CLI style invocation (youwee <url> [--quality ...]) while the app
             // is already running.
             if let Some(cli_request) = commands::build_cli_download_request_from_argv(&argv) {
-                let requests = vec![cli_request];
-                commands::enqueue_cli_download_requests(requests.clone());
-                let _ = app.emit(
-                    "external-cli-download",
-                    commands::ExternalCliDownloadEventPayload { requests },
-                );
+                let requests = commands::dispatch_cli_download_requests(app, vec![cli_request]);
+                if !requests.is_empty() {
+                    commands::enqueue_cli_download_requests(requests.clone());
+                    let _ = app.emit(
+                        "external-cli-download",
+                        commands::ExternalCliDownloadEventPayload { requests },
+                    );
+                }
                 if !has_links {
                     return;
                 }
@@ -263,13 +286,59 @@ pub fn run() {
                             cli_args.subtitle_embed = data.value.as_bool().unwrap_or(false);
                         }
                         if let Some(data) = matches.args.get("download-sections") {
-                            if let Some(value) = data.value.as_str() {
-                                cli_args.download_sections = Some(value.to_string());
+                            // A repeated flag comes as a list of values
+                            match &data.value {
+                                serde_json::Value::Array(values) => {
+                                    cli_args.download_sections.extend(
+                                        values.iter().filter_map(|v| v.as_str()).map(String::from),
+                                    )
+                                }
+                                value => cli_args
+                                    .download_sections
+                                    .extend(value.as_str().map(String::from)),
                             }
                         }
+                        if let Some(data) = matches.args.get("join-sections") {
+                            cli_args.join_sections = data.value.as_bool().unwrap_or(false);
+                        }
                         if let Some(data) = matches.args.get("live-from-start") {
                             cli_args.live_from_start = data.value.as_bool().unwrap_or(false);
                         }
+                        if let Some(data) = matches.args.get("profile") {
+                            if let Some(value) = data.value.as_str() {
+                                cli_args.profile = Some(value.to_string());
+                            }
+                        }
+                        for (name, value) in [
+                            ("gallery-range", &mut cli_args.gallery_range),
+                            ("gallery-filter", &mut cli_args.gallery_filter),
+                            ("gallery-filename", &mut cli_args.gallery_filename),
+                        ] {
+                            if let Some(data) = matches.args.get(name) {
+                                *value = data.value.as_str().map(String::from);
+                            }
+                        }
+                        if let Some(data) = matches.args.get("gallery-directory") {
+                            match &data.value {
+                                serde_json::Value::Array(values) => {
+                                    cli_args.gallery_directory.extend(
+                                        values.iter().filter_map(|v| v.as_str()).map(String::from),
+                                    )
+                                }
+                                value => cli_args
+                                    .gallery_directory
+                                    .extend(value.as_str().map(String::from)),
+                            }
+                        }
+                        if let Some(data) = matches.args.get("write-metadata") {
+                            cli_args.write_metadata = data.value.as_bool().unwrap_or(false);
+                        }
+                        if let Some(data) = matches.args.get("zip") {
+                            cli_args.zip = data.value.as_bool().unwrap_or(false);
+                        }
+                        if let Some(data) = matches.args.get("ugoira-conv") {
+                            cli_args.ugoira_conv = data.value.as_bool().unwrap_or(false);
+                        }
                         commands::build_cli_download_request(&cli_args)
                     }
                     Err(_) => commands::build_cli_download_request_from_argv(&argv),
@@ -285,6 +354,35 @@ pub fn run() {
                 log::error!("Failed to initialize database: {}", e);
             }
 
+            // Make sure videos downloaded before the archive existed are skipped too
+            if let Err(e) = database::seed_download_archive_from_history_db() {
+                log::warn!("Failed to seed download archive: {}", e);
+            }
+
+            // Keep the download journal from growing forever
+            if let Err(e) =
+                database::prune_download_events_db(chrono::Utc::now().timestamp_millis())
+            {
+                log::warn!("Failed to prune download events: {}", e);
+            }
+
+            // Start the backend download queue and hand it any cold-start CLI downloads
+            commands::start_download_scheduler(app.handle().clone());
+            let cli_requests = commands::dispatch_cli_download_requests(
+                app.handle(),
+                commands::take_pending_cli_download_requests(),
+            );
+            commands::enqueue_cli_download_requests(cli_requests);
+
+            // Start the timer that queues scheduled downloads
+            commands::start_download_schedules(app.handle().clone());
+
+            // Start polling upcoming streams waiting to be recorded
+            commands::start_live_recorder(app.handle().clone());
+
+            // Keep running downloads within the shared bandwidth budget
+            commands::start_bandwidth_balancer();
+
             // Start background channel polling
             services::polling::start_polling(app.handle().clone());
 
@@ -323,11 +421,15 @@ pub fn run() {
             // Download commands
             commands::download_video,
             commands::stop_download,
+            commands::pause_download,
+            commands::resume_download,
             commands::download_gallery,
             commands::stop_gallery_download,
             // Video info commands
             commands::get_video_basic_info,
             commands::get_video_info,
+            commands::preview_output_template,
+            commands::explain_format_preferences,
             commands::get_playlist_entries,
             commands::search_youtube_videos,
             commands::get_available_subtitles,
@@ -355,7 +457,14 @@ pub fn run() {
             commands::check_deno,
             commands::check_deno_update,
             commands::download_deno,
+            // gallery-dl commands
             commands::check_gallerydl,
+            commands::get_gallerydl_source_cmd,
+            commands::set_gallerydl_source_cmd,
+            commands::get_gallerydl_channel_cmd,
+            commands::set_gallerydl_channel_cmd,
+            commands::check_gallerydl_update,
+            commands::download_gallerydl,
             // Browser detection
             commands::detect_installed_browsers,
             commands::get_browser_profiles,
@@ -384,6 +493,10 @@ pub fn run() {
             commands::remove_history_from_collection,
             commands::open_file_location,
             commands::check_file_exists,
+            commands::scan_library_folder,
+            commands::cancel_library_scan,
+            commands::relink_library_files,
+            commands::relink_history_entry,
             // Asset scope & history helpers
             commands::allow_asset_file,
             commands::sync_asset_scope_paths,
@@ -409,6 +522,7 @@ pub fn run() {
             commands::generate_processing_command,
             commands::generate_quick_action_command,
             commands::execute_ffmpeg_command,
+            commands::execute_precise_cut,
             commands::cancel_ffmpeg,
             commands::get_processing_history,
             commands::save_processing_job,
@@ -476,6 +590,28 @@ pub fn run() {
             commands::load_download_queue,
             commands::save_download_queue,
             commands::clear_download_queue,
+            commands::enqueue_download,
+            commands::get_download_queue_items,
+            commands::remove_download_queue_item,
+            commands::cancel_download_queue_item,
+            commands::retry_download_queue_item,
+            commands::clear_finished_download_queue_items,
+            commands::set_download_scheduler_config,
+            commands::get_download_scheduler_config,
+            commands::list_download_profiles,
+            commands::save_download_profile,
+            commands::delete_download_profile,
+            commands::list_download_schedules,
+            commands::add_download_schedule,
+            commands::set_download_schedule_enabled,
+            commands::remove_download_schedule,
+            commands::list_live_recordings,
+            commands::add_live_recording,
+            commands::cancel_live_recording,
+            commands::remove_live_recording,
+            commands::get_download_stats,
+            commands::get_download_events,
+            commands::clear_download_events,
             // External deep-link commands
             commands::consume_pending_external_links,
             commands::consume_pending_cli_download_requests,
@@ -635,6 +771,8 @@ fn tray_text(key: &str) -> &'static str {
         ("vi", "errors") => "Lỗi",
         ("vi", "idle") => "Rảnh",
         ("vi", "remote_download") => "Tải từ xa",
+        ("vi", "scheduled_downloads") => "Tải theo lịch",
+        ("vi", "next_run") => "tiếp theo",
         ("vi", "running") => "Đang chạy",
         ("vi", "disabled") => "Đã tắt",
         ("vi", "check_all") => "Kiểm tra kênh theo dõi ngay",
@@ -654,6 +792,8 @@ fn tray_text(key: &str) -> &'static str {
         ("zh-CN", "errors") => "错误",
         ("zh-CN", "idle") => "空闲",
         ("zh-CN", "remote_download") => "远程下载",
+        ("zh-CN", "scheduled_downloads") => "计划下载",
+        ("zh-CN", "next_run") => "下次",
         ("zh-CN", "running") => "运行中",
         ("zh-CN", "disabled") => "已禁用",
         ("zh-CN", "check_all") => "立即检查已关注频道",
@@ -673,6 +813,8 @@ fn tray_text(key: &str) -> &'static str {
         ("fr", "errors") => "Erreurs",
         ("fr", "idle") => "Inactif",
         ("fr", "remote_download") => "Telechargement distant",
+        ("fr", "scheduled_downloads") => "Telechargements planifies",
+        ("fr", "next_run") => "prochain",
         ("fr", "running") => "En cours",
         ("fr", "disabled") => "Desactive",
         ("fr", "check_all") => "Verifier les chaines suivies maintenant",
@@ -692,6 +834,8 @@ fn tray_text(key: &str) -> &'static str {
         (_, "errors") => "Errors",
         (_, "idle") => "Idle",
         (_, "remote_download") => "Remote Download",
+        (_, "scheduled_downloads") => "Scheduled Downloads",
+        (_, "next_run") => "next",
         (_, "running") => "Running",
         (_, "disabled") => "Disabled",
         (_, "check_all") => "Check Followed Channels Now",
@@ -742,6 +886,51 @@ fn tray_remote_status_label() -> String {
     }
 }
 
+/// Tray lines for the schedule started from the download pages and for backend download schedules
+fn tray_schedule_items(
+    app_handle: &tauri::AppHandle,
+) -> Result<Vec<tauri::menu::MenuItem<tauri::Wry>>, Box<dyn std::error::Error>> {
+    use chrono::TimeZone;
+
+    let mut items = Vec::new();
+
+    let page_status = TRAY_SCHEDULE_STATUS
+        .lock()
+        .map(|status| status.clone())
+        .unwrap_or_default();
+    if !page_status.is_empty() {
+        items.push(
+            MenuItemBuilder::with_id("schedule_status", &page_status)
+                .enabled(false)
+                .build(app_handle)?,
+        );
+    }
+
+    let (waiting, next_run_at) = TRAY_DOWNLOAD_SCHEDULES
+        .lock()
+        .map(|schedules| *schedules)
+        .unwrap_or((0, None));
+    if waiting > 0 {
+        let mut label = format!("{} ({})", tray_text("scheduled_downloads"), waiting);
+        if let Some(next) =
+            next_run_at.and_then(|at| chrono::Local.timestamp_millis_opt(at).single())
+        {
+            label.push_str(&format!(
+                " - {} {}",
+                tray_text("next_run"),
+                next.format("%Y-%m-%d %H:%M")
+            ));
+        }
+        items.push(
+            MenuItemBuilder::with_id("download_schedules", &label)
+                .enabled(false)
+                .build(app_handle)?,
+        );
+    }
+
+    Ok(items)
+}
+
 fn rebuild_tray_menu_inner(
     app_handle: &tauri::AppHandle,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -829,6 +1018,8 @@ fn rebuild_tray_menu_inner(
     .enabled(false)
     .build(app_handle)?;
 
+    let schedule_items = tray_schedule_items(app_handle)?;
+
     // Build full menu
     let check_now =
         MenuItemBuilder::with_id("check_now", tray_text("check_all")).build(app_handle)?;
@@ -845,7 +1036,10 @@ fn rebuild_tray_menu_inner(
         .item(&show)
         .separator()
         .item(&download_submenu)
-        .item(&remote_status)
+        .item(&remote_status);
+    let menu = schedule_items
+        .iter()
+        .fold(menu, |menu, item| menu.item(item))
         .separator()
         .item(&built_submenu)
         .item(&check_now)
@@ -0,0 +1,270 @@
+//! Global bandwidth budget: the limit in force for the time of day, split evenly across
+//! running downloads. A download takes its share when it starts; the balancer in
+//! `commands::download` restarts downloads whose share has moved since.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use tokio::sync::Notify;
+
+use super::{get_scheduler_config, is_in_window, minute_of_day};
+use crate::types::{BandwidthConfig, DownloadPhase};
+use crate::utils::ProgressUpdate;
+
+/// Smallest share a download is throttled to, however many are running
+const MIN_SHARE_BYTES: u64 = 64 * 1024;
+/// A running download is only restarted when its share moves by more than this fraction
+const REBALANCE_TOLERANCE: f64 = 0.25;
+/// How long a download keeps its limit before it may be restarted with another one
+const SETTLE_MS: i64 = 15_000;
+
+struct BandwidthJob {
+    /// The download's own speed limit
+    own_limit: Option<u64>,
+    /// Limit the running process was started with
+    applied: Option<u64>,
+    /// Single videos resume where they stopped; playlists, live streams, clips and
+    /// post-processing would start over
+    restartable: bool,
+    started_at: i64,
+}
+
+/// Downloads counted in the budget, keyed by download id
+static BANDWIDTH_JOBS: LazyLock<Mutex<HashMap<String, BandwidthJob>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static BANDWIDTH_WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Budget in force at `minute` of the day: the first profile covering it, else the base limit.
+pub fn budget_at(config: &BandwidthConfig, minute: u32) -> Option<u64> {
+    config
+        .profiles
+        .iter()
+        .find(|profile| is_in_window(profile.start_minute, profile.end_minute, minute))
+        .map_or(config.limit, |profile| profile.limit)
+}
+
+/// Limit of one of `jobs` running downloads: an even share of the budget,
+/// capped by the download's own limit.
+pub fn job_limit(budget: Option<u64>, jobs: usize, own_limit: Option<u64>) -> Option<u64> {
+    let share = budget.map(|budget| (budget / jobs.max(1) as u64).max(MIN_SHARE_BYTES.min(budget)));
+    match (share, own_limit) {
+        (Some(share), Some(own)) => Some(share.min(own)),
+        (share, own) => share.or(own),
+    }
+}
+
+/// Bytes per second of a yt-dlp rate such as `500K`, `2.5M` or `1048576`
+pub fn parse_rate(rate: &str) -> Option<u64> {
+    let rate = rate.trim();
+    let (number, multiplier) = match rate.chars().last()?.to_ascii_uppercase() {
+        'K' => (&rate[..rate.len() - 1], 1u64 << 10),
+        'M' => (&rate[..rate.len() - 1], 1 << 20),
+        'G' => (&rate[..rate.len() - 1], 1 << 30),
+        'T' => (&rate[..rate.len() - 1], 1 << 40),
+        _ => (rate, 1),
+    };
+    let value: f64 = number.trim().parse().ok()?;
+    (value.is_finite() && value > 0.0).then(|| (value * multiplier as f64) as u64)
+}
+
+/// Whether a download running at `applied` is far enough from `target` to restart it
+fn share_moved(applied: Option<u64>, target: Option<u64>) -> bool {
+    match (applied, target) {
+        (Some(applied), Some(target)) => {
+            applied.abs_diff(target) as f64 > applied as f64 * REBALANCE_TOLERANCE
+        }
+        (applied, target) => applied != target,
+    }
+}
+
+/// Downloads to restart at `now` (ms) so they run at their share of `budget`.
+/// They count as restarted right away, so the next pass doesn't pick them again.
+fn rebalance_jobs(
+    jobs: &mut HashMap<String, BandwidthJob>,
+    budget: Option<u64>,
+    now: i64,
+) -> Vec<String> {
+    let count = jobs.len();
+    jobs.iter_mut()
+        .filter_map(|(id, job)| {
+            let target = job_limit(budget, count, job.own_limit);
+            if !job.restartable
+                || now - job.started_at < SETTLE_MS
+                || !share_moved(job.applied, target)
+            {
+                return None;
+            }
+            job.applied = target;
+            job.started_at = now;
+            Some(id.clone())
+        })
+        .collect()
+}
+
+fn current_budget() -> Option<u64> {
+    budget_at(
+        &get_scheduler_config().bandwidth,
+        minute_of_day(&chrono::Local::now()),
+    )
+}
+
+/// Limit a download (re)starting now runs with, counting it among the running downloads.
+pub fn take_bandwidth_share(id: &str, own_limit: Option<u64>, restartable: bool) -> Option<u64> {
+    let budget = current_budget();
+    let Ok(mut jobs) = BANDWIDTH_JOBS.lock() else {
+        return own_limit;
+    };
+    let is_new = !jobs.contains_key(id);
+    let applied = job_limit(budget, jobs.len() + usize::from(is_new), own_limit);
+    jobs.insert(
+        id.to_string(),
+        BandwidthJob {
+            own_limit,
+            applied,
+            restartable,
+            started_at: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+    drop(jobs);
+
+    // The others now get a smaller share
+    if is_new {
+        wake_bandwidth_balancer();
+    }
+    applied
+}
+
+/// Leave a download alone once it is past downloading (merging, converting, ...)
+/// or turns out to be a stream without a known size, such as a live stream.
+pub fn note_bandwidth_progress(id: &str, update: &ProgressUpdate) {
+    if update.phase == DownloadPhase::Downloading && update.downloaded_size.is_none() {
+        return;
+    }
+    if let Ok(mut jobs) = BANDWIDTH_JOBS.lock() {
+        if let Some(job) = jobs.get_mut(id) {
+            job.restartable = false;
+        }
+    }
+}
+
+/// Downloads whose share of the current budget has moved and that should be restarted.
+pub fn plan_bandwidth_rebalance() -> Vec<String> {
+    let budget = current_budget();
+    BANDWIDTH_JOBS
+        .lock()
+        .map(|mut jobs| rebalance_jobs(&mut jobs, budget, chrono::Utc::now().timestamp_millis()))
+        .unwrap_or_default()
+}
+
+/// Ask the balancer to look at the shares again (download started or finished, new config).
+pub fn wake_bandwidth_balancer() {
+    BANDWIDTH_WAKE.notify_one();
+}
+
+/// Wait until the balancer is woken up.
+pub async fn bandwidth_balancer_woken() {
+    BANDWIDTH_WAKE.notified().await;
+}
+
+/// Keeps a download counted in the budget across restarts, until dropped.
+pub struct BandwidthGuard {
+    id: String,
+}
+
+impl BandwidthGuard {
+    pub fn new(id: &str) -> Self {
+        Self { id: id.to_string() }
+    }
+}
+
+impl Drop for BandwidthGuard {
+    fn drop(&mut self) {
+        if let Ok(mut jobs) = BANDWIDTH_JOBS.lock() {
+            jobs.remove(&self.id);
+        }
+        // The others now get a bigger share
+        wake_bandwidth_balancer();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BandwidthProfile;
+
+    const MIB: u64 = 1 << 20;
+
+    #[test]
+    fn profiles_override_the_base_limit() {
+        let config = BandwidthConfig {
+            limit: Some(10 * MIB),
+            profiles: vec![
+                // Unlimited at night
+                BandwidthProfile {
+                    start_minute: 22 * 60,
+                    end_minute: 7 * 60,
+                    limit: None,
+                },
+                BandwidthProfile {
+                    start_minute: 9 * 60,
+                    end_minute: 17 * 60,
+                    limit: Some(2 * MIB),
+                },
+            ],
+        };
+        assert_eq!(budget_at(&config, 23 * 60), None);
+        assert_eq!(budget_at(&config, 3 * 60), None);
+        assert_eq!(budget_at(&config, 12 * 60), Some(2 * MIB));
+        assert_eq!(budget_at(&config, 18 * 60), Some(10 * MIB));
+    }
+
+    #[test]
+    fn budget_is_split_across_jobs() {
+        assert_eq!(job_limit(Some(6 * MIB), 3, None), Some(2 * MIB));
+        assert_eq!(job_limit(Some(6 * MIB), 3, Some(MIB)), Some(MIB));
+        assert_eq!(job_limit(None, 3, Some(MIB)), Some(MIB));
+        assert_eq!(job_limit(None, 3, None), None);
+        // Many jobs still get a usable share, but never more than the budget
+        assert_eq!(job_limit(Some(MIB), 100, None), Some(MIN_SHARE_BYTES));
+        assert_eq!(job_limit(Some(1000), 4, None), Some(1000));
+    }
+
+    #[test]
+    fn parses_ytdlp_rates() {
+        assert_eq!(parse_rate("10M"), Some(10 * MIB));
+        assert_eq!(parse_rate("500k"), Some(500 * 1024));
+        assert_eq!(parse_rate("1.5M"), Some(3 * MIB / 2));
+        assert_eq!(parse_rate("4096"), Some(4096));
+        assert_eq!(parse_rate(""), None);
+        assert_eq!(parse_rate("0M"), None);
+        assert_eq!(parse_rate("fast"), None);
+    }
+
+    #[test]
+    fn only_settled_jobs_with_moved_shares_restart() {
+        let job = |applied: Option<u64>, restartable: bool, started_at: i64| BandwidthJob {
+            own_limit: None,
+            applied,
+            restartable,
+            started_at,
+        };
+        let now = 1_000_000;
+        let mut jobs = HashMap::from([
+            // Started alone with the whole budget
+            ("a".to_string(), job(Some(4 * MIB), true, 0)),
+            // Started second, already at its share
+            ("b".to_string(), job(Some(2 * MIB), true, 0)),
+        ]);
+        assert_eq!(rebalance_jobs(&mut jobs, Some(4 * MIB), now), vec!["a"]);
+        assert_eq!(jobs["a"].applied, Some(2 * MIB));
+        // Picked once, it isn't picked again while it restarts
+        assert!(rebalance_jobs(&mut jobs, Some(4 * MIB), now + 1).is_empty());
+
+        let mut jobs = HashMap::from([
+            ("merging".to_string(), job(Some(MIB), false, 0)),
+            ("fresh".to_string(), job(Some(MIB), true, now - 1_000)),
+            ("close".to_string(), job(Some(MIB + MIB / 10), true, 0)),
+        ]);
+        assert!(rebalance_jobs(&mut jobs, Some(3 * MIB), now).is_empty());
+    }
+}
@@ -0,0 +1,335 @@
+//! Downloads of several time ranges: yt-dlp writes one clip per range, and the clips
+//! are either kept as separate files or joined into one afterwards. For precise cuts the
+//! whole video is downloaded and the clips are cut from it here instead.
+
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tokio::process::Command;
+
+use super::{get_ffmpeg_path, precise_cut, PreciseCutRange};
+use crate::utils::{ChapterPart, CommandExt};
+
+/// Added to the file name of each clip, so clips of the same video don't overwrite each other
+const CLIP_MARKER: &str = " - clip ";
+const CLIP_START_FIELD: &str = "%(section_start>%H-%M-%S)s";
+
+/// The ranges of a `download_sections` value, e.g. `*00:30-02:10,*05:00-06:00`.
+/// Each one is passed to yt-dlp with its own `--download-sections`.
+pub fn split_download_sections(sections: &str) -> Vec<String> {
+    sections
+        .split(',')
+        .map(str::trim)
+        .filter(|range| !range.is_empty() && *range != "*")
+        .map(str::to_string)
+        .collect()
+}
+
+/// The ranges as recorded in history and plugin payloads: `00:30-02:10, 05:00-06:00`
+pub fn time_range_label(sections: &str) -> Option<String> {
+    let ranges: Vec<String> = split_download_sections(sections)
+        .iter()
+        .map(|range| range.strip_prefix('*').unwrap_or(range).to_string())
+        .collect();
+    (!ranges.is_empty()).then(|| ranges.join(", "))
+}
+
+/// Seconds of a `*start-end` range, e.g. `*1:02:03-1:05:00`. `None` for open-ended ranges
+/// and anything that isn't a pair of time markers.
+pub fn parse_section_range(range: &str) -> Option<PreciseCutRange> {
+    let (start, end) = range.trim().trim_start_matches('*').split_once('-')?;
+    let seconds = |marker: &str| {
+        marker.split(':').try_fold(0.0, |total, part: &str| {
+            let part: f64 = part.trim().parse().ok()?;
+            (part.is_finite() && part >= 0.0).then_some(total * 60.0 + part)
+        })
+    };
+    let range = PreciseCutRange {
+        start: seconds(start)?,
+        end: seconds(end)?,
+    };
+    (range.end > range.start).then_some(range)
+}
+
+/// How the files of a download of time ranges are finished
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipPlan {
+    /// Join the clips into one file
+    pub join: bool,
+    /// Ranges cut from the whole downloaded video for precise cuts; empty when yt-dlp
+    /// downloads the clips itself
+    pub precise_ranges: Vec<PreciseCutRange>,
+}
+
+impl ClipPlan {
+    /// The plan for the `ranges` of a single video; `None` when the download is one file.
+    /// Precise cuts fall back to yt-dlp's own cutting when a range can't be parsed.
+    pub fn for_sections(ranges: &[String], join: bool, precise: bool) -> Option<Self> {
+        let precise_ranges = if precise {
+            ranges
+                .iter()
+                .map(|range| parse_section_range(range))
+                .collect::<Option<Vec<_>>>()
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        (!precise_ranges.is_empty() || ranges.len() > 1).then(|| Self {
+            join: join && ranges.len() > 1,
+            precise_ranges,
+        })
+    }
+
+    /// Whether the whole video is downloaded and cut afterwards
+    pub fn cuts_locally(&self) -> bool {
+        !self.precise_ranges.is_empty()
+    }
+}
+
+/// Output template of a download of several ranges: each clip is named after where it starts.
+pub fn clip_output_template(template: &str) -> String {
+    match template.strip_suffix(".%(ext)s") {
+        Some(stem) => format!("{}{}{}.%(ext)s", stem, CLIP_MARKER, CLIP_START_FIELD),
+        None => format!("{}{}{}", template, CLIP_MARKER, CLIP_START_FIELD),
+    }
+}
+
+/// Path of a clip cut from `video`, named like the clips yt-dlp writes
+fn precise_clip_path(video: &str, start: f64) -> Option<PathBuf> {
+    let path = Path::new(video);
+    let stem = path.file_stem()?.to_str()?;
+    let ext = path.extension()?.to_str()?;
+    let start = start as u64;
+    Some(path.with_file_name(format!(
+        "{}{}{:02}-{:02}-{:02}.{}",
+        stem,
+        CLIP_MARKER,
+        start / 3600,
+        start / 60 % 60,
+        start % 60,
+        ext
+    )))
+}
+
+/// Path of the file the clips are joined into: the first clip's name without the clip suffix
+pub fn joined_clip_path(clip: &str) -> Option<PathBuf> {
+    let path = Path::new(clip);
+    let (name, _) = path.file_stem()?.to_str()?.rsplit_once(CLIP_MARKER)?;
+    let ext = path.extension()?.to_str()?;
+    Some(path.with_file_name(format!("{}.{}", name, ext)))
+}
+
+/// List of files for ffmpeg's concat demuxer
+pub fn concat_list(clips: &[String]) -> String {
+    let mut list = String::from("ffconcat version 1.0\n");
+    for clip in clips {
+        list.push_str(&format!("file '{}'\n", clip.replace('\'', "'\\''")));
+    }
+    list
+}
+
+/// ffmpeg arguments joining the clips listed in `list` into `output` without re-encoding
+pub fn concat_args(list: &str, output: &str) -> Vec<String> {
+    [
+        "-hide_banner",
+        "-loglevel",
+        "error",
+        "-y",
+        "-f",
+        "concat",
+        "-safe",
+        "0",
+        "-i",
+        list,
+        // Cover art is left out; it would be joined like a video stream
+        "-map",
+        "0:V?",
+        "-map",
+        "0:a?",
+        "-c",
+        "copy",
+        output,
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect()
+}
+
+/// Join `clips` into one file next to them and delete the clips. Returns the joined file.
+async fn join_clips(app: &AppHandle, id: &str, clips: &[String]) -> Result<String, String> {
+    let output = joined_clip_path(&clips[0])
+        .ok_or_else(|| format!("Unexpected clip file name: {}", clips[0]))?
+        .to_string_lossy()
+        .to_string();
+    let ffmpeg_path = get_ffmpeg_path(app)
+        .await
+        .ok_or_else(|| "FFmpeg not found".to_string())?;
+
+    let list_path = std::env::temp_dir().join(format!("youwee-clips-{}.txt", id));
+    std::fs::write(&list_path, concat_list(clips))
+        .map_err(|e| format!("Failed to write the clip list: {}", e))?;
+    let mut cmd = Command::new(&ffmpeg_path);
+    cmd.args(concat_args(&list_path.to_string_lossy(), &output));
+    cmd.hide_window();
+    let result = cmd.output().await;
+    std::fs::remove_file(&list_path).ok();
+
+    match result {
+        Ok(result) if result.status.success() => {
+            for clip in clips {
+                std::fs::remove_file(clip).ok();
+            }
+            Ok(output)
+        }
+        Ok(result) => {
+            std::fs::remove_file(&output).ok();
+            Err(String::from_utf8_lossy(&result.stderr).trim().to_string())
+        }
+        Err(e) => Err(format!("Failed to run ffmpeg: {}", e)),
+    }
+}
+
+/// Cut `ranges` out of `video` frame-accurately and delete it. On failure the clips cut
+/// so far are removed and the video is kept.
+async fn cut_clips(
+    app: &AppHandle,
+    video: &str,
+    ranges: &[PreciseCutRange],
+) -> Result<Vec<String>, String> {
+    let mut clips = Vec::new();
+    for range in ranges {
+        let clip = precise_clip_path(video, range.start)
+            .ok_or_else(|| format!("Unexpected file name: {}", video))?
+            .to_string_lossy()
+            .to_string();
+        if let Err(e) = precise_cut(app, video, *range, &clip, |_| {}).await {
+            std::fs::remove_file(&clip).ok();
+            for clip in &clips {
+                std::fs::remove_file(clip).ok();
+            }
+            return Err(e);
+        }
+        clips.push(clip);
+    }
+    std::fs::remove_file(video).ok();
+    Ok(clips)
+}
+
+/// Files of a download of time ranges, from the paths yt-dlp printed: the joined file
+/// when the plan joins them, otherwise the first clip with the other clips as its parts.
+/// Clips that fail to join are kept as separate files, and a video that fails to cut
+/// is kept whole.
+pub async fn finish_clips(
+    app: &AppHandle,
+    id: &str,
+    printed: &str,
+    plan: &ClipPlan,
+) -> (Option<String>, Vec<ChapterPart>) {
+    let mut clips: Vec<String> = printed
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    if plan.cuts_locally() {
+        let Some(video) = clips.first().cloned() else {
+            return (None, Vec::new());
+        };
+        match cut_clips(app, &video, &plan.precise_ranges).await {
+            Ok(cut) => clips = cut,
+            Err(e) => {
+                log::warn!("Failed to cut clips of {}: {}", id, e);
+                return (Some(video), Vec::new());
+            }
+        }
+    }
+    if clips.len() > 1 && plan.join {
+        match join_clips(app, id, &clips).await {
+            Ok(joined) => return (Some(joined), Vec::new()),
+            Err(e) => log::warn!("Failed to join clips of {}: {}", id, e),
+        }
+    }
+
+    let parts = clips
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(index, clip)| ChapterPart {
+            index: index as u32 + 1,
+            filepath: clip.clone(),
+        })
+        .collect();
+    (clips.into_iter().next(), parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_and_labels_ranges() {
+        assert_eq!(
+            split_download_sections("*00:30-02:10, *05:00-06:00,,"),
+            vec!["*00:30-02:10", "*05:00-06:00"]
+        );
+        assert_eq!(
+            time_range_label("*00:30-02:10,*05:00-06:00").as_deref(),
+            Some("00:30-02:10, 05:00-06:00")
+        );
+        assert_eq!(time_range_label("*1:00-2:00").as_deref(), Some("1:00-2:00"));
+        assert_eq!(time_range_label(" * "), None);
+    }
+
+    #[test]
+    fn clips_are_named_after_their_start_and_joined_without_it() {
+        assert_eq!(
+            clip_output_template("%(uploader)s/%(title)s.%(ext)s"),
+            "%(uploader)s/%(title)s - clip %(section_start>%H-%M-%S)s.%(ext)s"
+        );
+        assert_eq!(
+            joined_clip_path("/videos/Talk - part 1 - clip 00-05-00.mp4"),
+            Some(PathBuf::from("/videos/Talk - part 1.mp4"))
+        );
+        assert_eq!(joined_clip_path("/videos/Talk.mp4"), None);
+    }
+
+    #[test]
+    fn precise_plans_parse_ranges_and_name_clips() {
+        assert_eq!(
+            parse_section_range("*1:02:03-1:05:00.5"),
+            Some(PreciseCutRange {
+                start: 3723.0,
+                end: 3900.5
+            })
+        );
+        assert_eq!(parse_section_range("*05:00-inf"), None);
+        assert_eq!(parse_section_range("*02:00-01:00"), None);
+
+        let ranges = vec!["*00:30-02:10".to_string(), "*05:00-06:00".to_string()];
+        assert_eq!(ClipPlan::for_sections(&ranges[..1], true, false), None);
+        let plan = ClipPlan::for_sections(&ranges, true, true).unwrap();
+        assert!(plan.join && plan.cuts_locally());
+        assert_eq!(plan.precise_ranges[1].start, 300.0);
+        let single = ClipPlan::for_sections(&ranges[..1], true, true).unwrap();
+        assert!(!single.join && single.cuts_locally());
+        let unparsed = vec!["*00:30-02:10".to_string(), "*05:00-inf".to_string()];
+        assert!(!ClipPlan::for_sections(&unparsed, false, true)
+            .unwrap()
+            .cuts_locally());
+
+        assert_eq!(
+            precise_clip_path("/videos/Talk.mp4", 3723.4),
+            Some(PathBuf::from("/videos/Talk - clip 01-02-03.mp4"))
+        );
+    }
+
+    #[test]
+    fn concat_list_quotes_paths() {
+        assert_eq!(
+            concat_list(&["/v/a.mp4".to_string(), "/v/it's.mp4".to_string()]),
+            "ffconcat version 1.0\nfile '/v/a.mp4'\nfile '/v/it'\\''s.mp4'\n"
+        );
+        let args = concat_args("/tmp/list.txt", "/v/out.mp4");
+        assert_eq!(args.last().map(String::as_str), Some("/v/out.mp4"));
+        assert!(args.windows(2).any(|pair| pair == ["-c", "copy"]));
+    }
+}
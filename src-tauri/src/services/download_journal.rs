@@ -0,0 +1,201 @@
+//! Download journal: records when downloads are queued, start, make progress and finish
+//! in the `download_events` table, which the statistics commands aggregate.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use super::{backend_error_wire, is_retryable_error, site_key};
+use crate::database::insert_download_event_db;
+use crate::types::{code, DownloadAttempt, DownloadEvent, DownloadEventKind, DownloadPhase};
+use crate::utils::ProgressUpdate;
+
+/// Shortest time between two progress samples of a download
+const PROGRESS_SAMPLE_INTERVAL_MS: i64 = 15_000;
+
+struct JournaledDownload {
+    url: String,
+    site: String,
+    attempt: Option<u32>,
+    started_at: i64,
+    last_sample_at: i64,
+    /// Bytes of the streams already downloaded (video and audio are separate streams)
+    finished_bytes: u64,
+    /// Bytes of the stream being downloaded
+    current_bytes: u64,
+}
+
+impl JournaledDownload {
+    fn total_bytes(&self) -> u64 {
+        self.finished_bytes + self.current_bytes
+    }
+}
+
+/// Attempts running, keyed by download id
+static JOURNALED_DOWNLOADS: LazyLock<Mutex<HashMap<String, JournaledDownload>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn new_event(id: &str, kind: DownloadEventKind, url: &str, now: i64) -> DownloadEvent {
+    DownloadEvent {
+        id: 0,
+        download_id: id.to_string(),
+        kind,
+        url: Some(url.to_string()),
+        site: Some(site_key(url)),
+        attempt: None,
+        percent: None,
+        bytes: None,
+        speed_bps: None,
+        duration_ms: None,
+        error_code: None,
+        error_message: None,
+        created_at: now,
+    }
+}
+
+/// The journal never gets in the way of a download.
+fn record(event: DownloadEvent) {
+    if let Err(e) = insert_download_event_db(&event) {
+        log::warn!("Failed to journal download {}: {}", event.download_id, e);
+    }
+}
+
+/// A download was added to the backend queue.
+pub fn journal_download_queued(id: &str, url: &str) {
+    let now = chrono::Utc::now().timestamp_millis();
+    record(new_event(id, DownloadEventKind::Queued, url, now));
+}
+
+/// An attempt of a download started.
+pub fn journal_download_started(id: &str, url: &str, attempt: Option<DownloadAttempt>) {
+    let now = chrono::Utc::now().timestamp_millis();
+    let attempt = attempt.map(|a| a.attempt);
+    if let Ok(mut downloads) = JOURNALED_DOWNLOADS.lock() {
+        downloads.insert(
+            id.to_string(),
+            JournaledDownload {
+                url: url.to_string(),
+                site: site_key(url),
+                attempt,
+                started_at: now,
+                last_sample_at: now,
+                finished_bytes: 0,
+                current_bytes: 0,
+            },
+        );
+    }
+    record(DownloadEvent {
+        attempt,
+        ..new_event(id, DownloadEventKind::Started, url, now)
+    });
+}
+
+/// Count the bytes of a running download and sample its speed now and then.
+pub fn journal_download_progress(id: &str, update: &ProgressUpdate) {
+    if update.phase != DownloadPhase::Downloading {
+        return;
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    let sample = {
+        let Ok(mut downloads) = JOURNALED_DOWNLOADS.lock() else {
+            return;
+        };
+        let Some(download) = downloads.get_mut(id) else {
+            return;
+        };
+        if let Some(bytes) = update.downloaded_bytes {
+            // Fewer bytes than before: yt-dlp moved on to the next stream or playlist entry
+            if bytes < download.current_bytes {
+                download.finished_bytes += download.current_bytes;
+            }
+            download.current_bytes = bytes;
+        }
+        if now - download.last_sample_at < PROGRESS_SAMPLE_INTERVAL_MS {
+            return;
+        }
+        download.last_sample_at = now;
+        DownloadEvent {
+            site: Some(download.site.clone()),
+            attempt: download.attempt,
+            percent: Some(update.percent),
+            bytes: Some(download.total_bytes()),
+            speed_bps: update.speed_bytes,
+            duration_ms: Some(now - download.started_at),
+            ..new_event(id, DownloadEventKind::Progress, &download.url, now)
+        }
+    };
+    record(sample);
+}
+
+/// Which event ends an attempt that returned `result`
+fn finish_kind(result: &Result<(), String>, attempt: Option<DownloadAttempt>) -> DownloadEventKind {
+    let Err(raw) = result else {
+        return DownloadEventKind::Completed;
+    };
+    let error = backend_error_wire(raw);
+    match error.code.as_str() {
+        code::DOWNLOAD_CANCELLED => DownloadEventKind::Cancelled,
+        code::DOWNLOAD_ALREADY_ARCHIVED | code::YT_SKIPPED_LIVE => DownloadEventKind::Skipped,
+        _ if attempt.is_some_and(|a| a.attempt < a.max_attempts) && is_retryable_error(&error) => {
+            DownloadEventKind::Retried
+        }
+        _ => DownloadEventKind::Failed,
+    }
+}
+
+/// An attempt of a download ended with `result`.
+pub fn journal_download_finished(
+    id: &str,
+    url: &str,
+    attempt: Option<DownloadAttempt>,
+    result: &Result<(), String>,
+) {
+    let now = chrono::Utc::now().timestamp_millis();
+    let download = JOURNALED_DOWNLOADS
+        .lock()
+        .ok()
+        .and_then(|mut downloads| downloads.remove(id));
+    let kind = finish_kind(result, attempt);
+    let error = result.as_ref().err().map(|raw| backend_error_wire(raw));
+
+    record(DownloadEvent {
+        attempt: attempt.map(|a| a.attempt),
+        bytes: download
+            .as_ref()
+            .map(JournaledDownload::total_bytes)
+            .filter(|bytes| *bytes > 0),
+        duration_ms: download.as_ref().map(|d| now - d.started_at),
+        error_code: error.as_ref().map(|e| e.code.clone()),
+        error_message: error.map(|e| e.message),
+        ..new_event(id, kind, url, now)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_how_an_attempt_ended() {
+        let retrying = Some(DownloadAttempt {
+            attempt: 1,
+            max_attempts: 3,
+        });
+        let last = Some(DownloadAttempt {
+            attempt: 3,
+            max_attempts: 3,
+        });
+        let rate_limited = Err("HTTP Error 429: Too Many Requests".to_string());
+
+        assert_eq!(finish_kind(&Ok(()), last), DownloadEventKind::Completed);
+        assert_eq!(
+            finish_kind(&Err("Download cancelled".to_string()), retrying),
+            DownloadEventKind::Cancelled
+        );
+        assert_eq!(
+            finish_kind(&rate_limited, retrying),
+            DownloadEventKind::Retried
+        );
+        assert_eq!(finish_kind(&rate_limited, last), DownloadEventKind::Failed);
+        assert_eq!(finish_kind(&rate_limited, None), DownloadEventKind::Failed);
+    }
+}
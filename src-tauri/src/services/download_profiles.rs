@@ -0,0 +1,73 @@
+use crate::database::get_download_profile_db;
+use crate::types::{code, BackendError, DownloadOptions};
+
+const MAX_PROFILE_NAME_LEN: usize = 64;
+
+/// Check a profile name. Names are single words so they can follow a URL
+/// in a Telegram command or a CLI flag without quoting.
+pub fn validate_download_profile_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty()
+        || name.chars().count() > MAX_PROFILE_NAME_LEN
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
+    {
+        return Err(BackendError::new(
+            code::VALIDATION_INVALID_INPUT,
+            format!(
+                "Invalid profile name: {} (use letters, digits, '-', '_' or '.')",
+                name
+            ),
+        )
+        .with_param("profile", name)
+        .to_wire_string());
+    }
+    Ok(name.to_string())
+}
+
+/// Whether a profile with this name is saved. Lookup errors count as missing.
+pub fn download_profile_exists(name: &str) -> bool {
+    matches!(get_download_profile_db(name.trim()), Ok(Some(_)))
+}
+
+/// Apply the profile named in `options.profile`, if any, to the options.
+/// Fails when the profile does not exist.
+pub fn apply_download_profile(options: &mut DownloadOptions) -> Result<(), String> {
+    let Some(name) = options
+        .profile
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    else {
+        return Ok(());
+    };
+    let profile = get_download_profile_db(name)?.ok_or_else(|| {
+        BackendError::new(
+            code::VALIDATION_INVALID_INPUT,
+            format!("Unknown download profile: {}", name),
+        )
+        .with_param("profile", name)
+        .to_wire_string()
+    })?;
+    profile.settings.apply_to(options);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_names_are_single_words() {
+        assert_eq!(
+            validate_download_profile_name(" audio-320 ").unwrap(),
+            "audio-320"
+        );
+        assert!(validate_download_profile_name("Lưu_trữ.v2").is_ok());
+        assert!(validate_download_profile_name("").is_err());
+        assert!(validate_download_profile_name("two words").is_err());
+        assert!(validate_download_profile_name("a/b").is_err());
+        assert!(validate_download_profile_name(&"x".repeat(65)).is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Timing rules of scheduled downloads. The timer itself runs in
+//! `commands::download_schedule`; this module only decides what is due when.
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, TimeZone, Timelike};
+
+use crate::types::{DownloadQueueStatus, ScheduleRule};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// What a due schedule does with its download
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleAction {
+    /// Add the download to the queue
+    Enqueue,
+    /// Continue the download paused when the window closed
+    Resume,
+    /// Pause the download because the window closed
+    Pause,
+    Nothing,
+}
+
+/// Reject rules that can never run.
+pub fn validate_schedule_rule(rule: &ScheduleRule) -> Result<(), String> {
+    match rule {
+        ScheduleRule::Once { .. } => Ok(()),
+        ScheduleRule::Window {
+            start_minute,
+            end_minute,
+        } => {
+            if *start_minute >= MINUTES_PER_DAY || *end_minute >= MINUTES_PER_DAY {
+                Err("Schedule window times must be within a day".to_string())
+            } else if start_minute == end_minute {
+                Err("Schedule window must not start and end at the same time".to_string())
+            } else {
+                Ok(())
+            }
+        }
+        ScheduleRule::Weekly { weekdays, minute } => {
+            if *minute >= MINUTES_PER_DAY {
+                Err("Schedule time must be within a day".to_string())
+            } else if weekdays.is_empty() || weekdays.iter().any(|day| *day > 6) {
+                Err("Weekly schedules need at least one valid weekday".to_string())
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Whether `minute` of the day falls inside a window; an end before the start spans midnight.
+pub fn is_in_window(start_minute: u32, end_minute: u32, minute: u32) -> bool {
+    if start_minute <= end_minute {
+        minute >= start_minute && minute < end_minute
+    } else {
+        minute >= start_minute || minute < end_minute
+    }
+}
+
+/// `minute` of `date` in the local time zone. A time skipped by a DST change runs an hour later.
+fn local_time<Tz: TimeZone>(tz: &Tz, date: NaiveDate, minute: u32) -> Option<DateTime<Tz>> {
+    let naive = date.and_hms_opt(minute / 60, minute % 60, 0)?;
+    tz.from_local_datetime(&naive).earliest().or_else(|| {
+        tz.from_local_datetime(&(naive + chrono::Duration::hours(1)))
+            .earliest()
+    })
+}
+
+/// Next time after `now` that `minute` of the day comes around, on one of `weekdays`
+/// (0 = Sunday) when given.
+fn next_time_of_day<Tz: TimeZone>(
+    now: &DateTime<Tz>,
+    minute: u32,
+    weekdays: Option<&[u8]>,
+) -> Option<DateTime<Tz>> {
+    let today = now.date_naive();
+    (0..=7)
+        .filter_map(|offset| today.checked_add_days(Days::new(offset)))
+        .filter(|date| {
+            weekdays.map_or(true, |days| {
+                days.contains(&(date.weekday().num_days_from_sunday() as u8))
+            })
+        })
+        .filter_map(|date| local_time(&now.timezone(), date, minute))
+        .find(|at| at > now)
+}
+
+/// Minutes since midnight of `now`
+pub fn minute_of_day<Tz: TimeZone>(now: &DateTime<Tz>) -> u32 {
+    now.hour() * 60 + now.minute()
+}
+
+/// When a new (or restarted) schedule first needs looking at, in ms.
+pub fn first_run_at<Tz: TimeZone>(rule: &ScheduleRule, now: &DateTime<Tz>) -> Option<i64> {
+    match rule {
+        ScheduleRule::Once { at } => Some(*at),
+        ScheduleRule::Window {
+            start_minute,
+            end_minute,
+        } => {
+            if is_in_window(*start_minute, *end_minute, minute_of_day(now)) {
+                Some(now.timestamp_millis())
+            } else {
+                next_time_of_day(now, *start_minute, None).map(|at| at.timestamp_millis())
+            }
+        }
+        ScheduleRule::Weekly { weekdays, minute } => {
+            next_time_of_day(now, *minute, Some(weekdays)).map(|at| at.timestamp_millis())
+        }
+    }
+}
+
+/// Decide what a due schedule does at `now`, given the state of the download it
+/// queued last (`None` if it has not queued one yet, `Cancelled` if that item is gone).
+/// Also returns when the schedule next needs looking at; `None` means it is done.
+pub fn plan_schedule_run<Tz: TimeZone>(
+    rule: &ScheduleRule,
+    last_item: Option<DownloadQueueStatus>,
+    now: &DateTime<Tz>,
+) -> (ScheduleAction, Option<i64>) {
+    let at_ms = |at: Option<DateTime<Tz>>| at.map(|at| at.timestamp_millis());
+    match rule {
+        ScheduleRule::Once { .. } => match last_item {
+            None => (ScheduleAction::Enqueue, None),
+            Some(_) => (ScheduleAction::Nothing, None),
+        },
+        ScheduleRule::Weekly { weekdays, minute } => {
+            let next = at_ms(next_time_of_day(now, *minute, Some(weekdays)));
+            // A re-check still waiting or running from last time is not queued twice.
+            match last_item {
+                Some(status) if !status.is_finished() => (ScheduleAction::Nothing, next),
+                _ => (ScheduleAction::Enqueue, next),
+            }
+        }
+        ScheduleRule::Window {
+            start_minute,
+            end_minute,
+        } => {
+            let open = is_in_window(*start_minute, *end_minute, minute_of_day(now));
+            let next_open = at_ms(next_time_of_day(now, *start_minute, None));
+            let next_close = at_ms(next_time_of_day(now, *end_minute, None));
+            match (last_item, open) {
+                (None, true) => (ScheduleAction::Enqueue, next_close),
+                (None, false) => (ScheduleAction::Nothing, next_open),
+                (Some(DownloadQueueStatus::Paused), true) => (ScheduleAction::Resume, next_close),
+                (Some(DownloadQueueStatus::Paused), false) => (ScheduleAction::Nothing, next_open),
+                (Some(DownloadQueueStatus::Pending | DownloadQueueStatus::Downloading), true) => {
+                    (ScheduleAction::Nothing, next_close)
+                }
+                (Some(DownloadQueueStatus::Pending | DownloadQueueStatus::Downloading), false) => {
+                    (ScheduleAction::Pause, next_open)
+                }
+                (Some(_), _) => (ScheduleAction::Nothing, None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    // 2026-03-04 is a Wednesday.
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(2 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2026, 3, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn nightly() -> ScheduleRule {
+        ScheduleRule::Window {
+            start_minute: 60,
+            end_minute: 360,
+        }
+    }
+
+    #[test]
+    fn windows_may_span_midnight() {
+        assert!(is_in_window(60, 360, 60));
+        assert!(!is_in_window(60, 360, 360));
+        assert!(is_in_window(23 * 60, 5 * 60, 23 * 60 + 30));
+        assert!(is_in_window(23 * 60, 5 * 60, 30));
+        assert!(!is_in_window(23 * 60, 5 * 60, 12 * 60));
+    }
+
+    #[test]
+    fn weekly_rule_runs_on_the_next_matching_day() {
+        let sunday_3am = ScheduleRule::Weekly {
+            weekdays: vec![0],
+            minute: 180,
+        };
+        assert_eq!(
+            first_run_at(&sunday_3am, &at(4, 12, 0)),
+            Some(at(8, 3, 0).timestamp_millis())
+        );
+        // Exactly at the run time, the next run is a week later.
+        let (action, next) = plan_schedule_run(&sunday_3am, None, &at(8, 3, 0));
+        assert_eq!(action, ScheduleAction::Enqueue);
+        assert_eq!(next, Some(at(15, 3, 0).timestamp_millis()));
+
+        // Last week's check still running is not queued again.
+        let (action, _) = plan_schedule_run(
+            &sunday_3am,
+            Some(DownloadQueueStatus::Downloading),
+            &at(8, 3, 0),
+        );
+        assert_eq!(action, ScheduleAction::Nothing);
+        let (action, _) = plan_schedule_run(
+            &sunday_3am,
+            Some(DownloadQueueStatus::Completed),
+            &at(8, 3, 0),
+        );
+        assert_eq!(action, ScheduleAction::Enqueue);
+    }
+
+    #[test]
+    fn window_download_pauses_outside_the_window() {
+        let rule = nightly();
+        assert_eq!(
+            first_run_at(&rule, &at(4, 12, 0)),
+            Some(at(5, 1, 0).timestamp_millis())
+        );
+        assert_eq!(
+            first_run_at(&rule, &at(4, 2, 0)),
+            Some(at(4, 2, 0).timestamp_millis())
+        );
+
+        let (action, next) = plan_schedule_run(&rule, None, &at(5, 1, 0));
+        assert_eq!(action, ScheduleAction::Enqueue);
+        assert_eq!(next, Some(at(5, 6, 0).timestamp_millis()));
+
+        let (action, next) =
+            plan_schedule_run(&rule, Some(DownloadQueueStatus::Downloading), &at(5, 6, 0));
+        assert_eq!(action, ScheduleAction::Pause);
+        assert_eq!(next, Some(at(6, 1, 0).timestamp_millis()));
+
+        let (action, _) = plan_schedule_run(&rule, Some(DownloadQueueStatus::Paused), &at(6, 1, 0));
+        assert_eq!(action, ScheduleAction::Resume);
+
+        let (action, next) =
+            plan_schedule_run(&rule, Some(DownloadQueueStatus::Completed), &at(6, 6, 0));
+        assert_eq!((action, next), (ScheduleAction::Nothing, None));
+    }
+
+    #[test]
+    fn once_rule_runs_a_single_time() {
+        let rule = ScheduleRule::Once {
+            at: at(4, 23, 0).timestamp_millis(),
+        };
+        assert_eq!(
+            first_run_at(&rule, &at(4, 12, 0)),
+            Some(at(4, 23, 0).timestamp_millis())
+        );
+        assert_eq!(
+            plan_schedule_run(&rule, None, &at(5, 8, 0)),
+            (ScheduleAction::Enqueue, None)
+        );
+        assert_eq!(
+            plan_schedule_run(&rule, Some(DownloadQueueStatus::Pending), &at(5, 8, 0)),
+            (ScheduleAction::Nothing, None)
+        );
+    }
+
+    #[test]
+    fn rules_that_cannot_run_are_rejected() {
+        assert!(validate_schedule_rule(&nightly()).is_ok());
+        assert!(validate_schedule_rule(&ScheduleRule::Window {
+            start_minute: 60,
+            end_minute: 60,
+        })
+        .is_err());
+        assert!(validate_schedule_rule(&ScheduleRule::Weekly {
+            weekdays: vec![],
+            minute: 0,
+        })
+        .is_err());
+        assert!(validate_schedule_rule(&ScheduleRule::Weekly {
+            weekdays: vec![7],
+            minute: 0,
+        })
+        .is_err());
+    }
+}
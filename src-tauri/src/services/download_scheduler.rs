@@ -0,0 +1,481 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Notify;
+
+use crate::database::{
+    delete_download_queue_item_db, get_download_queue_item_db, insert_download_queue_item_db,
+    update_channel_video_status_db,
+};
+use crate::services::{
+    apply_download_profile, enqueue_plugin_trigger_workflow, journal_download_queued,
+    normalize_quota_path, quota_for_folder, rate_limit_cooldown_remaining, recent_folder_usage,
+    time_range_label, wake_bandwidth_balancer,
+};
+use crate::types::{
+    BackendError, BandwidthConfig, DownloadQueueItem, DownloadSchedulerConfig, FolderQuota,
+    NewDownloadQueueItem, PostDownloadPluginPayload, RetryPolicy,
+};
+use crate::utils::{normalize_url, validate_url};
+
+/// Event emitted with the item id whenever a queue row changes
+pub const DOWNLOAD_QUEUE_UPDATED_EVENT: &str = "download-queue-updated";
+
+/// Queue kinds the scheduler knows how to run (gallery downloads stay frontend-driven)
+const SCHEDULED_QUEUE_KINDS: &[&str] = &["youtube", "universal"];
+
+static SCHEDULER_CONFIG: LazyLock<Mutex<DownloadSchedulerConfig>> =
+    LazyLock::new(|| Mutex::new(DownloadSchedulerConfig::default()));
+
+static SCHEDULER_WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Set once settings are known, from the last run's saved copy or the frontend's sync;
+/// queued items wait until then so they run with the user's output folder rather than
+/// failing at startup.
+static SCHEDULER_CONFIGURED: AtomicBool = AtomicBool::new(false);
+
+/// Update the concurrency limits used by the download scheduler.
+/// Called from the frontend whenever settings change, and with the saved settings at startup.
+pub fn set_scheduler_config(config: DownloadSchedulerConfig) {
+    if let Ok(mut guard) = SCHEDULER_CONFIG.lock() {
+        *guard = DownloadSchedulerConfig {
+            max_concurrent: config.max_concurrent.max(1),
+            max_per_site: config.max_per_site.max(1),
+            default_output_path: config
+                .default_output_path
+                .map(|path| path.trim().to_string())
+                .filter(|path| !path.is_empty()),
+            retry: RetryPolicy {
+                max_attempts: config.retry.max_attempts.max(1),
+                base_delay_secs: config.retry.base_delay_secs.max(1),
+                max_delay_secs: config
+                    .retry
+                    .max_delay_secs
+                    .max(config.retry.base_delay_secs.max(1)),
+                rate_limit_cooldown_secs: config.retry.rate_limit_cooldown_secs,
+            },
+            folder_quotas: config
+                .folder_quotas
+                .into_iter()
+                .filter(|quota| quota.max_bytes > 0 && !quota.path.trim().is_empty())
+                .map(|quota| FolderQuota {
+                    path: normalize_quota_path(&quota.path),
+                    max_bytes: quota.max_bytes,
+                })
+                .collect(),
+            bandwidth: BandwidthConfig {
+                limit: config.bandwidth.limit.filter(|limit| *limit > 0),
+                profiles: config
+                    .bandwidth
+                    .profiles
+                    .into_iter()
+                    .filter(|profile| {
+                        profile.start_minute != profile.end_minute
+                            && profile.start_minute < 24 * 60
+                            && profile.end_minute <= 24 * 60
+                    })
+                    .map(|mut profile| {
+                        profile.limit = profile.limit.filter(|limit| *limit > 0);
+                        profile
+                    })
+                    .collect(),
+            },
+        };
+    }
+    SCHEDULER_CONFIGURED.store(true, Ordering::SeqCst);
+    wake_scheduler();
+    wake_bandwidth_balancer();
+}
+
+/// Whether the scheduler has received its config yet.
+pub fn is_scheduler_configured() -> bool {
+    SCHEDULER_CONFIGURED.load(Ordering::SeqCst)
+}
+
+/// Read a snapshot of the current scheduler config.
+pub fn get_scheduler_config() -> DownloadSchedulerConfig {
+    SCHEDULER_CONFIG
+        .lock()
+        .map(|g| g.clone())
+        .unwrap_or_default()
+}
+
+/// Ask the scheduler loop to re-evaluate the queue (new item, finished job, config change).
+pub fn wake_scheduler() {
+    SCHEDULER_WAKE.notify_one();
+}
+
+/// Wait until the scheduler is woken up.
+pub async fn scheduler_woken() {
+    SCHEDULER_WAKE.notified().await;
+}
+
+/// Key used for per-site concurrency limits: the URL host without `www.`/`m.` prefixes.
+/// Short-link hosts are folded into the site they redirect to.
+pub fn site_key(url: &str) -> String {
+    let host = reqwest::Url::parse(url.trim())
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(|host| host.to_ascii_lowercase()))
+        .unwrap_or_default();
+    let host = host
+        .strip_prefix("www.")
+        .or_else(|| host.strip_prefix("m."))
+        .unwrap_or(&host);
+
+    match host {
+        "" => "unknown".to_string(),
+        "youtu.be" | "music.youtube.com" => "youtube.com".to_string(),
+        "b23.tv" => "bilibili.com".to_string(),
+        "vm.tiktok.com" | "vt.tiktok.com" => "tiktok.com".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Notify listeners that a queue item changed.
+pub fn emit_download_queue_updated(app: &AppHandle, item_id: &str) {
+    let _ = app.emit(DOWNLOAD_QUEUE_UPDATED_EVENT, item_id.to_string());
+}
+
+/// Add a download to the backend queue and wake the scheduler.
+///
+/// Shared by the `enqueue_download` command, the CLI, the Telegram bot and
+/// channel auto-download. Items queued outside the download pages fire the
+/// `download.queued` plugin trigger here; the pages fire their own.
+pub fn enqueue_download_item(
+    app: &AppHandle,
+    request: NewDownloadQueueItem,
+) -> Result<DownloadQueueItem, String> {
+    validate_url(&request.url).map_err(|e| BackendError::from_message(e).to_wire_string())?;
+    let url = normalize_url(&request.url);
+
+    let queue_kind = request
+        .queue_kind
+        .unwrap_or_else(|| default_queue_kind(&url).to_string());
+    if !SCHEDULED_QUEUE_KINDS.contains(&queue_kind.as_str()) {
+        return Err(format!(
+            "Download queue kind is not scheduled by the backend: {}",
+            queue_kind
+        ));
+    }
+
+    let origin = request
+        .origin
+        .map(|origin| origin.trim().to_string())
+        .filter(|origin| !origin.is_empty())
+        .unwrap_or_else(|| "app".to_string());
+    let title = request
+        .title
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty());
+    let id = request
+        .id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty());
+
+    // Starting a page item again replaces the row its last run left behind.
+    if let Some(id) = id.as_deref() {
+        if let Some(existing) = get_download_queue_item_db(id)? {
+            if !existing.status.is_finished() {
+                return Err(format!("Download is already queued: {}", id));
+            }
+            delete_download_queue_item_db(id)?;
+        }
+    }
+
+    // Profiles are resolved at queue time, like workflow snapshots, so later
+    // edits to a profile do not change items already waiting in the queue.
+    let mut options = request.options;
+    apply_download_profile(&mut options)?;
+    options.profile = None;
+
+    let item = insert_download_queue_item_db(
+        id.as_deref(),
+        &queue_kind,
+        &url,
+        title.as_deref(),
+        &site_key(&url),
+        &origin,
+        request.origin_ref.as_deref(),
+        request.priority.unwrap_or(0),
+        &options,
+    )?;
+
+    // Hide queued channel videos from the next poll's "new" list.
+    if item.origin == "channel" {
+        if let Some(video_row_id) = item.origin_ref.clone() {
+            update_channel_video_status_db(video_row_id, "downloading".to_string()).ok();
+        }
+    }
+    if item.origin != "app" {
+        fire_queued_trigger(app, &item);
+    }
+    journal_download_queued(&item.id, &item.url);
+
+    emit_download_queue_updated(app, &item.id);
+    wake_scheduler();
+    Ok(item)
+}
+
+fn default_queue_kind(url: &str) -> &'static str {
+    if site_key(url) == "youtube.com" {
+        "youtube"
+    } else {
+        "universal"
+    }
+}
+
+fn fire_queued_trigger(app: &AppHandle, item: &DownloadQueueItem) {
+    let directory = item
+        .options
+        .output_path
+        .clone()
+        .or_else(|| get_scheduler_config().default_output_path)
+        .unwrap_or_default();
+    let filename = item.title.clone().unwrap_or_else(|| item.url.clone());
+
+    let payload = PostDownloadPluginPayload {
+        job_id: item.id.clone(),
+        source: item.options.source.clone(),
+        trigger: "download.queued".to_string(),
+        filepath: String::new(),
+        filename,
+        directory,
+        filesize: None,
+        format: Some(item.options.format.clone()),
+        quality: Some(item.options.quality.clone()),
+        url: item.url.clone(),
+        title: item.title.clone(),
+        thumbnail: item.options.thumbnail.clone(),
+        history_id: None,
+        time_range: item
+            .options
+            .download_sections
+            .as_deref()
+            .and_then(time_range_label),
+        download_kind: item
+            .options
+            .download_kind
+            .clone()
+            .unwrap_or_else(|| format!("{}-queue", item.origin)),
+        relative_path: None,
+        extra_files: Vec::new(),
+        workflow_run_id: None,
+        workflow_step_index: None,
+        workflow_step_plugin_id: None,
+        chain_state: None,
+    };
+
+    let workflow_steps = item
+        .options
+        .plugin_workflow_snapshots
+        .as_ref()
+        .and_then(|snapshots| snapshots.get("download.queued").cloned());
+    enqueue_plugin_trigger_workflow(app, "download.queued", workflow_steps, payload);
+}
+
+/// Whether a pending item may start at `now` (ms): its retry delay has passed
+/// and its site is not cooling down after a rate limit.
+pub fn is_due_for_dispatch(item: &DownloadQueueItem, now: i64) -> bool {
+    item.next_attempt_at.map_or(true, |at| at <= now)
+        && rate_limit_cooldown_remaining(&item.site).is_none()
+}
+
+/// Sizes of the quota folders for one scheduling pass, keyed by quota path. Folders are
+/// walked off the async runtime, and a size measured in the last half minute is reused.
+pub async fn quota_folder_usage(config: &DownloadSchedulerConfig) -> HashMap<String, u64> {
+    let mut usage = HashMap::new();
+    for quota in &config.folder_quotas {
+        let used = recent_folder_usage(Path::new(&quota.path)).await;
+        usage.insert(quota.path.clone(), used);
+    }
+    usage
+}
+
+/// Whether the folder an item downloads to has reached its quota. Such items wait
+/// in the queue until files are removed or the quota is raised.
+/// `usage` holds the quota folder sizes from `quota_folder_usage`.
+pub fn is_folder_full(
+    item: &DownloadQueueItem,
+    config: &DownloadSchedulerConfig,
+    usage: &HashMap<String, u64>,
+) -> bool {
+    if config.folder_quotas.is_empty() {
+        return false;
+    }
+    let Some(folder) = item
+        .options
+        .output_path
+        .clone()
+        .filter(|path| !path.trim().is_empty())
+        .or_else(|| config.default_output_path.clone())
+    else {
+        return false;
+    };
+    let Some(quota) = quota_for_folder(&config.folder_quotas, &normalize_quota_path(&folder))
+    else {
+        return false;
+    };
+    // A quota added after the sizes were measured is checked on the next pass
+    usage
+        .get(&quota.path)
+        .is_some_and(|used| *used >= quota.max_bytes)
+}
+
+/// Pick the pending items that may start now.
+///
+/// `pending` must already be in scheduling order (priority, then FIFO) and
+/// `running_by_site` counts the jobs currently downloading per site key.
+/// Items whose site is at its limit are skipped so they don't block other sites.
+pub fn select_runnable<'a>(
+    pending: &'a [DownloadQueueItem],
+    running_by_site: &HashMap<String, u32>,
+    config: &DownloadSchedulerConfig,
+) -> Vec<&'a DownloadQueueItem> {
+    let max_concurrent = config.max_concurrent.max(1);
+    let max_per_site = config.max_per_site.max(1);
+    let mut running_total: u32 = running_by_site.values().sum();
+    let mut per_site = running_by_site.clone();
+    let mut selected = Vec::new();
+
+    for item in pending {
+        if running_total >= max_concurrent {
+            break;
+        }
+        let site_count = per_site.entry(item.site.clone()).or_insert(0);
+        if *site_count >= max_per_site {
+            continue;
+        }
+        *site_count += 1;
+        running_total += 1;
+        selected.push(item);
+    }
+
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DownloadOptions, DownloadQueueStatus};
+
+    fn item(id: &str, site: &str) -> DownloadQueueItem {
+        DownloadQueueItem {
+            id: id.to_string(),
+            queue_kind: "universal".to_string(),
+            url: format!("https://{site}/{id}"),
+            title: None,
+            site: site.to_string(),
+            origin: "app".to_string(),
+            origin_ref: None,
+            status: DownloadQueueStatus::Pending,
+            priority: 0,
+            attempts: 0,
+            options: DownloadOptions::default(),
+            partial_paths: Vec::new(),
+            error_code: None,
+            error_message: None,
+            created_at: 0,
+            updated_at: 0,
+            started_at: None,
+            finished_at: None,
+            next_attempt_at: None,
+        }
+    }
+
+    fn config(max_concurrent: u32, max_per_site: u32) -> DownloadSchedulerConfig {
+        DownloadSchedulerConfig {
+            max_concurrent,
+            max_per_site,
+            default_output_path: None,
+            retry: RetryPolicy::default(),
+            folder_quotas: Vec::new(),
+            bandwidth: BandwidthConfig::default(),
+        }
+    }
+
+    fn ids(selected: Vec<&DownloadQueueItem>) -> Vec<&str> {
+        selected.into_iter().map(|item| item.id.as_str()).collect()
+    }
+
+    #[test]
+    fn site_key_normalizes_hosts() {
+        assert_eq!(
+            site_key("https://www.youtube.com/watch?v=abc"),
+            "youtube.com"
+        );
+        assert_eq!(site_key("https://youtu.be/abc"), "youtube.com");
+        assert_eq!(site_key("https://m.bilibili.com/video/BV1"), "bilibili.com");
+        assert_eq!(site_key("https://b23.tv/xyz"), "bilibili.com");
+        assert_eq!(site_key("https://Vimeo.com/123"), "vimeo.com");
+        assert_eq!(site_key("not a url"), "unknown");
+    }
+
+    #[test]
+    fn select_runnable_respects_global_limit() {
+        let pending = vec![item("a", "a.com"), item("b", "b.com"), item("c", "c.com")];
+        let selected = select_runnable(&pending, &HashMap::new(), &config(2, 2));
+        assert_eq!(ids(selected), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn select_runnable_skips_saturated_site_without_blocking_others() {
+        let pending = vec![
+            item("yt-1", "youtube.com"),
+            item("yt-2", "youtube.com"),
+            item("vimeo", "vimeo.com"),
+        ];
+        let selected = select_runnable(&pending, &HashMap::new(), &config(3, 1));
+        assert_eq!(ids(selected), vec!["yt-1", "vimeo"]);
+    }
+
+    #[test]
+    fn items_waiting_for_a_retry_are_not_due() {
+        let mut waiting = item("retry", "retry-wait.example");
+        waiting.next_attempt_at = Some(10_000);
+        assert!(!is_due_for_dispatch(&waiting, 9_999));
+        assert!(is_due_for_dispatch(&waiting, 10_000));
+        assert!(is_due_for_dispatch(&item("fresh", "retry-wait.example"), 0));
+    }
+
+    #[tokio::test]
+    async fn items_for_a_full_folder_wait() {
+        let root = std::env::temp_dir().join(format!("youwee-quota-{}", uuid::Uuid::new_v4()));
+        let folder = root.join("videos");
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("a.mp4"), vec![0u8; 100]).unwrap();
+        let folder = folder.to_string_lossy().to_string();
+
+        let mut queued = item("quota", "vimeo.com");
+        queued.options.output_path = Some(folder.clone());
+        let mut config = config(2, 2);
+        config.folder_quotas = vec![FolderQuota {
+            path: normalize_quota_path(&root.to_string_lossy()),
+            max_bytes: 100,
+        }];
+        let usage = quota_folder_usage(&config).await;
+        assert!(is_folder_full(&queued, &config, &usage));
+
+        config.folder_quotas[0].max_bytes = 1000;
+        assert!(!is_folder_full(&queued, &config, &usage));
+
+        // Without a folder of its own the item goes to the default folder.
+        queued.options.output_path = None;
+        config.default_output_path = Some(std::env::temp_dir().to_string_lossy().to_string());
+        assert!(!is_folder_full(&queued, &config, &usage));
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn select_runnable_counts_running_jobs() {
+        let pending = vec![item("yt", "youtube.com"), item("vimeo", "vimeo.com")];
+        let running = HashMap::from([("youtube.com".to_string(), 1)]);
+
+        let selected = select_runnable(&pending, &running, &config(2, 1));
+        assert_eq!(ids(selected), vec!["vimeo"]);
+
+        let running = HashMap::from([("youtube.com".to_string(), 2)]);
+        assert!(select_runnable(&pending, &running, &config(2, 2)).is_empty());
+    }
+}
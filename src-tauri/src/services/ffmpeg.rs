@@ -131,6 +131,41 @@ pub async fn get_ffmpeg_path(app: &AppHandle) -> Option<PathBuf> {
     }
 }
 
+/// Get the FFprobe binary path: app data, system, or next to the FFmpeg in use
+pub async fn get_ffprobe_path(app: &AppHandle) -> Option<PathBuf> {
+    if let Ok(app_data_dir) = app.path().app_data_dir() {
+        let bin_dir = app_data_dir.join("bin");
+        #[cfg(windows)]
+        let ffprobe_path = bin_dir.join("ffprobe.exe");
+        #[cfg(not(windows))]
+        let ffprobe_path = bin_dir.join("ffprobe");
+
+        if ffprobe_path.exists() {
+            return Some(ffprobe_path);
+        }
+    }
+
+    #[cfg(windows)]
+    let binary_name = "ffprobe.exe";
+    #[cfg(not(windows))]
+    let binary_name = "ffprobe";
+
+    if let Some(path) = find_system_binary(binary_name, &unix_system_binary_dirs()) {
+        return Some(path);
+    }
+
+    if let Some(ffmpeg_path) = get_ffmpeg_path(app).await {
+        if let Some(parent) = ffmpeg_path.parent() {
+            let ffprobe_path = parent.join(binary_name);
+            if ffprobe_path.exists() {
+                return Some(ffprobe_path);
+            }
+        }
+    }
+
+    None
+}
+
 /// Check FFmpeg status
 pub async fn check_ffmpeg_internal(app: &AppHandle) -> Result<FfmpegStatus, String> {
     if let Some(ffmpeg_path) = get_ffmpeg_path(app).await {
@@ -1,32 +1,109 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 
+use serde::Deserialize;
 use tauri::{AppHandle, Manager};
 use tokio::process::Command;
 
-use crate::types::{BackendError, GalleryDlStatus};
+use crate::types::{
+    BackendError, DependencySource, GalleryDlChannel, GalleryDlOptions, GalleryDlStatus,
+};
 use crate::utils::{find_system_binary, unix_system_binary_dirs, CommandExt};
 
+const SOURCE_CONFIG_FILE: &str = "gallerydl-source.txt";
+const CHANNEL_CONFIG_FILE: &str = "gallerydl-channel.txt";
+
 pub fn system_gallerydl_not_found_message() -> String {
     #[cfg(target_os = "macos")]
     {
-        return "System gallery-dl not found. Install it with Homebrew (`brew install gallery-dl`) and ensure `gallery-dl` is available in PATH.".to_string();
+        return "System gallery-dl not found. Install it with Homebrew (`brew install gallery-dl`) and ensure `gallery-dl` is available in PATH, or switch to App managed in Settings > Dependencies.".to_string();
     }
     #[cfg(target_os = "windows")]
     {
-        return "System gallery-dl not found. Install it with a package manager (e.g. `choco install gallery-dl` or `scoop install gallery-dl`) and ensure `gallery-dl` is available in PATH.".to_string();
+        return "System gallery-dl not found. Install it with a package manager (e.g. `choco install gallery-dl` or `scoop install gallery-dl`) and ensure `gallery-dl` is available in PATH, or switch to App managed in Settings > Dependencies.".to_string();
     }
     #[cfg(target_os = "linux")]
     {
-        return "System gallery-dl not found. Install it with your distro package manager and ensure `gallery-dl` is available in PATH.".to_string();
+        return "System gallery-dl not found. Install it with your distro package manager and ensure `gallery-dl` is available in PATH, or switch to App managed in Settings > Dependencies.".to_string();
     }
     #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
     {
-        "System gallery-dl not found. Install it and ensure `gallery-dl` is available in PATH."
+        "System gallery-dl not found. Install it and ensure `gallery-dl` is available in PATH, or switch to App managed in Settings > Dependencies."
             .to_string()
     }
 }
 
+/// Why no gallery-dl binary was found for the selected source
+pub fn gallerydl_not_found_message(source: &DependencySource) -> String {
+    match source {
+        DependencySource::App => {
+            "App managed gallery-dl not found. Install it in Settings > Dependencies.".to_string()
+        }
+        _ => system_gallerydl_not_found_message(),
+    }
+}
+
+pub fn system_gallerydl_upgrade_message() -> String {
+    "System gallery-dl is managed externally. Update it with your package manager or switch source to App managed.".to_string()
+}
+
+fn get_bin_config_path(app: &AppHandle, file_name: &str) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|p| p.join("bin").join(file_name))
+}
+
+async fn write_bin_config(app: &AppHandle, file_name: &str, value: &str) -> Result<(), String> {
+    let config_path = get_bin_config_path(app, file_name).ok_or("Failed to get config path")?;
+
+    if let Some(parent) = config_path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create bin directory: {}", e))?;
+    }
+
+    tokio::fs::write(&config_path, value)
+        .await
+        .map_err(|e| format!("Failed to save gallery-dl config: {}", e))
+}
+
+/// Read the gallery-dl source from config file
+pub async fn get_gallerydl_source(app: &AppHandle) -> DependencySource {
+    if let Some(config_path) = get_bin_config_path(app, SOURCE_CONFIG_FILE) {
+        if let Ok(content) = tokio::fs::read_to_string(&config_path).await {
+            return DependencySource::from_str(content.trim());
+        }
+    }
+    DependencySource::Auto
+}
+
+/// Save the gallery-dl source to config file
+pub async fn set_gallerydl_source(
+    app: &AppHandle,
+    source: &DependencySource,
+) -> Result<(), String> {
+    write_bin_config(app, SOURCE_CONFIG_FILE, source.as_str()).await
+}
+
+/// Read the channel of the app-managed gallery-dl from config file
+pub async fn get_gallerydl_channel(app: &AppHandle) -> GalleryDlChannel {
+    if let Some(config_path) = get_bin_config_path(app, CHANNEL_CONFIG_FILE) {
+        if let Ok(content) = tokio::fs::read_to_string(&config_path).await {
+            return GalleryDlChannel::from_str(content.trim());
+        }
+    }
+    GalleryDlChannel::Stable
+}
+
+/// Save the channel of the app-managed gallery-dl to config file
+pub async fn set_gallerydl_channel(
+    app: &AppHandle,
+    channel: GalleryDlChannel,
+) -> Result<(), String> {
+    write_bin_config(app, CHANNEL_CONFIG_FILE, channel.as_str()).await
+}
+
 pub fn get_system_gallerydl_path() -> Option<PathBuf> {
     #[cfg(windows)]
     let binary_name = "gallery-dl.exe";
@@ -36,7 +113,48 @@ pub fn get_system_gallerydl_path() -> Option<PathBuf> {
     find_system_binary(binary_name, &unix_system_binary_dirs())
 }
 
-fn get_app_gallerydl_path(app: &AppHandle) -> Option<PathBuf> {
+/// File name of the app-managed binary of a channel, in the app's `bin` folder
+pub fn app_gallerydl_binary_name(channel: GalleryDlChannel) -> &'static str {
+    #[cfg(windows)]
+    match channel {
+        GalleryDlChannel::Stable => "gallery-dl-stable.exe",
+        GalleryDlChannel::Nightly => "gallery-dl-nightly.exe",
+    }
+    #[cfg(not(windows))]
+    match channel {
+        GalleryDlChannel::Stable => "gallery-dl-stable",
+        GalleryDlChannel::Nightly => "gallery-dl-nightly",
+    }
+}
+
+/// App-managed binary of `channel`, if installed
+pub fn get_app_gallerydl_channel_path(
+    app: &AppHandle,
+    channel: GalleryDlChannel,
+) -> Option<PathBuf> {
+    let binary_path = app
+        .path()
+        .app_data_dir()
+        .ok()?
+        .join("bin")
+        .join(app_gallerydl_binary_name(channel));
+    binary_path.exists().then_some(binary_path)
+}
+
+/// The selected channel's binary, else the other channel's, else a binary placed in the
+/// app's `bin` folder by hand
+async fn get_app_gallerydl_path(app: &AppHandle) -> Option<PathBuf> {
+    let channel = get_gallerydl_channel(app).await;
+    let other = match channel {
+        GalleryDlChannel::Stable => GalleryDlChannel::Nightly,
+        GalleryDlChannel::Nightly => GalleryDlChannel::Stable,
+    };
+    if let Some(path) = get_app_gallerydl_channel_path(app, channel)
+        .or_else(|| get_app_gallerydl_channel_path(app, other))
+    {
+        return Some(path);
+    }
+
     let app_data_dir = app.path().app_data_dir().ok()?;
     #[cfg(windows)]
     let binary_name = "gallery-dl.exe";
@@ -51,21 +169,21 @@ fn get_app_gallerydl_path(app: &AppHandle) -> Option<PathBuf> {
     }
 }
 
-pub fn get_gallerydl_path(app: &AppHandle) -> Option<PathBuf> {
-    get_system_gallerydl_path().or_else(|| get_app_gallerydl_path(app))
+/// gallery-dl binary for the selected source. `auto` prefers a system install.
+pub async fn get_gallerydl_path(app: &AppHandle) -> Option<PathBuf> {
+    match get_gallerydl_source(app).await {
+        DependencySource::System => get_system_gallerydl_path(),
+        DependencySource::App => get_app_gallerydl_path(app).await,
+        DependencySource::Auto => match get_system_gallerydl_path() {
+            Some(path) => Some(path),
+            None => get_app_gallerydl_path(app).await,
+        },
+    }
 }
 
-pub async fn check_gallerydl_internal(app: &AppHandle) -> Result<GalleryDlStatus, String> {
-    let Some(binary_path) = get_gallerydl_path(app) else {
-        return Ok(GalleryDlStatus {
-            installed: false,
-            version: None,
-            binary_path: None,
-            is_system: true,
-        });
-    };
-
-    let mut cmd = Command::new(&binary_path);
+/// Version printed by a gallery-dl binary
+pub async fn gallerydl_binary_version(binary_path: &Path) -> Result<String, String> {
+    let mut cmd = Command::new(binary_path);
     cmd.arg("--version")
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
@@ -84,13 +202,328 @@ pub async fn check_gallerydl_internal(app: &AppHandle) -> Result<GalleryDlStatus
         .to_wire_string());
     }
 
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub async fn check_gallerydl_internal(app: &AppHandle) -> Result<GalleryDlStatus, String> {
+    let source = get_gallerydl_source(app).await.as_str().to_string();
+    let channel = get_gallerydl_channel(app).await.as_str().to_string();
+    let Some(binary_path) = get_gallerydl_path(app).await else {
+        return Ok(GalleryDlStatus {
+            installed: false,
+            version: None,
+            binary_path: None,
+            is_system: source == "system",
+            source,
+            channel,
+        });
+    };
+
+    let version = gallerydl_binary_version(&binary_path).await?;
+    let app_bin_dir = app.path().app_data_dir().ok().map(|dir| dir.join("bin"));
+
     Ok(GalleryDlStatus {
         installed: true,
-        version: Some(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+        version: Some(version),
         binary_path: Some(binary_path.to_string_lossy().to_string()),
-        is_system: get_app_gallerydl_path(app)
-            .as_ref()
-            .map(|app_path| app_path != &binary_path)
+        is_system: app_bin_dir
+            .map(|bin_dir| !binary_path.starts_with(bin_dir))
             .unwrap_or(true),
+        source,
+        channel,
+    })
+}
+
+/// GitHub API URL of the latest release of a channel
+pub fn gallerydl_release_api_url(channel: GalleryDlChannel) -> &'static str {
+    match channel {
+        GalleryDlChannel::Stable => "https://api.github.com/repos/mikf/gallery-dl/releases/latest",
+        GalleryDlChannel::Nightly => "https://api.github.com/repos/gdl-org/builds/releases/latest",
+    }
+}
+
+/// Standalone executable published for this platform, if the channel has one
+pub fn gallerydl_asset_name(channel: GalleryDlChannel) -> Option<&'static str> {
+    match channel {
+        GalleryDlChannel::Stable => {
+            #[cfg(target_os = "windows")]
+            {
+                Some("gallery-dl.exe")
+            }
+            #[cfg(target_os = "linux")]
+            {
+                Some("gallery-dl.bin")
+            }
+            #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+            {
+                None
+            }
+        }
+        GalleryDlChannel::Nightly => {
+            #[cfg(target_os = "windows")]
+            {
+                Some("gallery-dl_windows.exe")
+            }
+            #[cfg(target_os = "linux")]
+            {
+                Some("gallery-dl_linux")
+            }
+            #[cfg(target_os = "macos")]
+            {
+                Some("gallery-dl_macos")
+            }
+            #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+            {
+                None
+            }
+        }
+    }
+}
+
+/// A GitHub release of gallery-dl
+#[derive(Debug, Deserialize)]
+pub struct GalleryDlRelease {
+    pub tag_name: String,
+    #[serde(default)]
+    pub assets: Vec<GalleryDlReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GalleryDlReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    /// Checksum computed by GitHub, e.g. `sha256:<hex>`
+    #[serde(default)]
+    pub digest: Option<String>,
+}
+
+impl GalleryDlRelease {
+    pub fn asset(&self, name: &str) -> Option<&GalleryDlReleaseAsset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+impl GalleryDlReleaseAsset {
+    /// SHA-256 of the asset, from the digest GitHub publishes for it
+    pub fn sha256(&self) -> Option<&str> {
+        self.digest
+            .as_deref()
+            .and_then(|digest| digest.strip_prefix("sha256:"))
+            .filter(|hash| hash.len() == 64)
+    }
+}
+
+/// Latest release of a channel, from the GitHub API
+pub async fn fetch_gallerydl_release(
+    channel: GalleryDlChannel,
+) -> Result<GalleryDlRelease, String> {
+    let client = reqwest::Client::builder()
+        .user_agent("Youwee/0.6.0")
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client
+        .get(gallerydl_release_api_url(channel))
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_timeout() {
+                "Request timed out. Please try again later.".to_string()
+            } else if e.is_connect() {
+                "Unable to connect. Please check your internet connection.".to_string()
+            } else {
+                format!("Failed to check for updates: {}", e)
+            }
+        })?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::FORBIDDEN || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+    {
+        return Err("GitHub API rate limit exceeded. Please try again later.".to_string());
+    }
+    if !status.is_success() {
+        return Err(format!("GitHub API error: {}", status));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse release info: {}", e))
+}
+
+/// Release tag recorded next to an app-managed binary when it was installed. Nightly
+/// builds print the upcoming version, so the tag is what tells them apart.
+pub fn gallerydl_installed_release_path(binary_path: &Path) -> PathBuf {
+    binary_path.with_extension("release")
+}
+
+/// Arguments for the options of a gallery download; they must have passed
+/// `validate_gallerydl_options`.
+pub fn gallerydl_option_args(options: &GalleryDlOptions) -> Vec<String> {
+    let mut args = Vec::new();
+    let values = [
+        ("--range", &options.range),
+        ("--filter", &options.filter),
+        ("--filename", &options.filename_format),
+    ];
+    for (flag, value) in values {
+        if let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            args.push(flag.to_string());
+            args.push(value.to_string());
+        }
+    }
+    let folders: Vec<&str> = options
+        .directory_format
+        .iter()
+        .map(|folder| folder.trim())
+        .filter(|folder| !folder.is_empty())
+        .collect();
+    if !folders.is_empty() {
+        // gallery-dl reads `-o` values as JSON
+        let value = serde_json::to_string(&folders).unwrap_or_default();
+        args.push("--option".to_string());
+        args.push(format!("directory={}", value));
+    }
+    if options.write_metadata {
+        args.push("--write-metadata".to_string());
+    }
+    if options.zip {
+        args.push("--zip".to_string());
+    }
+    if options.ugoira_conv {
+        args.push("--ugoira-conv".to_string());
+    }
+    args
+}
+
+/// A file line of gallery-dl's output. With stdout piped, gallery-dl prints the path of
+/// every file it saves, and skipped files prefixed with `# `.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GalleryOutputLine {
+    Saved(String),
+    /// Already on disk or in the download archive
+    Skipped(String),
+}
+
+pub fn parse_gallery_output_line(line: &str) -> Option<GalleryOutputLine> {
+    let line = line.trim_end();
+    let (path, skipped) = match line.strip_prefix("# ") {
+        Some(path) => (path, true),
+        None => (line, false),
+    };
+    if path.trim().is_empty() {
+        return None;
+    }
+    let path = path.to_string();
+    Some(if skipped {
+        GalleryOutputLine::Skipped(path)
+    } else {
+        GalleryOutputLine::Saved(path)
     })
 }
+
+/// Folder holding every file of a gallery (their deepest common folder), or `fallback`
+/// when nothing was saved.
+pub fn gallery_album_dir(files: &[String], fallback: &str) -> String {
+    let mut parents = files.iter().filter_map(|file| Path::new(file).parent());
+    let Some(first) = parents.next() else {
+        return fallback.to_string();
+    };
+    let mut common = first.to_path_buf();
+    for parent in parents {
+        while !parent.starts_with(&common) {
+            if !common.pop() {
+                return fallback.to_string();
+            }
+        }
+    }
+    common.to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_saved_and_skipped_files() {
+        assert_eq!(
+            parse_gallery_output_line("/dl/pixiv/123/01.jpg"),
+            Some(GalleryOutputLine::Saved("/dl/pixiv/123/01.jpg".to_string()))
+        );
+        assert_eq!(
+            parse_gallery_output_line("# /dl/pixiv/123/02.png\r"),
+            Some(GalleryOutputLine::Skipped(
+                "/dl/pixiv/123/02.png".to_string()
+            ))
+        );
+        assert_eq!(parse_gallery_output_line("   "), None);
+    }
+
+    #[test]
+    fn reads_asset_checksums_from_release_digests() {
+        let release: GalleryDlRelease = serde_json::from_str(
+            r#"{
+                "tag_name": "v1.29.7",
+                "assets": [
+                    {
+                        "name": "gallery-dl.exe",
+                        "browser_download_url": "https://example.com/gallery-dl.exe",
+                        "digest": "sha256:6f1ed002ab5595859014ebf0951522d9b8e2c4d16b13b3e9c6e6a7bb3f8f4b2a"
+                    },
+                    {
+                        "name": "gallery-dl.bin",
+                        "browser_download_url": "https://example.com/gallery-dl.bin",
+                        "digest": null
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            release.asset("gallery-dl.exe").and_then(|a| a.sha256()),
+            Some("6f1ed002ab5595859014ebf0951522d9b8e2c4d16b13b3e9c6e6a7bb3f8f4b2a")
+        );
+        assert_eq!(
+            release.asset("gallery-dl.bin").and_then(|a| a.sha256()),
+            None
+        );
+        assert!(release.asset("gallery-dl_macos").is_none());
+    }
+
+    #[test]
+    fn builds_arguments_for_gallery_options() {
+        let options = GalleryDlOptions {
+            range: Some(" 1-20 ".to_string()),
+            filter: Some(String::new()),
+            directory_format: vec!["{category}".to_string(), "{user[name]}".to_string()],
+            zip: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            gallerydl_option_args(&options),
+            vec![
+                "--range",
+                "1-20",
+                "--option",
+                r#"directory=["{category}","{user[name]}"]"#,
+                "--zip",
+            ]
+        );
+        assert!(gallerydl_option_args(&GalleryDlOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn album_dir_is_the_common_folder_of_the_files() {
+        let files = vec![
+            "/dl/twitter/user/a/1.jpg".to_string(),
+            "/dl/twitter/user/b/2.jpg".to_string(),
+            "/dl/twitter/user/a/3.mp4".to_string(),
+        ];
+        assert_eq!(gallery_album_dir(&files, "/dl"), "/dl/twitter/user");
+        assert_eq!(gallery_album_dir(&files[..1], "/dl"), "/dl/twitter/user/a");
+        assert_eq!(gallery_album_dir(&[], "/dl"), "/dl");
+    }
+}
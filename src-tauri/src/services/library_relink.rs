@@ -0,0 +1,310 @@
+//! Finds where the files of history entries went after they were moved or renamed outside
+//! the app, by file name, size and a sampled content hash.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::types::{HistoryFileRecord, RelinkCandidate};
+
+/// Bytes hashed at the start, middle and end of a file
+const CONTENT_HASH_SAMPLE: u64 = 64 * 1024;
+
+/// Most candidates offered for one missing file
+const MAX_RELINK_CANDIDATES: usize = 10;
+
+/// SHA-256 of a file's size and of samples from its start, middle and end. Reading a few
+/// samples keeps large libraries fast, and media files that differ almost always differ in
+/// size or in their first bytes.
+pub fn library_content_hash(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut hasher = Sha256::new();
+    hasher.update(size.to_le_bytes());
+
+    let mut offsets = vec![0];
+    if size > CONTENT_HASH_SAMPLE {
+        offsets.push((size / 2).saturating_sub(CONTENT_HASH_SAMPLE / 2));
+        offsets.push(size - CONTENT_HASH_SAMPLE);
+    }
+    let mut sample = Vec::with_capacity(CONTENT_HASH_SAMPLE as usize);
+    for offset in offsets {
+        sample.clear();
+        file.seek(SeekFrom::Start(offset))?;
+        (&mut file)
+            .take(CONTENT_HASH_SAMPLE)
+            .read_to_end(&mut sample)?;
+        hasher.update(&sample);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn file_name_key(path: &Path) -> Option<String> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+}
+
+/// A file under the library folders
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibraryFile {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// The files under the library folders, looked up by file name and size. A file that a
+/// history entry was relinked to is claimed and not offered again.
+#[derive(Debug, Default)]
+pub struct LibraryFileIndex {
+    files: Vec<LibraryFile>,
+    by_name: HashMap<String, Vec<usize>>,
+    by_size: HashMap<u64, Vec<usize>>,
+    claimed: HashSet<usize>,
+}
+
+impl LibraryFileIndex {
+    pub fn new(files: Vec<LibraryFile>) -> Self {
+        let mut index = LibraryFileIndex::default();
+        for (position, file) in files.iter().enumerate() {
+            if let Some(name) = file_name_key(&file.path) {
+                index.by_name.entry(name).or_default().push(position);
+            }
+            index.by_size.entry(file.size).or_default().push(position);
+        }
+        index.files = files;
+        index
+    }
+
+    pub fn claim(&mut self, path: &Path) {
+        if let Some(position) = self.files.iter().position(|file| file.path == path) {
+            self.claimed.insert(position);
+        }
+    }
+
+    /// Unclaimed files with the same name as `record`'s file, or its size
+    fn candidates(&self, record: &HistoryFileRecord) -> Vec<&LibraryFile> {
+        let by_name = file_name_key(Path::new(&record.filepath))
+            .and_then(|name| self.by_name.get(&name))
+            .into_iter()
+            .flatten();
+        let by_size = record
+            .filesize
+            .and_then(|size| self.by_size.get(&size))
+            .into_iter()
+            .flatten();
+
+        let mut positions: Vec<usize> = by_name
+            .chain(by_size)
+            .copied()
+            .filter(|position| !self.claimed.contains(position))
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+            .into_iter()
+            .map(|position| &self.files[position])
+            .collect()
+    }
+}
+
+/// Where a missing history file went
+#[derive(Debug, Clone, PartialEq)]
+pub enum RelinkMatch {
+    /// Exactly one file has the same content, or the same name and size when no content
+    /// hash was recorded
+    Confident(RelinkCandidate),
+    /// Files that may be it, most likely first
+    Ambiguous(Vec<RelinkCandidate>),
+    NotFound,
+}
+
+/// Look for `record`'s missing file in `index`. `hash_of` gives the content hash of a
+/// file; it is only asked about files of the recorded size.
+pub fn find_moved_file(
+    index: &LibraryFileIndex,
+    record: &HistoryFileRecord,
+    mut hash_of: impl FnMut(&Path) -> Option<String>,
+) -> RelinkMatch {
+    let name = file_name_key(Path::new(&record.filepath));
+    let mut candidates: Vec<RelinkCandidate> = index
+        .candidates(record)
+        .into_iter()
+        .map(|file| {
+            let same_size = record.filesize == Some(file.size);
+            let same_content = same_size
+                && record
+                    .content_hash
+                    .as_deref()
+                    .is_some_and(|hash| hash_of(&file.path).as_deref() == Some(hash));
+            RelinkCandidate {
+                filepath: file.path.to_string_lossy().to_string(),
+                filesize: file.size,
+                same_name: name.is_some() && file_name_key(&file.path) == name,
+                same_content,
+            }
+        })
+        .collect();
+    if candidates.is_empty() {
+        return RelinkMatch::NotFound;
+    }
+
+    let confident: Vec<&RelinkCandidate> = if record.content_hash.is_some() {
+        let same_content: Vec<&RelinkCandidate> = candidates
+            .iter()
+            .filter(|candidate| candidate.same_content)
+            .collect();
+        if same_content.len() > 1 {
+            // Copies of the same file: the one that kept its name wins
+            same_content
+                .into_iter()
+                .filter(|candidate| candidate.same_name)
+                .collect()
+        } else {
+            same_content
+        }
+    } else {
+        candidates
+            .iter()
+            .filter(|candidate| candidate.same_name && record.filesize == Some(candidate.filesize))
+            .collect()
+    };
+    if let [candidate] = confident.as_slice() {
+        return RelinkMatch::Confident((*candidate).clone());
+    }
+
+    candidates.sort_by_key(|candidate| {
+        (
+            !candidate.same_content,
+            !candidate.same_name,
+            record.filesize != Some(candidate.filesize),
+        )
+    });
+    candidates.truncate(MAX_RELINK_CANDIDATES);
+    RelinkMatch::Ambiguous(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(
+        filepath: &str,
+        filesize: Option<u64>,
+        content_hash: Option<&str>,
+    ) -> HistoryFileRecord {
+        HistoryFileRecord {
+            id: "entry".to_string(),
+            title: "Entry".to_string(),
+            filepath: filepath.to_string(),
+            filesize,
+            content_hash: content_hash.map(str::to_string),
+        }
+    }
+
+    fn file(path: &str, size: u64) -> LibraryFile {
+        LibraryFile {
+            path: PathBuf::from(path),
+            size,
+        }
+    }
+
+    #[test]
+    fn content_hash_finds_renamed_files() {
+        let mut index = LibraryFileIndex::new(vec![
+            file("/lib/new name.mp4", 500),
+            file("/lib/other.mp4", 500),
+            file("/lib/Clip.mp4", 900),
+        ]);
+        let hashes = |path: &Path| match path.to_str() {
+            Some("/lib/new name.mp4") => Some("aaa".to_string()),
+            _ => Some("bbb".to_string()),
+        };
+
+        let renamed = find_moved_file(
+            &index,
+            &record("/old/clip.mp4", Some(500), Some("aaa")),
+            hashes,
+        );
+        let RelinkMatch::Confident(found) = renamed else {
+            panic!("expected a confident match, got {:?}", renamed);
+        };
+        assert_eq!(found.filepath, "/lib/new name.mp4");
+        assert!(found.same_content && !found.same_name);
+
+        // A file with the same name but other content is only offered
+        let edited = find_moved_file(
+            &index,
+            &record("/old/clip.mp4", Some(900), Some("ccc")),
+            hashes,
+        );
+        let RelinkMatch::Ambiguous(candidates) = edited else {
+            panic!("expected candidates, got {:?}", edited);
+        };
+        assert_eq!(candidates[0].filepath, "/lib/Clip.mp4");
+        assert!(candidates[0].same_name && !candidates[0].same_content);
+
+        index.claim(Path::new("/lib/new name.mp4"));
+        let claimed = find_moved_file(
+            &index,
+            &record("/old/clip.mp4", Some(500), Some("aaa")),
+            hashes,
+        );
+        let RelinkMatch::Ambiguous(candidates) = claimed else {
+            panic!("expected candidates, got {:?}", claimed);
+        };
+        assert!(candidates
+            .iter()
+            .all(|candidate| candidate.filepath != "/lib/new name.mp4"));
+    }
+
+    #[test]
+    fn without_a_hash_name_and_size_must_both_match() {
+        let index = LibraryFileIndex::new(vec![
+            file("/a/song.mp3", 300),
+            file("/b/song.mp3", 301),
+            file("/c/intro.mp3", 300),
+        ]);
+        let no_hash = |_: &Path| -> Option<String> { panic!("no hash was recorded") };
+
+        let moved = find_moved_file(&index, &record("/old/Song.mp3", Some(300), None), no_hash);
+        assert_eq!(
+            moved,
+            RelinkMatch::Confident(RelinkCandidate {
+                filepath: "/a/song.mp3".to_string(),
+                filesize: 300,
+                same_name: true,
+                same_content: false,
+            })
+        );
+
+        let unknown_size = find_moved_file(&index, &record("/old/song.mp3", None, None), no_hash);
+        assert!(
+            matches!(unknown_size, RelinkMatch::Ambiguous(ref candidates) if candidates.len() == 2)
+        );
+        assert_eq!(
+            find_moved_file(&index, &record("/old/gone.mp3", Some(1), None), no_hash),
+            RelinkMatch::NotFound
+        );
+    }
+
+    #[test]
+    fn content_hash_covers_size_and_samples() {
+        let dir = std::env::temp_dir().join(format!("youwee-relink-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let large = vec![7u8; (CONTENT_HASH_SAMPLE * 3) as usize];
+        let mut changed_end = large.clone();
+        *changed_end.last_mut().expect("last byte") = 8;
+        for (name, bytes) in [("a", &large), ("b", &large), ("c", &changed_end)] {
+            std::fs::write(dir.join(name), bytes).expect("write temp file");
+        }
+        std::fs::write(dir.join("short"), b"hello").expect("write temp file");
+
+        let hash = |name: &str| library_content_hash(&dir.join(name)).expect("hash file");
+        assert_eq!(hash("a"), hash("b"));
+        assert_ne!(hash("a"), hash("c"));
+        assert_eq!(hash("short").len(), 64);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
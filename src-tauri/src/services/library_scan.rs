@@ -0,0 +1,399 @@
+//! Library scanner: finds the media files under a folder and reads what is known about
+//! them from yt-dlp `.info.json` sidecars, embedded tags and ffprobe.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Deserialize;
+use tauri::AppHandle;
+use tokio::process::Command;
+
+use super::get_ffprobe_path;
+use crate::types::ImportedHistoryEntry;
+use crate::utils::CommandExt;
+
+const LIBRARY_MEDIA_EXTENSIONS: &[&str] = &[
+    "mp4", "mkv", "webm", "mov", "m4v", "avi", "flv", "wmv", "ts", "3gp", "mp3", "m4a", "opus",
+    "ogg", "oga", "flac", "wav", "aac", "wma",
+];
+
+/// Format files yt-dlp downloads before merging them, e.g. `clip.f137.mp4`
+static FORMAT_PART_STEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\.f\d+(-\d+)?$").expect("valid regex"));
+
+/// The ` [id]` yt-dlp's default output template puts after the title
+static TITLE_ID_SUFFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*\[[A-Za-z0-9_-]{6,}\]$").expect("valid regex"));
+
+/// What a library scan knows about a media file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LibraryMediaInfo {
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub thumbnail: Option<String>,
+    /// Seconds
+    pub duration: Option<f64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub video: bool,
+    pub audio: bool,
+}
+
+impl LibraryMediaInfo {
+    /// Fill what this source doesn't know from `other`.
+    pub fn or(self, other: LibraryMediaInfo) -> LibraryMediaInfo {
+        let has_size = self.width.is_some() && self.height.is_some();
+        let has_streams = self.video || self.audio;
+        LibraryMediaInfo {
+            title: self.title.or(other.title),
+            url: self.url.or(other.url),
+            thumbnail: self.thumbnail.or(other.thumbnail),
+            duration: self.duration.or(other.duration),
+            width: if has_size { self.width } else { other.width },
+            height: if has_size { self.height } else { other.height },
+            video: if has_streams { self.video } else { other.video },
+            audio: if has_streams { self.audio } else { other.audio },
+        }
+    }
+}
+
+fn is_library_media_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    if name.starts_with('.') || name.contains(".temp.") {
+        return false;
+    }
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("");
+    if FORMAT_PART_STEM.is_match(stem) {
+        return false;
+    }
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| LIBRARY_MEDIA_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Media files under `root`, sorted. Hidden folders and symbolic links are not followed,
+/// and the walk stops early once `cancelled` returns true.
+pub fn collect_library_media_files(root: &Path, cancelled: impl Fn() -> bool) -> Vec<PathBuf> {
+    walk_library_folder(root, cancelled, is_library_media_file)
+}
+
+/// Every file under `root` that isn't hidden, sorted, walked like
+/// [`collect_library_media_files`].
+pub fn collect_library_files(root: &Path, cancelled: impl Fn() -> bool) -> Vec<PathBuf> {
+    walk_library_folder(root, cancelled, |path| {
+        path.file_name()
+            .is_some_and(|name| !name.to_string_lossy().starts_with('.'))
+    })
+}
+
+fn walk_library_folder(
+    root: &Path,
+    cancelled: impl Fn() -> bool,
+    keep: impl Fn(&Path) -> bool,
+) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut folders = vec![root.to_path_buf()];
+
+    while let Some(folder) = folders.pop() {
+        if cancelled() {
+            break;
+        }
+        let entries = match std::fs::read_dir(&folder) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Library scan cannot read {}: {}", folder.display(), e);
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                let hidden = entry.file_name().to_string_lossy().starts_with('.');
+                if !hidden {
+                    folders.push(path);
+                }
+            } else if file_type.is_file() && keep(&path) {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/// The `.info.json` yt-dlp wrote next to a media file, if any
+pub fn info_json_sidecar(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_str()?;
+    let name = path.file_name()?.to_str()?;
+    [format!("{}.info.json", stem), format!("{}.info.json", name)]
+        .into_iter()
+        .map(|sidecar| path.with_file_name(sidecar))
+        .find(|sidecar| sidecar.is_file())
+}
+
+fn non_empty(value: Option<&serde_json::Value>) -> Option<String> {
+    value
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn http_url(value: &str) -> Option<String> {
+    let value = value.trim();
+    let is_url = (value.starts_with("https://") || value.starts_with("http://"))
+        && !value.contains(char::is_whitespace);
+    is_url.then(|| value.to_string())
+}
+
+/// Read a yt-dlp `.info.json` sidecar.
+pub fn parse_info_json(json: &str) -> Option<LibraryMediaInfo> {
+    let info: serde_json::Value = serde_json::from_str(json).ok()?;
+    let info = info.as_object()?;
+    let has_codec = |key: &str| non_empty(info.get(key)).is_some_and(|codec| codec != "none");
+
+    Some(LibraryMediaInfo {
+        title: non_empty(info.get("title")).or_else(|| non_empty(info.get("fulltitle"))),
+        url: non_empty(info.get("webpage_url"))
+            .or_else(|| non_empty(info.get("original_url")))
+            .and_then(|url| http_url(&url)),
+        thumbnail: non_empty(info.get("thumbnail")).and_then(|url| http_url(&url)),
+        duration: info.get("duration").and_then(|value| value.as_f64()),
+        width: info
+            .get("width")
+            .and_then(|value| value.as_u64())
+            .map(|value| value as u32),
+        height: info
+            .get("height")
+            .and_then(|value| value.as_u64())
+            .map(|value| value as u32),
+        video: has_codec("vcodec"),
+        audio: has_codec("acodec"),
+    })
+}
+
+#[derive(Deserialize)]
+struct FfprobeOutput {
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+    format: Option<FfprobeFormat>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeStream {
+    codec_type: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    #[serde(default)]
+    disposition: FfprobeDisposition,
+}
+
+#[derive(Deserialize, Default)]
+struct FfprobeDisposition {
+    #[serde(default)]
+    attached_pic: u8,
+}
+
+#[derive(Deserialize)]
+struct FfprobeFormat {
+    duration: Option<String>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+}
+
+/// Read `ffprobe -print_format json -show_format -show_streams` output. yt-dlp's
+/// `--embed-metadata` stores the page URL in the `purl` and `comment` tags.
+pub fn parse_ffprobe_library_info(json: &str) -> Option<LibraryMediaInfo> {
+    let output: FfprobeOutput = serde_json::from_str(json).ok()?;
+    let tags: HashMap<String, String> = output
+        .format
+        .as_ref()
+        .map(|format| {
+            format
+                .tags
+                .iter()
+                .map(|(key, value)| (key.to_ascii_lowercase(), value.trim().to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let video = output.streams.iter().find(|stream| {
+        stream.codec_type.as_deref() == Some("video") && stream.disposition.attached_pic == 0
+    });
+
+    Some(LibraryMediaInfo {
+        title: tags.get("title").filter(|title| !title.is_empty()).cloned(),
+        url: ["purl", "comment", "description"]
+            .iter()
+            .find_map(|key| tags.get(*key).and_then(|value| http_url(value))),
+        thumbnail: None,
+        duration: output
+            .format
+            .and_then(|format| format.duration)
+            .and_then(|duration| duration.parse().ok()),
+        width: video.and_then(|stream| stream.width),
+        height: video.and_then(|stream| stream.height),
+        video: video.is_some(),
+        audio: output
+            .streams
+            .iter()
+            .any(|stream| stream.codec_type.as_deref() == Some("audio")),
+    })
+}
+
+/// Read a media file with ffprobe. `None` without a working ffprobe or for unreadable files.
+pub async fn probe_library_file(app: &AppHandle, path: &Path) -> Option<LibraryMediaInfo> {
+    let ffprobe_path = get_ffprobe_path(app).await?;
+    let mut cmd = Command::new(&ffprobe_path);
+    cmd.args([
+        "-v",
+        "quiet",
+        "-print_format",
+        "json",
+        "-show_format",
+        "-show_streams",
+    ])
+    .arg(path);
+    cmd.hide_window();
+    let output = cmd.output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_ffprobe_library_info(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Quality label of a file, as downloads record it: `Audio`, `1080p`, `4K`...
+fn quality_label(info: &LibraryMediaInfo) -> Option<String> {
+    if !info.video {
+        return info.audio.then(|| "Audio".to_string());
+    }
+    // The short side, so vertical videos get the same label as landscape ones
+    let lines = match (info.width, info.height) {
+        (Some(width), Some(height)) => width.min(height),
+        (_, Some(height)) => height,
+        _ => return None,
+    };
+    Some(match lines {
+        4320.. => "8K".to_string(),
+        2160.. => "4K".to_string(),
+        1440.. => "2K".to_string(),
+        lines => format!("{}p", lines),
+    })
+}
+
+/// The history entry of a media file found by a scan. Files nothing is known about get their
+/// file name as title and no URL.
+pub fn build_imported_entry(
+    path: &Path,
+    info: &LibraryMediaInfo,
+    filesize: Option<u64>,
+    modified_at: i64,
+) -> ImportedHistoryEntry {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let title = info
+        .title
+        .clone()
+        .unwrap_or_else(|| TITLE_ID_SUFFIX.replace(&stem, "").to_string());
+
+    ImportedHistoryEntry {
+        url: info.url.clone().unwrap_or_default(),
+        title: if title.is_empty() { stem } else { title },
+        thumbnail: info.thumbnail.clone(),
+        filepath: path.to_string_lossy().to_string(),
+        filesize,
+        duration: info
+            .duration
+            .filter(|duration| *duration > 0.0)
+            .map(|duration| duration.round() as u64),
+        quality: quality_label(info),
+        format: path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase()),
+        source: None,
+        downloaded_at: modified_at,
+        content_hash: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_partial_and_hidden_files() {
+        assert!(is_library_media_file(Path::new(
+            "/m/Clip [abc123def45].mp4"
+        )));
+        assert!(is_library_media_file(Path::new("/m/song.FLAC")));
+        assert!(!is_library_media_file(Path::new("/m/clip.f137.mp4")));
+        assert!(!is_library_media_file(Path::new("/m/clip.temp.mkv")));
+        assert!(!is_library_media_file(Path::new("/m/clip.mp4.part")));
+        assert!(!is_library_media_file(Path::new("/m/.clip.mp4")));
+        assert!(!is_library_media_file(Path::new("/m/clip.info.json")));
+    }
+
+    #[test]
+    fn merges_embedded_sidecar_and_file_details() {
+        let sidecar = parse_info_json(
+            r#"{"title": "Sidecar title", "webpage_url": "https://www.youtube.com/watch?v=abc123def45",
+                "thumbnail": "https://i.ytimg.com/vi/abc123def45/hq.jpg", "duration": 212,
+                "vcodec": "none", "acodec": "opus", "width": 640, "height": 360}"#,
+        )
+        .expect("info.json");
+        let embedded = parse_ffprobe_library_info(
+            r#"{
+                "streams": [
+                    {"codec_type": "video", "width": 1080, "height": 1920, "disposition": {"attached_pic": 0}},
+                    {"codec_type": "audio", "disposition": {"attached_pic": 0}}
+                ],
+                "format": {"duration": "211.6", "tags": {"TITLE": "Embedded", "comment": "Not a URL"}}
+            }"#,
+        )
+        .expect("ffprobe output");
+
+        let info = embedded.or(sidecar);
+        assert_eq!(info.title.as_deref(), Some("Embedded"));
+        assert_eq!(
+            info.url.as_deref(),
+            Some("https://www.youtube.com/watch?v=abc123def45")
+        );
+        assert_eq!(
+            info.thumbnail.as_deref(),
+            Some("https://i.ytimg.com/vi/abc123def45/hq.jpg")
+        );
+        assert!(info.video);
+
+        let entry = build_imported_entry(
+            Path::new("/m/Clip [abc123def45].MP4"),
+            &info,
+            Some(1024),
+            1_700_000_000,
+        );
+        assert_eq!(entry.quality.as_deref(), Some("1080p"));
+        assert_eq!(entry.format.as_deref(), Some("mp4"));
+        assert_eq!(entry.duration, Some(212));
+
+        let bare = build_imported_entry(
+            Path::new("/m/Clip [abc123def45].mp3"),
+            &LibraryMediaInfo::default(),
+            None,
+            0,
+        );
+        assert_eq!(bare.title, "Clip");
+        assert_eq!(bare.url, "");
+        assert_eq!(bare.quality, None);
+    }
+}
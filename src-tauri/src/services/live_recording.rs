@@ -0,0 +1,366 @@
+//! Live recordings: polling an upcoming stream until it goes live, and cutting long
+//! recordings into parts. The recorder loop runs in `commands::live_recording`.
+
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+
+use super::{
+    build_cookie_args, build_proxy_args, build_site_header_args, get_deno_path, get_ffmpeg_path,
+    is_upcoming_live_error, parse_ytdlp_error, run_ytdlp_with_stderr,
+};
+use crate::types::{BackendError, DownloadOptions};
+use crate::utils::{ChapterPart, CommandExt};
+
+/// First delay between polls of a stream that hasn't started
+const CHECK_BASE_MS: i64 = 30_000;
+/// Longest delay between polls once the announced start has passed
+const CHECK_MAX_MS: i64 = 5 * 60_000;
+/// Polling picks up again this long before an announced start
+const RELEASE_LEAD_MS: i64 = 2 * 60_000;
+/// Longest sleep before an announced start, so a moved start time is noticed
+const RELEASE_MAX_WAIT_MS: i64 = 60 * 60_000;
+
+/// `live_status` yt-dlp reports for a URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveStatus {
+    Upcoming,
+    Live,
+    /// Ended, still being processed by the site
+    PostLive,
+    /// Ended and available as a video
+    WasLive,
+    /// A regular video
+    NotLive,
+}
+
+/// What polling a live URL found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveProbe {
+    pub status: LiveStatus,
+    /// Announced start (ms)
+    pub release_at: Option<i64>,
+    pub title: Option<String>,
+}
+
+/// Parse the `--print "%(live_status)s %(release_timestamp)s %(title)s"` line of a probe.
+pub fn parse_live_probe(stdout: &str) -> Option<LiveProbe> {
+    stdout.lines().find_map(|line| {
+        let mut fields = line.trim().splitn(3, ' ');
+        let status = match fields.next()? {
+            "is_upcoming" => LiveStatus::Upcoming,
+            "is_live" => LiveStatus::Live,
+            "post_live" => LiveStatus::PostLive,
+            "was_live" => LiveStatus::WasLive,
+            // Sites without live streams leave the field empty
+            "not_live" | "NA" => LiveStatus::NotLive,
+            _ => return None,
+        };
+        let release_at = fields
+            .next()
+            .and_then(|value| value.parse::<i64>().ok())
+            .map(|secs| secs * 1000);
+        let title = fields
+            .next()
+            .map(str::trim)
+            .filter(|title| !title.is_empty() && *title != "NA")
+            .map(str::to_string);
+        Some(LiveProbe {
+            status,
+            release_at,
+            title,
+        })
+    })
+}
+
+/// When to poll a stream that hasn't started again, and the poll count to store.
+/// Polls back off from 30s to 5 minutes; a start announced further ahead is slept
+/// towards instead (at most an hour at a time), and the backoff starts over there.
+pub fn plan_live_check(checks: u32, release_at: Option<i64>, now: i64) -> (i64, u32) {
+    if let Some(release_at) = release_at {
+        let wake_at = release_at - RELEASE_LEAD_MS;
+        if wake_at > now + CHECK_BASE_MS {
+            return (wake_at.min(now + RELEASE_MAX_WAIT_MS), 0);
+        }
+    }
+    let delay = (CHECK_BASE_MS << checks.min(4)).min(CHECK_MAX_MS);
+    (now + delay, checks + 1)
+}
+
+/// Output template of a later recording session of a stream, so a stream that drops
+/// and comes back doesn't overwrite the earlier recording.
+pub fn session_output_template(template: &str, session: u32) -> String {
+    if session <= 1 {
+        return template.to_string();
+    }
+    match template.strip_suffix(".%(ext)s") {
+        Some(stem) => format!("{} (session {}).%(ext)s", stem, session),
+        None => format!("{} (session {})", template, session),
+    }
+}
+
+/// ffmpeg output pattern for the parts of `input`: `<stem> - part %03d.<ext>` next to it
+fn segment_pattern(input: &Path) -> Option<String> {
+    let stem = input.file_stem()?.to_str()?;
+    let ext = input.extension()?.to_str()?;
+    // `%` is the only character the segment muxer treats specially
+    let name = format!("{} - part %03d.{}", stem.replace('%', "%%"), ext);
+    Some(input.with_file_name(name).to_string_lossy().to_string())
+}
+
+/// Path of part `index` (1-based) of `input`
+fn segment_part_path(input: &Path, index: u32) -> Option<PathBuf> {
+    let stem = input.file_stem()?.to_str()?;
+    let ext = input.extension()?.to_str()?;
+    Some(input.with_file_name(format!("{} - part {:03}.{}", stem, index, ext)))
+}
+
+/// ffmpeg arguments cutting `input` into stream-copied parts of `segment_secs`.
+/// Parts start at keyframes, so their lengths are approximate.
+pub fn segment_args(input: &str, pattern: &str, segment_secs: u32) -> Vec<String> {
+    [
+        "-hide_banner",
+        "-loglevel",
+        "error",
+        "-y",
+        "-i",
+        input,
+        // Cover art is left out; the segment muxer can't repeat it per part
+        "-map",
+        "0:V?",
+        "-map",
+        "0:a?",
+        "-c",
+        "copy",
+        "-f",
+        "segment",
+        "-segment_time",
+        &segment_secs.to_string(),
+        "-segment_start_number",
+        "1",
+        "-reset_timestamps",
+        "1",
+        pattern,
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect()
+}
+
+fn existing_parts(input: &Path) -> Vec<ChapterPart> {
+    (1..)
+        .map_while(|index| {
+            let path = segment_part_path(input, index)?;
+            path.exists().then(|| ChapterPart {
+                index,
+                filepath: path.to_string_lossy().to_string(),
+            })
+        })
+        .collect()
+}
+
+fn remove_parts(parts: &[ChapterPart]) {
+    for part in parts {
+        std::fs::remove_file(&part.filepath).ok();
+    }
+}
+
+/// Cut a finished download into parts of `segment_secs` and delete the full file once they
+/// are written. A file no longer than one part, or a failed cut, is kept and gives no parts.
+pub async fn split_into_segments(
+    app: &AppHandle,
+    input: &str,
+    segment_secs: u32,
+) -> Vec<ChapterPart> {
+    let input_path = Path::new(input);
+    let Some(pattern) = segment_pattern(input_path) else {
+        return Vec::new();
+    };
+    let Some(ffmpeg_path) = get_ffmpeg_path(app).await else {
+        log::warn!("FFmpeg not found, not splitting {} into parts", input);
+        return Vec::new();
+    };
+
+    // Parts of an earlier, longer recording under the same name would be picked up
+    remove_parts(&existing_parts(input_path));
+
+    let mut cmd = Command::new(&ffmpeg_path);
+    cmd.args(segment_args(input, &pattern, segment_secs));
+    cmd.hide_window();
+    let succeeded = match cmd.output().await {
+        Ok(output) if output.status.success() => true,
+        Ok(output) => {
+            log::warn!(
+                "Failed to split {} into parts: {}",
+                input,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            false
+        }
+        Err(e) => {
+            log::warn!("Failed to run ffmpeg to split {}: {}", input, e);
+            false
+        }
+    };
+
+    let parts = existing_parts(input_path);
+    if !succeeded || parts.len() < 2 {
+        remove_parts(&parts);
+        return Vec::new();
+    }
+    if let Err(e) = std::fs::remove_file(input_path) {
+        log::warn!("Failed to remove {} after splitting it: {}", input, e);
+    }
+    parts
+}
+
+/// Ask yt-dlp whether `url` is live, upcoming or over, without downloading it.
+pub async fn probe_live_status(
+    app: &AppHandle,
+    url: &str,
+    options: &DownloadOptions,
+) -> Result<LiveProbe, String> {
+    let mut args = vec![
+        "--skip-download".to_string(),
+        "--no-playlist".to_string(),
+        "--ignore-no-formats-error".to_string(),
+        "--no-warnings".to_string(),
+        "--socket-timeout".to_string(),
+        "15".to_string(),
+        "--print".to_string(),
+        "%(live_status)s %(release_timestamp)s %(title)s".to_string(),
+    ];
+    if url.contains("youtube.com") || url.contains("youtu.be") {
+        if let Some(deno_path) = get_deno_path(app).await {
+            args.push("--js-runtimes".to_string());
+            args.push(format!("deno:{}", deno_path.to_string_lossy()));
+        }
+    }
+    args.extend(build_site_header_args(url));
+    args.extend(build_cookie_args(
+        options.cookie_mode.as_deref(),
+        options.cookie_browser.as_deref(),
+        options.cookie_browser_profile.as_deref(),
+        options.cookie_file_path.as_deref(),
+    ));
+    args.extend(build_proxy_args(options.proxy_url.as_deref()));
+    args.push("--".to_string());
+    args.push(url.to_string());
+
+    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = timeout(
+        Duration::from_secs(60),
+        run_ytdlp_with_stderr(app, &args_ref),
+    )
+    .await
+    .map_err(|_| "Timed out checking the live stream".to_string())??;
+
+    if let Some(probe) = parse_live_probe(&output.stdout) {
+        return Ok(probe);
+    }
+    // Some sites refuse to extract a stream that hasn't started
+    if is_upcoming_live_error(&output.stderr) {
+        return Ok(LiveProbe {
+            status: LiveStatus::Upcoming,
+            release_at: None,
+            title: None,
+        });
+    }
+    let error = parse_ytdlp_error(&output.stderr).unwrap_or_else(|| {
+        BackendError::from_message(format!(
+            "Failed to check the live stream: {}",
+            output.stderr.trim()
+        ))
+    });
+    Err(error.message().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_probe_lines() {
+        assert_eq!(
+            parse_live_probe("is_upcoming 1772600400 Launch stream\n"),
+            Some(LiveProbe {
+                status: LiveStatus::Upcoming,
+                release_at: Some(1_772_600_400_000),
+                title: Some("Launch stream".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_live_probe("is_live NA NA"),
+            Some(LiveProbe {
+                status: LiveStatus::Live,
+                release_at: None,
+                title: None,
+            })
+        );
+        assert_eq!(
+            parse_live_probe("NA NA Some clip").map(|probe| probe.status),
+            Some(LiveStatus::NotLive)
+        );
+        assert_eq!(parse_live_probe("[youtube] abc: Downloading webpage"), None);
+    }
+
+    #[test]
+    fn polling_backs_off_and_waits_for_an_announced_start() {
+        let now = 1_000_000_000;
+        assert_eq!(plan_live_check(0, None, now), (now + 30_000, 1));
+        assert_eq!(plan_live_check(1, None, now), (now + 60_000, 2));
+        assert_eq!(plan_live_check(9, None, now), (now + CHECK_MAX_MS, 10));
+
+        // A start three hours away is slept towards an hour at a time.
+        let release_at = now + 3 * 3_600_000;
+        assert_eq!(
+            plan_live_check(5, Some(release_at), now),
+            (now + RELEASE_MAX_WAIT_MS, 0)
+        );
+        // Close to the start, polling wakes just before it.
+        let release_at = now + 10 * 60_000;
+        assert_eq!(
+            plan_live_check(0, Some(release_at), now),
+            (release_at - RELEASE_LEAD_MS, 0)
+        );
+        // A start that has passed falls back to the backoff.
+        assert_eq!(
+            plan_live_check(2, Some(now - 60_000), now),
+            (now + 120_000, 3)
+        );
+    }
+
+    #[test]
+    fn later_sessions_get_their_own_file() {
+        assert_eq!(
+            session_output_template("%(title)s.%(ext)s", 1),
+            "%(title)s.%(ext)s"
+        );
+        assert_eq!(
+            session_output_template("%(uploader)s/%(title)s.%(ext)s", 2),
+            "%(uploader)s/%(title)s (session 2).%(ext)s"
+        );
+    }
+
+    #[test]
+    fn parts_are_named_after_the_recording() {
+        let input = Path::new("/videos/100% live.mp4");
+        assert_eq!(
+            segment_pattern(input).as_deref(),
+            Some("/videos/100%% live - part %03d.mp4")
+        );
+        assert_eq!(
+            segment_part_path(input, 2),
+            Some(PathBuf::from("/videos/100% live - part 002.mp4"))
+        );
+
+        let args = segment_args("/videos/a.mp4", "/videos/a - part %03d.mp4", 1800);
+        let time = args.iter().position(|arg| arg == "-segment_time").unwrap();
+        assert_eq!(args[time + 1], "1800");
+        assert_eq!(
+            args.last().map(String::as_str),
+            Some("/videos/a - part %03d.mp4")
+        );
+    }
+}
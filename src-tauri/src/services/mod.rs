@@ -1,19 +1,45 @@
 mod ai;
+mod bandwidth;
+mod clips;
 mod deno;
+mod download_journal;
+mod download_profiles;
+mod download_schedule;
+mod download_scheduler;
 mod ffmpeg;
 mod gallerydl;
+mod library_relink;
+mod library_scan;
+mod live_recording;
 mod plugin;
 pub mod polling;
+mod precise_cut;
+mod retry;
+mod storage;
 pub mod telegram;
+mod verify;
 mod whisper;
 mod youtube_search;
 mod ytdlp;
 
 pub use ai::*;
+pub use bandwidth::*;
+pub use clips::*;
 pub use deno::*;
+pub use download_journal::*;
+pub use download_profiles::*;
+pub use download_schedule::*;
+pub use download_scheduler::*;
 pub use ffmpeg::*;
 pub use gallerydl::*;
+pub use library_relink::*;
+pub use library_scan::*;
+pub use live_recording::*;
 pub use plugin::*;
+pub use precise_cut::*;
+pub use retry::*;
+pub use storage::*;
+pub use verify::*;
 pub use whisper::*;
 pub use youtube_search::*;
 pub use ytdlp::*;
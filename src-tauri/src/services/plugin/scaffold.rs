@@ -612,6 +612,8 @@ pub(super) fn sample_download_payload() -> PostDownloadPluginPayload {
         history_id: Some("sample-history-id".to_string()),
         time_range: None,
         download_kind: "download".to_string(),
+        relative_path: Some("sample.mp4".to_string()),
+        extra_files: Vec::new(),
         workflow_run_id: None,
         workflow_step_index: None,
         workflow_step_plugin_id: None,
@@ -108,7 +108,7 @@ pub(super) fn build_chain_state(payload: &PostDownloadPluginPayload) -> PluginCh
         filesize: payload.filesize,
         format: payload.format.clone(),
         quality: payload.quality.clone(),
-        extra_files: Vec::new(),
+        extra_files: payload.extra_files.clone(),
         metadata: None,
     }
 }
@@ -136,6 +136,8 @@ pub(super) fn payload_from_chain_state(
         history_id: chain_state.history_id.clone(),
         time_range: chain_state.time_range.clone(),
         download_kind: chain_state.download_kind.clone(),
+        relative_path: payload.relative_path.clone(),
+        extra_files: chain_state.extra_files.clone(),
         workflow_run_id: Some(workflow_run_id.to_string()),
         workflow_step_index: Some(step_index),
         workflow_step_plugin_id: Some(step_plugin_id.to_string()),
@@ -4,9 +4,10 @@ use tauri::{AppHandle, Emitter};
 
 use crate::database;
 use crate::services::{
-    build_cookie_args, build_site_header_args, get_deno_path, run_ytdlp_with_stderr,
+    build_cookie_args, build_site_header_args, get_deno_path, parse_ytdlp_error,
+    rate_limit_cooldown_remaining, run_ytdlp_with_stderr, site_key, with_retry,
 };
-use crate::types::{ChannelVideo, FollowedChannel};
+use crate::types::{BackendError, ChannelVideo, FollowedChannel};
 use crate::utils::normalize_channel_content_urls;
 
 /// Cookie/proxy configuration synced from the frontend for background polling.
@@ -36,7 +37,7 @@ pub fn set_network_config(config: PollingNetworkConfig) {
 }
 
 /// Read a snapshot of the current network config.
-fn get_network_config() -> PollingNetworkConfig {
+pub fn get_network_config() -> PollingNetworkConfig {
     POLLING_NETWORK_CONFIG
         .lock()
         .map(|g| g.clone())
@@ -78,6 +79,7 @@ pub struct AutoDownloadEvent {
     pub format: String,
     pub video_codec: String,
     pub audio_bitrate: String,
+    pub profile: Option<String>,
     pub download_threads: i64,
 }
 
@@ -129,6 +131,11 @@ pub fn start_polling(app: AppHandle) {
                     continue;
                 }
 
+                // A rate-limited site is left alone until its cooldown ends
+                if rate_limit_cooldown_remaining(&site_key(&channel.url)).is_some() {
+                    continue;
+                }
+
                 match check_channel_for_new_videos(&app, channel).await {
                     Ok(new_count) => {
                         if new_count > 0 {
@@ -169,6 +176,7 @@ pub fn start_polling(app: AppHandle) {
                                         format: channel.download_format.clone(),
                                         video_codec: channel.download_video_codec.clone(),
                                         audio_bitrate: channel.download_audio_bitrate.clone(),
+                                        profile: channel.download_profile.clone(),
                                         download_threads: channel.download_threads,
                                     },
                                 );
@@ -279,12 +287,24 @@ async fn check_channel_for_new_videos(
         args.push(channel_url);
 
         let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-
-        let output_result = run_ytdlp_with_stderr(app, &args_ref).await?;
-        if !output_result.success && output_result.stdout.is_empty() {
-            return Err("Failed to fetch channel videos".to_string());
-        }
-        output.push_str(&output_result.stdout);
+        let args_ref = &args_ref;
+
+        let stdout = with_retry(
+            "Checking channel for new videos",
+            &channel.url,
+            || async move {
+                let output_result = run_ytdlp_with_stderr(app, args_ref).await?;
+                if !output_result.success && output_result.stdout.is_empty() {
+                    let error = parse_ytdlp_error(&output_result.stderr).unwrap_or_else(|| {
+                        BackendError::from_message("Failed to fetch channel videos")
+                    });
+                    return Err(error.to_wire_string());
+                }
+                Ok(output_result.stdout)
+            },
+        )
+        .await?;
+        output.push_str(&stdout);
         if !output.ends_with('\n') {
             output.push('\n');
         }
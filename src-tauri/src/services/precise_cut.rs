@@ -0,0 +1,698 @@
+//! Frame-accurate cutting without re-encoding the whole range: the GOPs around the cut
+//! points are re-encoded, the video between the first and last keyframe inside the range
+//! is copied, and the audio is cut on its own, since it is cheap to encode.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tokio::process::Command;
+
+use super::{concat_args, concat_list, get_ffmpeg_path, get_ffprobe_path};
+use crate::utils::CommandExt;
+
+/// How far a keyframe may be from a cut point, in seconds, and still count as on it
+const KEYFRAME_TOLERANCE_SECS: f64 = 0.001;
+/// Shortest run of keyframes worth copying; below this the whole range is re-encoded
+const MIN_COPY_SECS: f64 = 1.0;
+
+/// A range of a file to cut, in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PreciseCutRange {
+    pub start: f64,
+    pub end: f64,
+}
+
+/// A piece of a precise cut, in seconds of the source file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CutSegment {
+    /// Re-encoded, so it can start or end between keyframes
+    Encode { start: f64, end: f64 },
+    /// Copied; starts on a keyframe and ends right before one
+    Copy { start: f64, end: f64 },
+}
+
+/// The video stream of the file being cut and its keyframes around the range
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CutSource {
+    pub codec: Option<String>,
+    pub profile: Option<String>,
+    pub level: Option<i64>,
+    pub pix_fmt: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub keyframes: Vec<f64>,
+}
+
+impl CutSource {
+    /// Whether pieces of both streams can be joined without re-encoding: the decoder
+    /// setup that matters stays the same across the join
+    fn joins_with(&self, other: &CutSource) -> bool {
+        self.codec == other.codec
+            && self.profile == other.profile
+            && self.pix_fmt == other.pix_fmt
+            && self.width == other.width
+            && self.height == other.height
+    }
+}
+
+#[derive(Deserialize)]
+struct FfprobeOutput {
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+    #[serde(default)]
+    packets: Vec<FfprobePacket>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeStream {
+    codec_name: Option<String>,
+    profile: Option<String>,
+    level: Option<i64>,
+    pix_fmt: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct FfprobePacket {
+    pts_time: Option<String>,
+    #[serde(default)]
+    flags: String,
+}
+
+/// Parse `ffprobe -select_streams v:0 -show_entries stream=codec_name,profile,level,pix_fmt,width,height:packet=pts_time,flags`
+/// JSON output. `None` when the file has no video stream.
+pub fn parse_cut_source(json: &str) -> Option<CutSource> {
+    let output: FfprobeOutput = serde_json::from_str(json).ok()?;
+    let stream = output.streams.into_iter().next()?;
+    let mut keyframes: Vec<f64> = output
+        .packets
+        .iter()
+        .filter(|packet| packet.flags.starts_with('K'))
+        .filter_map(|packet| packet.pts_time.as_deref()?.parse().ok())
+        .collect();
+    keyframes.sort_by(f64::total_cmp);
+    keyframes.dedup();
+    Some(CutSource {
+        codec: stream.codec_name,
+        profile: stream.profile,
+        level: stream.level.filter(|level| *level > 0),
+        pix_fmt: stream.pix_fmt,
+        width: stream.width,
+        height: stream.height,
+        keyframes,
+    })
+}
+
+/// Split `start..end` into the re-encoded head up to the first keyframe in the range,
+/// the copied middle, and the re-encoded tail from the last keyframe in the range.
+/// Without two keyframes far enough apart the whole range is re-encoded.
+pub fn plan_precise_cut(start: f64, end: f64, keyframes: &[f64]) -> Vec<CutSegment> {
+    let first = keyframes
+        .iter()
+        .copied()
+        .find(|keyframe| *keyframe >= start - KEYFRAME_TOLERANCE_SECS);
+    let last = keyframes
+        .iter()
+        .copied()
+        .rev()
+        .find(|keyframe| *keyframe <= end + KEYFRAME_TOLERANCE_SECS);
+
+    match (first, last) {
+        (Some(first), Some(last)) if last - first >= MIN_COPY_SECS => {
+            let mut segments = Vec::new();
+            if first - start > KEYFRAME_TOLERANCE_SECS {
+                segments.push(CutSegment::Encode { start, end: first });
+            }
+            segments.push(CutSegment::Copy {
+                start: first,
+                end: last,
+            });
+            if end - last > KEYFRAME_TOLERANCE_SECS {
+                segments.push(CutSegment::Encode { start: last, end });
+            }
+            segments
+        }
+        _ => vec![CutSegment::Encode { start, end }],
+    }
+}
+
+/// Encoder matching the source codec, so re-encoded pieces can be joined to copied ones.
+/// `None` for codecs without one; those ranges are re-encoded whole with x264.
+fn boundary_encoder(codec: &str) -> Option<&'static [&'static str]> {
+    match codec {
+        "h264" => Some(&["-c:v", "libx264", "-preset", "veryfast", "-crf", "18"]),
+        "hevc" => Some(&["-c:v", "libx265", "-preset", "veryfast", "-crf", "20"]),
+        "vp9" => Some(&[
+            "-c:v",
+            "libvpx-vp9",
+            "-crf",
+            "30",
+            "-b:v",
+            "0",
+            "-row-mt",
+            "1",
+            "-cpu-used",
+            "4",
+        ]),
+        "av1" => Some(&["-c:v", "libsvtav1", "-preset", "8", "-crf", "30"]),
+        _ => None,
+    }
+}
+
+const FALLBACK_ENCODER: &[&str] = &["-c:v", "libx264", "-preset", "veryfast", "-crf", "18"];
+
+/// Encoder profile name of an ffprobe profile, for the profiles the encoder can produce
+fn encoder_profile(codec: &str, profile: &str) -> Option<&'static str> {
+    match (codec, profile) {
+        ("h264", "Baseline" | "Constrained Baseline") => Some("baseline"),
+        ("h264", "Main") => Some("main"),
+        ("h264", "High") => Some("high"),
+        ("h264", "High 10") => Some("high10"),
+        ("h264", "High 4:2:2") => Some("high422"),
+        ("h264", "High 4:4:4 Predictive") => Some("high444"),
+        ("hevc", "Main") => Some("main"),
+        ("hevc", "Main 10") => Some("main10"),
+        ("vp9", "Profile 0") => Some("0"),
+        ("vp9", "Profile 1") => Some("1"),
+        ("vp9", "Profile 2") => Some("2"),
+        ("vp9", "Profile 3") => Some("3"),
+        ("av1", "Main") => Some("main"),
+        _ => None,
+    }
+}
+
+/// Arguments re-encoding boundary pieces so they can be joined to pieces copied from
+/// `source`: the same codec, profile, level, pixel format and size, with the parameter
+/// sets repeated in-band, since the concat demuxer keeps only the first piece's.
+/// `None` when the source's profile can't be produced; the range is then re-encoded whole.
+fn boundary_encoder_args(source: &CutSource) -> Option<Vec<String>> {
+    let codec = source.codec.as_deref()?;
+    let mut args: Vec<String> = boundary_encoder(codec)?
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    // ffprobe leaves the profile out for some files; the encoder default is kept then
+    if let Some(profile) = source.profile.as_deref() {
+        args.push("-profile:v".to_string());
+        args.push(encoder_profile(codec, profile)?.to_string());
+    }
+    // H.264 levels are probed as 10 * level, HEVC ones as 30 * level
+    let level = match (codec, source.level) {
+        ("h264", Some(level)) if level >= 10 => Some(format!("{}.{}", level / 10, level % 10)),
+        ("hevc", Some(level)) => Some(format!("{}.{}", level / 30, level % 30 / 3)),
+        _ => None,
+    };
+    match codec {
+        "h264" => {
+            if let Some(level) = level {
+                args.push("-level:v".to_string());
+                args.push(level);
+            }
+            args.push("-x264-params".to_string());
+            args.push("repeat-headers=1".to_string());
+        }
+        "hevc" => {
+            args.push("-x265-params".to_string());
+            args.push(match level {
+                Some(level) => format!("repeat-headers=1:level-idc={}", level),
+                None => "repeat-headers=1".to_string(),
+            });
+        }
+        _ => {}
+    }
+    args.extend(full_encoder_args(source, &[]));
+    Some(args)
+}
+
+/// Pixel format and size of `source`, after the arguments of `encoder`
+fn full_encoder_args(source: &CutSource, encoder: &[&str]) -> Vec<String> {
+    let mut args: Vec<String> = encoder.iter().map(|arg| arg.to_string()).collect();
+    if let Some(pix_fmt) = source.pix_fmt.as_deref() {
+        args.push("-pix_fmt".to_string());
+        args.push(pix_fmt.to_string());
+    }
+    if let (Some(width), Some(height)) = (source.width, source.height) {
+        args.push("-s".to_string());
+        args.push(format!("{}x{}", width, height));
+    }
+    args
+}
+
+/// Container of the pieces: MPEG-TS for H.264 and HEVC, which stores the parameter sets
+/// of copied pieces in-band too, so every piece carries its own
+fn piece_extension(codec: Option<&str>) -> &'static str {
+    match codec {
+        Some("h264" | "hevc") => "ts",
+        _ => "mkv",
+    }
+}
+
+/// ffmpeg arguments writing the video of one segment of `input` to `output`. `encoder`
+/// is only used for re-encoded segments.
+fn piece_args(input: &str, segment: CutSegment, encoder: &[String], output: &str) -> Vec<String> {
+    let (start, end) = match segment {
+        CutSegment::Encode { start, end } | CutSegment::Copy { start, end } => (start, end),
+    };
+    // A copy seeks to the keyframe at or before its start; aim just past it so
+    // rounding of the probed timestamp can't land on the keyframe before, and stop
+    // just short of the keyframe it ends on, which starts the next piece.
+    let (seek, duration) = match segment {
+        CutSegment::Copy { .. } => (
+            start + KEYFRAME_TOLERANCE_SECS,
+            end - start - 2.0 * KEYFRAME_TOLERANCE_SECS,
+        ),
+        CutSegment::Encode { .. } => (start, end - start),
+    };
+
+    let mut args: Vec<String> = [
+        "-hide_banner",
+        "-loglevel",
+        "error",
+        "-y",
+        "-ss",
+        &format!("{:.6}", seek),
+        "-i",
+        input,
+        "-t",
+        &format!("{:.6}", duration),
+        "-map",
+        "0:v:0",
+        "-an",
+        "-sn",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    match segment {
+        CutSegment::Copy { .. } => {
+            args.extend(["-c:v", "copy"].iter().map(|arg| arg.to_string()));
+        }
+        CutSegment::Encode { .. } => args.extend(encoder.iter().cloned()),
+    }
+    args.push("-avoid_negative_ts".to_string());
+    args.push("make_zero".to_string());
+    args.push(output.to_string());
+    args
+}
+
+/// ffmpeg arguments putting the joined `video` together with the audio of
+/// `start..end` of `input`, re-encoded so it starts exactly on the cut
+pub fn mux_args(video: &str, input: &str, start: f64, end: f64, output: &str) -> Vec<String> {
+    let audio_codec = match Path::new(output).extension().and_then(|ext| ext.to_str()) {
+        Some("webm") | Some("opus") => "libopus",
+        _ => "aac",
+    };
+    [
+        "-hide_banner",
+        "-loglevel",
+        "error",
+        "-y",
+        "-i",
+        video,
+        "-ss",
+        &format!("{:.6}", start),
+        "-t",
+        &format!("{:.6}", end - start),
+        "-i",
+        input,
+        "-map",
+        "0:v:0",
+        "-map",
+        "1:a?",
+        "-c:v",
+        "copy",
+        "-c:a",
+        audio_codec,
+        "-b:a",
+        "192k",
+        "-shortest",
+        output,
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect()
+}
+
+/// Working files of one cut, removed with it
+struct CutWorkDir(PathBuf);
+
+impl Drop for CutWorkDir {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.0).ok();
+    }
+}
+
+async fn run_tool(program: &Path, args: &[String]) -> Result<Vec<u8>, String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args).kill_on_drop(true);
+    cmd.hide_window();
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", program.display(), e))?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Probe the video stream of `input` and its keyframes from a little before `start` to `end`
+async fn probe_cut_source(
+    app: &AppHandle,
+    input: &str,
+    start: f64,
+    end: f64,
+) -> Result<Option<CutSource>, String> {
+    let ffprobe_path = get_ffprobe_path(app)
+        .await
+        .ok_or_else(|| "FFprobe not found".to_string())?;
+    let args: Vec<String> = [
+        "-v",
+        "error",
+        "-select_streams",
+        "v:0",
+        "-show_entries",
+        "stream=codec_name,profile,level,pix_fmt,width,height:packet=pts_time,flags",
+        "-read_intervals",
+        &format!("{:.6}%{:.6}", start, end),
+        "-print_format",
+        "json",
+        input,
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    let stdout = run_tool(&ffprobe_path, &args).await?;
+    Ok(parse_cut_source(&String::from_utf8_lossy(&stdout)))
+}
+
+/// Probe the video stream of a piece written by the cut
+async fn probe_piece(ffprobe_path: &Path, piece: &str) -> Result<Option<CutSource>, String> {
+    let args: Vec<String> = [
+        "-v",
+        "error",
+        "-select_streams",
+        "v:0",
+        "-show_entries",
+        "stream=codec_name,profile,level,pix_fmt,width,height",
+        "-print_format",
+        "json",
+        piece,
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    let stdout = run_tool(ffprobe_path, &args).await?;
+    Ok(parse_cut_source(&String::from_utf8_lossy(&stdout)))
+}
+
+/// Whether the re-encoded pieces came out like the copied ones. Encoders don't produce
+/// every profile and level, so the cut falls back to re-encoding the whole range otherwise.
+async fn pieces_join(
+    app: &AppHandle,
+    pieces: &[String],
+    segments: &[CutSegment],
+) -> Result<bool, String> {
+    let ffprobe_path = get_ffprobe_path(app)
+        .await
+        .ok_or_else(|| "FFprobe not found".to_string())?;
+    let mut streams = Vec::new();
+    for piece in pieces {
+        match probe_piece(&ffprobe_path, piece).await? {
+            Some(stream) => streams.push(stream),
+            None => return Ok(false),
+        }
+    }
+    let Some(copied) = segments
+        .iter()
+        .position(|segment| matches!(segment, CutSegment::Copy { .. }))
+    else {
+        return Ok(true);
+    };
+    Ok(streams
+        .iter()
+        .all(|stream| stream.joins_with(&streams[copied])))
+}
+
+/// Cut `range` of `input` into `output` to the frame. `progress` is called with the share
+/// of the work done, from 0 to 1. Dropping the future stops ffmpeg and removes the
+/// working files; the caller removes a partial `output`.
+pub async fn precise_cut(
+    app: &AppHandle,
+    input: &str,
+    range: PreciseCutRange,
+    output: &str,
+    mut progress: impl FnMut(f64),
+) -> Result<(), String> {
+    if range.end <= range.start {
+        return Err("The end of the cut must be after its start".to_string());
+    }
+    let ffmpeg_path = get_ffmpeg_path(app)
+        .await
+        .ok_or_else(|| "FFmpeg not found".to_string())?;
+
+    let Some(source) = probe_cut_source(app, input, range.start, range.end).await? else {
+        // Audio packets are short, so a copy is already as exact as a cut gets
+        let args: Vec<String> = [
+            "-hide_banner",
+            "-loglevel",
+            "error",
+            "-y",
+            "-ss",
+            &format!("{:.6}", range.start),
+            "-i",
+            input,
+            "-t",
+            &format!("{:.6}", range.end - range.start),
+            "-map",
+            "0:a",
+            "-c",
+            "copy",
+            output,
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        run_tool(&ffmpeg_path, &args).await?;
+        progress(1.0);
+        return Ok(());
+    };
+
+    let encoder = boundary_encoder_args(&source);
+    let segments = match encoder {
+        Some(_) => plan_precise_cut(range.start, range.end, &source.keyframes),
+        None => vec![CutSegment::Encode {
+            start: range.start,
+            end: range.end,
+        }],
+    };
+    let whole_encoder = full_encoder_args(
+        &source,
+        source
+            .codec
+            .as_deref()
+            .and_then(boundary_encoder)
+            .unwrap_or(FALLBACK_ENCODER),
+    );
+    let encoder = encoder.unwrap_or_else(|| whole_encoder.clone());
+
+    let work_dir =
+        CutWorkDir(std::env::temp_dir().join(format!("youwee-cut-{}", uuid::Uuid::new_v4())));
+    std::fs::create_dir_all(&work_dir.0)
+        .map_err(|e| format!("Failed to create a working folder: {}", e))?;
+    let extension = piece_extension(source.codec.as_deref());
+
+    // One step per segment, then joining and adding the audio
+    let steps = segments.len() as f64 + 2.0;
+    let mut pieces = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        let piece = work_dir
+            .0
+            .join(format!("piece-{}.{}", index, extension))
+            .to_string_lossy()
+            .to_string();
+        run_tool(&ffmpeg_path, &piece_args(input, *segment, &encoder, &piece)).await?;
+        pieces.push(piece);
+        progress((index + 1) as f64 / steps);
+    }
+
+    if pieces.len() > 1 && !pieces_join(app, &pieces, &segments).await? {
+        log::warn!(
+            "Re-encoded pieces of {} differ from the copied video; re-encoding the whole range",
+            input
+        );
+        let piece = work_dir
+            .0
+            .join(format!("whole.{}", extension))
+            .to_string_lossy()
+            .to_string();
+        let whole = CutSegment::Encode {
+            start: range.start,
+            end: range.end,
+        };
+        run_tool(
+            &ffmpeg_path,
+            &piece_args(input, whole, &whole_encoder, &piece),
+        )
+        .await?;
+        pieces = vec![piece];
+    }
+
+    let list = work_dir.0.join("pieces.txt");
+    std::fs::write(&list, concat_list(&pieces))
+        .map_err(|e| format!("Failed to write the piece list: {}", e))?;
+    let video = work_dir.0.join("video.mkv").to_string_lossy().to_string();
+    run_tool(&ffmpeg_path, &concat_args(&list.to_string_lossy(), &video)).await?;
+    progress((steps - 1.0) / steps);
+
+    run_tool(
+        &ffmpeg_path,
+        &mux_args(&video, input, range.start, range.end, output),
+    )
+    .await?;
+    progress(1.0);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_reencodes_only_around_the_cut_points() {
+        let keyframes = [0.0, 2.0, 4.0, 6.0, 8.0, 10.0];
+        assert_eq!(
+            plan_precise_cut(1.5, 9.0, &keyframes),
+            vec![
+                CutSegment::Encode {
+                    start: 1.5,
+                    end: 2.0
+                },
+                CutSegment::Copy {
+                    start: 2.0,
+                    end: 8.0
+                },
+                CutSegment::Encode {
+                    start: 8.0,
+                    end: 9.0
+                },
+            ]
+        );
+        // Cut points on keyframes need no re-encoding
+        assert_eq!(
+            plan_precise_cut(2.0, 8.0, &keyframes),
+            vec![CutSegment::Copy {
+                start: 2.0,
+                end: 8.0
+            }]
+        );
+        // No room for a copy between the keyframes in the range
+        assert_eq!(
+            plan_precise_cut(2.5, 4.5, &keyframes),
+            vec![CutSegment::Encode {
+                start: 2.5,
+                end: 4.5
+            }]
+        );
+        assert_eq!(
+            plan_precise_cut(1.0, 3.0, &[]),
+            vec![CutSegment::Encode {
+                start: 1.0,
+                end: 3.0
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_keyframes_from_ffprobe() {
+        let json = r#"{
+            "packets": [
+                {"pts_time": "4.004000", "flags": "K__"},
+                {"pts_time": "4.037367", "flags": "___"},
+                {"pts_time": "0.000000", "flags": "K_"},
+                {"flags": "K__"}
+            ],
+            "streams": [{"codec_name": "h264", "profile": "High", "level": 41,
+                "pix_fmt": "yuv420p", "width": 1920, "height": 1080}]
+        }"#;
+        assert_eq!(
+            parse_cut_source(json),
+            Some(CutSource {
+                codec: Some("h264".to_string()),
+                profile: Some("High".to_string()),
+                level: Some(41),
+                pix_fmt: Some("yuv420p".to_string()),
+                width: Some(1920),
+                height: Some(1080),
+                keyframes: vec![0.0, 4.004],
+            })
+        );
+        assert_eq!(parse_cut_source(r#"{"streams": []}"#), None);
+    }
+
+    #[test]
+    fn copies_seek_past_the_keyframe_and_encodes_match_the_source() {
+        let source = CutSource {
+            codec: Some("hevc".to_string()),
+            profile: Some("Main 10".to_string()),
+            level: Some(123),
+            pix_fmt: Some("yuv420p10le".to_string()),
+            width: Some(3840),
+            height: Some(2160),
+            keyframes: Vec::new(),
+        };
+        let encoder = boundary_encoder_args(&source).expect("hevc encoder");
+
+        let copy = piece_args(
+            "/v/in.mp4",
+            CutSegment::Copy {
+                start: 2.0,
+                end: 8.0,
+            },
+            &encoder,
+            "/tmp/piece-1.ts",
+        );
+        assert!(copy.windows(2).any(|pair| pair == ["-ss", "2.001000"]));
+        assert!(copy.windows(2).any(|pair| pair == ["-t", "5.998000"]));
+        assert!(copy.windows(2).any(|pair| pair == ["-c:v", "copy"]));
+        assert!(!copy.iter().any(|arg| arg == "-pix_fmt"));
+
+        let encode = piece_args(
+            "/v/in.mp4",
+            CutSegment::Encode {
+                start: 1.5,
+                end: 2.0,
+            },
+            &encoder,
+            "/tmp/piece-0.ts",
+        );
+        assert!(encode.windows(2).any(|pair| pair == ["-ss", "1.500000"]));
+        assert!(encode.windows(2).any(|pair| pair == ["-t", "0.500000"]));
+        assert!(encode.windows(2).any(|pair| pair == ["-c:v", "libx265"]));
+        assert!(encode
+            .windows(2)
+            .any(|pair| pair == ["-profile:v", "main10"]));
+        assert!(encode
+            .windows(2)
+            .any(|pair| pair == ["-x265-params", "repeat-headers=1:level-idc=4.1"]));
+        assert!(encode
+            .windows(2)
+            .any(|pair| pair == ["-pix_fmt", "yuv420p10le"]));
+        assert!(encode.windows(2).any(|pair| pair == ["-s", "3840x2160"]));
+        assert_eq!(piece_extension(source.codec.as_deref()), "ts");
+
+        // A profile the encoder can't produce is re-encoded whole
+        let unsupported = CutSource {
+            codec: Some("h264".to_string()),
+            profile: Some("Extended".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(boundary_encoder_args(&unsupported), None);
+
+        let mux = mux_args("/tmp/video.mkv", "/v/in.webm", 1.5, 9.0, "/v/out.webm");
+        assert!(mux.windows(2).any(|pair| pair == ["-c:a", "libopus"]));
+        assert_eq!(mux.last().map(String::as_str), Some("/v/out.webm"));
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::database::add_log_internal;
+use crate::services::{get_scheduler_config, site_key};
+use crate::types::{
+    code, default_retryable, parse_wire_error_string, BackendError, BackendErrorWire, RetryPolicy,
+};
+
+/// Tries of a metadata fetch or channel poll, including the first one.
+/// These are cheap and user-facing, so they do not follow the download auto-retry setting.
+pub const FETCH_MAX_ATTEMPTS: u32 = 3;
+
+/// Longest wait between tries of a fetch the user is waiting on
+pub const INTERACTIVE_MAX_RETRY_WAIT: Duration = Duration::from_secs(5);
+
+/// Sites that rate-limited us, with the time their cooldown ends
+static RATE_LIMIT_COOLDOWNS: LazyLock<Mutex<HashMap<String, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Decode an error returned by a command, inferring the code for plain messages.
+pub fn backend_error_wire(raw: &str) -> BackendErrorWire {
+    parse_wire_error_string(raw).unwrap_or_else(|| BackendError::from_message(raw).to_wire())
+}
+
+/// Whether an error is worth retrying: the explicit flag wins over the code's default.
+pub fn is_retryable_error(error: &BackendErrorWire) -> bool {
+    error
+        .retryable
+        .unwrap_or_else(|| default_retryable(&error.code))
+}
+
+/// Wait before retrying after `failed_attempts` failures: the base delay doubled
+/// for every failure after the first, capped at the policy's maximum.
+pub fn retry_delay(policy: &RetryPolicy, failed_attempts: u32) -> Duration {
+    let exponent = failed_attempts.saturating_sub(1).min(16);
+    let secs = policy
+        .base_delay_secs
+        .max(1)
+        .saturating_mul(1u64 << exponent)
+        .min(policy.max_delay_secs.max(1));
+    Duration::from_secs(secs)
+}
+
+/// Start the cooldown of a site after it answered with a rate limit.
+pub fn start_rate_limit_cooldown(site: &str, policy: &RetryPolicy) {
+    let until = Instant::now() + Duration::from_secs(policy.rate_limit_cooldown_secs);
+    if let Ok(mut cooldowns) = RATE_LIMIT_COOLDOWNS.lock() {
+        let entry = cooldowns.entry(site.to_string()).or_insert(until);
+        *entry = (*entry).max(until);
+    }
+    log::warn!(
+        "{} rate-limited us; pausing its jobs for {}s",
+        site,
+        policy.rate_limit_cooldown_secs
+    );
+}
+
+/// Start the cooldown of the URL's site if the error is a rate limit.
+/// Returns the cooldown length when one was started.
+pub fn note_rate_limit(url: &str, error_code: &str) -> Option<u64> {
+    if error_code != code::YT_RATE_LIMITED {
+        return None;
+    }
+    let policy = get_scheduler_config().retry;
+    start_rate_limit_cooldown(&site_key(url), &policy);
+    Some(policy.rate_limit_cooldown_secs)
+}
+
+/// Time left before jobs for a site may run again, if it is cooling down.
+pub fn rate_limit_cooldown_remaining(site: &str) -> Option<Duration> {
+    let mut cooldowns = RATE_LIMIT_COOLDOWNS.lock().ok()?;
+    let until = *cooldowns.get(site)?;
+    let remaining = until.checked_duration_since(Instant::now());
+    if remaining.is_none() {
+        cooldowns.remove(site);
+    }
+    remaining.filter(|left| !left.is_zero())
+}
+
+/// Run `operation` until it succeeds, fails with an error that is not retryable,
+/// or has been tried [`FETCH_MAX_ATTEMPTS`] times. Retries wait with exponential
+/// backoff, or until the site's rate-limit cooldown ends if that is longer.
+///
+/// For background jobs (scheduler, format fetches of queued downloads, channel polls);
+/// fetches the user is waiting on use [`with_interactive_retry`].
+pub async fn with_retry<T, F, Fut>(label: &str, url: &str, operation: F) -> Result<T, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    run_with_retry(label, url, false, operation).await
+}
+
+/// Like [`with_retry`], but fails fast: a rate limit is returned right away (the
+/// site's cooldown still starts for background jobs) and no retry waits longer
+/// than [`INTERACTIVE_MAX_RETRY_WAIT`].
+pub async fn with_interactive_retry<T, F, Fut>(
+    label: &str,
+    url: &str,
+    operation: F,
+) -> Result<T, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    run_with_retry(label, url, true, operation).await
+}
+
+async fn run_with_retry<T, F, Fut>(
+    label: &str,
+    url: &str,
+    interactive: bool,
+    mut operation: F,
+) -> Result<T, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    let policy = get_scheduler_config().retry;
+    let site = site_key(url);
+    let mut attempt = 1;
+    loop {
+        let raw = match operation().await {
+            Ok(value) => return Ok(value),
+            Err(raw) => raw,
+        };
+        let error = backend_error_wire(&raw);
+        let rate_limited = error.code == code::YT_RATE_LIMITED;
+        if rate_limited {
+            start_rate_limit_cooldown(&site, &policy);
+        }
+        if attempt >= FETCH_MAX_ATTEMPTS
+            || !is_retryable_error(&error)
+            || (interactive && rate_limited)
+        {
+            return Err(raw);
+        }
+
+        let delay = if interactive {
+            retry_delay(&policy, attempt).min(INTERACTIVE_MAX_RETRY_WAIT)
+        } else {
+            retry_delay(&policy, attempt)
+                .max(rate_limit_cooldown_remaining(&site).unwrap_or_default())
+        };
+        add_log_internal(
+            "info",
+            &format!(
+                "{} failed (attempt {}/{}), retrying in {}s: {}",
+                label,
+                attempt,
+                FETCH_MAX_ATTEMPTS,
+                delay.as_secs(),
+                error.message
+            ),
+            None,
+            Some(url),
+        )
+        .ok();
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay_secs: 5,
+            max_delay_secs: 30,
+            rate_limit_cooldown_secs: 60,
+        };
+        let delays: Vec<u64> = (1..=5)
+            .map(|failed| retry_delay(&policy, failed).as_secs())
+            .collect();
+        assert_eq!(delays, vec![5, 10, 20, 30, 30]);
+        assert_eq!(retry_delay(&policy, 200).as_secs(), 30);
+    }
+
+    #[test]
+    fn retryable_flag_overrides_code_default() {
+        let timeout = BackendError::new(code::NETWORK_TIMEOUT, "Timed out").to_wire();
+        assert!(is_retryable_error(&timeout));
+
+        let forced = BackendError::new(code::NETWORK_TIMEOUT, "Timed out")
+            .with_retryable(false)
+            .to_wire();
+        assert!(!is_retryable_error(&forced));
+
+        let plain = backend_error_wire("HTTP Error 429: Too Many Requests");
+        assert_eq!(plain.code, code::YT_RATE_LIMITED);
+        assert!(is_retryable_error(&plain));
+        assert!(!is_retryable_error(&backend_error_wire("Private video")));
+    }
+
+    #[test]
+    fn rate_limit_cooldown_applies_per_site() {
+        let policy = RetryPolicy {
+            rate_limit_cooldown_secs: 120,
+            ..RetryPolicy::default()
+        };
+        start_rate_limit_cooldown("cooldown-test.example", &policy);
+
+        let remaining = rate_limit_cooldown_remaining("cooldown-test.example").expect("cooling");
+        assert!(remaining > Duration::from_secs(110));
+        assert!(rate_limit_cooldown_remaining("other-site.example").is_none());
+    }
+
+    #[tokio::test]
+    async fn interactive_retry_returns_rate_limit_right_away() {
+        let url = "https://interactive-retry-test.example/watch?v=1";
+        let mut calls = 0;
+        let result: Result<(), String> = with_interactive_retry("Fetching video info", url, || {
+            calls += 1;
+            async { Err("HTTP Error 429: Too Many Requests".to_string()) }
+        })
+        .await;
+
+        assert_eq!(calls, 1);
+        assert_eq!(
+            backend_error_wire(&result.unwrap_err()).code,
+            code::YT_RATE_LIMITED
+        );
+        assert!(rate_limit_cooldown_remaining(&site_key(url)).is_some());
+    }
+}
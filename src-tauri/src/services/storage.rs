@@ -0,0 +1,278 @@
+//! Disk-space and folder-quota checks made before a download starts, so a
+//! download that cannot fit fails up front instead of leaving partial files.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::types::{code, BackendError, FolderQuota};
+use crate::utils::format_size;
+
+/// Room kept free on top of the estimate for fragments, subtitles and thumbnails
+const FREE_SPACE_HEADROOM: u64 = 64 * 1024 * 1024;
+
+/// How long a measured folder size is reused by the scheduler before the folder is
+/// walked again
+const FOLDER_USAGE_TTL: Duration = Duration::from_secs(30);
+
+/// Folder sizes last measured by `measure_folder_usage`, with when they were measured
+static FOLDER_USAGE_CACHE: LazyLock<Mutex<HashMap<PathBuf, (Instant, u64)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// With this much free space and no quota, a download is not probed for its size.
+const PROBE_BELOW_FREE_SPACE: u64 = 20 * 1024 * 1024 * 1024;
+
+/// Bytes free for the current user on the volume holding `path`.
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)] // statvfs field types differ between platforms
+pub fn available_space(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some((stat.f_bavail as u64).saturating_mul(stat.f_frsize as u64))
+}
+
+/// Bytes free for the current user on the volume holding `path`.
+#[cfg(windows)]
+pub fn available_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut available: u64 = 0;
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (ok != 0).then_some(available)
+}
+
+/// Total size of the files under `folder`, including subfolders. Symlinks are not followed.
+pub fn folder_usage(folder: &Path) -> u64 {
+    let mut total: u64 = 0;
+    let mut pending = vec![folder.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                total = total.saturating_add(metadata.len());
+            }
+        }
+    }
+    total
+}
+
+/// Walk `folder` for its size on a blocking thread, so large folders don't stall the
+/// async runtime, and remember the result for `recent_folder_usage`.
+pub async fn measure_folder_usage(folder: &Path) -> u64 {
+    let path = folder.to_path_buf();
+    let walked = path.clone();
+    let Ok(used) = tokio::task::spawn_blocking(move || folder_usage(&walked)).await else {
+        return 0;
+    };
+    if let Ok(mut cache) = FOLDER_USAGE_CACHE.lock() {
+        cache.insert(path, (Instant::now(), used));
+    }
+    used
+}
+
+/// Size of `folder` as measured in the last `FOLDER_USAGE_TTL`, or measured now.
+pub async fn recent_folder_usage(folder: &Path) -> u64 {
+    let cached = FOLDER_USAGE_CACHE.lock().ok().and_then(|cache| {
+        cache
+            .get(folder)
+            .filter(|(measured_at, _)| measured_at.elapsed() < FOLDER_USAGE_TTL)
+            .map(|(_, used)| *used)
+    });
+    match cached {
+        Some(used) => used,
+        None => measure_folder_usage(folder).await,
+    }
+}
+
+/// Key a quota or output folder is compared by: the resolved path when it exists.
+pub fn normalize_quota_path(path: &str) -> String {
+    let path = path.trim();
+    Path::new(path)
+        .canonicalize()
+        .map(|canonical| canonical.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+/// The most specific quota covering `folder`.
+pub fn quota_for_folder<'a>(quotas: &'a [FolderQuota], folder: &str) -> Option<&'a FolderQuota> {
+    let folder = Path::new(folder);
+    quotas
+        .iter()
+        .filter(|quota| folder.starts_with(&quota.path))
+        .max_by_key(|quota| Path::new(&quota.path).components().count())
+}
+
+/// Whether the size of a download is worth asking yt-dlp for before it starts.
+pub fn should_probe_size(available: Option<u64>, has_quota: bool) -> bool {
+    has_quota || available.is_some_and(|available| available < PROBE_BELOW_FREE_SPACE)
+}
+
+/// Bytes a download needs on disk while it runs. Merging streams or converting audio
+/// keeps the inputs next to the output until it is written, doubling the peak.
+pub fn required_space(estimated_size: Option<u64>, keeps_intermediate: bool) -> u64 {
+    let size = estimated_size.unwrap_or(0);
+    let peak = if keeps_intermediate {
+        size.saturating_mul(2)
+    } else {
+        size
+    };
+    peak.saturating_add(FREE_SPACE_HEADROOM)
+}
+
+/// Refuse a download the volume has no room for. Unknown free space is not checked.
+pub fn check_free_space(
+    folder: &str,
+    available: Option<u64>,
+    required: u64,
+) -> Result<(), BackendError> {
+    match available {
+        Some(available) if available < required => Err(BackendError::new(
+            code::DISK_SPACE_INSUFFICIENT,
+            format!(
+                "Not enough disk space in {}: about {} needed, {} free",
+                folder,
+                format_size(required),
+                format_size(available)
+            ),
+        )
+        .with_param("path", folder)
+        .with_param("required", format_size(required))
+        .with_param("available", format_size(available))),
+        _ => Ok(()),
+    }
+}
+
+/// Refuse a download that would take its folder past its quota.
+pub fn check_quota(
+    quota: &FolderQuota,
+    used: u64,
+    estimated_size: Option<u64>,
+) -> Result<(), BackendError> {
+    let after = used.saturating_add(estimated_size.unwrap_or(0));
+    if used < quota.max_bytes && after <= quota.max_bytes {
+        return Ok(());
+    }
+    Err(BackendError::new(
+        code::STORAGE_QUOTA_EXCEEDED,
+        format!(
+            "Download folder quota exceeded for {}: {} of {} used",
+            quota.path,
+            format_size(used),
+            format_size(quota.max_bytes)
+        ),
+    )
+    .with_param("path", quota.path.clone())
+    .with_param("used", format_size(used))
+    .with_param("quota", format_size(quota.max_bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    fn quota(path: &str, max_bytes: u64) -> FolderQuota {
+        FolderQuota {
+            path: path.to_string(),
+            max_bytes,
+        }
+    }
+
+    #[test]
+    fn most_specific_quota_applies() {
+        let quotas = vec![quota("/media", 100 * GB), quota("/media/videos", 10 * GB)];
+
+        let matched = quota_for_folder(&quotas, "/media/videos/music").expect("quota");
+        assert_eq!(matched.path, "/media/videos");
+        assert_eq!(
+            quota_for_folder(&quotas, "/media/podcasts").map(|q| q.max_bytes),
+            Some(100 * GB)
+        );
+        // Whole path components only: /media-archive is not inside /media.
+        assert!(quota_for_folder(&quotas, "/media-archive").is_none());
+    }
+
+    #[test]
+    fn free_space_covers_intermediate_files() {
+        let required = required_space(Some(2 * GB), true);
+        assert!(required > 4 * GB);
+        assert!(check_free_space("/downloads", Some(3 * GB), required).is_err());
+        assert!(check_free_space("/downloads", Some(5 * GB), required).is_ok());
+        assert!(check_free_space("/downloads", None, required).is_ok());
+
+        let error = check_free_space("/downloads", Some(0), required_space(None, false))
+            .expect_err("disk full");
+        assert_eq!(error.code(), code::DISK_SPACE_INSUFFICIENT);
+    }
+
+    #[test]
+    fn quota_counts_the_download_being_started() {
+        let limit = quota("/downloads", 10 * GB);
+        assert!(check_quota(&limit, 8 * GB, Some(GB)).is_ok());
+        assert!(check_quota(&limit, 8 * GB, None).is_ok());
+
+        let error = check_quota(&limit, 8 * GB, Some(3 * GB)).expect_err("over quota");
+        assert_eq!(error.code(), code::STORAGE_QUOTA_EXCEEDED);
+        assert!(check_quota(&limit, 10 * GB, None).is_err());
+    }
+
+    #[test]
+    fn size_is_probed_only_when_it_matters() {
+        assert!(should_probe_size(Some(GB), false));
+        assert!(should_probe_size(Some(500 * GB), true));
+        assert!(!should_probe_size(Some(500 * GB), false));
+        assert!(!should_probe_size(None, false));
+    }
+
+    #[test]
+    fn folder_usage_includes_subfolders() {
+        let root = std::env::temp_dir().join(format!("youwee-usage-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("nested")).unwrap();
+        std::fs::write(root.join("a.bin"), vec![0u8; 1000]).unwrap();
+        std::fs::write(root.join("nested").join("b.bin"), vec![0u8; 24]).unwrap();
+
+        assert_eq!(folder_usage(&root), 1024);
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[tokio::test]
+    async fn recent_folder_usage_reuses_the_last_measurement() {
+        let root = std::env::temp_dir().join(format!("youwee-usage-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("a.bin"), vec![0u8; 100]).unwrap();
+
+        assert_eq!(recent_folder_usage(&root).await, 100);
+        std::fs::write(root.join("b.bin"), vec![0u8; 50]).unwrap();
+        assert_eq!(recent_folder_usage(&root).await, 100);
+        assert_eq!(measure_folder_usage(&root).await, 150);
+        assert_eq!(recent_folder_usage(&root).await, 150);
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
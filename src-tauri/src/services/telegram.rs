@@ -6,9 +6,15 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter};
 
+use crate::services::{
+    download_profile_exists, enqueue_download_item, validate_download_profile_name,
+};
+use crate::types::{parse_wire_error_string, DownloadOptions, NewDownloadQueueItem};
+
 const TELEGRAM_API_BASE: &str = "https://api.telegram.org";
 const LONG_POLL_TIMEOUT_SECS: u64 = 30;
 const MAX_BACKOFF_SECS: u64 = 60;
+const TELEGRAM_VIDEO_QUALITIES: [&str; 8] = ["best", "8k", "4k", "2k", "1080", "720", "480", "360"];
 
 static TELEGRAM_CONFIG: Mutex<TelegramConfig> = Mutex::new(TelegramConfig {
     enabled: false,
@@ -388,7 +394,8 @@ async fn handle_update(
             emit_url_command(app, "add", &url, quality.as_deref(), &chat_id);
         }
         TelegramCommand::Download { url, quality } => {
-            emit_url_command(app, "download", &url, quality.as_deref(), &chat_id);
+            let reply = queue_download(app, &url, quality.as_deref(), &chat_id);
+            let _ = send_message_with_keyboard(client, bot_token, &chat_id, &reply).await;
         }
         TelegramCommand::Status => {
             emit_simple_command(app, "status", &chat_id);
@@ -417,6 +424,68 @@ async fn handle_update(
     }
 }
 
+/// Hand a `/download` request to the backend queue and describe the outcome.
+fn queue_download(app: &AppHandle, url: &str, quality: Option<&str>, chat_id: &str) -> String {
+    let Some(options) = queued_options_for_choice(quality, download_profile_exists) else {
+        return "Unsupported quality. Use: best, 8k, 4k, 2k, 1080, 720, 480, 360, audio, mp3, or a download profile name."
+            .to_string();
+    };
+
+    let request = NewDownloadQueueItem {
+        id: None,
+        url: url.to_string(),
+        queue_kind: None,
+        title: None,
+        origin: Some("telegram".to_string()),
+        origin_ref: Some(chat_id.to_string()),
+        priority: None,
+        options,
+    };
+    match enqueue_download_item(app, request) {
+        Ok(_) => "Added to the Youwee download queue.".to_string(),
+        Err(error) => {
+            let message = parse_wire_error_string(&error)
+                .map(|wire| wire.message)
+                .unwrap_or(error);
+            format!("Could not queue that URL: {}", message)
+        }
+    }
+}
+
+/// Map the token after a `/download` URL to download options. Known qualities
+/// win; any other name selects that profile when `profile_exists` knows it. `None` for
+/// unsupported values, so an unknown profile is refused before anything is queued.
+fn queued_options_for_choice(
+    choice: Option<&str>,
+    profile_exists: impl Fn(&str) -> bool,
+) -> Option<DownloadOptions> {
+    let normalized = choice.map(|value| value.trim().to_lowercase());
+    let mut options = DownloadOptions {
+        download_kind: Some("telegram".to_string()),
+        ..Default::default()
+    };
+
+    match normalized.as_deref() {
+        None | Some("") => {}
+        Some("audio") | Some("mp3") => {
+            options.quality = "audio".to_string();
+            options.format = "mp3".to_string();
+        }
+        Some(value) if TELEGRAM_VIDEO_QUALITIES.contains(&value) => {
+            options.quality = value.to_string();
+        }
+        Some(_) => {
+            let profile = validate_download_profile_name(choice.unwrap_or_default()).ok()?;
+            if !profile_exists(&profile) {
+                return None;
+            }
+            options.profile = Some(profile);
+        }
+    }
+
+    Some(options)
+}
+
 fn emit_url_command(
     app: &AppHandle,
     command: &str,
@@ -582,7 +651,7 @@ fn set_status(state: TelegramStatusState, message: Option<String>) -> bool {
 }
 
 fn help_text() -> &'static str {
-    "Youwee Telegram commands:\n/start - Show command keyboard.\n/add <url> [quality] - Add a URL to the queue.\n/download <url> [quality] - Add a URL and start downloading when idle.\n/status - Show download status.\n/queue - Show recent queue items.\n/run - Start pending downloads.\n/stop - Stop the current download.\n/help - Show this help.\n\nYou can also send a link directly.\nQuality: best, 8k, 4k, 2k, 1080, 720, 480, 360, audio, mp3."
+    "Youwee Telegram commands:\n/start - Show command keyboard.\n/add <url> [quality] - Add a URL to the queue.\n/download <url> [quality|profile] - Download a URL in the background queue.\n/status - Show download status.\n/queue - Show recent queue items.\n/run - Start pending downloads.\n/stop - Stop the current download.\n/help - Show this help.\n\nYou can also send a link directly.\nQuality: best, 8k, 4k, 2k, 1080, 720, 480, 360, audio, mp3.\nProfile: the name of a download profile saved in Youwee."
 }
 
 pub fn parse_command(text: &str) -> TelegramCommand {
@@ -729,6 +798,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn maps_download_quality_or_profile_to_queue_options() {
+        let saved = |name: &str| name == "Podcast-320";
+        let default = queued_options_for_choice(None, saved).expect("default options");
+        assert_eq!(default.quality, "best");
+        assert_eq!(default.format, "mp4");
+
+        let audio = queued_options_for_choice(Some("MP3"), saved).expect("audio options");
+        assert_eq!(audio.quality, "audio");
+        assert_eq!(audio.format, "mp3");
+
+        let hd = queued_options_for_choice(Some("1080"), saved).expect("video options");
+        assert_eq!(hd.quality, "1080");
+        assert_eq!(hd.profile, None);
+
+        let profile =
+            queued_options_for_choice(Some("Podcast-320"), saved).expect("profile options");
+        assert_eq!(profile.quality, "best");
+        assert_eq!(profile.profile.as_deref(), Some("Podcast-320"));
+
+        assert!(queued_options_for_choice(Some("potato"), saved).is_none());
+        assert!(queued_options_for_choice(Some("../etc"), |_| true).is_none());
+    }
+
     #[test]
     fn rejects_missing_url() {
         assert_eq!(parse_command("/add"), TelegramCommand::Unsupported);
@@ -0,0 +1,289 @@
+//! Post-download integrity check: ffprobe reads the file yt-dlp wrote, and its
+//! container, streams and duration are compared with what yt-dlp reported.
+
+use serde::Deserialize;
+use tauri::AppHandle;
+use tokio::process::Command;
+
+use super::get_ffprobe_path;
+use crate::types::{code, BackendError};
+use crate::utils::{CommandExt, DownloadedMedia};
+
+/// How far a file's duration may be off, in seconds, or as a share of the
+/// expected duration when that is more (containers round differently).
+const DURATION_TOLERANCE_SECS: f64 = 2.0;
+const DURATION_TOLERANCE_RATIO: f64 = 0.01;
+
+/// What a downloaded file must contain
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpectedMedia {
+    pub video: bool,
+    pub audio: bool,
+    /// Seconds; left out for files cut on purpose (time ranges, SponsorBlock)
+    pub duration: Option<f64>,
+}
+
+impl ExpectedMedia {
+    /// Expect the streams yt-dlp downloaded; a codec it doesn't know is not required.
+    /// Audio extraction drops the video stream.
+    pub fn from_download(media: &DownloadedMedia, audio_only: bool, check_duration: bool) -> Self {
+        let has_stream = |codec: &Option<String>| {
+            codec
+                .as_deref()
+                .is_some_and(|codec| !matches!(codec, "" | "none" | "NA"))
+        };
+        Self {
+            video: !audio_only && has_stream(&media.vcodec),
+            audio: has_stream(&media.acodec),
+            duration: media
+                .duration
+                .filter(|&duration| check_duration && duration > 0.0),
+        }
+    }
+}
+
+/// What ffprobe found in a file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProbedMedia {
+    pub container: Option<String>,
+    pub video: bool,
+    pub audio: bool,
+    pub duration: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeOutput {
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+    format: Option<FfprobeFormat>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeStream {
+    codec_type: Option<String>,
+    #[serde(default)]
+    disposition: FfprobeDisposition,
+}
+
+#[derive(Deserialize, Default)]
+struct FfprobeDisposition {
+    #[serde(default)]
+    attached_pic: u8,
+}
+
+#[derive(Deserialize)]
+struct FfprobeFormat {
+    format_name: Option<String>,
+    duration: Option<String>,
+}
+
+/// Parse `ffprobe -print_format json -show_format -show_streams` output.
+/// Embedded cover art is a video stream to ffprobe, but not a video track.
+pub fn parse_ffprobe_media(json: &str) -> Option<ProbedMedia> {
+    let output: FfprobeOutput = serde_json::from_str(json).ok()?;
+    let has_stream = |kind: &str| {
+        output.streams.iter().any(|stream| {
+            stream.codec_type.as_deref() == Some(kind) && stream.disposition.attached_pic == 0
+        })
+    };
+    let video = has_stream("video");
+    let audio = has_stream("audio");
+    let format = output.format;
+
+    Some(ProbedMedia {
+        container: format
+            .as_ref()
+            .and_then(|format| format.format_name.clone())
+            .filter(|name| !name.is_empty()),
+        video,
+        audio,
+        duration: format
+            .and_then(|format| format.duration)
+            .and_then(|duration| duration.parse().ok()),
+    })
+}
+
+/// Compare what ffprobe found with what the file should contain.
+pub fn check_media(expected: &ExpectedMedia, probed: &ProbedMedia) -> Result<(), BackendError> {
+    if probed.container.is_none() {
+        return Err(verification_error(
+            "unreadable",
+            "ffprobe could not read the file",
+        ));
+    }
+    if expected.video && !probed.video {
+        return Err(verification_error("missingVideo", "the file has no video"));
+    }
+    if expected.audio && !probed.audio {
+        return Err(verification_error("missingAudio", "the file has no audio"));
+    }
+    if let Some(expected_duration) = expected.duration {
+        let tolerance = (expected_duration * DURATION_TOLERANCE_RATIO).max(DURATION_TOLERANCE_SECS);
+        let actual = probed.duration.unwrap_or(0.0);
+        if (actual - expected_duration).abs() > tolerance {
+            return Err(verification_error(
+                "duration",
+                format!(
+                    "the file is {:.0}s long instead of {:.0}s",
+                    actual, expected_duration
+                ),
+            )
+            .with_param("expected", expected_duration.round() as u64)
+            .with_param("actual", actual.round() as u64));
+        }
+    }
+    Ok(())
+}
+
+fn verification_error(reason: &str, detail: impl Into<String>) -> BackendError {
+    BackendError::new(
+        code::DOWNLOAD_VERIFICATION_FAILED,
+        format!("Downloaded file failed verification: {}", detail.into()),
+    )
+    .with_param("reason", reason)
+}
+
+/// Check a downloaded file with ffprobe. Without a working ffprobe the check is skipped.
+pub async fn verify_media_file(
+    app: &AppHandle,
+    path: &str,
+    expected: &ExpectedMedia,
+) -> Result<(), BackendError> {
+    let Some(ffprobe_path) = get_ffprobe_path(app).await else {
+        log::warn!("FFprobe not found, skipping verification of {}", path);
+        return Ok(());
+    };
+
+    let mut cmd = Command::new(&ffprobe_path);
+    cmd.args([
+        "-v",
+        "quiet",
+        "-print_format",
+        "json",
+        "-show_format",
+        "-show_streams",
+        path,
+    ]);
+    cmd.hide_window();
+    let output = match cmd.output().await {
+        Ok(output) => output,
+        Err(e) => {
+            log::warn!("Failed to run ffprobe, skipping verification: {}", e);
+            return Ok(());
+        }
+    };
+
+    // ffprobe exits with an error for files it cannot parse at all.
+    let probed = if output.status.success() {
+        parse_ffprobe_media(&String::from_utf8_lossy(&output.stdout)).unwrap_or_default()
+    } else {
+        ProbedMedia::default()
+    };
+    check_media(expected, &probed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MERGED_MP4: &str = r#"{
+        "streams": [
+            {"index": 0, "codec_type": "video", "codec_name": "h264", "disposition": {"attached_pic": 0}},
+            {"index": 1, "codec_type": "audio", "codec_name": "aac", "disposition": {"attached_pic": 0}}
+        ],
+        "format": {"format_name": "mov,mp4,m4a,3gp,3g2,mj2", "duration": "212.091000"}
+    }"#;
+
+    fn downloaded(vcodec: &str, acodec: &str, duration: f64) -> DownloadedMedia {
+        DownloadedMedia {
+            vcodec: Some(vcodec.to_string()),
+            acodec: Some(acodec.to_string()),
+            duration: Some(duration),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_streams_and_ignores_cover_art() {
+        let probed = parse_ffprobe_media(MERGED_MP4).expect("ffprobe output");
+        assert_eq!(probed.container.as_deref(), Some("mov,mp4,m4a,3gp,3g2,mj2"));
+        assert!(probed.video && probed.audio);
+        assert_eq!(probed.duration, Some(212.091));
+
+        let mp3_with_cover = r#"{
+            "streams": [
+                {"codec_type": "audio", "codec_name": "mp3"},
+                {"codec_type": "video", "codec_name": "mjpeg", "disposition": {"attached_pic": 1}}
+            ],
+            "format": {"format_name": "mp3", "duration": "180.0"}
+        }"#;
+        let probed = parse_ffprobe_media(mp3_with_cover).expect("ffprobe output");
+        assert!(!probed.video);
+        assert!(probed.audio);
+    }
+
+    #[test]
+    fn accepts_a_complete_file_within_tolerance() {
+        let expected = ExpectedMedia::from_download(
+            &downloaded("avc1.640028", "mp4a.40.2", 213.0),
+            false,
+            true,
+        );
+        let probed = parse_ffprobe_media(MERGED_MP4).expect("ffprobe output");
+        assert!(check_media(&expected, &probed).is_ok());
+    }
+
+    #[test]
+    fn rejects_missing_streams_and_truncated_files() {
+        let probed = parse_ffprobe_media(MERGED_MP4).expect("ffprobe output");
+        let reason = |expected: &ExpectedMedia, probed: &ProbedMedia| {
+            check_media(expected, probed)
+                .expect_err("verification error")
+                .params()
+                .and_then(|params| params.get("reason"))
+                .and_then(|reason| reason.as_str().map(str::to_string))
+        };
+
+        let truncated =
+            ExpectedMedia::from_download(&downloaded("vp9", "opus", 600.0), false, true);
+        assert_eq!(reason(&truncated, &probed).as_deref(), Some("duration"));
+
+        let video_only = ProbedMedia {
+            audio: false,
+            ..probed.clone()
+        };
+        let expected = ExpectedMedia::from_download(&downloaded("vp9", "opus", 212.0), false, true);
+        assert_eq!(
+            reason(&expected, &video_only).as_deref(),
+            Some("missingAudio")
+        );
+        assert_eq!(
+            reason(&expected, &ProbedMedia::default()).as_deref(),
+            Some("unreadable")
+        );
+        let error = check_media(&expected, &video_only).expect_err("verification error");
+        assert_eq!(error.code(), code::DOWNLOAD_VERIFICATION_FAILED);
+    }
+
+    #[test]
+    fn expectations_follow_the_download() {
+        // Extracted audio has no video, and a cut file keeps no duration to compare.
+        let expected =
+            ExpectedMedia::from_download(&downloaded("avc1", "mp4a", 100.0), true, false);
+        assert_eq!(
+            expected,
+            ExpectedMedia {
+                video: false,
+                audio: true,
+                duration: None,
+            }
+        );
+
+        let unknown = ExpectedMedia::from_download(&DownloadedMedia::default(), false, true);
+        assert_eq!(unknown, ExpectedMedia::default());
+        let audio_only_format =
+            ExpectedMedia::from_download(&downloaded("none", "opus", 90.0), false, true);
+        assert!(!audio_only_format.video);
+        assert_eq!(audio_only_format.duration, Some(90.0));
+    }
+}
@@ -32,6 +32,7 @@ pub struct FollowedChannel {
     pub download_video_codec: String,            // video codec (h264, vp9, av1, auto)
     pub download_audio_bitrate: String,          // audio bitrate (128, 192, 256, 320, auto)
     pub youtube_content_type: String,            // videos, shorts, streams, videos_shorts
+    pub download_profile: Option<String>,        // download profile applied to auto-downloads
 }
 
 /// A video belonging to a followed channel
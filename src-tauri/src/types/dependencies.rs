@@ -119,4 +119,44 @@ pub struct GalleryDlStatus {
     pub version: Option<String>,
     pub binary_path: Option<String>,
     pub is_system: bool,
+    /// Selected source: "auto" (system first, then app managed), "app" or "system"
+    pub source: String,
+    /// Selected channel of the app-managed binary
+    pub channel: String,
+}
+
+/// Release channel of the app-managed gallery-dl binary
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GalleryDlChannel {
+    /// Tagged releases of mikf/gallery-dl
+    #[default]
+    Stable,
+    /// Daily builds from gdl-org/builds
+    Nightly,
+}
+
+impl GalleryDlChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GalleryDlChannel::Stable => "stable",
+            GalleryDlChannel::Nightly => "nightly",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "nightly" => GalleryDlChannel::Nightly,
+            _ => GalleryDlChannel::Stable,
+        }
+    }
+}
+
+/// gallery-dl channel update info
+#[derive(Clone, Serialize, Debug)]
+pub struct GalleryDlUpdateInfo {
+    pub channel: String,
+    pub current_version: Option<String>,
+    pub latest_version: String,
+    pub update_available: bool,
 }
@@ -1,7 +1,10 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 
-#[derive(Clone, Serialize)]
+use super::{FormatPreferences, PluginWorkflowStepSnapshot};
+
+#[derive(Clone, Serialize, Default)]
 pub struct DownloadProgress {
     pub id: String,
     pub percent: f64,
@@ -21,4 +24,177 @@ pub struct DownloadProgress {
     pub filepath: Option<String>,   // Final output path when finished
     pub downloaded_size: Option<String>, // For live streams: "2.87 MiB"
     pub elapsed_time: Option<String>, // For live streams: "00:00:07"
+    pub phase: Option<DownloadPhase>,
+    pub downloaded_bytes: Option<u64>,
+    pub total_bytes: Option<u64>, // Exact size, or yt-dlp's estimate for fragmented streams
+    pub fragment_index: Option<u32>,
+    pub fragment_count: Option<u32>,
+    pub attempt: Option<u32>, // Current try when the download is retried automatically
+    pub max_attempts: Option<u32>,
+    pub item_id: Option<String>, // One entry of a playlist download: "<job id>:<playlist index>"
+    pub playlist_id: Option<String>,
+}
+
+/// What yt-dlp is currently doing for a download
+#[derive(Clone, Copy, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadPhase {
+    Downloading,
+    Merging,
+    Converting,
+    Embedding,
+    Sponsorblock,
+    Moving,
+    Postprocessing,
+}
+
+/// Options of a download, shared by every entry point (app, queue, CLI, Telegram, channels).
+/// Stored with queued items and sent inside a [`DownloadRequest`].
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DownloadOptions {
+    pub output_path: Option<String>,
+    pub quality: String,
+    pub format: String,
+    pub download_playlist: bool,
+    pub video_codec: String,
+    pub audio_bitrate: String,
+    pub playlist_limit: Option<u32>,
+    pub subtitle_mode: String,
+    pub subtitle_langs: String,
+    pub subtitle_embed: bool,
+    pub subtitle_format: String,
+    pub log_stderr: Option<bool>,
+    pub use_actual_player_js: Option<bool>,
+    pub history_id: Option<String>,
+    pub cookie_mode: Option<String>,
+    pub cookie_browser: Option<String>,
+    pub cookie_browser_profile: Option<String>,
+    pub cookie_file_path: Option<String>,
+    pub embed_metadata: Option<bool>,
+    pub embed_thumbnail: Option<bool>,
+    pub proxy_url: Option<String>,
+    pub live_from_start: Option<bool>,
+    pub skip_live: Option<bool>,
+    pub speed_limit: Option<String>,
+    pub use_aria2: Option<bool>,
+    pub aria2_args: Option<String>,
+    pub sponsorblock_remove: Option<String>,
+    pub sponsorblock_mark: Option<String>,
+    /// Comma-separated `*start-end` ranges; each one is downloaded as its own clip
+    pub download_sections: Option<String>,
+    /// Join the clips of several ranges into one file instead of keeping one file per range
+    pub join_sections: Option<bool>,
+    /// Cut the ranges to the frame: the whole video is downloaded and only the GOPs
+    /// around each cut point are re-encoded
+    pub precise_sections: Option<bool>,
+    pub thumbnail: Option<String>,
+    pub source: Option<String>,
+    pub plugin_workflow_snapshots: Option<BTreeMap<String, Vec<PluginWorkflowStepSnapshot>>>,
+    pub post_download_workflow_steps: Option<Vec<PluginWorkflowStepSnapshot>>,
+    pub download_kind: Option<String>,
+    /// Download even if the video is already in the download archive
+    pub force_redownload: Option<bool>,
+    /// Filename/folder template relative to the output path
+    pub output_template: Option<String>,
+    /// Name of a saved download profile whose settings override these options
+    pub profile: Option<String>,
+    /// Exact video stream from `get_video_info`, replacing the quality/codec preset
+    pub video_format_id: Option<String>,
+    /// Exact audio streams; more than one are merged with `--audio-multistreams`
+    pub audio_format_ids: Option<Vec<String>>,
+    /// Codec, dynamic range, fps, language and size rules replacing the codec preset
+    pub format_preferences: Option<FormatPreferences>,
+    /// Also write one file per chapter, each with its own history entry
+    pub split_chapters: Option<bool>,
+    /// Check the finished file with ffprobe and fail the job if it is incomplete
+    pub verify_output: Option<bool>,
+    /// Also cut the finished file into parts of this many minutes, each with its own
+    /// history entry (long live recordings)
+    pub segment_minutes: Option<u32>,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            output_path: None,
+            quality: "best".to_string(),
+            format: "mp4".to_string(),
+            download_playlist: false,
+            video_codec: "h264".to_string(),
+            audio_bitrate: "192".to_string(),
+            playlist_limit: None,
+            subtitle_mode: "off".to_string(),
+            subtitle_langs: String::new(),
+            subtitle_embed: false,
+            subtitle_format: "srt".to_string(),
+            log_stderr: None,
+            use_actual_player_js: None,
+            history_id: None,
+            cookie_mode: None,
+            cookie_browser: None,
+            cookie_browser_profile: None,
+            cookie_file_path: None,
+            embed_metadata: None,
+            embed_thumbnail: None,
+            proxy_url: None,
+            live_from_start: None,
+            skip_live: None,
+            speed_limit: None,
+            use_aria2: None,
+            aria2_args: None,
+            sponsorblock_remove: None,
+            sponsorblock_mark: None,
+            download_sections: None,
+            join_sections: None,
+            precise_sections: None,
+            thumbnail: None,
+            source: None,
+            plugin_workflow_snapshots: None,
+            post_download_workflow_steps: None,
+            download_kind: None,
+            force_redownload: None,
+            output_template: None,
+            profile: None,
+            video_format_id: None,
+            audio_format_ids: None,
+            format_preferences: None,
+            split_chapters: None,
+            verify_output: None,
+            segment_minutes: None,
+        }
+    }
+}
+
+/// Arguments of the `download_video` command
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadRequest {
+    pub id: String,
+    pub url: String,
+    /// Display title passed from the frontend
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Legacy snapshot of plugin ids enabled when the job was queued
+    #[serde(default)]
+    pub post_download_plugins: Option<Vec<String>>,
+    /// When false, the caller is responsible for firing the final download.failed workflow
+    #[serde(default)]
+    pub emit_failed_workflow: Option<bool>,
+    /// Set when the caller retries failed downloads; reported back in progress events
+    #[serde(default)]
+    pub attempt: Option<DownloadAttempt>,
+    /// Set by the download queue: `.part` files are kept and resumed instead of overwritten
+    #[serde(default)]
+    pub resumable: Option<bool>,
+    #[serde(flatten)]
+    pub options: DownloadOptions,
+}
+
+/// Which try of an automatically retried download is running
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadAttempt {
+    pub attempt: u32,
+    pub max_attempts: u32,
 }
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+
+/// A step in the life of a download, as recorded in the download journal
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadEventKind {
+    /// Added to the backend queue
+    Queued,
+    /// An attempt started
+    Started,
+    /// Sample of a running attempt
+    Progress,
+    /// An attempt failed and the download will run again
+    Retried,
+    Completed,
+    Failed,
+    Cancelled,
+    /// Not downloaded on purpose (already archived, live stream skipped)
+    Skipped,
+}
+
+impl DownloadEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Started => "started",
+            Self::Progress => "progress",
+            Self::Retried => "retried",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+            Self::Skipped => "skipped",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "queued" => Some(Self::Queued),
+            "started" => Some(Self::Started),
+            "progress" => Some(Self::Progress),
+            "retried" => Some(Self::Retried),
+            "completed" => Some(Self::Completed),
+            "failed" => Some(Self::Failed),
+            "cancelled" => Some(Self::Cancelled),
+            "skipped" => Some(Self::Skipped),
+            _ => None,
+        }
+    }
+}
+
+/// One entry of the download journal (one `download_events` row)
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadEvent {
+    pub id: i64,
+    /// Id of the download job (the queue item id for queued downloads)
+    pub download_id: String,
+    pub kind: DownloadEventKind,
+    pub url: Option<String>,
+    /// Site of the URL, as used for per-site limits (`youtube.com`)
+    pub site: Option<String>,
+    pub attempt: Option<u32>,
+    pub percent: Option<f64>,
+    /// Bytes downloaded so far; for `completed`, by the whole attempt
+    pub bytes: Option<u64>,
+    /// Download speed at a progress sample, bytes per second
+    pub speed_bps: Option<u64>,
+    /// Time since the attempt started (ms)
+    pub duration_ms: Option<i64>,
+    pub error_code: Option<String>,
+    pub error_message: Option<String>,
+    pub created_at: i64,
+}
+
+/// Downloads finished on one day (local time)
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyDownloadStats {
+    /// `YYYY-MM-DD`
+    pub day: String,
+    pub bytes: u64,
+    pub completed: u64,
+    pub failed: u64,
+}
+
+/// Outcome of the downloads from one site
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteDownloadStats {
+    pub site: String,
+    pub completed: u64,
+    pub failed: u64,
+    /// Share of finished downloads that completed, from 0 to 1
+    pub success_rate: Option<f64>,
+    pub bytes: u64,
+    /// Bytes per second over the completed downloads
+    pub average_speed_bps: Option<f64>,
+}
+
+/// How often an error code ended an attempt, final or retried
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FailureCodeStats {
+    pub error_code: String,
+    pub count: u64,
+    /// Sites the error came from, most affected first
+    pub sites: Vec<String>,
+    pub last_message: Option<String>,
+    pub last_seen_at: i64,
+}
+
+/// Aggregates of the download journal over a period
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadStats {
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    /// Attempts started, retries included
+    pub started: u64,
+    pub completed: u64,
+    pub failed: u64,
+    pub cancelled: u64,
+    pub retried: u64,
+    /// Share of finished downloads that completed, from 0 to 1
+    pub success_rate: Option<f64>,
+    pub total_bytes: u64,
+    /// Bytes per second over the completed downloads
+    pub average_speed_bps: Option<f64>,
+    pub daily: Vec<DailyDownloadStats>,
+    pub sites: Vec<SiteDownloadStats>,
+    pub top_failures: Vec<FailureCodeStats>,
+}
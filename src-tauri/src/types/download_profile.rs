@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+
+use super::DownloadOptions;
+
+/// Settings saved in a download profile.
+/// Fields left unset keep the value of the download the profile is applied to.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DownloadProfileSettings {
+    pub quality: Option<String>,
+    pub format: Option<String>,
+    pub video_codec: Option<String>,
+    pub audio_bitrate: Option<String>,
+    pub subtitle_mode: Option<String>,
+    pub subtitle_langs: Option<String>,
+    pub subtitle_embed: Option<bool>,
+    pub subtitle_format: Option<String>,
+    pub embed_metadata: Option<bool>,
+    pub embed_thumbnail: Option<bool>,
+    pub sponsorblock_remove: Option<String>,
+    pub sponsorblock_mark: Option<String>,
+    pub cookie_mode: Option<String>,
+    pub cookie_browser: Option<String>,
+    pub cookie_browser_profile: Option<String>,
+    pub cookie_file_path: Option<String>,
+    pub proxy_url: Option<String>,
+    pub output_template: Option<String>,
+}
+
+impl DownloadProfileSettings {
+    /// Override the options of a download with the settings of this profile.
+    pub fn apply_to(&self, options: &mut DownloadOptions) {
+        fn set<T: Clone>(target: &mut T, value: &Option<T>) {
+            if let Some(value) = value {
+                *target = value.clone();
+            }
+        }
+        fn set_opt<T: Clone>(target: &mut Option<T>, value: &Option<T>) {
+            if value.is_some() {
+                *target = value.clone();
+            }
+        }
+
+        set(&mut options.quality, &self.quality);
+        set(&mut options.format, &self.format);
+        set(&mut options.video_codec, &self.video_codec);
+        set(&mut options.audio_bitrate, &self.audio_bitrate);
+        set(&mut options.subtitle_mode, &self.subtitle_mode);
+        set(&mut options.subtitle_langs, &self.subtitle_langs);
+        set(&mut options.subtitle_embed, &self.subtitle_embed);
+        set(&mut options.subtitle_format, &self.subtitle_format);
+        set_opt(&mut options.embed_metadata, &self.embed_metadata);
+        set_opt(&mut options.embed_thumbnail, &self.embed_thumbnail);
+        set_opt(&mut options.sponsorblock_remove, &self.sponsorblock_remove);
+        set_opt(&mut options.sponsorblock_mark, &self.sponsorblock_mark);
+        set_opt(&mut options.cookie_mode, &self.cookie_mode);
+        set_opt(&mut options.cookie_browser, &self.cookie_browser);
+        set_opt(
+            &mut options.cookie_browser_profile,
+            &self.cookie_browser_profile,
+        );
+        set_opt(&mut options.cookie_file_path, &self.cookie_file_path);
+        set_opt(&mut options.proxy_url, &self.proxy_url);
+        set_opt(&mut options.output_template, &self.output_template);
+    }
+}
+
+/// A named download preset (one `download_profiles` row)
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProfile {
+    pub name: String,
+    pub settings: DownloadProfileSettings,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_to_overrides_only_set_fields() {
+        let mut options = DownloadOptions {
+            proxy_url: Some("http://proxy:8080".to_string()),
+            embed_metadata: Some(true),
+            ..Default::default()
+        };
+        let settings = DownloadProfileSettings {
+            quality: Some("1080".to_string()),
+            format: Some("mkv".to_string()),
+            subtitle_embed: Some(true),
+            sponsorblock_remove: Some("sponsor,intro".to_string()),
+            output_template: Some("%(uploader)s/%(title)s.%(ext)s".to_string()),
+            ..Default::default()
+        };
+
+        settings.apply_to(&mut options);
+
+        assert_eq!(options.quality, "1080");
+        assert_eq!(options.format, "mkv");
+        assert_eq!(options.video_codec, "h264");
+        assert!(options.subtitle_embed);
+        assert_eq!(
+            options.sponsorblock_remove.as_deref(),
+            Some("sponsor,intro")
+        );
+        assert_eq!(options.proxy_url.as_deref(), Some("http://proxy:8080"));
+        assert_eq!(options.embed_metadata, Some(true));
+        assert_eq!(
+            options.output_template.as_deref(),
+            Some("%(uploader)s/%(title)s.%(ext)s")
+        );
+    }
+}
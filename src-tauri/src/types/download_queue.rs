@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+
+use super::DownloadOptions;
+
+/// Lifecycle state of a download owned by the backend scheduler
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadQueueStatus {
+    #[default]
+    Pending,
+    Downloading,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl DownloadQueueStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Downloading => "downloading",
+            Self::Paused => "paused",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "downloading" => Self::Downloading,
+            "paused" => Self::Paused,
+            "completed" => Self::Completed,
+            "failed" => Self::Failed,
+            "cancelled" => Self::Cancelled,
+            _ => Self::Pending,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// A download owned by the backend scheduler (one `download_queue_items` row)
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadQueueItem {
+    pub id: String,
+    pub queue_kind: String,
+    pub url: String,
+    pub title: Option<String>,
+    pub site: String,
+    pub origin: String,             // "app", "cli", "telegram", "channel"
+    pub origin_ref: Option<String>, // e.g. channel video row id or Telegram chat id
+    pub status: DownloadQueueStatus,
+    pub priority: i64,
+    pub attempts: i64,
+    pub options: DownloadOptions,
+    /// Files yt-dlp was writing when the job stopped; their `.part` data is resumed
+    pub partial_paths: Vec<String>,
+    pub error_code: Option<String>,
+    pub error_message: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+    /// When a pending item waiting for an automatic retry may start again
+    pub next_attempt_at: Option<i64>,
+}
+
+/// Request payload for adding an item to the backend download queue
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NewDownloadQueueItem {
+    /// Id for the new row; the download pages pass their own item ids so progress
+    /// events match the items they show. Generated when empty.
+    #[serde(default)]
+    pub id: Option<String>,
+    pub url: String,
+    #[serde(default)]
+    pub queue_kind: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub origin: Option<String>,
+    #[serde(default)]
+    pub origin_ref: Option<String>,
+    #[serde(default)]
+    pub priority: Option<i64>,
+    #[serde(default)]
+    pub options: DownloadOptions,
+}
+
+/// Concurrency settings for the backend download scheduler
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DownloadSchedulerConfig {
+    pub max_concurrent: u32,
+    pub max_per_site: u32,
+    /// Output folder for items queued without one (CLI, Telegram)
+    pub default_output_path: Option<String>,
+    pub retry: RetryPolicy,
+    /// Size limits for download folders; items for a full folder wait in the queue
+    pub folder_quotas: Vec<FolderQuota>,
+    /// Download speed shared by all running downloads
+    pub bandwidth: BandwidthConfig,
+}
+
+impl Default for DownloadSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 2,
+            max_per_site: 2,
+            default_output_path: None,
+            retry: RetryPolicy::default(),
+            folder_quotas: Vec::new(),
+            bandwidth: BandwidthConfig::default(),
+        }
+    }
+}
+
+/// Most bytes the files under a folder (and its subfolders) may take up
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderQuota {
+    pub path: String,
+    pub max_bytes: u64,
+}
+
+/// Global download speed budget, split evenly across running downloads
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BandwidthConfig {
+    /// Bytes per second for all downloads together; `None` is unlimited
+    pub limit: Option<u64>,
+    /// Limits for times of day, used instead of `limit` while they apply
+    pub profiles: Vec<BandwidthProfile>,
+}
+
+/// Budget for a daily time window; an end before the start spans midnight
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthProfile {
+    pub start_minute: u32,
+    pub end_minute: u32,
+    /// Bytes per second; `None` is unlimited
+    pub limit: Option<u64>,
+}
+
+/// How failed downloads, metadata fetches and channel polls are retried
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetryPolicy {
+    /// Total tries of a queued download, including the first one
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubled for every further one
+    pub base_delay_secs: u64,
+    pub max_delay_secs: u64,
+    /// How long every job for a site waits after the site rate-limited us
+    pub rate_limit_cooldown_secs: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_secs: 5,
+            max_delay_secs: 300,
+            rate_limit_cooldown_secs: 300,
+        }
+    }
+}
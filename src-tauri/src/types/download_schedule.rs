@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+use super::DownloadOptions;
+
+/// When a scheduled download runs. Times of day are minutes after local midnight.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ScheduleRule {
+    /// Once, at a fixed time (ms); a time missed while the app was closed runs at startup
+    Once { at: i64 },
+    /// Once, inside a daily window. A download still running when the window closes
+    /// is paused and continues when it opens again. An end before the start spans midnight.
+    #[serde(rename_all = "camelCase")]
+    Window { start_minute: u32, end_minute: u32 },
+    /// Every week on the given days (0 = Sunday) at a time of day
+    Weekly { weekdays: Vec<u8>, minute: u32 },
+}
+
+/// A download the backend queues by itself (one `download_schedules` row)
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadSchedule {
+    pub id: String,
+    pub url: String,
+    pub title: Option<String>,
+    pub queue_kind: Option<String>,
+    pub options: DownloadOptions,
+    pub rule: ScheduleRule,
+    pub enabled: bool,
+    /// When the schedule next queues, pauses or resumes its download; unset once it is done
+    pub next_run_at: Option<i64>,
+    pub last_run_at: Option<i64>,
+    /// Queue item the schedule added last
+    pub last_item_id: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Request payload for scheduling a download
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NewDownloadSchedule {
+    pub url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub queue_kind: Option<String>,
+    #[serde(default)]
+    pub options: DownloadOptions,
+    pub rule: ScheduleRule,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_use_the_frontend_shape() {
+        let window: ScheduleRule =
+            serde_json::from_str(r#"{"kind":"window","startMinute":60,"endMinute":360}"#)
+                .expect("window rule");
+        assert_eq!(
+            window,
+            ScheduleRule::Window {
+                start_minute: 60,
+                end_minute: 360,
+            }
+        );
+
+        let weekly = ScheduleRule::Weekly {
+            weekdays: vec![0],
+            minute: 180,
+        };
+        assert_eq!(
+            serde_json::to_string(&weekly).unwrap(),
+            r#"{"kind":"weekly","weekdays":[0],"minute":180}"#
+        );
+    }
+}
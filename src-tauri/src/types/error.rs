@@ -8,6 +8,11 @@ pub mod code {
     pub const VALIDATION_INVALID_URL: &str = "VALIDATION_INVALID_URL";
     pub const VALIDATION_INVALID_INPUT: &str = "VALIDATION_INVALID_INPUT";
     pub const DOWNLOAD_CANCELLED: &str = "DOWNLOAD_CANCELLED";
+    pub const DOWNLOAD_ALREADY_ARCHIVED: &str = "DOWNLOAD_ALREADY_ARCHIVED";
+    pub const DOWNLOAD_FORMAT_UNAVAILABLE: &str = "DOWNLOAD_FORMAT_UNAVAILABLE";
+    pub const DOWNLOAD_VERIFICATION_FAILED: &str = "DOWNLOAD_VERIFICATION_FAILED";
+    pub const DISK_SPACE_INSUFFICIENT: &str = "DISK_SPACE_INSUFFICIENT";
+    pub const STORAGE_QUOTA_EXCEEDED: &str = "STORAGE_QUOTA_EXCEEDED";
     pub const TRANSCRIPT_NOT_AVAILABLE: &str = "TRANSCRIPT_NOT_AVAILABLE";
     pub const YT_RATE_LIMITED: &str = "YT_RATE_LIMITED";
     pub const YT_PRIVATE_VIDEO: &str = "YT_PRIVATE_VIDEO";
@@ -34,6 +39,7 @@ pub mod code {
     pub const YTDLP_APP_NOT_FOUND: &str = "YTDLP_APP_NOT_FOUND";
     pub const YTDLP_SYSTEM_MANAGED: &str = "YTDLP_SYSTEM_MANAGED";
     pub const GALLERYDL_NOT_FOUND: &str = "GALLERYDL_NOT_FOUND";
+    pub const GALLERYDL_SYSTEM_MANAGED: &str = "GALLERYDL_SYSTEM_MANAGED";
     pub const ARIA2_NOT_FOUND: &str = "ARIA2_NOT_FOUND";
     pub const FFMPEG_NOT_FOUND: &str = "FFMPEG_NOT_FOUND";
     pub const FFMPEG_SYSTEM_MANAGED: &str = "FFMPEG_SYSTEM_MANAGED";
@@ -161,6 +167,9 @@ pub fn infer_error_code(message: &str) -> &'static str {
     if m.contains("no transcript available") || m.contains("no subtitles") {
         return code::TRANSCRIPT_NOT_AVAILABLE;
     }
+    if m.contains("no space left on device") || m.contains("not enough space on the disk") {
+        return code::DISK_SPACE_INSUFFICIENT;
+    }
     if m.contains("could not copy") && m.contains("cookie") && m.contains("database") {
         return code::YT_COOKIE_DB_LOCKED;
     }
@@ -214,6 +223,9 @@ pub fn infer_error_code(message: &str) -> &'static str {
     if m.contains("yt-dlp not found") {
         return code::YTDLP_NOT_FOUND;
     }
+    if m.contains("system gallery-dl is managed externally") {
+        return code::GALLERYDL_SYSTEM_MANAGED;
+    }
     if m.contains("gallery-dl not found") || m.contains("system gallery-dl not found") {
         return code::GALLERYDL_NOT_FOUND;
     }
@@ -301,6 +313,7 @@ pub fn default_retryable(code: &str) -> bool {
             | code::PROCESS_START_FAILED
             | code::PROCESS_EXECUTION_FAILED
             | code::PROCESS_EXIT_NON_ZERO
+            | code::DOWNLOAD_VERIFICATION_FAILED
     )
 }
 
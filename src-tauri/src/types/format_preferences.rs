@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::FormatOption;
+
+/// Video codec families a format preference can name
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoCodecFamily {
+    H264,
+    H265,
+    Vp9,
+    Av1,
+}
+
+impl VideoCodecFamily {
+    /// Prefixes of the `vcodec` values yt-dlp reports for this family
+    pub fn vcodec_prefixes(self) -> &'static [&'static str] {
+        match self {
+            Self::H264 => &["avc"],
+            Self::H265 => &["hev", "hvc"],
+            Self::Vp9 => &["vp9", "vp09"],
+            Self::Av1 => &["av01"],
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::H264 => "h264",
+            Self::H265 => "h265",
+            Self::Vp9 => "vp9",
+            Self::Av1 => "av1",
+        }
+    }
+}
+
+/// Which dynamic range a download should use
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum DynamicRangePreference {
+    #[default]
+    Any,
+    PreferSdr,
+    PreferHdr,
+    SdrOnly,
+    HdrOnly,
+}
+
+/// A constraint the fallback order may drop when no format satisfies all of them.
+/// Forbidden codecs and the quality's height limit are never dropped.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FormatConstraint {
+    DynamicRange,
+    MaxFps,
+    AudioLanguage,
+    MaxFilesize,
+}
+
+impl FormatConstraint {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DynamicRange => "dynamicRange",
+            Self::MaxFps => "maxFps",
+            Self::AudioLanguage => "audioLanguage",
+            Self::MaxFilesize => "maxFilesize",
+        }
+    }
+}
+
+/// Declarative format preferences saved in settings and compiled into yt-dlp `-f`/`-S`.
+/// Quality (height limit) and container still come from the download's own options.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FormatPreferences {
+    /// Codecs tried first, in this order, before any other allowed codec
+    pub preferred_codecs: Vec<VideoCodecFamily>,
+    /// Codecs never downloaded
+    pub forbidden_codecs: Vec<VideoCodecFamily>,
+    pub dynamic_range: DynamicRangePreference,
+    pub max_fps: Option<u32>,
+    /// Language code of the audio track, e.g. `en` or `pt-BR`
+    pub audio_language: Option<String>,
+    /// Largest size of each downloaded stream, in MiB
+    pub max_filesize_mb: Option<u64>,
+    /// Constraints dropped one after another, cumulatively, when nothing matches
+    pub fallback_order: Vec<FormatConstraint>,
+}
+
+impl Default for FormatPreferences {
+    fn default() -> Self {
+        Self {
+            preferred_codecs: Vec::new(),
+            forbidden_codecs: Vec::new(),
+            dynamic_range: DynamicRangePreference::Any,
+            max_fps: None,
+            audio_language: None,
+            max_filesize_mb: None,
+            fallback_order: vec![
+                FormatConstraint::AudioLanguage,
+                FormatConstraint::MaxFps,
+                FormatConstraint::DynamicRange,
+                FormatConstraint::MaxFilesize,
+            ],
+        }
+    }
+}
+
+/// One step of a "why this format" explanation; the UI translates `key` with `params`
+#[derive(Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct FormatReason {
+    pub key: String,
+    pub params: BTreeMap<String, String>,
+}
+
+/// Streams picked from a format list by the same rules yt-dlp receives
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatChoice {
+    pub video: Option<FormatOption>,
+    pub audio: Option<FormatOption>,
+    /// Constraints the fallback order had to drop to find these streams
+    pub relaxed: Vec<FormatConstraint>,
+    pub reasons: Vec<FormatReason>,
+}
+
+/// yt-dlp arguments compiled from format preferences, with the reasons behind them
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatPlan {
+    /// Value of `-f`
+    pub format: String,
+    /// Value of `-S`, when any rule sorts instead of filtering
+    pub sort: Option<String>,
+    pub reasons: Vec<FormatReason>,
+    /// Set when a format list was given to explain the choice against
+    pub choice: Option<FormatChoice>,
+}
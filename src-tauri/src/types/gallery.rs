@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// gallery-dl options of one gallery download, from the Gallery page, the CLI or a deep
+/// link. Checked by `validate_gallerydl_options` before they become arguments.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GalleryDlOptions {
+    /// `--range`: positions of the files to download, e.g. `1-20` or `5,10:40:2`
+    pub range: Option<String>,
+    /// `--filter`: condition on each file's metadata, e.g. `width >= 1000 and extension != 'gif'`
+    pub filter: Option<String>,
+    /// `--filename`: format string of file names, e.g. `{id}_{num}.{extension}`
+    pub filename_format: Option<String>,
+    /// `directory` option: one format string per folder under the destination
+    pub directory_format: Vec<String>,
+    /// `--write-metadata`: save the metadata of every file as JSON next to it
+    pub write_metadata: bool,
+    /// `--zip`: store the files of a gallery in a zip archive
+    pub zip: bool,
+    /// `--ugoira-conv`: convert Pixiv ugoira animations to WebM
+    pub ugoira_conv: bool,
+}
+
+impl GalleryDlOptions {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
@@ -32,8 +32,12 @@ pub struct HistoryEntry {
     pub source: Option<String>, // "youtube", "tiktok", etc.
     pub downloaded_at: String,
     pub file_exists: bool,
-    pub summary: Option<String>,    // AI-generated summary
-    pub time_range: Option<String>, // Time range cut (e.g. "00:10-01:00")
+    pub summary: Option<String>,     // AI-generated summary
+    pub time_range: Option<String>,  // Time range cut (e.g. "00:10-01:00")
+    pub parent_id: Option<String>,   // Full download this chapter part was split from
+    pub chapter_index: Option<u32>,  // 1-based chapter number of a part
+    pub playlist_id: Option<String>, // Playlist this entry was downloaded from
+    pub playlist_index: Option<u32>, // 1-based position in that playlist
     pub tags: Vec<HistoryTag>,
     pub collections: Vec<HistoryCollection>,
 }
@@ -86,3 +90,78 @@ pub struct HistoryAdvancedFilters {
     pub collection_ids: Option<Vec<String>>,
     pub match_mode: Option<HistoryFilterMatchMode>,
 }
+
+/// A media file found by a library scan, as it is recorded in history
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportedHistoryEntry {
+    pub url: String, // Empty when nothing tells where the file came from
+    pub title: String,
+    pub thumbnail: Option<String>,
+    pub filepath: String,
+    pub filesize: Option<u64>,
+    pub duration: Option<u64>,
+    pub quality: Option<String>,
+    pub format: Option<String>,
+    pub source: Option<String>,
+    pub downloaded_at: i64, // The file's modification time, in seconds
+    pub content_hash: Option<String>,
+}
+
+/// Where a history entry's file is, and what identifies it when it is moved
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HistoryFileRecord {
+    pub id: String,
+    pub title: String,
+    pub filepath: String,
+    pub filesize: Option<u64>,
+    pub content_hash: Option<String>,
+}
+
+/// A file under the library folders that may be a missing history file
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkCandidate {
+    pub filepath: String,
+    pub filesize: u64,
+    pub same_name: bool,
+    pub same_content: bool, // Same size and content hash
+}
+
+/// A history entry whose file is missing, with the files it may have become
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingHistoryFile {
+    pub history_id: String,
+    pub title: String,
+    pub filepath: String,
+    pub candidates: Vec<RelinkCandidate>,
+}
+
+/// Progress of a search for moved library files, also its result
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryRelinkProgress {
+    pub total: u64, // History entries whose file is missing
+    pub processed: u64,
+    pub relinked: u64,
+    pub not_found: u64,
+    pub ambiguous: Vec<MissingHistoryFile>, // Left for the user to pick a candidate
+    pub current: Option<String>,
+    pub finished: bool,
+    pub cancelled: bool,
+}
+
+/// Progress of a library folder scan, also its result
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryScanProgress {
+    pub folder: String,
+    pub total: u64, // Media files found
+    pub processed: u64,
+    pub imported: u64,
+    pub skipped: u64, // Already in history
+    pub failed: u64,
+    pub current: Option<String>,
+    pub finished: bool,
+    pub cancelled: bool,
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use super::DownloadOptions;
+
+/// Lifecycle state of a live recording
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LiveRecordingStatus {
+    /// Polling the URL until the stream goes live
+    #[default]
+    Waiting,
+    /// A queued download is recording the stream
+    Recording,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl LiveRecordingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Waiting => "waiting",
+            Self::Recording => "recording",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "recording" => Self::Recording,
+            "completed" => Self::Completed,
+            "failed" => Self::Failed,
+            "cancelled" => Self::Cancelled,
+            _ => Self::Waiting,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// An upcoming or running stream the backend records when it goes live
+/// (one `live_recordings` row)
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveRecording {
+    pub id: String,
+    pub url: String,
+    pub title: Option<String>,
+    pub options: DownloadOptions,
+    pub status: LiveRecordingStatus,
+    /// Polls since the recording last started waiting; drives the backoff
+    pub checks: u32,
+    /// Downloads started for the stream; a stream that drops and comes back gets another one
+    pub sessions: u32,
+    /// Polls or sessions that failed in a row
+    pub failures: u32,
+    /// Scheduled start of the stream (ms), when the site announces one
+    pub release_at: Option<i64>,
+    /// When the URL is polled next
+    pub next_check_at: Option<i64>,
+    /// Queue item recording the current session
+    pub current_item_id: Option<String>,
+    pub error_message: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Request payload for recording a stream when it goes live
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NewLiveRecording {
+    pub url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub options: DownloadOptions,
+}
@@ -1,8 +1,15 @@
 mod channel;
 mod dependencies;
 mod download;
+mod download_event;
+mod download_profile;
+mod download_queue;
+mod download_schedule;
 mod error;
+mod format_preferences;
+mod gallery;
 mod history;
+mod live_recording;
 mod log;
 mod plugin;
 mod video;
@@ -11,8 +18,15 @@ mod youtube_search;
 pub use channel::*;
 pub use dependencies::*;
 pub use download::*;
+pub use download_event::*;
+pub use download_profile::*;
+pub use download_queue::*;
+pub use download_schedule::*;
 pub use error::*;
+pub use format_preferences::*;
+pub use gallery::*;
 pub use history::*;
+pub use live_recording::*;
 pub use log::*;
 pub use plugin::*;
 pub use video::*;
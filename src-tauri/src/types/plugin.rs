@@ -653,6 +653,12 @@ pub struct PostDownloadPluginPayload {
     pub history_id: Option<String>,
     pub time_range: Option<String>,
     pub download_kind: String,
+    /// Filepath relative to the output folder, including template subfolders.
+    #[serde(default)]
+    pub relative_path: Option<String>,
+    /// Further files the download produced, such as the parts of a split by chapters.
+    #[serde(default)]
+    pub extra_files: Vec<String>,
     #[serde(default)]
     pub workflow_run_id: Option<String>,
     #[serde(default)]
@@ -38,6 +38,8 @@ pub struct FormatOption {
     pub format_note: Option<String>,
     pub fps: Option<f64>,
     pub quality: Option<f64>,
+    pub dynamic_range: Option<String>, // "SDR", "HDR10", "HLG", "DV", ...
+    pub language: Option<String>,
 }
 
 /// Response containing video info and available formats
@@ -47,6 +49,14 @@ pub struct VideoInfoResponse {
     pub formats: Vec<FormatOption>,
 }
 
+/// Output template rendered against a video before downloading
+#[derive(Clone, Serialize, Debug)]
+pub struct OutputTemplatePreview {
+    pub template: String,
+    pub relative_path: String,
+    pub filepath: Option<String>,
+}
+
 /// Playlist entry with basic video info
 #[derive(Clone, Serialize, Debug)]
 pub struct PlaylistVideoEntry {
@@ -43,3 +43,46 @@ impl CommandExt for std::process::Command {
         self
     }
 }
+
+/// Forcefully terminate a process and every descendant it spawned.
+///
+/// yt-dlp hands merging and post-processing to ffmpeg child processes, so
+/// killing only the direct child would leave them running in the background.
+pub fn kill_process_tree(pid: u32) {
+    #[cfg(unix)]
+    {
+        use std::process::Command as StdCommand;
+
+        let mut pids = vec![pid];
+        let mut index = 0;
+        while index < pids.len() {
+            let parent = pids[index].to_string();
+            if let Ok(output) = StdCommand::new("pgrep").args(["-P", &parent]).output() {
+                for child in String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .filter_map(|line| line.trim().parse::<u32>().ok())
+                {
+                    if !pids.contains(&child) {
+                        pids.push(child);
+                    }
+                }
+            }
+            index += 1;
+        }
+
+        // Kill descendants first so the parent cannot react to their exit.
+        for target in pids.iter().rev() {
+            StdCommand::new("kill")
+                .args(["-9", &target.to_string()])
+                .output()
+                .ok();
+        }
+    }
+    #[cfg(windows)]
+    {
+        let mut cmd = std::process::Command::new("taskkill");
+        cmd.args(["/F", "/T", "/PID", &pid.to_string()]);
+        cmd.hide_window();
+        cmd.output().ok();
+    }
+}
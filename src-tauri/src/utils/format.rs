@@ -1,3 +1,7 @@
+use serde_json::Value;
+
+use crate::types::{code, BackendError, FormatOption};
+
 /// Format file size in human readable format
 pub fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
@@ -15,10 +19,29 @@ pub fn format_size(bytes: u64) -> String {
     }
 }
 
+/// Whether a quality/format pair asks for audio only
+pub fn is_audio_only_request(quality: &str, format: &str) -> bool {
+    quality == "audio" || format == "mp3" || format == "m4a" || format == "opus"
+}
+
+/// Largest video height allowed by a quality setting; None means no limit
+pub fn max_height_for_quality(quality: &str) -> Option<u32> {
+    match quality {
+        "8k" => Some(4320),
+        "4k" => Some(2160),
+        "2k" => Some(1440),
+        "1080" => Some(1080),
+        "720" => Some(720),
+        "480" => Some(480),
+        "360" => Some(360),
+        _ => None,
+    }
+}
+
 /// Build yt-dlp format string based on quality, format and codec preferences
 pub fn build_format_string(quality: &str, format: &str, video_codec: &str) -> String {
     // Audio-only formats
-    if quality == "audio" || format == "mp3" || format == "m4a" || format == "opus" {
+    if is_audio_only_request(quality, format) {
         return match format {
             "mp3" => "bestaudio/best".to_string(),
             "m4a" => "bestaudio[ext=m4a]/bestaudio/best".to_string(),
@@ -27,16 +50,7 @@ pub fn build_format_string(quality: &str, format: &str, video_codec: &str) -> St
         };
     }
 
-    let height = match quality {
-        "8k" => Some("4320"),
-        "4k" => Some("2160"),
-        "2k" => Some("1440"),
-        "1080" => Some("1080"),
-        "720" => Some("720"),
-        "480" => Some("480"),
-        "360" => Some("360"),
-        _ => None,
-    };
+    let height = max_height_for_quality(quality);
 
     // Build codec filter based on user selection
     // Respect user's explicit codec choice for ALL qualities
@@ -184,9 +198,156 @@ pub fn build_format_string(quality: &str, format: &str, video_codec: &str) -> St
     }
 }
 
+/// Read the formats of a yt-dlp info JSON
+pub fn parse_format_options(json: &Value) -> Vec<FormatOption> {
+    let Some(formats_arr) = json.get("formats").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+    formats_arr
+        .iter()
+        .filter_map(|f| {
+            let format_id = f.get("format_id").and_then(|v| v.as_str())?;
+            let ext = f.get("ext").and_then(|v| v.as_str()).unwrap_or("unknown");
+
+            Some(FormatOption {
+                format_id: format_id.to_string(),
+                ext: ext.to_string(),
+                resolution: f
+                    .get("resolution")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                width: f.get("width").and_then(|v| v.as_u64()).map(|v| v as u32),
+                height: f.get("height").and_then(|v| v.as_u64()).map(|v| v as u32),
+                vcodec: f
+                    .get("vcodec")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                acodec: f
+                    .get("acodec")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                filesize: f.get("filesize").and_then(|v| v.as_u64()),
+                filesize_approx: f.get("filesize_approx").and_then(|v| v.as_u64()),
+                tbr: f.get("tbr").and_then(|v| v.as_f64()),
+                format_note: f
+                    .get("format_note")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                fps: f.get("fps").and_then(|v| v.as_f64()),
+                quality: f.get("quality").and_then(|v| v.as_f64()),
+                dynamic_range: f
+                    .get("dynamic_range")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                language: f
+                    .get("language")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Build a yt-dlp format string from explicitly picked format ids: the video stream
+/// merged with every audio stream. Returns None when nothing was picked.
+pub fn build_explicit_format_string(
+    video_format_id: Option<&str>,
+    audio_format_ids: &[String],
+) -> Option<String> {
+    let ids: Vec<&str> = video_format_id
+        .into_iter()
+        .chain(audio_format_ids.iter().map(|id| id.as_str()))
+        .collect();
+    if ids.is_empty() {
+        None
+    } else {
+        Some(ids.join("+"))
+    }
+}
+
+/// yt-dlp marks a missing stream with the codec "none"; an unknown codec may still be there.
+pub(super) fn has_stream(codec: &Option<String>) -> bool {
+    codec.as_deref() != Some("none")
+}
+
+/// Check explicitly picked format ids against the formats the extractor offers:
+/// every id must exist, the video id must carry video and each audio id audio.
+pub fn validate_format_selection(
+    formats: &[FormatOption],
+    video_format_id: Option<&str>,
+    audio_format_ids: &[String],
+) -> Result<(), BackendError> {
+    let picked = video_format_id
+        .into_iter()
+        .map(|id| (id, true))
+        .chain(audio_format_ids.iter().map(|id| (id.as_str(), false)));
+    let mut seen = Vec::new();
+    for (format_id, is_video) in picked {
+        // Ids are joined into a format selector, so selector syntax must not sneak in.
+        if format_id.is_empty()
+            || !format_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(BackendError::new(
+                code::VALIDATION_INVALID_INPUT,
+                format!("Invalid format id: {}", format_id),
+            )
+            .with_retryable(false));
+        }
+        if seen.contains(&format_id) {
+            return Err(BackendError::new(
+                code::VALIDATION_INVALID_INPUT,
+                format!("Format {} was selected more than once", format_id),
+            )
+            .with_retryable(false));
+        }
+        seen.push(format_id);
+
+        let Some(format) = formats.iter().find(|f| f.format_id == format_id) else {
+            return Err(format_unavailable(
+                format_id,
+                "is not offered for this video",
+            ));
+        };
+        if is_video && !has_stream(&format.vcodec) {
+            return Err(format_unavailable(format_id, "has no video stream"));
+        }
+        if !is_video && !has_stream(&format.acodec) {
+            return Err(format_unavailable(format_id, "has no audio stream"));
+        }
+    }
+    Ok(())
+}
+
+/// Size of the picked formats together, if the site reports it for each of them.
+pub fn selected_formats_size(
+    formats: &[FormatOption],
+    video_format_id: Option<&str>,
+    audio_format_ids: &[String],
+) -> Option<u64> {
+    video_format_id
+        .into_iter()
+        .chain(audio_format_ids.iter().map(|id| id.as_str()))
+        .map(|format_id| {
+            let format = formats.iter().find(|f| f.format_id == format_id)?;
+            format.filesize.or(format.filesize_approx)
+        })
+        .sum()
+}
+
+fn format_unavailable(format_id: &str, reason: &str) -> BackendError {
+    BackendError::new(
+        code::DOWNLOAD_FORMAT_UNAVAILABLE,
+        format!("Format {} {}", format_id, reason),
+    )
+    .with_param("formatId", format_id)
+    .with_retryable(false)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::build_format_string;
+    use super::*;
 
     #[test]
     fn webm_4k_ignores_h264_and_uses_webm_streams() {
@@ -206,4 +367,85 @@ mod tests {
         assert!(format.contains("bestvideo[height<=2160][ext=webm][vcodec^=av01]"));
         assert!(format.contains("bestaudio[ext=webm]"));
     }
+
+    fn format(format_id: &str, vcodec: &str, acodec: &str) -> FormatOption {
+        FormatOption {
+            format_id: format_id.to_string(),
+            ext: "mp4".to_string(),
+            resolution: None,
+            width: None,
+            height: None,
+            vcodec: Some(vcodec.to_string()),
+            acodec: Some(acodec.to_string()),
+            filesize: None,
+            filesize_approx: None,
+            tbr: None,
+            format_note: None,
+            fps: None,
+            quality: None,
+            dynamic_range: None,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn explicit_format_ids_merge_video_with_every_audio_track() {
+        let audio = vec!["251-0".to_string(), "251-1".to_string()];
+
+        assert_eq!(
+            build_explicit_format_string(Some("137"), &audio).as_deref(),
+            Some("137+251-0+251-1")
+        );
+        assert_eq!(
+            build_explicit_format_string(None, &audio[..1]).as_deref(),
+            Some("251-0")
+        );
+        assert_eq!(build_explicit_format_string(None, &[]), None);
+    }
+
+    #[test]
+    fn format_selection_is_checked_against_offered_formats() {
+        let formats = vec![
+            format("137", "avc1.640028", "none"),
+            format("251-0", "none", "opus"),
+            format("251-1", "none", "opus"),
+        ];
+        let audio = vec!["251-0".to_string(), "251-1".to_string()];
+        assert!(validate_format_selection(&formats, Some("137"), &audio).is_ok());
+
+        let missing = validate_format_selection(&formats, Some("299"), &[]).unwrap_err();
+        assert_eq!(missing.code(), code::DOWNLOAD_FORMAT_UNAVAILABLE);
+
+        let audio_as_video = validate_format_selection(&formats, Some("251-0"), &[]).unwrap_err();
+        assert_eq!(audio_as_video.code(), code::DOWNLOAD_FORMAT_UNAVAILABLE);
+
+        let video_as_audio =
+            validate_format_selection(&formats, None, &["137".to_string()]).unwrap_err();
+        assert_eq!(video_as_audio.code(), code::DOWNLOAD_FORMAT_UNAVAILABLE);
+
+        let injected = validate_format_selection(&formats, Some("137+bestaudio"), &[]).unwrap_err();
+        assert_eq!(injected.code(), code::VALIDATION_INVALID_INPUT);
+
+        let repeated = vec!["251-0".to_string(), "251-0".to_string()];
+        let duplicate = validate_format_selection(&formats, Some("137"), &repeated).unwrap_err();
+        assert_eq!(duplicate.code(), code::VALIDATION_INVALID_INPUT);
+    }
+
+    #[test]
+    fn selected_formats_size_needs_every_size() {
+        let mut video = format("137", "avc1.640028", "none");
+        video.filesize = Some(40_000_000);
+        let mut audio = format("140", "none", "mp4a.40.2");
+        audio.filesize_approx = Some(3_000_000);
+        let formats = vec![video, audio, format("251", "none", "opus")];
+
+        assert_eq!(
+            selected_formats_size(&formats, Some("137"), &["140".to_string()]),
+            Some(43_000_000)
+        );
+        assert_eq!(
+            selected_formats_size(&formats, Some("137"), &["251".to_string()]),
+            None
+        );
+    }
 }
@@ -0,0 +1,842 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use super::format::has_stream;
+use super::{is_audio_only_request, max_height_for_quality};
+use crate::types::{
+    code, BackendError, DynamicRangePreference, FormatChoice, FormatConstraint, FormatOption,
+    FormatPlan, FormatPreferences, FormatReason, VideoCodecFamily,
+};
+
+/// Preferences checked and resolved against one download's quality and container
+struct Rules<'a> {
+    prefs: &'a FormatPreferences,
+    max_height: Option<u32>,
+    audio_only: bool,
+    audio_language: Option<String>,
+    /// Preferred codecs that are not also forbidden, without repeats
+    preferred: Vec<VideoCodecFamily>,
+    container: String,
+}
+
+/// One selector of the `-f` expression; alternatives are tried in order
+#[derive(Clone, Copy)]
+enum Selector {
+    /// Best video-only stream (of a codec, when set) merged with the best audio stream
+    Merge(Option<(VideoCodecFamily, &'static str)>),
+    /// Best single file that already carries every needed stream
+    Single,
+    /// Best audio-only stream
+    Audio,
+}
+
+struct Alternative<'t> {
+    relaxed: &'t [FormatConstraint],
+    selector: Selector,
+}
+
+fn reason(key: &str, params: &[(&str, String)]) -> FormatReason {
+    FormatReason {
+        key: key.to_string(),
+        params: params
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect::<BTreeMap<_, _>>(),
+    }
+}
+
+fn codec_list(codecs: &[VideoCodecFamily], separator: &str) -> String {
+    codecs
+        .iter()
+        .map(|codec| codec.as_str())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+impl<'a> Rules<'a> {
+    fn new(
+        prefs: &'a FormatPreferences,
+        quality: &str,
+        format: &str,
+    ) -> Result<Self, BackendError> {
+        let audio_language = prefs
+            .audio_language
+            .as_deref()
+            .map(str::trim)
+            .filter(|lang| !lang.is_empty())
+            .map(str::to_string);
+        // The language is pasted into the selector, so only plain language tags are allowed.
+        if let Some(lang) = &audio_language {
+            if lang.len() > 16 || !lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                return Err(invalid_preference(format!(
+                    "Invalid audio language: {}",
+                    lang
+                )));
+            }
+        }
+        if prefs.max_fps == Some(0) {
+            return Err(invalid_preference("Maximum fps must be greater than 0"));
+        }
+        if prefs.max_filesize_mb == Some(0) {
+            return Err(invalid_preference(
+                "Maximum file size must be greater than 0",
+            ));
+        }
+
+        let mut preferred = Vec::new();
+        for codec in &prefs.preferred_codecs {
+            if !prefs.forbidden_codecs.contains(codec) && !preferred.contains(codec) {
+                preferred.push(*codec);
+            }
+        }
+
+        Ok(Self {
+            prefs,
+            max_height: max_height_for_quality(quality),
+            audio_only: is_audio_only_request(quality, format),
+            audio_language,
+            preferred,
+            container: format.to_string(),
+        })
+    }
+
+    /// Whether a constraint is set at all; unset ones are skipped by the fallback order.
+    fn is_set(&self, constraint: FormatConstraint) -> bool {
+        match constraint {
+            FormatConstraint::DynamicRange => {
+                !self.audio_only
+                    && matches!(
+                        self.prefs.dynamic_range,
+                        DynamicRangePreference::SdrOnly | DynamicRangePreference::HdrOnly
+                    )
+            }
+            FormatConstraint::MaxFps => !self.audio_only && self.prefs.max_fps.is_some(),
+            FormatConstraint::AudioLanguage => self.audio_language.is_some(),
+            FormatConstraint::MaxFilesize => self.prefs.max_filesize_mb.is_some(),
+        }
+    }
+
+    /// Constraints to drop, strictest tier first: nothing, then each step of the fallback order.
+    fn tiers(&self) -> Vec<Vec<FormatConstraint>> {
+        let mut tiers = vec![Vec::new()];
+        let mut relaxed = Vec::new();
+        for constraint in &self.prefs.fallback_order {
+            if self.is_set(*constraint) && !relaxed.contains(constraint) {
+                relaxed.push(*constraint);
+                tiers.push(relaxed.clone());
+            }
+        }
+        tiers
+    }
+
+    fn selectors(&self) -> Vec<Selector> {
+        if self.audio_only {
+            return vec![Selector::Audio, Selector::Single];
+        }
+        let mut selectors = Vec::new();
+        for codec in &self.preferred {
+            for prefix in codec.vcodec_prefixes() {
+                selectors.push(Selector::Merge(Some((*codec, *prefix))));
+            }
+        }
+        selectors.push(Selector::Merge(None));
+        selectors.push(Selector::Single);
+        selectors
+    }
+
+    fn alternatives<'t>(&self, tiers: &'t [Vec<FormatConstraint>]) -> Vec<Alternative<'t>> {
+        let selectors = self.selectors();
+        let mut alternatives = Vec::new();
+        for relaxed in tiers {
+            for selector in &selectors {
+                alternatives.push(Alternative {
+                    relaxed: relaxed.as_slice(),
+                    selector: *selector,
+                });
+            }
+        }
+        alternatives
+    }
+
+    fn video_filter(&self, relaxed: &[FormatConstraint], prefix: Option<&str>) -> String {
+        if self.audio_only {
+            return String::new();
+        }
+        let mut filter = String::new();
+        if let Some(height) = self.max_height {
+            filter.push_str(&format!("[height<={}]", height));
+        }
+        if let Some(prefix) = prefix {
+            filter.push_str(&format!("[vcodec^={}]", prefix));
+        }
+        for codec in &self.prefs.forbidden_codecs {
+            for prefix in codec.vcodec_prefixes() {
+                filter.push_str(&format!("[vcodec!^=?{}]", prefix));
+            }
+        }
+        if !relaxed.contains(&FormatConstraint::DynamicRange) {
+            match self.prefs.dynamic_range {
+                DynamicRangePreference::SdrOnly => filter.push_str("[dynamic_range=?SDR]"),
+                DynamicRangePreference::HdrOnly => filter.push_str("[dynamic_range!=SDR]"),
+                _ => {}
+            }
+        }
+        if let Some(fps) = self.prefs.max_fps {
+            if !relaxed.contains(&FormatConstraint::MaxFps) {
+                filter.push_str(&format!("[fps<=?{}]", fps));
+            }
+        }
+        filter.push_str(&self.size_filter(relaxed));
+        filter
+    }
+
+    fn audio_filter(&self, relaxed: &[FormatConstraint]) -> String {
+        format!(
+            "{}{}",
+            self.language_filter(relaxed),
+            self.size_filter(relaxed)
+        )
+    }
+
+    fn language_filter(&self, relaxed: &[FormatConstraint]) -> String {
+        match &self.audio_language {
+            Some(lang) if !relaxed.contains(&FormatConstraint::AudioLanguage) => {
+                format!("[language^={}]", lang)
+            }
+            _ => String::new(),
+        }
+    }
+
+    fn size_filter(&self, relaxed: &[FormatConstraint]) -> String {
+        match self.prefs.max_filesize_mb {
+            Some(mb) if !relaxed.contains(&FormatConstraint::MaxFilesize) => {
+                format!("[filesize<=?{}MiB][filesize_approx<=?{}MiB]", mb, mb)
+            }
+            _ => String::new(),
+        }
+    }
+
+    fn render(&self, alternative: &Alternative) -> String {
+        let relaxed = alternative.relaxed;
+        match alternative.selector {
+            Selector::Merge(codec) => format!(
+                "bv{}+ba{}",
+                self.video_filter(relaxed, codec.map(|(_, prefix)| prefix)),
+                self.audio_filter(relaxed)
+            ),
+            // One file: the size limit of the video filter covers it as a whole.
+            Selector::Single if self.audio_only => format!("b{}", self.audio_filter(relaxed)),
+            Selector::Single => format!(
+                "b{}{}",
+                self.video_filter(relaxed, None),
+                self.language_filter(relaxed)
+            ),
+            Selector::Audio => format!("ba{}", self.audio_filter(relaxed)),
+        }
+    }
+
+    fn sort(&self) -> Option<String> {
+        let mut keys = Vec::new();
+        if !self.audio_only {
+            match self.prefs.dynamic_range {
+                DynamicRangePreference::PreferHdr => keys.push("hdr"),
+                DynamicRangePreference::PreferSdr => keys.push("+hdr"),
+                _ => {}
+            }
+            if self.container == "mp4" {
+                keys.push("ext:mp4:m4a");
+            }
+        }
+        if keys.is_empty() {
+            return None;
+        }
+        // Keep resolution ahead of the sort keys added here.
+        if !self.audio_only {
+            keys.insert(0, "res");
+        }
+        Some(keys.join(","))
+    }
+
+    fn reasons(&self) -> Vec<FormatReason> {
+        let mut reasons = Vec::new();
+        if self.audio_only {
+            reasons.push(reason("audioOnly", &[]));
+        } else {
+            if let Some(height) = self.max_height {
+                reasons.push(reason("maxHeight", &[("height", height.to_string())]));
+            }
+            if !self.preferred.is_empty() {
+                reasons.push(reason(
+                    "preferredCodecs",
+                    &[("codecs", codec_list(&self.preferred, " > "))],
+                ));
+            }
+            if !self.prefs.forbidden_codecs.is_empty() {
+                reasons.push(reason(
+                    "forbiddenCodecs",
+                    &[("codecs", codec_list(&self.prefs.forbidden_codecs, ", "))],
+                ));
+            }
+            let dynamic_range = match self.prefs.dynamic_range {
+                DynamicRangePreference::Any => None,
+                DynamicRangePreference::PreferSdr => Some("preferSdr"),
+                DynamicRangePreference::PreferHdr => Some("preferHdr"),
+                DynamicRangePreference::SdrOnly => Some("sdrOnly"),
+                DynamicRangePreference::HdrOnly => Some("hdrOnly"),
+            };
+            if let Some(key) = dynamic_range {
+                reasons.push(reason(key, &[]));
+            }
+            if let Some(fps) = self.prefs.max_fps {
+                reasons.push(reason("maxFps", &[("fps", fps.to_string())]));
+            }
+            if self.container == "mp4" {
+                reasons.push(reason("containerMp4", &[]));
+            }
+        }
+        if let Some(lang) = &self.audio_language {
+            reasons.push(reason("audioLanguage", &[("language", lang.clone())]));
+        }
+        if let Some(mb) = self.prefs.max_filesize_mb {
+            reasons.push(reason("maxFilesize", &[("size", mb.to_string())]));
+        }
+        let tiers = self.tiers();
+        if let Some(loosest) = tiers.last() {
+            for (index, constraint) in loosest.iter().enumerate() {
+                reasons.push(reason(
+                    "fallback",
+                    &[
+                        ("step", (index + 1).to_string()),
+                        ("constraint", constraint.as_str().to_string()),
+                    ],
+                ));
+            }
+        }
+        reasons
+    }
+
+    fn video_matches(
+        &self,
+        format: &FormatOption,
+        relaxed: &[FormatConstraint],
+        prefix: Option<&str>,
+    ) -> bool {
+        if self.audio_only {
+            return true;
+        }
+        let vcodec = format.vcodec.as_deref();
+        if let Some(height) = self.max_height {
+            if format.height.map_or(true, |h| h > height) {
+                return false;
+            }
+        }
+        if let Some(prefix) = prefix {
+            if !vcodec.is_some_and(|v| v.starts_with(prefix)) {
+                return false;
+            }
+        }
+        let forbidden = self
+            .prefs
+            .forbidden_codecs
+            .iter()
+            .flat_map(|codec| codec.vcodec_prefixes())
+            .any(|prefix| vcodec.is_some_and(|v| v.starts_with(prefix)));
+        if forbidden {
+            return false;
+        }
+        if !relaxed.contains(&FormatConstraint::DynamicRange) {
+            let range = format.dynamic_range.as_deref();
+            match self.prefs.dynamic_range {
+                DynamicRangePreference::SdrOnly if range.is_some_and(|r| r != "SDR") => {
+                    return false
+                }
+                DynamicRangePreference::HdrOnly if range.map_or(true, |r| r == "SDR") => {
+                    return false
+                }
+                _ => {}
+            }
+        }
+        if let Some(max_fps) = self.prefs.max_fps {
+            if !relaxed.contains(&FormatConstraint::MaxFps)
+                && format.fps.is_some_and(|fps| fps > f64::from(max_fps))
+            {
+                return false;
+            }
+        }
+        self.size_matches(format, relaxed)
+    }
+
+    fn audio_matches(&self, format: &FormatOption, relaxed: &[FormatConstraint]) -> bool {
+        if let Some(lang) = &self.audio_language {
+            if !relaxed.contains(&FormatConstraint::AudioLanguage)
+                && !format
+                    .language
+                    .as_deref()
+                    .is_some_and(|l| l.starts_with(lang.as_str()))
+            {
+                return false;
+            }
+        }
+        self.size_matches(format, relaxed)
+    }
+
+    fn size_matches(&self, format: &FormatOption, relaxed: &[FormatConstraint]) -> bool {
+        match self.prefs.max_filesize_mb {
+            Some(mb) if !relaxed.contains(&FormatConstraint::MaxFilesize) => {
+                let limit = mb.saturating_mul(1024 * 1024);
+                format.filesize.map_or(true, |size| size <= limit)
+                    && format.filesize_approx.map_or(true, |size| size <= limit)
+            }
+            _ => true,
+        }
+    }
+
+    /// Order video streams roughly like yt-dlp: resolution, fps, then dynamic range.
+    fn compare_video(&self, a: &FormatOption, b: &FormatOption) -> Ordering {
+        let hdr_rank = |f: &FormatOption| {
+            let is_hdr = f.dynamic_range.as_deref().is_some_and(|r| r != "SDR");
+            match self.prefs.dynamic_range {
+                DynamicRangePreference::PreferSdr => i32::from(!is_hdr),
+                _ => i32::from(is_hdr),
+            }
+        };
+        a.height
+            .unwrap_or(0)
+            .cmp(&b.height.unwrap_or(0))
+            .then_with(|| compare_f64(a.fps, b.fps))
+            .then_with(|| hdr_rank(a).cmp(&hdr_rank(b)))
+            .then_with(|| compare_f64(a.tbr, b.tbr))
+    }
+}
+
+fn compare_f64(a: Option<f64>, b: Option<f64>) -> Ordering {
+    a.unwrap_or(0.0)
+        .partial_cmp(&b.unwrap_or(0.0))
+        .unwrap_or(Ordering::Equal)
+}
+
+fn compare_audio(a: &FormatOption, b: &FormatOption) -> Ordering {
+    compare_f64(a.tbr, b.tbr)
+}
+
+fn invalid_preference(message: impl Into<String>) -> BackendError {
+    BackendError::new(code::VALIDATION_INVALID_INPUT, message).with_retryable(false)
+}
+
+/// Compile format preferences into yt-dlp `-f` and `-S` values for a download
+/// with the given quality and container, with the reasons behind every filter.
+pub fn compile_format_preferences(
+    prefs: &FormatPreferences,
+    quality: &str,
+    format: &str,
+) -> Result<FormatPlan, BackendError> {
+    let rules = Rules::new(prefs, quality, format)?;
+    let tiers = rules.tiers();
+    let mut selectors: Vec<String> = Vec::new();
+    for alternative in rules.alternatives(&tiers) {
+        let rendered = rules.render(&alternative);
+        if !selectors.contains(&rendered) {
+            selectors.push(rendered);
+        }
+    }
+
+    Ok(FormatPlan {
+        format: selectors.join("/"),
+        sort: rules.sort(),
+        reasons: rules.reasons(),
+        choice: None,
+    })
+}
+
+/// Pick streams from a `get_video_info` format list the way the compiled expression
+/// would, and explain the pick. Returns None when no format satisfies the rules.
+pub fn explain_format_choice(
+    prefs: &FormatPreferences,
+    quality: &str,
+    format: &str,
+    formats: &[FormatOption],
+) -> Result<Option<FormatChoice>, BackendError> {
+    let rules = Rules::new(prefs, quality, format)?;
+    let tiers = rules.tiers();
+    let video_only =
+        |f: &&FormatOption| has_stream(&f.vcodec) && f.acodec.as_deref() == Some("none");
+    let audio_only =
+        |f: &&FormatOption| has_stream(&f.acodec) && f.vcodec.as_deref() == Some("none");
+
+    for alternative in rules.alternatives(&tiers) {
+        let relaxed = alternative.relaxed;
+        let best_audio = || {
+            formats
+                .iter()
+                .filter(audio_only)
+                .filter(|f| rules.audio_matches(f, relaxed))
+                .max_by(|a, b| compare_audio(a, b))
+        };
+        let (video, audio) = match alternative.selector {
+            Selector::Merge(codec) => {
+                let prefix = codec.map(|(_, prefix)| prefix);
+                let video = formats
+                    .iter()
+                    .filter(video_only)
+                    .filter(|f| rules.video_matches(f, relaxed, prefix))
+                    .max_by(|a, b| rules.compare_video(a, b));
+                match (video, best_audio()) {
+                    (Some(video), Some(audio)) => (Some(video), Some(audio)),
+                    _ => continue,
+                }
+            }
+            Selector::Audio => match best_audio() {
+                Some(audio) => (None, Some(audio)),
+                None => continue,
+            },
+            Selector::Single => {
+                let single = formats
+                    .iter()
+                    .filter(|f| has_stream(&f.acodec))
+                    .filter(|f| rules.audio_only || has_stream(&f.vcodec))
+                    .filter(|f| rules.video_matches(f, relaxed, None))
+                    .filter(|f| rules.audio_matches(f, relaxed))
+                    .max_by(|a, b| rules.compare_video(a, b).then_with(|| compare_audio(a, b)));
+                match single {
+                    Some(single) if rules.audio_only => (None, Some(single)),
+                    Some(single) => (Some(single), None),
+                    None => continue,
+                }
+            }
+        };
+
+        let mut reasons = Vec::new();
+        for constraint in relaxed {
+            reasons.push(reason(
+                "relaxed",
+                &[("constraint", constraint.as_str().to_string())],
+            ));
+        }
+        match alternative.selector {
+            Selector::Merge(Some((codec, _))) => {
+                reasons.push(reason(
+                    "matchedPreferredCodec",
+                    &[("codec", codec.as_str().to_string())],
+                ));
+            }
+            Selector::Merge(None) if !rules.preferred.is_empty() => {
+                reasons.push(reason("noPreferredCodec", &[]));
+            }
+            Selector::Single => reasons.push(reason("singleFile", &[])),
+            _ => {}
+        }
+        if let Some(video) = video {
+            reasons.push(reason(
+                "videoStream",
+                &[
+                    ("formatId", video.format_id.clone()),
+                    (
+                        "resolution",
+                        video
+                            .resolution
+                            .clone()
+                            .or_else(|| video.height.map(|h| format!("{}p", h)))
+                            .unwrap_or_default(),
+                    ),
+                    ("codec", video.vcodec.clone().unwrap_or_default()),
+                ],
+            ));
+        }
+        if let Some(audio) = audio {
+            reasons.push(reason(
+                "audioStream",
+                &[
+                    ("formatId", audio.format_id.clone()),
+                    ("codec", audio.acodec.clone().unwrap_or_default()),
+                    ("language", audio.language.clone().unwrap_or_default()),
+                ],
+            ));
+        }
+
+        return Ok(Some(FormatChoice {
+            video: video.cloned(),
+            audio: audio.cloned(),
+            relaxed: relaxed.to_vec(),
+            reasons,
+        }));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prefs() -> FormatPreferences {
+        FormatPreferences {
+            fallback_order: Vec::new(),
+            ..Default::default()
+        }
+    }
+
+    fn compile(prefs: &FormatPreferences, quality: &str) -> FormatPlan {
+        compile_format_preferences(prefs, quality, "mkv").unwrap()
+    }
+
+    fn stream(format_id: &str, vcodec: &str, acodec: &str) -> FormatOption {
+        FormatOption {
+            format_id: format_id.to_string(),
+            ext: "mp4".to_string(),
+            resolution: None,
+            width: None,
+            height: None,
+            vcodec: Some(vcodec.to_string()),
+            acodec: Some(acodec.to_string()),
+            filesize: None,
+            filesize_approx: None,
+            tbr: None,
+            format_note: None,
+            fps: None,
+            quality: None,
+            dynamic_range: None,
+            language: None,
+        }
+    }
+
+    fn video(format_id: &str, vcodec: &str, height: u32, fps: f64) -> FormatOption {
+        FormatOption {
+            height: Some(height),
+            fps: Some(fps),
+            dynamic_range: Some("SDR".to_string()),
+            ..stream(format_id, vcodec, "none")
+        }
+    }
+
+    fn audio(format_id: &str, language: &str, tbr: f64) -> FormatOption {
+        FormatOption {
+            language: Some(language.to_string()),
+            tbr: Some(tbr),
+            ..stream(format_id, "none", "opus")
+        }
+    }
+
+    #[test]
+    fn without_rules_the_best_streams_are_merged() {
+        let plan = compile(&prefs(), "best");
+        assert_eq!(plan.format, "bv+ba/b");
+        assert_eq!(plan.sort, None);
+    }
+
+    #[test]
+    fn preferred_codecs_are_tried_in_order() {
+        let prefs = FormatPreferences {
+            preferred_codecs: vec![VideoCodecFamily::Av1, VideoCodecFamily::Vp9],
+            ..prefs()
+        };
+        let plan = compile(&prefs, "1080");
+        assert_eq!(
+            plan.format,
+            "bv[height<=1080][vcodec^=av01]+ba/bv[height<=1080][vcodec^=vp9]+ba/\
+             bv[height<=1080][vcodec^=vp09]+ba/bv[height<=1080]+ba/b[height<=1080]"
+        );
+        assert!(plan.reasons.contains(&reason(
+            "preferredCodecs",
+            &[("codecs", "av1 > vp9".to_string())]
+        )));
+    }
+
+    #[test]
+    fn forbidden_codecs_filter_every_alternative() {
+        let prefs = FormatPreferences {
+            preferred_codecs: vec![VideoCodecFamily::H265, VideoCodecFamily::Vp9],
+            forbidden_codecs: vec![VideoCodecFamily::H265],
+            fallback_order: vec![FormatConstraint::MaxFps],
+            max_fps: Some(30),
+            ..Default::default()
+        };
+        let plan = compile(&prefs, "best");
+        for selector in plan.format.split('/') {
+            assert!(
+                selector.contains("[vcodec!^=?hev][vcodec!^=?hvc]"),
+                "{}",
+                selector
+            );
+        }
+        assert!(!plan.format.contains("[vcodec^=hev]"));
+    }
+
+    #[test]
+    fn dynamic_range_filters_or_sorts() {
+        let sdr_only = FormatPreferences {
+            dynamic_range: DynamicRangePreference::SdrOnly,
+            ..prefs()
+        };
+        assert_eq!(
+            compile(&sdr_only, "best").format,
+            "bv[dynamic_range=?SDR]+ba/b[dynamic_range=?SDR]"
+        );
+
+        let hdr_only = FormatPreferences {
+            dynamic_range: DynamicRangePreference::HdrOnly,
+            ..prefs()
+        };
+        assert!(compile(&hdr_only, "best")
+            .format
+            .starts_with("bv[dynamic_range!=SDR]+ba"));
+
+        let prefer_sdr = FormatPreferences {
+            dynamic_range: DynamicRangePreference::PreferSdr,
+            ..prefs()
+        };
+        let plan = compile(&prefer_sdr, "best");
+        assert_eq!(plan.format, "bv+ba/b");
+        assert_eq!(plan.sort.as_deref(), Some("res,+hdr"));
+
+        let prefer_hdr = FormatPreferences {
+            dynamic_range: DynamicRangePreference::PreferHdr,
+            ..prefs()
+        };
+        assert_eq!(
+            compile(&prefer_hdr, "best").sort.as_deref(),
+            Some("res,hdr")
+        );
+    }
+
+    #[test]
+    fn max_fps_keeps_formats_without_fps() {
+        let prefs = FormatPreferences {
+            max_fps: Some(30),
+            ..prefs()
+        };
+        assert_eq!(
+            compile(&prefs, "best").format,
+            "bv[fps<=?30]+ba/b[fps<=?30]"
+        );
+    }
+
+    #[test]
+    fn audio_language_filters_the_audio_stream() {
+        let prefs = FormatPreferences {
+            audio_language: Some(" pt-BR ".to_string()),
+            ..prefs()
+        };
+        assert_eq!(
+            compile(&prefs, "best").format,
+            "bv+ba[language^=pt-BR]/b[language^=pt-BR]"
+        );
+
+        let injected = FormatPreferences {
+            audio_language: Some("en]+bv".to_string()),
+            ..Default::default()
+        };
+        let error = compile_format_preferences(&injected, "best", "mp4").unwrap_err();
+        assert_eq!(error.code(), code::VALIDATION_INVALID_INPUT);
+    }
+
+    #[test]
+    fn max_filesize_limits_each_stream() {
+        let prefs = FormatPreferences {
+            max_filesize_mb: Some(500),
+            ..prefs()
+        };
+        let limit = "[filesize<=?500MiB][filesize_approx<=?500MiB]";
+        assert_eq!(
+            compile(&prefs, "best").format,
+            format!("bv{limit}+ba{limit}/b{limit}", limit = limit)
+        );
+    }
+
+    #[test]
+    fn fallback_order_relaxes_constraints_cumulatively() {
+        let prefs = FormatPreferences {
+            max_fps: Some(30),
+            audio_language: Some("en".to_string()),
+            // Unset constraints are skipped; repeats are ignored.
+            fallback_order: vec![
+                FormatConstraint::MaxFps,
+                FormatConstraint::DynamicRange,
+                FormatConstraint::AudioLanguage,
+                FormatConstraint::MaxFps,
+            ],
+            ..Default::default()
+        };
+        let plan = compile(&prefs, "best");
+        assert_eq!(
+            plan.format,
+            "bv[fps<=?30]+ba[language^=en]/b[fps<=?30][language^=en]/\
+             bv+ba[language^=en]/b[language^=en]/bv+ba/b"
+        );
+        let fallback: Vec<_> = plan
+            .reasons
+            .iter()
+            .filter(|r| r.key == "fallback")
+            .map(|r| r.params["constraint"].clone())
+            .collect();
+        assert_eq!(fallback, vec!["maxFps", "audioLanguage"]);
+    }
+
+    #[test]
+    fn audio_only_requests_ignore_video_rules() {
+        let prefs = FormatPreferences {
+            preferred_codecs: vec![VideoCodecFamily::Av1],
+            max_fps: Some(30),
+            audio_language: Some("en".to_string()),
+            ..prefs()
+        };
+        let plan = compile_format_preferences(&prefs, "audio", "mp3").unwrap();
+        assert_eq!(plan.format, "ba[language^=en]/b[language^=en]");
+        assert_eq!(plan.sort, None);
+        assert_eq!(plan.reasons[0].key, "audioOnly");
+    }
+
+    #[test]
+    fn mp4_container_is_preferred_by_sort() {
+        let plan = compile_format_preferences(&prefs(), "720", "mp4").unwrap();
+        assert_eq!(plan.sort.as_deref(), Some("res,ext:mp4:m4a"));
+    }
+
+    #[test]
+    fn choice_explains_the_preferred_codec_and_relaxed_rules() {
+        let prefs = FormatPreferences {
+            preferred_codecs: vec![VideoCodecFamily::Vp9],
+            max_fps: Some(30),
+            audio_language: Some("de".to_string()),
+            fallback_order: vec![FormatConstraint::AudioLanguage],
+            ..Default::default()
+        };
+        let formats = vec![
+            video("137", "avc1.640028", 1080, 30.0),
+            video("248", "vp9", 1080, 30.0),
+            video("303", "vp9", 1080, 60.0),
+            audio("251", "en", 130.0),
+            audio("140", "en", 128.0),
+        ];
+
+        let choice = explain_format_choice(&prefs, "best", "mkv", &formats)
+            .unwrap()
+            .expect("a format matches");
+        assert_eq!(choice.video.unwrap().format_id, "248");
+        assert_eq!(choice.audio.unwrap().format_id, "251");
+        assert_eq!(choice.relaxed, vec![FormatConstraint::AudioLanguage]);
+        let keys: Vec<_> = choice.reasons.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "relaxed",
+                "matchedPreferredCodec",
+                "videoStream",
+                "audioStream"
+            ]
+        );
+    }
+
+    #[test]
+    fn choice_is_none_when_nothing_matches() {
+        let prefs = FormatPreferences {
+            forbidden_codecs: vec![VideoCodecFamily::H264],
+            ..prefs()
+        };
+        let formats = vec![
+            video("137", "avc1.640028", 1080, 30.0),
+            audio("140", "en", 128.0),
+        ];
+        assert!(explain_format_choice(&prefs, "best", "mkv", &formats)
+            .unwrap()
+            .is_none());
+    }
+}
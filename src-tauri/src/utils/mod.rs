@@ -1,6 +1,8 @@
 mod command;
 mod extract;
 mod format;
+mod format_rules;
+mod output_template;
 mod path;
 mod progress;
 mod security;
@@ -8,6 +10,8 @@ mod security;
 pub use command::*;
 pub use extract::*;
 pub use format::*;
+pub use format_rules::*;
+pub use output_template::*;
 pub use path::*;
 pub use progress::*;
 pub use security::*;
@@ -0,0 +1,441 @@
+use crate::types::VideoInfo;
+use chrono::format::{Item, StrftimeItems};
+use std::fmt::Write;
+
+/// Output template used when none is configured, relative to the output folder
+pub const DEFAULT_OUTPUT_TEMPLATE: &str = "%(title)s.%(ext)s";
+
+/// Chapter parts of a split download, in a folder named after the video
+const CHAPTER_FILE_TEMPLATE: &str = "%(title)s/%(section_number)03d - %(section_title)s.%(ext)s";
+
+/// Conversion types yt-dlp accepts after a `%(field)` reference
+const CONVERSION_TYPES: &str = "diouxXeEfFgGcrsaBjhlqDSU";
+
+/// Placeholder yt-dlp writes for fields without a value
+const MISSING_FIELD: &str = "NA";
+
+#[derive(Debug, PartialEq)]
+enum TemplateSegment<'a> {
+    Literal(&'a str),
+    Percent,
+    Field {
+        expr: &'a str,
+        spec: &'a str,
+        conversion: char,
+    },
+}
+
+fn parse_template(template: &str) -> Result<Vec<TemplateSegment<'_>>, String> {
+    let bytes = template.as_bytes();
+    let mut segments = Vec::new();
+    let mut literal_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'%' {
+            i += 1;
+            continue;
+        }
+        if literal_start < i {
+            segments.push(TemplateSegment::Literal(&template[literal_start..i]));
+        }
+
+        match bytes.get(i + 1) {
+            Some(b'%') => {
+                segments.push(TemplateSegment::Percent);
+                i += 2;
+            }
+            Some(b'(') => {
+                let expr_start = i + 2;
+                let mut depth = 1;
+                let mut j = expr_start;
+                while j < bytes.len() && depth > 0 {
+                    match bytes[j] {
+                        b'(' => depth += 1,
+                        b')' => depth -= 1,
+                        _ => {}
+                    }
+                    j += 1;
+                }
+                if depth > 0 {
+                    return Err(format!(
+                        "Invalid output template: unclosed field at \"{}\"",
+                        &template[i..]
+                    ));
+                }
+                let expr = &template[expr_start..j - 1];
+                validate_field_expr(expr)?;
+
+                let spec_start = j;
+                while j < bytes.len()
+                    && (b"#0-+ .".contains(&bytes[j]) || bytes[j].is_ascii_digit())
+                {
+                    j += 1;
+                }
+                let conversion = template[j..]
+                    .chars()
+                    .next()
+                    .filter(|c| CONVERSION_TYPES.contains(*c));
+                let Some(conversion) = conversion else {
+                    return Err(format!(
+                        "Invalid output template: field %({}) needs a type such as \"s\" or \"d\"",
+                        expr
+                    ));
+                };
+                segments.push(TemplateSegment::Field {
+                    expr,
+                    spec: &template[spec_start..j],
+                    conversion,
+                });
+                i = j + conversion.len_utf8();
+            }
+            _ => {
+                return Err(
+                    "Invalid output template: use %% for a literal percent sign".to_string()
+                );
+            }
+        }
+        literal_start = i;
+    }
+
+    if literal_start < template.len() {
+        segments.push(TemplateSegment::Literal(&template[literal_start..]));
+    }
+    Ok(segments)
+}
+
+fn validate_field_expr(expr: &str) -> Result<(), String> {
+    let name = field_names(expr).next().unwrap_or_default();
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    if !valid {
+        return Err(format!(
+            "Invalid output template: \"{}\" is not a field name",
+            expr
+        ));
+    }
+
+    if let Some(date_format) = date_format(expr) {
+        if StrftimeItems::new(date_format).any(|item| matches!(item, Item::Error)) {
+            return Err(format!(
+                "Invalid output template: \"{}\" is not a valid date format",
+                date_format
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Date format of an expression like `upload_date>%Y-%m|unknown`, if any.
+fn date_format(expr: &str) -> Option<&str> {
+    let end = expr.find(['&', '|']).unwrap_or(expr.len());
+    expr[..end]
+        .split_once('>')
+        .map(|(_, date_format)| date_format)
+}
+
+/// Field names of an expression like `upload_date>%Y|unknown` or `artist,uploader`.
+fn field_names(expr: &str) -> impl Iterator<Item = &str> {
+    let end = expr.find(['>', '&', '|']).unwrap_or(expr.len());
+    expr[..end].split(',').map(str::trim)
+}
+
+/// Validate a user-supplied yt-dlp output template and return it normalized.
+///
+/// Templates are relative to the output folder and may contain sub-folders,
+/// e.g. `%(uploader)s/%(upload_date>%Y-%m)s/%(title)s [%(id)s].%(ext)s`.
+/// An empty template falls back to [`DEFAULT_OUTPUT_TEMPLATE`].
+pub fn validate_output_template(template: &str) -> Result<String, String> {
+    let template = template.trim();
+    if template.is_empty() {
+        return Ok(DEFAULT_OUTPUT_TEMPLATE.to_string());
+    }
+    if template.contains(['\0', '\n', '\r']) {
+        return Err("Invalid output template: control characters are not allowed".to_string());
+    }
+
+    let first_component = template.split(['/', '\\']).next().unwrap_or_default();
+    if template.starts_with(['/', '\\', '~'])
+        || (first_component.len() == 2 && first_component.ends_with(':'))
+    {
+        return Err("Invalid output template: must be relative to the output folder".to_string());
+    }
+    if template
+        .split(['/', '\\'])
+        .any(|component| component.trim() == "..")
+    {
+        return Err("Invalid output template: path traversal detected".to_string());
+    }
+    if template.ends_with(['/', '\\']) {
+        return Err("Invalid output template: must end with a file name".to_string());
+    }
+
+    let segments = parse_template(template)?;
+    let has_ext = segments.iter().any(|segment| {
+        matches!(segment, TemplateSegment::Field { expr, .. } if field_names(expr).any(|name| name == "ext"))
+    });
+    if !has_ext {
+        return Err("Invalid output template: must include %(ext)s".to_string());
+    }
+
+    Ok(template.to_string())
+}
+
+/// Template for the `chapter:` output of `--split-chapters`, derived from a validated
+/// output template so the parts land next to the full download.
+pub fn chapter_output_template(template: &str) -> String {
+    match template.rfind(['/', '\\']) {
+        Some(end) => format!("{}/{}", &template[..end], CHAPTER_FILE_TEMPLATE),
+        None => CHAPTER_FILE_TEMPLATE.to_string(),
+    }
+}
+
+/// Render an output template the way yt-dlp would for `info`, as a path
+/// relative to the output folder. Used to preview templates before downloading.
+pub fn render_output_template(
+    template: &str,
+    info: &VideoInfo,
+    ext: &str,
+) -> Result<String, String> {
+    let template = validate_output_template(template)?;
+    let mut rendered = String::new();
+
+    for segment in parse_template(&template)? {
+        match segment {
+            TemplateSegment::Literal(text) => rendered.push_str(text),
+            TemplateSegment::Percent => rendered.push('%'),
+            TemplateSegment::Field {
+                expr,
+                spec,
+                conversion,
+            } => {
+                let value = evaluate_field(expr, info, ext);
+                let value = apply_conversion(value, spec, conversion);
+                rendered.push_str(&sanitize_field_value(&value));
+            }
+        }
+    }
+
+    Ok(rendered)
+}
+
+fn evaluate_field(expr: &str, info: &VideoInfo, ext: &str) -> String {
+    let (expr, default) = match expr.split_once('|') {
+        Some((expr, default)) => (expr, Some(default)),
+        None => (expr, None),
+    };
+    let (expr, replacement) = match expr.split_once('&') {
+        Some((expr, replacement)) => (expr, Some(replacement)),
+        None => (expr, None),
+    };
+    let date_format = date_format(expr);
+
+    let value = field_names(expr)
+        .filter_map(|name| field_value(name, info, ext))
+        .find(|value| !value.is_empty());
+    let value = match (value, date_format) {
+        (Some(value), Some(date_format)) => Some(format_date(&value, date_format)),
+        (value, _) => value,
+    };
+
+    match (value, replacement) {
+        (Some(value), Some(replacement)) => replacement.replace("{}", &value),
+        (Some(value), None) => value,
+        (None, _) => default.unwrap_or(MISSING_FIELD).to_string(),
+    }
+}
+
+fn field_value(name: &str, info: &VideoInfo, ext: &str) -> Option<String> {
+    match name {
+        "id" | "display_id" => Some(info.id.clone()),
+        "title" | "fulltitle" => Some(info.title.clone()),
+        "ext" => Some(ext.to_string()),
+        "uploader" => info.uploader.clone().or_else(|| info.channel.clone()),
+        "channel" => info.channel.clone().or_else(|| info.uploader.clone()),
+        "upload_date" => info.upload_date.clone(),
+        "duration" => info.duration.map(|d| (d.round() as i64).to_string()),
+        "duration_string" => info.duration.map(format_duration),
+        "view_count" => info.view_count.map(|count| count.to_string()),
+        "extractor" => info.extractor.clone(),
+        "extractor_key" => info.extractor_key.clone(),
+        "live_status" => info.live_status.clone(),
+        _ => None,
+    }
+}
+
+fn format_date(value: &str, date_format: &str) -> String {
+    let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y%m%d") else {
+        return value.to_string();
+    };
+    // `to_string` panics on a spec chrono can't format, so write it out and keep the raw value
+    let mut formatted = String::new();
+    match write!(formatted, "{}", date.format(date_format)) {
+        Ok(()) => formatted,
+        Err(_) => value.to_string(),
+    }
+}
+
+fn format_duration(seconds: f64) -> String {
+    let total = seconds.round() as u64;
+    let (hours, minutes, secs) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{}:{:02}", minutes, secs)
+    }
+}
+
+/// Apply zero-padding and width for numeric conversions such as `%(playlist_index)03d`.
+fn apply_conversion(value: String, spec: &str, conversion: char) -> String {
+    if conversion != 'd' {
+        return value;
+    }
+    let Ok(number) = value.parse::<i64>() else {
+        return value;
+    };
+    let zero_pad = spec.starts_with('0');
+    let width: usize = spec
+        .trim_start_matches(['0', '-', '+', ' ', '#'])
+        .parse()
+        .unwrap_or(0);
+    if zero_pad {
+        format!("{:0width$}", number, width = width)
+    } else {
+        format!("{:width$}", number, width = width)
+    }
+}
+
+/// yt-dlp swaps characters that would break the path for look-alikes.
+fn sanitize_field_value(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '/' => '⧸',
+            '\\' if cfg!(windows) => '⧹',
+            ':' if cfg!(windows) => '：',
+            '*' if cfg!(windows) => '＊',
+            '?' if cfg!(windows) => '？',
+            '"' if cfg!(windows) => '＂',
+            '<' if cfg!(windows) => '＜',
+            '>' if cfg!(windows) => '＞',
+            '|' if cfg!(windows) => '｜',
+            '\n' | '\r' | '\0' => ' ',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_info() -> VideoInfo {
+        VideoInfo {
+            id: "dQw4w9WgXcQ".to_string(),
+            title: "Never Gonna Give You Up".to_string(),
+            thumbnail: None,
+            duration: Some(213.0),
+            channel: Some("Rick Astley".to_string()),
+            uploader: Some("Rick Astley".to_string()),
+            upload_date: Some("20091025".to_string()),
+            view_count: Some(1),
+            description: None,
+            is_playlist: false,
+            playlist_count: None,
+            extractor: Some("youtube".to_string()),
+            extractor_key: Some("Youtube".to_string()),
+            is_live: None,
+            was_live: None,
+            live_status: None,
+        }
+    }
+
+    #[test]
+    fn validate_output_template_accepts_nested_fields() {
+        let template = "%(uploader)s/%(upload_date>%Y-%m)s/%(title)s [%(id)s].%(ext)s";
+        assert_eq!(validate_output_template(template).unwrap(), template);
+        assert_eq!(
+            validate_output_template("  ").unwrap(),
+            DEFAULT_OUTPUT_TEMPLATE
+        );
+    }
+
+    #[test]
+    fn validate_output_template_rejects_unsafe_or_broken_templates() {
+        for template in [
+            "../%(title)s.%(ext)s",
+            "%(uploader)s/../../%(title)s.%(ext)s",
+            "/etc/%(title)s.%(ext)s",
+            "C:/%(title)s.%(ext)s",
+            "%(title)s",
+            "%(title.%(ext)s",
+            "%(title)z.%(ext)s",
+            "100% %(title)s.%(ext)s",
+            "%(1abc)s.%(ext)s",
+            "%(upload_date>%Q)s/%(title)s.%(ext)s",
+            "%(upload_date>%Y-%Q|unknown)s.%(ext)s",
+        ] {
+            assert!(
+                validate_output_template(template).is_err(),
+                "{} should be rejected",
+                template
+            );
+        }
+    }
+
+    #[test]
+    fn chapter_output_template_follows_template_folders() {
+        assert_eq!(
+            chapter_output_template(DEFAULT_OUTPUT_TEMPLATE),
+            "%(title)s/%(section_number)03d - %(section_title)s.%(ext)s"
+        );
+        assert_eq!(
+            chapter_output_template("%(uploader)s/%(title)s [%(id)s].%(ext)s"),
+            "%(uploader)s/%(title)s/%(section_number)03d - %(section_title)s.%(ext)s"
+        );
+    }
+
+    #[test]
+    fn render_output_template_previews_nested_path() {
+        let rendered = render_output_template(
+            "%(uploader)s/%(upload_date>%Y-%m)s/%(title)s [%(id)s].%(ext)s",
+            &sample_info(),
+            "mp4",
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            "Rick Astley/2009-10/Never Gonna Give You Up [dQw4w9WgXcQ].mp4"
+        );
+    }
+
+    #[test]
+    fn render_output_template_handles_defaults_padding_and_separators() {
+        let mut info = sample_info();
+        info.title = "AC/DC Live".to_string();
+
+        let rendered = render_output_template(
+            "%(playlist_title|Singles)s/%(playlist_index|7)03d - %(title)s 100%%.%(ext)s",
+            &info,
+            "mp3",
+        )
+        .unwrap();
+        assert_eq!(rendered, "Singles/007 - AC⧸DC Live 100%.mp3");
+
+        let rendered =
+            render_output_template("%(album,uploader)s/%(series)s.%(ext)s", &info, "mp4").unwrap();
+        assert_eq!(rendered, "Rick Astley/NA.mp4");
+    }
+
+    #[test]
+    fn format_date_keeps_raw_value_for_invalid_format() {
+        assert_eq!(format_date("20091025", "%Y-%m"), "2009-10");
+        assert_eq!(format_date("20091025", "%Q"), "20091025");
+        assert_eq!(format_date("unknown", "%Y"), "unknown");
+    }
+}
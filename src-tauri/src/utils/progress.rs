@@ -1,94 +1,482 @@
-/// Parse yt-dlp progress output
-/// Returns (percent, speed, eta, playlist_index, playlist_count, downloaded_size, elapsed_time)
-pub fn parse_progress(
-    line: &str,
-) -> Option<(
-    f64,
-    String,
-    String,
-    Option<u32>,
-    Option<u32>,
-    Option<String>,
-    Option<String>,
-)> {
-    let mut playlist_index: Option<u32> = None;
-    let mut playlist_count: Option<u32> = None;
-
-    // Check for playlist progress
-    if line.contains("Downloading item") {
-        let re = regex::Regex::new(r"Downloading item (\d+) of (\d+)").ok()?;
-        if let Some(caps) = re.captures(line) {
-            playlist_index = caps.get(1).and_then(|m| m.as_str().parse().ok());
-            playlist_count = caps.get(2).and_then(|m| m.as_str().parse().ok());
-        }
+use super::format_size;
+use crate::types::DownloadPhase;
+use serde::Deserialize;
+
+/// Marker of the download progress lines printed by [`progress_template_args`]
+const DOWNLOAD_PROGRESS_MARKER: &str = "youwee-progress|";
+/// Marker of the post-processing lines printed by [`progress_template_args`]
+const POSTPROCESS_PROGRESS_MARKER: &str = "youwee-postprocess|";
+
+/// yt-dlp arguments that print progress as machine-readable lines for [`parse_progress`].
+///
+/// Download lines carry the playlist position and yt-dlp's progress dict as JSON,
+/// post-processing lines carry the playlist position, postprocessor and its status.
+pub fn progress_template_args() -> Vec<String> {
+    vec![
+        "--progress-template".to_string(),
+        format!(
+            "download:{}%(info.playlist_index)s|%(info.n_entries)s|%(progress)j",
+            DOWNLOAD_PROGRESS_MARKER
+        ),
+        "--progress-template".to_string(),
+        format!(
+            "postprocess:{}%(info.playlist_index)s|%(info.n_entries)s|%(progress.postprocessor)s|%(progress.status)s",
+            POSTPROCESS_PROGRESS_MARKER
+        ),
+    ]
+}
+
+/// yt-dlp arguments that append one JSON line to `path` for each playlist entry it
+/// finishes, read back with [`parse_playlist_item`].
+pub fn playlist_item_print_args(path: &str) -> Vec<String> {
+    vec![
+        "--print-to-file".to_string(),
+        "after_move:%(.{title,webpage_url,thumbnail,playlist_id,playlist_index,n_entries,filepath,id,extractor_key,duration,vcodec,acodec})j"
+            .to_string(),
+        path.to_string(),
+    ]
+}
+
+/// yt-dlp arguments that write what yt-dlp knows about the finished file to `path`,
+/// read back with [`parse_downloaded_media`].
+pub fn downloaded_media_print_args(path: &str) -> Vec<String> {
+    vec![
+        "--print-to-file".to_string(),
+        "after_move:%(.{id,extractor_key,duration,vcodec,acodec})j".to_string(),
+        path.to_string(),
+    ]
+}
+
+/// A progress line from yt-dlp, decoded from the `--progress-template` output
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressUpdate {
+    pub phase: DownloadPhase,
+    pub percent: f64,
+    pub speed: String,
+    /// Download speed in bytes per second, when yt-dlp reports one
+    pub speed_bytes: Option<u64>,
+    pub eta: String,
+    pub playlist_index: Option<u32>,
+    pub playlist_count: Option<u32>,
+    pub downloaded_bytes: Option<u64>,
+    /// Exact size of the stream being downloaded, when yt-dlp knows it
+    pub total_bytes: Option<u64>,
+    /// yt-dlp's size estimate, used for fragmented streams without an exact size
+    pub total_bytes_estimate: Option<u64>,
+    pub fragment_index: Option<u32>,
+    pub fragment_count: Option<u32>,
+    /// For live streams (no size known): "2.87 MB"
+    pub downloaded_size: Option<String>,
+    /// For live streams (no size known): "00:00:07"
+    pub elapsed_time: Option<String>,
+}
+
+/// A chapter file written by `--split-chapters`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChapterPart {
+    /// 1-based chapter number
+    pub index: u32,
+    pub filepath: String,
+}
+
+/// What yt-dlp reports about a file it finished, to check the file against
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct DownloadedMedia {
+    pub id: Option<String>,
+    pub extractor_key: Option<String>,
+    /// Duration of the video in seconds, unknown for live streams
+    pub duration: Option<f64>,
+    /// `none` when the download has no video stream
+    pub vcodec: Option<String>,
+    /// `none` when the download has no audio stream
+    pub acodec: Option<String>,
+}
+
+impl DownloadedMedia {
+    /// The line yt-dlp writes to a download archive for this video
+    pub fn archive_line(&self) -> Option<String> {
+        Some(format!(
+            "{} {}",
+            self.extractor_key.as_deref()?.to_lowercase(),
+            self.id.as_deref()?
+        ))
     }
+}
 
-    // Standard progress with percentage (normal videos)
-    if line.contains("[download]") && line.contains("%") {
-        // Try to match with speed and ETA
-        if line.contains(" at ") {
-            let re = regex::Regex::new(r"(\d+\.?\d*)%.*at\s+(\S+)(?:.*ETA\s+(\S+))?").ok()?;
-            if let Some(caps) = re.captures(line) {
-                let percent: f64 = caps.get(1)?.as_str().parse().ok()?;
-                let speed = caps
-                    .get(2)
-                    .map(|m| m.as_str().to_string())
-                    .unwrap_or_default();
-                let eta = caps
-                    .get(3)
-                    .map(|m| m.as_str().to_string())
-                    .unwrap_or_default();
-                return Some((
-                    percent,
-                    speed,
-                    eta,
-                    playlist_index,
-                    playlist_count,
-                    None,
-                    None,
-                ));
-            }
-        }
-        // Fallback: just extract percent (no speed/eta available)
-        let re = regex::Regex::new(r"(\d+\.?\d*)%").ok()?;
-        if let Some(caps) = re.captures(line) {
-            let percent: f64 = caps.get(1)?.as_str().parse().ok()?;
-            return Some((
-                percent,
-                String::new(),
-                String::new(),
-                playlist_index,
-                playlist_count,
-                None,
-                None,
-            ));
+/// A finished playlist entry, as written by [`playlist_item_print_args`]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PlaylistItemRecord {
+    pub title: Option<String>,
+    pub webpage_url: Option<String>,
+    pub thumbnail: Option<String>,
+    pub playlist_id: Option<String>,
+    /// 1-based position in the playlist
+    pub playlist_index: Option<u32>,
+    /// Number of entries in the playlist
+    pub n_entries: Option<u32>,
+    pub filepath: String,
+    #[serde(flatten)]
+    pub media: DownloadedMedia,
+}
+
+/// The subset of yt-dlp's progress hook dict we use
+#[derive(Debug, Deserialize)]
+struct YtdlpProgress {
+    status: Option<String>,
+    downloaded_bytes: Option<f64>,
+    total_bytes: Option<f64>,
+    total_bytes_estimate: Option<f64>,
+    speed: Option<f64>,
+    eta: Option<f64>,
+    elapsed: Option<f64>,
+    fragment_index: Option<f64>,
+    fragment_count: Option<f64>,
+}
+
+/// Whether a line of yt-dlp output was printed by [`progress_template_args`].
+pub fn is_progress_line(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with(DOWNLOAD_PROGRESS_MARKER) || line.starts_with(POSTPROCESS_PROGRESS_MARKER)
+}
+
+/// Parse a yt-dlp progress line printed through [`progress_template_args`].
+/// Returns `None` for any other output.
+pub fn parse_progress(line: &str) -> Option<ProgressUpdate> {
+    let line = line.trim();
+    if let Some(rest) = line.strip_prefix(DOWNLOAD_PROGRESS_MARKER) {
+        return parse_download_progress(rest);
+    }
+    if let Some(rest) = line.strip_prefix(POSTPROCESS_PROGRESS_MARKER) {
+        return parse_postprocess_progress(rest);
+    }
+    None
+}
+
+fn parse_download_progress(rest: &str) -> Option<ProgressUpdate> {
+    let mut parts = rest.splitn(3, '|');
+    let playlist_index = parse_template_number(parts.next()?);
+    let playlist_count = parse_template_number(parts.next()?);
+    // Python's json.dumps writes non-finite floats as bare words, which are not JSON.
+    let json = parts
+        .next()?
+        .replace("-Infinity", "null")
+        .replace("Infinity", "null")
+        .replace("NaN", "null");
+    let progress: YtdlpProgress = serde_json::from_str(&json).ok()?;
+
+    let downloaded_bytes = progress.downloaded_bytes.and_then(to_u64);
+    let total_bytes = progress.total_bytes.and_then(to_u64).filter(|&b| b > 0);
+    let total_bytes_estimate = progress
+        .total_bytes_estimate
+        .and_then(to_u64)
+        .filter(|&b| b > 0);
+    let fragment_index = progress.fragment_index.and_then(to_u64).map(|v| v as u32);
+    let fragment_count = progress
+        .fragment_count
+        .and_then(to_u64)
+        .map(|v| v as u32)
+        .filter(|&v| v > 0);
+    let finished = progress.status.as_deref() == Some("finished");
+
+    let percent = if finished {
+        100.0
+    } else if let (Some(done), Some(total)) =
+        (downloaded_bytes, total_bytes.or(total_bytes_estimate))
+    {
+        done as f64 / total as f64 * 100.0
+    } else if let (Some(index), Some(count)) = (fragment_index, fragment_count) {
+        index as f64 / count as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    // Without any size, yt-dlp is recording a live stream: report what we have so far.
+    let is_unsized = total_bytes.is_none() && total_bytes_estimate.is_none() && !finished;
+    let downloaded_size = downloaded_bytes.filter(|_| is_unsized).map(format_size);
+    let elapsed_time = progress
+        .elapsed
+        .filter(|_| is_unsized)
+        .and_then(to_u64)
+        .map(|secs| format_clock(secs, true));
+    let speed_bytes = progress.speed.and_then(to_u64);
+
+    Some(ProgressUpdate {
+        phase: DownloadPhase::Downloading,
+        percent: percent.clamp(0.0, 100.0),
+        speed: speed_bytes
+            .map(|speed| format!("{}/s", format_size(speed)))
+            .unwrap_or_default(),
+        speed_bytes,
+        eta: progress
+            .eta
+            .and_then(to_u64)
+            .map(|secs| format_clock(secs, false))
+            .unwrap_or_default(),
+        playlist_index,
+        playlist_count,
+        downloaded_bytes,
+        total_bytes,
+        total_bytes_estimate,
+        fragment_index,
+        fragment_count,
+        downloaded_size,
+        elapsed_time,
+    })
+}
+
+fn parse_postprocess_progress(rest: &str) -> Option<ProgressUpdate> {
+    let mut parts = rest.splitn(4, '|');
+    let playlist_index = parse_template_number(parts.next()?);
+    let playlist_count = parse_template_number(parts.next()?);
+    let postprocessor = parts.next()?.trim();
+    let status = parts.next()?.trim();
+    // A finished step is followed by the next step or the end of the download.
+    if status == "finished" {
+        return None;
+    }
+
+    Some(ProgressUpdate {
+        phase: postprocessor_phase(postprocessor),
+        percent: 100.0,
+        speed: String::new(),
+        speed_bytes: None,
+        eta: String::new(),
+        playlist_index,
+        playlist_count,
+        downloaded_bytes: None,
+        total_bytes: None,
+        total_bytes_estimate: None,
+        fragment_index: None,
+        fragment_count: None,
+        downloaded_size: None,
+        elapsed_time: None,
+    })
+}
+
+/// Map a yt-dlp postprocessor key (`PostProcessor.pp_key()`) to a phase.
+fn postprocessor_phase(postprocessor: &str) -> DownloadPhase {
+    match postprocessor {
+        "Merger" => DownloadPhase::Merging,
+        "ExtractAudio" | "VideoConvertor" | "VideoRemuxer" | "FixupM3u8" | "FixupM4a"
+        | "FixupStretched" | "FixupDuration" | "FixupTimestamp" | "FixupDuplicateMoov" => {
+            DownloadPhase::Converting
         }
+        "Metadata"
+        | "EmbedThumbnail"
+        | "EmbedSubtitle"
+        | "ThumbnailsConvertor"
+        | "SubtitlesConvertor" => DownloadPhase::Embedding,
+        "SponsorBlock" | "ModifyChapters" => DownloadPhase::Sponsorblock,
+        "MoveFilesAfterDownload" | "MoveFiles" => DownloadPhase::Moving,
+        _ => DownloadPhase::Postprocessing,
+    }
+}
+
+/// Template fields without a value are printed as "NA".
+fn parse_template_number(value: &str) -> Option<u32> {
+    value.trim().parse().ok()
+}
+
+fn to_u64(value: f64) -> Option<u64> {
+    (value.is_finite() && value >= 0.0).then_some(value as u64)
+}
+
+/// Format seconds as `MM:SS` (or `H:MM:SS`), or always `HH:MM:SS` when `full` is set.
+fn format_clock(seconds: u64, full: bool) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let secs = seconds % 60;
+    if full {
+        format!("{:02}:{:02}:{:02}", hours, minutes, secs)
+    } else if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{:02}:{:02}", minutes, secs)
     }
+}
+
+/// Parse the line yt-dlp prints for each chapter it splits off, e.g.
+/// `[SplitChapters] Chapter 003; Destination: /videos/Talk/003 - Q&A.mp4`.
+pub fn parse_chapter_part(line: &str) -> Option<ChapterPart> {
+    let rest = line.trim().strip_prefix("[SplitChapters] Chapter ")?;
+    let (number, destination) = rest.split_once("; Destination:")?;
+    let filepath = destination.trim();
+    if filepath.is_empty() {
+        return None;
+    }
+    Some(ChapterPart {
+        index: number.trim().parse().ok()?,
+        filepath: filepath.to_string(),
+    })
+}
+
+/// Parse a line written by [`playlist_item_print_args`].
+pub fn parse_playlist_item(line: &str) -> Option<PlaylistItemRecord> {
+    serde_json::from_str::<PlaylistItemRecord>(line.trim())
+        .ok()
+        .filter(|record| !record.filepath.is_empty())
+}
+
+/// Parse what [`downloaded_media_print_args`] wrote. A download of several time ranges
+/// writes a line per clip; they only differ in their duration.
+pub fn parse_downloaded_media(contents: &str) -> Option<DownloadedMedia> {
+    let line = contents.lines().find(|line| !line.trim().is_empty())?;
+    serde_json::from_str(line.trim()).ok()
+}
+
+/// The entry number of the line yt-dlp prints before each playlist entry, e.g.
+/// `[download] Downloading item 3 of 10` (`video` instead of `item` in older versions).
+pub fn parse_playlist_item_start(line: &str) -> Option<u32> {
+    let rest = line.trim().strip_prefix("[download] Downloading ")?;
+    let rest = rest
+        .strip_prefix("item ")
+        .or_else(|| rest.strip_prefix("video "))?;
+    let (number, _) = rest.split_once(" of ")?;
+    number.trim().parse().ok()
+}
+
+/// The message of an `ERROR:` line, e.g. for a playlist entry yt-dlp could not download.
+pub fn parse_error_line(line: &str) -> Option<&str> {
+    let message = line.trim().strip_prefix("ERROR:")?.trim();
+    (!message.is_empty()).then_some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_download_progress_json() {
+        let line = r#"youwee-progress|2|5|{"status": "downloading", "downloaded_bytes": 1048576, "total_bytes": 4194304, "speed": 524288.0, "eta": 6, "elapsed": 2.1, "filename": "a.mp4"}"#;
+        let update = parse_progress(line).expect("progress line");
 
-    // Live stream progress: [download]    2.87MiB at  506.63KiB/s (00:00:07) (frag 91/2097)
-    if line.contains("[download]") && !line.contains("%") && line.contains(" at ") {
-        let re = regex::Regex::new(
-            r"\[download\]\s+([\d.]+\s*\w+)\s+at\s+([\d.]+\s*\w+/s)\s*(?:\((\d{2}:\d{2}:\d{2})\))?",
+        assert_eq!(update.phase, DownloadPhase::Downloading);
+        assert_eq!(update.percent, 25.0);
+        assert_eq!(update.speed, "512.00 KB/s");
+        assert_eq!(update.speed_bytes, Some(524288));
+        assert_eq!(update.eta, "00:06");
+        assert_eq!(update.playlist_index, Some(2));
+        assert_eq!(update.playlist_count, Some(5));
+        assert_eq!(update.downloaded_bytes, Some(1048576));
+        assert_eq!(update.total_bytes, Some(4194304));
+        assert_eq!(update.downloaded_size, None);
+    }
+
+    #[test]
+    fn parses_fragmented_and_live_progress() {
+        let fragmented = r#"youwee-progress|NA|NA|{"status": "downloading", "downloaded_bytes": 500, "total_bytes_estimate": 2000.5, "fragment_index": 3, "fragment_count": 12, "speed": NaN, "eta": null}"#;
+        let update = parse_progress(fragmented).expect("fragmented line");
+        assert_eq!(update.playlist_index, None);
+        assert_eq!(update.total_bytes, None);
+        assert_eq!(update.total_bytes_estimate, Some(2000));
+        assert_eq!(update.fragment_index, Some(3));
+        assert_eq!(update.fragment_count, Some(12));
+        assert_eq!(update.speed, "");
+        assert_eq!(update.downloaded_size, None);
+
+        let live = r#"youwee-progress|NA|NA|{"status": "downloading", "downloaded_bytes": 3010000, "elapsed": 7.4, "speed": 518789.1}"#;
+        let update = parse_progress(live).expect("live line");
+        assert_eq!(update.percent, 0.0);
+        assert_eq!(update.downloaded_size.as_deref(), Some("2.87 MB"));
+        assert_eq!(update.elapsed_time.as_deref(), Some("00:00:07"));
+    }
+
+    #[test]
+    fn parses_postprocess_phases() {
+        let merging = parse_progress("youwee-postprocess|NA|NA|Merger|started").expect("merger");
+        assert_eq!(merging.phase, DownloadPhase::Merging);
+        assert_eq!(merging.percent, 100.0);
+
+        let phase = |pp: &str| {
+            parse_progress(&format!("youwee-postprocess|1|3|{}|started", pp)).map(|u| u.phase)
+        };
+        assert_eq!(phase("EmbedThumbnail"), Some(DownloadPhase::Embedding));
+        assert_eq!(phase("SponsorBlock"), Some(DownloadPhase::Sponsorblock));
+        assert_eq!(phase("MoveFilesAfterDownload"), Some(DownloadPhase::Moving));
+        assert_eq!(phase("ExtractAudio"), Some(DownloadPhase::Converting));
+        assert_eq!(phase("Exec"), Some(DownloadPhase::Postprocessing));
+        assert_eq!(
+            parse_progress("youwee-postprocess|NA|NA|Merger|finished"),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_split_chapter_destinations() {
+        assert_eq!(
+            parse_chapter_part(
+                "[SplitChapters] Chapter 003; Destination: /videos/Talk/003 - Q&A.mp4"
+            ),
+            Some(ChapterPart {
+                index: 3,
+                filepath: "/videos/Talk/003 - Q&A.mp4".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_chapter_part("[SplitChapters] Splitting video by chapters; 5 chapters found"),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_playlist_item_lines() {
+        let record = parse_playlist_item(
+            r#"{"title": "Second", "webpage_url": "https://www.youtube.com/watch?v=abc", "playlist_id": "PL123", "playlist_index": 2, "n_entries": 5, "filepath": "/videos/Second.mp4"}"#,
         )
-        .ok()?;
-        if let Some(caps) = re.captures(line) {
-            let downloaded_size = caps.get(1).map(|m| m.as_str().trim().to_string());
-            let speed = caps
-                .get(2)
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default();
-            let elapsed_time = caps.get(3).map(|m| m.as_str().to_string());
-            return Some((
-                0.0,
-                speed,
-                String::new(),
-                playlist_index,
-                playlist_count,
-                downloaded_size,
-                elapsed_time,
-            ));
-        }
+        .expect("playlist item");
+        assert_eq!(record.title.as_deref(), Some("Second"));
+        assert_eq!(record.playlist_id.as_deref(), Some("PL123"));
+        assert_eq!(record.playlist_index, Some(2));
+        assert_eq!(record.n_entries, Some(5));
+        assert_eq!(record.thumbnail, None);
+        assert_eq!(record.filepath, "/videos/Second.mp4");
+        assert_eq!(record.media, DownloadedMedia::default());
+        assert_eq!(parse_playlist_item("/videos/Second.mp4"), None);
+
+        assert_eq!(
+            parse_playlist_item_start("[download] Downloading item 3 of 10"),
+            Some(3)
+        );
+        assert_eq!(
+            parse_playlist_item_start("[download] Downloading video 7 of 9"),
+            Some(7)
+        );
+        assert_eq!(
+            parse_playlist_item_start("[download] Downloading playlist: Mix"),
+            None
+        );
+
+        assert_eq!(
+            parse_error_line("ERROR: [youtube] abc: Private video"),
+            Some("[youtube] abc: Private video")
+        );
+        assert_eq!(parse_error_line("WARNING: slow"), None);
     }
 
-    None
+    #[test]
+    fn parses_downloaded_media() {
+        let media = parse_downloaded_media(
+            r#"{"id": "dQw4w9WgXcQ", "extractor_key": "Youtube", "duration": 212.0, "vcodec": "avc1.640028", "acodec": "none"}"#,
+        )
+        .expect("downloaded media");
+        assert_eq!(media.duration, Some(212.0));
+        assert_eq!(media.acodec.as_deref(), Some("none"));
+        assert_eq!(media.archive_line().as_deref(), Some("youtube dQw4w9WgXcQ"));
+        assert_eq!(DownloadedMedia::default().archive_line(), None);
+
+        let clips = "{\"id\": \"a\", \"duration\": 30.0}\n{\"id\": \"a\", \"duration\": 60.0}\n";
+        assert_eq!(
+            parse_downloaded_media(clips).and_then(|media| media.duration),
+            Some(30.0)
+        );
+    }
+
+    #[test]
+    fn ignores_other_output() {
+        assert_eq!(
+            parse_progress("[download]  42.0% of 10.00MiB at 1.00MiB/s ETA 00:05"),
+            None
+        );
+        assert_eq!(parse_progress("youwee-progress|NA|NA|not json"), None);
+    }
 }
@@ -1,3 +1,9 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::types::GalleryDlOptions;
+
 /// Validate a URL before passing to yt-dlp.
 /// Only allows http:// and https:// schemes, rejects option-injection attempts.
 pub fn validate_url(url: &str) -> Result<(), String> {
@@ -353,6 +359,209 @@ pub fn args_to_display_command(args: &[String]) -> String {
     parts.join(" ")
 }
 
+const MAX_GALLERYDL_OPTION_LENGTH: usize = 512;
+const MAX_GALLERYDL_DIRECTORY_FOLDERS: usize = 8;
+
+/// A replacement field that only looks metadata up: a name, `[key]` lookups or slices,
+/// `.attribute`s, a `!x` conversion and a plain format spec. gallery-dl's own specs
+/// (`R`, `?`, `J`...) take `/`-delimited text and are not allowed, nor are globals such
+/// as `_lit`, `_env` and `_now`, which start with `_`.
+static GALLERYDL_FORMAT_FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^[A-Za-z][A-Za-z0-9_]*(\[(-?[0-9]*:-?[0-9]*|[A-Za-z0-9_-]+)\]|\.[A-Za-z][A-Za-z0-9_]*)*(![A-Za-z])?(:[A-Za-z0-9%<>^=+,.: _-]*)?$",
+    )
+    .expect("valid regex")
+});
+
+/// Python keywords a filter has no use for: they build functions, loops or comprehensions.
+const GALLERYDL_FILTER_BLOCKED_WORDS: [&str; 8] = [
+    "lambda", "for", "while", "import", "yield", "await", "async", "global",
+];
+
+/// Validate gallery-dl options before they are passed as arguments.
+///
+/// gallery-dl evaluates `--filter` as a Python expression and can evaluate expressions
+/// inside format strings, so filters are limited to comparisons of metadata fields and
+/// format strings to plain field lookups.
+pub fn validate_gallerydl_options(options: &GalleryDlOptions) -> Result<(), String> {
+    if let Some(range) = non_empty(options.range.as_deref()) {
+        validate_gallerydl_range(range)?;
+    }
+    if let Some(filter) = non_empty(options.filter.as_deref()) {
+        validate_gallerydl_filter(filter)?;
+    }
+    if let Some(format) = non_empty(options.filename_format.as_deref()) {
+        validate_gallerydl_format(format, "filename")?;
+    }
+    if options.directory_format.len() > MAX_GALLERYDL_DIRECTORY_FOLDERS {
+        return Err(format!(
+            "Invalid gallery-dl directory format: at most {} folders",
+            MAX_GALLERYDL_DIRECTORY_FOLDERS
+        ));
+    }
+    for folder in &options.directory_format {
+        validate_gallerydl_format(folder, "directory")?;
+    }
+    Ok(())
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+fn check_gallerydl_option_text(value: &str, name: &str) -> Result<(), String> {
+    if value.len() > MAX_GALLERYDL_OPTION_LENGTH {
+        return Err(format!("Invalid gallery-dl {}: too long", name));
+    }
+    if value.chars().any(char::is_control) {
+        return Err(format!("Invalid gallery-dl {}: control characters", name));
+    }
+    Ok(())
+}
+
+/// `--range` takes comma-separated positions, ranges (`5-10`, `-10`, `5-`) and slices
+/// (`1:20:2`).
+fn validate_gallerydl_range(range: &str) -> Result<(), String> {
+    check_gallerydl_option_text(range, "range")?;
+    let is_number = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    let valid = range.split(',').map(str::trim).all(|part| {
+        if part.is_empty() {
+            return false;
+        }
+        if let Some((start, end)) = part.split_once('-') {
+            return is_number(start) && is_number(end) && !(start.is_empty() && end.is_empty());
+        }
+        let steps: Vec<&str> = part.split(':').collect();
+        steps.len() <= 3
+            && steps.iter().all(|step| is_number(step))
+            && steps.iter().any(|step| !step.is_empty())
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid gallery-dl range: {} (use e.g. 1-20 or 5,10:40:2)",
+            range
+        ))
+    }
+}
+
+/// Filters may compare metadata fields with literals (`width >= 1000`,
+/// `extension in ('jpg', 'png')`, `tags[0] == 'cat'`), but not call functions, read
+/// attributes or reach private names.
+fn validate_gallerydl_filter(filter: &str) -> Result<(), String> {
+    check_gallerydl_option_text(filter, "filter")?;
+    let invalid = |reason: &str| Err(format!("Invalid gallery-dl filter: {}", reason));
+
+    let chars: Vec<char> = filter.chars().collect();
+    let mut i = 0;
+    // Whether the previous token ends a value, so `(` after it would be a call
+    let mut after_value = false;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c == '\'' || c == '"' {
+            let Some(len) = chars[i + 1..].iter().position(|&ch| ch == c) else {
+                return invalid("unterminated string");
+            };
+            if chars[i + 1..i + 1 + len].contains(&'\\') {
+                return invalid("escapes in strings are not supported");
+            }
+            i += len + 2;
+            after_value = true;
+            continue;
+        }
+        if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            after_value = true;
+            continue;
+        }
+        if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            if word.starts_with('_') {
+                return invalid(&format!("private name {}", word));
+            }
+            if GALLERYDL_FILTER_BLOCKED_WORDS.contains(&word.as_str()) {
+                return invalid(&format!("{} is not allowed", word));
+            }
+            if i < chars.len() && (chars[i] == '\'' || chars[i] == '"') {
+                return invalid("string prefixes are not supported");
+            }
+            after_value = !matches!(
+                word.as_str(),
+                "and" | "or" | "not" | "in" | "is" | "if" | "else"
+            );
+            continue;
+        }
+        match c {
+            '(' if after_value => return invalid("function calls are not allowed"),
+            '(' | '[' | ',' | '+' | '-' | '/' => after_value = false,
+            ')' | ']' => after_value = true,
+            '=' | '!' | '<' | '>' => {
+                // `==`, `!=`, `<`, `<=`, `>`, `>=`; a lone `=` or `!` is an assignment or typo
+                let next = chars.get(i + 1).copied();
+                let pair = next == Some('=');
+                if (c == '=' || c == '!') && !pair {
+                    return invalid(&format!("unexpected {}", c));
+                }
+                if pair {
+                    i += 1;
+                }
+                after_value = false;
+            }
+            _ => return invalid(&format!("unexpected {}", c)),
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+/// Format strings are literal text with `{field}` replacement fields. Fields may only
+/// look metadata up (see [`GALLERYDL_FORMAT_FIELD`]): no expressions (`\f` prefixes),
+/// globals, private attributes or format specs that can produce path text, so a format
+/// cannot leave the folder it names.
+fn validate_gallerydl_format(format: &str, name: &str) -> Result<(), String> {
+    let label = format!("{} format", name);
+    check_gallerydl_option_text(format, &label)?;
+    let invalid = |reason: &str| Err(format!("Invalid gallery-dl {}: {}", label, reason));
+
+    let trimmed = format.trim();
+    if trimmed == "." || trimmed == ".." {
+        return invalid("must name a file or folder");
+    }
+
+    let mut field: Option<String> = None;
+    for c in format.chars() {
+        match (c, field.as_mut()) {
+            ('{', Some(_)) => return invalid("nested fields"),
+            ('{', None) => field = Some(String::new()),
+            ('}', None) => return invalid("unbalanced }"),
+            ('}', Some(text)) => {
+                if !GALLERYDL_FORMAT_FIELD.is_match(text) || text.contains("..") {
+                    return invalid(&format!("field {{{}}} is not allowed", text));
+                }
+                field = None;
+            }
+            (_, Some(text)) => text.push(c),
+            ('/' | '\\', None) => return invalid("path separators are not allowed"),
+            _ => {}
+        }
+    }
+    if field.is_some() {
+        return invalid("unbalanced {");
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -695,6 +904,74 @@ mod tests {
         assert!(err.contains("Command substitution"));
     }
 
+    #[test]
+    fn gallerydl_options_accept_common_values() {
+        let options = GalleryDlOptions {
+            range: Some("1-20,25,30:60:2,-5".to_string()),
+            filter: Some(
+                "width >= 1000 and extension in ('jpg', \"png\") and not tags[0] == 'nsfw'"
+                    .to_string(),
+            ),
+            filename_format: Some("{date:%Y-%m-%d}_{id}_{num:>02}.{extension}".to_string()),
+            directory_format: vec!["{category}".to_string(), "{user[name]}".to_string()],
+            write_metadata: true,
+            ..Default::default()
+        };
+
+        assert_eq!(validate_gallerydl_options(&options), Ok(()));
+    }
+
+    #[test]
+    fn gallerydl_options_reject_code_and_path_escapes() {
+        let filter = |value: &str| GalleryDlOptions {
+            filter: Some(value.to_string()),
+            ..Default::default()
+        };
+        let filename = |value: &str| GalleryDlOptions {
+            filename_format: Some(value.to_string()),
+            ..Default::default()
+        };
+
+        for rejected in [
+            "__import__('os').system('id')",
+            "open('/etc/passwd')",
+            "extension.lower() == 'jpg'",
+            "[x for x in tags]",
+            "f'{width}' == '1'",
+            "width = 1",
+        ] {
+            assert!(
+                validate_gallerydl_options(&filter(rejected)).is_err(),
+                "{}",
+                rejected
+            );
+        }
+        for rejected in [
+            "\u{c}E 1+1",
+            "{_env[HOME]}",
+            "{a.__class__}",
+            "../{id}",
+            "{id",
+            "{_lit[..]}",
+            "{_now:%Y}",
+            "{category:Rpixiv/../}",
+            "{id:?../x/}",
+            "{title:..}",
+            "{user[{id}]}",
+        ] {
+            assert!(
+                validate_gallerydl_options(&filename(rejected)).is_err(),
+                "{}",
+                rejected
+            );
+        }
+        let range = GalleryDlOptions {
+            range: Some("1-2-3".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_gallerydl_options(&range).is_err());
+    }
+
     #[test]
     fn validate_ffmpeg_args_rejects_shell_operator_arg() {
         let err =
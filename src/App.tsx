@@ -61,7 +61,7 @@ function AppContent() {
   const { ffmpegStatus, ffmpegLoading, isAutoDownloadingDeno, denoStatus, denoSuccess } =
     useDependencies();
   const { isTransitioning, oldMode, applyPendingTheme, onTransitionComplete } = useTheme();
-  const externalStartLockRef = useRef({ youtube: false, universal: false });
+  const externalStartLockRef = useRef({ youtube: false, universal: false, gallery: false });
   const externalSummaryRequestIdRef = useRef(0);
 
   const openSettingsPage = useCallback((section: SettingsSectionId = 'general') => {
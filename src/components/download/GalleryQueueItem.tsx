@@ -99,6 +99,14 @@ export function GalleryQueueItem({
             </span>
           </span>
 
+          {(isActive || isCompleted) && item.galleryFiles != null && (
+            <span className="text-[11px] text-muted-foreground">
+              {t('queue.files', { count: item.galleryFiles })}
+              {!!item.gallerySkipped &&
+                ` · ${t('queue.skipped', { count: item.gallerySkipped })}`}
+            </span>
+          )}
+
           {item.error && isError && (
             <span className="text-xs text-red-500/80 line-clamp-2" title={item.error}>
               {item.error}
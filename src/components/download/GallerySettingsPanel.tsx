@@ -1,6 +1,8 @@
-import { FolderOpen, Settings2 } from 'lucide-react';
+import { Archive, FileJson, Film, FolderOpen, Settings2 } from 'lucide-react';
+import { useState } from 'react';
 import { useTranslation } from 'react-i18next';
 import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
 import { Label } from '@/components/ui/label';
 import { Popover, PopoverContent, PopoverTrigger } from '@/components/ui/popover';
 import {
@@ -10,10 +12,13 @@ import {
   SelectTrigger,
   SelectValue,
 } from '@/components/ui/select';
+import { Switch } from '@/components/ui/switch';
+import type { GalleryDlOptions } from '@/lib/types';
 
 interface GallerySettings {
   outputPath: string;
   concurrentDownloads: number;
+  options: GalleryDlOptions;
 }
 
 interface GallerySettingsPanelProps {
@@ -21,6 +26,26 @@ interface GallerySettingsPanelProps {
   disabled?: boolean;
   onSelectFolder: () => Promise<void>;
   onConcurrentChange: (concurrent: number) => void;
+  onOptionsChange: (options: GalleryDlOptions) => void;
+}
+
+// Split a directory format into folder levels at slashes outside of {fields}
+function splitDirectoryFormat(value: string): string[] {
+  const segments: string[] = [];
+  let current = '';
+  let depth = 0;
+  for (const char of value) {
+    if (char === '{') depth += 1;
+    if (char === '}') depth = Math.max(0, depth - 1);
+    if (depth === 0 && (char === '/' || char === '\\')) {
+      segments.push(current);
+      current = '';
+      continue;
+    }
+    current += char;
+  }
+  segments.push(current);
+  return segments.map((segment) => segment.trim()).filter(Boolean);
 }
 
 export function GallerySettingsPanel({
@@ -28,8 +53,23 @@ export function GallerySettingsPanel({
   disabled,
   onSelectFolder,
   onConcurrentChange,
+  onOptionsChange,
 }: GallerySettingsPanelProps) {
   const { t } = useTranslation('gallery');
+  const options = settings.options;
+  const [directoryDraft, setDirectoryDraft] = useState(() =>
+    (options.directoryFormat ?? []).join(' / '),
+  );
+
+  const updateOption = <K extends keyof GalleryDlOptions>(key: K, value: GalleryDlOptions[K]) => {
+    onOptionsChange({ ...options, [key]: value });
+  };
+
+  const optionToggles = [
+    { key: 'writeMetadata' as const, icon: FileJson, label: t('settings.writeMetadata') },
+    { key: 'zip' as const, icon: Archive, label: t('settings.zip') },
+    { key: 'ugoiraConv' as const, icon: Film, label: t('settings.ugoiraConv') },
+  ];
   const outputFolderName = settings.outputPath
     ? settings.outputPath.split('/').pop() || settings.outputPath
     : t('settings.notSelected');
@@ -59,11 +99,11 @@ export function GallerySettingsPanel({
             <span className="hidden sm:inline text-xs">{t('settings.more')}</span>
           </Button>
         </PopoverTrigger>
-        <PopoverContent className="w-72 p-0" align="end" side="bottom" sideOffset={8}>
+        <PopoverContent className="w-80 p-0" align="end" side="bottom" sideOffset={8}>
           <div className="px-4 py-3 border-b bg-muted/30">
             <h4 className="text-sm font-medium">{t('settings.advanced')}</h4>
           </div>
-          <div className="p-4 space-y-4">
+          <div className="p-4 space-y-4 max-h-[70vh] overflow-y-auto">
             <div className="space-y-1.5">
               <Label className="text-[11px] text-muted-foreground">
                 {t('settings.parallelDownloads')}
@@ -85,6 +125,78 @@ export function GallerySettingsPanel({
                 </SelectContent>
               </Select>
             </div>
+
+            <div className="space-y-1.5">
+              <Label className="text-[11px] text-muted-foreground">{t('settings.range')}</Label>
+              <Input
+                value={options.range ?? ''}
+                onChange={(event) => updateOption('range', event.target.value)}
+                placeholder="1-20, 30"
+                disabled={disabled}
+                className="h-8 text-xs font-mono"
+              />
+            </div>
+
+            <div className="space-y-1.5">
+              <Label className="text-[11px] text-muted-foreground">{t('settings.filter')}</Label>
+              <Input
+                value={options.filter ?? ''}
+                onChange={(event) => updateOption('filter', event.target.value)}
+                placeholder="extension in ('jpg', 'png')"
+                disabled={disabled}
+                className="h-8 text-xs font-mono"
+              />
+            </div>
+
+            <div className="space-y-1.5">
+              <Label className="text-[11px] text-muted-foreground">
+                {t('settings.filenameFormat')}
+              </Label>
+              <Input
+                value={options.filenameFormat ?? ''}
+                onChange={(event) => updateOption('filenameFormat', event.target.value)}
+                placeholder="{category}_{id}.{extension}"
+                disabled={disabled}
+                className="h-8 text-xs font-mono"
+              />
+            </div>
+
+            <div className="space-y-1.5">
+              <Label className="text-[11px] text-muted-foreground">
+                {t('settings.directoryFormat')}
+              </Label>
+              <Input
+                value={directoryDraft}
+                onChange={(event) => {
+                  setDirectoryDraft(event.target.value);
+                  updateOption('directoryFormat', splitDirectoryFormat(event.target.value));
+                }}
+                placeholder="{category} / {user[name]}"
+                disabled={disabled}
+                className="h-8 text-xs font-mono"
+              />
+              <p className="text-[10px] text-muted-foreground">{t('settings.formatHint')}</p>
+            </div>
+
+            <div className="space-y-2">
+              {optionToggles.map(({ key, icon: Icon, label }) => (
+                <div
+                  key={key}
+                  className="flex items-center justify-between py-1.5 px-2.5 rounded-md bg-muted/50"
+                >
+                  <div className="flex items-center gap-2">
+                    <Icon className="w-3.5 h-3.5 text-primary" />
+                    <span className="text-[11px] font-medium">{label}</span>
+                  </div>
+                  <Switch
+                    checked={options[key] === true}
+                    onCheckedChange={(checked) => updateOption(key, checked)}
+                    disabled={disabled}
+                    className="scale-90"
+                  />
+                </div>
+              ))}
+            </div>
           </div>
         </PopoverContent>
       </Popover>
@@ -6,7 +6,7 @@ import { Badge } from '@/components/ui/badge';
 import { Button } from '@/components/ui/button';
 import { ScrollArea } from '@/components/ui/scroll-area';
 import type { ScheduleConfig } from '@/hooks/useSchedule';
-import type { DownloadItem } from '@/lib/types';
+import type { DownloadItem, TimeRange } from '@/lib/types';
 import { QueueItem } from './QueueItem';
 import {
   filterQueueItems,
@@ -27,10 +27,14 @@ interface QueueListProps {
     title: string;
   } | null;
   onRemove: (id: string) => void;
-  onUpdateTimeRange: (id: string, start?: string, end?: string) => void;
+  onUpdateTimeRanges: (id: string, ranges: TimeRange[], join: boolean, precise: boolean) => void;
   onRename: (id: string, newName: string) => Promise<void>;
   onClearCompleted: () => void;
   onScheduleUpcomingLive?: (config: ScheduleConfig) => void;
+  onRecordWhenLive?: (id: string) => Promise<boolean>; // Resolves to whether it was added
+  onRedownload?: (id: string) => void;
+  onPause?: (id: string) => void;
+  onResume?: (id: string) => void;
 }
 
 export function QueueList({
@@ -40,10 +44,14 @@ export function QueueList({
   showPlaylistBadge,
   currentPlaylistInfo,
   onRemove,
-  onUpdateTimeRange,
+  onUpdateTimeRanges,
   onRename,
   onClearCompleted,
   onScheduleUpcomingLive,
+  onRecordWhenLive,
+  onRedownload,
+  onPause,
+  onResume,
 }: QueueListProps) {
   const { t } = useTranslation('download');
   const containerRef = useRef<HTMLDivElement | null>(null);
@@ -144,9 +152,13 @@ export function QueueList({
                   showPlaylistBadge={showPlaylistBadge}
                   disabled={isDownloading}
                   onRemove={onRemove}
-                  onUpdateTimeRange={onUpdateTimeRange}
+                  onUpdateTimeRanges={onUpdateTimeRanges}
                   onRename={onRename}
                   onScheduleUpcomingLive={onScheduleUpcomingLive}
+                  onRecordWhenLive={onRecordWhenLive}
+                  onRedownload={onRedownload}
+                  onPause={isDownloading ? onPause : undefined}
+                  onResume={onResume}
                 />
               ))}
             </div>
@@ -48,6 +48,8 @@ const FILTERS: QueueStatusFilterValue[] = [
 function matchesFilter(item: DownloadItem, filter: QueueStatusFilterValue): boolean {
   if (filter === 'all') return true;
   if (filter === 'active') return item.status === 'fetching' || item.status === 'downloading';
+  // Paused items are still waiting to be downloaded
+  if (filter === 'pending') return item.status === 'pending' || item.status === 'paused';
   return item.status === filter;
 }
 
@@ -71,7 +73,7 @@ export function getQueueStatusCounts(items: DownloadItem[]): QueueStatusCounts {
   };
 
   for (const item of items) {
-    if (item.status === 'pending') counts.pending += 1;
+    if (item.status === 'pending' || item.status === 'paused') counts.pending += 1;
     if (item.status === 'fetching' || item.status === 'downloading') counts.active += 1;
     if (item.status === 'completed') {
       counts.completed += 1;
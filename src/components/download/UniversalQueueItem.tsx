@@ -1,5 +1,6 @@
 import { revealItemInDir } from '@tauri-apps/plugin-opener';
 import {
+  AlertTriangle,
   CheckCircle2,
   ChevronDown,
   ChevronUp,
@@ -11,7 +12,10 @@ import {
   Lightbulb,
   Loader2,
   MonitorPlay,
+  Pause,
   Pencil,
+  Play,
+  Radio,
   RefreshCw,
   Scissors,
   Sparkles,
@@ -24,7 +28,8 @@ import { SchedulePopover } from '@/components/download/SchedulePopover';
 import { SimpleMarkdown } from '@/components/ui/simple-markdown';
 import { useAI } from '@/contexts/AIContext';
 import type { ScheduleConfig } from '@/hooks/useSchedule';
-import type { DownloadItem, ItemUniversalSettings } from '@/lib/types';
+import { formatTimeRanges } from '@/lib/time-ranges';
+import type { DownloadItem, ItemUniversalSettings, TimeRange } from '@/lib/types';
 import { cn } from '@/lib/utils';
 import { SourceBadge } from './SourceBadge';
 import { ThumbnailCompletedBadge, ThumbnailFailedBadge } from './ThumbnailStatusBadge';
@@ -102,9 +107,13 @@ interface UniversalQueueItemProps {
   isFocused?: boolean;
   disabled?: boolean;
   onRemove: (id: string) => void;
-  onUpdateTimeRange: (id: string, start?: string, end?: string) => void;
+  onUpdateTimeRanges: (id: string, ranges: TimeRange[], join: boolean, precise: boolean) => void;
   onRename: (id: string, newName: string) => Promise<void>;
   onScheduleUpcomingLive?: (config: ScheduleConfig) => void;
+  onRecordWhenLive?: (id: string) => Promise<boolean>; // Resolves to whether it was added
+  onRedownload?: (id: string) => void;
+  onPause?: (id: string) => void; // Set while the item is queued and can be paused
+  onResume?: (id: string) => void;
 }
 
 export function UniversalQueueItem({
@@ -112,9 +121,13 @@ export function UniversalQueueItem({
   isFocused = false,
   disabled,
   onRemove,
-  onUpdateTimeRange,
+  onUpdateTimeRanges,
   onRename,
   onScheduleUpcomingLive,
+  onRecordWhenLive,
+  onRedownload,
+  onPause,
+  onResume,
 }: UniversalQueueItemProps) {
   const { t } = useTranslation('universal');
   const ai = useAI();
@@ -127,6 +140,7 @@ export function UniversalQueueItem({
   const [renameName, setRenameName] = useState('');
   const [renameError, setRenameError] = useState<string | null>(null);
   const [isRenaming, setIsRenaming] = useState(false);
+  const [recordWhenLiveAdded, setRecordWhenLiveAdded] = useState(false);
   const handleThumbError = useCallback(() => {
     setThumbError(true);
   }, []);
@@ -150,39 +164,71 @@ export function UniversalQueueItem({
   const isError = item.status === 'error';
   const isPending = item.status === 'pending';
   const isSkipped = item.status === 'skipped';
+  const isPaused = item.status === 'paused';
   const retryState = item.retryState;
   const isFetchingMeta = isPending && !item.thumbnail && item.title === item.url && !item.extractor;
   const isUpcomingLiveError = item.errorCode === 'YT_UPCOMING_LIVE';
+  const isArchivedSkip = isSkipped && item.errorCode === 'DOWNLOAD_ALREADY_ARCHIVED';
 
   // Get saved settings for pending items
   const itemSettings = item.settings as ItemUniversalSettings | undefined;
 
-  const hasTimeRange = !!(itemSettings?.timeRangeStart && itemSettings?.timeRangeEnd);
+  const timeRanges = useMemo(() => itemSettings?.timeRanges ?? [], [itemSettings?.timeRanges]);
+  const joinTimeRanges = itemSettings?.joinTimeRanges ?? false;
+  const preciseTimeRanges = itemSettings?.preciseTimeRanges ?? false;
+  const hasTimeRange = timeRanges.length > 0;
+  const timeRangeLabel = formatTimeRanges(timeRanges);
 
-  const handleApplyTimeRange = useCallback(() => {
+  const handleAddTimeRange = useCallback(() => {
     if (timeStart && timeEnd) {
-      onUpdateTimeRange(item.id, timeStart, timeEnd);
-      setShowTimeRange(false);
+      const ranges = [...timeRanges, { start: timeStart, end: timeEnd }];
+      onUpdateTimeRanges(item.id, ranges, joinTimeRanges, preciseTimeRanges);
+      setTimeStart('');
+      setTimeEnd('');
     }
-  }, [item.id, onUpdateTimeRange, timeStart, timeEnd]);
+  }, [
+    item.id,
+    onUpdateTimeRanges,
+    timeRanges,
+    joinTimeRanges,
+    preciseTimeRanges,
+    timeStart,
+    timeEnd,
+  ]);
+
+  const handleRemoveTimeRange = useCallback(
+    (index: number) => {
+      const ranges = timeRanges.filter((_, i) => i !== index);
+      onUpdateTimeRanges(item.id, ranges, joinTimeRanges, preciseTimeRanges);
+    },
+    [item.id, onUpdateTimeRanges, timeRanges, joinTimeRanges, preciseTimeRanges],
+  );
+
+  const handleToggleJoinTimeRanges = useCallback(() => {
+    onUpdateTimeRanges(item.id, timeRanges, !joinTimeRanges, preciseTimeRanges);
+  }, [item.id, onUpdateTimeRanges, timeRanges, joinTimeRanges, preciseTimeRanges]);
+
+  const handleTogglePreciseTimeRanges = useCallback(() => {
+    onUpdateTimeRanges(item.id, timeRanges, joinTimeRanges, !preciseTimeRanges);
+  }, [item.id, onUpdateTimeRanges, timeRanges, joinTimeRanges, preciseTimeRanges]);
 
   const handleClearTimeRange = useCallback(() => {
-    onUpdateTimeRange(item.id, undefined, undefined);
+    onUpdateTimeRanges(item.id, [], false, false);
     setTimeStart('');
     setTimeEnd('');
     setShowTimeRange(false);
-  }, [item.id, onUpdateTimeRange]);
+  }, [item.id, onUpdateTimeRanges]);
 
   const handleToggleTimeRange = useCallback(() => {
     setShowTimeRange((v) => {
       if (!v) {
-        // Restore saved values when opening
-        setTimeStart(itemSettings?.timeRangeStart ?? '');
-        setTimeEnd(itemSettings?.timeRangeEnd ?? '');
+        // The inputs add a new range; saved ranges are listed above them
+        setTimeStart('');
+        setTimeEnd('');
       }
       return !v;
     });
-  }, [itemSettings?.timeRangeStart, itemSettings?.timeRangeEnd]);
+  }, []);
 
   const handleTimeStartChange = useCallback((e: ChangeEvent<HTMLInputElement>) => {
     setTimeStart(autoFormatTimeInput(e.target.value));
@@ -404,7 +450,7 @@ export function UniversalQueueItem({
           <span
             className={cn(
               'inline-flex items-center gap-1 text-xs px-2 py-0.5 rounded-full font-medium',
-              isPending && 'bg-muted text-muted-foreground',
+              (isPending || isPaused) && 'bg-muted text-muted-foreground',
               isActive && 'bg-primary/10 text-primary',
               isCompleted && 'bg-emerald-500/10 text-emerald-600 dark:text-emerald-400',
               isError && 'bg-red-500/10 text-red-600 dark:text-red-400',
@@ -412,16 +458,18 @@ export function UniversalQueueItem({
             )}
           >
             {isPending && <Clock className="w-3 h-3" />}
+            {isPaused && <Pause className="w-3 h-3" />}
             {isActive && <Loader2 className="w-3 h-3 animate-spin" />}
             {isCompleted && <CheckCircle2 className="w-3 h-3" />}
             {isError && <XCircle className="w-3 h-3" />}
             {isSkipped && <CircleSlash className="w-3 h-3" />}
             <span>
               {isPending && t('queue.status.pending')}
+              {isPaused && t('queue.status.paused')}
               {isActive &&
                 (item.status === 'fetching'
                   ? t('queue.status.fetching')
-                  : t('queue.status.downloading'))}
+                  : t(`queue.status.${item.phase ?? 'downloading'}`))}
               {isCompleted && t('queue.status.completed')}
               {isError && t('queue.status.failed')}
               {isSkipped && t('queue.status.skipped')}
@@ -439,6 +487,18 @@ export function UniversalQueueItem({
             </span>
           )}
 
+          {item.playlistFailures && item.playlistFailures.length > 0 && (
+            <span
+              className="inline-flex items-center gap-1 text-[11px] px-2 py-0.5 rounded-full bg-amber-500/10 text-amber-600 dark:text-amber-400 font-medium"
+              title={item.playlistFailures
+                .map((failure) => `#${failure.index}: ${failure.error}`)
+                .join('\n')}
+            >
+              <AlertTriangle className="w-3 h-3" />
+              {t('queue.playlistFailures', { count: item.playlistFailures.length })}
+            </span>
+          )}
+
           {/* Settings badges for pending/downloading items */}
           {(isPending || isActive) && itemSettings && (
             <>
@@ -456,7 +516,7 @@ export function UniversalQueueItem({
           {!isPending && hasTimeRange && itemSettings && (
             <span className="inline-flex items-center gap-1 text-[11px] px-1.5 py-0.5 rounded bg-amber-500/10 text-amber-600 dark:text-amber-400 font-medium">
               <Scissors className="w-3 h-3" />
-              {itemSettings.timeRangeStart}-{itemSettings.timeRangeEnd}
+              {timeRangeLabel}
             </span>
           )}
 
@@ -507,6 +567,59 @@ export function UniversalQueueItem({
               triggerClassName="border-muted-foreground/30 bg-transparent text-muted-foreground hover:border-muted-foreground/50 hover:bg-muted/50 hover:text-foreground"
             />
           )}
+          {isUpcomingLiveError && onRecordWhenLive && (
+            <button
+              type="button"
+              onClick={() => onRecordWhenLive(item.id).then(setRecordWhenLiveAdded)}
+              disabled={recordWhenLiveAdded}
+              className="inline-flex items-center gap-1 text-[11px] px-2 py-0.5 rounded-full border border-muted-foreground/30 text-muted-foreground hover:border-muted-foreground/50 hover:bg-muted/50 hover:text-foreground transition-colors disabled:opacity-50"
+            >
+              <Radio className="w-3 h-3" />
+              {recordWhenLiveAdded
+                ? t('queue.upcomingLive.recordAdded')
+                : t('queue.upcomingLive.record')}
+            </button>
+          )}
+
+          {/* Already in the download archive - offer a forced re-download */}
+          {isArchivedSkip && item.error && (
+            <span className="text-xs text-amber-600/80 dark:text-amber-400/80 line-clamp-2">
+              {item.error}
+            </span>
+          )}
+          {isArchivedSkip && onRedownload && (
+            <button
+              type="button"
+              onClick={() => onRedownload(item.id)}
+              disabled={disabled}
+              className="inline-flex items-center gap-1 text-[11px] px-2 py-0.5 rounded-full border border-muted-foreground/30 text-muted-foreground hover:border-muted-foreground/50 hover:bg-muted/50 hover:text-foreground transition-colors disabled:opacity-50"
+            >
+              <RefreshCw className="w-3 h-3" />
+              {t('queue.redownload')}
+            </button>
+          )}
+
+          {/* Paused items keep their partial files and continue from them */}
+          {onPause && (isPending || item.status === 'downloading') && (
+            <button
+              type="button"
+              onClick={() => onPause(item.id)}
+              className="inline-flex items-center gap-1 text-[11px] px-2 py-0.5 rounded-full border border-muted-foreground/30 text-muted-foreground hover:border-muted-foreground/50 hover:bg-muted/50 hover:text-foreground transition-colors"
+            >
+              <Pause className="w-3 h-3" />
+              {t('queue.pause')}
+            </button>
+          )}
+          {isPaused && onResume && (
+            <button
+              type="button"
+              onClick={() => onResume(item.id)}
+              className="inline-flex items-center gap-1 text-[11px] px-2 py-0.5 rounded-full border border-muted-foreground/30 text-muted-foreground hover:border-muted-foreground/50 hover:bg-muted/50 hover:text-foreground transition-colors"
+            >
+              <Play className="w-3 h-3" />
+              {t('queue.resume')}
+            </button>
+          )}
 
           {/* Generating Status (inline with info badges) */}
           {isGenerating && (
@@ -535,9 +648,7 @@ export function UniversalQueueItem({
                 )}
               >
                 <Scissors className="w-3 h-3" />
-                {hasTimeRange
-                  ? `${itemSettings.timeRangeStart}-${itemSettings.timeRangeEnd}`
-                  : t('queue.timeRange.title')}
+                {hasTimeRange ? timeRangeLabel : t('queue.timeRange.title')}
               </button>
             )}
 
@@ -636,61 +747,106 @@ export function UniversalQueueItem({
 
         {/* Time Range Inline Panel */}
         {showTimeRange && isPending && (
-          <div className="flex items-center gap-2 mt-2 p-2 rounded-lg bg-amber-500/5 border border-amber-500/10">
-            <Scissors className="w-3.5 h-3.5 text-amber-600 dark:text-amber-400 flex-shrink-0" />
-            <input
-              type="text"
-              placeholder={timePlaceholder}
-              value={timeStart}
-              onChange={handleTimeStartChange}
-              maxLength={8}
-              className={cn(
-                'w-[5.5rem] text-xs px-2 py-1 rounded bg-background border border-border/50 text-center font-mono',
-                'focus:outline-none focus:ring-1 focus:ring-amber-500/50 focus:border-amber-500/50',
-                'placeholder:text-muted-foreground/40',
-                timeStart &&
-                  (!isStartValid || !isRangeValid) &&
-                  'border-red-500/60 focus:ring-red-500/50 focus:border-red-500/50',
-              )}
-            />
-            <span className="text-xs text-muted-foreground">-</span>
-            <input
-              type="text"
-              placeholder={timePlaceholder}
-              value={timeEnd}
-              onChange={handleTimeEndChange}
-              maxLength={8}
-              className={cn(
-                'w-[5.5rem] text-xs px-2 py-1 rounded bg-background border border-border/50 text-center font-mono',
-                'focus:outline-none focus:ring-1 focus:ring-amber-500/50 focus:border-amber-500/50',
-                'placeholder:text-muted-foreground/40',
-                timeEnd &&
-                  (!isEndValid || !isRangeValid) &&
-                  'border-red-500/60 focus:ring-red-500/50 focus:border-red-500/50',
-              )}
-            />
-            {durationSeconds > 0 && (
-              <span className="text-[10px] text-muted-foreground/70 flex-shrink-0">
-                {t('queue.timeRange.duration', { duration: item.duration })}
-              </span>
-            )}
-            <button
-              type="button"
-              onClick={handleApplyTimeRange}
-              disabled={!canApply}
-              className="text-[11px] px-2 py-0.5 rounded-md bg-amber-500/10 text-amber-600 dark:text-amber-400 hover:bg-amber-500/20 font-medium transition-colors disabled:opacity-40 disabled:cursor-not-allowed"
-            >
-              {t('queue.timeRange.apply')}
-            </button>
+          <div className="mt-2 p-2 space-y-2 rounded-lg bg-amber-500/5 border border-amber-500/10">
             {hasTimeRange && (
+              <div className="flex flex-wrap items-center gap-1.5">
+                {timeRanges.map((range, index) => (
+                  <span
+                    key={`${range.start}-${range.end}-${index}`}
+                    className="inline-flex items-center gap-1 text-[11px] px-1.5 py-0.5 rounded bg-amber-500/10 text-amber-600 dark:text-amber-400 font-mono"
+                  >
+                    {range.start}-{range.end}
+                    <button
+                      type="button"
+                      onClick={() => handleRemoveTimeRange(index)}
+                      className="hover:text-foreground transition-colors"
+                      title={t('queue.timeRange.remove')}
+                    >
+                      <X className="w-3 h-3" />
+                    </button>
+                  </span>
+                ))}
+                {timeRanges.length > 1 && (
+                  <label className="inline-flex items-center gap-1 text-[11px] text-muted-foreground cursor-pointer">
+                    <input
+                      type="checkbox"
+                      checked={joinTimeRanges}
+                      onChange={handleToggleJoinTimeRanges}
+                      className="accent-amber-500"
+                    />
+                    {t('queue.timeRange.join')}
+                  </label>
+                )}
+                <label
+                  className="inline-flex items-center gap-1 text-[11px] text-muted-foreground cursor-pointer"
+                  title={t('queue.timeRange.preciseHint')}
+                >
+                  <input
+                    type="checkbox"
+                    checked={preciseTimeRanges}
+                    onChange={handleTogglePreciseTimeRanges}
+                    className="accent-amber-500"
+                  />
+                  {t('queue.timeRange.precise')}
+                </label>
+              </div>
+            )}
+            <div className="flex items-center gap-2">
+              <Scissors className="w-3.5 h-3.5 text-amber-600 dark:text-amber-400 flex-shrink-0" />
+              <input
+                type="text"
+                placeholder={timePlaceholder}
+                value={timeStart}
+                onChange={handleTimeStartChange}
+                maxLength={8}
+                className={cn(
+                  'w-[5.5rem] text-xs px-2 py-1 rounded bg-background border border-border/50 text-center font-mono',
+                  'focus:outline-none focus:ring-1 focus:ring-amber-500/50 focus:border-amber-500/50',
+                  'placeholder:text-muted-foreground/40',
+                  timeStart &&
+                    (!isStartValid || !isRangeValid) &&
+                    'border-red-500/60 focus:ring-red-500/50 focus:border-red-500/50',
+                )}
+              />
+              <span className="text-xs text-muted-foreground">-</span>
+              <input
+                type="text"
+                placeholder={timePlaceholder}
+                value={timeEnd}
+                onChange={handleTimeEndChange}
+                maxLength={8}
+                className={cn(
+                  'w-[5.5rem] text-xs px-2 py-1 rounded bg-background border border-border/50 text-center font-mono',
+                  'focus:outline-none focus:ring-1 focus:ring-amber-500/50 focus:border-amber-500/50',
+                  'placeholder:text-muted-foreground/40',
+                  timeEnd &&
+                    (!isEndValid || !isRangeValid) &&
+                    'border-red-500/60 focus:ring-red-500/50 focus:border-red-500/50',
+                )}
+              />
+              {durationSeconds > 0 && (
+                <span className="text-[10px] text-muted-foreground/70 flex-shrink-0">
+                  {t('queue.timeRange.duration', { duration: item.duration })}
+                </span>
+              )}
               <button
                 type="button"
-                onClick={handleClearTimeRange}
-                className="text-[11px] px-2 py-0.5 rounded-md text-muted-foreground hover:text-foreground hover:bg-muted/50 font-medium transition-colors"
+                onClick={handleAddTimeRange}
+                disabled={!canApply}
+                className="text-[11px] px-2 py-0.5 rounded-md bg-amber-500/10 text-amber-600 dark:text-amber-400 hover:bg-amber-500/20 font-medium transition-colors disabled:opacity-40 disabled:cursor-not-allowed"
               >
-                {t('queue.timeRange.clear')}
+                {t('queue.timeRange.add')}
               </button>
-            )}
+              {hasTimeRange && (
+                <button
+                  type="button"
+                  onClick={handleClearTimeRange}
+                  className="text-[11px] px-2 py-0.5 rounded-md text-muted-foreground hover:text-foreground hover:bg-muted/50 font-medium transition-colors"
+                >
+                  {t('queue.timeRange.clear')}
+                </button>
+              )}
+            </div>
           </div>
         )}
 
@@ -21,7 +21,7 @@ import { Badge } from '@/components/ui/badge';
 import { Button } from '@/components/ui/button';
 import { ScrollArea } from '@/components/ui/scroll-area';
 import type { ScheduleConfig } from '@/hooks/useSchedule';
-import type { DownloadItem } from '@/lib/types';
+import type { DownloadItem, TimeRange } from '@/lib/types';
 import { cn } from '@/lib/utils';
 import {
   filterQueueItems,
@@ -50,10 +50,14 @@ interface UniversalQueueListProps {
   focusedItemId?: string | null;
   isDownloading: boolean;
   onRemove: (id: string) => void;
-  onUpdateTimeRange: (id: string, start?: string, end?: string) => void;
+  onUpdateTimeRanges: (id: string, ranges: TimeRange[], join: boolean, precise: boolean) => void;
   onRename: (id: string, newName: string) => Promise<void>;
   onClearCompleted: () => void;
   onScheduleUpcomingLive?: (config: ScheduleConfig) => void;
+  onRecordWhenLive?: (id: string) => Promise<boolean>; // Resolves to whether it was added
+  onRedownload?: (id: string) => void;
+  onPause?: (id: string) => void;
+  onResume?: (id: string) => void;
 }
 
 export function UniversalQueueList({
@@ -61,10 +65,14 @@ export function UniversalQueueList({
   focusedItemId,
   isDownloading,
   onRemove,
-  onUpdateTimeRange,
+  onUpdateTimeRanges,
   onRename,
   onClearCompleted,
   onScheduleUpcomingLive,
+  onRecordWhenLive,
+  onRedownload,
+  onPause,
+  onResume,
 }: UniversalQueueListProps) {
   const { t } = useTranslation('universal');
   const containerRef = useRef<HTMLDivElement | null>(null);
@@ -187,9 +195,13 @@ export function UniversalQueueList({
                 isFocused={focusedItemId === item.id}
                 disabled={isDownloading}
                 onRemove={onRemove}
-                onUpdateTimeRange={onUpdateTimeRange}
+                onUpdateTimeRanges={onUpdateTimeRanges}
                 onRename={onRename}
                 onScheduleUpcomingLive={onScheduleUpcomingLive}
+                onRecordWhenLive={onRecordWhenLive}
+                onRedownload={onRedownload}
+                onPause={isDownloading ? onPause : undefined}
+                onResume={onResume}
               />
             ))}
           </div>
@@ -23,6 +23,7 @@ import {
 import { Input } from '@/components/ui/input';
 import { Textarea } from '@/components/ui/textarea';
 import { normalizeShellEscapedUrl } from '@/lib/sources';
+import type { FormatPreferences } from '@/lib/types';
 import { cn } from '@/lib/utils';
 import { VideoPreview } from './VideoPreview';
 
@@ -36,6 +37,11 @@ interface UrlInputProps {
   onImportFile: () => Promise<number>;
   onImportClipboard: () => Promise<number>;
   onGoToSettings?: () => void;
+  outputTemplate?: string;
+  outputFormat?: string;
+  outputPath?: string;
+  quality?: string;
+  formatPreferences?: FormatPreferences | null;
 }
 
 function extractFirstUrl(text: string): string | null {
@@ -80,6 +86,11 @@ export function UrlInput({
   onImportFile,
   onImportClipboard,
   onGoToSettings,
+  outputTemplate,
+  outputFormat,
+  outputPath,
+  quality,
+  formatPreferences,
 }: UrlInputProps) {
   const { t } = useTranslation('download');
   const [value, setValue] = useState('');
@@ -430,6 +441,11 @@ export function UrlInput({
       {showPreview && previewUrl && (
         <VideoPreview
           url={previewUrl}
+          outputTemplate={outputTemplate}
+          outputFormat={outputFormat}
+          outputPath={outputPath}
+          quality={quality}
+          formatPreferences={formatPreferences}
           onClose={() => {
             setValue('');
             setShowPreview(false);
@@ -5,6 +5,7 @@ import {
   ChevronDown,
   ChevronUp,
   Eye,
+  FolderTree,
   ListVideo,
   Loader2,
   User,
@@ -14,7 +15,9 @@ import { useEffect, useState } from 'react';
 import { Badge } from '@/components/ui/badge';
 import { Button } from '@/components/ui/button';
 import { localizeUnknownError } from '@/lib/backend-error';
+import { explainFormatPreferences, formatReasonText } from '@/lib/format-preferences';
 import { buildCookieProxyInvokeOptions, loadNetworkSettings } from '@/lib/network-config';
+import type { FormatChoice, FormatPreferences } from '@/lib/types';
 import { cn } from '@/lib/utils';
 
 interface VideoInfo {
@@ -31,6 +34,12 @@ interface VideoInfo {
   playlist_count: number | null;
 }
 
+interface OutputTemplatePreview {
+  template: string;
+  relative_path: string;
+  filepath: string | null;
+}
+
 interface FormatOption {
   format_id: string;
   ext: string;
@@ -45,6 +54,8 @@ interface FormatOption {
   format_note: string | null;
   fps: number | null;
   quality: number | null;
+  dynamic_range?: string | null;
+  language?: string | null;
 }
 
 interface VideoInfoResponse {
@@ -56,6 +67,11 @@ interface VideoPreviewProps {
   url: string;
   onClose?: () => void;
   onFormatSelect?: (formatId: string) => void;
+  outputTemplate?: string;
+  outputFormat?: string;
+  outputPath?: string;
+  quality?: string;
+  formatPreferences?: FormatPreferences | null;
   className?: string;
 }
 
@@ -107,7 +123,17 @@ function formatFilesize(bytes: number | null | undefined): string {
   return `${bytes} B`;
 }
 
-export function VideoPreview({ url, onClose, onFormatSelect, className }: VideoPreviewProps) {
+export function VideoPreview({
+  url,
+  onClose,
+  onFormatSelect,
+  outputTemplate,
+  outputFormat,
+  outputPath,
+  quality,
+  formatPreferences,
+  className,
+}: VideoPreviewProps) {
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [data, setData] = useState<VideoInfoResponse | null>(null);
@@ -116,6 +142,8 @@ export function VideoPreview({ url, onClose, onFormatSelect, className }: VideoP
   );
   const [showDetails, setShowDetails] = useState(false);
   const [showFormats, setShowFormats] = useState(false);
+  const [savePath, setSavePath] = useState<string | null>(null);
+  const [formatChoice, setFormatChoice] = useState<FormatChoice | null>(null);
 
   useEffect(() => {
     let cancelled = false;
@@ -157,6 +185,49 @@ export function VideoPreview({ url, onClose, onFormatSelect, className }: VideoP
     };
   }, [url]);
 
+  // Preview the output template against the real video info
+  useEffect(() => {
+    if (!data || data.info.is_playlist) {
+      setSavePath(null);
+      return;
+    }
+    let cancelled = false;
+    invoke<OutputTemplatePreview>('preview_output_template', {
+      template: outputTemplate ?? '',
+      info: data.info,
+      ext: outputFormat,
+      outputPath,
+    })
+      .then((preview) => {
+        if (!cancelled) setSavePath(preview.relative_path);
+      })
+      .catch(() => {
+        if (!cancelled) setSavePath(null);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [data, outputTemplate, outputFormat, outputPath]);
+
+  // Explain which formats the saved format rules would pick for this video
+  useEffect(() => {
+    if (!data || data.info.is_playlist || !formatPreferences || !quality || !outputFormat) {
+      setFormatChoice(null);
+      return;
+    }
+    let cancelled = false;
+    explainFormatPreferences(formatPreferences, quality, outputFormat, data.formats)
+      .then((plan) => {
+        if (!cancelled) setFormatChoice(plan.choice);
+      })
+      .catch(() => {
+        if (!cancelled) setFormatChoice(null);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [data, formatPreferences, quality, outputFormat]);
+
   if (loading) {
     return (
       <div className={cn('rounded-xl border bg-card/50 backdrop-blur-sm p-4 sm:p-5', className)}>
@@ -286,6 +357,17 @@ export function VideoPreview({ url, onClose, onFormatSelect, className }: VideoP
             )}
           </div>
 
+          {savePath && (
+            <div
+              className="mt-2 flex min-w-0 items-center gap-1 text-xs text-muted-foreground"
+              title={savePath}
+            >
+              <FolderTree className="h-3 w-3 flex-shrink-0" />
+              <span className="flex-shrink-0">Saves as</span>
+              <span className="truncate font-mono">{savePath}</span>
+            </div>
+          )}
+
           <div className="mt-2 flex flex-wrap items-center gap-2">
             {(videoFormats.length > 0 || audioFormats.length > 0) && (
               <button
@@ -367,6 +449,16 @@ export function VideoPreview({ url, onClose, onFormatSelect, className }: VideoP
               </button>
             ))}
           </div>
+          {formatChoice && (
+            <div className="mt-3 text-xs text-muted-foreground">
+              <p className="mb-1 font-medium">Why this format</p>
+              <ul className="list-disc space-y-0.5 pl-4">
+                {formatChoice.reasons.map((reason, index) => (
+                  <li key={`${reason.key}-${index}`}>{formatReasonText(reason)}</li>
+                ))}
+              </ul>
+            </div>
+          )}
         </div>
       )}
     </div>
@@ -12,6 +12,8 @@ import {
   FolderOpen,
   HardDrive,
   Hash,
+  ListOrdered,
+  ListVideo,
   Loader2,
   Pause,
   Pencil,
@@ -371,6 +373,21 @@ export function HistoryItem({ entry }: HistoryItemProps) {
                 {entry.time_range}
               </span>
             )}
+            {entry.chapter_index != null && (
+              <span className="inline-flex items-center gap-1 font-medium px-1.5 py-0.5 rounded bg-sky-500/10 text-sky-600 dark:text-sky-400">
+                <ListOrdered className="w-3 h-3" />
+                {t('library.item.chapterPart', { index: entry.chapter_index })}
+              </span>
+            )}
+            {entry.playlist_index != null && (
+              <span
+                className="inline-flex items-center gap-1 font-medium px-1.5 py-0.5 rounded bg-violet-500/10 text-violet-600 dark:text-violet-400"
+                title={entry.playlist_id}
+              >
+                <ListVideo className="w-3 h-3" />
+                {t('library.item.playlistEntry', { index: entry.playlist_index })}
+              </span>
+            )}
             <span className="flex items-center gap-1">
               <HardDrive className="w-3 h-3" />
               {formatSize(entry.filesize, t('library.item.unknown'))}
@@ -0,0 +1,243 @@
+import { FolderPlus, Link2, Loader2, Search, X } from 'lucide-react';
+import { useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Button } from '@/components/ui/button';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Progress } from '@/components/ui/progress';
+import { useHistory } from '@/contexts/HistoryContext';
+import { localizeUnknownError } from '@/lib/backend-error';
+
+interface LibraryRelinkDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}
+
+function formatFileSize(bytes: number): string {
+  if (bytes >= 1024 * 1024 * 1024) {
+    return `${(bytes / (1024 * 1024 * 1024)).toFixed(1)} GB`;
+  } else if (bytes >= 1024 * 1024) {
+    return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  } else if (bytes >= 1024) {
+    return `${(bytes / 1024).toFixed(0)} KB`;
+  }
+  return `${bytes} B`;
+}
+
+export function LibraryRelinkDialog({ open, onOpenChange }: LibraryRelinkDialogProps) {
+  const { t } = useTranslation('pages');
+  const {
+    libraryRoots,
+    libraryRelink,
+    libraryScan,
+    addLibraryRoot,
+    removeLibraryRoot,
+    relinkLibraryFiles,
+    relinkHistoryEntry,
+    cancelLibraryScan,
+    dismissLibraryRelink,
+  } = useHistory();
+  const [error, setError] = useState<string | null>(null);
+  const [pickingId, setPickingId] = useState<string | null>(null);
+
+  const running = !!libraryRelink && !libraryRelink.finished;
+  const scanRunning = !!libraryScan && !libraryScan.finished;
+  const total = libraryRelink?.total ?? 0;
+  const processed = libraryRelink?.processed ?? 0;
+  const percent = total > 0 ? Math.round((processed / total) * 100) : 0;
+
+  const handleStart = async () => {
+    setError(null);
+    try {
+      await relinkLibraryFiles();
+    } catch (err) {
+      setError(localizeUnknownError(err));
+    }
+  };
+
+  const handlePick = async (historyId: string, filepath: string) => {
+    setPickingId(historyId);
+    setError(null);
+    try {
+      await relinkHistoryEntry(historyId, filepath);
+    } catch (err) {
+      setError(localizeUnknownError(err));
+    } finally {
+      setPickingId(null);
+    }
+  };
+
+  let status = '';
+  if (error) {
+    status = t('library.relink.failed');
+  } else if (running) {
+    status =
+      total > 0 ? t('library.relink.checking', { processed, total }) : t('library.relink.searching');
+  } else if (libraryRelink?.cancelled) {
+    status = t('library.relink.stopped');
+  } else if (libraryRelink) {
+    status = t('library.relink.finished');
+  }
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-[680px]">
+        <DialogHeader>
+          <DialogTitle>{t('library.relink.title')}</DialogTitle>
+          <DialogDescription>{t('library.relink.description')}</DialogDescription>
+        </DialogHeader>
+
+        <div className="space-y-4">
+          <div className="rounded-lg border border-border/60 bg-muted/20 p-3 space-y-2">
+            <div className="flex items-center justify-between gap-2">
+              <p className="text-sm font-medium">{t('library.relink.folders')}</p>
+              <Button
+                variant="outline"
+                size="sm"
+                className="h-7 gap-1.5 text-xs"
+                onClick={() => void addLibraryRoot()}
+                disabled={running}
+              >
+                <FolderPlus className="w-3.5 h-3.5" />
+                {t('library.relink.addFolder')}
+              </Button>
+            </div>
+            {libraryRoots.length === 0 ? (
+              <p className="text-xs text-muted-foreground">{t('library.relink.noFolders')}</p>
+            ) : (
+              <div className="space-y-1">
+                {libraryRoots.map((root) => (
+                  <div key={root} className="flex items-center gap-2">
+                    <span className="flex-1 min-w-0 truncate text-xs font-mono" title={root}>
+                      {root}
+                    </span>
+                    <Button
+                      variant="ghost"
+                      size="icon"
+                      className="h-6 w-6"
+                      onClick={() => removeLibraryRoot(root)}
+                      disabled={running}
+                      title={t('library.relink.removeFolder')}
+                    >
+                      <X className="w-3.5 h-3.5" />
+                    </Button>
+                  </div>
+                ))}
+              </div>
+            )}
+          </div>
+
+          <div className="flex items-center gap-2">
+            {running ? (
+              <Button variant="outline" onClick={() => void cancelLibraryScan()}>
+                {t('library.relink.stop')}
+              </Button>
+            ) : (
+              <Button
+                onClick={() => void handleStart()}
+                disabled={libraryRoots.length === 0 || scanRunning}
+              >
+                <Search className="w-4 h-4" />
+                {t('library.relink.start')}
+              </Button>
+            )}
+            {libraryRelink && !running && (
+              <Button variant="ghost" size="sm" onClick={dismissLibraryRelink}>
+                {t('library.relink.clear')}
+              </Button>
+            )}
+          </div>
+
+          {status && (
+            <div className="rounded-lg border border-border/50 bg-card/50 px-3 py-2.5 space-y-2">
+              <div className="flex items-center gap-2">
+                {running && <Loader2 className="w-4 h-4 shrink-0 animate-spin text-primary" />}
+                <div className="flex-1 min-w-0">
+                  <p className="text-xs font-medium">{status}</p>
+                  <p
+                    className="text-[11px] text-muted-foreground truncate"
+                    title={libraryRelink?.current ?? ''}
+                  >
+                    {error ??
+                      (running && libraryRelink?.current
+                        ? libraryRelink.current
+                        : libraryRelink &&
+                          t('library.relink.summary', {
+                            relinked: libraryRelink.relinked,
+                            ambiguous: libraryRelink.ambiguous.length,
+                            notFound: libraryRelink.notFound,
+                          }))}
+                  </p>
+                </div>
+              </div>
+              {running && total > 0 && <Progress value={percent} className="h-1.5" />}
+            </div>
+          )}
+
+          {!running && libraryRelink && libraryRelink.ambiguous.length > 0 && (
+            <div className="space-y-2">
+              <p className="text-sm font-medium">{t('library.relink.pickTitle')}</p>
+              <div className="max-h-[320px] overflow-y-auto space-y-2 pr-1">
+                {libraryRelink.ambiguous.map((missing) => (
+                  <div
+                    key={missing.historyId}
+                    className="rounded-lg border border-border/50 bg-background/60 p-3 space-y-2"
+                  >
+                    <div className="min-w-0">
+                      <p className="text-sm font-medium truncate">{missing.title}</p>
+                      <p
+                        className="text-[11px] text-muted-foreground truncate line-through"
+                        title={missing.filepath}
+                      >
+                        {missing.filepath}
+                      </p>
+                    </div>
+                    {missing.candidates.length === 0 ? (
+                      <p className="text-xs text-muted-foreground">
+                        {t('library.relink.noCandidates')}
+                      </p>
+                    ) : (
+                      missing.candidates.map((candidate) => (
+                        <div key={candidate.filepath} className="flex items-center gap-2">
+                          <div className="flex-1 min-w-0">
+                            <p className="text-xs font-mono truncate" title={candidate.filepath}>
+                              {candidate.filepath}
+                            </p>
+                            <p className="text-[11px] text-muted-foreground">
+                              {[
+                                formatFileSize(candidate.filesize),
+                                candidate.sameName && t('library.relink.sameName'),
+                                candidate.sameContent && t('library.relink.sameContent'),
+                              ]
+                                .filter(Boolean)
+                                .join(' · ')}
+                            </p>
+                          </div>
+                          <Button
+                            variant="outline"
+                            size="sm"
+                            className="h-7 gap-1.5 text-xs"
+                            onClick={() => void handlePick(missing.historyId, candidate.filepath)}
+                            disabled={pickingId !== null}
+                          >
+                            <Link2 className="w-3.5 h-3.5" />
+                            {t('library.relink.use')}
+                          </Button>
+                        </div>
+                      ))
+                    )}
+                  </div>
+                ))}
+              </div>
+            </div>
+          )}
+        </div>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -0,0 +1,76 @@
+import { FolderSearch, Loader2, X } from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import { Button } from '@/components/ui/button';
+import { Progress } from '@/components/ui/progress';
+import type { LibraryScanProgress } from '@/lib/types';
+
+interface LibraryScanStatusProps {
+  scan: LibraryScanProgress | null;
+  error: string | null;
+  onCancel: () => void;
+  onDismiss: () => void;
+}
+
+export function LibraryScanStatus({ scan, error, onCancel, onDismiss }: LibraryScanStatusProps) {
+  const { t } = useTranslation('pages');
+
+  if (!scan && !error) return null;
+
+  const running = !!scan && !scan.finished;
+  const total = scan?.total ?? 0;
+  const processed = scan?.processed ?? 0;
+  const percent = total > 0 ? Math.round((processed / total) * 100) : 0;
+  const summary = scan
+    ? t('library.scan.summary', {
+        imported: scan.imported,
+        skipped: scan.skipped,
+        failed: scan.failed,
+      })
+    : '';
+
+  let title = '';
+  if (error) {
+    title = t('library.scan.failed');
+  } else if (running) {
+    title =
+      total > 0 ? t('library.scan.importing', { processed, total }) : t('library.scan.searching');
+  } else if (scan?.cancelled) {
+    title = t('library.scan.stopped');
+  } else {
+    title = t('library.scan.finished');
+  }
+
+  return (
+    <div className="rounded-lg border border-border/50 bg-card/50 px-3 py-2.5 space-y-2">
+      <div className="flex items-center gap-2">
+        {running ? (
+          <Loader2 className="w-4 h-4 shrink-0 animate-spin text-primary" />
+        ) : (
+          <FolderSearch className="w-4 h-4 shrink-0 text-muted-foreground" />
+        )}
+        <div className="flex-1 min-w-0">
+          <p className="text-xs font-medium">{title}</p>
+          <p className="text-[11px] text-muted-foreground truncate" title={scan?.current ?? ''}>
+            {error ?? (running && scan?.current ? scan.current : summary)}
+          </p>
+        </div>
+        {running ? (
+          <Button variant="ghost" size="sm" className="h-7 px-2 text-xs" onClick={onCancel}>
+            {t('library.scan.stop')}
+          </Button>
+        ) : (
+          <Button
+            variant="ghost"
+            size="icon"
+            className="h-7 w-7"
+            onClick={onDismiss}
+            title={t('library.scan.dismiss')}
+          >
+            <X className="w-3.5 h-3.5" />
+          </Button>
+        )}
+      </div>
+      {running && total > 0 && <Progress value={percent} className="h-1.5" />}
+    </div>
+  );
+}
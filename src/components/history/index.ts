@@ -2,3 +2,5 @@ export { CollectionManagerDialog } from './CollectionManagerDialog';
 export { HistoryItem } from './HistoryItem';
 export { HistoryTagsCollectionsDialog } from './HistoryTagsCollectionsDialog';
 export { HistoryToolbar } from './HistoryToolbar';
+export { LibraryRelinkDialog } from './LibraryRelinkDialog';
+export { LibraryScanStatus } from './LibraryScanStatus';
@@ -0,0 +1,147 @@
+import { Plus, Trash2 } from 'lucide-react';
+import { useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { useDownload } from '@/contexts/DownloadContext';
+import { formatRate, rateToBytes, type RateUnit } from '@/lib/bandwidth';
+import { minutesToTime, timeToMinutes } from '@/lib/download-schedules';
+import { SettingsCard } from './SettingsSection';
+
+interface BandwidthProfilesCardProps {
+  highlight?: boolean;
+}
+
+export function BandwidthProfilesCard({ highlight }: BandwidthProfilesCardProps) {
+  const { t } = useTranslation('settings');
+  const { settings, updateBandwidthProfiles } = useDownload();
+  const [start, setStart] = useState('09:00');
+  const [end, setEnd] = useState('17:00');
+  const [limited, setLimited] = useState(true);
+  const [value, setValue] = useState(2);
+  const [unit, setUnit] = useState<RateUnit>('M');
+
+  const startMinute = timeToMinutes(start);
+  const endMinute = timeToMinutes(end);
+  const valid = startMinute !== null && endMinute !== null && startMinute !== endMinute;
+
+  const handleAdd = () => {
+    if (startMinute === null || endMinute === null || startMinute === endMinute) return;
+    updateBandwidthProfiles([
+      ...settings.bandwidthProfiles,
+      { startMinute, endMinute, limit: limited ? rateToBytes(value, unit) : null },
+    ]);
+  };
+
+  return (
+    <SettingsCard id="bandwidth-profiles" highlight={highlight}>
+      <div className="pb-2">
+        <p className="text-sm font-medium">{t('download.bandwidthProfiles')}</p>
+        <p className="text-xs text-muted-foreground">{t('download.bandwidthProfilesDesc')}</p>
+      </div>
+      {settings.bandwidthProfiles.length === 0 ? (
+        <p className="py-2 text-sm text-muted-foreground">
+          {t('download.bandwidthProfileEmpty')}
+        </p>
+      ) : (
+        settings.bandwidthProfiles.map((profile, index) => (
+          <div
+            key={`${profile.startMinute}-${profile.endMinute}-${index}`}
+            className="flex items-center justify-between gap-3 py-2"
+          >
+            <span className="text-sm">
+              {minutesToTime(profile.startMinute)} – {minutesToTime(profile.endMinute)}
+              {' · '}
+              {profile.limit === null ? t('download.unlimited') : formatRate(profile.limit)}
+            </span>
+            <Button
+              variant="ghost"
+              size="icon"
+              className="h-8 w-8 shrink-0 text-muted-foreground hover:text-destructive"
+              onClick={() =>
+                updateBandwidthProfiles(settings.bandwidthProfiles.filter((_, i) => i !== index))
+              }
+              title={t('download.bandwidthProfileRemove')}
+            >
+              <Trash2 className="h-4 w-4" />
+            </Button>
+          </div>
+        ))
+      )}
+
+      <div className="space-y-3 border-t border-border/50 pt-3">
+        <div className="flex flex-wrap items-center gap-2">
+          <Input
+            type="time"
+            value={start}
+            onChange={(e) => setStart(e.target.value)}
+            className="h-9 w-28 bg-background"
+          />
+          <span className="text-xs text-muted-foreground">–</span>
+          <Input
+            type="time"
+            value={end}
+            onChange={(e) => setEnd(e.target.value)}
+            className="h-9 w-28 bg-background"
+          />
+          <Select
+            value={limited ? 'limited' : 'unlimited'}
+            onValueChange={(v) => setLimited(v === 'limited')}
+          >
+            <SelectTrigger className="h-9 w-[120px] bg-background">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="limited">{t('download.limited')}</SelectItem>
+              <SelectItem value="unlimited">{t('download.unlimited')}</SelectItem>
+            </SelectContent>
+          </Select>
+          {limited && (
+            <>
+              <Input
+                type="number"
+                min={1}
+                max={9999}
+                value={value}
+                onChange={(e) =>
+                  setValue(Math.max(1, Math.min(9999, Number(e.target.value) || 1)))
+                }
+                className="h-9 w-20 bg-background text-center"
+              />
+              <Select value={unit} onValueChange={(v: RateUnit) => setUnit(v)}>
+                <SelectTrigger className="h-9 w-[85px] bg-background">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  <SelectItem value="K">KB/s</SelectItem>
+                  <SelectItem value="M">MB/s</SelectItem>
+                  <SelectItem value="G">GB/s</SelectItem>
+                </SelectContent>
+              </Select>
+            </>
+          )}
+        </div>
+        <div className="flex items-center justify-between gap-3">
+          <p className="text-xs text-muted-foreground">{t('download.bandwidthProfileHint')}</p>
+          <Button
+            size="sm"
+            variant="outline"
+            className="shrink-0 gap-1.5"
+            onClick={handleAdd}
+            disabled={!valid}
+          >
+            <Plus className="w-3.5 h-3.5" />
+            {t('download.bandwidthProfileAdd')}
+          </Button>
+        </div>
+      </div>
+    </SettingsCard>
+  );
+}
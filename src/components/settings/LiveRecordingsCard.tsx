@@ -0,0 +1,164 @@
+import { Plus, Trash2, X } from 'lucide-react';
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { useDownload } from '@/contexts/DownloadContext';
+import { extractBackendError } from '@/lib/backend-error';
+import {
+  addLiveRecording,
+  cancelLiveRecording,
+  listLiveRecordings,
+  liveRecordingOptions,
+  onLiveRecordingsUpdated,
+  removeLiveRecording,
+} from '@/lib/live-recordings';
+import type { LiveRecording } from '@/lib/types';
+import { cn } from '@/lib/utils';
+import { SettingsCard } from './SettingsSection';
+
+interface LiveRecordingsCardProps {
+  highlight?: boolean;
+}
+
+export function LiveRecordingsCard({ highlight }: LiveRecordingsCardProps) {
+  const { t, i18n } = useTranslation('settings');
+  const { settings } = useDownload();
+  const [recordings, setRecordings] = useState<LiveRecording[]>([]);
+  const [error, setError] = useState<string | null>(null);
+  const [url, setUrl] = useState('');
+
+  const refresh = useCallback(async () => {
+    try {
+      setRecordings(await listLiveRecordings());
+    } catch (e) {
+      setError(extractBackendError(e).message);
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const unlisten = onLiveRecordingsUpdated(() => {
+      refresh();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [refresh]);
+
+  const handleAdd = async () => {
+    if (!url.trim()) return;
+    setError(null);
+    try {
+      await addLiveRecording({ url: url.trim(), options: liveRecordingOptions(settings) });
+      setUrl('');
+      await refresh();
+    } catch (e) {
+      setError(extractBackendError(e).message);
+    }
+  };
+
+  const runAction = async (action: () => Promise<void>) => {
+    try {
+      await action();
+      await refresh();
+    } catch (e) {
+      setError(extractBackendError(e).message);
+    }
+  };
+
+  const describe = (recording: LiveRecording) => {
+    const time = (ms: number) => new Date(ms).toLocaleString(i18n.language);
+    switch (recording.status) {
+      case 'waiting':
+        return recording.releaseAt !== null && recording.sessions === 0
+          ? t('download.liveRecordingStartsAt', { time: time(recording.releaseAt) })
+          : t('download.liveRecordingWaiting');
+      case 'recording':
+        return t('download.liveRecordingRecording', { count: recording.sessions });
+      case 'completed':
+        return t('download.liveRecordingCompleted', { count: recording.sessions });
+      case 'failed':
+        return t('download.liveRecordingFailed', { error: recording.errorMessage ?? '' });
+      case 'cancelled':
+        return t('download.liveRecordingCancelled');
+    }
+  };
+
+  return (
+    <SettingsCard id="live-recordings" highlight={highlight}>
+      <div className="pb-2">
+        <p className="text-sm font-medium">{t('download.liveRecordings')}</p>
+        <p className="text-xs text-muted-foreground">{t('download.liveRecordingsDesc')}</p>
+      </div>
+      {recordings.length === 0 ? (
+        <p className="py-2 text-sm text-muted-foreground">{t('download.liveRecordingEmpty')}</p>
+      ) : (
+        recordings.map((recording) => {
+          const active = recording.status === 'waiting' || recording.status === 'recording';
+          return (
+            <div key={recording.id} className="flex items-center justify-between gap-3 py-2">
+              <div className="min-w-0">
+                <p className="truncate text-sm" title={recording.url}>
+                  {recording.title || recording.url}
+                </p>
+                <p
+                  className={cn(
+                    'truncate text-xs',
+                    recording.status === 'recording'
+                      ? 'text-red-500'
+                      : recording.status === 'failed'
+                        ? 'text-destructive'
+                        : 'text-muted-foreground',
+                  )}
+                  title={recording.errorMessage ?? undefined}
+                >
+                  {describe(recording)}
+                </p>
+              </div>
+              <Button
+                variant="ghost"
+                size="icon"
+                className="h-8 w-8 shrink-0 text-muted-foreground hover:text-destructive"
+                onClick={() =>
+                  runAction(() =>
+                    active ? cancelLiveRecording(recording.id) : removeLiveRecording(recording.id),
+                  )
+                }
+                title={
+                  active ? t('download.liveRecordingCancel') : t('download.liveRecordingRemove')
+                }
+              >
+                {active ? <X className="h-4 w-4" /> : <Trash2 className="h-4 w-4" />}
+              </Button>
+            </div>
+          );
+        })
+      )}
+
+      <div className="space-y-3 border-t border-border/50 pt-3">
+        <div className="flex items-center gap-2">
+          <Input
+            value={url}
+            onChange={(e) => setUrl(e.target.value)}
+            placeholder={t('download.liveRecordingUrlPlaceholder')}
+            className="h-9 bg-background"
+          />
+          <Button
+            size="sm"
+            variant="outline"
+            className="shrink-0 gap-1.5"
+            onClick={handleAdd}
+            disabled={!url.trim()}
+          >
+            <Plus className="w-3.5 h-3.5" />
+            {t('download.liveRecordingAdd')}
+          </Button>
+        </div>
+        <p className="text-xs text-muted-foreground">{t('download.liveRecordingHint')}</p>
+      </div>
+
+      {error && <p className="break-all pt-2 text-xs text-destructive">{error}</p>}
+    </SettingsCard>
+  );
+}
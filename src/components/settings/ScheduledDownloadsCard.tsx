@@ -0,0 +1,290 @@
+import { Plus, Trash2 } from 'lucide-react';
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { Switch } from '@/components/ui/switch';
+import { useDownload } from '@/contexts/DownloadContext';
+import { extractBackendError } from '@/lib/backend-error';
+import {
+  addDownloadSchedule,
+  describeScheduleRule,
+  listDownloadSchedules,
+  onDownloadSchedulesUpdated,
+  removeDownloadSchedule,
+  setDownloadScheduleEnabled,
+  timeToMinutes,
+  weekdayName,
+} from '@/lib/download-schedules';
+import type { DownloadSchedule, ScheduleRule } from '@/lib/types';
+import { cn } from '@/lib/utils';
+import { SettingsCard } from './SettingsSection';
+
+type RuleKind = ScheduleRule['kind'];
+
+const RULE_KINDS: RuleKind[] = ['once', 'window', 'weekly'];
+
+// Sunday first, matching Date.getDay()
+const WEEKDAYS = [0, 1, 2, 3, 4, 5, 6];
+
+interface ScheduledDownloadsCardProps {
+  highlight?: boolean;
+}
+
+export function ScheduledDownloadsCard({ highlight }: ScheduledDownloadsCardProps) {
+  const { t, i18n } = useTranslation('settings');
+  const { settings } = useDownload();
+  const [schedules, setSchedules] = useState<DownloadSchedule[]>([]);
+  const [error, setError] = useState<string | null>(null);
+  const [url, setUrl] = useState('');
+  const [kind, setKind] = useState<RuleKind>('window');
+  const [onceAt, setOnceAt] = useState('');
+  const [windowStart, setWindowStart] = useState('01:00');
+  const [windowEnd, setWindowEnd] = useState('06:00');
+  const [weekdays, setWeekdays] = useState<number[]>([0]);
+  const [weeklyTime, setWeeklyTime] = useState('03:00');
+  const [wholePlaylist, setWholePlaylist] = useState(false);
+
+  const refresh = useCallback(async () => {
+    try {
+      setSchedules(await listDownloadSchedules());
+    } catch (e) {
+      setError(extractBackendError(e).message);
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const unlisten = onDownloadSchedulesUpdated(() => {
+      refresh();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [refresh]);
+
+  const buildRule = (): ScheduleRule | null => {
+    switch (kind) {
+      case 'once': {
+        const at = new Date(onceAt).getTime();
+        return Number.isNaN(at) ? null : { kind, at };
+      }
+      case 'window': {
+        const startMinute = timeToMinutes(windowStart);
+        const endMinute = timeToMinutes(windowEnd);
+        if (startMinute === null || endMinute === null) return null;
+        return { kind, startMinute, endMinute };
+      }
+      case 'weekly': {
+        const minute = timeToMinutes(weeklyTime);
+        if (minute === null || weekdays.length === 0) return null;
+        return { kind, weekdays, minute };
+      }
+    }
+  };
+
+  const rule = buildRule();
+
+  const handleAdd = async () => {
+    if (!rule || !url.trim()) return;
+    setError(null);
+    try {
+      // Network settings are filled in by the backend, like other queued downloads
+      await addDownloadSchedule({
+        url: url.trim(),
+        rule,
+        options: {
+          outputPath: settings.outputPath || null,
+          quality: settings.quality,
+          format: settings.format,
+          videoCodec: settings.videoCodec,
+          audioBitrate: settings.audioBitrate,
+          downloadPlaylist: wholePlaylist,
+          embedMetadata: settings.embedMetadata,
+          embedThumbnail: settings.embedThumbnail,
+          verifyOutput: settings.verifyDownloads,
+          outputTemplate: settings.outputTemplate || null,
+          formatPreferences: settings.formatPreferences,
+          profile: settings.downloadProfile || null,
+          downloadKind: 'scheduled',
+        },
+      });
+      setUrl('');
+      await refresh();
+    } catch (e) {
+      setError(extractBackendError(e).message);
+    }
+  };
+
+  const handleToggle = async (schedule: DownloadSchedule, enabled: boolean) => {
+    try {
+      await setDownloadScheduleEnabled(schedule.id, enabled);
+      await refresh();
+    } catch (e) {
+      setError(extractBackendError(e).message);
+    }
+  };
+
+  const handleRemove = async (schedule: DownloadSchedule) => {
+    try {
+      await removeDownloadSchedule(schedule.id);
+      await refresh();
+    } catch (e) {
+      setError(extractBackendError(e).message);
+    }
+  };
+
+  const toggleWeekday = (day: number) => {
+    setWeekdays((days) =>
+      days.includes(day) ? days.filter((d) => d !== day) : [...days, day].sort((a, b) => a - b),
+    );
+  };
+
+  return (
+    <SettingsCard id="scheduled-downloads" highlight={highlight}>
+      {schedules.length === 0 ? (
+        <p className="py-2 text-sm text-muted-foreground">{t('download.scheduleEmpty')}</p>
+      ) : (
+        schedules.map((schedule) => (
+          <div key={schedule.id} className="flex items-center justify-between gap-3 py-2">
+            <div className="min-w-0">
+              <p className="truncate text-sm" title={schedule.url}>
+                {schedule.title || schedule.url}
+              </p>
+              <p className="text-xs text-muted-foreground">
+                {describeScheduleRule(schedule.rule)}
+                {' · '}
+                {schedule.enabled && schedule.nextRunAt !== null
+                  ? t('download.scheduleNextRun', {
+                      time: new Date(schedule.nextRunAt).toLocaleString(i18n.language),
+                    })
+                  : schedule.enabled
+                    ? t('download.scheduleDone')
+                    : t('download.scheduleOff')}
+              </p>
+            </div>
+            <div className="flex shrink-0 items-center gap-1.5">
+              <Switch
+                checked={schedule.enabled}
+                onCheckedChange={(checked) => handleToggle(schedule, checked)}
+              />
+              <Button
+                variant="ghost"
+                size="icon"
+                className="h-8 w-8 text-muted-foreground hover:text-destructive"
+                onClick={() => handleRemove(schedule)}
+                title={t('download.scheduleRemove')}
+              >
+                <Trash2 className="h-4 w-4" />
+              </Button>
+            </div>
+          </div>
+        ))
+      )}
+
+      <div className="space-y-3 border-t border-border/50 pt-3">
+        <Input
+          value={url}
+          onChange={(e) => setUrl(e.target.value)}
+          placeholder={t('download.scheduleUrlPlaceholder')}
+          className="h-9 bg-background"
+        />
+        <div className="flex flex-wrap items-center gap-2">
+          <Select value={kind} onValueChange={(value) => setKind(value as RuleKind)}>
+            <SelectTrigger className="h-9 w-[160px] bg-background">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {RULE_KINDS.map((ruleKind) => (
+                <SelectItem key={ruleKind} value={ruleKind}>
+                  {t(`download.scheduleKind_${ruleKind}`)}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+
+          {kind === 'once' && (
+            <Input
+              type="datetime-local"
+              value={onceAt}
+              onChange={(e) => setOnceAt(e.target.value)}
+              className="h-9 w-[220px] bg-background"
+            />
+          )}
+          {kind === 'window' && (
+            <>
+              <Input
+                type="time"
+                value={windowStart}
+                onChange={(e) => setWindowStart(e.target.value)}
+                className="h-9 w-28 bg-background"
+              />
+              <span className="text-xs text-muted-foreground">–</span>
+              <Input
+                type="time"
+                value={windowEnd}
+                onChange={(e) => setWindowEnd(e.target.value)}
+                className="h-9 w-28 bg-background"
+              />
+            </>
+          )}
+          {kind === 'weekly' && (
+            <>
+              <div className="flex gap-1">
+                {WEEKDAYS.map((day) => (
+                  <button
+                    key={day}
+                    type="button"
+                    onClick={() => toggleWeekday(day)}
+                    className={cn(
+                      'h-8 w-8 rounded-lg text-xs font-medium transition-colors',
+                      weekdays.includes(day)
+                        ? 'bg-primary text-primary-foreground'
+                        : 'bg-muted text-muted-foreground hover:bg-muted/80',
+                    )}
+                  >
+                    {weekdayName(day, 'narrow')}
+                  </button>
+                ))}
+              </div>
+              <Input
+                type="time"
+                value={weeklyTime}
+                onChange={(e) => setWeeklyTime(e.target.value)}
+                className="h-9 w-28 bg-background"
+              />
+            </>
+          )}
+        </div>
+        <div className="flex items-center justify-between gap-3">
+          <div className="flex items-center gap-2">
+            <Switch checked={wholePlaylist} onCheckedChange={setWholePlaylist} />
+            <span className="text-xs text-muted-foreground">
+              {t('download.scheduleWholePlaylist')}
+            </span>
+          </div>
+          <Button
+            size="sm"
+            variant="outline"
+            className="gap-1.5"
+            onClick={handleAdd}
+            disabled={!rule || !url.trim()}
+          >
+            <Plus className="w-3.5 h-3.5" />
+            {t('download.scheduleAdd')}
+          </Button>
+        </div>
+        <p className="text-xs text-muted-foreground">{t('download.scheduleHint')}</p>
+      </div>
+
+      {error && <p className="break-all pt-2 text-xs text-destructive">{error}</p>}
+    </SettingsCard>
+  );
+}
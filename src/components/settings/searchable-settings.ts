@@ -122,6 +122,34 @@ export const SEARCHABLE_SETTINGS: SearchableSetting[] = [
     keywords: ['thumbnail', 'cover', 'art', 'image', 'post-processing'],
     section: 'download',
   },
+  {
+    id: 'split-chapters',
+    labelKey: 'download.splitChapters',
+    descriptionKey: 'download.splitChaptersDesc',
+    keywords: ['chapter', 'split', 'parts', 'lecture', 'mix', 'tracks', 'post-processing'],
+    section: 'download',
+  },
+  {
+    id: 'verify-downloads',
+    labelKey: 'download.verifyDownloads',
+    descriptionKey: 'download.verifyDownloadsDesc',
+    keywords: ['verify', 'check', 'ffprobe', 'corrupt', 'integrity', 'duration', 'post-processing'],
+    section: 'download',
+  },
+  {
+    id: 'storage-quotas',
+    labelKey: 'download.storageQuotas',
+    descriptionKey: 'download.storageQuotasDesc',
+    keywords: ['quota', 'disk', 'space', 'storage', 'limit', 'folder', 'size'],
+    section: 'download',
+  },
+  {
+    id: 'scheduled-downloads',
+    labelKey: 'download.scheduledDownloads',
+    descriptionKey: 'download.scheduledDownloadsDesc',
+    keywords: ['schedule', 'timer', 'night', 'window', 'weekly', 'recurring', 'later', 'cron'],
+    section: 'download',
+  },
   {
     id: 'plugins-manager',
     labelKey: 'plugins.title',
@@ -149,6 +177,20 @@ export const SEARCHABLE_SETTINGS: SearchableSetting[] = [
     keywords: ['live', 'stream', 'start', 'beginning', 'broadcast'],
     section: 'download',
   },
+  {
+    id: 'live-segment-minutes',
+    labelKey: 'download.liveSegmentMinutes',
+    descriptionKey: 'download.liveSegmentMinutesDesc',
+    keywords: ['live', 'segment', 'split', 'parts', 'long', 'duration'],
+    section: 'download',
+  },
+  {
+    id: 'live-recordings',
+    labelKey: 'download.liveRecordings',
+    descriptionKey: 'download.liveRecordingsDesc',
+    keywords: ['live', 'record', 'premiere', 'upcoming', 'stream', 'wait', 'dvr'],
+    section: 'download',
+  },
   {
     id: 'speed-limit',
     labelKey: 'download.speedLimit',
@@ -156,6 +198,20 @@ export const SEARCHABLE_SETTINGS: SearchableSetting[] = [
     keywords: ['speed', 'limit', 'bandwidth', 'rate', 'throttle', 'slow'],
     section: 'download',
   },
+  {
+    id: 'max-per-site',
+    labelKey: 'download.maxPerSite',
+    descriptionKey: 'download.maxPerSiteDesc',
+    keywords: ['site', 'per site', 'parallel', 'concurrent', 'simultaneous', 'rate limit', 'queue'],
+    section: 'download',
+  },
+  {
+    id: 'bandwidth-profiles',
+    labelKey: 'download.bandwidthProfiles',
+    descriptionKey: 'download.bandwidthProfilesDesc',
+    keywords: ['bandwidth', 'schedule', 'night', 'work', 'hours', 'time', 'speed', 'limit'],
+    section: 'download',
+  },
   {
     id: 'aria2-toggle',
     labelKey: 'download.aria2Toggle',
@@ -171,6 +227,34 @@ export const SEARCHABLE_SETTINGS: SearchableSetting[] = [
     ],
     section: 'download',
   },
+  {
+    id: 'output-template',
+    labelKey: 'download.outputTemplateField',
+    descriptionKey: 'download.outputTemplateFieldDesc',
+    keywords: ['output', 'template', 'filename', 'folder', 'naming', 'path', 'rename'],
+    section: 'download',
+  },
+  {
+    id: 'download-profile-default',
+    labelKey: 'download.downloadProfileDefault',
+    descriptionKey: 'download.downloadProfileDefaultDesc',
+    keywords: ['profile', 'preset', 'default', 'download'],
+    section: 'download',
+  },
+  {
+    id: 'download-profile-save',
+    labelKey: 'download.downloadProfileSave',
+    descriptionKey: 'download.downloadProfileSaveDesc',
+    keywords: ['profile', 'preset', 'save', 'settings'],
+    section: 'download',
+  },
+  {
+    id: 'format-rules',
+    labelKey: 'download.formatRulesEnable',
+    descriptionKey: 'download.formatRulesEnableDesc',
+    keywords: ['format', 'codec', 'av1', 'vp9', 'h264', 'hdr', 'fps', 'language', 'size', 'rules'],
+    section: 'download',
+  },
   {
     id: 'auto-retry-toggle',
     labelKey: 'download.autoRetryEnable',
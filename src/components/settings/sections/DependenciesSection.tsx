@@ -30,7 +30,7 @@ import { Button } from '@/components/ui/button';
 import { Switch } from '@/components/ui/switch';
 import { useDependencies } from '@/contexts/DependenciesContext';
 import { useDownload } from '@/contexts/DownloadContext';
-import type { DependencySource, YtdlpChannel } from '@/lib/types';
+import type { DependencySource, GalleryDlChannel, YtdlpChannel } from '@/lib/types';
 import { cn } from '@/lib/utils';
 import { SettingsCard, SettingsSection } from '../SettingsSection';
 
@@ -41,7 +41,7 @@ interface DependenciesSectionProps {
 export function DependenciesSection({ highlightId }: DependenciesSectionProps) {
   const { t } = useTranslation('settings');
   const [pendingSourceChange, setPendingSourceChange] = useState<{
-    tool: 'ytdlp' | 'ffmpeg';
+    tool: 'ytdlp' | 'ffmpeg' | 'gallerydl';
     source: Exclude<DependencySource, 'auto'>;
   } | null>(null);
   const { settings, updateUseActualPlayerJs } = useDownload();
@@ -100,7 +100,15 @@ export function DependenciesSection({ highlightId }: DependenciesSectionProps) {
     galleryDlStatus,
     galleryDlLoading,
     galleryDlError,
+    galleryDlDownloading,
+    galleryDlSuccess,
+    galleryDlUpdateInfo,
+    galleryDlCheckingUpdate,
     checkGalleryDl,
+    setGalleryDlSource,
+    setGalleryDlChannel,
+    checkGalleryDlUpdate,
+    downloadGalleryDl,
   } = useDependencies();
 
   // Compare versions with normalization to avoid false positives (e.g. "v2026.02.04")
@@ -160,6 +168,15 @@ export function DependenciesSection({ highlightId }: DependenciesSectionProps) {
     await setFfmpegSource(nextSource);
   };
 
+  const handleGalleryDlSourceChange = async (nextSource: Exclude<DependencySource, 'auto'>) => {
+    if (nextSource === galleryDlSourceUi) return;
+    if (nextSource === 'system') {
+      setPendingSourceChange({ tool: 'gallerydl', source: nextSource });
+      return;
+    }
+    await setGalleryDlSource(nextSource);
+  };
+
   const handleConfirmSourceChange = async () => {
     if (!pendingSourceChange) return;
     const { tool, source } = pendingSourceChange;
@@ -167,6 +184,8 @@ export function DependenciesSection({ highlightId }: DependenciesSectionProps) {
 
     if (tool === 'ytdlp') {
       await setYtdlpSource(source);
+    } else if (tool === 'gallerydl') {
+      await setGalleryDlSource(source);
     } else {
       await setFfmpegSource(source);
     }
@@ -195,6 +214,14 @@ export function DependenciesSection({ highlightId }: DependenciesSectionProps) {
       ? 'system'
       : 'app';
   const isFfmpegSystemSource = ffmpegSourceUi === 'system';
+  // gallery-dl `auto` prefers a system binary, so display the resolved source like FFmpeg.
+  const galleryDlSourceUi: Exclude<DependencySource, 'auto'> =
+    galleryDlStatus?.source === 'system' ||
+    (galleryDlStatus?.source === 'auto' && galleryDlStatus.is_system)
+      ? 'system'
+      : 'app';
+  const isGalleryDlSystemSource = galleryDlSourceUi === 'system';
+  const galleryDlChannel: GalleryDlChannel = galleryDlStatus?.channel ?? 'stable';
 
   return (
     <>
@@ -210,7 +237,9 @@ export function DependenciesSection({ highlightId }: DependenciesSectionProps) {
             <AlertDialogDescription>
               {pendingSourceChange?.tool === 'ffmpeg'
                 ? t('dependencies.confirmSwitchSystemFfmpeg')
-                : t('dependencies.confirmSwitchSystemYtdlp')}
+                : pendingSourceChange?.tool === 'gallerydl'
+                  ? t('dependencies.confirmSwitchSystemGallerydl')
+                  : t('dependencies.confirmSwitchSystemYtdlp')}
             </AlertDialogDescription>
           </AlertDialogHeader>
           <AlertDialogFooter>
@@ -781,41 +810,141 @@ export function DependenciesSection({ highlightId }: DependenciesSectionProps) {
                     )}
                   </div>
                   <p className="text-xs text-muted-foreground mt-0.5">
-                    {galleryDlLoading ? (
+                    {galleryDlDownloading ? (
+                      <span className="flex items-center gap-1 text-primary">
+                        <Loader2 className="w-3 h-3 animate-spin" />
+                        {galleryDlStatus?.installed
+                          ? t('dependencies.updating')
+                          : t('dependencies.installing')}
+                      </span>
+                    ) : galleryDlLoading || galleryDlCheckingUpdate ? (
                       <span className="flex items-center gap-1 text-muted-foreground">
                         <Loader2 className="w-3 h-3 animate-spin" />
                         {t('dependencies.checkingUpdates')}
                       </span>
+                    ) : galleryDlSuccess ? (
+                      <span className="text-emerald-500">{t('dependencies.installed')}</span>
                     ) : galleryDlError ? (
                       <span className="text-destructive">{galleryDlError}</span>
-                    ) : galleryDlStatus?.installed ? (
-                      t('dependencies.systemGallerydl')
-                    ) : (
+                    ) : !galleryDlStatus?.installed ? (
                       <span className="text-amber-500">
-                        {t('dependencies.systemGallerydlNotFound')}
+                        {isGalleryDlSystemSource
+                          ? t('dependencies.systemGallerydlNotFound')
+                          : t('dependencies.appGallerydlNotInstalled')}
                       </span>
+                    ) : isGalleryDlSystemSource ? (
+                      t('dependencies.systemGallerydl')
+                    ) : galleryDlUpdateInfo?.update_available ? (
+                      <span className="text-primary">
+                        {t('dependencies.available', {
+                          version: galleryDlUpdateInfo.latest_version,
+                        })}
+                      </span>
+                    ) : galleryDlUpdateInfo ? (
+                      <span className="text-emerald-500">{t('dependencies.upToDate')}</span>
+                    ) : (
+                      t('dependencies.galleryCollectionsEngine')
                     )}
                   </p>
                 </div>
               </div>
-              <Button
-                variant="ghost"
-                size="icon"
-                onClick={() => void checkGalleryDl()}
-                disabled={galleryDlLoading}
-                title={t('dependencies.checkForUpdates')}
-              >
-                {galleryDlLoading ? (
-                  <Loader2 className="w-4 h-4 animate-spin" />
-                ) : (
-                  <RefreshCw className="w-4 h-4" />
-                )}
-              </Button>
+              <div className="flex items-center gap-2">
+                {!isGalleryDlSystemSource &&
+                  !galleryDlLoading &&
+                  (!galleryDlStatus?.installed || galleryDlUpdateInfo?.update_available) && (
+                    <Button
+                      size="sm"
+                      onClick={() => void downloadGalleryDl(galleryDlChannel)}
+                      disabled={galleryDlDownloading}
+                    >
+                      {galleryDlDownloading ? (
+                        <Loader2 className="w-4 h-4 animate-spin" />
+                      ) : galleryDlStatus?.installed ? (
+                        t('dependencies.update')
+                      ) : (
+                        t('dependencies.install')
+                      )}
+                    </Button>
+                  )}
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  onClick={() =>
+                    void (isGalleryDlSystemSource
+                      ? checkGalleryDl()
+                      : checkGalleryDlUpdate(galleryDlChannel))
+                  }
+                  disabled={galleryDlLoading || galleryDlDownloading || galleryDlCheckingUpdate}
+                  title={t('dependencies.checkForUpdates')}
+                >
+                  {galleryDlLoading || galleryDlCheckingUpdate ? (
+                    <Loader2 className="w-4 h-4 animate-spin" />
+                  ) : (
+                    <RefreshCw className="w-4 h-4" />
+                  )}
+                </Button>
+              </div>
             </div>
 
-            <p className="mt-3 text-xs text-muted-foreground">
-              {t('dependencies.galleryCollectionsEngine')}
-            </p>
+            <div className="mt-3 pt-3 border-t border-border/50 space-y-2">
+              <p className="text-[11px] font-medium text-muted-foreground">
+                {t('dependencies.source')}
+              </p>
+              <div className="grid grid-cols-2 gap-1">
+                {sourceOptions.map((option) => {
+                  const isActive = galleryDlSourceUi === option.value;
+                  return (
+                    <button
+                      key={`gallerydl-source-${option.value}`}
+                      type="button"
+                      onClick={() => handleGalleryDlSourceChange(option.value)}
+                      disabled={galleryDlDownloading || galleryDlCheckingUpdate}
+                      className={cn(
+                        'rounded-md px-2 py-1.5 text-xs transition-all border border-dashed',
+                        isActive
+                          ? 'border-primary/50 bg-primary/10 text-primary'
+                          : 'border-border text-muted-foreground hover:text-foreground hover:bg-muted/50',
+                      )}
+                    >
+                      {option.label}
+                    </button>
+                  );
+                })}
+              </div>
+            </div>
+
+            {/* Channel selector */}
+            {!isGalleryDlSystemSource && (
+              <div className="mt-3 pt-3 border-t border-border/50 space-y-2">
+                <p className="text-[11px] font-medium text-muted-foreground">
+                  {t('dependencies.channel')}
+                </p>
+                <div className="grid grid-cols-2 gap-1">
+                  {(['stable', 'nightly'] as GalleryDlChannel[]).map((ch) => {
+                    const isActive = galleryDlChannel === ch;
+                    const channelName = ch === 'stable' ? 'Stable' : 'Nightly';
+                    return (
+                      <button
+                        key={`gallerydl-channel-${ch}`}
+                        type="button"
+                        onClick={() => void setGalleryDlChannel(ch)}
+                        disabled={galleryDlDownloading || galleryDlCheckingUpdate || isActive}
+                        title={t(`dependencies.gallerydlChannel${channelName}Desc`)}
+                        className={cn(
+                          'rounded-md px-2 py-1.5 text-xs transition-all border border-dashed',
+                          isActive
+                            ? 'border-primary/50 bg-primary/10 text-primary'
+                            : 'border-border text-muted-foreground hover:text-foreground hover:bg-muted/50',
+                        )}
+                      >
+                        {t(`dependencies.channel${channelName}`)}
+                      </button>
+                    );
+                  })}
+                </div>
+              </div>
+            )}
+
 
             <a
               href="https://github.com/mikf/gallery-dl"
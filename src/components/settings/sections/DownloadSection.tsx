@@ -1,5 +1,25 @@
-import { Database, Film, Gauge, Radio, Rocket, RotateCcw, ShieldCheck } from 'lucide-react';
+import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
+import {
+  ArrowUp,
+  CalendarClock,
+  Database,
+  Film,
+  FolderOpen,
+  FolderTree,
+  Gauge,
+  HardDrive,
+  Layers,
+  ListFilter,
+  Radio,
+  Rocket,
+  RotateCcw,
+  ShieldCheck,
+  Trash2,
+} from 'lucide-react';
+import { useCallback, useEffect, useState } from 'react';
 import { useTranslation } from 'react-i18next';
+import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import {
   Select,
@@ -10,36 +30,233 @@ import {
 } from '@/components/ui/select';
 import { Switch } from '@/components/ui/switch';
 import { useDownload } from '@/contexts/DownloadContext';
+import { extractBackendError } from '@/lib/backend-error';
+import { deleteDownloadProfile, listDownloadProfiles } from '@/lib/download-profiles';
 import { clampAutoRetryDelaySeconds, clampAutoRetryMaxAttempts } from '@/lib/download-retry';
 import {
+  DEFAULT_FORMAT_PREFERENCES,
+  DYNAMIC_RANGE_PREFERENCES,
+  explainFormatPreferences,
+  formatReasonText,
+  VIDEO_CODEC_FAMILIES,
+} from '@/lib/format-preferences';
+import { BYTES_PER_GB } from '@/lib/storage-quotas';
+import {
+  type DownloadProfile,
+  type DynamicRangePreference,
+  type FormatPlan,
+  type FormatPreferences,
   SPONSORBLOCK_CATEGORIES,
   type SponsorBlockAction,
   type SponsorBlockCategory,
   type SponsorBlockMode,
+  type VideoCodecFamily,
 } from '@/lib/types';
 import { cn } from '@/lib/utils';
+import { BandwidthProfilesCard } from '../BandwidthProfilesCard';
+import { LiveRecordingsCard } from '../LiveRecordingsCard';
+import { ScheduledDownloadsCard } from '../ScheduledDownloadsCard';
 import { SettingsCard, SettingsDivider, SettingsRow, SettingsSection } from '../SettingsSection';
 
 interface DownloadSectionProps {
   highlightId?: string | null;
 }
 
+interface OutputTemplatePreview {
+  template: string;
+  relative_path: string;
+  filepath: string | null;
+}
+
+// Radix Select does not accept an empty value, so "no profile" uses a sentinel
+const NO_PROFILE_VALUE = '__none__';
+
+// Sample video used to preview output templates in settings
+const SAMPLE_VIDEO_INFO = {
+  id: 'dQw4w9WgXcQ',
+  title: 'Never Gonna Give You Up',
+  uploader: 'Rick Astley',
+  channel: 'Rick Astley',
+  upload_date: '20091025',
+  duration: 213,
+  extractor: 'youtube',
+  extractor_key: 'Youtube',
+  is_playlist: false,
+};
+
+// Part lengths offered for live recordings, in minutes (0 = keep one file)
+const LIVE_SEGMENT_MINUTES = [0, 15, 30, 60, 120, 240];
+
+function parsePositive(value: string): number | null {
+  const parsed = Math.floor(Number(value));
+  return Number.isFinite(parsed) && parsed > 0 ? parsed : null;
+}
+
 export function DownloadSection({ highlightId }: DownloadSectionProps) {
   const { t } = useTranslation('settings');
   const {
     settings,
     updateEmbedMetadata,
     updateEmbedThumbnail,
+    updateSplitChapters,
+    updateVerifyDownloads,
     updateLiveFromStart,
     updateSpeedLimit,
     updateUseAria2,
     updateAria2Args,
+    updateOutputTemplate,
     updateAutoRetry,
     updateSettings,
     updateSponsorBlock,
     updateSponsorBlockMode,
     updateSponsorBlockCategory,
+    updateDownloadProfile,
+    updateFormatPreferences,
+    saveCurrentAsDownloadProfile,
+    updateFolderQuotas,
   } = useDownload();
+  const [templatePreview, setTemplatePreview] = useState<string | null>(null);
+  const [templateError, setTemplateError] = useState<string | null>(null);
+  const [profiles, setProfiles] = useState<DownloadProfile[]>([]);
+  const [profileName, setProfileName] = useState('');
+  const [profileError, setProfileError] = useState<string | null>(null);
+  const [formatPlan, setFormatPlan] = useState<FormatPlan | null>(null);
+  const [formatPlanError, setFormatPlanError] = useState<string | null>(null);
+  const formatPreferences = settings.formatPreferences;
+
+  const patchFormatPreferences = (patch: Partial<FormatPreferences>) => {
+    updateFormatPreferences({ ...(formatPreferences ?? DEFAULT_FORMAT_PREFERENCES), ...patch });
+  };
+
+  const toggleCodec = (list: 'preferredCodecs' | 'forbiddenCodecs', codec: VideoCodecFamily) => {
+    if (!formatPreferences) return;
+    const current = formatPreferences[list];
+    const next = current.includes(codec)
+      ? current.filter((item) => item !== codec)
+      : [...current, codec];
+    // A codec cannot be preferred and forbidden at once
+    const without = (codecs: VideoCodecFamily[]) => codecs.filter((item) => item !== codec);
+    patchFormatPreferences(
+      list === 'preferredCodecs'
+        ? { preferredCodecs: next, forbiddenCodecs: without(formatPreferences.forbiddenCodecs) }
+        : { forbiddenCodecs: next, preferredCodecs: without(formatPreferences.preferredCodecs) },
+    );
+  };
+
+  const moveFallbackUp = (index: number) => {
+    if (!formatPreferences || index === 0) return;
+    const order = [...formatPreferences.fallbackOrder];
+    [order[index - 1], order[index]] = [order[index], order[index - 1]];
+    patchFormatPreferences({ fallbackOrder: order });
+  };
+
+  useEffect(() => {
+    if (!formatPreferences) {
+      setFormatPlan(null);
+      setFormatPlanError(null);
+      return;
+    }
+    let cancelled = false;
+    explainFormatPreferences(formatPreferences, settings.quality, settings.format)
+      .then((plan) => {
+        if (cancelled) return;
+        setFormatPlan(plan);
+        setFormatPlanError(null);
+      })
+      .catch((error) => {
+        if (cancelled) return;
+        setFormatPlan(null);
+        setFormatPlanError(extractBackendError(error).message);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [formatPreferences, settings.quality, settings.format]);
+
+  const handleAddFolderQuota = async () => {
+    try {
+      const folder = await open({
+        directory: true,
+        multiple: false,
+        title: t('download.storageQuotaAdd'),
+      });
+      if (typeof folder !== 'string') return;
+      if (settings.folderQuotas.some((quota) => quota.path === folder)) return;
+      updateFolderQuotas([...settings.folderQuotas, { path: folder, maxBytes: 50 * BYTES_PER_GB }]);
+    } catch (error) {
+      console.error('Failed to select quota folder:', error);
+    }
+  };
+
+  const updateFolderQuotaLimit = (path: string, gigabytes: number | null) => {
+    if (!gigabytes) return;
+    updateFolderQuotas(
+      settings.folderQuotas.map((quota) =>
+        quota.path === path ? { ...quota, maxBytes: gigabytes * BYTES_PER_GB } : quota,
+      ),
+    );
+  };
+
+  const refreshProfiles = useCallback(async () => {
+    try {
+      setProfiles(await listDownloadProfiles());
+    } catch (error) {
+      setProfileError(extractBackendError(error).message);
+    }
+  }, []);
+
+  useEffect(() => {
+    refreshProfiles();
+  }, [refreshProfiles]);
+
+  const handleSaveProfile = async () => {
+    try {
+      const profile = await saveCurrentAsDownloadProfile(profileName);
+      setProfileName('');
+      setProfileError(null);
+      await refreshProfiles();
+      if (!settings.downloadProfile) {
+        updateDownloadProfile(profile.name);
+      }
+    } catch (error) {
+      setProfileError(extractBackendError(error).message);
+    }
+  };
+
+  const handleDeleteProfile = async (name: string) => {
+    try {
+      await deleteDownloadProfile(name);
+      setProfileError(null);
+      if (settings.downloadProfile.toLowerCase() === name.toLowerCase()) {
+        updateDownloadProfile('');
+      }
+      await refreshProfiles();
+    } catch (error) {
+      setProfileError(extractBackendError(error).message);
+    }
+  };
+
+  useEffect(() => {
+    let cancelled = false;
+    invoke<OutputTemplatePreview>('preview_output_template', {
+      template: settings.outputTemplate,
+      info: SAMPLE_VIDEO_INFO,
+      ext: settings.format,
+    })
+      .then((preview) => {
+        if (cancelled) return;
+        setTemplatePreview(preview.relative_path);
+        setTemplateError(null);
+      })
+      .catch((error) => {
+        if (cancelled) return;
+        setTemplatePreview(null);
+        setTemplateError(extractBackendError(error).message);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [settings.outputTemplate, settings.format]);
 
   return (
     <div className="space-y-8">
@@ -91,6 +308,24 @@ export function DownloadSection({ highlightId }: DownloadSectionProps) {
           >
             <Switch checked={settings.embedThumbnail} onCheckedChange={updateEmbedThumbnail} />
           </SettingsRow>
+
+          <SettingsRow
+            id="split-chapters"
+            label={t('download.splitChapters')}
+            description={t('download.splitChaptersDesc')}
+            highlight={highlightId === 'split-chapters'}
+          >
+            <Switch checked={settings.splitChapters} onCheckedChange={updateSplitChapters} />
+          </SettingsRow>
+
+          <SettingsRow
+            id="verify-downloads"
+            label={t('download.verifyDownloads')}
+            description={t('download.verifyDownloadsDesc')}
+            highlight={highlightId === 'verify-downloads'}
+          >
+            <Switch checked={settings.verifyDownloads} onCheckedChange={updateVerifyDownloads} />
+          </SettingsRow>
         </SettingsCard>
       </SettingsSection>
 
@@ -205,7 +440,32 @@ export function DownloadSection({ highlightId }: DownloadSectionProps) {
           >
             <Switch checked={settings.liveFromStart} onCheckedChange={updateLiveFromStart} />
           </SettingsRow>
+          <SettingsRow
+            id="live-segment-minutes"
+            label={t('download.liveSegmentMinutes')}
+            description={t('download.liveSegmentMinutesDesc')}
+            highlight={highlightId === 'live-segment-minutes'}
+          >
+            <Select
+              value={String(settings.liveSegmentMinutes)}
+              onValueChange={(value) => updateSettings({ liveSegmentMinutes: Number(value) })}
+            >
+              <SelectTrigger className="h-9 w-[140px] bg-background">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {LIVE_SEGMENT_MINUTES.map((minutes) => (
+                  <SelectItem key={minutes} value={String(minutes)}>
+                    {minutes === 0
+                      ? t('download.liveSegmentOff')
+                      : t('download.liveSegmentEvery', { count: minutes })}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </SettingsRow>
         </SettingsCard>
+        <LiveRecordingsCard highlight={highlightId === 'live-recordings'} />
       </SettingsSection>
 
       <SettingsDivider />
@@ -290,7 +550,31 @@ export function DownloadSection({ highlightId }: DownloadSectionProps) {
               )}
             </div>
           </SettingsRow>
+
+          <SettingsRow
+            id="max-per-site"
+            label={t('download.maxPerSite')}
+            description={t('download.maxPerSiteDesc')}
+            highlight={highlightId === 'max-per-site'}
+          >
+            <Select
+              value={String(settings.maxPerSite)}
+              onValueChange={(value) => updateSettings({ maxPerSite: Number(value) })}
+            >
+              <SelectTrigger className="h-9 w-[85px] bg-background">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {[1, 2, 3, 4, 5].map((n) => (
+                  <SelectItem key={n} value={String(n)}>
+                    {n}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </SettingsRow>
         </SettingsCard>
+        <BandwidthProfilesCard highlight={highlightId === 'bandwidth-profiles'} />
       </SettingsSection>
 
       <SettingsDivider />
@@ -371,6 +655,69 @@ export function DownloadSection({ highlightId }: DownloadSectionProps) {
 
       <SettingsDivider />
 
+      {/* Storage Quotas */}
+      <SettingsSection
+        title={t('download.storageQuotas')}
+        description={t('download.storageQuotasDesc')}
+        icon={<HardDrive className="w-5 h-5 text-white" />}
+        iconClassName="bg-gradient-to-br from-slate-500 to-zinc-600 shadow-slate-500/20"
+      >
+        <SettingsCard id="storage-quotas" highlight={highlightId === 'storage-quotas'}>
+          {settings.folderQuotas.map((quota) => (
+            <div key={quota.path} className="flex items-center justify-between gap-3 py-2">
+              <span className="truncate font-mono text-sm" title={quota.path}>
+                {quota.path}
+              </span>
+              <div className="flex shrink-0 items-center gap-1.5">
+                <Input
+                  type="number"
+                  min={1}
+                  value={Math.max(1, Math.round(quota.maxBytes / BYTES_PER_GB))}
+                  onChange={(e) =>
+                    updateFolderQuotaLimit(quota.path, parsePositive(e.target.value))
+                  }
+                  className="h-9 w-24 bg-background text-center"
+                />
+                <span className="text-xs text-muted-foreground">GB</span>
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  className="h-8 w-8 text-muted-foreground hover:text-destructive"
+                  onClick={() =>
+                    updateFolderQuotas(settings.folderQuotas.filter((q) => q.path !== quota.path))
+                  }
+                  title={t('download.storageQuotaRemove')}
+                >
+                  <Trash2 className="h-4 w-4" />
+                </Button>
+              </div>
+            </div>
+          ))}
+
+          <div className="flex items-center justify-between gap-3 pt-2">
+            <p className="text-xs text-muted-foreground">{t('download.storageQuotaHint')}</p>
+            <Button size="sm" variant="outline" className="gap-1.5" onClick={handleAddFolderQuota}>
+              <FolderOpen className="w-3.5 h-3.5" />
+              {t('download.storageQuotaAdd')}
+            </Button>
+          </div>
+        </SettingsCard>
+      </SettingsSection>
+
+      <SettingsDivider />
+
+      {/* Scheduled Downloads */}
+      <SettingsSection
+        title={t('download.scheduledDownloads')}
+        description={t('download.scheduledDownloadsDesc')}
+        icon={<CalendarClock className="w-5 h-5 text-white" />}
+        iconClassName="bg-gradient-to-br from-sky-500 to-indigo-600 shadow-sky-500/20"
+      >
+        <ScheduledDownloadsCard highlight={highlightId === 'scheduled-downloads'} />
+      </SettingsSection>
+
+      <SettingsDivider />
+
       {/* Aria2 Integration */}
       <SettingsSection
         title={t('download.aria2')}
@@ -405,6 +752,286 @@ export function DownloadSection({ highlightId }: DownloadSectionProps) {
           )}
         </SettingsCard>
       </SettingsSection>
+
+      <SettingsDivider />
+
+      {/* Output Naming */}
+      <SettingsSection
+        title={t('download.outputTemplate')}
+        description={t('download.outputTemplateDesc')}
+        icon={<FolderTree className="w-5 h-5 text-white" />}
+        iconClassName="bg-gradient-to-br from-amber-500 to-orange-600 shadow-amber-500/20"
+      >
+        <SettingsCard>
+          <SettingsRow
+            id="output-template"
+            label={t('download.outputTemplateField')}
+            description={t('download.outputTemplateFieldDesc')}
+            highlight={highlightId === 'output-template'}
+          >
+            <Input
+              value={settings.outputTemplate}
+              onChange={(e) => updateOutputTemplate(e.target.value)}
+              placeholder="%(title)s.%(ext)s"
+              className="h-9 w-full bg-background font-mono md:w-[340px]"
+            />
+          </SettingsRow>
+          <p
+            className={cn(
+              'break-all pt-2 text-xs',
+              templateError ? 'text-destructive' : 'text-muted-foreground',
+            )}
+          >
+            {templateError ??
+              (templatePreview
+                ? t('download.outputTemplatePreview', { path: templatePreview })
+                : '')}
+          </p>
+        </SettingsCard>
+      </SettingsSection>
+
+      <SettingsDivider />
+
+      {/* Download Profiles */}
+      <SettingsSection
+        title={t('download.downloadProfiles')}
+        description={t('download.downloadProfilesDesc')}
+        icon={<Layers className="w-5 h-5 text-white" />}
+        iconClassName="bg-gradient-to-br from-violet-500 to-purple-600 shadow-violet-500/20"
+      >
+        <SettingsCard>
+          <SettingsRow
+            id="download-profile-default"
+            label={t('download.downloadProfileDefault')}
+            description={t('download.downloadProfileDefaultDesc')}
+            highlight={highlightId === 'download-profile-default'}
+          >
+            <Select
+              value={settings.downloadProfile || NO_PROFILE_VALUE}
+              onValueChange={(value) =>
+                updateDownloadProfile(value === NO_PROFILE_VALUE ? '' : value)
+              }
+            >
+              <SelectTrigger className="h-9 w-full bg-background md:w-[200px]">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value={NO_PROFILE_VALUE}>
+                  {t('download.downloadProfileNone')}
+                </SelectItem>
+                {profiles.map((profile) => (
+                  <SelectItem key={profile.name} value={profile.name}>
+                    {profile.name}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </SettingsRow>
+
+          <SettingsRow
+            id="download-profile-save"
+            label={t('download.downloadProfileSave')}
+            description={t('download.downloadProfileSaveDesc')}
+            highlight={highlightId === 'download-profile-save'}
+          >
+            <div className="flex w-full items-center gap-2 md:w-auto">
+              <Input
+                value={profileName}
+                onChange={(e) => setProfileName(e.target.value)}
+                placeholder={t('download.downloadProfileNamePlaceholder')}
+                className="h-9 w-full bg-background md:w-[200px]"
+              />
+              <Button
+                size="sm"
+                className="h-9"
+                disabled={!profileName.trim()}
+                onClick={handleSaveProfile}
+              >
+                {t('download.downloadProfileSaveButton')}
+              </Button>
+            </div>
+          </SettingsRow>
+
+          {profiles.map((profile) => (
+            <div key={profile.name} className="flex items-center justify-between gap-3 py-2">
+              <span className="truncate font-mono text-sm">{profile.name}</span>
+              <Button
+                variant="ghost"
+                size="icon"
+                className="h-8 w-8 text-muted-foreground hover:text-destructive"
+                onClick={() => handleDeleteProfile(profile.name)}
+                title={t('download.downloadProfileDelete')}
+              >
+                <Trash2 className="h-4 w-4" />
+              </Button>
+            </div>
+          ))}
+
+          {profileError && (
+            <p className="break-all pt-2 text-xs text-destructive">{profileError}</p>
+          )}
+        </SettingsCard>
+      </SettingsSection>
+
+      <SettingsDivider />
+
+      {/* Format Rules */}
+      <SettingsSection
+        title={t('download.formatRules')}
+        description={t('download.formatRulesDesc')}
+        icon={<ListFilter className="w-5 h-5 text-white" />}
+        iconClassName="bg-gradient-to-br from-fuchsia-500 to-pink-600 shadow-fuchsia-500/20"
+      >
+        <SettingsCard>
+          <SettingsRow
+            id="format-rules"
+            label={t('download.formatRulesEnable')}
+            description={t('download.formatRulesEnableDesc')}
+            highlight={highlightId === 'format-rules'}
+          >
+            <Switch
+              checked={formatPreferences !== null}
+              onCheckedChange={(enabled) =>
+                updateFormatPreferences(enabled ? { ...DEFAULT_FORMAT_PREFERENCES } : null)
+              }
+            />
+          </SettingsRow>
+
+          {formatPreferences && (
+            <>
+              {(['preferredCodecs', 'forbiddenCodecs'] as const).map((list) => (
+                <SettingsRow
+                  key={list}
+                  id={`format-rules-${list}`}
+                  label={t(`download.formatRules_${list}`)}
+                  description={t(`download.formatRules_${list}Desc`)}
+                >
+                  <div className="flex flex-wrap gap-1.5">
+                    {VIDEO_CODEC_FAMILIES.map((codec) => {
+                      const position = formatPreferences[list].indexOf(codec);
+                      return (
+                        <Button
+                          key={codec}
+                          size="sm"
+                          variant={position >= 0 ? 'default' : 'outline'}
+                          className="h-8 font-mono text-xs"
+                          onClick={() => toggleCodec(list, codec)}
+                        >
+                          {list === 'preferredCodecs' && position >= 0
+                            ? `${position + 1}. ${codec}`
+                            : codec}
+                        </Button>
+                      );
+                    })}
+                  </div>
+                </SettingsRow>
+              ))}
+
+              <SettingsRow
+                id="format-rules-dynamic-range"
+                label={t('download.formatRulesDynamicRange')}
+                description={t('download.formatRulesDynamicRangeDesc')}
+              >
+                <Select
+                  value={formatPreferences.dynamicRange}
+                  onValueChange={(value) =>
+                    patchFormatPreferences({ dynamicRange: value as DynamicRangePreference })
+                  }
+                >
+                  <SelectTrigger className="h-9 w-full bg-background md:w-[200px]">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {DYNAMIC_RANGE_PREFERENCES.map((range) => (
+                      <SelectItem key={range} value={range}>
+                        {t(`formatRules.range_${range}`)}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+              </SettingsRow>
+
+              <SettingsRow
+                id="format-rules-limits"
+                label={t('download.formatRulesLimits')}
+                description={t('download.formatRulesLimitsDesc')}
+              >
+                <div className="flex w-full flex-wrap items-center gap-2 md:justify-end">
+                  <Input
+                    type="number"
+                    min={1}
+                    value={formatPreferences.maxFps ?? ''}
+                    onChange={(e) =>
+                      patchFormatPreferences({ maxFps: parsePositive(e.target.value) })
+                    }
+                    placeholder={t('formatRules.constraint_maxFps')}
+                    className="h-9 w-24 bg-background text-center"
+                  />
+                  <Input
+                    value={formatPreferences.audioLanguage ?? ''}
+                    onChange={(e) =>
+                      patchFormatPreferences({ audioLanguage: e.target.value.trim() || null })
+                    }
+                    placeholder={t('formatRules.constraint_audioLanguage')}
+                    className="h-9 w-28 bg-background text-center"
+                  />
+                  <Input
+                    type="number"
+                    min={1}
+                    value={formatPreferences.maxFilesizeMb ?? ''}
+                    onChange={(e) =>
+                      patchFormatPreferences({ maxFilesizeMb: parsePositive(e.target.value) })
+                    }
+                    placeholder={t('formatRules.constraint_maxFilesize')}
+                    className="h-9 w-28 bg-background text-center"
+                  />
+                </div>
+              </SettingsRow>
+
+              <SettingsRow
+                id="format-rules-fallback"
+                label={t('download.formatRulesFallback')}
+                description={t('download.formatRulesFallbackDesc')}
+              >
+                <div className="flex flex-wrap items-center gap-1.5">
+                  {formatPreferences.fallbackOrder.map((constraint, index) => (
+                    <Button
+                      key={constraint}
+                      size="sm"
+                      variant="outline"
+                      className="h-8 gap-1 text-xs"
+                      disabled={index === 0}
+                      onClick={() => moveFallbackUp(index)}
+                      title={t('download.formatRulesMoveEarlier')}
+                    >
+                      {index > 0 && <ArrowUp className="h-3 w-3" />}
+                      {`${index + 1}. ${t(`formatRules.constraint_${constraint}`)}`}
+                    </Button>
+                  ))}
+                </div>
+              </SettingsRow>
+
+              {formatPlanError ? (
+                <p className="break-all pt-2 text-xs text-destructive">{formatPlanError}</p>
+              ) : (
+                formatPlan && (
+                  <div className="space-y-1 pt-2 text-xs text-muted-foreground">
+                    <p className="break-all font-mono">
+                      -f {formatPlan.format}
+                      {formatPlan.sort ? ` -S ${formatPlan.sort}` : ''}
+                    </p>
+                    <ul className="list-disc space-y-0.5 pl-4">
+                      {formatPlan.reasons.map((reason, index) => (
+                        <li key={`${reason.key}-${index}`}>{formatReasonText(reason)}</li>
+                      ))}
+                    </ul>
+                  </div>
+                )
+              )}
+            </>
+          )}
+        </SettingsCard>
+      </SettingsSection>
     </div>
   );
 }
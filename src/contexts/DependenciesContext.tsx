@@ -5,6 +5,8 @@ import { createContext, type ReactNode, useCallback, useContext, useEffect, useS
 import { localizeUnknownError } from '@/lib/backend-error';
 import type {
   DependencySource,
+  GalleryDlChannel,
+  GalleryDlUpdateInfo,
   YtdlpAllVersions,
   YtdlpChannel,
   YtdlpChannelUpdateInfo,
@@ -37,6 +39,8 @@ export interface GalleryDlStatus {
   version: string | null;
   binary_path: string | null;
   is_system: boolean;
+  source: DependencySource;
+  channel: GalleryDlChannel;
 }
 
 export interface FfmpegUpdateInfo {
@@ -132,7 +136,15 @@ interface DependenciesContextType {
   galleryDlStatus: GalleryDlStatus | null;
   galleryDlLoading: boolean;
   galleryDlError: string | null;
+  galleryDlDownloading: boolean;
+  galleryDlSuccess: boolean;
+  galleryDlUpdateInfo: GalleryDlUpdateInfo | null;
+  galleryDlCheckingUpdate: boolean;
   checkGalleryDl: () => Promise<GalleryDlStatus | null>;
+  setGalleryDlSource: (source: DependencySource) => Promise<void>;
+  setGalleryDlChannel: (channel: GalleryDlChannel) => Promise<void>;
+  checkGalleryDlUpdate: (channel: GalleryDlChannel) => Promise<void>;
+  downloadGalleryDl: (channel: GalleryDlChannel) => Promise<void>;
 }
 
 const DependenciesContext = createContext<DependenciesContextType | null>(null);
@@ -186,6 +198,12 @@ export function DependenciesProvider({ children }: { children: ReactNode }) {
   const [galleryDlStatus, setGalleryDlStatus] = useState<GalleryDlStatus | null>(null);
   const [galleryDlLoading, setGalleryDlLoading] = useState(false);
   const [galleryDlError, setGalleryDlError] = useState<string | null>(null);
+  const [galleryDlDownloading, setGalleryDlDownloading] = useState(false);
+  const [galleryDlSuccess, setGalleryDlSuccess] = useState(false);
+  const [galleryDlUpdateInfo, setGalleryDlUpdateInfo] = useState<GalleryDlUpdateInfo | null>(
+    null,
+  );
+  const [galleryDlCheckingUpdate, setGalleryDlCheckingUpdate] = useState(false);
 
   // Load yt-dlp version (only once on first mount)
   const refreshYtdlpVersion = useCallback(async () => {
@@ -459,6 +477,72 @@ export function DependenciesProvider({ children }: { children: ReactNode }) {
     }
   }, []);
 
+  const setGalleryDlSource = useCallback(
+    async (source: DependencySource) => {
+      setGalleryDlError(null);
+      try {
+        await invoke('set_gallerydl_source_cmd', { source });
+        setGalleryDlUpdateInfo(null);
+        await checkGalleryDl();
+      } catch (err) {
+        setGalleryDlError(localizeUnknownError(err));
+      }
+    },
+    [checkGalleryDl],
+  );
+
+  const setGalleryDlChannel = useCallback(
+    async (channel: GalleryDlChannel) => {
+      setGalleryDlError(null);
+      try {
+        await invoke('set_gallerydl_channel_cmd', { channel });
+        setGalleryDlUpdateInfo(null);
+        await checkGalleryDl();
+      } catch (err) {
+        setGalleryDlError(localizeUnknownError(err));
+      }
+    },
+    [checkGalleryDl],
+  );
+
+  const checkGalleryDlUpdate = useCallback(async (channel: GalleryDlChannel) => {
+    setGalleryDlCheckingUpdate(true);
+    setGalleryDlError(null);
+    try {
+      const updateInfo = await invoke<GalleryDlUpdateInfo>('check_gallerydl_update', {
+        channel,
+      });
+      setGalleryDlUpdateInfo(updateInfo);
+    } catch (err) {
+      setGalleryDlError(localizeUnknownError(err));
+    } finally {
+      setGalleryDlCheckingUpdate(false);
+    }
+  }, []);
+
+  // Install or update the app-managed gallery-dl of a channel
+  const downloadGalleryDl = useCallback(
+    async (channel: GalleryDlChannel) => {
+      setGalleryDlDownloading(true);
+      setGalleryDlError(null);
+      setGalleryDlSuccess(false);
+      try {
+        await invoke<string>('download_gallerydl', { channel });
+        setGalleryDlSuccess(true);
+        // The update check compares release tags, so ask again rather than reuse the version
+        setGalleryDlUpdateInfo(null);
+        // Hide success message after 3 seconds
+        setTimeout(() => setGalleryDlSuccess(false), 3000);
+        await checkGalleryDl();
+      } catch (err) {
+        setGalleryDlError(localizeUnknownError(err));
+      } finally {
+        setGalleryDlDownloading(false);
+      }
+    },
+    [checkGalleryDl],
+  );
+
   // Initialize on first mount - auto download Deno and yt-dlp stable if not installed
   useEffect(() => {
     if (!initialized) {
@@ -538,7 +622,7 @@ export function DependenciesProvider({ children }: { children: ReactNode }) {
       });
 
       checkGalleryDl().catch(() => {
-        // gallery-dl is system-managed only, so failure here is non-fatal
+        // gallery-dl is optional, so failure here is non-fatal
       });
     }
   }, [
@@ -676,7 +760,15 @@ export function DependenciesProvider({ children }: { children: ReactNode }) {
         galleryDlStatus,
         galleryDlLoading,
         galleryDlError,
+        galleryDlDownloading,
+        galleryDlSuccess,
+        galleryDlUpdateInfo,
+        galleryDlCheckingUpdate,
         checkGalleryDl,
+        setGalleryDlSource,
+        setGalleryDlChannel,
+        checkGalleryDlUpdate,
+        downloadGalleryDl,
       }}
     >
       {children}
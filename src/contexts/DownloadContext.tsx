@@ -13,19 +13,17 @@ import {
   useRef,
   useState,
 } from 'react';
+import { useBackendDownloadQueue } from '@/hooks/useBackendDownloadQueue';
 import { usePersistedDownloadQueue } from '@/hooks/usePersistedDownloadQueue';
-import {
-  extractBackendError,
-  localizeBackendError,
-  localizeProgressError,
-} from '@/lib/backend-error';
+import { localizeProgressError } from '@/lib/backend-error';
+import { bandwidthConfig, normalizeBandwidthProfiles } from '@/lib/bandwidth';
+import { saveDownloadProfile } from '@/lib/download-profiles';
+import { normalizeFormatPreferences } from '@/lib/format-preferences';
+import { normalizeFolderQuotas } from '@/lib/storage-quotas';
 import {
   AUTO_RETRY_LIMITS,
   clampAutoRetryDelaySeconds,
   clampAutoRetryMaxAttempts,
-  isNonRetryableError,
-  isRetryableError,
-  waitWithCancellation,
 } from '@/lib/download-retry';
 import {
   buildCookieProxyInvokeOptions,
@@ -42,15 +40,20 @@ import {
   refreshPostDownloadWorkflowSteps,
 } from '@/lib/post-download-plugins';
 import { normalizeShellEscapedUrl } from '@/lib/sources';
+import { formatTimeRanges, toDownloadSections } from '@/lib/time-ranges';
 import type {
   AudioBitrate,
+  BandwidthProfile,
   CookieSettings,
   DownloadItem,
+  DownloadProfile,
   DownloadProgress,
   DownloadSettings,
   ExternalEnqueueOptions,
   ExternalEnqueueResult,
   Format,
+  FolderQuota,
+  FormatPreferences,
   ItemDownloadSettings,
   PlaylistVideoEntry,
   PostDownloadPluginPayload,
@@ -62,6 +65,7 @@ import type {
   SubtitleFormat,
   SubtitleMode,
   TelegramStatus,
+  TimeRange,
   VideoCodec,
   YoutubeSearchQueueResult,
   YoutubeSearchVideo,
@@ -70,7 +74,6 @@ import { DEFAULT_SPONSORBLOCK_CATEGORIES } from '@/lib/types';
 import { extractYouTubeVideoId } from '@/lib/youtube-url';
 
 const STORAGE_KEY = 'youwee-settings';
-const DOWNLOAD_QUEUE_IDLE_GRACE_MS = 1000;
 
 // Check if path is absolute (cross-platform)
 const isAbsolutePath = (path: string): boolean => {
@@ -160,6 +163,7 @@ function saveSettings(settings: DownloadSettings) {
         videoCodec: settings.videoCodec,
         audioBitrate: settings.audioBitrate,
         concurrentDownloads: settings.concurrentDownloads,
+        maxPerSite: settings.maxPerSite,
         playlistLimit: settings.playlistLimit,
         autoCheckUpdate: settings.autoCheckUpdate,
         subtitleMode: settings.subtitleMode,
@@ -170,16 +174,24 @@ function saveSettings(settings: DownloadSettings) {
         useActualPlayerJs: settings.useActualPlayerJs,
         embedMetadata: settings.embedMetadata,
         embedThumbnail: settings.embedThumbnail,
+        splitChapters: settings.splitChapters,
+        verifyDownloads: settings.verifyDownloads,
         liveFromStart: settings.liveFromStart,
         skipLive: settings.skipLive,
+        liveSegmentMinutes: settings.liveSegmentMinutes,
         speedLimitEnabled: settings.speedLimitEnabled,
         speedLimitValue: settings.speedLimitValue,
         speedLimitUnit: settings.speedLimitUnit,
+        bandwidthProfiles: settings.bandwidthProfiles,
         useAria2: settings.useAria2,
         aria2Args: settings.aria2Args,
+        outputTemplate: settings.outputTemplate,
+        downloadProfile: settings.downloadProfile,
+        formatPreferences: settings.formatPreferences,
         autoRetryEnabled: settings.autoRetryEnabled,
         autoRetryMaxAttempts: settings.autoRetryMaxAttempts,
         autoRetryDelaySeconds: settings.autoRetryDelaySeconds,
+        folderQuotas: settings.folderQuotas,
         persistDownloadQueue: settings.persistDownloadQueue,
         sponsorBlock: settings.sponsorBlock,
         sponsorBlockMode: settings.sponsorBlockMode,
@@ -230,6 +242,8 @@ interface DownloadContextType {
   clearCompleted: () => void;
   startDownload: () => Promise<void>;
   stopDownload: () => Promise<void>;
+  pauseItem: (id: string) => Promise<void>;
+  resumeItem: (id: string) => Promise<void>;
   updateSettings: (updates: Partial<DownloadSettings>) => void;
   updateQuality: (quality: Quality) => void;
   updateFormat: (format: Format) => void;
@@ -255,6 +269,8 @@ interface DownloadContextType {
   // Post-processing settings
   updateEmbedMetadata: (enabled: boolean) => void;
   updateEmbedThumbnail: (enabled: boolean) => void;
+  updateSplitChapters: (enabled: boolean) => void;
+  updateVerifyDownloads: (enabled: boolean) => void;
   // Live stream settings
   updateLiveFromStart: (enabled: boolean) => void;
   updateSkipLive: (enabled: boolean) => void;
@@ -263,8 +279,19 @@ interface DownloadContextType {
   // External downloader settings
   updateUseAria2: (enabled: boolean) => void;
   updateAria2Args: (args: string) => void;
+  // Output naming
+  updateOutputTemplate: (template: string) => void;
+  // Download profiles
+  updateDownloadProfile: (name: string) => void;
+  // Format rules
+  updateFormatPreferences: (preferences: FormatPreferences | null) => void;
+  saveCurrentAsDownloadProfile: (name: string) => Promise<DownloadProfile>;
   // Auto retry settings
   updateAutoRetry: (enabled: boolean, maxAttempts: number, delaySeconds: number) => void;
+  // Storage quotas
+  updateFolderQuotas: (quotas: FolderQuota[]) => void;
+  // Bandwidth
+  updateBandwidthProfiles: (profiles: BandwidthProfile[]) => void;
   // SponsorBlock settings
   updateSponsorBlock: (enabled: boolean) => void;
   updateSponsorBlockMode: (mode: SponsorBlockMode) => void;
@@ -280,8 +307,9 @@ interface DownloadContextType {
   cookieError: { show: boolean; itemId?: string; kind: 'db_locked' | 'fresh_cookies' } | null;
   clearCookieError: () => void;
   retryFailedDownload: (itemId: string) => void;
+  redownloadItem: (itemId: string) => void;
   // Per-item time range
-  updateItemTimeRange: (id: string, start?: string, end?: string) => void;
+  updateItemTimeRanges: (id: string, ranges: TimeRange[], join: boolean, precise: boolean) => void;
   // Rename completed file
   renameCompletedItem: (id: string, newName: string) => Promise<void>;
 }
@@ -291,7 +319,6 @@ const DownloadContext = createContext<DownloadContextType | null>(null);
 export function DownloadProvider({ children }: { children: ReactNode }) {
   const [items, setItems] = useState<DownloadItem[]>([]);
   const [focusedItemId, setFocusedItemId] = useState<string | null>(null);
-  const [isDownloading, setIsDownloading] = useState(false);
   const [isExpandingPlaylist, setIsExpandingPlaylist] = useState(false);
   const [cookieError, setCookieError] = useState<{
     show: boolean;
@@ -310,6 +337,7 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
       videoCodec: saved.videoCodec || 'auto',
       audioBitrate: saved.audioBitrate || 'auto',
       concurrentDownloads: saved.concurrentDownloads || 1,
+      maxPerSite: saved.maxPerSite || 2,
       playlistLimit: saved.playlistLimit || 0, // 0 = unlimited
       autoCheckUpdate: saved.autoCheckUpdate !== false, // Default to true
       // Subtitle settings
@@ -323,16 +351,24 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
       // Post-processing settings
       embedMetadata: saved.embedMetadata !== false, // Default to true
       embedThumbnail: saved.embedThumbnail === true, // Default to false (requires FFmpeg)
+      splitChapters: saved.splitChapters === true, // Default to false
+      verifyDownloads: saved.verifyDownloads === true, // Default to false (requires FFprobe)
       // Live stream settings
       liveFromStart: saved.liveFromStart === true, // Default to false
       skipLive: saved.skipLive === true, // Default to false
+      liveSegmentMinutes: saved.liveSegmentMinutes || 0, // Default to off
       // Speed limit settings
       speedLimitEnabled: saved.speedLimitEnabled === true, // Default to false (unlimited)
       speedLimitValue: saved.speedLimitValue || 10,
       speedLimitUnit: saved.speedLimitUnit || 'M',
+      bandwidthProfiles: normalizeBandwidthProfiles(saved.bandwidthProfiles),
       // External downloader settings
       useAria2: saved.useAria2 === true, // Default to false
       aria2Args: saved.aria2Args || '',
+      // Output naming
+      outputTemplate: typeof saved.outputTemplate === 'string' ? saved.outputTemplate : '',
+      downloadProfile: typeof saved.downloadProfile === 'string' ? saved.downloadProfile : '',
+      formatPreferences: normalizeFormatPreferences(saved.formatPreferences),
       // Auto retry settings
       autoRetryEnabled: saved.autoRetryEnabled === true, // Default to false
       autoRetryMaxAttempts: clampAutoRetryMaxAttempts(
@@ -341,6 +377,7 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
       autoRetryDelaySeconds: clampAutoRetryDelaySeconds(
         saved.autoRetryDelaySeconds || AUTO_RETRY_LIMITS.delaySeconds.default,
       ),
+      folderQuotas: normalizeFolderQuotas(saved.folderQuotas),
       // Queue persistence
       persistDownloadQueue: saved.persistDownloadQueue === true,
       // SponsorBlock settings
@@ -373,7 +410,6 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
 
   const [currentPlaylistInfo, setCurrentPlaylistInfo] = useState<PlaylistInfo | null>(null);
 
-  const isDownloadingRef = useRef(false);
   const itemsRef = useRef<DownloadItem[]>([]);
   const settingsRef = useRef<DownloadSettings>(settings);
   const focusClearTimerRef = useRef<number | null>(null);
@@ -391,6 +427,37 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
     syncPollingNetworkConfig(loadCookieSettings(), loadProxySettings());
   }, [syncPollingNetworkConfig]);
 
+  // Keep the backend download scheduler's limits and fallback folder in sync
+  useEffect(() => {
+    invoke('set_download_scheduler_config', {
+      config: {
+        maxConcurrent: Math.max(1, settings.concurrentDownloads || 1),
+        maxPerSite: Math.max(1, settings.maxPerSite || 2),
+        defaultOutputPath: settings.outputPath || null,
+        retry: {
+          maxAttempts: settings.autoRetryEnabled
+            ? clampAutoRetryMaxAttempts(settings.autoRetryMaxAttempts) + 1
+            : 1,
+          baseDelaySecs: clampAutoRetryDelaySeconds(settings.autoRetryDelaySeconds),
+        },
+        folderQuotas: settings.folderQuotas,
+        bandwidth: bandwidthConfig(settings),
+      },
+    }).catch((e) => console.error('Failed to sync download scheduler config:', e));
+  }, [
+    settings.concurrentDownloads,
+    settings.maxPerSite,
+    settings.outputPath,
+    settings.autoRetryEnabled,
+    settings.autoRetryMaxAttempts,
+    settings.autoRetryDelaySeconds,
+    settings.folderQuotas,
+    settings.speedLimitEnabled,
+    settings.speedLimitValue,
+    settings.speedLimitUnit,
+    settings.bandwidthProfiles,
+  ]);
+
   useEffect(() => {
     refreshPostDownloadWorkflowSteps();
   }, []);
@@ -471,6 +538,29 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
         });
       }
 
+      // One entry of a playlist download finished or failed; the job itself keeps going
+      if (progress.status === 'item_finished' || progress.status === 'item_error') {
+        if (progress.status === 'item_error') {
+          const failure = {
+            index: progress.playlist_index ?? 0,
+            error:
+              localizeProgressError(
+                progress.error_code,
+                progress.error_message,
+                progress.error_params,
+              ) || '',
+          };
+          setItems((currentItems) =>
+            currentItems.map((item) =>
+              item.id === progress.id
+                ? { ...item, playlistFailures: [...(item.playlistFailures ?? []), failure] }
+                : item,
+            ),
+          );
+        }
+        return;
+      }
+
       // Detect cookie error on Windows (lock error or DPAPI/App-Bound Encryption)
       const cookieDbLockedPattern =
         /could not copy.*cookie|permission denied.*cookies|cookie.*database|failed to.*cookie|failed to decrypt.*dpapi|app.bound.encryption/i;
@@ -510,6 +600,7 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
                 playlistTotal: progress.playlist_count,
                 downloadedSize: progress.downloaded_size,
                 elapsedTime: progress.elapsed_time,
+                phase: progress.status === 'downloading' ? progress.phase : undefined,
                 // Auto-detect live stream if we receive downloaded_size (live stream format)
                 isLive: progress.downloaded_size ? true : item.isLive,
                 // Store completed info when finished
@@ -565,10 +656,7 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
     for (const item of queuedItems) {
       const itemSettings = item.settings as ItemDownloadSettings | undefined;
       const workflowSnapshots = itemSettings?.pluginWorkflowSnapshots;
-      const timeRange =
-        itemSettings?.timeRangeStart && itemSettings?.timeRangeEnd
-          ? `${itemSettings.timeRangeStart}-${itemSettings.timeRangeEnd}`
-          : null;
+      const timeRange = formatTimeRanges(itemSettings?.timeRanges);
       const payload: PostDownloadPluginPayload = {
         jobId: item.id,
         source: item.extractor || null,
@@ -598,43 +686,6 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
     }
   }, []);
 
-  const enqueueFailedWorkflowForItem = useCallback(
-    (item: DownloadItem, itemSettings: ItemDownloadSettings | undefined) => {
-      const workflowSnapshots = itemSettings?.pluginWorkflowSnapshots;
-      const timeRange =
-        itemSettings?.timeRangeStart && itemSettings?.timeRangeEnd
-          ? `${itemSettings.timeRangeStart}-${itemSettings.timeRangeEnd}`
-          : null;
-      const payload: PostDownloadPluginPayload = {
-        jobId: item.id,
-        source: item.extractor || null,
-        trigger: 'download.failed',
-        filepath: '',
-        filename: item.title || item.url,
-        directory: itemSettings?.outputPath ?? settings.outputPath,
-        filesize: item.filesize ?? null,
-        format: itemSettings?.format ?? settings.format,
-        quality: itemSettings?.quality ?? settings.quality,
-        url: item.url,
-        title: item.title || null,
-        thumbnail: item.thumbnail || null,
-        historyId: null,
-        timeRange,
-        downloadKind: 'download',
-        workflowRunId: null,
-        workflowStepIndex: null,
-        workflowStepPluginId: null,
-        chainState: null,
-      };
-      void enqueuePluginWorkflowTrigger('download.failed', payload, workflowSnapshots).catch(
-        (error) => {
-          console.error('Failed to enqueue download.failed workflow:', error);
-        },
-      );
-    },
-    [settings.format, settings.outputPath, settings.quality],
-  );
-
   // Add individual URLs (not playlist expansion)
   const addUrlsDirectly = useCallback(
     (urls: string[], playlistId?: string) => {
@@ -653,6 +704,9 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
         audioBitrate: currentSettings.audioBitrate,
         useAria2: currentSettings.useAria2,
         aria2Args: currentSettings.aria2Args,
+        outputTemplate: currentSettings.outputTemplate,
+        profile: currentSettings.downloadProfile || undefined,
+        formatPreferences: currentSettings.formatPreferences,
         subtitleMode: currentSettings.subtitleMode,
         subtitleLangs: [...currentSettings.subtitleLangs],
         subtitleEmbed: currentSettings.subtitleEmbed,
@@ -747,12 +801,16 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
         audioBitrate: mediaType === 'audio' ? audioBitrate : currentSettings.audioBitrate,
         useAria2: currentSettings.useAria2,
         aria2Args: currentSettings.aria2Args,
+        outputTemplate: currentSettings.outputTemplate,
+        profile: options?.profile ?? (currentSettings.downloadProfile || undefined),
+        formatPreferences: currentSettings.formatPreferences,
         subtitleMode: options?.subtitleMode ?? currentSettings.subtitleMode,
         subtitleLangs: options?.subtitleLangs ?? [...currentSettings.subtitleLangs],
         subtitleEmbed: options?.subtitleEmbed ?? currentSettings.subtitleEmbed,
         subtitleFormat: options?.subtitleFormat ?? currentSettings.subtitleFormat,
-        timeRangeStart: options?.timeRangeStart,
-        timeRangeEnd: options?.timeRangeEnd,
+        timeRanges: options?.timeRanges,
+        joinTimeRanges: options?.joinTimeRanges,
+        preciseTimeRanges: options?.preciseTimeRanges,
         liveFromStart: options?.liveFromStart ?? currentSettings.liveFromStart,
         skipLive: options?.skipLive ?? currentSettings.skipLive,
         pluginWorkflowSnapshots: workflowSnapshots,
@@ -798,6 +856,9 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
         audioBitrate: currentSettings.audioBitrate,
         useAria2: currentSettings.useAria2,
         aria2Args: currentSettings.aria2Args,
+        outputTemplate: currentSettings.outputTemplate,
+        profile: currentSettings.downloadProfile || undefined,
+        formatPreferences: currentSettings.formatPreferences,
         subtitleMode: currentSettings.subtitleMode,
         subtitleLangs: [...currentSettings.subtitleLangs],
         subtitleEmbed: currentSettings.subtitleEmbed,
@@ -885,6 +946,9 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
           audioBitrate: settingsRef.current.audioBitrate,
           useAria2: settingsRef.current.useAria2,
           aria2Args: settingsRef.current.aria2Args,
+          outputTemplate: settingsRef.current.outputTemplate,
+          profile: settingsRef.current.downloadProfile || undefined,
+          formatPreferences: settingsRef.current.formatPreferences,
           subtitleMode: settingsRef.current.subtitleMode,
           subtitleLangs: [...settingsRef.current.subtitleLangs],
           subtitleEmbed: settingsRef.current.subtitleEmbed,
@@ -1023,26 +1087,130 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
     }
   }, [settings.outputPath]);
 
-  const removeItem = useCallback((id: string) => {
-    setItems((items) => {
-      const nextItems = items.filter((item) => item.id !== id);
-      itemsRef.current = nextItems;
-      return nextItems;
-    });
-  }, []);
+  // Options sent with an item to the backend queue: its settings snapshot, with the
+  // current settings for anything the snapshot does not cover
+  const buildDownloadOptions = useCallback(
+    (item: DownloadItem): Record<string, unknown> => {
+      const itemSettings = item.settings as ItemDownloadSettings | undefined;
+      const logStderr = localStorage.getItem('youwee_log_stderr') !== 'false';
+      const sponsorBlockArgs = buildSponsorBlockArgs(settings);
 
-  const updateItemTimeRange = useCallback((id: string, start?: string, end?: string) => {
-    setItems((items) =>
-      items.map((item) => {
-        if (item.id !== id || !item.settings) return item;
-        const settings = item.settings as ItemDownloadSettings;
-        return {
-          ...item,
-          settings: { ...settings, timeRangeStart: start, timeRangeEnd: end },
-        };
-      }),
-    );
-  }, []);
+      return {
+        outputPath: itemSettings?.outputPath || settings.outputPath,
+        quality: itemSettings?.quality ?? settings.quality,
+        format: itemSettings?.format ?? settings.format,
+        downloadPlaylist: itemSettings?.downloadPlaylist ?? false,
+        videoCodec: itemSettings?.videoCodec ?? settings.videoCodec,
+        audioBitrate: itemSettings?.audioBitrate ?? settings.audioBitrate,
+        playlistLimit:
+          itemSettings?.playlistLimit && itemSettings.playlistLimit > 0
+            ? itemSettings.playlistLimit
+            : null,
+        // Subtitle settings
+        subtitleMode: itemSettings?.subtitleMode ?? settings.subtitleMode,
+        subtitleLangs: (itemSettings?.subtitleLangs ?? settings.subtitleLangs).join(','),
+        subtitleEmbed: itemSettings?.subtitleEmbed ?? settings.subtitleEmbed,
+        subtitleFormat: itemSettings?.subtitleFormat ?? settings.subtitleFormat,
+        // Logging settings
+        logStderr,
+        // YouTube specific settings
+        useActualPlayerJs: settings.useActualPlayerJs,
+        // Network settings
+        ...buildCookieProxyInvokeOptions(cookieSettings, proxySettings),
+        // Post-processing settings
+        embedMetadata: settings.embedMetadata,
+        embedThumbnail: settings.embedThumbnail,
+        splitChapters: settings.splitChapters,
+        verifyOutput: settings.verifyDownloads,
+        // Live stream settings
+        liveFromStart: itemSettings?.liveFromStart ?? settings.liveFromStart,
+        skipLive: itemSettings?.skipLive ?? false,
+        // External downloader settings
+        useAria2: itemSettings?.useAria2 ?? settings.useAria2,
+        aria2Args: itemSettings?.aria2Args ?? settings.aria2Args,
+        // SponsorBlock settings
+        sponsorblockRemove: sponsorBlockArgs.remove,
+        sponsorblockMark: sponsorBlockArgs.mark,
+        // Download sections (time ranges), one clip per range unless joined
+        downloadSections: toDownloadSections(itemSettings?.timeRanges),
+        joinSections: itemSettings?.joinTimeRanges ?? false,
+        preciseSections: itemSettings?.preciseTimeRanges ?? false,
+        // No history_id for new downloads
+        historyId: null,
+        // Thumbnail from video info fetch
+        thumbnail: item.thumbnail || null,
+        // Source/extractor from video info fetch
+        source: item.extractor || null,
+        pluginWorkflowSnapshots:
+          itemSettings?.pluginWorkflowSnapshots ?? loadPluginWorkflowSnapshots(),
+        postDownloadWorkflowSteps:
+          itemSettings?.postDownloadWorkflowSteps ?? loadPostDownloadWorkflowSteps(),
+        downloadKind: 'download',
+        forceRedownload: itemSettings?.forceRedownload ?? false,
+        outputTemplate: itemSettings?.outputTemplate ?? settings.outputTemplate,
+        profile: itemSettings?.profile ?? (settings.downloadProfile || null),
+        formatPreferences: itemSettings
+          ? (itemSettings.formatPreferences ?? null)
+          : settings.formatPreferences,
+      };
+    },
+    [settings, cookieSettings, proxySettings],
+  );
+
+  const {
+    isDownloading,
+    startDownload: startQueuedDownloads,
+    stopDownload: stopQueuedDownloads,
+    pauseItem,
+    resumeItem,
+    forgetItems,
+  } = useBackendDownloadQueue({
+    queueKind: 'youtube',
+    items,
+    itemsRef,
+    setItems,
+    buildOptions: buildDownloadOptions,
+    logLabel: 'download queue',
+  });
+
+  useEffect(() => {
+    if (!isDownloading) {
+      setCurrentPlaylistInfo(null);
+    }
+  }, [isDownloading]);
+
+  const removeItem = useCallback(
+    (id: string) => {
+      forgetItems([id]);
+      setItems((items) => {
+        const nextItems = items.filter((item) => item.id !== id);
+        itemsRef.current = nextItems;
+        return nextItems;
+      });
+    },
+    [forgetItems],
+  );
+
+  const updateItemTimeRanges = useCallback(
+    (id: string, ranges: TimeRange[], join: boolean, precise: boolean) => {
+      setItems((items) =>
+        items.map((item) => {
+          if (item.id !== id || !item.settings) return item;
+          const settings = item.settings as ItemDownloadSettings;
+          return {
+            ...item,
+            settings: {
+              ...settings,
+              timeRanges: ranges,
+              joinTimeRanges: join,
+              preciseTimeRanges: precise,
+            },
+          };
+        }),
+      );
+    },
+    [],
+  );
 
   const renameCompletedItem = useCallback(async (id: string, newName: string) => {
     const item = itemsRef.current.find((i) => i.id === id);
@@ -1086,12 +1254,18 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
   }, []);
 
   const clearAll = useCallback(() => {
+    forgetItems(itemsRef.current.map((item) => item.id));
     itemsRef.current = [];
     setItems([]);
     setCurrentPlaylistInfo(null);
-  }, []);
+  }, [forgetItems]);
 
   const clearCompleted = useCallback(() => {
+    forgetItems(
+      itemsRef.current
+        .filter((item) => item.status === 'completed' || item.status === 'skipped')
+        .map((item) => item.id),
+    );
     setItems((items) => {
       const nextItems = items.filter(
         (item) => item.status !== 'completed' && item.status !== 'skipped',
@@ -1099,312 +1273,17 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
       itemsRef.current = nextItems;
       return nextItems;
     });
-  }, []);
+  }, [forgetItems]);
 
   const startDownload = useCallback(async () => {
-    const hasPendingItems = () =>
-      itemsRef.current.some((item) => item.status === 'pending' || item.status === 'error');
-
-    if (!hasPendingItems()) return;
-
-    setIsDownloading(true);
-    isDownloadingRef.current = true;
     setCurrentPlaylistInfo(null);
-
-    // Reset only pending/error items, keep completed items and playlist info as-is
-    setItems((items) =>
-      items.map((item) => {
-        if (item.status === 'pending' || item.status === 'error') {
-          return {
-            ...item,
-            status: 'pending' as const,
-            progress: 0,
-            speed: '',
-            eta: '',
-            error: undefined,
-            retryState: undefined,
-            // Keep playlistIndex and playlistTotal for display
-          };
-        }
-        return item;
-      }),
-    );
-
-    const concurrentLimit = Math.max(1, settings.concurrentDownloads || 1);
-
-    // Download single item
-    const downloadItem = async (item: DownloadItem) => {
-      if (!isDownloadingRef.current) return;
-
-      // Use item's saved settings (snapshot from when it was added)
-      // Fallback to current global settings if not available
-      const itemSettings = item.settings as ItemDownloadSettings | undefined;
-      const logStderr = localStorage.getItem('youwee_log_stderr') !== 'false';
-      const sponsorBlockArgs = buildSponsorBlockArgs(settings);
-
-      const autoRetryEnabled = itemSettings?.autoRetryEnabled ?? settings.autoRetryEnabled;
-      const maxRetries = clampAutoRetryMaxAttempts(
-        itemSettings?.autoRetryMaxAttempts ?? settings.autoRetryMaxAttempts,
-      );
-      const retryDelaySeconds = clampAutoRetryDelaySeconds(
-        itemSettings?.autoRetryDelaySeconds ?? settings.autoRetryDelaySeconds,
-      );
-
-      let retryIndex = 0;
-
-      while (isDownloadingRef.current) {
-        setItems((items) =>
-          items.map((i) =>
-            i.id === item.id
-              ? {
-                  ...i,
-                  status: 'downloading',
-                  error: undefined,
-                  errorCode: undefined,
-                  retryState: undefined,
-                }
-              : i,
-          ),
-        );
-
-        try {
-          await invoke('download_video', {
-            id: item.id,
-            url: item.url,
-            outputPath: itemSettings?.outputPath || settings.outputPath,
-            quality: itemSettings?.quality ?? settings.quality,
-            format: itemSettings?.format ?? settings.format,
-            downloadPlaylist: itemSettings?.downloadPlaylist ?? false,
-            videoCodec: itemSettings?.videoCodec ?? settings.videoCodec,
-            audioBitrate: itemSettings?.audioBitrate ?? settings.audioBitrate,
-            playlistLimit:
-              itemSettings?.playlistLimit && itemSettings.playlistLimit > 0
-                ? itemSettings.playlistLimit
-                : null,
-            // Subtitle settings
-            subtitleMode: itemSettings?.subtitleMode ?? settings.subtitleMode,
-            subtitleLangs: (itemSettings?.subtitleLangs ?? settings.subtitleLangs).join(','),
-            subtitleEmbed: itemSettings?.subtitleEmbed ?? settings.subtitleEmbed,
-            subtitleFormat: itemSettings?.subtitleFormat ?? settings.subtitleFormat,
-            // Logging settings
-            logStderr,
-            // YouTube specific settings
-            useBunRuntime: settings.useBunRuntime,
-            useActualPlayerJs: settings.useActualPlayerJs,
-            // Network settings
-            ...buildCookieProxyInvokeOptions(cookieSettings, proxySettings),
-            // Post-processing settings
-            embedMetadata: settings.embedMetadata,
-            embedThumbnail: settings.embedThumbnail,
-            // Live stream settings
-            liveFromStart: itemSettings?.liveFromStart ?? settings.liveFromStart,
-            skipLive: itemSettings?.skipLive ?? false,
-            // Speed limit settings
-            speedLimit: settings.speedLimitEnabled
-              ? `${settings.speedLimitValue}${settings.speedLimitUnit}`
-              : null,
-            // External downloader settings
-            useAria2: itemSettings?.useAria2 ?? settings.useAria2,
-            aria2Args: itemSettings?.aria2Args ?? settings.aria2Args,
-            // SponsorBlock settings
-            sponsorblockRemove: sponsorBlockArgs.remove,
-            sponsorblockMark: sponsorBlockArgs.mark,
-            // Download sections (time range)
-            downloadSections:
-              itemSettings?.timeRangeStart && itemSettings?.timeRangeEnd
-                ? `*${itemSettings.timeRangeStart}-${itemSettings.timeRangeEnd}`
-                : null,
-            // No history_id for new downloads
-            historyId: null,
-            // Title from video info fetch
-            title: item.title || null,
-            // Thumbnail from video info fetch
-            thumbnail: item.thumbnail || null,
-            // Source/extractor from video info fetch
-            source: item.extractor || null,
-            pluginWorkflowSnapshots:
-              itemSettings?.pluginWorkflowSnapshots ?? loadPluginWorkflowSnapshots(),
-            postDownloadWorkflowSteps:
-              itemSettings?.postDownloadWorkflowSteps ?? loadPostDownloadWorkflowSteps(),
-            emitFailedWorkflow: false,
-            downloadKind: 'download',
-          });
-
-          setItems((items) =>
-            items.map((i) =>
-              i.id === item.id
-                ? { ...i, status: 'completed', progress: 100, retryState: undefined }
-                : i,
-            ),
-          );
-          return;
-        } catch (error) {
-          const parsedError = extractBackendError(error);
-          const errorMessage = localizeBackendError(parsedError);
-          if (parsedError.code === 'YT_SKIPPED_LIVE') {
-            setItems((items) =>
-              items.map((i) =>
-                i.id === item.id
-                  ? {
-                      ...i,
-                      status: 'skipped',
-                      progress: 0,
-                      error: errorMessage,
-                      errorCode: parsedError.code,
-                      retryState: undefined,
-                    }
-                  : i,
-              ),
-            );
-            return;
-          }
-          const canRetry =
-            isDownloadingRef.current &&
-            autoRetryEnabled &&
-            retryIndex < maxRetries &&
-            !isNonRetryableError(parsedError.message, parsedError.code) &&
-            isRetryableError(parsedError.message, parsedError.code, parsedError.retryable);
-
-          if (!canRetry) {
-            enqueueFailedWorkflowForItem(item, itemSettings);
-            setItems((items) =>
-              items.map((i) =>
-                i.id === item.id
-                  ? {
-                      ...i,
-                      status: 'error',
-                      error: errorMessage,
-                      errorCode: parsedError.code,
-                      retryState: undefined,
-                    }
-                  : i,
-              ),
-            );
-            return;
-          }
-
-          retryIndex += 1;
-          setItems((items) =>
-            items.map((i) =>
-              i.id === item.id
-                ? {
-                    ...i,
-                    status: 'pending',
-                    error: errorMessage,
-                    errorCode: parsedError.code,
-                    retryState: {
-                      retryIndex,
-                      maxRetries,
-                      delaySeconds: retryDelaySeconds,
-                      remainingSeconds: retryDelaySeconds,
-                    },
-                  }
-                : i,
-            ),
-          );
-
-          const shouldContinue = await waitWithCancellation(
-            retryDelaySeconds * 1000,
-            () => !isDownloadingRef.current,
-            (remainingSeconds) => {
-              setItems((items) =>
-                items.map((i) =>
-                  i.id === item.id && i.retryState
-                    ? {
-                        ...i,
-                        retryState: {
-                          ...i.retryState,
-                          remainingSeconds,
-                        },
-                      }
-                    : i,
-                ),
-              );
-            },
-          );
-
-          if (!shouldContinue) {
-            return;
-          }
-        }
-      }
-    };
-
-    try {
-      const claimedIds = new Set<string>();
-      const processedIds = new Set<string>();
-      let activeCount = 0;
-
-      const claimNextItem = (): DownloadItem | null => {
-        const next = itemsRef.current.find(
-          (candidate) =>
-            (candidate.status === 'pending' || candidate.status === 'error') &&
-            !claimedIds.has(candidate.id) &&
-            !processedIds.has(candidate.id),
-        );
-        if (!next) return null;
-        claimedIds.add(next.id);
-        return next;
-      };
-
-      const hasUnclaimedPendingItems = () =>
-        itemsRef.current.some(
-          (candidate) =>
-            (candidate.status === 'pending' || candidate.status === 'error') &&
-            !claimedIds.has(candidate.id) &&
-            !processedIds.has(candidate.id),
-        );
-
-      const processNext = async (): Promise<void> => {
-        while (isDownloadingRef.current) {
-          const item = claimNextItem();
-          if (!item) {
-            if (activeCount === 0 && !hasUnclaimedPendingItems()) {
-              await new Promise<void>((resolve) => {
-                window.setTimeout(resolve, DOWNLOAD_QUEUE_IDLE_GRACE_MS);
-              });
-              if (!isDownloadingRef.current || !hasUnclaimedPendingItems()) {
-                return;
-              }
-              continue;
-            }
-            await new Promise<void>((resolve) => {
-              window.setTimeout(resolve, 200);
-            });
-            continue;
-          }
-
-          activeCount += 1;
-          try {
-            await downloadItem(item);
-          } finally {
-            activeCount -= 1;
-            claimedIds.delete(item.id);
-            processedIds.add(item.id);
-          }
-        }
-      };
-
-      const workers = Array.from({ length: concurrentLimit }, () => processNext());
-      await Promise.all(workers);
-    } finally {
-      setIsDownloading(false);
-      isDownloadingRef.current = false;
-      setCurrentPlaylistInfo(null);
-    }
-  }, [enqueueFailedWorkflowForItem, settings, cookieSettings, proxySettings]);
+    await startQueuedDownloads();
+  }, [startQueuedDownloads]);
 
   const stopDownload = useCallback(async () => {
-    try {
-      await invoke('stop_download');
-    } catch (error) {
-      console.error('Failed to stop download:', error);
-    }
-    setItems((items) => items.map((item) => ({ ...item, retryState: undefined })));
-    setIsDownloading(false);
-    isDownloadingRef.current = false;
+    await stopQueuedDownloads();
     setCurrentPlaylistInfo(null);
-  }, []);
+  }, [stopQueuedDownloads]);
 
   const updateSettings = useCallback((updates: Partial<DownloadSettings>) => {
     setSettings((s) => {
@@ -1572,6 +1451,22 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
     });
   }, []);
 
+  const updateSplitChapters = useCallback((splitChapters: boolean) => {
+    setSettings((s) => {
+      const newSettings = { ...s, splitChapters };
+      saveSettings(newSettings);
+      return newSettings;
+    });
+  }, []);
+
+  const updateVerifyDownloads = useCallback((verifyDownloads: boolean) => {
+    setSettings((s) => {
+      const newSettings = { ...s, verifyDownloads };
+      saveSettings(newSettings);
+      return newSettings;
+    });
+  }, []);
+
   const updateLiveFromStart = useCallback((liveFromStart: boolean) => {
     setSettings((s) => {
       const newSettings = { ...s, liveFromStart };
@@ -1615,6 +1510,73 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
     });
   }, []);
 
+  const updateOutputTemplate = useCallback((outputTemplate: string) => {
+    setSettings((s) => {
+      const newSettings = { ...s, outputTemplate };
+      saveSettings(newSettings);
+      return newSettings;
+    });
+  }, []);
+
+  const updateDownloadProfile = useCallback((downloadProfile: string) => {
+    setSettings((s) => {
+      const newSettings = { ...s, downloadProfile };
+      saveSettings(newSettings);
+      return newSettings;
+    });
+  }, []);
+
+  const updateFolderQuotas = useCallback((folderQuotas: FolderQuota[]) => {
+    setSettings((s) => {
+      const newSettings = { ...s, folderQuotas };
+      saveSettings(newSettings);
+      return newSettings;
+    });
+  }, []);
+
+  const updateBandwidthProfiles = useCallback((bandwidthProfiles: BandwidthProfile[]) => {
+    setSettings((s) => {
+      const newSettings = { ...s, bandwidthProfiles };
+      saveSettings(newSettings);
+      return newSettings;
+    });
+  }, []);
+
+  const updateFormatPreferences = useCallback((formatPreferences: FormatPreferences | null) => {
+    setSettings((s) => {
+      const newSettings = { ...s, formatPreferences };
+      saveSettings(newSettings);
+      return newSettings;
+    });
+  }, []);
+
+  // Save the current download settings, cookies and proxy as a named profile
+  const saveCurrentAsDownloadProfile = useCallback(
+    (name: string) => {
+      const sponsorBlockArgs = buildSponsorBlockArgs(settings);
+      const network = buildCookieProxyInvokeOptions(cookieSettings, proxySettings);
+      return saveDownloadProfile(name, {
+        quality: settings.quality,
+        format: settings.format,
+        videoCodec: settings.videoCodec,
+        audioBitrate: settings.audioBitrate,
+        subtitleMode: settings.subtitleMode,
+        subtitleLangs: settings.subtitleLangs.join(','),
+        subtitleEmbed: settings.subtitleEmbed,
+        subtitleFormat: settings.subtitleFormat,
+        embedMetadata: settings.embedMetadata,
+        embedThumbnail: settings.embedThumbnail,
+        // Empty values switch SponsorBlock and the proxy off for downloads using the profile
+        sponsorblockRemove: sponsorBlockArgs.remove ?? '',
+        sponsorblockMark: sponsorBlockArgs.mark ?? '',
+        ...network,
+        proxyUrl: network.proxyUrl ?? '',
+        outputTemplate: settings.outputTemplate,
+      });
+    },
+    [settings, cookieSettings, proxySettings],
+  );
+
   const updateAutoRetry = useCallback(
     (autoRetryEnabled: boolean, autoRetryMaxAttempts: number, autoRetryDelaySeconds: number) => {
       setSettings((s) => {
@@ -1700,6 +1662,7 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
                 error: undefined,
                 errorCode: undefined,
                 retryState: undefined,
+                playlistFailures: undefined,
               }
             : item,
         ),
@@ -1714,6 +1677,32 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
     [startDownload],
   );
 
+  const redownloadItem = useCallback(
+    (itemId: string) => {
+      // Download an archived item again, bypassing the download archive
+      setItems((currentItems) =>
+        currentItems.map((item) =>
+          item.id === itemId && item.settings
+            ? {
+                ...item,
+                status: 'pending',
+                progress: 0,
+                error: undefined,
+                errorCode: undefined,
+                retryState: undefined,
+                playlistFailures: undefined,
+                settings: { ...(item.settings as ItemDownloadSettings), forceRedownload: true },
+              }
+            : item,
+        ),
+      );
+      setTimeout(() => {
+        startDownload();
+      }, 100);
+    },
+    [startDownload],
+  );
+
   const value: DownloadContextType = useMemo(
     () => ({
       items,
@@ -1736,6 +1725,8 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
       clearCompleted,
       startDownload,
       stopDownload,
+      pauseItem,
+      resumeItem,
       updateSettings,
       updateQuality,
       updateFormat,
@@ -1756,12 +1747,20 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
       getProxyUrl,
       updateEmbedMetadata,
       updateEmbedThumbnail,
+      updateSplitChapters,
+      updateVerifyDownloads,
       updateLiveFromStart,
       updateSkipLive,
       updateSpeedLimit,
       updateUseAria2,
       updateAria2Args,
+      updateOutputTemplate,
+      updateDownloadProfile,
+      updateFormatPreferences,
+      saveCurrentAsDownloadProfile,
       updateAutoRetry,
+      updateFolderQuotas,
+      updateBandwidthProfiles,
       // SponsorBlock settings
       updateSponsorBlock,
       updateSponsorBlockMode,
@@ -1772,8 +1771,9 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
       cookieError,
       clearCookieError,
       retryFailedDownload,
+      redownloadItem,
       // Per-item time range
-      updateItemTimeRange,
+      updateItemTimeRanges,
       renameCompletedItem,
     }),
     [
@@ -1797,6 +1797,8 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
       clearCompleted,
       startDownload,
       stopDownload,
+      pauseItem,
+      resumeItem,
       updateSettings,
       updateQuality,
       updateFormat,
@@ -1817,12 +1819,20 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
       getProxyUrl,
       updateEmbedMetadata,
       updateEmbedThumbnail,
+      updateSplitChapters,
+      updateVerifyDownloads,
       updateLiveFromStart,
       updateSkipLive,
       updateSpeedLimit,
       updateUseAria2,
       updateAria2Args,
+      updateOutputTemplate,
+      updateDownloadProfile,
+      updateFormatPreferences,
+      saveCurrentAsDownloadProfile,
       updateAutoRetry,
+      updateFolderQuotas,
+      updateBandwidthProfiles,
       updateSponsorBlock,
       updateSponsorBlockMode,
       updateSponsorBlockCategory,
@@ -1831,7 +1841,8 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
       cookieError,
       clearCookieError,
       retryFailedDownload,
-      updateItemTimeRange,
+      redownloadItem,
+      updateItemTimeRanges,
       renameCompletedItem,
     ],
   );
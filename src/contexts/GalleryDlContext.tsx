@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { downloadDir, homeDir } from '@tauri-apps/api/path';
 import { open } from '@tauri-apps/plugin-dialog';
 import { readTextFile } from '@tauri-apps/plugin-fs';
@@ -22,9 +23,10 @@ import {
   isRetryableError,
   waitWithCancellation,
 } from '@/lib/download-retry';
+import { normalizeGalleryOptions } from '@/lib/external-link';
 import { buildCookieProxyInvokeOptions, loadNetworkSettings } from '@/lib/network-config';
 import { parseUniversalUrls } from '@/lib/sources';
-import type { DownloadItem } from '@/lib/types';
+import type { DownloadItem, ExternalEnqueueResult, GalleryDlOptions } from '@/lib/types';
 import { useDownload } from './DownloadContext';
 
 const STORAGE_KEY = 'youwee-gallerydl-settings';
@@ -36,11 +38,22 @@ interface GalleryDlSettings {
   autoRetryEnabled: boolean;
   autoRetryMaxAttempts: number;
   autoRetryDelaySeconds: number;
+  options: GalleryDlOptions;
 }
 
 interface GalleryDownloadResult {
-  filepath: string;
+  filepath: string; // Folder holding the saved files
   history_id?: string | null;
+  files: string[];
+  skipped: number;
+}
+
+// Emitted by the backend for every file gallery-dl saves or skips
+interface GalleryProgress {
+  id: string;
+  downloaded: number;
+  skipped: number;
+  filepath: string;
 }
 
 interface GalleryDlContextType {
@@ -52,6 +65,7 @@ interface GalleryDlContextType {
   addFromText: (text: string) => Promise<number>;
   importFromFile: () => Promise<number>;
   importFromClipboard: () => Promise<number>;
+  enqueueExternalUrl: (url: string, options?: GalleryDlOptions) => Promise<ExternalEnqueueResult>;
   selectOutputFolder: () => Promise<void>;
   removeItem: (id: string) => void;
   clearAll: () => void;
@@ -59,6 +73,7 @@ interface GalleryDlContextType {
   startDownload: () => Promise<void>;
   stopDownload: () => Promise<void>;
   updateConcurrentDownloads: (concurrent: number) => void;
+  updateOptions: (options: GalleryDlOptions) => void;
 }
 
 const GalleryDlContext = createContext<GalleryDlContextType | null>(null);
@@ -126,6 +141,7 @@ export function GalleryDlProvider({ children }: { children: ReactNode }) {
       autoRetryDelaySeconds: clampAutoRetryDelaySeconds(
         saved.autoRetryDelaySeconds || AUTO_RETRY_LIMITS.delaySeconds.default,
       ),
+      options: saved.options ?? {},
     };
   });
 
@@ -151,6 +167,20 @@ export function GalleryDlProvider({ children }: { children: ReactNode }) {
     settingsRef.current = settings;
   }, [settings]);
 
+  useEffect(() => {
+    const unlisten = listen<GalleryProgress>('gallery-progress', (event) => {
+      const { id, downloaded, skipped } = event.payload;
+      setItems((current) =>
+        current.map((item) =>
+          item.id === id ? { ...item, galleryFiles: downloaded, gallerySkipped: skipped } : item,
+        ),
+      );
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   useEffect(() => {
     return () => {
       if (focusClearTimerRef.current !== null) {
@@ -226,6 +256,36 @@ export function GalleryDlProvider({ children }: { children: ReactNode }) {
     [focusItem],
   );
 
+  const enqueueExternalUrl = useCallback(
+    async (url: string, options?: GalleryDlOptions): Promise<ExternalEnqueueResult> => {
+      const normalizedUrl = url.trim();
+      if (!normalizedUrl) return { added: false, itemId: null };
+
+      const existingItem = itemsRef.current.find((item) => item.url === normalizedUrl);
+      if (existingItem) {
+        focusItem(existingItem.id);
+        return { added: false, itemId: existingItem.id };
+      }
+
+      const item: DownloadItem = {
+        id: crypto.randomUUID(),
+        url: normalizedUrl,
+        title: buildItemTitle(normalizedUrl),
+        status: 'pending',
+        progress: 0,
+        speed: '',
+        eta: '',
+        extractor: buildExtractor(normalizedUrl),
+        galleryOptions: options,
+      };
+      itemsRef.current = [...itemsRef.current, item];
+      setItems((prev) => [...prev, item]);
+      focusItem(item.id);
+      return { added: true, itemId: item.id };
+    },
+    [focusItem],
+  );
+
   const importFromFile = useCallback(async (): Promise<number> => {
     try {
       const file = await open({
@@ -306,6 +366,14 @@ export function GalleryDlProvider({ children }: { children: ReactNode }) {
     });
   }, []);
 
+  const updateOptions = useCallback((options: GalleryDlOptions) => {
+    setSettings((current) => {
+      const next = { ...current, options };
+      saveSettings(next);
+      return next;
+    });
+  }, []);
+
   const startDownload = useCallback(async () => {
     const hasPendingItems = () =>
       itemsRef.current.some((item) => item.status === 'pending' || item.status === 'error');
@@ -327,6 +395,8 @@ export function GalleryDlProvider({ children }: { children: ReactNode }) {
               eta: '',
               error: undefined,
               retryState: undefined,
+              galleryFiles: undefined,
+              gallerySkipped: undefined,
             }
           : item,
       ),
@@ -358,6 +428,9 @@ export function GalleryDlProvider({ children }: { children: ReactNode }) {
             logStderr,
             ...networkOptions,
             source: item.extractor || null,
+            id: item.id,
+            galleryOptions:
+              item.galleryOptions ?? normalizeGalleryOptions(settingsRef.current.options) ?? null,
           });
 
           setItems((current) =>
@@ -369,6 +442,8 @@ export function GalleryDlProvider({ children }: { children: ReactNode }) {
                     progress: 100,
                     completedFilepath: result.filepath,
                     completedHistoryId: result.history_id ?? undefined,
+                    galleryFiles: result.files.length,
+                    gallerySkipped: result.skipped,
                     retryState: undefined,
                   }
                 : entry,
@@ -518,6 +593,7 @@ export function GalleryDlProvider({ children }: { children: ReactNode }) {
       addFromText,
       importFromFile,
       importFromClipboard,
+      enqueueExternalUrl,
       selectOutputFolder,
       removeItem,
       clearAll,
@@ -525,6 +601,7 @@ export function GalleryDlProvider({ children }: { children: ReactNode }) {
       startDownload,
       stopDownload,
       updateConcurrentDownloads,
+      updateOptions,
     }),
     [
       items,
@@ -535,6 +612,7 @@ export function GalleryDlProvider({ children }: { children: ReactNode }) {
       addFromText,
       importFromFile,
       importFromClipboard,
+      enqueueExternalUrl,
       selectOutputFolder,
       removeItem,
       clearAll,
@@ -542,6 +620,7 @@ export function GalleryDlProvider({ children }: { children: ReactNode }) {
       startDownload,
       stopDownload,
       updateConcurrentDownloads,
+      updateOptions,
     ],
   );
 
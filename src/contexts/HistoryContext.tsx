@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import { open } from '@tauri-apps/plugin-dialog';
 import {
   createContext,
   type ReactNode,
@@ -11,7 +12,13 @@ import {
 } from 'react';
 import { syncAssetScopePaths } from '@/lib/asset-access';
 import { collectAssetScopeCandidates } from '@/lib/asset-paths';
-import { localizeUnknownError } from '@/lib/backend-error';
+import { localizeProgressError, localizeUnknownError } from '@/lib/backend-error';
+import {
+  enqueueDownload,
+  getDownloadQueueItems,
+  onDownloadQueueUpdated,
+  removeDownloadQueueItem,
+} from '@/lib/download-queue';
 import { buildCookieProxyInvokeOptions, loadNetworkSettings } from '@/lib/network-config';
 import {
   loadPluginWorkflowSnapshots,
@@ -25,6 +32,8 @@ import type {
   HistoryFilter,
   HistorySort,
   HistoryTag,
+  LibraryRelinkProgress,
+  LibraryScanProgress,
 } from '@/lib/types';
 
 // Re-download task state
@@ -51,6 +60,9 @@ interface HistoryContextType {
   totalCount: number;
   maxEntries: number;
   redownloadTasks: Map<string, RedownloadTask>;
+  libraryScan: LibraryScanProgress | null;
+  libraryRoots: string[];
+  libraryRelink: LibraryRelinkProgress | null;
   setFilter: (filter: HistoryFilter) => void;
   setSearch: (search: string) => void;
   setAdvancedFilters: (updates: Partial<HistoryAdvancedFilters>) => void;
@@ -73,12 +85,30 @@ interface HistoryContextType {
   removeHistoryFromCollection: (historyId: string, collectionId: string) => Promise<void>;
   redownload: (entry: HistoryEntry) => Promise<void>;
   getRedownloadTask: (entryId: string) => RedownloadTask | undefined;
+  scanLibraryFolder: () => Promise<LibraryScanProgress | null>;
+  cancelLibraryScan: () => Promise<void>;
+  dismissLibraryScan: () => void;
+  addLibraryRoot: () => Promise<void>;
+  removeLibraryRoot: (root: string) => void;
+  relinkLibraryFiles: () => Promise<LibraryRelinkProgress | null>;
+  relinkHistoryEntry: (historyId: string, filepath: string) => Promise<void>;
+  dismissLibraryRelink: () => void;
 }
 
 const HistoryContext = createContext<HistoryContextType | null>(null);
 
 const MAX_HISTORY_KEY = 'youwee_max_history';
 const HISTORY_SORT_KEY = 'youwee_history_sort';
+const LIBRARY_ROOTS_KEY = 'youwee_library_roots';
+
+function loadLibraryRoots(): string[] {
+  try {
+    const saved = JSON.parse(localStorage.getItem(LIBRARY_ROOTS_KEY) || '[]');
+    return Array.isArray(saved) ? saved.filter((root) => typeof root === 'string') : [];
+  } catch {
+    return [];
+  }
+}
 
 const DEFAULT_ADVANCED_FILTERS: HistoryAdvancedFilters = {
   searchScope: 'all',
@@ -242,6 +272,9 @@ export function HistoryProvider({ children }: { children: ReactNode }) {
     return saved ? parseInt(saved, 10) : 500;
   });
   const [redownloadTasks, setRedownloadTasks] = useState<Map<string, RedownloadTask>>(new Map());
+  const [libraryScan, setLibraryScan] = useState<LibraryScanProgress | null>(null);
+  const [libraryRoots, setLibraryRoots] = useState<string[]>(loadLibraryRoots);
+  const [libraryRelink, setLibraryRelink] = useState<LibraryRelinkProgress | null>(null);
   const lastAssetScopeKeyRef = useRef('');
 
   useEffect(() => {
@@ -539,7 +572,6 @@ export function HistoryProvider({ children }: { children: ReactNode }) {
 
       // Get settings from localStorage
       const logStderr = localStorage.getItem('youwee_log_stderr') !== 'false';
-      let useBunRuntime = false;
       let useActualPlayerJs = false;
       let useAria2 = false;
       let aria2Args = '';
@@ -548,7 +580,6 @@ export function HistoryProvider({ children }: { children: ReactNode }) {
         const savedSettings = localStorage.getItem('youwee-settings');
         if (savedSettings) {
           const parsed = JSON.parse(savedSettings);
-          useBunRuntime = parsed.useBunRuntime || false;
           useActualPlayerJs = parsed.useActualPlayerJs || false;
           useAria2 = parsed.useAria2 === true;
           aria2Args = parsed.aria2Args || '';
@@ -603,45 +634,35 @@ export function HistoryProvider({ children }: { children: ReactNode }) {
       });
 
       try {
-        await invoke('download_video', {
+        await enqueueDownload({
           id: downloadId,
           url: entry.url,
-          outputPath,
-          quality,
-          format,
-          downloadPlaylist: false,
-          videoCodec: 'auto',
-          audioBitrate: '192',
-          playlistLimit: null,
-          subtitleMode: 'off',
-          subtitleLangs: '',
-          subtitleEmbed: false,
-          subtitleFormat: 'srt',
-          logStderr,
-          useBunRuntime,
-          useActualPlayerJs,
-          historyId: entry.id,
-          ...networkOptions,
-          // External downloader settings
-          useAria2,
-          aria2Args,
-          pluginWorkflowSnapshots: loadPluginWorkflowSnapshots(),
-          postDownloadWorkflowSteps: loadPostDownloadWorkflowSteps(),
-          downloadKind: 'history-redownload',
-        });
-
-        // Mark as completed
-        setRedownloadTasks((prev) => {
-          const newMap = new Map(prev);
-          const task = newMap.get(entry.id);
-          if (task) {
-            newMap.set(entry.id, { ...task, status: 'completed', progress: 100 });
-          }
-          return newMap;
+          title: entry.title || null,
+          options: {
+            outputPath,
+            quality,
+            format,
+            downloadPlaylist: false,
+            videoCodec: 'auto',
+            audioBitrate: '192',
+            playlistLimit: null,
+            subtitleMode: 'off',
+            subtitleLangs: '',
+            subtitleEmbed: false,
+            subtitleFormat: 'srt',
+            logStderr,
+            useActualPlayerJs,
+            historyId: entry.id,
+            forceRedownload: true,
+            ...networkOptions,
+            // External downloader settings
+            useAria2,
+            aria2Args,
+            pluginWorkflowSnapshots: loadPluginWorkflowSnapshots(),
+            postDownloadWorkflowSteps: loadPostDownloadWorkflowSteps(),
+            downloadKind: 'history-redownload',
+          },
         });
-
-        // Refresh history to update file_exists status
-        setTimeout(() => refreshHistory(), 1000);
       } catch (error) {
         console.error('Failed to redownload:', error);
         // Mark as error
@@ -660,9 +681,55 @@ export function HistoryProvider({ children }: { children: ReactNode }) {
         throw error;
       }
     },
-    [checkFileExists, refreshHistory],
+    [checkFileExists],
   );
 
+  // Re-downloads run in the backend queue; its updates tell when one has finished
+  useEffect(() => {
+    const finishRedownload = async (downloadId: string) => {
+      const queued = (await getDownloadQueueItems()).find((item) => item.id === downloadId);
+      if (!queued || !['completed', 'failed', 'cancelled'].includes(queued.status)) return;
+
+      setRedownloadTasks((prev) => {
+        const newMap = new Map(prev);
+        for (const [entryId, task] of newMap.entries()) {
+          if (task.downloadId !== downloadId) continue;
+          newMap.set(
+            entryId,
+            queued.status === 'completed'
+              ? { ...task, status: 'completed', progress: 100 }
+              : {
+                  ...task,
+                  status: 'error',
+                  error: localizeProgressError(
+                    queued.errorCode ?? 'DOWNLOAD_CANCELLED',
+                    queued.errorMessage ?? undefined,
+                  ),
+                },
+          );
+          break;
+        }
+        return newMap;
+      });
+      await removeDownloadQueueItem(downloadId);
+
+      // Refresh history to update file_exists status
+      if (queued.status === 'completed') {
+        setTimeout(() => refreshHistory(), 1000);
+      }
+    };
+
+    const unlisten = onDownloadQueueUpdated((id) => {
+      if (!id.startsWith('redownload-')) return;
+      finishRedownload(id).catch((error) => {
+        console.error('Failed to update redownload:', error);
+      });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [refreshHistory]);
+
   const getRedownloadTask = useCallback(
     (entryId: string) => {
       return redownloadTasks.get(entryId);
@@ -689,10 +756,132 @@ export function HistoryProvider({ children }: { children: ReactNode }) {
     return () => clearInterval(interval);
   }, [refreshHistory]);
 
+  useEffect(() => {
+    const unlisten = listen<LibraryScanProgress>('library-scan-progress', (event) => {
+      setLibraryScan(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    const unlisten = listen<LibraryRelinkProgress>('library-relink-progress', (event) => {
+      setLibraryRelink(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Folders that were imported or added by hand are searched for moved files
+  const rememberLibraryRoot = useCallback((root: string) => {
+    setLibraryRoots((current) => {
+      if (current.includes(root)) return current;
+      const next = [...current, root];
+      localStorage.setItem(LIBRARY_ROOTS_KEY, JSON.stringify(next));
+      return next;
+    });
+  }, []);
+
+  const addLibraryRoot = useCallback(async () => {
+    const folder = await open({
+      directory: true,
+      multiple: false,
+      title: 'Add library folder',
+    });
+    if (folder) {
+      rememberLibraryRoot(folder as string);
+    }
+  }, [rememberLibraryRoot]);
+
+  const removeLibraryRoot = useCallback((root: string) => {
+    setLibraryRoots((current) => {
+      const next = current.filter((item) => item !== root);
+      localStorage.setItem(LIBRARY_ROOTS_KEY, JSON.stringify(next));
+      return next;
+    });
+  }, []);
+
+  const scanLibraryFolder = useCallback(async (): Promise<LibraryScanProgress | null> => {
+    const folder = await open({
+      directory: true,
+      multiple: false,
+      title: 'Import media folder',
+    });
+    if (!folder) return null;
+    rememberLibraryRoot(folder as string);
+
+    try {
+      const result = await invoke<LibraryScanProgress>('scan_library_folder', {
+        folder: folder as string,
+      });
+      setLibraryScan(result);
+      return result;
+    } finally {
+      await refreshHistory();
+    }
+  }, [refreshHistory, rememberLibraryRoot]);
+
+  const cancelLibraryScan = useCallback(async () => {
+    try {
+      await invoke('cancel_library_scan');
+    } catch (error) {
+      console.error('Failed to stop library scan:', error);
+    }
+  }, []);
+
+  const dismissLibraryScan = useCallback(() => {
+    setLibraryScan((current) => (current?.finished ? null : current));
+  }, []);
+
+  const relinkLibraryFiles = useCallback(async (): Promise<LibraryRelinkProgress | null> => {
+    if (libraryRoots.length === 0) return null;
+    try {
+      const result = await invoke<LibraryRelinkProgress>('relink_library_files', {
+        roots: libraryRoots,
+      });
+      setLibraryRelink(result);
+      return result;
+    } finally {
+      await refreshHistory();
+    }
+  }, [libraryRoots, refreshHistory]);
+
+  const relinkHistoryEntry = useCallback(
+    async (historyId: string, filepath: string) => {
+      await invoke('relink_history_entry', { id: historyId, filepath });
+      // The picked file can't be the file of another missing entry any more
+      setLibraryRelink((current) =>
+        current
+          ? {
+              ...current,
+              relinked: current.relinked + 1,
+              ambiguous: current.ambiguous
+                .filter((missing) => missing.historyId !== historyId)
+                .map((missing) => ({
+                  ...missing,
+                  candidates: missing.candidates.filter(
+                    (candidate) => candidate.filepath !== filepath,
+                  ),
+                })),
+            }
+          : current,
+      );
+      await refreshHistory();
+    },
+    [refreshHistory],
+  );
+
+  const dismissLibraryRelink = useCallback(() => {
+    setLibraryRelink((current) => (current?.finished ? null : current));
+  }, []);
+
   // Refresh history whenever any download completes (including audio)
   useEffect(() => {
     const unlisten = listen<DownloadProgress>('download-progress', (event) => {
-      if (event.payload.status === 'finished') {
+      // Playlist downloads add a history entry per finished entry
+      if (event.payload.status === 'finished' || event.payload.status === 'item_finished') {
         // Delay slightly to ensure Rust has finished writing the DB record
         setTimeout(() => refreshHistory(), 800);
       }
@@ -717,6 +906,9 @@ export function HistoryProvider({ children }: { children: ReactNode }) {
         totalCount,
         maxEntries,
         redownloadTasks,
+        libraryScan,
+        libraryRoots,
+        libraryRelink,
         setFilter,
         setSearch,
         setAdvancedFilters,
@@ -739,6 +931,14 @@ export function HistoryProvider({ children }: { children: ReactNode }) {
         removeHistoryFromCollection,
         redownload,
         getRedownloadTask,
+        scanLibraryFolder,
+        cancelLibraryScan,
+        dismissLibraryScan,
+        addLibraryRoot,
+        removeLibraryRoot,
+        relinkLibraryFiles,
+        relinkHistoryEntry,
+        dismissLibraryRelink,
       }}
     >
       {children}
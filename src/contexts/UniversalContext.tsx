@@ -13,20 +13,15 @@ import {
   useRef,
   useState,
 } from 'react';
+import { useBackendDownloadQueue } from '@/hooks/useBackendDownloadQueue';
 import { usePersistedDownloadQueue } from '@/hooks/usePersistedDownloadQueue';
-import {
-  extractBackendError,
-  localizeBackendError,
-  localizeProgressError,
-} from '@/lib/backend-error';
+import { localizeProgressError } from '@/lib/backend-error';
 import {
   AUTO_RETRY_LIMITS,
   clampAutoRetryDelaySeconds,
   clampAutoRetryMaxAttempts,
-  isNonRetryableError,
-  isRetryableError,
-  waitWithCancellation,
 } from '@/lib/download-retry';
+import { normalizeFormatPreferences } from '@/lib/format-preferences';
 import {
   buildCookieProxyInvokeOptions,
   loadCookieSettings,
@@ -39,6 +34,7 @@ import {
   refreshPostDownloadWorkflowSteps,
 } from '@/lib/post-download-plugins';
 import { parseUniversalUrls } from '@/lib/sources';
+import { formatTimeRanges, toDownloadSections } from '@/lib/time-ranges';
 import type {
   AudioBitrate,
   DownloadItem,
@@ -46,16 +42,17 @@ import type {
   ExternalEnqueueOptions,
   ExternalEnqueueResult,
   Format,
+  FormatPreferences,
   ItemUniversalSettings,
   PostDownloadPluginPayload,
   Quality,
+  TimeRange,
   VideoInfoResponse,
 } from '@/lib/types';
 import { useDownload } from './DownloadContext';
 
 const STORAGE_KEY = 'youwee-universal-settings';
 const DOWNLOAD_STORAGE_KEY = 'youwee-settings';
-const DOWNLOAD_QUEUE_IDLE_GRACE_MS = 1000;
 
 // Format duration in seconds to HH:MM:SS or MM:SS
 function formatDuration(seconds: number): string {
@@ -135,7 +132,12 @@ function loadSavedSettings(): Partial<UniversalSettings> {
 }
 
 // Load embed settings from main download settings
-function loadEmbedSettings(): { embedMetadata: boolean; embedThumbnail: boolean } {
+function loadEmbedSettings(): {
+  embedMetadata: boolean;
+  embedThumbnail: boolean;
+  splitChapters: boolean;
+  verifyDownloads: boolean;
+} {
   try {
     const saved = localStorage.getItem(DOWNLOAD_STORAGE_KEY);
     if (saved) {
@@ -143,12 +145,19 @@ function loadEmbedSettings(): { embedMetadata: boolean; embedThumbnail: boolean
       return {
         embedMetadata: parsed.embedMetadata !== false, // Default true
         embedThumbnail: parsed.embedThumbnail === true, // Default false (requires FFmpeg)
+        splitChapters: parsed.splitChapters === true, // Default false
+        verifyDownloads: parsed.verifyDownloads === true, // Default false (requires FFprobe)
       };
     }
   } catch (e) {
     console.error('Failed to load embed settings:', e);
   }
-  return { embedMetadata: true, embedThumbnail: false };
+  return {
+    embedMetadata: true,
+    embedThumbnail: false,
+    splitChapters: false,
+    verifyDownloads: false,
+  };
 }
 
 // Load SponsorBlock settings from main download settings
@@ -197,6 +206,44 @@ function loadAria2Settings(): { useAria2: boolean; aria2Args: string } {
   return { useAria2: false, aria2Args: '' };
 }
 
+function loadOutputTemplate(): string {
+  try {
+    const saved = localStorage.getItem(DOWNLOAD_STORAGE_KEY);
+    if (saved) {
+      const parsed = JSON.parse(saved);
+      return typeof parsed.outputTemplate === 'string' ? parsed.outputTemplate : '';
+    }
+  } catch (e) {
+    console.error('Failed to load output template:', e);
+  }
+  return '';
+}
+
+function loadDownloadProfile(): string {
+  try {
+    const saved = localStorage.getItem(DOWNLOAD_STORAGE_KEY);
+    if (saved) {
+      const parsed = JSON.parse(saved);
+      return typeof parsed.downloadProfile === 'string' ? parsed.downloadProfile : '';
+    }
+  } catch (e) {
+    console.error('Failed to load download profile:', e);
+  }
+  return '';
+}
+
+function loadFormatPreferences(): FormatPreferences | null {
+  try {
+    const saved = localStorage.getItem(DOWNLOAD_STORAGE_KEY);
+    if (saved) {
+      return normalizeFormatPreferences(JSON.parse(saved).formatPreferences);
+    }
+  } catch (e) {
+    console.error('Failed to load format preferences:', e);
+  }
+  return null;
+}
+
 // Save settings to localStorage
 function saveSettings(settings: UniversalSettings) {
   try {
@@ -242,6 +289,8 @@ interface UniversalContextType {
   clearCompleted: () => void;
   startDownload: () => Promise<void>;
   stopDownload: () => Promise<void>;
+  pauseItem: (id: string) => Promise<void>;
+  resumeItem: (id: string) => Promise<void>;
   updateQuality: (quality: Quality) => void;
   updateFormat: (format: Format) => void;
   updateAudioBitrate: (bitrate: AudioBitrate) => void;
@@ -253,8 +302,9 @@ interface UniversalContextType {
   cookieError: { show: boolean; itemId?: string; kind: 'db_locked' | 'fresh_cookies' } | null;
   clearCookieError: () => void;
   retryFailedDownload: (itemId: string) => void;
+  redownloadItem: (itemId: string) => void;
   // Per-item time range
-  updateItemTimeRange: (id: string, start?: string, end?: string) => void;
+  updateItemTimeRanges: (id: string, ranges: TimeRange[], join: boolean, precise: boolean) => void;
   // Rename completed file
   renameCompletedItem: (id: string, newName: string) => Promise<void>;
 }
@@ -269,7 +319,6 @@ interface RenameDownloadedFileResult {
 export function UniversalProvider({ children }: { children: ReactNode }) {
   const [items, setItems] = useState<DownloadItem[]>([]);
   const [focusedItemId, setFocusedItemId] = useState<string | null>(null);
-  const [isDownloading, setIsDownloading] = useState(false);
   const [cookieError, setCookieError] = useState<{
     show: boolean;
     itemId?: string;
@@ -303,7 +352,6 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
     };
   });
 
-  const isDownloadingRef = useRef(false);
   const itemsRef = useRef<DownloadItem[]>([]);
   const settingsRef = useRef<UniversalSettings>(settings);
   const focusClearTimerRef = useRef<number | null>(null);
@@ -361,6 +409,29 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
     const unlisten = listen<DownloadProgress>('download-progress', (event) => {
       const progress = event.payload;
 
+      // One entry of a playlist download finished or failed; the job itself keeps going
+      if (progress.status === 'item_finished' || progress.status === 'item_error') {
+        if (progress.status === 'item_error') {
+          const failure = {
+            index: progress.playlist_index ?? 0,
+            error:
+              localizeProgressError(
+                progress.error_code,
+                progress.error_message,
+                progress.error_params,
+              ) || '',
+          };
+          setItems((currentItems) =>
+            currentItems.map((item) =>
+              item.id === progress.id
+                ? { ...item, playlistFailures: [...(item.playlistFailures ?? []), failure] }
+                : item,
+            ),
+          );
+        }
+        return;
+      }
+
       // Detect cookie error on Windows (lock error or DPAPI/App-Bound Encryption)
       const cookieDbLockedPattern =
         /could not copy.*cookie|permission denied.*cookies|cookie.*database|failed to.*cookie|failed to decrypt.*dpapi|app.bound.encryption/i;
@@ -398,6 +469,7 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
                 retryState: undefined,
                 downloadedSize: progress.downloaded_size,
                 elapsedTime: progress.elapsed_time,
+                phase: progress.status === 'downloading' ? progress.phase : undefined,
                 // Auto-detect live stream if we receive downloaded_size (live stream format)
                 isLive: progress.downloaded_size ? true : item.isLive,
                 // Store completed info when finished
@@ -463,10 +535,7 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
     for (const item of queuedItems) {
       const itemSettings = item.settings as ItemUniversalSettings | undefined;
       const workflowSnapshots = itemSettings?.pluginWorkflowSnapshots;
-      const timeRange =
-        itemSettings?.timeRangeStart && itemSettings?.timeRangeEnd
-          ? `${itemSettings.timeRangeStart}-${itemSettings.timeRangeEnd}`
-          : null;
+      const timeRange = formatTimeRanges(itemSettings?.timeRanges);
       const payload: PostDownloadPluginPayload = {
         jobId: item.id,
         source: item.extractor || null,
@@ -496,43 +565,6 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
     }
   }, []);
 
-  const enqueueFailedWorkflowForItem = useCallback(
-    (item: DownloadItem, itemSettings: ItemUniversalSettings | undefined) => {
-      const workflowSnapshots = itemSettings?.pluginWorkflowSnapshots;
-      const timeRange =
-        itemSettings?.timeRangeStart && itemSettings?.timeRangeEnd
-          ? `${itemSettings.timeRangeStart}-${itemSettings.timeRangeEnd}`
-          : null;
-      const payload: PostDownloadPluginPayload = {
-        jobId: item.id,
-        source: item.extractor || null,
-        trigger: 'download.failed',
-        filepath: '',
-        filename: item.title || item.url,
-        directory: itemSettings?.outputPath ?? settings.outputPath,
-        filesize: item.filesize ?? null,
-        format: itemSettings?.format ?? settings.format,
-        quality: itemSettings?.quality ?? settings.quality,
-        url: item.url,
-        title: item.title || null,
-        thumbnail: item.thumbnail || null,
-        historyId: null,
-        timeRange,
-        downloadKind: 'universal',
-        workflowRunId: null,
-        workflowStepIndex: null,
-        workflowStepPluginId: null,
-        chainState: null,
-      };
-      void enqueuePluginWorkflowTrigger('download.failed', payload, workflowSnapshots).catch(
-        (error) => {
-          console.error('Failed to enqueue universal download.failed workflow:', error);
-        },
-      );
-    },
-    [settings.format, settings.outputPath, settings.quality],
-  );
-
   const addFromText = useCallback(
     async (text: string): Promise<number> => {
       const urls = parseUniversalUrls(text);
@@ -551,6 +583,9 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
         audioBitrate: currentSettings.audioBitrate,
         useAria2: aria2Settings.useAria2,
         aria2Args: aria2Settings.aria2Args,
+        outputTemplate: loadOutputTemplate(),
+        profile: loadDownloadProfile() || undefined,
+        formatPreferences: loadFormatPreferences(),
         liveFromStart: currentSettings.liveFromStart,
         skipLive: currentSettings.skipLive,
         pluginWorkflowSnapshots: workflowSnapshots,
@@ -637,8 +672,12 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
         audioBitrate: mediaType === 'audio' ? audioBitrate : currentSettings.audioBitrate,
         useAria2: aria2Settings.useAria2,
         aria2Args: aria2Settings.aria2Args,
-        timeRangeStart: options?.timeRangeStart,
-        timeRangeEnd: options?.timeRangeEnd,
+        outputTemplate: loadOutputTemplate(),
+        profile: options?.profile ?? (loadDownloadProfile() || undefined),
+        formatPreferences: loadFormatPreferences(),
+        timeRanges: options?.timeRanges,
+        joinTimeRanges: options?.joinTimeRanges,
+        preciseTimeRanges: options?.preciseTimeRanges,
         liveFromStart: options?.liveFromStart ?? currentSettings.liveFromStart,
         skipLive: options?.skipLive ?? currentSettings.skipLive,
         pluginWorkflowSnapshots: workflowSnapshots,
@@ -719,26 +758,125 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
     }
   }, [settings.outputPath]);
 
-  const removeItem = useCallback((id: string) => {
-    setItems((items) => {
-      const nextItems = items.filter((item) => item.id !== id);
-      itemsRef.current = nextItems;
-      return nextItems;
-    });
-  }, []);
+  // Options sent with an item to the backend queue: its settings snapshot, with the
+  // current settings for anything the snapshot does not cover
+  const buildDownloadOptions = useCallback(
+    (item: DownloadItem): Record<string, unknown> => {
+      const itemSettings = item.settings as ItemUniversalSettings | undefined;
+      const logStderr = localStorage.getItem('youwee_log_stderr') !== 'false';
+      const cookieSettings = loadCookieSettings();
+      const proxySettings = loadProxySettings();
+      const networkOptions = buildCookieProxyInvokeOptions(cookieSettings, proxySettings);
+      const embedSettings = loadEmbedSettings();
+      const sponsorBlockArgs = loadSponsorBlockArgs();
+      const aria2Settings = loadAria2Settings();
 
-  const updateItemTimeRange = useCallback((id: string, start?: string, end?: string) => {
-    setItems((items) =>
-      items.map((item) => {
-        if (item.id !== id || !item.settings) return item;
-        const settings = item.settings as ItemUniversalSettings;
-        return {
-          ...item,
-          settings: { ...settings, timeRangeStart: start, timeRangeEnd: end },
-        };
-      }),
-    );
-  }, []);
+      return {
+        outputPath: itemSettings?.outputPath || settings.outputPath,
+        quality: itemSettings?.quality ?? settings.quality,
+        format: itemSettings?.format ?? settings.format,
+        downloadPlaylist: false,
+        videoCodec: 'auto', // Use auto for universal downloads
+        audioBitrate: itemSettings?.audioBitrate ?? settings.audioBitrate,
+        playlistLimit: null,
+        subtitleMode: 'off',
+        subtitleLangs: '',
+        subtitleEmbed: false,
+        subtitleFormat: 'srt',
+        // Logging settings
+        logStderr,
+        // Cookie settings
+        ...networkOptions,
+        // Post-processing settings (from main download settings)
+        embedMetadata: embedSettings.embedMetadata,
+        embedThumbnail: embedSettings.embedThumbnail,
+        splitChapters: embedSettings.splitChapters,
+        verifyOutput: embedSettings.verifyDownloads,
+        // Live stream settings
+        liveFromStart: itemSettings?.liveFromStart ?? settings.liveFromStart,
+        skipLive: itemSettings?.skipLive ?? false,
+        // Speed limit settings
+        speedLimit: settings.speedLimitEnabled
+          ? `${settings.speedLimitValue}${settings.speedLimitUnit}`
+          : null,
+        // External downloader settings (from item snapshot, fallback to global settings)
+        useAria2: itemSettings?.useAria2 ?? aria2Settings.useAria2,
+        aria2Args: itemSettings?.aria2Args ?? aria2Settings.aria2Args,
+        // SponsorBlock settings
+        sponsorblockRemove: sponsorBlockArgs.remove,
+        sponsorblockMark: sponsorBlockArgs.mark,
+        // Download sections (time ranges), one clip per range unless joined
+        downloadSections: toDownloadSections(itemSettings?.timeRanges),
+        joinSections: itemSettings?.joinTimeRanges ?? false,
+        preciseSections: itemSettings?.preciseTimeRanges ?? false,
+        // Thumbnail from video info fetch (for non-YouTube sites)
+        thumbnail: item.thumbnail || null,
+        // Source/extractor from video info fetch (e.g. "BiliBili", "TikTok")
+        source: item.extractor || null,
+        pluginWorkflowSnapshots:
+          itemSettings?.pluginWorkflowSnapshots ?? loadPluginWorkflowSnapshots(),
+        postDownloadWorkflowSteps:
+          itemSettings?.postDownloadWorkflowSteps ?? loadPostDownloadWorkflowSteps(),
+        downloadKind: 'universal',
+        forceRedownload: itemSettings?.forceRedownload ?? false,
+        outputTemplate: itemSettings?.outputTemplate ?? loadOutputTemplate(),
+        profile: itemSettings?.profile ?? (loadDownloadProfile() || null),
+        formatPreferences: itemSettings
+          ? (itemSettings.formatPreferences ?? null)
+          : loadFormatPreferences(),
+      };
+    },
+    [settings],
+  );
+
+  const {
+    isDownloading,
+    startDownload,
+    stopDownload,
+    pauseItem,
+    resumeItem,
+    forgetItems,
+  } = useBackendDownloadQueue({
+    queueKind: 'universal',
+    items,
+    itemsRef,
+    setItems,
+    buildOptions: buildDownloadOptions,
+    logLabel: 'universal queue',
+  });
+
+  const removeItem = useCallback(
+    (id: string) => {
+      forgetItems([id]);
+      setItems((items) => {
+        const nextItems = items.filter((item) => item.id !== id);
+        itemsRef.current = nextItems;
+        return nextItems;
+      });
+    },
+    [forgetItems],
+  );
+
+  const updateItemTimeRanges = useCallback(
+    (id: string, ranges: TimeRange[], join: boolean, precise: boolean) => {
+      setItems((items) =>
+        items.map((item) => {
+          if (item.id !== id || !item.settings) return item;
+          const settings = item.settings as ItemUniversalSettings;
+          return {
+            ...item,
+            settings: {
+              ...settings,
+              timeRanges: ranges,
+              joinTimeRanges: join,
+              preciseTimeRanges: precise,
+            },
+          };
+        }),
+      );
+    },
+    [],
+  );
 
   const renameCompletedItem = useCallback(async (id: string, newName: string) => {
     const item = itemsRef.current.find((i) => i.id === id);
@@ -782,11 +920,17 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
   }, []);
 
   const clearAll = useCallback(() => {
+    forgetItems(itemsRef.current.map((item) => item.id));
     itemsRef.current = [];
     setItems([]);
-  }, []);
+  }, [forgetItems]);
 
   const clearCompleted = useCallback(() => {
+    forgetItems(
+      itemsRef.current
+        .filter((item) => item.status === 'completed' || item.status === 'skipped')
+        .map((item) => item.id),
+    );
     setItems((items) => {
       const nextItems = items.filter(
         (item) => item.status !== 'completed' && item.status !== 'skipped',
@@ -794,303 +938,7 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
       itemsRef.current = nextItems;
       return nextItems;
     });
-  }, []);
-
-  const startDownload = useCallback(async () => {
-    const hasPendingItems = () =>
-      itemsRef.current.some((item) => item.status === 'pending' || item.status === 'error');
-
-    if (!hasPendingItems()) return;
-
-    setIsDownloading(true);
-    isDownloadingRef.current = true;
-
-    // Reset pending/error items
-    setItems((items) =>
-      items.map((item) => {
-        if (item.status === 'pending' || item.status === 'error') {
-          return {
-            ...item,
-            status: 'pending' as const,
-            progress: 0,
-            speed: '',
-            eta: '',
-            error: undefined,
-            retryState: undefined,
-          };
-        }
-        return item;
-      }),
-    );
-
-    const concurrentLimit = Math.max(1, settings.concurrentDownloads || 1);
-
-    const downloadItem = async (item: DownloadItem) => {
-      if (!isDownloadingRef.current) return;
-
-      // Use item's saved settings (snapshot from when it was added)
-      // Fallback to current global settings if not available
-      const itemSettings = item.settings as ItemUniversalSettings | undefined;
-      const logStderr = localStorage.getItem('youwee_log_stderr') !== 'false';
-      const cookieSettings = loadCookieSettings();
-      const proxySettings = loadProxySettings();
-      const networkOptions = buildCookieProxyInvokeOptions(cookieSettings, proxySettings);
-      const embedSettings = loadEmbedSettings();
-      const sponsorBlockArgs = loadSponsorBlockArgs();
-      const aria2Settings = loadAria2Settings();
-
-      const autoRetryEnabled = itemSettings?.autoRetryEnabled ?? settings.autoRetryEnabled;
-      const maxRetries = clampAutoRetryMaxAttempts(
-        itemSettings?.autoRetryMaxAttempts ?? settings.autoRetryMaxAttempts,
-      );
-      const retryDelaySeconds = clampAutoRetryDelaySeconds(
-        itemSettings?.autoRetryDelaySeconds ?? settings.autoRetryDelaySeconds,
-      );
-
-      let retryIndex = 0;
-
-      while (isDownloadingRef.current) {
-        setItems((items) =>
-          items.map((i) =>
-            i.id === item.id
-              ? {
-                  ...i,
-                  status: 'downloading',
-                  error: undefined,
-                  errorCode: undefined,
-                  retryState: undefined,
-                }
-              : i,
-          ),
-        );
-
-        try {
-          await invoke('download_video', {
-            id: item.id,
-            url: item.url,
-            outputPath: itemSettings?.outputPath || settings.outputPath,
-            quality: itemSettings?.quality ?? settings.quality,
-            format: itemSettings?.format ?? settings.format,
-            downloadPlaylist: false,
-            videoCodec: 'auto', // Use auto for universal downloads
-            audioBitrate: itemSettings?.audioBitrate ?? settings.audioBitrate,
-            playlistLimit: null,
-            subtitleMode: 'off',
-            subtitleLangs: '',
-            subtitleEmbed: false,
-            subtitleFormat: 'srt',
-            // Logging settings
-            logStderr,
-            // Cookie settings
-            ...networkOptions,
-            // Post-processing settings (from main download settings)
-            embedMetadata: embedSettings.embedMetadata,
-            embedThumbnail: embedSettings.embedThumbnail,
-            // Live stream settings
-            liveFromStart: itemSettings?.liveFromStart ?? settings.liveFromStart,
-            skipLive: itemSettings?.skipLive ?? false,
-            // Speed limit settings
-            speedLimit: settings.speedLimitEnabled
-              ? `${settings.speedLimitValue}${settings.speedLimitUnit}`
-              : null,
-            // External downloader settings (from item snapshot, fallback to global settings)
-            useAria2: itemSettings?.useAria2 ?? aria2Settings.useAria2,
-            aria2Args: itemSettings?.aria2Args ?? aria2Settings.aria2Args,
-            // SponsorBlock settings
-            sponsorblockRemove: sponsorBlockArgs.remove,
-            sponsorblockMark: sponsorBlockArgs.mark,
-            // Download sections (time range)
-            downloadSections:
-              itemSettings?.timeRangeStart && itemSettings?.timeRangeEnd
-                ? `*${itemSettings.timeRangeStart}-${itemSettings.timeRangeEnd}`
-                : null,
-            // Title from video info fetch
-            title: item.title || null,
-            // Thumbnail from video info fetch (for non-YouTube sites)
-            thumbnail: item.thumbnail || null,
-            // Source/extractor from video info fetch (e.g. "BiliBili", "TikTok")
-            source: item.extractor || null,
-            pluginWorkflowSnapshots:
-              itemSettings?.pluginWorkflowSnapshots ?? loadPluginWorkflowSnapshots(),
-            postDownloadWorkflowSteps:
-              itemSettings?.postDownloadWorkflowSteps ?? loadPostDownloadWorkflowSteps(),
-            emitFailedWorkflow: false,
-            downloadKind: 'universal',
-          });
-
-          setItems((items) =>
-            items.map((i) =>
-              i.id === item.id
-                ? { ...i, status: 'completed', progress: 100, retryState: undefined }
-                : i,
-            ),
-          );
-          return;
-        } catch (error) {
-          const parsedError = extractBackendError(error);
-          const errorMessage = localizeBackendError(parsedError);
-          if (parsedError.code === 'YT_SKIPPED_LIVE') {
-            setItems((items) =>
-              items.map((i) =>
-                i.id === item.id
-                  ? {
-                      ...i,
-                      status: 'skipped',
-                      progress: 0,
-                      error: errorMessage,
-                      errorCode: parsedError.code,
-                      retryState: undefined,
-                    }
-                  : i,
-              ),
-            );
-            return;
-          }
-          const canRetry =
-            isDownloadingRef.current &&
-            autoRetryEnabled &&
-            retryIndex < maxRetries &&
-            !isNonRetryableError(parsedError.message, parsedError.code) &&
-            isRetryableError(parsedError.message, parsedError.code, parsedError.retryable);
-
-          if (!canRetry) {
-            enqueueFailedWorkflowForItem(item, itemSettings);
-            setItems((items) =>
-              items.map((i) =>
-                i.id === item.id
-                  ? {
-                      ...i,
-                      status: 'error',
-                      error: errorMessage,
-                      errorCode: parsedError.code,
-                      retryState: undefined,
-                    }
-                  : i,
-              ),
-            );
-            return;
-          }
-
-          retryIndex += 1;
-          setItems((items) =>
-            items.map((i) =>
-              i.id === item.id
-                ? {
-                    ...i,
-                    status: 'pending',
-                    error: errorMessage,
-                    errorCode: parsedError.code,
-                    retryState: {
-                      retryIndex,
-                      maxRetries,
-                      delaySeconds: retryDelaySeconds,
-                      remainingSeconds: retryDelaySeconds,
-                    },
-                  }
-                : i,
-            ),
-          );
-
-          const shouldContinue = await waitWithCancellation(
-            retryDelaySeconds * 1000,
-            () => !isDownloadingRef.current,
-            (remainingSeconds) => {
-              setItems((items) =>
-                items.map((i) =>
-                  i.id === item.id && i.retryState
-                    ? {
-                        ...i,
-                        retryState: {
-                          ...i.retryState,
-                          remainingSeconds,
-                        },
-                      }
-                    : i,
-                ),
-              );
-            },
-          );
-
-          if (!shouldContinue) {
-            return;
-          }
-        }
-      }
-    };
-
-    try {
-      const claimedIds = new Set<string>();
-      const processedIds = new Set<string>();
-      let activeCount = 0;
-
-      const claimNextItem = (): DownloadItem | null => {
-        const next = itemsRef.current.find(
-          (candidate) =>
-            (candidate.status === 'pending' || candidate.status === 'error') &&
-            !claimedIds.has(candidate.id) &&
-            !processedIds.has(candidate.id),
-        );
-        if (!next) return null;
-        claimedIds.add(next.id);
-        return next;
-      };
-
-      const hasUnclaimedPendingItems = () =>
-        itemsRef.current.some(
-          (candidate) =>
-            (candidate.status === 'pending' || candidate.status === 'error') &&
-            !claimedIds.has(candidate.id) &&
-            !processedIds.has(candidate.id),
-        );
-
-      const processNext = async (): Promise<void> => {
-        while (isDownloadingRef.current) {
-          const item = claimNextItem();
-          if (!item) {
-            if (activeCount === 0 && !hasUnclaimedPendingItems()) {
-              await new Promise<void>((resolve) => {
-                window.setTimeout(resolve, DOWNLOAD_QUEUE_IDLE_GRACE_MS);
-              });
-              if (!isDownloadingRef.current || !hasUnclaimedPendingItems()) {
-                return;
-              }
-              continue;
-            }
-            await new Promise<void>((resolve) => {
-              window.setTimeout(resolve, 200);
-            });
-            continue;
-          }
-
-          activeCount += 1;
-          try {
-            await downloadItem(item);
-          } finally {
-            activeCount -= 1;
-            claimedIds.delete(item.id);
-            processedIds.add(item.id);
-          }
-        }
-      };
-
-      const workers = Array.from({ length: concurrentLimit }, () => processNext());
-      await Promise.all(workers);
-    } finally {
-      setIsDownloading(false);
-      isDownloadingRef.current = false;
-    }
-  }, [enqueueFailedWorkflowForItem, settings]);
-
-  const stopDownload = useCallback(async () => {
-    try {
-      await invoke('stop_download');
-    } catch (error) {
-      console.error('Failed to stop download:', error);
-    }
-    setItems((items) => items.map((item) => ({ ...item, retryState: undefined })));
-    setIsDownloading(false);
-    isDownloadingRef.current = false;
-  }, []);
+  }, [forgetItems]);
 
   const updateQuality = useCallback((quality: Quality) => {
     setSettings((s) => {
@@ -1176,6 +1024,7 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
                 error: undefined,
                 errorCode: undefined,
                 retryState: undefined,
+                playlistFailures: undefined,
               }
             : item,
         ),
@@ -1190,6 +1039,32 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
     [startDownload],
   );
 
+  const redownloadItem = useCallback(
+    (itemId: string) => {
+      // Download an archived item again, bypassing the download archive
+      setItems((currentItems) =>
+        currentItems.map((item) =>
+          item.id === itemId && item.settings
+            ? {
+                ...item,
+                status: 'pending',
+                progress: 0,
+                error: undefined,
+                errorCode: undefined,
+                retryState: undefined,
+                playlistFailures: undefined,
+                settings: { ...(item.settings as ItemUniversalSettings), forceRedownload: true },
+              }
+            : item,
+        ),
+      );
+      setTimeout(() => {
+        startDownload();
+      }, 100);
+    },
+    [startDownload],
+  );
+
   const value: UniversalContextType = useMemo(
     () => ({
       items,
@@ -1207,6 +1082,8 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
       clearCompleted,
       startDownload,
       stopDownload,
+      pauseItem,
+      resumeItem,
       updateQuality,
       updateFormat,
       updateAudioBitrate,
@@ -1218,8 +1095,9 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
       cookieError,
       clearCookieError,
       retryFailedDownload,
+      redownloadItem,
       // Per-item time range
-      updateItemTimeRange,
+      updateItemTimeRanges,
       renameCompletedItem,
     }),
     [
@@ -1238,6 +1116,8 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
       clearCompleted,
       startDownload,
       stopDownload,
+      pauseItem,
+      resumeItem,
       updateQuality,
       updateFormat,
       updateAudioBitrate,
@@ -1248,7 +1128,8 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
       cookieError,
       clearCookieError,
       retryFailedDownload,
-      updateItemTimeRange,
+      redownloadItem,
+      updateItemTimeRanges,
       renameCompletedItem,
     ],
   );
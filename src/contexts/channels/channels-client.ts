@@ -28,6 +28,7 @@ export type ChannelAutoDownloadEvent = {
   format: string;
   video_codec: string;
   audio_bitrate: string;
+  profile: string | null;
   download_threads: number;
 };
 
@@ -86,6 +87,7 @@ export async function updateChannelSettingsCommand(input: {
   filterMaxVideos: number | null;
   downloadThreads: number;
   youtubeContentType: YoutubeChannelContentType;
+  downloadProfile: string | null;
 }): Promise<void> {
   await invoke('update_channel_settings', input);
 }
@@ -140,12 +142,16 @@ export async function getSavedChannelVideosByVideoIds(input: {
   return invoke<ChannelVideo[]>('get_saved_channel_videos_by_video_ids', input);
 }
 
-export async function downloadVideoCommand(input: Record<string, unknown>): Promise<void> {
-  await invoke('download_video', input);
+export async function downloadVideoCommand(request: Record<string, unknown>): Promise<void> {
+  await invoke('download_video', { request });
 }
 
-export async function stopDownloadCommand(): Promise<void> {
-  await invoke('stop_download');
+export async function enqueueDownloadCommand(item: Record<string, unknown>): Promise<void> {
+  await invoke('enqueue_download', { item });
+}
+
+export async function stopDownloadCommand(id: string): Promise<void> {
+  await invoke('stop_download', { id });
 }
 
 export async function updateChannelVideoStatusByVideoId(input: {
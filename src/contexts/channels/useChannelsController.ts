@@ -20,6 +20,7 @@ import { persistManualChannelDownloadCompletion } from './channel-downloads';
 import {
   type ChannelAutoDownloadEvent,
   downloadVideoCommand,
+  enqueueDownloadCommand,
   followChannelCommand,
   getChannelInfo,
   getChannelVideos,
@@ -218,6 +219,7 @@ export interface ChannelsContextType {
     filterMaxVideos?: number | null;
     downloadThreads?: number;
     youtubeContentType?: YoutubeChannelContentType;
+    downloadProfile?: string | null;
   }) => Promise<void>;
 
   // Channel browsing
@@ -512,6 +514,7 @@ export function useChannelsController(): ChannelsContextType {
       filterMaxVideos?: number | null;
       downloadThreads?: number;
       youtubeContentType?: YoutubeChannelContentType;
+      downloadProfile?: string | null;
     }) => {
       await updateChannelSettingsCommand({
         id: settings.id,
@@ -528,6 +531,7 @@ export function useChannelsController(): ChannelsContextType {
         filterMaxVideos: settings.filterMaxVideos ?? null,
         downloadThreads: settings.downloadThreads ?? 1,
         youtubeContentType: settings.youtubeContentType ?? DEFAULT_YOUTUBE_CONTENT_TYPE,
+        downloadProfile: settings.downloadProfile ?? null,
       });
       await refreshChannels();
     },
@@ -758,14 +762,12 @@ export function useChannelsController(): ChannelsContextType {
       let subtitleEmbed = false;
       let subtitleFormat = 'srt';
       let logStderr = true;
-      let useBunRuntime = false;
       let useActualPlayerJs = false;
       let useAria2 = false;
       let aria2Args = '';
       let embedMetadata = false;
       let embedThumbnail = false;
       let liveFromStart = false;
-      let speedLimit: string | null = null;
       let sponsorBlockArgs = { remove: null as string | null, mark: null as string | null };
 
       try {
@@ -781,16 +783,12 @@ export function useChannelsController(): ChannelsContextType {
           subtitleLangs = parsed.subtitleLangs || [];
           subtitleEmbed = parsed.subtitleEmbed || false;
           subtitleFormat = parsed.subtitleFormat || 'srt';
-          useBunRuntime = parsed.useBunRuntime || false;
           useActualPlayerJs = parsed.useActualPlayerJs || false;
           useAria2 = parsed.useAria2 === true;
           aria2Args = parsed.aria2Args || '';
           embedMetadata = parsed.embedMetadata || false;
           embedThumbnail = parsed.embedThumbnail || false;
           liveFromStart = parsed.liveFromStart || false;
-          if (parsed.speedLimitEnabled && parsed.speedLimitValue) {
-            speedLimit = `${parsed.speedLimitValue}${parsed.speedLimitUnit || 'M'}`;
-          }
           sponsorBlockArgs = buildSponsorBlockArgs(parsed);
         }
         logStderr = localStorage.getItem('youwee_log_stderr') !== 'false';
@@ -899,13 +897,11 @@ export function useChannelsController(): ChannelsContextType {
                 subtitleEmbed,
                 subtitleFormat,
                 logStderr,
-                useBunRuntime,
                 useActualPlayerJs,
                 ...networkOptions,
                 embedMetadata,
                 embedThumbnail,
                 liveFromStart,
-                speedLimit,
                 useAria2,
                 aria2Args,
                 sponsorblockRemove: sponsorBlockArgs.remove,
@@ -970,7 +966,8 @@ export function useChannelsController(): ChannelsContextType {
   // Stop all downloads
   const stopDownload = useCallback(async () => {
     try {
-      await stopDownloadCommand();
+      const activeIds = Array.from(downloadIdMapRef.current.keys());
+      await Promise.all(activeIds.map((id) => stopDownloadCommand(id)));
     } catch (error) {
       console.error('Failed to stop downloads:', error);
     } finally {
@@ -1176,15 +1173,8 @@ export function useChannelsController(): ChannelsContextType {
   // Listen for auto-download events from backend polling
   useEffect(() => {
     const unlisten = onChannelAutoDownload(async (event: { payload: ChannelAutoDownloadEvent }) => {
-      const {
-        channel_id,
-        channel_name,
-        quality,
-        format,
-        video_codec,
-        audio_bitrate,
-        download_threads,
-      } = event.payload;
+      const { channel_id, channel_name, quality, format, video_codec, audio_bitrate, profile } =
+        event.payload;
 
       try {
         const newVideos = await getSavedChannelVideos({
@@ -1197,7 +1187,6 @@ export function useChannelsController(): ChannelsContextType {
 
         let autoOutputPath = '';
         let logStderr = true;
-        let useBunRuntime = false;
         let useActualPlayerJs = false;
         let useAria2 = false;
         let aria2Args = '';
@@ -1207,7 +1196,6 @@ export function useChannelsController(): ChannelsContextType {
           if (saved) {
             const parsed = JSON.parse(saved);
             autoOutputPath = parsed.outputPath || '';
-            useBunRuntime = parsed.useBunRuntime || false;
             useActualPlayerJs = parsed.useActualPlayerJs || false;
             useAria2 = parsed.useAria2 === true;
             aria2Args = parsed.aria2Args || '';
@@ -1225,104 +1213,43 @@ export function useChannelsController(): ChannelsContextType {
 
         const networkOptions = getNetworkOptions();
 
-        const maxConcurrent = Math.max(1, download_threads || 1);
         const workflowSnapshots = loadPluginWorkflowSnapshots();
-        const queuedAutoDownloads = newVideos.map((video) => ({
-          video,
-          downloadId: `auto-${video.video_id}-${Date.now()}-${crypto.randomUUID()}`,
-        }));
-
-        for (const { video, downloadId } of queuedAutoDownloads) {
-          const payload: PostDownloadPluginPayload = {
-            jobId: downloadId,
-            source: detectPlatform(video.url) || 'youtube',
-            trigger: 'download.queued',
-            filepath: '',
-            filename: video.title || video.url,
-            directory: autoOutputPath,
-            filesize: null,
-            format,
-            quality,
-            url: video.url,
-            title: video.title || null,
-            thumbnail: video.thumbnail || null,
-            historyId: null,
-            timeRange: null,
-            downloadKind: 'channel-auto',
-            workflowRunId: null,
-            workflowStepIndex: null,
-            workflowStepPluginId: null,
-            chainState: null,
-          };
-          void enqueuePluginWorkflowTrigger('download.queued', payload, workflowSnapshots).catch(
-            (error) => {
-              console.error('Failed to enqueue channel auto download.queued workflow:', error);
-            },
-          );
-        }
-
-        const downloadOneAuto = async ({
-          video,
-          downloadId,
-        }: {
-          video: ChannelVideo;
-          downloadId: string;
-        }) => {
-          await updateChannelVideoStatus({ id: video.id, status: 'downloading' });
 
+        // Hand the videos to the backend download queue, which applies the
+        // concurrency limits and keeps running while the window is hidden.
+        for (const video of newVideos) {
           try {
-            await downloadVideoCommand({
-              id: downloadId,
+            await enqueueDownloadCommand({
               url: video.url,
-              outputPath: autoOutputPath,
-              quality,
-              format,
-              downloadPlaylist: false,
-              videoCodec: video_codec,
-              audioBitrate: audio_bitrate,
-              playlistLimit: null,
-              subtitleMode: 'off',
-              subtitleLangs: '',
-              subtitleEmbed: false,
-              subtitleFormat: 'srt',
-              logStderr,
-              useBunRuntime,
-              useActualPlayerJs,
-              ...networkOptions,
-              useAria2,
-              aria2Args,
-              pluginWorkflowSnapshots: workflowSnapshots,
-              postDownloadWorkflowSteps: loadPostDownloadWorkflowSteps(),
-              downloadKind: 'channel-auto',
+              title: video.title || null,
+              origin: 'channel',
+              originRef: video.id,
+              options: {
+                outputPath: autoOutputPath,
+                quality,
+                format,
+                videoCodec: video_codec,
+                audioBitrate: audio_bitrate,
+                logStderr,
+                useActualPlayerJs,
+                ...networkOptions,
+                useAria2,
+                aria2Args,
+                thumbnail: video.thumbnail || null,
+                source: detectPlatform(video.url) || 'youtube',
+                pluginWorkflowSnapshots: workflowSnapshots,
+                postDownloadWorkflowSteps: loadPostDownloadWorkflowSteps(),
+                downloadKind: 'channel-auto',
+                profile: profile || null,
+              },
             });
-
-            await updateChannelVideoStatus({ id: video.id, status: 'downloaded' });
           } catch (error) {
-            console.error(`Auto-download failed for ${video.title}:`, error);
-            await updateChannelVideoStatus({ id: video.id, status: 'new' });
-          }
-        };
-
-        // Concurrency pool for auto-download
-        const queue = [...queuedAutoDownloads];
-        const running: Promise<void>[] = [];
-
-        while (queue.length > 0 || running.length > 0) {
-          while (running.length < maxConcurrent && queue.length > 0) {
-            const queuedDownload = queue.shift();
-            if (!queuedDownload) break;
-            const promise = downloadOneAuto(queuedDownload).then(() => {
-              running.splice(running.indexOf(promise), 1);
-            });
-            running.push(promise);
-          }
-          if (running.length > 0) {
-            await Promise.race(running);
+            console.error(`Failed to queue auto-download for ${video.title}:`, error);
           }
         }
 
         refreshChannelNewCounts();
-        // Update tray menu after auto-downloads
+        // Update tray menu after queueing auto-downloads
         rebuildTrayMenu().catch(() => {});
       } catch (error) {
         console.error('Auto-download error:', error);
@@ -120,6 +120,16 @@ export async function executeFfmpegCommand(input: {
   await invoke('execute_ffmpeg_command', input);
 }
 
+export async function executePreciseCut(input: {
+  jobId: string;
+  inputPath: string;
+  outputPath: string;
+  start: number;
+  end: number;
+}): Promise<void> {
+  await invoke('execute_precise_cut', input);
+}
+
 export async function updateProcessingJob(input: {
   id: string;
   status: string;
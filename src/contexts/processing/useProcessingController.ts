@@ -21,6 +21,7 @@ import {
   deleteProcessingPreset,
   executeFfmpegBatch,
   executeFfmpegCommand,
+  executePreciseCut,
   generateAudioPreview,
   generateProcessingCommand,
   generateQuickActionCommand,
@@ -132,6 +133,7 @@ export interface ProcessingContextValue {
 
   // Processing Actions
   executeCommand: (command?: FFmpegCommandResult) => Promise<void>;
+  cutSelection: (precise: boolean) => Promise<void>;
   cancelProcessing: () => Promise<void>;
   clearCommand: () => void;
   openOutputFolder: () => Promise<void>;
@@ -769,12 +771,22 @@ export function useProcessingController(): ProcessingContextValue {
           ffmpegCommand: cmdToExecute.command,
         });
 
-        await executeFfmpegCommand({
-          jobId,
-          commandArgs: cmdToExecute.command_args,
-          inputPath: videoPath,
-          outputPath: cmdToExecute.output_path,
-        });
+        if (cmdToExecute.precise_cut) {
+          await executePreciseCut({
+            jobId,
+            inputPath: videoPath,
+            outputPath: cmdToExecute.output_path,
+            start: cmdToExecute.precise_cut.start,
+            end: cmdToExecute.precise_cut.end,
+          });
+        } else {
+          await executeFfmpegCommand({
+            jobId,
+            commandArgs: cmdToExecute.command_args,
+            inputPath: videoPath,
+            outputPath: cmdToExecute.output_path,
+          });
+        }
 
         // Update job status
         await updateProcessingJob({
@@ -817,6 +829,31 @@ export function useProcessingController(): ProcessingContextValue {
     [generatedCommand, videoPath, messages, addMessage, loadHistory],
   );
 
+  // Cut the timeline selection; a precise cut re-encodes only around the cut points
+  const cutSelection = useCallback(
+    async (precise: boolean) => {
+      if (!videoMetadata || !selection) return;
+
+      try {
+        const result = await generateQuickActionCommand({
+          inputPath: videoPath,
+          taskType: 'cut',
+          options: { precise },
+          timelineStart: selection.start,
+          timelineEnd: selection.end,
+          metadata: videoMetadata,
+          outputDir: outputDirectory || null,
+        });
+        addMessage('assistant', result.explanation, { command: result });
+        await executeCommand(result);
+      } catch (error) {
+        setStatus('error');
+        addMessage('system', `Failed: ${localizeUnknownError(error)}`);
+      }
+    },
+    [videoPath, videoMetadata, selection, outputDirectory, addMessage, executeCommand],
+  );
+
   // Cancel processing
   const cancelProcessing = useCallback(async () => {
     if (currentJobId) {
@@ -1024,6 +1061,7 @@ export function useProcessingController(): ProcessingContextValue {
     removeAttachment,
     clearAttachments,
     executeCommand,
+    cutSelection,
     cancelProcessing,
     clearCommand,
     openOutputFolder,
@@ -0,0 +1,267 @@
+import {
+  type Dispatch,
+  type MutableRefObject,
+  type SetStateAction,
+  useCallback,
+  useEffect,
+  useRef,
+  useState,
+} from 'react';
+import { extractBackendError, localizeBackendError } from '@/lib/backend-error';
+import {
+  type BackendQueueKind,
+  cancelDownloadQueueItem,
+  enqueueDownload,
+  getDownloadQueueItems,
+  getSchedulerMaxAttempts,
+  isActiveQueueStatus,
+  mergeDownloadQueueItems,
+  onDownloadQueueUpdated,
+  pauseDownload,
+  queueItemVersion,
+  queueRetryState,
+  removeDownloadQueueItem,
+  resumeDownload,
+} from '@/lib/download-queue';
+import type { DownloadItem, DownloadQueueItem } from '@/lib/types';
+
+const REFRESH_DEBOUNCE_MS = 150;
+
+interface UseBackendDownloadQueueOptions {
+  queueKind: BackendQueueKind;
+  items: DownloadItem[];
+  itemsRef: MutableRefObject<DownloadItem[]>;
+  setItems: Dispatch<SetStateAction<DownloadItem[]>>;
+  // Download options sent with an item when it is queued
+  buildOptions: (item: DownloadItem) => Record<string, unknown>;
+  logLabel: string;
+}
+
+function isRunning(queued: DownloadQueueItem) {
+  return queued.status === 'pending' || queued.status === 'downloading';
+}
+
+// Runs a page's downloads through the backend scheduler: Start queues the page's
+// pending items, and the page's list follows the backend queue of its kind.
+export function useBackendDownloadQueue({
+  queueKind,
+  items,
+  itemsRef,
+  setItems,
+  buildOptions,
+  logLabel,
+}: UseBackendDownloadQueueOptions) {
+  const [isDownloading, setIsDownloading] = useState(false);
+  const [hasRetryingItems, setHasRetryingItems] = useState(false);
+  const queuedRef = useRef(new Map<string, DownloadQueueItem>());
+  const versionsRef = useRef(new Map<string, string>());
+  const submittingRef = useRef(new Set<string>());
+  // Set by Start: items added afterwards are queued too, until the queue runs dry or Stop
+  const runningRef = useRef(false);
+  const maxAttemptsRef = useRef(1);
+  const refreshChainRef = useRef<Promise<void>>(Promise.resolve());
+  const refreshTimerRef = useRef<number | null>(null);
+  const buildOptionsRef = useRef(buildOptions);
+  buildOptionsRef.current = buildOptions;
+
+  const refresh = useCallback(async () => {
+    try {
+      const [queued, maxAttempts] = await Promise.all([
+        getDownloadQueueItems(queueKind),
+        getSchedulerMaxAttempts(),
+      ]);
+      const changed = queued.filter(
+        (item) => versionsRef.current.get(item.id) !== queueItemVersion(item),
+      );
+      queuedRef.current = new Map(queued.map((item) => [item.id, item]));
+      versionsRef.current = new Map(queued.map((item) => [item.id, queueItemVersion(item)]));
+      maxAttemptsRef.current = maxAttempts;
+
+      setItems((currentItems) => mergeDownloadQueueItems(currentItems, changed, maxAttempts));
+
+      const running = queued.some(isRunning) || submittingRef.current.size > 0;
+      if (!running) {
+        runningRef.current = false;
+      }
+      setIsDownloading(running);
+      setHasRetryingItems(queued.some((item) => queueRetryState(item, maxAttempts) !== undefined));
+    } catch (error) {
+      console.error(`Failed to load ${logLabel}:`, error);
+    }
+  }, [logLabel, queueKind, setItems]);
+
+  // Refreshes run one after another so an older answer never overwrites a newer one
+  const queueRefresh = useCallback(() => {
+    refreshChainRef.current = refreshChainRef.current.then(refresh);
+    return refreshChainRef.current;
+  }, [refresh]);
+
+  useEffect(() => {
+    void queueRefresh();
+    const unlistenPromise = onDownloadQueueUpdated(() => {
+      if (refreshTimerRef.current !== null) return;
+      refreshTimerRef.current = window.setTimeout(() => {
+        refreshTimerRef.current = null;
+        void queueRefresh();
+      }, REFRESH_DEBOUNCE_MS);
+    });
+
+    return () => {
+      if (refreshTimerRef.current !== null) {
+        window.clearTimeout(refreshTimerRef.current);
+        refreshTimerRef.current = null;
+      }
+      unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, [queueRefresh]);
+
+  // Count down the wait of items the backend retries later
+  useEffect(() => {
+    if (!hasRetryingItems) return;
+
+    const timer = window.setInterval(() => {
+      setItems((currentItems) =>
+        currentItems.map((item) => {
+          const queued = queuedRef.current.get(item.id);
+          if (!item.retryState || !queued) return item;
+          const retryState = queueRetryState(queued, maxAttemptsRef.current);
+          return retryState ? { ...item, retryState } : item;
+        }),
+      );
+    }, 1000);
+
+    return () => {
+      window.clearInterval(timer);
+    };
+  }, [hasRetryingItems, setItems]);
+
+  const isQueued = useCallback((id: string) => {
+    const queued = queuedRef.current.get(id);
+    return queued !== undefined && isActiveQueueStatus(queued.status);
+  }, []);
+
+  const enqueueItems = useCallback(
+    async (statuses: DownloadItem['status'][]) => {
+      const staged = itemsRef.current.filter(
+        (item) =>
+          statuses.includes(item.status) && !submittingRef.current.has(item.id) && !isQueued(item.id),
+      );
+      if (staged.length === 0) return;
+
+      const stagedIds = new Set(staged.map((item) => item.id));
+      for (const item of staged) {
+        submittingRef.current.add(item.id);
+      }
+      setIsDownloading(true);
+      setItems((currentItems) =>
+        currentItems.map((item) =>
+          stagedIds.has(item.id)
+            ? {
+                ...item,
+                status: 'pending' as const,
+                progress: 0,
+                speed: '',
+                eta: '',
+                error: undefined,
+                errorCode: undefined,
+                retryState: undefined,
+                playlistFailures: undefined,
+              }
+            : item,
+        ),
+      );
+
+      for (const item of staged) {
+        try {
+          await enqueueDownload({
+            id: item.id,
+            url: item.url,
+            title: item.title || null,
+            queueKind,
+            options: buildOptionsRef.current(item),
+          });
+        } catch (error) {
+          const parsedError = extractBackendError(error);
+          setItems((currentItems) =>
+            currentItems.map((currentItem) =>
+              currentItem.id === item.id
+                ? {
+                    ...currentItem,
+                    status: 'error',
+                    error: localizeBackendError(parsedError),
+                    errorCode: parsedError.code,
+                  }
+                : currentItem,
+            ),
+          );
+        } finally {
+          submittingRef.current.delete(item.id);
+        }
+      }
+
+      await queueRefresh();
+    },
+    [isQueued, itemsRef, queueKind, queueRefresh, setItems],
+  );
+
+  // Items added while a run is going are picked up like before the backend owned the queue
+  useEffect(() => {
+    if (runningRef.current && items.some((item) => item.status === 'pending')) {
+      void enqueueItems(['pending']);
+    }
+  }, [enqueueItems, items]);
+
+  const startDownload = useCallback(async () => {
+    runningRef.current = true;
+    await enqueueItems(['pending', 'error']);
+  }, [enqueueItems]);
+
+  const stopDownload = useCallback(async () => {
+    runningRef.current = false;
+    const running = [...queuedRef.current.values()].filter(isRunning);
+    try {
+      await Promise.all(running.map((queued) => cancelDownloadQueueItem(queued.id)));
+    } catch (error) {
+      console.error('Failed to stop download:', error);
+    }
+    await queueRefresh();
+  }, [queueRefresh]);
+
+  const pauseItem = useCallback(
+    async (id: string) => {
+      if (!isQueued(id)) return;
+      try {
+        await pauseDownload(id);
+      } catch (error) {
+        console.error('Failed to pause download:', error);
+      }
+      await queueRefresh();
+    },
+    [isQueued, queueRefresh],
+  );
+
+  const resumeItem = useCallback(
+    async (id: string) => {
+      try {
+        await resumeDownload(id);
+      } catch (error) {
+        console.error('Failed to resume download:', error);
+      }
+      await queueRefresh();
+    },
+    [queueRefresh],
+  );
+
+  // Drop the backend rows of items removed from the page
+  const forgetItems = useCallback((ids: string[]) => {
+    for (const id of ids) {
+      if (!queuedRef.current.has(id)) continue;
+      queuedRef.current.delete(id);
+      removeDownloadQueueItem(id).catch((error) => {
+        console.error('Failed to remove queued download:', error);
+      });
+    }
+  }, []);
+
+  return { isDownloading, startDownload, stopDownload, pauseItem, resumeItem, forgetItems };
+}
@@ -4,6 +4,7 @@ import { onOpenUrl } from '@tauri-apps/plugin-deep-link';
 import { type MutableRefObject, useCallback, useEffect, useRef } from 'react';
 import type { Page } from '@/components/layout';
 import { useDownload } from '@/contexts/DownloadContext';
+import { useGalleryDl } from '@/contexts/GalleryDlContext';
 import { useUniversal } from '@/contexts/UniversalContext';
 import {
   type ExternalLinkAction,
@@ -12,14 +13,17 @@ import {
   isTrustedExternalSource,
   isYouTubeUrl,
   normalizeExternalVideoUrl,
+  normalizeGalleryOptions,
   parseExternalDeepLink,
   parseExternalSummaryDeepLink,
 } from '@/lib/external-link';
+import { parseDownloadSections } from '@/lib/time-ranges';
 import type { ExternalEnqueueOptions, Quality, SubtitleFormat, SubtitleMode } from '@/lib/types';
 
 type StartLockRef = MutableRefObject<{
   youtube: boolean;
   universal: boolean;
+  gallery: boolean;
 }>;
 
 interface ExternalOpenUrlEventPayload {
@@ -40,7 +44,18 @@ interface CliDownloadRequestPayload {
   subtitle_embed?: boolean;
   subtitle_format?: string;
   download_sections?: string | null;
+  join_sections?: boolean;
   live_from_start?: boolean;
+  profile?: string | null;
+  gallery_options?: {
+    range?: string | null;
+    filter?: string | null;
+    filenameFormat?: string | null;
+    directoryFormat?: string[];
+    writeMetadata?: boolean;
+    zip?: boolean;
+    ugoiraConv?: boolean;
+  } | null;
   trusted_local?: boolean;
 }
 
@@ -70,18 +85,6 @@ const ALLOWED_VIDEO_QUALITIES = new Set<Quality>([
 const ALLOWED_SUBTITLE_MODES = new Set<SubtitleMode>(['off', 'auto', 'manual']);
 const ALLOWED_SUBTITLE_FORMATS = new Set<SubtitleFormat>(['srt', 'vtt', 'ass']);
 
-function parseDownloadSections(section: string | null | undefined):
-  | {
-      timeRangeStart: string;
-      timeRangeEnd: string;
-    }
-  | undefined {
-  const normalized = section?.trim().replace(/^\*/, '') ?? '';
-  const [start, end] = normalized.split('-', 2);
-  if (!start || !end) return undefined;
-  return { timeRangeStart: start, timeRangeEnd: end };
-}
-
 function normalizeCliOutputPath(path: string | null | undefined): string | undefined {
   const normalized = path?.trim().replace(/^['"]+|['"]+$/g, '') ?? '';
   const hasControlCharacter = [...normalized].some((char) => {
@@ -109,7 +112,9 @@ function normalizeCliDownloadRequest(
   if (!isPublicHttpUrl(normalizedUrl)) return null;
 
   const target: ExternalLinkTarget =
-    payload.target === 'youtube' || payload.target === 'universal' ? payload.target : 'auto';
+    payload.target === 'youtube' || payload.target === 'universal' || payload.target === 'gallery'
+      ? payload.target
+      : 'auto';
   const action: ExternalLinkAction =
     payload.action === 'queue_only' ? 'queue_only' : 'download_now';
   const media = payload.media === 'audio' ? 'audio' : 'video';
@@ -129,7 +134,7 @@ function normalizeCliDownloadRequest(
             : 'best',
         };
   const outputPath = normalizeCliOutputPath(payload.output_path);
-  const downloadSections = parseDownloadSections(payload.download_sections);
+  const timeRanges = parseDownloadSections([payload.download_sections]);
   if (outputPath) {
     enqueueOptions.outputPath = outputPath;
   }
@@ -142,9 +147,9 @@ function normalizeCliDownloadRequest(
   if (typeof payload.download_playlist === 'boolean') {
     enqueueOptions.downloadPlaylist = payload.download_playlist;
   }
-  if (downloadSections) {
-    enqueueOptions.timeRangeStart = downloadSections.timeRangeStart;
-    enqueueOptions.timeRangeEnd = downloadSections.timeRangeEnd;
+  if (timeRanges.length > 0) {
+    enqueueOptions.timeRanges = timeRanges;
+    enqueueOptions.joinTimeRanges = payload.join_sections === true;
   }
   if (ALLOWED_SUBTITLE_MODES.has(payload.subtitle_mode as SubtitleMode)) {
     enqueueOptions.subtitleMode = payload.subtitle_mode as SubtitleMode;
@@ -158,6 +163,23 @@ function normalizeCliDownloadRequest(
   if (payload.subtitle_embed === true) {
     enqueueOptions.subtitleEmbed = true;
   }
+  if (payload.profile) {
+    enqueueOptions.profile = payload.profile;
+  }
+  const galleryOptions = payload.gallery_options
+    ? normalizeGalleryOptions({
+        range: payload.gallery_options.range ?? undefined,
+        filter: payload.gallery_options.filter ?? undefined,
+        filenameFormat: payload.gallery_options.filenameFormat ?? undefined,
+        directoryFormat: payload.gallery_options.directoryFormat,
+        writeMetadata: payload.gallery_options.writeMetadata,
+        zip: payload.gallery_options.zip,
+        ugoiraConv: payload.gallery_options.ugoiraConv,
+      })
+    : undefined;
+  if (target === 'gallery' && galleryOptions) {
+    enqueueOptions.galleryOptions = galleryOptions;
+  }
 
   return {
     url: normalizedUrl,
@@ -176,14 +198,17 @@ export function useExternalDownloadLinks(
 ) {
   const download = useDownload();
   const universal = useUniversal();
+  const gallery = useGalleryDl();
   const downloadRef = useRef(download);
   const universalRef = useRef(universal);
+  const galleryRef = useRef(gallery);
   const externalDedupRef = useRef<Map<string, number>>(new Map());
   const externalRequestRateRef = useRef<number[]>([]);
   const externalApprovalCacheRef = useRef<Map<string, number>>(new Map());
 
   downloadRef.current = download;
   universalRef.current = universal;
+  galleryRef.current = gallery;
 
   const handleExternalDownloadRequest = useCallback(
     async (request: ExternalDownloadRequest) => {
@@ -262,6 +287,22 @@ export function useExternalDownloadLinks(
         return;
       }
 
+      if (routeTarget === 'gallery') {
+        const galleryApi = galleryRef.current;
+        setCurrentPage('gallery');
+        await galleryApi.enqueueExternalUrl(request.url, request.enqueueOptions.galleryOptions);
+
+        if (allowAutoStart && !galleryApi.isDownloading && !startLockRef.current.gallery) {
+          startLockRef.current.gallery = true;
+          try {
+            await galleryApi.startDownload();
+          } finally {
+            startLockRef.current.gallery = false;
+          }
+        }
+        return;
+      }
+
       const universalApi = universalRef.current;
       setCurrentPage('universal');
       await universalApi.enqueueExternalUrl(request.url, request.enqueueOptions);
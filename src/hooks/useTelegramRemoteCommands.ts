@@ -4,8 +4,16 @@ import { type MutableRefObject, useCallback, useEffect, useRef } from 'react';
 import type { Page } from '@/components/layout';
 import { useDownload } from '@/contexts/DownloadContext';
 import { useUniversal } from '@/contexts/UniversalContext';
+import {
+  applyQueueItem,
+  cancelDownloadQueueItem,
+  downloadItemFromQueue,
+  getDownloadQueueItems,
+  getSchedulerMaxAttempts,
+  isActiveQueueStatus,
+} from '@/lib/download-queue';
 import { normalizeExternalVideoUrl, resolveExternalRouteTarget } from '@/lib/external-link';
-import type { DownloadItem, ExternalEnqueueOptions, Quality } from '@/lib/types';
+import type { DownloadItem, DownloadQueueItem, ExternalEnqueueOptions, Quality } from '@/lib/types';
 import { isSafeUrl } from '@/lib/utils';
 
 interface TelegramDownloadCommandEvent {
@@ -22,6 +30,13 @@ type StartLockRef = MutableRefObject<{
 
 type QueueSource = 'YouTube' | 'Universal';
 
+interface BackendQueueSnapshot {
+  youtubeItems: DownloadItem[];
+  universalItems: DownloadItem[];
+  // Rows waiting for or holding a download slot, from every queue
+  running: DownloadQueueItem[];
+}
+
 interface QueueEntry {
   item: DownloadItem;
   source: QueueSource;
@@ -31,7 +46,7 @@ interface QueueEntry {
 function summarizeItems(items: DownloadItem[]) {
   return items.reduce(
     (summary, item) => {
-      if (item.status === 'pending') {
+      if (item.status === 'pending' || item.status === 'paused') {
         summary.pending += 1;
       } else if (item.status === 'downloading' || item.status === 'fetching') {
         summary.downloading += 1;
@@ -46,6 +61,43 @@ function summarizeItems(items: DownloadItem[]) {
   );
 }
 
+// The pages' items with their backend queue state, plus downloads queued elsewhere
+// (CLI, Telegram, history re-downloads) that no page shows
+async function loadBackendQueue(
+  youtubeItems: DownloadItem[],
+  universalItems: DownloadItem[],
+): Promise<BackendQueueSnapshot> {
+  const [queued, maxAttempts] = await Promise.all([
+    getDownloadQueueItems(),
+    getSchedulerMaxAttempts(),
+  ]);
+  const queuedById = new Map(queued.map((item) => [item.id, item]));
+  const withQueueState = (items: DownloadItem[]) =>
+    items.map((item) => {
+      const row = queuedById.get(item.id);
+      return row ? applyQueueItem(item, row, maxAttempts) : item;
+    });
+
+  const pageIds = new Set([...youtubeItems, ...universalItems].map((item) => item.id));
+  const others = queued.filter((row) => !pageIds.has(row.id) && isActiveQueueStatus(row.status));
+
+  return {
+    youtubeItems: [
+      ...withQueueState(youtubeItems),
+      ...others
+        .filter((row) => row.queueKind === 'youtube')
+        .map((row) => downloadItemFromQueue(row, maxAttempts)),
+    ],
+    universalItems: [
+      ...withQueueState(universalItems),
+      ...others
+        .filter((row) => row.queueKind !== 'youtube')
+        .map((row) => downloadItemFromQueue(row, maxAttempts)),
+    ],
+    running: queued.filter((row) => row.status === 'pending' || row.status === 'downloading'),
+  };
+}
+
 function truncateText(text: string, maxLength = 80) {
   const normalized = text.trim().replace(/\s+/g, ' ');
   if (normalized.length <= maxLength) return normalized;
@@ -54,6 +106,7 @@ function truncateText(text: string, maxLength = 80) {
 
 function statusIcon(status: DownloadItem['status']) {
   if (status === 'pending') return '⏳';
+  if (status === 'paused') return '⏸️';
   if (status === 'downloading' || status === 'fetching') return '⬇️';
   if (status === 'completed') return '✅';
   if (status === 'error') return '❌';
@@ -184,30 +237,25 @@ export function useTelegramRemoteCommands(
       const { download, setCurrentPage, universal } = latestRef.current;
 
       if (payload.command === 'status') {
+        const queue = await loadBackendQueue(download.items, universal.items);
         await sendTelegramReply(
           payload.chatId,
-          buildStatusReply(
-            download.items,
-            universal.items,
-            download.isDownloading || universal.isDownloading,
-          ),
+          buildStatusReply(queue.youtubeItems, queue.universalItems, queue.running.length > 0),
         );
         return;
       }
 
       if (payload.command === 'queue') {
-        await sendTelegramReply(payload.chatId, buildQueueReply(download.items, universal.items));
+        const queue = await loadBackendQueue(download.items, universal.items);
+        await sendTelegramReply(
+          payload.chatId,
+          buildQueueReply(queue.youtubeItems, queue.universalItems),
+        );
         return;
       }
 
       if (payload.command === 'run') {
-        const isBusy =
-          download.isDownloading ||
-          universal.isDownloading ||
-          startLockRef.current.youtube ||
-          startLockRef.current.universal;
-
-        if (isBusy) {
+        if (startLockRef.current.youtube || startLockRef.current.universal) {
           await sendTelegramReply(payload.chatId, 'Youwee is already downloading.');
           return;
         }
@@ -216,16 +264,25 @@ export function useTelegramRemoteCommands(
         const shouldStartUniversal = hasStartableItems(universal.items);
 
         if (!shouldStartYoutube && !shouldStartUniversal) {
-          await sendTelegramReply(payload.chatId, 'No pending downloads in the queue.');
+          const queue = await loadBackendQueue(download.items, universal.items);
+          await sendTelegramReply(
+            payload.chatId,
+            queue.running.length > 0
+              ? 'Youwee is already downloading.'
+              : 'No pending downloads in the queue.',
+          );
           return;
         }
 
+        const starts: Promise<void>[] = [];
         if (shouldStartYoutube) {
           setCurrentPage('youtube');
           startLockRef.current.youtube = true;
-          void download.startDownload().finally(() => {
-            startLockRef.current.youtube = false;
-          });
+          starts.push(
+            download.startDownload().finally(() => {
+              startLockRef.current.youtube = false;
+            }),
+          );
         }
 
         if (shouldStartUniversal) {
@@ -233,23 +290,38 @@ export function useTelegramRemoteCommands(
             setCurrentPage('universal');
           }
           startLockRef.current.universal = true;
-          void universal.startDownload().finally(() => {
-            startLockRef.current.universal = false;
-          });
+          starts.push(
+            universal.startDownload().finally(() => {
+              startLockRef.current.universal = false;
+            }),
+          );
         }
 
-        await sendTelegramReply(payload.chatId, 'Started pending downloads.');
+        await Promise.all(starts);
+        const queue = await loadBackendQueue(download.items, universal.items);
+        await sendTelegramReply(
+          payload.chatId,
+          [
+            'Started pending downloads.',
+            '',
+            buildStatusReply(queue.youtubeItems, queue.universalItems, queue.running.length > 0),
+          ].join('\n'),
+        );
         return;
       }
 
       if (payload.command === 'stop') {
-        const wasDownloading = download.isDownloading || universal.isDownloading;
+        const queue = await loadBackendQueue(download.items, universal.items);
+        const wasDownloading =
+          queue.running.length > 0 || download.isDownloading || universal.isDownloading;
         if (download.isDownloading) {
           await download.stopDownload();
         }
         if (universal.isDownloading) {
           await universal.stopDownload();
         }
+        // Downloads queued from elsewhere are stopped too
+        await Promise.all(queue.running.map((row) => cancelDownloadQueueItem(row.id)));
         startLockRef.current.youtube = false;
         startLockRef.current.universal = false;
         await sendTelegramReply(
@@ -349,7 +421,10 @@ export function useTelegramRemoteCommands(
     const unlistenPromise = listen<TelegramDownloadCommandEvent>(
       'telegram-download-command',
       (event) => {
-        void handleTelegramDownloadCommand(event.payload);
+        handleTelegramDownloadCommand(event.payload).catch((error) => {
+          console.error('Failed to handle Telegram command:', error);
+          void sendTelegramReply(event.payload.chatId, 'Failed to read the Youwee queue.');
+        });
       },
     );
 
@@ -363,5 +438,5 @@ export function useTelegramRemoteCommands(
       disposed = true;
       unlistenPromise.then((unlisten) => unlisten());
     };
-  }, [handleTelegramDownloadCommand]);
+  }, [handleTelegramDownloadCommand, sendTelegramReply]);
 }
@@ -17,13 +17,36 @@ const RETRYABLE_CODES = new Set([
   'PROCESS_START_FAILED',
   'PROCESS_EXECUTION_FAILED',
   'PROCESS_EXIT_NON_ZERO',
+  'DOWNLOAD_VERIFICATION_FAILED',
 ]);
 
+// Verification failures are worded by what the check found
+const VERIFICATION_REASON_KEYS: Record<string, string> = {
+  missingVideo: 'DOWNLOAD_VERIFICATION_MISSING_VIDEO',
+  missingAudio: 'DOWNLOAD_VERIFICATION_MISSING_AUDIO',
+  duration: 'DOWNLOAD_VERIFICATION_DURATION',
+};
+
+function backendErrorKey(payload: BackendErrorPayload): string {
+  if (payload.code === 'YT_SKIPPED_LIVE' && payload.params?.liveStatus === 'is_upcoming') {
+    return 'common:backendErrors.YT_SKIPPED_UPCOMING_LIVE';
+  }
+  const reasonKey =
+    payload.code === 'DOWNLOAD_VERIFICATION_FAILED'
+      ? VERIFICATION_REASON_KEYS[String(payload.params?.reason)]
+      : undefined;
+  return `common:backendErrors.${reasonKey ?? payload.code}`;
+}
+
 const NON_RETRYABLE_CODES = new Set([
   'YT_PRIVATE_VIDEO',
   'YT_VIDEO_UNAVAILABLE',
   'YT_SKIPPED_LIVE',
   'YT_UPCOMING_LIVE',
+  'DOWNLOAD_ALREADY_ARCHIVED',
+  'DOWNLOAD_FORMAT_UNAVAILABLE',
+  'DISK_SPACE_INSUFFICIENT',
+  'STORAGE_QUOTA_EXCEEDED',
   'YT_AGE_RESTRICTED',
   'YT_MEMBERS_ONLY',
   'YT_SIGNIN_REQUIRED',
@@ -55,6 +78,10 @@ export function inferBackendErrorCode(message: string): string {
   if (m.includes('download cancelled') || m.includes('canceled') || m.includes('cancelled')) {
     return 'DOWNLOAD_CANCELLED';
   }
+  if (m.includes('found in download archive')) return 'DOWNLOAD_ALREADY_ARCHIVED';
+  if (m.includes('no space left on device') || m.includes('not enough space on the disk')) {
+    return 'DISK_SPACE_INSUFFICIENT';
+  }
   if (m.includes('could not copy') && m.includes('cookie') && m.includes('database')) {
     return 'YT_COOKIE_DB_LOCKED';
   }
@@ -90,6 +117,7 @@ export function inferBackendErrorCode(message: string): string {
   if (m.includes('app-managed yt-dlp not found')) return 'YTDLP_APP_NOT_FOUND';
   if (m.includes('system yt-dlp is managed externally')) return 'YTDLP_SYSTEM_MANAGED';
   if (m.includes('yt-dlp not found')) return 'YTDLP_NOT_FOUND';
+  if (m.includes('system gallery-dl is managed externally')) return 'GALLERYDL_SYSTEM_MANAGED';
   if (m.includes('gallery-dl not found') || m.includes('system gallery-dl not found')) {
     return 'GALLERYDL_NOT_FOUND';
   }
@@ -173,10 +201,7 @@ export function localizeBackendError(payload: BackendErrorPayload): string {
     return payload.message;
   }
 
-  const key =
-    payload.code === 'YT_SKIPPED_LIVE' && payload.params?.liveStatus === 'is_upcoming'
-      ? 'common:backendErrors.YT_SKIPPED_UPCOMING_LIVE'
-      : `common:backendErrors.${payload.code}`;
+  const key = backendErrorKey(payload);
   const translated = i18n.t(key, payload.params ?? {});
   return translated === key ? payload.message : translated;
 }
@@ -0,0 +1,46 @@
+import type { BandwidthProfile, DownloadSettings } from './types';
+
+export type RateUnit = 'K' | 'M' | 'G';
+
+const BYTES_PER_UNIT: Record<RateUnit, number> = {
+  K: 1024,
+  M: 1024 * 1024,
+  G: 1024 * 1024 * 1024,
+};
+
+// Bytes per second of a rate in KB/s, MB/s or GB/s
+export function rateToBytes(value: number, unit: RateUnit): number {
+  return Math.round(value * BYTES_PER_UNIT[unit]);
+}
+
+// "2 MB/s" for a rate in bytes per second
+export function formatRate(bytes: number): string {
+  const unit = (['G', 'M'] as RateUnit[]).find((u) => bytes >= BYTES_PER_UNIT[u]) ?? 'K';
+  const value = bytes / BYTES_PER_UNIT[unit];
+  return `${Number.isInteger(value) ? value : value.toFixed(1)} ${unit}B/s`;
+}
+
+// Read bandwidth profiles saved in settings, dropping entries without a valid time window
+export function normalizeBandwidthProfiles(value: unknown): BandwidthProfile[] {
+  if (!Array.isArray(value)) return [];
+  const isMinute = (minute: unknown): minute is number =>
+    typeof minute === 'number' && Number.isInteger(minute) && minute >= 0 && minute < 24 * 60;
+  return value.flatMap((entry) => {
+    if (!entry || typeof entry !== 'object') return [];
+    const { startMinute, endMinute, limit } = entry as Partial<BandwidthProfile>;
+    if (!isMinute(startMinute) || !isMinute(endMinute) || startMinute === endMinute) return [];
+    const validLimit = typeof limit === 'number' && Number.isFinite(limit) && limit > 0;
+    return [{ startMinute, endMinute, limit: validLimit ? Math.floor(limit) : null }];
+  });
+}
+
+// Bandwidth budget synced to the backend: the speed limit is shared by all running
+// downloads, and time-of-day profiles replace it while they apply
+export function bandwidthConfig(settings: DownloadSettings) {
+  return {
+    limit: settings.speedLimitEnabled
+      ? rateToBytes(settings.speedLimitValue, settings.speedLimitUnit)
+      : null,
+    profiles: settings.bandwidthProfiles,
+  };
+}
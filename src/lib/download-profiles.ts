@@ -0,0 +1,17 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { DownloadProfile, DownloadProfileSettings } from './types';
+
+export async function listDownloadProfiles(): Promise<DownloadProfile[]> {
+  return invoke<DownloadProfile[]>('list_download_profiles');
+}
+
+export async function saveDownloadProfile(
+  name: string,
+  settings: DownloadProfileSettings,
+): Promise<DownloadProfile> {
+  return invoke<DownloadProfile>('save_download_profile', { name, settings });
+}
+
+export async function deleteDownloadProfile(name: string): Promise<void> {
+  await invoke('delete_download_profile', { name });
+}
@@ -0,0 +1,206 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import { localizeProgressError } from './backend-error';
+import type { DownloadItem, DownloadQueueItem, DownloadQueueStatus } from './types';
+
+export type BackendQueueKind = 'youtube' | 'universal';
+
+// Nothing was downloaded on purpose, so these end up skipped rather than failed
+const SKIPPED_ERROR_CODES = new Set(['YT_SKIPPED_LIVE', 'DOWNLOAD_ALREADY_ARCHIVED']);
+
+export async function enqueueDownload(item: {
+  id: string;
+  url: string;
+  title?: string | null;
+  // Left out, the backend picks the queue from the URL's site
+  queueKind?: BackendQueueKind | null;
+  options: Record<string, unknown>;
+}): Promise<DownloadQueueItem> {
+  return invoke<DownloadQueueItem>('enqueue_download', { item });
+}
+
+export async function getDownloadQueueItems(
+  queueKind?: BackendQueueKind | null,
+): Promise<DownloadQueueItem[]> {
+  return invoke<DownloadQueueItem[]>('get_download_queue_items', {
+    queueKind: queueKind ?? null,
+  });
+}
+
+export async function cancelDownloadQueueItem(id: string): Promise<void> {
+  await invoke('cancel_download_queue_item', { id });
+}
+
+// A paused download keeps its partial files and continues from them when resumed
+export async function pauseDownload(id: string): Promise<void> {
+  await invoke('pause_download', { id });
+}
+
+export async function resumeDownload(id: string): Promise<void> {
+  await invoke('resume_download', { id });
+}
+
+export async function removeDownloadQueueItem(id: string): Promise<void> {
+  await invoke('remove_download_queue_item', { id });
+}
+
+export async function clearFinishedDownloadQueueItems(queueKind: BackendQueueKind): Promise<void> {
+  await invoke('clear_finished_download_queue_items', { queueKind });
+}
+
+export async function getSchedulerMaxAttempts(): Promise<number> {
+  const config = await invoke<{ retry: { maxAttempts: number } }>('get_download_scheduler_config');
+  return config.retry.maxAttempts;
+}
+
+// Fired by the backend whenever a queue item is added, changes state or is removed
+export function onDownloadQueueUpdated(handler: (id: string) => void): Promise<UnlistenFn> {
+  return listen<string>('download-queue-updated', (event) => handler(event.payload));
+}
+
+// Waiting for or holding a download slot; the item cannot be queued again yet
+export function isActiveQueueStatus(status: DownloadQueueStatus): boolean {
+  return status === 'pending' || status === 'downloading' || status === 'paused';
+}
+
+// Changes whenever the backend moves an item to another state
+export function queueItemVersion(queued: DownloadQueueItem): string {
+  return `${queued.status}:${queued.updatedAt}`;
+}
+
+export function queueRetryState(
+  queued: DownloadQueueItem,
+  maxAttempts: number,
+  now = Date.now(),
+): DownloadItem['retryState'] {
+  if (queued.status !== 'pending' || !queued.nextAttemptAt || queued.nextAttemptAt <= now) {
+    return undefined;
+  }
+  const delaySeconds = Math.max(1, Math.ceil((queued.nextAttemptAt - queued.updatedAt) / 1000));
+  return {
+    retryIndex: queued.attempts,
+    maxRetries: Math.max(queued.attempts, maxAttempts - 1),
+    delaySeconds,
+    remainingSeconds: Math.max(0, Math.ceil((queued.nextAttemptAt - now) / 1000)),
+  };
+}
+
+// Show the backend state of a queued item on the page's item
+export function applyQueueItem(
+  item: DownloadItem,
+  queued: DownloadQueueItem,
+  maxAttempts: number,
+): DownloadItem {
+  const errorCode =
+    queued.errorCode ?? (queued.status === 'cancelled' ? 'DOWNLOAD_CANCELLED' : undefined);
+  const error = localizeProgressError(errorCode, queued.errorMessage ?? undefined);
+
+  switch (queued.status) {
+    case 'pending': {
+      const retryState = queueRetryState(queued, maxAttempts);
+      return {
+        ...item,
+        status: 'pending',
+        progress: 0,
+        speed: '',
+        eta: '',
+        error: retryState ? error : undefined,
+        errorCode: retryState ? errorCode : undefined,
+        retryState,
+      };
+    }
+    case 'downloading':
+      // Progress events keep a running item up to date
+      if (item.status === 'downloading' || item.status === 'completed') return item;
+      return {
+        ...item,
+        status: 'downloading',
+        error: undefined,
+        errorCode: undefined,
+        retryState: undefined,
+      };
+    case 'paused':
+      return { ...item, status: 'paused', speed: '', eta: '', retryState: undefined };
+    default:
+      if (errorCode && SKIPPED_ERROR_CODES.has(errorCode)) {
+        return {
+          ...item,
+          status: 'skipped',
+          progress: 0,
+          speed: '',
+          eta: '',
+          error,
+          errorCode,
+          retryState: undefined,
+        };
+      }
+      if (queued.status === 'completed') {
+        return {
+          ...item,
+          status: 'completed',
+          progress: 100,
+          speed: '',
+          eta: '',
+          error: undefined,
+          errorCode: undefined,
+          retryState: undefined,
+        };
+      }
+      return {
+        ...item,
+        status: 'error',
+        speed: '',
+        eta: '',
+        error,
+        errorCode,
+        retryState: undefined,
+      };
+  }
+}
+
+// A page item for a download queued elsewhere (CLI, Telegram, a previous session)
+export function downloadItemFromQueue(queued: DownloadQueueItem, maxAttempts: number): DownloadItem {
+  const { options } = queued;
+  const item: DownloadItem = {
+    id: queued.id,
+    url: queued.url,
+    title: queued.title || queued.url,
+    status: 'pending',
+    progress: 0,
+    speed: '',
+    eta: '',
+    isPlaylist: options.downloadPlaylist === true,
+    thumbnail: typeof options.thumbnail === 'string' ? options.thumbnail : undefined,
+    extractor: typeof options.source === 'string' ? options.source : undefined,
+  };
+  return applyQueueItem(item, queued, maxAttempts);
+}
+
+// Apply changed queue items to the page's list. Items the page does not know yet are
+// added while they are still queued or running, except history re-downloads, which the
+// history page follows itself.
+export function mergeDownloadQueueItems(
+  items: DownloadItem[],
+  changed: DownloadQueueItem[],
+  maxAttempts: number,
+): DownloadItem[] {
+  if (changed.length === 0) return items;
+
+  const changedById = new Map(changed.map((queued) => [queued.id, queued]));
+  const merged = items.map((item) => {
+    const queued = changedById.get(item.id);
+    return queued ? applyQueueItem(item, queued, maxAttempts) : item;
+  });
+
+  const knownIds = new Set(items.map((item) => item.id));
+  const added = changed
+    .filter(
+      (queued) =>
+        !knownIds.has(queued.id) &&
+        isActiveQueueStatus(queued.status) &&
+        queued.options.downloadKind !== 'history-redownload',
+    )
+    .map((queued) => downloadItemFromQueue(queued, maxAttempts));
+
+  return added.length > 0 ? [...merged, ...added] : merged;
+}
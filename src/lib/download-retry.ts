@@ -50,6 +50,26 @@ export function clampAutoRetryDelaySeconds(value: number): number {
   return Math.max(min, Math.min(max, value || AUTO_RETRY_LIMITS.delaySeconds.default));
 }
 
+/** Longest wait between automatic retries, matching the backend retry policy */
+export const AUTO_RETRY_MAX_DELAY_SECONDS = 300;
+
+/**
+ * Seconds to wait before retry number `retryIndex` (1-based): the base delay doubled for
+ * every retry after the first, capped, and never shorter than the site's rate-limit cooldown.
+ */
+export function getAutoRetryDelaySeconds(
+  baseDelaySeconds: number,
+  retryIndex: number,
+  cooldownSeconds?: unknown,
+): number {
+  const backoff = Math.min(
+    AUTO_RETRY_MAX_DELAY_SECONDS,
+    baseDelaySeconds * 2 ** Math.max(0, retryIndex - 1),
+  );
+  const cooldown = typeof cooldownSeconds === 'number' ? cooldownSeconds : 0;
+  return Math.max(backoff, cooldown);
+}
+
 export function normalizeErrorMessage(error: unknown): string {
   if (typeof error === 'string') return error;
   if (error && typeof error === 'object') {
@@ -0,0 +1,71 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import i18n from '@/i18n';
+import type { DownloadSchedule, ScheduleRule } from './types';
+
+export async function listDownloadSchedules(): Promise<DownloadSchedule[]> {
+  return invoke<DownloadSchedule[]>('list_download_schedules');
+}
+
+export async function addDownloadSchedule(schedule: {
+  url: string;
+  title?: string | null;
+  options: Record<string, unknown>;
+  rule: ScheduleRule;
+}): Promise<DownloadSchedule> {
+  return invoke<DownloadSchedule>('add_download_schedule', { schedule });
+}
+
+export async function setDownloadScheduleEnabled(id: string, enabled: boolean): Promise<void> {
+  await invoke('set_download_schedule_enabled', { id, enabled });
+}
+
+export async function removeDownloadSchedule(id: string): Promise<void> {
+  await invoke('remove_download_schedule', { id });
+}
+
+// Fired by the backend when a schedule is added, changed, removed or runs
+export function onDownloadSchedulesUpdated(handler: () => void): Promise<UnlistenFn> {
+  return listen('download-schedules-updated', handler);
+}
+
+// "HH:MM" from an <input type="time"> to minutes after midnight
+export function timeToMinutes(value: string): number | null {
+  const match = /^(\d{1,2}):(\d{2})$/.exec(value);
+  if (!match) return null;
+  const hours = Number(match[1]);
+  const minutes = Number(match[2]);
+  return hours < 24 && minutes < 60 ? hours * 60 + minutes : null;
+}
+
+export function minutesToTime(minutes: number): string {
+  const hours = Math.floor(minutes / 60)
+    .toString()
+    .padStart(2, '0');
+  return `${hours}:${(minutes % 60).toString().padStart(2, '0')}`;
+}
+
+// Localized name of a weekday, 0 = Sunday
+export function weekdayName(day: number, width: 'short' | 'narrow' = 'short'): string {
+  // 2023-01-01 was a Sunday
+  return new Date(2023, 0, 1 + day).toLocaleDateString(i18n.language, { weekday: width });
+}
+
+export function describeScheduleRule(rule: ScheduleRule): string {
+  switch (rule.kind) {
+    case 'once':
+      return i18n.t('settings:download.scheduleOnceAt', {
+        time: new Date(rule.at).toLocaleString(i18n.language),
+      });
+    case 'window':
+      return i18n.t('settings:download.scheduleWindowBetween', {
+        start: minutesToTime(rule.startMinute),
+        end: minutesToTime(rule.endMinute),
+      });
+    case 'weekly':
+      return i18n.t('settings:download.scheduleWeeklyOn', {
+        days: rule.weekdays.map((day) => weekdayName(day)).join(', '),
+        time: minutesToTime(rule.minute),
+      });
+  }
+}
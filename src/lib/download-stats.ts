@@ -0,0 +1,21 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { DownloadEvent, DownloadStats } from './types';
+
+// Statistics of the downloads journaled in a period (ms timestamps, either end open)
+export async function getDownloadStats(
+  range: { since?: number | null; until?: number | null; topFailures?: number | null } = {},
+): Promise<DownloadStats> {
+  return invoke<DownloadStats>('get_download_stats', {
+    since: range.since ?? null,
+    until: range.until ?? null,
+    topFailures: range.topFailures ?? null,
+  });
+}
+
+export async function getDownloadEvents(downloadId: string): Promise<DownloadEvent[]> {
+  return invoke<DownloadEvent[]>('get_download_events', { downloadId });
+}
+
+export async function clearDownloadEvents(): Promise<void> {
+  await invoke('clear_download_events');
+}
@@ -1,9 +1,10 @@
-import type { AudioBitrate, ExternalEnqueueOptions, Quality } from './types';
+import { parseDownloadSections } from './time-ranges';
+import type { AudioBitrate, ExternalEnqueueOptions, GalleryDlOptions, Quality } from './types';
 import { isSafeUrl } from './utils';
 
-export type ExternalLinkTarget = 'auto' | 'youtube' | 'universal';
+export type ExternalLinkTarget = 'auto' | 'youtube' | 'universal' | 'gallery';
 export type ExternalLinkAction = 'download_now' | 'queue_only';
-export type ExternalRouteTarget = 'youtube' | 'universal';
+export type ExternalRouteTarget = 'youtube' | 'universal' | 'gallery';
 
 export interface ExternalLinkRequest {
   raw: string;
@@ -21,6 +22,8 @@ export interface ExternalSummaryLinkRequest {
 }
 
 const MAX_EXTERNAL_DEEPLINK_LENGTH = 4096;
+const MAX_GALLERY_OPTION_LENGTH = 512;
+const MAX_GALLERY_DIRECTORY_LEVELS = 8;
 const TRUSTED_EXTERNAL_SOURCES = new Set(['ext-chromium', 'ext-firefox']);
 
 function normalizeExternalSource(source: string | null): string | null {
@@ -78,6 +81,57 @@ export function isPublicHttpUrl(url: string): boolean {
   }
 }
 
+// Time ranges of a link: a `section=00:30-02:10` per clip, and `join=1` for one file
+function parseTimeRangeOptions(parsed: URL): ExternalEnqueueOptions {
+  const timeRanges = parseDownloadSections(parsed.searchParams.getAll('section'));
+  if (timeRanges.length === 0) return {};
+  return { timeRanges, joinTimeRanges: parsed.searchParams.get('join') === '1' };
+}
+
+function normalizeGalleryOptionText(value: string | null | undefined): string | undefined {
+  const normalized = value?.trim();
+  if (!normalized || normalized.length > MAX_GALLERY_OPTION_LENGTH) return undefined;
+  return normalized;
+}
+
+// Drop empty and oversized values; the backend validates what is left before running gallery-dl
+export function normalizeGalleryOptions(options: GalleryDlOptions): GalleryDlOptions | undefined {
+  const normalized: GalleryDlOptions = {};
+  const range = normalizeGalleryOptionText(options.range);
+  const filter = normalizeGalleryOptionText(options.filter);
+  const filenameFormat = normalizeGalleryOptionText(options.filenameFormat);
+  const directoryFormat = (options.directoryFormat ?? [])
+    .map((segment) => normalizeGalleryOptionText(segment))
+    .filter((segment): segment is string => !!segment);
+
+  if (range) normalized.range = range;
+  if (filter) normalized.filter = filter;
+  if (filenameFormat) normalized.filenameFormat = filenameFormat;
+  if (directoryFormat.length > 0 && directoryFormat.length <= MAX_GALLERY_DIRECTORY_LEVELS) {
+    normalized.directoryFormat = directoryFormat;
+  }
+  if (options.writeMetadata === true) normalized.writeMetadata = true;
+  if (options.zip === true) normalized.zip = true;
+  if (options.ugoiraConv === true) normalized.ugoiraConv = true;
+
+  return Object.keys(normalized).length > 0 ? normalized : undefined;
+}
+
+// gallery-dl options of a link: `range`, `filter`, `filename`, a `directory` per folder level,
+// and `metadata=1`, `zip=1`, `ugoira=1`
+function parseGalleryOptions(parsed: URL): GalleryDlOptions | undefined {
+  const params = parsed.searchParams;
+  return normalizeGalleryOptions({
+    range: params.get('range') ?? undefined,
+    filter: params.get('filter') ?? undefined,
+    filenameFormat: params.get('filename') ?? undefined,
+    directoryFormat: params.getAll('directory'),
+    writeMetadata: params.get('metadata') === '1',
+    zip: params.get('zip') === '1',
+    ugoiraConv: params.get('ugoira') === '1',
+  });
+}
+
 function parseEnqueueOptions(parsed: URL): ExternalEnqueueOptions {
   const media = parsed.searchParams.get('media') === 'audio' ? 'audio' : 'video';
   const qualityParam = parsed.searchParams.get('quality') || '';
@@ -88,6 +142,7 @@ function parseEnqueueOptions(parsed: URL): ExternalEnqueueOptions {
       mediaType: 'audio',
       quality: 'audio',
       audioBitrate,
+      ...parseTimeRangeOptions(parsed),
     };
   }
 
@@ -108,6 +163,7 @@ function parseEnqueueOptions(parsed: URL): ExternalEnqueueOptions {
   return {
     mediaType: 'video',
     quality,
+    ...parseTimeRangeOptions(parsed),
   };
 }
 
@@ -185,8 +241,18 @@ export function parseExternalDeepLink(raw: string): ExternalLinkRequest | null {
   }
 
   const targetParam = parsed.searchParams.get('target');
-  const target: ExternalLinkTarget =
-    targetParam === 'youtube' || targetParam === 'universal' ? targetParam : 'auto';
+  const galleryOptions = parseGalleryOptions(parsed);
+  let target: ExternalLinkTarget =
+    targetParam === 'youtube' || targetParam === 'universal' || targetParam === 'gallery'
+      ? targetParam
+      : 'auto';
+  if (target === 'auto' && galleryOptions) {
+    target = 'gallery';
+  }
+  const enqueueOptions = parseEnqueueOptions(parsed);
+  if (target === 'gallery' && galleryOptions) {
+    enqueueOptions.galleryOptions = galleryOptions;
+  }
 
   const actionParam = parsed.searchParams.get('action');
   const action: ExternalLinkAction = actionParam === 'queue_only' ? 'queue_only' : 'download_now';
@@ -196,7 +262,7 @@ export function parseExternalDeepLink(raw: string): ExternalLinkRequest | null {
     url: normalizedUrl,
     target,
     action,
-    enqueueOptions: parseEnqueueOptions(parsed),
+    enqueueOptions,
     source: normalizeExternalSource(parsed.searchParams.get('source')),
   };
 }
@@ -246,7 +312,7 @@ export function resolveExternalRouteTarget(
   preferredTarget: ExternalLinkTarget,
   url: string,
 ): ExternalRouteTarget {
-  if (preferredTarget === 'youtube' || preferredTarget === 'universal') {
+  if (preferredTarget !== 'auto') {
     return preferredTarget;
   }
   return isYouTubeUrl(url) ? 'youtube' : 'universal';
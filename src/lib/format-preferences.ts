@@ -0,0 +1,101 @@
+import { invoke } from '@tauri-apps/api/core';
+import i18n from '@/i18n';
+import type {
+  DynamicRangePreference,
+  FormatConstraint,
+  FormatOption,
+  FormatPlan,
+  FormatPreferences,
+  FormatReason,
+  VideoCodecFamily,
+} from './types';
+
+export const VIDEO_CODEC_FAMILIES: VideoCodecFamily[] = ['h264', 'h265', 'vp9', 'av1'];
+
+export const DYNAMIC_RANGE_PREFERENCES: DynamicRangePreference[] = [
+  'any',
+  'preferSdr',
+  'preferHdr',
+  'sdrOnly',
+  'hdrOnly',
+];
+
+export const FORMAT_CONSTRAINTS: FormatConstraint[] = [
+  'audioLanguage',
+  'maxFps',
+  'dynamicRange',
+  'maxFilesize',
+];
+
+export const DEFAULT_FORMAT_PREFERENCES: FormatPreferences = {
+  preferredCodecs: [],
+  forbiddenCodecs: [],
+  dynamicRange: 'any',
+  maxFps: null,
+  audioLanguage: null,
+  maxFilesizeMb: null,
+  fallbackOrder: [...FORMAT_CONSTRAINTS],
+};
+
+function positiveOrNull(value: unknown): number | null {
+  return typeof value === 'number' && Number.isFinite(value) && value > 0
+    ? Math.floor(value)
+    : null;
+}
+
+function pickAll<T extends string>(value: unknown, allowed: readonly T[]): T[] {
+  if (!Array.isArray(value)) return [];
+  return value.filter((item, index): item is T => {
+    return allowed.includes(item as T) && value.indexOf(item) === index;
+  });
+}
+
+// Read format preferences saved in settings; anything unusable turns the rules off
+export function normalizeFormatPreferences(value: unknown): FormatPreferences | null {
+  if (!value || typeof value !== 'object') return null;
+  const saved = value as Partial<Record<keyof FormatPreferences, unknown>>;
+  const dynamicRange = DYNAMIC_RANGE_PREFERENCES.includes(
+    saved.dynamicRange as DynamicRangePreference,
+  )
+    ? (saved.dynamicRange as DynamicRangePreference)
+    : 'any';
+  const audioLanguage =
+    typeof saved.audioLanguage === 'string' && saved.audioLanguage.trim()
+      ? saved.audioLanguage.trim()
+      : null;
+
+  return {
+    preferredCodecs: pickAll(saved.preferredCodecs, VIDEO_CODEC_FAMILIES),
+    forbiddenCodecs: pickAll(saved.forbiddenCodecs, VIDEO_CODEC_FAMILIES),
+    dynamicRange,
+    maxFps: positiveOrNull(saved.maxFps),
+    audioLanguage,
+    maxFilesizeMb: positiveOrNull(saved.maxFilesizeMb),
+    fallbackOrder: Array.isArray(saved.fallbackOrder)
+      ? pickAll(saved.fallbackOrder, FORMAT_CONSTRAINTS)
+      : [...FORMAT_CONSTRAINTS],
+  };
+}
+
+// Compile preferences to yt-dlp -f/-S and, given formats, explain which ones they pick
+export async function explainFormatPreferences(
+  preferences: FormatPreferences,
+  quality: string,
+  format: string,
+  formats?: FormatOption[],
+): Promise<FormatPlan> {
+  return invoke<FormatPlan>('explain_format_preferences', {
+    preferences,
+    quality,
+    format,
+    formats: formats ?? null,
+  });
+}
+
+export function formatReasonText(reason: FormatReason): string {
+  const params: Record<string, string> = { ...reason.params };
+  if (params.constraint) {
+    params.constraint = i18n.t(`settings:formatRules.constraint_${params.constraint}`);
+  }
+  return i18n.t(`settings:formatRules.reason_${reason.key}`, params);
+}
@@ -0,0 +1,48 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { DownloadSettings, LiveRecording } from './types';
+
+export async function listLiveRecordings(): Promise<LiveRecording[]> {
+  return invoke<LiveRecording[]>('list_live_recordings');
+}
+
+export async function addLiveRecording(recording: {
+  url: string;
+  title?: string | null;
+  options: Record<string, unknown>;
+}): Promise<LiveRecording> {
+  return invoke<LiveRecording>('add_live_recording', { recording });
+}
+
+export async function cancelLiveRecording(id: string): Promise<void> {
+  await invoke('cancel_live_recording', { id });
+}
+
+export async function removeLiveRecording(id: string): Promise<void> {
+  await invoke('remove_live_recording', { id });
+}
+
+// Fired by the backend when a recording is added, changes state or is removed
+export function onLiveRecordingsUpdated(handler: () => void): Promise<UnlistenFn> {
+  return listen('live-recordings-updated', handler);
+}
+
+// Download options of a recording. Network settings are filled in by the backend,
+// like other queued downloads.
+export function liveRecordingOptions(settings: DownloadSettings): Record<string, unknown> {
+  return {
+    outputPath: settings.outputPath || null,
+    quality: settings.quality,
+    format: settings.format,
+    videoCodec: settings.videoCodec,
+    audioBitrate: settings.audioBitrate,
+    embedMetadata: settings.embedMetadata,
+    embedThumbnail: settings.embedThumbnail,
+    outputTemplate: settings.outputTemplate || null,
+    formatPreferences: settings.formatPreferences,
+    profile: settings.downloadProfile || null,
+    liveFromStart: settings.liveFromStart,
+    segmentMinutes: settings.liveSegmentMinutes > 0 ? settings.liveSegmentMinutes : null,
+    downloadKind: 'live',
+  };
+}
@@ -3,6 +3,17 @@ import type { DownloadItem } from './types';
 
 export type PersistedQueueKind = 'youtube' | 'universal' | 'gallery';
 
+// Queues saved before items took several time ranges kept a single start and end
+function migrateTimeRange(settings: DownloadItem['settings']): DownloadItem['settings'] {
+  if (!settings || settings.timeRanges) return settings;
+  const { timeRangeStart, timeRangeEnd, ...rest } = settings as typeof settings & {
+    timeRangeStart?: string;
+    timeRangeEnd?: string;
+  };
+  if (!timeRangeStart || !timeRangeEnd) return settings;
+  return { ...rest, timeRanges: [{ start: timeRangeStart, end: timeRangeEnd }] };
+}
+
 function normalizeQueueItem(item: DownloadItem): DownloadItem {
   const isTransient = item.status === 'fetching' || item.status === 'downloading';
   const shouldResetProgress = isTransient || item.retryState !== undefined;
@@ -18,6 +29,7 @@ function normalizeQueueItem(item: DownloadItem): DownloadItem {
     downloadedSize: isTransient ? undefined : item.downloadedSize,
     elapsedTime: isTransient ? undefined : item.elapsedTime,
     retryState: undefined,
+    settings: migrateTimeRange(item.settings),
   };
 }
 
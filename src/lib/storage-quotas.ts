@@ -0,0 +1,15 @@
+import type { FolderQuota } from './types';
+
+export const BYTES_PER_GB = 1024 * 1024 * 1024;
+
+// Read folder quotas saved in settings, dropping entries without a folder or a limit
+export function normalizeFolderQuotas(value: unknown): FolderQuota[] {
+  if (!Array.isArray(value)) return [];
+  return value.flatMap((entry) => {
+    if (!entry || typeof entry !== 'object') return [];
+    const { path, maxBytes } = entry as Partial<FolderQuota>;
+    if (typeof path !== 'string' || !path.trim()) return [];
+    if (typeof maxBytes !== 'number' || !Number.isFinite(maxBytes) || maxBytes <= 0) return [];
+    return [{ path: path.trim(), maxBytes: Math.floor(maxBytes) }];
+  });
+}
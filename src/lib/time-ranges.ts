@@ -0,0 +1,30 @@
+import type { TimeRange } from './types';
+
+const MAX_TIME_RANGES = 20;
+const TIME_MARKER = /^\d{1,2}(:\d{1,2}){1,2}$/;
+
+// "0:30-2:10, 5:00-6:00", as ranges are shown and recorded in history
+export function formatTimeRanges(ranges: TimeRange[] | undefined): string | null {
+  if (!ranges?.length) return null;
+  return ranges.map((range) => `${range.start}-${range.end}`).join(', ');
+}
+
+// yt-dlp --download-sections value; the backend downloads each range as its own clip
+export function toDownloadSections(ranges: TimeRange[] | undefined): string | null {
+  if (!ranges?.length) return null;
+  return ranges.map((range) => `*${range.start}-${range.end}`).join(',');
+}
+
+// Ranges of `*start-end` values (CLI requests, deep links), each of which may list several
+// ranges separated by commas. Invalid ranges are dropped.
+export function parseDownloadSections(values: Array<string | null | undefined>): TimeRange[] {
+  return values
+    .flatMap((value) => value?.split(',') ?? [])
+    .flatMap((section) => {
+      const [start, end] = section.trim().replace(/^\*/, '').split('-', 2);
+      return start && end && TIME_MARKER.test(start) && TIME_MARKER.test(end)
+        ? [{ start, end }]
+        : [];
+    })
+    .slice(0, MAX_TIME_RANGES);
+}
@@ -4,6 +4,41 @@ export type VideoCodec = 'h264' | 'vp9' | 'av1' | 'auto';
 export type AudioBitrate = 'auto' | '128';
 export type SubtitleMode = 'off' | 'auto' | 'manual';
 export type SubtitleFormat = 'srt' | 'vtt' | 'ass';
+
+// Format preference rules, compiled by the backend into yt-dlp -f/-S
+export type VideoCodecFamily = 'h264' | 'h265' | 'vp9' | 'av1';
+export type DynamicRangePreference = 'any' | 'preferSdr' | 'preferHdr' | 'sdrOnly' | 'hdrOnly';
+export type FormatConstraint = 'dynamicRange' | 'maxFps' | 'audioLanguage' | 'maxFilesize';
+
+export interface FormatPreferences {
+  preferredCodecs: VideoCodecFamily[]; // Tried first, in order
+  forbiddenCodecs: VideoCodecFamily[]; // Never downloaded
+  dynamicRange: DynamicRangePreference;
+  maxFps: number | null;
+  audioLanguage: string | null; // e.g. 'en', 'pt-BR'
+  maxFilesizeMb: number | null; // Per downloaded stream
+  fallbackOrder: FormatConstraint[]; // Dropped one after another when nothing matches
+}
+
+// One step of a "why this format" explanation
+export interface FormatReason {
+  key: string;
+  params: Record<string, string>;
+}
+
+export interface FormatChoice {
+  video: FormatOption | null;
+  audio: FormatOption | null;
+  relaxed: FormatConstraint[];
+  reasons: FormatReason[];
+}
+
+export interface FormatPlan {
+  format: string; // -f
+  sort: string | null; // -S
+  reasons: FormatReason[];
+  choice: FormatChoice | null;
+}
 export type PluginTrigger =
   | 'download.queued'
   | 'download.beforeStart'
@@ -53,6 +88,12 @@ export type SourcePlatform =
   | 'data_export'
   | 'other';
 
+// Part of a video to download, as M:SS or H:MM:SS markers
+export interface TimeRange {
+  start: string;
+  end: string;
+}
+
 // Settings snapshot saved with each queue item (YouTube page)
 export interface ItemDownloadSettings {
   quality: Quality;
@@ -64,14 +105,19 @@ export interface ItemDownloadSettings {
   audioBitrate: AudioBitrate;
   useAria2: boolean;
   aria2Args: string;
+  outputTemplate?: string; // yt-dlp output template relative to outputPath
+  profile?: string; // Download profile applied by the backend
+  formatPreferences?: FormatPreferences | null;
   subtitleMode: SubtitleMode;
   subtitleLangs: string[];
   subtitleEmbed: boolean;
   subtitleFormat: SubtitleFormat;
-  timeRangeStart?: string;
-  timeRangeEnd?: string;
+  timeRanges?: TimeRange[];
+  joinTimeRanges?: boolean; // Join the clips of several ranges into one file
+  preciseTimeRanges?: boolean; // Cut ranges to the frame instead of at keyframes
   liveFromStart?: boolean;
   skipLive?: boolean;
+  forceRedownload?: boolean; // Ignore the download archive for this item
   pluginWorkflowSnapshots?: PluginWorkflowSnapshotMap;
   postDownloadWorkflowSteps?: PluginWorkflowStepSnapshot[];
   autoRetryEnabled: boolean;
@@ -87,10 +133,15 @@ export interface ItemUniversalSettings {
   audioBitrate: AudioBitrate;
   useAria2: boolean;
   aria2Args: string;
-  timeRangeStart?: string;
-  timeRangeEnd?: string;
+  outputTemplate?: string; // yt-dlp output template relative to outputPath
+  profile?: string; // Download profile applied by the backend
+  formatPreferences?: FormatPreferences | null;
+  timeRanges?: TimeRange[];
+  joinTimeRanges?: boolean; // Join the clips of several ranges into one file
+  preciseTimeRanges?: boolean; // Cut ranges to the frame instead of at keyframes
   liveFromStart?: boolean;
   skipLive?: boolean;
+  forceRedownload?: boolean; // Ignore the download archive for this item
   pluginWorkflowSnapshots?: PluginWorkflowSnapshotMap;
   postDownloadWorkflowSteps?: PluginWorkflowStepSnapshot[];
   autoRetryEnabled: boolean;
@@ -105,11 +156,17 @@ export interface DownloadRetryState {
   remainingSeconds: number;
 }
 
+// A playlist entry that could not be downloaded while the rest of the playlist went on
+export interface PlaylistItemFailure {
+  index: number;
+  error: string;
+}
+
 export interface DownloadItem {
   id: string;
   url: string;
   title: string;
-  status: 'pending' | 'fetching' | 'downloading' | 'completed' | 'error' | 'skipped';
+  status: 'pending' | 'fetching' | 'downloading' | 'paused' | 'completed' | 'error' | 'skipped';
   progress: number;
   speed: string;
   eta: string;
@@ -119,6 +176,7 @@ export interface DownloadItem {
   isLive?: boolean; // true if video is currently live streaming
   downloadedSize?: string; // For live streams: "2.87 MiB"
   elapsedTime?: string; // For live streams: "00:00:07"
+  phase?: DownloadPhase; // Current yt-dlp step while downloading
   playlistIndex?: number;
   playlistTotal?: number;
   thumbnail?: string;
@@ -137,6 +195,13 @@ export interface DownloadItem {
   settings?: ItemDownloadSettings | ItemUniversalSettings;
   // Auto retry status while waiting between attempts
   retryState?: DownloadRetryState;
+  // Playlist downloads: entries that failed on their own
+  playlistFailures?: PlaylistItemFailure[];
+  // Gallery downloads: files saved and files skipped (already downloaded) so far
+  galleryFiles?: number;
+  gallerySkipped?: number;
+  // Gallery downloads: gallery-dl options chosen when the item was added
+  galleryOptions?: GalleryDlOptions;
 }
 
 export interface YoutubeSearchVideo {
@@ -200,10 +265,37 @@ export interface ExternalEnqueueOptions {
   subtitleLangs?: string[];
   subtitleEmbed?: boolean;
   subtitleFormat?: SubtitleFormat;
-  timeRangeStart?: string;
-  timeRangeEnd?: string;
+  timeRanges?: TimeRange[];
+  joinTimeRanges?: boolean; // Join the clips of several ranges into one file
+  preciseTimeRanges?: boolean; // Cut ranges to the frame instead of at keyframes
   liveFromStart?: boolean;
   skipLive?: boolean;
+  profile?: string;
+  galleryOptions?: GalleryDlOptions;
+}
+
+// gallery-dl options; the backend rejects filters and formats that could run code
+export interface GalleryDlOptions {
+  range?: string; // e.g. "1-20,30"
+  filter?: string; // e.g. "extension in ('jpg', 'png')"
+  filenameFormat?: string; // e.g. "{category}_{id}.{extension}"
+  directoryFormat?: string[]; // One format per folder level
+  writeMetadata?: boolean; // Save a .json file of metadata next to each file
+  zip?: boolean; // Pack the files of each gallery into a .zip archive
+  ugoiraConv?: boolean; // Convert Pixiv ugoira animations to videos
+}
+
+// Most bytes the files under a folder may take up
+export interface FolderQuota {
+  path: string;
+  maxBytes: number;
+}
+
+// Bandwidth budget for a daily time window; an end before the start spans midnight
+export interface BandwidthProfile {
+  startMinute: number;
+  endMinute: number;
+  limit: number | null; // bytes per second, null = unlimited
 }
 
 export interface DownloadSettings {
@@ -214,6 +306,7 @@ export interface DownloadSettings {
   videoCodec: VideoCodec;
   audioBitrate: AudioBitrate;
   concurrentDownloads: number; // 1-5
+  maxPerSite: number; // 1-5, downloads from one site at once
   playlistLimit: number; // 0 = unlimited, 1-100
   autoCheckUpdate: boolean; // Auto check for app updates on startup
   // Subtitle settings
@@ -227,20 +320,32 @@ export interface DownloadSettings {
   // Post-processing settings
   embedMetadata: boolean; // Embed metadata (title, artist, description) into downloaded files
   embedThumbnail: boolean; // Embed thumbnail as cover art (requires FFmpeg)
+  splitChapters: boolean; // Also write one file and history entry per chapter (requires FFmpeg)
+  verifyDownloads: boolean; // Check finished files with FFprobe and fail incomplete ones
   // Live stream settings
   liveFromStart: boolean; // Download live streams from the beginning
   skipLive: boolean; // Skip live streams instead of downloading them
+  liveSegmentMinutes: number; // Cut live recordings into parts of this length (0 = off)
   // Speed limit settings
   speedLimitEnabled: boolean; // true = limited, false = unlimited
   speedLimitValue: number; // e.g. 10
   speedLimitUnit: 'K' | 'M' | 'G'; // KB/s, MB/s, GB/s
+  bandwidthProfiles: BandwidthProfile[]; // Time-of-day limits replacing the speed limit
   // External downloader settings
   useAria2: boolean; // Use aria2c as yt-dlp external downloader
   aria2Args: string; // Custom aria2 arguments (raw or aria2c: prefixed)
+  // Output naming
+  outputTemplate: string; // yt-dlp output template, e.g. %(uploader)s/%(title)s.%(ext)s ('' = default)
+  // Download profile applied to new downloads ('' = none)
+  downloadProfile: string;
+  // Format rules replacing the video codec preset (null = off)
+  formatPreferences: FormatPreferences | null;
   // Auto retry settings
   autoRetryEnabled: boolean; // Retry transient failures automatically
   autoRetryMaxAttempts: number; // Number of retries after initial failure (1-10)
   autoRetryDelaySeconds: number; // Delay between retries in seconds (1-60)
+  // Size limits for download folders; queued downloads for a full folder wait
+  folderQuotas: FolderQuota[];
   // Queue persistence
   persistDownloadQueue: boolean; // Keep download queue across app restarts
   // SponsorBlock settings
@@ -259,6 +364,176 @@ export interface TelegramStatus {
   message?: string | null;
 }
 
+// Settings stored in a download profile; unset fields keep the download's own value
+export interface DownloadProfileSettings {
+  quality?: Quality;
+  format?: Format;
+  videoCodec?: VideoCodec;
+  audioBitrate?: AudioBitrate;
+  subtitleMode?: SubtitleMode;
+  subtitleLangs?: string; // comma-separated
+  subtitleEmbed?: boolean;
+  subtitleFormat?: SubtitleFormat;
+  embedMetadata?: boolean;
+  embedThumbnail?: boolean;
+  sponsorblockRemove?: string | null;
+  sponsorblockMark?: string | null;
+  cookieMode?: string;
+  cookieBrowser?: string | null;
+  cookieBrowserProfile?: string | null;
+  cookieFilePath?: string | null;
+  proxyUrl?: string | null;
+  outputTemplate?: string | null;
+}
+
+export interface DownloadProfile {
+  name: string;
+  settings: DownloadProfileSettings;
+  createdAt: number;
+  updatedAt: number;
+}
+
+// When a scheduled download runs; times of day are minutes after local midnight
+export type ScheduleRule =
+  | { kind: 'once'; at: number }
+  | { kind: 'window'; startMinute: number; endMinute: number }
+  | { kind: 'weekly'; weekdays: number[]; minute: number }; // 0 = Sunday
+
+// A download the backend queues by itself at the times its rule gives
+export interface DownloadSchedule {
+  id: string;
+  url: string;
+  title: string | null;
+  queueKind: string | null;
+  options: Record<string, unknown>;
+  rule: ScheduleRule;
+  enabled: boolean;
+  nextRunAt: number | null; // null once the schedule is done
+  lastRunAt: number | null;
+  lastItemId: string | null;
+  createdAt: number;
+  updatedAt: number;
+}
+
+export type LiveRecordingStatus = 'waiting' | 'recording' | 'completed' | 'failed' | 'cancelled';
+
+// An upcoming or running stream the backend records once it is live
+export interface LiveRecording {
+  id: string;
+  url: string;
+  title: string | null;
+  options: Record<string, unknown>;
+  status: LiveRecordingStatus;
+  checks: number;
+  sessions: number; // Downloads started; a stream that drops and returns gets another one
+  failures: number;
+  releaseAt: number | null; // Announced start, when the site gives one
+  nextCheckAt: number | null;
+  currentItemId: string | null;
+  errorMessage: string | null;
+  createdAt: number;
+  updatedAt: number;
+}
+
+export type DownloadQueueStatus =
+  | 'pending'
+  | 'downloading'
+  | 'paused'
+  | 'completed'
+  | 'failed'
+  | 'cancelled';
+
+// A download owned by the backend scheduler; the download pages show these by id
+export interface DownloadQueueItem {
+  id: string;
+  queueKind: string;
+  url: string;
+  title: string | null;
+  site: string;
+  origin: string; // "app", "cli", "telegram", "channel", "schedule", "live"
+  originRef: string | null;
+  status: DownloadQueueStatus;
+  priority: number;
+  attempts: number;
+  options: Record<string, unknown>;
+  partialPaths: string[];
+  errorCode: string | null;
+  errorMessage: string | null;
+  createdAt: number;
+  updatedAt: number;
+  startedAt: number | null;
+  finishedAt: number | null;
+  nextAttemptAt: number | null; // Set while a failed item waits for its automatic retry
+}
+
+export type DownloadEventKind =
+  | 'queued'
+  | 'started'
+  | 'progress'
+  | 'retried'
+  | 'completed'
+  | 'failed'
+  | 'cancelled'
+  | 'skipped';
+
+// One entry of the backend's download journal
+export interface DownloadEvent {
+  id: number;
+  downloadId: string;
+  kind: DownloadEventKind;
+  url: string | null;
+  site: string | null;
+  attempt: number | null;
+  percent: number | null;
+  bytes: number | null;
+  speedBps: number | null;
+  durationMs: number | null;
+  errorCode: string | null;
+  errorMessage: string | null;
+  createdAt: number;
+}
+
+export interface DailyDownloadStats {
+  day: string; // YYYY-MM-DD, local time
+  bytes: number;
+  completed: number;
+  failed: number;
+}
+
+export interface SiteDownloadStats {
+  site: string;
+  completed: number;
+  failed: number;
+  successRate: number | null; // 0 to 1
+  bytes: number;
+  averageSpeedBps: number | null;
+}
+
+export interface FailureCodeStats {
+  errorCode: string;
+  count: number;
+  sites: string[];
+  lastMessage: string | null;
+  lastSeenAt: number;
+}
+
+// Aggregates of the download journal over a period
+export interface DownloadStats {
+  since: number | null;
+  until: number | null;
+  started: number;
+  completed: number;
+  failed: number;
+  cancelled: number;
+  retried: number;
+  successRate: number | null; // 0 to 1
+  totalBytes: number;
+  averageSpeedBps: number | null;
+  daily: DailyDownloadStats[];
+  sites: SiteDownloadStats[];
+  topFailures: FailureCodeStats[];
+}
+
 export interface DownloadProgress {
   id: string;
   percent: number;
@@ -281,7 +556,28 @@ export interface DownloadProgress {
   // For live streams (no percentage available)
   downloaded_size?: string; // e.g. "2.87 MiB"
   elapsed_time?: string; // e.g. "00:00:07"
-}
+  // Structured progress from yt-dlp's progress template
+  phase?: DownloadPhase;
+  downloaded_bytes?: number;
+  total_bytes?: number; // Exact size or yt-dlp's estimate
+  fragment_index?: number;
+  fragment_count?: number;
+  // Automatic retries: current try and the limit
+  attempt?: number;
+  max_attempts?: number;
+  // Playlist downloads: 'item_finished' and 'item_error' events describe one entry
+  item_id?: string; // "<job id>:<playlist index>"
+  playlist_id?: string;
+}
+
+export type DownloadPhase =
+  | 'downloading'
+  | 'merging'
+  | 'converting'
+  | 'embedding'
+  | 'sponsorblock'
+  | 'moving'
+  | 'postprocessing';
 
 export type PluginRuntimeLanguage = 'javascript' | 'python';
 export type PluginProvider = 'deno' | 'python';
@@ -629,6 +925,7 @@ export interface PostDownloadPluginPayload {
   historyId?: string | null;
   timeRange?: string | null;
   downloadKind: string;
+  extraFiles?: string[]; // Further files of the download, e.g. chapter parts
   workflowRunId?: string | null;
   workflowStepIndex?: number | null;
   workflowStepPluginId?: string | null;
@@ -667,6 +964,9 @@ export interface FormatOption {
   tbr?: number;
   abr?: number;
   format_note?: string;
+  fps?: number;
+  dynamic_range?: string; // 'SDR', 'HDR10', 'HLG', 'DV', ...
+  language?: string;
 }
 
 export interface VideoInfoResponse {
@@ -801,10 +1101,52 @@ export interface HistoryEntry {
   file_exists: boolean;
   summary?: string; // AI-generated summary
   time_range?: string; // Time range cut (e.g. "00:10-01:00")
+  parent_id?: string; // Full download this chapter part was split from
+  chapter_index?: number; // 1-based chapter number of a part
+  playlist_id?: string; // Playlist this entry was downloaded from
+  playlist_index?: number; // 1-based position in that playlist
   tags: HistoryTag[];
   collections: HistoryCollection[];
 }
 
+// Progress of importing a media folder into the library, emitted as `library-scan-progress`
+export interface LibraryScanProgress {
+  folder: string;
+  total: number; // Media files found
+  processed: number;
+  imported: number;
+  skipped: number; // Already in the library
+  failed: number;
+  current?: string | null;
+  finished: boolean;
+  cancelled: boolean;
+}
+
+export interface RelinkCandidate {
+  filepath: string;
+  filesize: number;
+  sameName: boolean;
+  sameContent: boolean; // Same size and content hash
+}
+
+export interface MissingHistoryFile {
+  historyId: string;
+  title: string;
+  filepath: string;
+  candidates: RelinkCandidate[];
+}
+
+export interface LibraryRelinkProgress {
+  total: number; // Entries whose file is missing
+  processed: number;
+  relinked: number;
+  notFound: number;
+  ambiguous: MissingHistoryFile[];
+  current?: string | null;
+  finished: boolean;
+  cancelled: boolean;
+}
+
 export type HistoryFilter =
   | 'all'
   | 'youtube'
@@ -996,6 +1338,8 @@ export interface FFmpegCommandResult {
   estimated_time_seconds: number;
   output_path: string;
   warnings: string[];
+  // Set for a precise cut, which runs with executePreciseCut instead of command_args
+  precise_cut?: TimelineSelection;
 }
 
 export interface ProcessingJob {
@@ -1161,6 +1505,15 @@ export interface YtdlpChannelUpdateInfo {
   update_available: boolean;
 }
 
+export type GalleryDlChannel = 'stable' | 'nightly';
+
+export interface GalleryDlUpdateInfo {
+  channel: GalleryDlChannel;
+  current_version: string | null;
+  latest_version: string;
+  update_available: boolean;
+}
+
 // ============================================
 // Channel Follow & Auto-Download Types
 // ============================================
@@ -1192,6 +1545,7 @@ export interface FollowedChannel {
   download_threads: number; // concurrent download threads (default 1)
   download_video_codec: string; // video codec (h264, vp9, av1, auto)
   download_audio_bitrate: string; // audio bitrate (128, 192, 256, 320, auto)
+  download_profile: string | null; // download profile applied to auto-downloads
   youtube_content_type: YoutubeChannelContentType;
 }
 
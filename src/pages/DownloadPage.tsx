@@ -17,6 +17,7 @@ import { Button } from '@/components/ui/button';
 import { useDependencies } from '@/contexts/DependenciesContext';
 import { useDownload } from '@/contexts/DownloadContext';
 import { useSchedule } from '@/hooks/useSchedule';
+import { addLiveRecording, liveRecordingOptions } from '@/lib/live-recordings';
 import type { Quality } from '@/lib/types';
 import { cn } from '@/lib/utils';
 import { extractYouTubeVideoId } from '@/lib/youtube-url';
@@ -64,7 +65,10 @@ export function DownloadPage({ onNavigateToSettings }: DownloadPageProps) {
     cookieError,
     clearCookieError,
     retryFailedDownload,
-    updateItemTimeRange,
+    redownloadItem,
+    pauseItem,
+    resumeItem,
+    updateItemTimeRanges,
     renameCompletedItem,
   } = useDownload();
 
@@ -106,6 +110,23 @@ export function DownloadPage({ onNavigateToSettings }: DownloadPageProps) {
     startDownload();
   };
 
+  // Hand an upcoming stream to the backend recorder, which waits for it to go live
+  const handleRecordWhenLive = async (id: string) => {
+    const item = items.find((i) => i.id === id);
+    if (!item) return false;
+    try {
+      await addLiveRecording({
+        url: item.url,
+        title: item.title !== item.url ? item.title : null,
+        options: liveRecordingOptions(settings),
+      });
+      return true;
+    } catch (e) {
+      console.error('Failed to add live recording:', e);
+      return false;
+    }
+  };
+
   // Continue download after FFmpeg dialog (user chose to continue anyway or installed FFmpeg)
   const handleFfmpegDialogContinue = () => {
     setShowFfmpegDialog(false);
@@ -164,6 +185,11 @@ export function DownloadPage({ onNavigateToSettings }: DownloadPageProps) {
               onImportFile={importFromFile}
               onImportClipboard={importFromClipboard}
               onGoToSettings={onNavigateToSettings}
+              outputTemplate={settings.outputTemplate}
+              outputFormat={settings.format}
+              outputPath={settings.outputPath}
+              quality={settings.quality}
+              formatPreferences={settings.formatPreferences}
             />
 
             {/* Settings Bar */}
@@ -202,10 +228,14 @@ export function DownloadPage({ onNavigateToSettings }: DownloadPageProps) {
               showPlaylistBadge={settings.downloadPlaylist}
               currentPlaylistInfo={currentPlaylistInfo}
               onRemove={removeItem}
-              onUpdateTimeRange={updateItemTimeRange}
+              onUpdateTimeRanges={updateItemTimeRanges}
               onRename={renameCompletedItem}
               onClearCompleted={clearCompleted}
               onScheduleUpcomingLive={schedule.setSchedule}
+              onRecordWhenLive={handleRecordWhenLive}
+              onRedownload={redownloadItem}
+              onPause={pauseItem}
+              onResume={resumeItem}
             />
           </div>
         </div>
@@ -1,4 +1,13 @@
-import { ExternalLink, Play, RefreshCw, Square, Trash2, TriangleAlert } from 'lucide-react';
+import {
+  Download,
+  ExternalLink,
+  Loader2,
+  Play,
+  RefreshCw,
+  Square,
+  Trash2,
+  TriangleAlert,
+} from 'lucide-react';
 import { useTranslation } from 'react-i18next';
 import { GalleryQueueList } from '@/components/download/GalleryQueueList';
 import { GallerySettingsPanel } from '@/components/download/GallerySettingsPanel';
@@ -31,12 +40,22 @@ export function GalleryPage({ onNavigateToSettings }: GalleryPageProps) {
     startDownload,
     stopDownload,
     updateConcurrentDownloads,
+    updateOptions,
   } = useGalleryDl();
-  const { galleryDlStatus, galleryDlLoading, galleryDlError, checkGalleryDl } = useDependencies();
+  const {
+    galleryDlStatus,
+    galleryDlLoading,
+    galleryDlError,
+    galleryDlDownloading,
+    checkGalleryDl,
+    downloadGalleryDl,
+  } = useDependencies();
 
   const pendingCount = items.filter((i) => i.status !== 'completed').length;
   const hasItems = items.length > 0;
   const isReady = galleryDlStatus?.installed === true;
+  // A system-only source is installed outside the app
+  const canInstall = galleryDlStatus != null && galleryDlStatus.source !== 'system';
 
   return (
     <div className="flex-1 flex flex-col overflow-hidden">
@@ -76,6 +95,21 @@ export function GalleryPage({ onNavigateToSettings }: GalleryPageProps) {
                     </Button>
                   </div>
                   <div className="mt-3 flex flex-wrap gap-2">
+                    {canInstall && (
+                      <Button
+                        size="sm"
+                        className="h-8"
+                        onClick={() => void downloadGalleryDl(galleryDlStatus?.channel ?? 'stable')}
+                        disabled={galleryDlDownloading || galleryDlLoading}
+                      >
+                        {galleryDlDownloading ? (
+                          <Loader2 className="w-3.5 h-3.5 mr-1.5 animate-spin" />
+                        ) : (
+                          <Download className="w-3.5 h-3.5 mr-1.5" />
+                        )}
+                        {galleryDlDownloading ? t('missing.installing') : t('missing.install')}
+                      </Button>
+                    )}
                     {onNavigateToSettings && (
                       <Button
                         variant="outline"
@@ -116,6 +150,7 @@ export function GalleryPage({ onNavigateToSettings }: GalleryPageProps) {
             disabled={!isReady || isDownloading}
             onSelectFolder={selectOutputFolder}
             onConcurrentChange={updateConcurrentDownloads}
+            onOptionsChange={updateOptions}
           />
         </div>
 
@@ -1,17 +1,35 @@
-import { FolderDown } from 'lucide-react';
+import { FolderDown, FolderInput, Link2 } from 'lucide-react';
 import { useEffect, useMemo, useState } from 'react';
 import { useTranslation } from 'react-i18next';
-import { HistoryItem, HistoryToolbar } from '@/components/history';
+import {
+  HistoryItem,
+  HistoryToolbar,
+  LibraryRelinkDialog,
+  LibraryScanStatus,
+} from '@/components/history';
 import { ThemePicker } from '@/components/settings/ThemePicker';
 import { EmptyStateIllustration } from '@/components/shared/EmptyStateIllustration';
+import { Button } from '@/components/ui/button';
 import { useHistory } from '@/contexts/HistoryContext';
+import { localizeUnknownError } from '@/lib/backend-error';
 
 const INITIAL_VISIBLE_HISTORY_ITEMS = 10;
 const HISTORY_RENDER_BATCH_SIZE = 40;
 
 export function HistoryPage() {
   const { t } = useTranslation('pages');
-  const { entries, loading, totalCount } = useHistory();
+  const {
+    entries,
+    loading,
+    totalCount,
+    libraryScan,
+    scanLibraryFolder,
+    cancelLibraryScan,
+    dismissLibraryScan,
+  } = useHistory();
+  const [libraryScanError, setLibraryScanError] = useState<string | null>(null);
+  const libraryScanRunning = !!libraryScan && !libraryScan.finished;
+  const [relinkOpen, setRelinkOpen] = useState(false);
   const [visibleCount, setVisibleCount] = useState(INITIAL_VISIBLE_HISTORY_ITEMS);
   const visibleEntries = useMemo(() => entries.slice(0, visibleCount), [entries, visibleCount]);
 
@@ -31,6 +49,15 @@ export function HistoryPage() {
     return () => window.clearTimeout(timeout);
   }, [entries.length, visibleCount]);
 
+  const handleImportFolder = async () => {
+    setLibraryScanError(null);
+    try {
+      await scanLibraryFolder();
+    } catch (error) {
+      setLibraryScanError(localizeUnknownError(error));
+    }
+  };
+
   return (
     <div className="flex-1 flex flex-col overflow-hidden">
       {/* Header */}
@@ -43,7 +70,30 @@ export function HistoryPage() {
             </span>
           )}
         </div>
-        <ThemePicker />
+        <div className="flex items-center gap-2">
+          <Button
+            variant="outline"
+            size="sm"
+            className="h-8 gap-1.5"
+            onClick={() => void handleImportFolder()}
+            disabled={libraryScanRunning}
+            title={t('library.scan.importHint')}
+          >
+            <FolderInput className="w-3.5 h-3.5" />
+            <span className="hidden sm:inline text-xs">{t('library.scan.import')}</span>
+          </Button>
+          <Button
+            variant="outline"
+            size="sm"
+            className="h-8 gap-1.5"
+            onClick={() => setRelinkOpen(true)}
+            title={t('library.relink.buttonHint')}
+          >
+            <Link2 className="w-3.5 h-3.5" />
+            <span className="hidden sm:inline text-xs">{t('library.relink.button')}</span>
+          </Button>
+          <ThemePicker />
+        </div>
       </header>
 
       {/* Subtle divider */}
@@ -52,7 +102,16 @@ export function HistoryPage() {
       {/* Main Content */}
       <div className="flex-1 flex flex-col overflow-hidden">
         {/* Toolbar */}
-        <div className="flex-shrink-0 p-4 sm:p-6">
+        <div className="flex-shrink-0 p-4 sm:p-6 space-y-3">
+          <LibraryScanStatus
+            scan={libraryScan}
+            error={libraryScanError}
+            onCancel={() => void cancelLibraryScan()}
+            onDismiss={() => {
+              setLibraryScanError(null);
+              dismissLibraryScan();
+            }}
+          />
           <HistoryToolbar />
         </div>
 
@@ -84,6 +143,8 @@ export function HistoryPage() {
           </div>
         </div>
       </div>
+
+      <LibraryRelinkDialog open={relinkOpen} onOpenChange={setRelinkOpen} />
     </div>
   );
 }
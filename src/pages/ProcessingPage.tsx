@@ -1,4 +1,14 @@
-import { Clock, FileDown, Film, History, Maximize2, Music, Zap } from 'lucide-react';
+import {
+  Clock,
+  Crosshair,
+  FileDown,
+  Film,
+  History,
+  Maximize2,
+  Music,
+  Scissors,
+  Zap,
+} from 'lucide-react';
 import { useEffect, useState } from 'react';
 import { useTranslation } from 'react-i18next';
 import {
@@ -37,6 +47,7 @@ export function ProcessingPage() {
     selectOutputDirectory,
     setVideoError,
     sendMessage,
+    cutSelection,
     cancelProcessing,
     loadHistory,
     deleteJob,
@@ -172,6 +183,38 @@ export function ProcessingPage() {
                       {metadata.format.toUpperCase()}
                     </Badge>
                   </div>
+
+                  {selection && (
+                    <div className="mt-3 flex flex-wrap items-center gap-2">
+                      <span className="text-xs text-muted-foreground">
+                        {t('processing.cut.selection', {
+                          start: formatTime(selection.start),
+                          end: formatTime(selection.end),
+                        })}
+                      </span>
+                      <Button
+                        variant="outline"
+                        size="sm"
+                        className="h-7 gap-1.5"
+                        disabled={isProcessing || isGenerating}
+                        onClick={() => cutSelection(false)}
+                      >
+                        <Scissors className="w-3.5 h-3.5" />
+                        {t('processing.cut.fast')}
+                      </Button>
+                      <Button
+                        variant="outline"
+                        size="sm"
+                        className="h-7 gap-1.5"
+                        disabled={isProcessing || isGenerating}
+                        onClick={() => cutSelection(true)}
+                        title={t('processing.cut.preciseHint')}
+                      >
+                        <Crosshair className="w-3.5 h-3.5" />
+                        {t('processing.cut.precise')}
+                      </Button>
+                    </div>
+                  )}
                 </div>
               </div>
             )}
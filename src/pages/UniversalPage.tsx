@@ -14,8 +14,10 @@ import { FreshCookieRequiredDialog } from '@/components/FreshCookieRequiredDialo
 import { ThemePicker } from '@/components/settings/ThemePicker';
 import { Button } from '@/components/ui/button';
 import { useDependencies } from '@/contexts/DependenciesContext';
+import { useDownload } from '@/contexts/DownloadContext';
 import { useUniversal } from '@/contexts/UniversalContext';
 import { useSchedule } from '@/hooks/useSchedule';
+import { addLiveRecording, liveRecordingOptions } from '@/lib/live-recordings';
 import { loadCookieSettings } from '@/lib/network-config';
 import type { Quality } from '@/lib/types';
 import { cn } from '@/lib/utils';
@@ -52,11 +54,15 @@ export function UniversalPage({ onNavigateToSettings }: UniversalPageProps) {
     cookieError,
     clearCookieError,
     retryFailedDownload,
-    updateItemTimeRange,
+    redownloadItem,
+    pauseItem,
+    resumeItem,
+    updateItemTimeRanges,
     renameCompletedItem,
   } = useUniversal();
 
   const { ffmpegStatus } = useDependencies();
+  const { settings: downloadSettings } = useDownload();
 
   const [showFfmpegDialog, setShowFfmpegDialog] = useState(false);
 
@@ -84,6 +90,31 @@ export function UniversalPage({ onNavigateToSettings }: UniversalPageProps) {
     startDownload();
   };
 
+  // Hand an upcoming stream to the backend recorder, which waits for it to go live.
+  // Post-processing options come from the main download settings, like Universal downloads.
+  const handleRecordWhenLive = async (id: string) => {
+    const item = items.find((i) => i.id === id);
+    if (!item) return false;
+    try {
+      await addLiveRecording({
+        url: item.url,
+        title: item.title !== item.url ? item.title : null,
+        options: {
+          ...liveRecordingOptions(downloadSettings),
+          outputPath: settings.outputPath || null,
+          quality: settings.quality,
+          format: settings.format,
+          audioBitrate: settings.audioBitrate,
+          liveFromStart: settings.liveFromStart,
+        },
+      });
+      return true;
+    } catch (e) {
+      console.error('Failed to add live recording:', e);
+      return false;
+    }
+  };
+
   // Continue download after FFmpeg dialog
   const handleFfmpegDialogContinue = () => {
     setShowFfmpegDialog(false);
@@ -142,10 +173,14 @@ export function UniversalPage({ onNavigateToSettings }: UniversalPageProps) {
             focusedItemId={focusedItemId}
             isDownloading={isDownloading}
             onRemove={removeItem}
-            onUpdateTimeRange={updateItemTimeRange}
+            onUpdateTimeRanges={updateItemTimeRanges}
             onRename={renameCompletedItem}
             onClearCompleted={clearCompleted}
             onScheduleUpcomingLive={schedule.setSchedule}
+            onRecordWhenLive={handleRecordWhenLive}
+            onRedownload={redownloadItem}
+            onPause={pauseItem}
+            onResume={resumeItem}
           />
         </div>
       </div>
@@ -20,7 +20,13 @@ import { Label } from '@/components/ui/label';
 import { Switch } from '@/components/ui/switch';
 import { useChannels } from '@/contexts/ChannelsContext';
 import { useDependencies } from '@/contexts/DependenciesContext';
-import type { FollowedChannel, Quality, YoutubeChannelContentType } from '@/lib/types';
+import { listDownloadProfiles } from '@/lib/download-profiles';
+import type {
+  DownloadProfile,
+  FollowedChannel,
+  Quality,
+  YoutubeChannelContentType,
+} from '@/lib/types';
 import { cn } from '@/lib/utils';
 import { ChannelFetchLoadingState } from '@/pages/channels/ChannelFetchLoadingState';
 import { ChannelSettingsBar, YoutubeContentTypeSelect } from '@/pages/channels/ChannelSettingsBar';
@@ -102,6 +108,10 @@ export function ChannelDetailView({ channel, onBack }: ChannelDetailViewProps) {
   );
   const [settingsYoutubeContentType, setSettingsYoutubeContentType] =
     useState<YoutubeChannelContentType>(channel.youtube_content_type || 'videos');
+  const [settingsDownloadProfile, setSettingsDownloadProfile] = useState(
+    channel.download_profile || '',
+  );
+  const [downloadProfiles, setDownloadProfiles] = useState<DownloadProfile[]>([]);
   const [settingsIsAudioMode, setSettingsIsAudioMode] = useState(
     channel.download_quality === 'audio' ||
       ['mp3', 'm4a', 'opus'].includes(channel.download_format),
@@ -128,12 +138,26 @@ export function ChannelDetailView({ channel, onBack }: ChannelDetailViewProps) {
     setSettingsDownloadVideoCodec(channel.download_video_codec || 'h264');
     setSettingsDownloadAudioBitrate(channel.download_audio_bitrate || '192');
     setSettingsYoutubeContentType(channel.youtube_content_type || 'videos');
+    setSettingsDownloadProfile(channel.download_profile || '');
     setSettingsIsAudioMode(
       channel.download_quality === 'audio' ||
         ['mp3', 'm4a', 'opus'].includes(channel.download_format),
     );
   }, [channel]);
 
+  useEffect(() => {
+    if (!showSettings) return;
+    listDownloadProfiles()
+      .then(setDownloadProfiles)
+      .catch((error) => console.error('Failed to load download profiles:', error));
+  }, [showSettings]);
+
+  // Keep the channel's profile selectable even if it was deleted since
+  const profileNames = downloadProfiles.map((profile) => profile.name);
+  if (settingsDownloadProfile && !profileNames.includes(settingsDownloadProfile)) {
+    profileNames.push(settingsDownloadProfile);
+  }
+
   const handleSaveSettings = useCallback(async () => {
     setSavingSettings(true);
     try {
@@ -154,6 +178,7 @@ export function ChannelDetailView({ channel, onBack }: ChannelDetailViewProps) {
         youtubeContentType: isYoutubeChannelContentUrl(channel.url)
           ? settingsYoutubeContentType
           : 'videos',
+        downloadProfile: settingsDownloadProfile || null,
       });
       setActiveChannel({
         ...channel,
@@ -176,6 +201,7 @@ export function ChannelDetailView({ channel, onBack }: ChannelDetailViewProps) {
         youtube_content_type: isYoutubeChannelContentUrl(channel.url)
           ? settingsYoutubeContentType
           : 'videos',
+        download_profile: settingsDownloadProfile || null,
       });
       setShowSettings(false);
     } catch (error) {
@@ -199,6 +225,7 @@ export function ChannelDetailView({ channel, onBack }: ChannelDetailViewProps) {
     settingsFilterExcludeKeywords,
     settingsFilterMaxVideos,
     settingsDownloadThreads,
+    settingsDownloadProfile,
     setActiveChannel,
     updateChannelSettings,
   ]);
@@ -513,6 +540,22 @@ export function ChannelDetailView({ channel, onBack }: ChannelDetailViewProps) {
                     </select>
                   </div>
                 )}
+
+                <div className="flex items-center gap-1.5">
+                  <Label className="text-xs text-muted-foreground">{t('downloadProfile')}</Label>
+                  <select
+                    value={settingsDownloadProfile}
+                    onChange={(event) => setSettingsDownloadProfile(event.target.value)}
+                    className="h-8 px-2 rounded-md text-xs bg-background/50 border border-border/50"
+                  >
+                    <option value="">{t('downloadProfileNone')}</option>
+                    {profileNames.map((name) => (
+                      <option key={name} value={name}>
+                        {name}
+                      </option>
+                    ))}
+                  </select>
+                </div>
               </div>
             </div>
 
@@ -1,5 +1,5 @@
 import { describe, expect, test } from 'bun:test';
-import { parseExternalSummaryDeepLink } from '../src/lib/external-link';
+import { parseExternalDeepLink, parseExternalSummaryDeepLink } from '../src/lib/external-link';
 
 describe('parseExternalSummaryDeepLink', () => {
   test('accepts YouTube summary deep links', () => {
@@ -19,3 +19,48 @@ describe('parseExternalSummaryDeepLink', () => {
     ).toBeNull();
   });
 });
+
+describe('parseExternalDeepLink', () => {
+  test('collects repeated time ranges', () => {
+    const parsed = parseExternalDeepLink(
+      'youwee://download?v=1&url=https%3A%2F%2Fwww.youtube.com%2Fwatch%3Fv%3Dabc123&section=00:30-02:10&section=*05:00-06:00&section=bad&join=1',
+    );
+
+    expect(parsed?.enqueueOptions.timeRanges).toEqual([
+      { start: '00:30', end: '02:10' },
+      { start: '05:00', end: '06:00' },
+    ]);
+    expect(parsed?.enqueueOptions.joinTimeRanges).toBe(true);
+  });
+
+  test('leaves time ranges unset without sections', () => {
+    const parsed = parseExternalDeepLink(
+      'youwee://download?v=1&url=https%3A%2F%2Fwww.youtube.com%2Fwatch%3Fv%3Dabc123&media=audio',
+    );
+
+    expect(parsed?.enqueueOptions.timeRanges).toBeUndefined();
+  });
+
+  test('routes gallery options to the gallery target', () => {
+    const parsed = parseExternalDeepLink(
+      'youwee://download?v=1&url=https%3A%2F%2Fwww.pixiv.net%2Fen%2Fusers%2F11&range=1-20&filter=width%20%3E%3D%201000&directory=%7Bcategory%7D&directory=%20&metadata=1',
+    );
+
+    expect(parsed?.target).toBe('gallery');
+    expect(parsed?.enqueueOptions.galleryOptions).toEqual({
+      range: '1-20',
+      filter: 'width >= 1000',
+      directoryFormat: ['{category}'],
+      writeMetadata: true,
+    });
+  });
+
+  test('ignores gallery options for other targets', () => {
+    const parsed = parseExternalDeepLink(
+      'youwee://download?v=1&url=https%3A%2F%2Fvimeo.com%2F123&target=universal&range=1-20',
+    );
+
+    expect(parsed?.target).toBe('universal');
+    expect(parsed?.enqueueOptions.galleryOptions).toBeUndefined();
+  });
+});